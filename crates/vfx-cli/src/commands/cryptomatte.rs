@@ -0,0 +1,78 @@
+//! Cryptomatte matte extraction.
+//!
+//! Decodes the ranked ID/coverage channels of a Cryptomatte EXR and writes
+//! a matte for the selected object names, or a false-color preview of all
+//! IDs. Names may use `*` and `?` wildcards and are matched against the
+//! manifest.
+
+#[allow(unused_imports)]
+use tracing::{debug, info, trace};
+use anyhow::{bail, Context, Result};
+use vfx_io::exr;
+use vfx_io::imagebufalgo::{cryptomatte_matte, cryptomatte_preview, Cryptomatte};
+
+use crate::CryptomatteArgs;
+
+/// Run cryptomatte command
+pub fn run(args: CryptomatteArgs, verbose: u8) -> Result<()> {
+    let image = exr::read_layers(&args.input)
+        .with_context(|| format!("Failed to read EXR: {}", args.input.display()))?;
+    let cryptos = Cryptomatte::from_metadata(&image.metadata, args.input.parent())
+        .context("Failed to read Cryptomatte metadata")?;
+
+    if cryptos.is_empty() {
+        bail!("No Cryptomatte layers in {}", args.input.display());
+    }
+
+    if args.list {
+        for crypto in &cryptos {
+            println!("{} ({} names)", crypto.name, crypto.manifest.len());
+            for (name, id) in &crypto.manifest {
+                println!("  {:08x}  {}", id, name);
+            }
+        }
+        return Ok(());
+    }
+
+    let crypto = match &args.layer {
+        Some(name) => cryptos
+            .iter()
+            .find(|c| &c.name == name)
+            .with_context(|| {
+                let names: Vec<_> = cryptos.iter().map(|c| c.name.as_str()).collect();
+                format!("Cryptomatte layer '{}' not found (available: {})", name, names.join(", "))
+            })?,
+        None => &cryptos[0],
+    };
+
+    let layer = crypto
+        .find_layer(&image)
+        .with_context(|| format!("No channels found for Cryptomatte layer '{}'", crypto.name))?;
+
+    let Some(output) = &args.output else {
+        bail!("An output file is required");
+    };
+
+    let buf = if args.preview {
+        cryptomatte_preview(layer, crypto)?
+    } else {
+        if args.names.is_empty() {
+            bail!("No names given, use --matte NAME or --preview");
+        }
+        let patterns: Vec<&str> = args.names.iter().map(String::as_str).collect();
+        if verbose > 0 {
+            let ids = crypto.ids_matching(&patterns);
+            println!("{}: {} matching ID(s)", crypto.name, ids.len());
+        }
+        cryptomatte_matte(layer, crypto, &patterns)?
+    };
+
+    let image = buf.to_image_data()?;
+    super::save_image(output, &image)?;
+
+    if verbose > 0 {
+        println!("Wrote {}", output.display());
+    }
+
+    Ok(())
+}
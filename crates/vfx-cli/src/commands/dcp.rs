@@ -0,0 +1,147 @@
+//! DCP frame encoding command.
+//!
+//! Converts P3 frames to DCI X'Y'Z' and writes one JPEG 2000 codestream
+//! per frame with the DCI 2K or 4K profile, ready for MXF wrapping.
+
+#[allow(unused_imports)]
+use tracing::{debug, info, trace};
+use anyhow::{Context, Result, bail};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use crate::DcpFramesArgs;
+use super::load_image;
+use vfx_io::ImageData;
+use vfx_io::imagebuf::ImageBuf;
+use vfx_io::imagebufalgo::{dci_xyz, DciWhite, DciXyzOptions};
+use vfx_io::j2k::{self, J2kWriterOptions};
+
+pub fn run(args: DcpFramesArgs, verbose: u8) -> Result<()> {
+    trace!(frames = args.input.len(), profile = %args.profile, "dcp::run");
+
+    if args.fps == 0 {
+        bail!("Frame rate must be positive");
+    }
+
+    let options = match args.profile.to_lowercase().as_str() {
+        "2k" => J2kWriterOptions::cinema_2k(args.fps),
+        "4k" => J2kWriterOptions::cinema_4k(args.fps),
+        other => bail!("Unknown DCI profile: '{}'. Use: 2k, 4k", other),
+    };
+    let white = match args.white.to_lowercase().as_str() {
+        "d65" | "p3-d65" => DciWhite::D65,
+        "d60" | "p3-d60" => DciWhite::D60,
+        "dci" | "p3-dci" => DciWhite::Dci,
+        other => bail!("Unknown white point: '{}'. Use: d65, d60, dci", other),
+    };
+    let xyz_options = DciXyzOptions {
+        white,
+        input_gamma: if args.linear { None } else { Some(2.6) },
+        peak_luminance: args.peak,
+    };
+
+    // Outputs are named by file stem, so inputs from different directories may collide
+    let outputs: Vec<PathBuf> = args
+        .input
+        .iter()
+        .map(|input| {
+            let stem = input.file_stem().and_then(|s| s.to_str()).unwrap_or("frame");
+            args.output.join(format!("{}.j2c", stem))
+        })
+        .collect();
+    let mut sources: HashMap<&Path, &Path> = HashMap::new();
+    for (input, output) in args.input.iter().zip(&outputs) {
+        if let Some(previous) = sources.insert(output, input) {
+            bail!(
+                "{} and {} would both be written to {}",
+                previous.display(),
+                input.display(),
+                output.display()
+            );
+        }
+    }
+
+    std::fs::create_dir_all(&args.output)
+        .with_context(|| format!("Failed to create: {}", args.output.display()))?;
+
+    info!(
+        frames = args.input.len(),
+        profile = %args.profile,
+        fps = args.fps,
+        budget = j2k::dci_max_frame_bytes(args.fps),
+        "Encoding DCP frames"
+    );
+
+    let results: Vec<Result<usize>> = args
+        .input
+        .par_iter()
+        .zip(&outputs)
+        .map(|(input, output)| {
+            let bytes = encode_frame(input, output, &xyz_options, &options)?;
+            if verbose > 0 {
+                println!("{} -> {} ({} bytes)", input.display(), output.display(), bytes);
+            }
+            Ok(bytes)
+        })
+        .collect();
+
+    let mut total = 0;
+    let mut failed = 0;
+    for (input, result) in args.input.iter().zip(results) {
+        match result {
+            Ok(bytes) => total += bytes,
+            Err(e) => {
+                failed += 1;
+                eprintln!("Error: {}: {:#}", input.display(), e);
+            }
+        }
+    }
+
+    let encoded = args.input.len() - failed;
+    println!(
+        "Encoded {} frames to {} ({:.1} MB, {} failed)",
+        encoded,
+        args.output.display(),
+        total as f64 / 1_000_000.0,
+        failed
+    );
+    if failed > 0 {
+        bail!("{} frames failed", failed);
+    }
+    Ok(())
+}
+
+/// Converts one frame to X'Y'Z' and writes its codestream, returning its size.
+fn encode_frame(
+    input: &Path,
+    output: &Path,
+    xyz_options: &DciXyzOptions,
+    options: &J2kWriterOptions,
+) -> Result<usize> {
+    let image = load_image(input)?;
+    let channels = image.channels as usize;
+    if channels < 3 {
+        bail!("DCP frames need RGB input, got {} channels", channels);
+    }
+
+    // Drop alpha and any extra channels
+    let rgb = if channels == 3 {
+        image
+    } else {
+        let data: Vec<f32> = image
+            .to_f32()
+            .chunks_exact(channels)
+            .flat_map(|p| [p[0], p[1], p[2]])
+            .collect();
+        ImageData::from_f32(image.width, image.height, 3, data)
+    };
+
+    let xyz = dci_xyz(&ImageBuf::from_image_data(&rgb), xyz_options, None)
+        .to_image_data()
+        .context("X'Y'Z' conversion failed")?;
+    let codestream = j2k::encode(&xyz, options)?;
+    debug!(output = %output.display(), bytes = codestream.len(), "Writing codestream");
+    std::fs::write(output, &codestream)
+        .with_context(|| format!("Failed to save: {}", output.display()))?;
+    Ok(codestream.len())
+}
@@ -1,20 +1,24 @@
 //! Image info command (like iinfo).
 //!
 //! Displays image metadata, dimensions, channels, and for EXR files - layer information.
+//! With `--check`, every pixel block of an EXR file is decoded and damaged
+//! offset tables or lost blocks are reported.
 
 use crate::InfoArgs;
 #[allow(unused_imports)]
 use tracing::{debug, info, trace};
-use anyhow::Result;
+use anyhow::{Context, Result};
 use std::fs;
-use vfx_io::exr::ExrReader;
+use vfx_io::exr::{self, ExrReader, ExrRecoveryReport};
 use vfx_io::Format;
 
 /// Runs the info command, displaying image metadata.
 ///
 /// For EXR files with multiple layers, shows layer details when verbose or --all.
+/// With `--check`, fails if any EXR file is damaged, after printing all files.
 pub fn run(args: InfoArgs, verbose: u8) -> Result<()> {
     trace!(files = args.input.len(), "info::run");
+    let mut damaged = 0;
     
     for path in &args.input {
         let metadata = fs::metadata(path)?;
@@ -30,12 +34,35 @@ pub fn run(args: InfoArgs, verbose: u8) -> Result<()> {
             None
         };
 
-        let image = super::load_image(path)?;
+        let check = if args.check && format == Format::Exr {
+            Some(exr::check(path).with_context(|| format!("Failed to check: {}", path.display()))?)
+        } else {
+            None
+        };
+
+        let image = match (super::load_image(path), &check) {
+            (Ok(image), _) => image,
+            (Err(_), Some(report)) if !report.is_intact() => {
+                // Damaged file, describe what could be recovered
+                let (layered, _) = exr::recover_layers(path, 0.0)
+                    .with_context(|| format!("Failed to recover: {}", path.display()))?;
+                layered
+                    .layers
+                    .first()
+                    .context("No layers recovered")?
+                    .to_image_data()?
+            }
+            (Err(err), _) => return Err(err),
+        };
+
+        if check.as_ref().is_some_and(|report| !report.is_intact()) {
+            damaged += 1;
+        }
 
         if args.json {
-            print_json(&args, path, &image, file_size, &layer_info);
+            print_json(&args, path, &image, file_size, &layer_info, &check);
         } else {
-            print_text(&args, path, &image, file_size, format, verbose, &layer_info);
+            print_text(&args, path, &image, file_size, format, verbose, &layer_info, &check);
         }
 
         if args.input.len() > 1 {
@@ -43,10 +70,15 @@ pub fn run(args: InfoArgs, verbose: u8) -> Result<()> {
         }
     }
 
+    if damaged > 0 {
+        anyhow::bail!("{} of {} file(s) damaged", damaged, args.input.len());
+    }
+
     Ok(())
 }
 
 /// Prints info in human-readable text format.
+#[allow(clippy::too_many_arguments)]
 fn print_text(
     args: &InfoArgs,
     path: &std::path::Path,
@@ -55,6 +87,7 @@ fn print_text(
     format: Format,
     verbose: u8,
     layer_info: &Option<vfx_io::LayeredImage>,
+    check: &Option<ExrRecoveryReport>,
 ) {
     println!("{}", path.display());
     println!("  Resolution: {}x{}", image.width, image.height);
@@ -69,6 +102,10 @@ fn print_text(
         }
     }
 
+    if let Some(report) = check {
+        print_check_text(report);
+    }
+
     if args.stats || args.all {
         let data = image.to_f32();
         let (min, max, avg) = compute_stats(&data);
@@ -124,6 +161,27 @@ fn print_text(
     }
 }
 
+/// Prints the result of `--check`.
+fn print_check_text(report: &ExrRecoveryReport) {
+    if report.is_intact() {
+        println!("  Integrity:  OK ({} blocks)", report.blocks);
+        return;
+    }
+
+    println!(
+        "  Integrity:  DAMAGED ({} of {} blocks lost, {} offsets rebuilt)",
+        report.lost_blocks.len(),
+        report.blocks,
+        report.rebuilt_offsets
+    );
+    for block in &report.lost_blocks {
+        println!(
+            "    Lost: part {}, level {}x{}, {}x{} at {},{}",
+            block.part, block.level.0, block.level.1, block.width, block.height, block.x, block.y
+        );
+    }
+}
+
 /// Prints info in JSON format.
 fn print_json(
     args: &InfoArgs,
@@ -131,6 +189,7 @@ fn print_json(
     image: &vfx_io::ImageData,
     file_size: u64,
     layer_info: &Option<vfx_io::LayeredImage>,
+    check: &Option<ExrRecoveryReport>,
 ) {
     println!("{{");
     println!("  \"file\": \"{}\",", path.display());
@@ -161,6 +220,25 @@ fn print_json(
         println!("  ],");
     }
 
+    if let Some(report) = check {
+        let lost: Vec<String> = report
+            .lost_blocks
+            .iter()
+            .map(|b| {
+                format!(
+                    "{{\"part\": {}, \"level\": [{}, {}], \"x\": {}, \"y\": {}, \"width\": {}, \"height\": {}}}",
+                    b.part, b.level.0, b.level.1, b.x, b.y, b.width, b.height
+                )
+            })
+            .collect();
+        println!("  \"integrity\": {{");
+        println!("    \"intact\": {},", report.is_intact());
+        println!("    \"blocks\": {},", report.blocks);
+        println!("    \"rebuilt_offsets\": {},", report.rebuilt_offsets);
+        println!("    \"lost_blocks\": [{}]", lost.join(", "));
+        println!("  }},");
+    }
+
     println!("  \"metadata\": {{");
     if let Some(colorspace) = &image.metadata.colorspace {
         println!("    \"colorspace\": \"{}\",", json_escape(colorspace));
@@ -7,13 +7,19 @@
 //!
 //! These operations are essential for compositing workflows where EXR files
 //! contain multiple render passes (beauty, specular, diffuse, depth, etc.).
+//!
+//! With `--raw`, EXR parts are copied as compressed chunks, so the pixel
+//! data stays bit-identical and lossy compression is not applied again.
+//!
+//! Multi-view (stereo) EXR files list their views, and `--view` restricts
+//! both commands to the layers and channels of one view.
 
 use crate::{ExtractLayerArgs, LayersArgs, MergeLayersArgs};
 #[allow(unused_imports)]
 use tracing::{debug, info, trace};
 use anyhow::{Context, Result};
 use std::path::Path;
-use vfx_io::exr::{ExrReader, ExrWriter};
+use vfx_io::exr::{self, ExrPartCopy, ExrReader, ExrViews, ExrWriter};
 use vfx_io::{Format, LayeredImage};
 
 /// Loads a layered image, supporting both EXR multi-layer and single-layer formats.
@@ -30,6 +36,11 @@ fn load_layered(path: &Path) -> Result<LayeredImage> {
                 .read_layers(path)
                 .with_context(|| format!("Failed to read EXR layers: {}", path.display()))
         }
+        // Z buffer, mattes and aux channels live outside the color image
+        Format::Iff => vfx_io::iff::read_layers(path)
+            .with_context(|| format!("Failed to read IFF layers: {}", path.display())),
+        Format::Rla => vfx_io::rla::read_layers(path)
+            .with_context(|| format!("Failed to read RLA layers: {}", path.display())),
         _ => {
             // Non-EXR: load as ImageData and convert to single layer
             let image = vfx_io::read(path)
@@ -39,18 +50,46 @@ fn load_layered(path: &Path) -> Result<LayeredImage> {
     }
 }
 
+/// Loads the layers of one view of a multi-view EXR file.
+fn load_view(path: &Path, view: &str) -> Result<LayeredImage> {
+    let format = Format::detect(path).unwrap_or(Format::Unknown);
+    if format != Format::Exr {
+        anyhow::bail!("--view requires an EXR file: {}", path.display());
+    }
+
+    exr::read_view(path, view)
+        .with_context(|| format!("Failed to read view '{}': {}", view, path.display()))
+}
+
+/// Reads the views of a file. Non-EXR files have no views.
+fn load_views(path: &Path) -> Result<ExrViews> {
+    match Format::detect(path).unwrap_or(Format::Unknown) {
+        Format::Exr => exr::read_views(path)
+            .with_context(|| format!("Failed to read EXR views: {}", path.display())),
+        _ => Ok(ExrViews::default()),
+    }
+}
+
 /// Lists all layers and channels in the input file(s).
 ///
 /// For each file, prints layer names, dimensions, and channel details
 /// including name, type, and semantic kind (Color/Alpha/Depth/Id/etc).
 pub fn run_layers(args: LayersArgs, verbose: u8) -> Result<()> {
     for path in &args.input {
-        let layered = load_layered(path)?;
+        let mut views = load_views(path)?;
+        let layered = match &args.view {
+            Some(view) => {
+                // Part views no longer line up with the layers of a single view
+                views.part_views.clear();
+                load_view(path, view)?
+            }
+            None => load_layered(path)?,
+        };
 
         if args.json {
-            print_layers_json(path, &layered);
+            print_layers_json(path, &layered, &views);
         } else {
-            print_layers_text(path, &layered, verbose);
+            print_layers_text(path, &layered, &views, verbose);
         }
 
         if args.input.len() > 1 {
@@ -62,13 +101,19 @@ pub fn run_layers(args: LayersArgs, verbose: u8) -> Result<()> {
 }
 
 /// Prints layer information in human-readable text format.
-fn print_layers_text(path: &Path, layered: &LayeredImage, verbose: u8) {
+fn print_layers_text(path: &Path, layered: &LayeredImage, views: &ExrViews, verbose: u8) {
     println!("{}", path.display());
+    if let Some(default) = views.default_view() {
+        println!("  Views: {} (default: {})", views.views.join(", "), default);
+    }
     println!("  Layers: {}", layered.layers.len());
 
     for (idx, layer) in layered.layers.iter().enumerate() {
         println!();
-        println!("  [{}] \"{}\"", idx, layer.name);
+        match views.part_views.get(idx).and_then(|v| v.as_deref()) {
+            Some(view) => println!("  [{}] \"{}\" (view: {})", idx, layer.name, view),
+            None => println!("  [{}] \"{}\"", idx, layer.name),
+        }
         println!("      Size: {}x{}", layer.width, layer.height);
         println!("      Channels: {}", layer.channels.len());
 
@@ -88,15 +133,20 @@ fn print_layers_text(path: &Path, layered: &LayeredImage, verbose: u8) {
 }
 
 /// Prints layer information in JSON format for scripting/automation.
-fn print_layers_json(path: &Path, layered: &LayeredImage) {
+fn print_layers_json(path: &Path, layered: &LayeredImage, views: &ExrViews) {
     println!("{{");
     println!("  \"file\": \"{}\",", path.display());
+    let view_names: Vec<String> = views.views.iter().map(|v| format!("\"{}\"", v)).collect();
+    println!("  \"views\": [{}],", view_names.join(", "));
     println!("  \"layers\": [");
 
     for (idx, layer) in layered.layers.iter().enumerate() {
         let comma = if idx + 1 < layered.layers.len() { "," } else { "" };
         println!("    {{");
         println!("      \"name\": \"{}\",", layer.name);
+        if let Some(view) = views.part_views.get(idx).and_then(|v| v.as_deref()) {
+            println!("      \"view\": \"{}\",", view);
+        }
         println!("      \"width\": {},", layer.width);
         println!("      \"height\": {},", layer.height);
         println!("      \"channels\": [");
@@ -120,9 +170,17 @@ fn print_layers_json(path: &Path, layered: &LayeredImage) {
 /// Extracts a single layer from a multi-layer file and saves it.
 ///
 /// The layer can be specified by name or index. If no layer is specified,
-/// lists available layers and exits.
+/// lists available layers and exits. With `--view`, only the layers of that
+/// view are considered, and a view with a single layer needs no `--layer`.
 pub fn run_extract_layer(args: ExtractLayerArgs, verbose: u8) -> Result<()> {
-    let layered = load_layered(&args.input)?;
+    if args.raw {
+        return run_extract_layer_raw(args, verbose);
+    }
+
+    let layered = match &args.view {
+        Some(view) => load_view(&args.input, view)?,
+        None => load_layered(&args.input)?,
+    };
 
     // Find the requested layer
     let layer_idx = if let Some(ref name) = args.layer {
@@ -151,6 +209,8 @@ pub fn run_extract_layer(args: ExtractLayerArgs, verbose: u8) -> Result<()> {
                     )
                 })?
         }
+    } else if args.view.is_some() && layered.layers.len() == 1 {
+        0
     } else {
         // No layer specified - list available and exit
         println!("No layer specified. Available layers:");
@@ -187,6 +247,127 @@ pub fn run_extract_layer(args: ExtractLayerArgs, verbose: u8) -> Result<()> {
     Ok(())
 }
 
+/// Copies one part of an EXR file into a new file without decoding it.
+fn run_extract_layer_raw(args: ExtractLayerArgs, verbose: u8) -> Result<()> {
+    require_exr(&args.input)?;
+    require_exr(&args.output)?;
+
+    let names: Vec<String> = exr::read_part_infos(&args.input)
+        .with_context(|| format!("Failed to read EXR parts: {}", args.input.display()))?
+        .into_iter()
+        .map(|info| info.name)
+        .collect();
+
+    let part = match &args.layer {
+        Some(layer) => match layer.parse::<usize>() {
+            Ok(idx) if idx < names.len() => idx,
+            Ok(idx) => anyhow::bail!(
+                "Layer index {} out of range (file has {} layers)",
+                idx,
+                names.len()
+            ),
+            Err(_) => names.iter().position(|name| name == layer).ok_or_else(|| {
+                anyhow::anyhow!("Layer '{}' not found. Available: {}", layer, names.join(", "))
+            })?,
+        },
+        None if names.len() == 1 => 0,
+        None => {
+            println!("No layer specified. Available layers:");
+            for (idx, name) in names.iter().enumerate() {
+                println!("  [{}] {}", idx, name);
+            }
+            return Ok(());
+        }
+    };
+
+    if verbose > 0 {
+        println!("Copying part [{}] '{}' without recompression", part, names[part]);
+    }
+
+    exr::copy_parts(&[&args.input], &[ExrPartCopy::new(0, part)], &args.output)
+        .with_context(|| format!("Failed to write: {}", args.output.display()))?;
+
+    if verbose > 0 {
+        println!("Saved to {}", args.output.display());
+    }
+
+    Ok(())
+}
+
+/// Fails unless the path is an EXR file, which raw chunk copies require.
+fn require_exr(path: &Path) -> Result<()> {
+    if Format::detect(path).unwrap_or(Format::Unknown) != Format::Exr {
+        anyhow::bail!("--raw requires EXR files: {}", path.display());
+    }
+    Ok(())
+}
+
+/// Parses `OLD=NEW` channel renames.
+fn parse_channel_renames(renames: &[String]) -> Result<Vec<(String, String)>> {
+    renames
+        .iter()
+        .map(|rename| {
+            rename
+                .split_once('=')
+                .filter(|(old, new)| !old.is_empty() && !new.is_empty())
+                .map(|(old, new)| (old.to_string(), new.to_string()))
+                .ok_or_else(|| anyhow::anyhow!("Invalid channel rename '{}', expected OLD=NEW", rename))
+        })
+        .collect()
+}
+
+/// Merges EXR parts from multiple files by copying their compressed chunks.
+///
+/// Naming follows the decoding merge: single-part inputs take the custom
+/// name or the file stem when unnamed, multi-part inputs keep their names.
+fn run_merge_layers_raw(args: MergeLayersArgs, verbose: u8) -> Result<()> {
+    require_exr(&args.output)?;
+    let renames = parse_channel_renames(&args.rename_channels)?;
+    let mut copies = Vec::new();
+
+    for (source, path) in args.input.iter().enumerate() {
+        require_exr(path)?;
+        let infos = exr::read_part_infos(path)
+            .with_context(|| format!("Failed to read EXR parts: {}", path.display()))?;
+
+        for (part, info) in infos.iter().enumerate() {
+            let name = &info.name;
+            let mut copy = ExrPartCopy::new(source, part);
+
+            if infos.len() == 1 {
+                if let Some(name) = args.names.get(source) {
+                    copy = copy.with_name(name.as_str());
+                } else if name.is_empty() {
+                    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("layer");
+                    copy = copy.with_name(stem);
+                }
+            }
+
+            for (old, new) in &renames {
+                if info.channels.contains(old) {
+                    copy = copy.with_channel_name(old.as_str(), new.as_str());
+                }
+            }
+
+            if verbose > 0 {
+                println!(
+                    "Copying part [{}] '{}' from {}",
+                    part,
+                    copy.name.as_deref().unwrap_or(name),
+                    path.display()
+                );
+            }
+
+            copies.push(copy);
+        }
+    }
+
+    exr::copy_parts(&args.input, &copies, &args.output)
+        .with_context(|| format!("Failed to write: {}", args.output.display()))?;
+
+    Ok(())
+}
+
 /// Merges layers from multiple input files into a single multi-layer EXR.
 ///
 /// Each input file contributes one or more layers to the output.
@@ -196,6 +377,11 @@ pub fn run_merge_layers(args: MergeLayersArgs, verbose: u8) -> Result<()> {
         anyhow::bail!("No input files specified");
     }
 
+    if args.raw {
+        return run_merge_layers_raw(args, verbose);
+    }
+
+    let renames = parse_channel_renames(&args.rename_channels)?;
     let mut output = LayeredImage::default();
     let custom_names: Vec<&str> = args.names.iter().map(|s| s.as_str()).collect();
 
@@ -219,6 +405,12 @@ pub fn run_merge_layers(args: MergeLayersArgs, verbose: u8) -> Result<()> {
             }
             // For multi-layer inputs, preserve original names
 
+            for channel in &mut layer.channels {
+                if let Some((_, new)) = renames.iter().find(|(old, _)| *old == channel.name) {
+                    channel.name = new.clone();
+                }
+            }
+
             if verbose > 0 {
                 println!(
                     "Adding layer '{}' from {} ({}x{}, {} ch)",
@@ -1,12 +1,14 @@
 //! Texture creation command (like maketx)
 //!
 //! Uses vfx-compute GPU backend for accelerated mipmap generation.
-//! Writes mipmapped tiled EXR using vfx-exr.
+//! Writes mipmapped tiled EXR using vfx-exr, or BCn-compressed DDS/KTX2
+//! for real-time engines (`--format dds|ktx2`).
 
 use crate::MaketxArgs;
 #[allow(unused_imports)]
 use tracing::{debug, info, trace};
-use anyhow::{Result, Context};
+use anyhow::{bail, Result, Context};
+use vfx_io::ImageData;
 use vfx_compute::{ImageProcessor, ComputeImage, Backend, ResizeFilter};
 use vfx_exr::prelude::*;
 use vfx_exr::math::RoundingMode;
@@ -25,6 +27,17 @@ pub fn run(args: MaketxArgs, verbose: u8, allow_non_color: bool) -> Result<()> {
         println!("  Wrap: {}", args.wrap);
     }
 
+    let container = match &args.format {
+        Some(format) => format.to_lowercase(),
+        None => args.output.extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase(),
+    };
+    if container == "dds" || container == "ktx2" {
+        return write_gpu_texture(&args, image, &container, verbose);
+    }
+
     if args.mipmap {
         // Initialize GPU compute backend
         let processor = ImageProcessor::new(Backend::Auto)
@@ -81,13 +94,7 @@ pub fn run(args: MaketxArgs, verbose: u8, allow_non_color: bool) -> Result<()> {
             println!("  Generated {} mip levels", mip_data.len());
         }
 
-        // Check output format
-        let output_ext = args.output.extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("")
-            .to_lowercase();
-        
-        if output_ext == "exr" {
+        if container == "exr" {
             // Write mipmapped EXR using vfx-exr
             write_mipmapped_exr(&args, &mip_data, image.channels as usize, verbose)?;
         } else {
@@ -184,6 +191,162 @@ fn write_mipmapped_exr(
     Ok(())
 }
 
+/// Write a DDS or KTX2 texture: mips from `make_texture`, then BCn or
+/// uncompressed surfaces.
+#[cfg(any(feature = "dds", feature = "ktx"))]
+fn write_gpu_texture(
+    args: &MaketxArgs,
+    image: ImageData,
+    container: &str,
+    verbose: u8,
+) -> Result<()> {
+    use vfx_io::bcn::{BcFormat, BcQuality, GpuTexture};
+    use vfx_io::imagebuf::WrapMode;
+    use vfx_io::imagebufalgo::{MipmapFilter, MipmapOptions};
+
+    let quality = BcQuality::from_name(&args.quality)
+        .with_context(|| format!("Unknown quality '{}' (fast, normal, high)", args.quality))?;
+
+    let mut texture = if args.cubemap {
+        GpuTexture::cubemap(split_cube_strip(&image)?)?
+    } else {
+        GpuTexture::from_image(image)
+    };
+    if args.mipmap {
+        let filter = match args.filter.to_lowercase().as_str() {
+            "box" | "nearest" => MipmapFilter::Box,
+            "bilinear" => MipmapFilter::Bilinear,
+            "kaiser" => MipmapFilter::Kaiser,
+            _ => MipmapFilter::Lanczos,
+        };
+        // Cube faces meet other faces at their edges, so never wrap them
+        let wrap = match args.wrap.to_lowercase().as_str() {
+            _ if args.cubemap => WrapMode::Clamp,
+            "clamp" => WrapMode::Clamp,
+            "periodic" => WrapMode::Periodic,
+            "mirror" => WrapMode::Mirror,
+            _ => WrapMode::Black,
+        };
+        texture = texture.with_mipmaps(&MipmapOptions {
+            filter,
+            srgb: args.srgb,
+            premultiply_alpha: false,
+            wrap,
+        })?;
+    }
+
+    if verbose > 0 {
+        println!("  Container: {}", container.to_uppercase());
+        println!("  Compression: {} ({:?})", args.compression, quality);
+        println!("  Surfaces: {} x {} levels", texture.surfaces.len(), texture.mip_count());
+    }
+
+    let name = args.compression.to_lowercase();
+    let bc = BcFormat::from_name(&name);
+    let unknown = || anyhow::anyhow!(
+        "Unknown compression '{}' \
+         (bc1, bc3, bc4, bc5, bc6h, bc7, r8, rg8, rgba8, rgba16f, rgba32f)",
+        args.compression
+    );
+
+    if container == "dds" {
+        #[cfg(feature = "dds")]
+        {
+            use vfx_io::dds::{DdsFormat, DdsWriter, DdsWriterOptions};
+            let format = match (bc, name.as_str()) {
+                (Some(bc), _) => bc.into(),
+                (None, "r8") => DdsFormat::R8,
+                (None, "rg8") => DdsFormat::Rg8,
+                (None, "rgba8") => DdsFormat::Rgba8,
+                (None, "bgra8") => DdsFormat::Bgra8,
+                (None, "rgba16f") => DdsFormat::Rgba16Float,
+                (None, "rgba32f") => DdsFormat::Rgba32Float,
+                _ => return Err(unknown()),
+            };
+            if args.zstd {
+                println!("  Note: --zstd only applies to KTX2");
+            }
+            let writer = DdsWriter::with_options(DdsWriterOptions {
+                format,
+                quality,
+                srgb: args.srgb,
+                ..Default::default()
+            });
+            writer.write_texture(&args.output, &texture)
+                .with_context(|| format!("Failed to write {}", args.output.display()))?;
+        }
+        #[cfg(not(feature = "dds"))]
+        bail!("DDS output requires the `dds` feature");
+    } else {
+        #[cfg(feature = "ktx")]
+        {
+            use vfx_io::ktx::{KtxFormat, KtxSupercompression, KtxWriter, KtxWriterOptions};
+            let format = match (bc, name.as_str()) {
+                (Some(bc), _) => bc.into(),
+                (None, "r8") => KtxFormat::R8,
+                (None, "rg8") => KtxFormat::Rg8,
+                (None, "rgba8") => KtxFormat::Rgba8,
+                (None, "rgba16f") => KtxFormat::Rgba16Float,
+                (None, "rgba32f") => KtxFormat::Rgba32Float,
+                _ => return Err(unknown()),
+            };
+            let writer = KtxWriter::with_options(KtxWriterOptions {
+                format,
+                quality,
+                srgb: args.srgb,
+                supercompression: if args.zstd {
+                    KtxSupercompression::Zstd
+                } else {
+                    KtxSupercompression::None
+                },
+                ..Default::default()
+            });
+            writer.write_texture(&args.output, &texture)
+                .with_context(|| format!("Failed to write {}", args.output.display()))?;
+        }
+        #[cfg(not(feature = "ktx"))]
+        bail!("KTX2 output requires the `ktx` feature");
+    }
+
+    if verbose > 0 {
+        println!("Done.");
+    }
+    Ok(())
+}
+
+#[cfg(not(any(feature = "dds", feature = "ktx")))]
+fn write_gpu_texture(
+    _args: &MaketxArgs,
+    _image: ImageData,
+    container: &str,
+    _verbose: u8,
+) -> Result<()> {
+    bail!("{} output requires the `dds` or `ktx` feature", container.to_uppercase())
+}
+
+/// Split a horizontal strip of six square faces into cubemap faces.
+#[cfg(any(feature = "dds", feature = "ktx"))]
+fn split_cube_strip(image: &ImageData) -> Result<Vec<ImageData>> {
+    let size = image.height as usize;
+    if image.width as usize != size * 6 {
+        bail!("Cubemap strip must be 6:1 ({}x{} given)", image.width, image.height);
+    }
+    let channels = image.channels as usize;
+    let data = image.to_f32();
+    let row = image.width as usize * channels;
+    Ok((0..6)
+        .map(|face| {
+            let pixels = (0..size)
+                .flat_map(|y| {
+                    let start = y * row + face * size * channels;
+                    data[start..start + size * channels].iter().copied()
+                })
+                .collect();
+            ImageData::from_f32(size as u32, size as u32, image.channels, pixels)
+        })
+        .collect())
+}
+
 fn parse_filter(filter: &str) -> ResizeFilter {
     match filter.to_lowercase().as_str() {
         "box" | "nearest" => ResizeFilter::Nearest,
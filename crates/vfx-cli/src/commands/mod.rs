@@ -16,6 +16,8 @@ pub mod lut;
 pub mod transform;
 pub mod maketx;
 pub mod grep;
+pub mod attr;
+pub mod cryptomatte;
 pub mod batch;
 pub mod layers;
 pub mod channels;
@@ -27,6 +29,7 @@ pub mod udim;
 pub mod grade;
 pub mod clamp;
 pub mod premult;
+pub mod dcp;
 #[cfg(feature = "viewer")]
 pub mod view;
 
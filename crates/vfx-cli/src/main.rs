@@ -125,6 +125,7 @@ Examples:
   vfx color input.exr -o out.exr --from ACEScg --to sRGB
   vfx lut input.exr -o out.exr -l look.cube
   vfx maketx input.exr -o tex.tx -m -t 64
+  vfx maketx albedo.png -o albedo.ktx2 -m --compression bc7 --srgb --zstd
   vfx --allow-non-color blur id.exr -o id_blur.exr
 ")]
 struct Cli {
@@ -195,6 +196,12 @@ enum Commands {
     /// Search for pattern in image metadata (like igrep)
     Grep(GrepArgs),
 
+    /// Set or delete EXR header attributes without re-encoding pixels
+    Attr(AttrArgs),
+
+    /// Extract Cryptomatte mattes and preview images
+    Cryptomatte(CryptomatteArgs),
+
     /// Batch process multiple images
     Batch(BatchArgs),
 
@@ -246,6 +253,10 @@ enum Commands {
 
     /// Control alpha premultiplication
     Premult(PremultArgs),
+
+    /// Encode P3 frames as DCI X'Y'Z' JPEG 2000 codestreams for a DCP
+    #[command(name = "dcp-frames")]
+    DcpFrames(DcpFramesArgs),
 }
 
 #[derive(Args)]
@@ -265,6 +276,10 @@ struct InfoArgs {
     /// Machine-readable output (JSON)
     #[arg(long)]
     json: bool,
+
+    /// Decode every EXR block and report damaged offset tables or lost blocks
+    #[arg(long)]
+    check: bool,
 }
 
 #[derive(Args)]
@@ -540,6 +555,30 @@ struct MaketxArgs {
     /// Wrap mode: black, clamp, periodic
     #[arg(short, long, default_value = "black")]
     wrap: String,
+
+    /// Output format: exr, dds, ktx2 (default: from the output extension)
+    #[arg(long)]
+    format: Option<String>,
+
+    /// DDS/KTX2 pixel format: bc1, bc3, bc4, bc5, bc6h, bc7, r8, rg8, rgba8, rgba16f, rgba32f
+    #[arg(long, default_value = "bc7")]
+    compression: String,
+
+    /// BCn encoder quality: fast, normal, high
+    #[arg(long, default_value = "normal")]
+    quality: String,
+
+    /// Input is sRGB-encoded (DDS/KTX2 sRGB formats, mips filtered in linear light)
+    #[arg(long)]
+    srgb: bool,
+
+    /// Input is a horizontal strip of six cube faces (+X, -X, +Y, -Y, +Z, -Z)
+    #[arg(long)]
+    cubemap: bool,
+
+    /// Zstd supercompression (KTX2 only)
+    #[arg(long)]
+    zstd: bool,
 }
 
 #[derive(Args)]
@@ -589,6 +628,10 @@ struct LayersArgs {
     /// Machine-readable output (JSON)
     #[arg(long)]
     json: bool,
+
+    /// Only list the layers of this view (multi-view EXR)
+    #[arg(long)]
+    view: Option<String>,
 }
 
 /// Arguments for the `extract-layer` command.
@@ -601,9 +644,17 @@ struct ExtractLayerArgs {
     #[arg(short, long)]
     output: PathBuf,
 
-    /// Layer name or index to extract
-    #[arg(short, long)]
+    /// Layer name or index to extract (`-l` is the global log flag)
+    #[arg(long)]
     layer: Option<String>,
+
+    /// View to extract from (multi-view EXR, e.g. "left")
+    #[arg(long, conflicts_with = "raw")]
+    view: Option<String>,
+
+    /// Copy the compressed EXR part without decoding (bit-identical pixels)
+    #[arg(long)]
+    raw: bool,
 }
 
 /// Arguments for the `merge-layers` command.
@@ -620,6 +671,14 @@ struct MergeLayersArgs {
     /// Custom layer names (one per input)
     #[arg(short, long)]
     names: Vec<String>,
+
+    /// Rename channels (OLD=NEW, repeatable)
+    #[arg(long = "rename-channel", value_name = "OLD=NEW")]
+    rename_channels: Vec<String>,
+
+    /// Copy compressed EXR parts without decoding (bit-identical pixels)
+    #[arg(long)]
+    raw: bool,
 }
 
 /// Arguments for the `channel-shuffle` command.
@@ -744,6 +803,38 @@ struct AcesArgs {
     rrt_variant: String,
 }
 
+/// Arguments for the `dcp-frames` command.
+#[derive(Args)]
+struct DcpFramesArgs {
+    /// Input P3 frames
+    #[arg(required = true)]
+    input: Vec<PathBuf>,
+
+    /// Output directory for the .j2c codestreams
+    #[arg(short, long)]
+    output: PathBuf,
+
+    /// DCI profile: 2k, 4k
+    #[arg(short, long, default_value = "2k")]
+    profile: String,
+
+    /// Creator white point of the input: d65, d60, dci
+    #[arg(short, long, default_value = "d65")]
+    white: String,
+
+    /// Frame rate, which sets the per-frame size limit
+    #[arg(long, default_value = "24")]
+    fps: u32,
+
+    /// Input is linear light instead of gamma 2.6 code values
+    #[arg(long)]
+    linear: bool,
+
+    /// Luminance of RGB white in cd/m2
+    #[arg(long, default_value = "48.0")]
+    peak: f32,
+}
+
 /// Arguments for the `view` command.
 use commands::grade::GradeArgs;
 use commands::clamp::ClampArgs;
@@ -772,6 +863,73 @@ struct ViewArgs {
     colorspace: Option<String>,
 }
 
+/// Arguments for the `attr` command.
+#[derive(Args)]
+pub struct AttrArgs {
+    /// Attribute subcommand
+    #[command(subcommand)]
+    pub command: AttrCommand,
+}
+
+/// Attribute subcommands.
+#[derive(Subcommand)]
+pub enum AttrCommand {
+    /// Set an attribute (e.g. `vfx attr set owner comp shot.*.exr`)
+    Set {
+        /// EXR attribute name
+        name: String,
+        /// Value; lists are comma separated
+        value: String,
+        /// Input EXR file(s)
+        #[arg(required = true)]
+        input: Vec<PathBuf>,
+        /// Value type: string, int, float, double, rational, floats, strings
+        #[arg(short = 't', long = "type")]
+        kind: Option<String>,
+        /// Only edit this part of a multi-part file
+        #[arg(long)]
+        part: Option<usize>,
+    },
+    /// Delete an attribute
+    Del {
+        /// EXR attribute name
+        name: String,
+        /// Input EXR file(s)
+        #[arg(required = true)]
+        input: Vec<PathBuf>,
+        /// Only edit this part of a multi-part file
+        #[arg(long)]
+        part: Option<usize>,
+    },
+}
+
+/// Arguments for the `cryptomatte` command.
+#[derive(Args)]
+struct CryptomatteArgs {
+    /// Input EXR file with Cryptomatte layers
+    input: PathBuf,
+
+    /// Output file (matte or preview)
+    #[arg(short, long, required_unless_present = "list")]
+    output: Option<PathBuf>,
+
+    /// Object names to include in the matte, wildcards allowed (repeatable)
+    #[arg(short = 'm', long = "matte")]
+    names: Vec<String>,
+
+    /// Cryptomatte layer name, e.g. CryptoObject (default: first)
+    #[arg(long)]
+    layer: Option<String>,
+
+    /// Write a false-color preview of all IDs instead of a matte
+    #[arg(long, conflicts_with = "names")]
+    preview: bool,
+
+    /// List Cryptomatte layers and manifest names
+    #[arg(long)]
+    list: bool,
+}
+
 /// Arguments for the `udim` command.
 #[derive(Args)]
 pub struct UdimArgs {
@@ -871,6 +1029,8 @@ fn main() -> Result<()> {
         }
         Commands::Maketx(args) => commands::maketx::run(args, cli.verbose, cli.allow_non_color),
         Commands::Grep(args) => commands::grep::run(args, cli.verbose),
+        Commands::Attr(args) => commands::attr::run(args, cli.verbose),
+        Commands::Cryptomatte(args) => commands::cryptomatte::run(args, cli.verbose),
         Commands::Batch(args) => commands::batch::run(args, cli.verbose, cli.allow_non_color),
         Commands::Layers(args) => commands::layers::run_layers(args, cli.verbose),
         Commands::ExtractLayer(args) => commands::layers::run_extract_layer(args, cli.verbose),
@@ -887,5 +1047,6 @@ fn main() -> Result<()> {
         Commands::Grade(args) => commands::grade::run(args, cli.verbose, cli.allow_non_color),
         Commands::Clamp(args) => commands::clamp::run(args, cli.verbose),
         Commands::Premult(args) => commands::premult::run(args, cli.verbose),
+        Commands::DcpFrames(args) => commands::dcp::run(args, cli.verbose),
     }
 }
@@ -320,10 +320,8 @@ impl CompressedDeepScanLineBlock {
 impl CompressedDeepTileBlock {
     /// Without validation, write this instance to the byte stream.
     pub fn write<W: Write>(&self, write: &mut W) -> UnitResult {
-        // Validate that sample data is not empty - empty data corrupts the EXR file
-        if self.compressed_sample_data_le.is_empty() {
-            return Err(Error::invalid("empty deep sample data cannot be written to file"));
-        }
+        // Note: empty sample data is valid for deep tiles with 0 samples per pixel.
+        // The offset table still needs to be written even if sample_data is empty.
 
         self.coordinates.write(write)?;
         u64::write_le(self.compressed_pixel_offset_table.len() as u64, write)?;
@@ -0,0 +1,447 @@
+//! Copy compressed chunks between files without decompressing them.
+//!
+//! Parts of several files can be merged into one multi-part file,
+//! single parts can be split out, and parts and channels can be renamed.
+//! The compressed pixel bytes are copied as they are, so lossy
+//! compression methods like DWA or B44 are not applied a second time.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
+use std::path::Path;
+
+use crate::block::chunk::TileCoordinates;
+use crate::block::reader::Reader;
+use crate::block::writer::ChunksWriter;
+use crate::error::{Error, UnitResult};
+use crate::meta::attribute::{ChannelList, Text};
+use crate::meta::header::Header;
+use crate::meta::Headers;
+
+/// A part of a source file that is copied into the output file.
+/// The output file contains the parts in the order they are specified.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartCopy {
+    /// Index of the source file in the list of sources.
+    pub source: usize,
+
+    /// Index of the part (header) inside the source file.
+    pub part: usize,
+
+    /// Replaces the name of the part, if specified.
+    pub name: Option<Text>,
+
+    /// Channels to rename, as pairs of old and new names.
+    /// Renaming must not change the alphabetical order of the channels,
+    /// as the order of the samples inside the compressed chunks depends on it.
+    /// In DWA parts, a channel must also keep the part of its name after the
+    /// last dot, and channels sharing a layer prefix must keep sharing one.
+    pub channel_names: Vec<(Text, Text)>,
+}
+
+impl PartCopy {
+    /// Copy the specified part of the specified source file unchanged.
+    pub fn new(source: usize, part: usize) -> Self {
+        Self {
+            source,
+            part,
+            name: None,
+            channel_names: Vec::new(),
+        }
+    }
+
+    /// Store the part under a different name.
+    pub fn with_name(self, name: impl Into<Text>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..self
+        }
+    }
+
+    /// Rename a channel of the part.
+    pub fn with_channel_name(mut self, old: impl Into<Text>, new: impl Into<Text>) -> Self {
+        self.channel_names.push((old.into(), new.into()));
+        self
+    }
+}
+
+/// Copy parts of the source files into a new file, without decompressing any pixels.
+/// See `copy_parts` for details.
+pub fn copy_parts_to_file(
+    sources: &[impl AsRef<Path>],
+    parts: &[PartCopy],
+    output: impl AsRef<Path>,
+) -> UnitResult {
+    let sources = sources
+        .iter()
+        .map(|path| File::open(path).map(BufReader::new))
+        .collect::<std::io::Result<Vec<_>>>()?;
+
+    let output = BufWriter::new(File::create(output)?);
+    copy_parts(sources, parts, output)
+}
+
+/// Copy parts of the source images into a new image, without decompressing any pixels.
+/// The headers of the copied parts are kept, except for the names specified in the `PartCopy`s.
+/// A single copied part results in a single-part file, several parts in a multi-part file,
+/// where every part needs a unique name.
+/// The sources and the destination are assumed to be buffered.
+pub fn copy_parts<R: Read + Seek, W: Write + Seek>(
+    sources: Vec<R>,
+    parts: &[PartCopy],
+    write: W,
+) -> UnitResult {
+    if parts.is_empty() {
+        return Err(Error::invalid("no parts to copy"));
+    }
+
+    let readers = sources
+        .into_iter()
+        .map(|source| Reader::read_from_buffered(source, false))
+        .collect::<crate::error::Result<Vec<_>>>()?;
+
+    let mut headers = Headers::with_capacity(parts.len());
+    for copy in parts {
+        let header = readers
+            .get(copy.source)
+            .ok_or_else(|| Error::invalid(format!("source file index {}", copy.source)))?
+            .headers()
+            .get(copy.part)
+            .ok_or_else(|| {
+                Error::invalid(format!(
+                    "part index {} of source file {}",
+                    copy.part, copy.source
+                ))
+            })?;
+
+        headers.push(renamed_header(header, copy)?);
+    }
+
+    if headers.len() > 1 {
+        for (index, header) in headers.iter().enumerate() {
+            let name = header.own_attributes.layer_name.as_ref().ok_or_else(|| {
+                Error::invalid(format!(
+                    "part {} has no name, which multi-part files require",
+                    index
+                ))
+            })?;
+
+            let duplicate = headers[..index]
+                .iter()
+                .any(|other| other.own_attributes.layer_name.as_ref() == Some(name));
+
+            if duplicate {
+                return Err(Error::invalid(format!("duplicate part name: `{}`", name)));
+            }
+        }
+    }
+
+    crate::block::write(write, headers, false, move |_meta, chunk_writer| {
+        for (source_index, reader) in readers.into_iter().enumerate() {
+            // output parts of each part of this source file
+            let mut targets: Vec<Vec<usize>> = vec![Vec::new(); reader.headers().len()];
+            for (output_index, copy) in parts.iter().enumerate() {
+                if copy.source == source_index {
+                    targets[copy.part].push(output_index);
+                }
+            }
+
+            if targets.iter().all(Vec::is_empty) {
+                continue;
+            }
+
+            // the offset table index of each block, which is its index in increasing y order
+            let block_indices: Vec<HashMap<TileCoordinates, usize>> = reader
+                .headers()
+                .iter()
+                .zip(&targets)
+                .map(|(header, targets)| {
+                    if targets.is_empty() {
+                        return HashMap::new();
+                    }
+
+                    header
+                        .blocks_increasing_y_order()
+                        .enumerate()
+                        .map(|(index, tile)| (tile.location, index))
+                        .collect()
+                })
+                .collect();
+
+            let source_headers = reader.headers().to_vec();
+            let chunks =
+                reader.filter_chunks(false, |_, _, block| !targets[block.layer].is_empty())?;
+
+            for chunk in chunks {
+                let chunk = chunk?;
+                let header = source_headers
+                    .get(chunk.layer_index)
+                    .ok_or_else(|| Error::invalid("chunk layer index"))?;
+
+                let coordinates = header.get_block_data_indices(&chunk.compressed_block)?;
+                let block_index = *block_indices[chunk.layer_index]
+                    .get(&coordinates)
+                    .ok_or_else(|| Error::invalid("chunk coordinates"))?;
+
+                for &output_index in &targets[chunk.layer_index] {
+                    let mut chunk = chunk.clone();
+                    chunk.layer_index = output_index;
+                    chunk_writer.write_chunk(block_index, chunk)?;
+                }
+            }
+        }
+
+        Ok(())
+    })
+}
+
+/// Apply the part and channel names of the copy to a source header.
+fn renamed_header(header: &Header, copy: &PartCopy) -> crate::error::Result<Header> {
+    let mut header = header.clone();
+
+    if let Some(name) = &copy.name {
+        header.own_attributes.layer_name = Some(name.clone());
+    }
+
+    if !copy.channel_names.is_empty() {
+        let mut list = header.channels.list.clone();
+
+        for (old, new) in &copy.channel_names {
+            let channel = list
+                .iter_mut()
+                .find(|channel| &channel.name == old)
+                .ok_or_else(|| Error::invalid(format!("no channel named `{}` to rename", old)))?;
+
+            channel.name = new.clone();
+        }
+
+        if !list.windows(2).all(|pair| pair[0].name < pair[1].name) {
+            return Err(Error::invalid(
+                "renaming channels would change their order, which requires recompression",
+            ));
+        }
+
+        let channels = ChannelList::new(list);
+        if !header.compression.supports_channel_renames(&header.channels, &channels) {
+            return Err(Error::invalid(format!(
+                "renaming these channels would change how {} decodes them, which requires recompression",
+                header.compression
+            )));
+        }
+
+        header.channels = channels;
+    }
+
+    Ok(header)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::image::read::read;
+    use crate::prelude::*;
+    use std::io::Cursor;
+
+    fn write_test_image(name: &str, compression: Compression, value: f32) -> Vec<u8> {
+        let size = Vec2(33, 21);
+        let samples = |offset: f32| {
+            FlatSamples::F32((0..size.area()).map(|i| i as f32 * 0.25 + offset).collect())
+        };
+
+        let channels = AnyChannels::sort(smallvec::smallvec![
+            AnyChannel::new("R", samples(value)),
+            AnyChannel::new("Z", samples(value * 2.0)),
+        ]);
+
+        let layer = Layer::new(
+            size,
+            LayerAttributes::named(name),
+            Encoding {
+                compression,
+                ..Encoding::default()
+            },
+            channels,
+        );
+
+        let mut bytes = Vec::new();
+        Image::from_layer(layer)
+            .write()
+            .to_buffered(Cursor::new(&mut bytes))
+            .unwrap();
+        bytes
+    }
+
+    fn pixel_bytes(file: &[u8], part: usize) -> Vec<Vec<u8>> {
+        let mut chunks: Vec<(TileCoordinates, Vec<u8>)> =
+            crate::block::read(Cursor::new(file), false)
+                .unwrap()
+                .all_chunks(false)
+                .unwrap()
+                .map(|chunk| chunk.unwrap())
+                .filter(|chunk| chunk.layer_index == part)
+                .map(|chunk| match chunk.compressed_block {
+                    crate::block::chunk::CompressedBlock::ScanLine(block) => (
+                        TileCoordinates {
+                            tile_index: Vec2(0, block.y_coordinate as usize),
+                            level_index: Vec2(0, 0),
+                        },
+                        block.compressed_pixels_le,
+                    ),
+                    crate::block::chunk::CompressedBlock::Tile(block) => {
+                        (block.coordinates, block.compressed_pixels_le)
+                    }
+                    _ => panic!("unexpected deep block"),
+                })
+                .collect();
+
+        chunks.sort_by_key(|(coordinates, _)| {
+            (coordinates.tile_index.y(), coordinates.tile_index.x())
+        });
+        chunks.into_iter().map(|(_, bytes)| bytes).collect()
+    }
+
+    #[test]
+    fn merge_and_split_keeps_compressed_bytes() {
+        let first = write_test_image("beauty", Compression::DWAA(None), 1.0);
+        let second = write_test_image("beauty", Compression::PIZ, 5.0);
+
+        let mut merged = Vec::new();
+        copy_parts(
+            vec![Cursor::new(&first), Cursor::new(&second)],
+            &[
+                PartCopy::new(0, 0).with_name("left"),
+                PartCopy::new(1, 0)
+                    .with_name("right")
+                    .with_channel_name("Z", "depth"),
+            ],
+            Cursor::new(&mut merged),
+        )
+        .unwrap();
+
+        let image = read()
+            .no_deep_data()
+            .largest_resolution_level()
+            .all_channels()
+            .all_layers()
+            .all_attributes()
+            .from_buffered(Cursor::new(&merged))
+            .unwrap();
+
+        assert_eq!(image.layer_data.len(), 2);
+        assert_eq!(
+            image.layer_data[1].attributes.layer_name,
+            Some(Text::from("right"))
+        );
+        assert_eq!(
+            image.layer_data[1].channel_data.list[1].name,
+            Text::from("depth")
+        );
+
+        assert_eq!(pixel_bytes(&merged, 0), pixel_bytes(&first, 0));
+        assert_eq!(pixel_bytes(&merged, 1), pixel_bytes(&second, 0));
+
+        let mut split = Vec::new();
+        copy_parts(
+            vec![Cursor::new(&merged)],
+            &[PartCopy::new(0, 1)],
+            Cursor::new(&mut split),
+        )
+        .unwrap();
+        assert_eq!(pixel_bytes(&split, 0), pixel_bytes(&second, 0));
+    }
+
+    #[test]
+    fn renames_dwa_channels_only_if_blocks_stay_valid() {
+        let size = Vec2(37, 19);
+        let channel = |name: &str, offset: f32| {
+            let samples = (0..size.area()).map(|i| (i % 29) as f32 * 0.1 + offset);
+            AnyChannel::new(name, FlatSamples::F32(samples.collect()))
+        };
+
+        let layer = Layer::new(
+            size,
+            LayerAttributes::named("beauty"),
+            Encoding {
+                compression: Compression::DWAA(None),
+                ..Encoding::default()
+            },
+            AnyChannels::sort(smallvec::smallvec![
+                channel("diffuse.R", 0.1),
+                channel("diffuse.G", 0.2),
+                channel("diffuse.B", 0.3),
+                channel("diffuse.A", 1.0),
+            ]),
+        );
+
+        let mut original = Vec::new();
+        Image::from_layer(layer)
+            .write()
+            .to_buffered(Cursor::new(&mut original))
+            .unwrap();
+
+        let rename = |names: &[(&str, &str)]| {
+            let copy = names
+                .iter()
+                .fold(PartCopy::new(0, 0), |copy, (old, new)| copy.with_channel_name(*old, *new));
+
+            let mut bytes = Vec::new();
+            copy_parts(vec![Cursor::new(&original)], &[copy], Cursor::new(&mut bytes))
+                .map(|()| bytes)
+        };
+
+        let pixels = |bytes: &[u8]| -> Vec<(Text, Vec<f32>)> {
+            read()
+                .no_deep_data()
+                .largest_resolution_level()
+                .all_channels()
+                .first_valid_layer()
+                .all_attributes()
+                .from_buffered(Cursor::new(bytes))
+                .unwrap()
+                .layer_data
+                .channel_data
+                .list
+                .into_iter()
+                .map(|channel| (channel.name, channel.sample_data.values_as_f32().collect()))
+                .collect()
+        };
+
+        // moving the whole layer keeps every scheme and the Y'CbCr group
+        let moved = rename(&[
+            ("diffuse.A", "albedo.A"),
+            ("diffuse.B", "albedo.B"),
+            ("diffuse.G", "albedo.G"),
+            ("diffuse.R", "albedo.R"),
+        ])
+        .unwrap();
+
+        let before = pixels(&original);
+        let after = pixels(&moved);
+        assert_eq!(after.len(), before.len());
+        for ((old_name, old_values), (new_name, new_values)) in before.iter().zip(&after) {
+            assert_eq!(new_name.to_string(), old_name.to_string().replace("diffuse", "albedo"));
+            assert_eq!(new_values, old_values, "{}", new_name);
+        }
+        assert_eq!(pixel_bytes(&moved, 0), pixel_bytes(&original, 0));
+
+        // `X` would be decoded losslessly instead of with the dct
+        assert!(rename(&[("diffuse.R", "diffuse.X")]).is_err());
+
+        // red would leave the Y'CbCr group of green and blue
+        assert!(rename(&[("diffuse.R", "diffuse2.R")]).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_copies() {
+        let first = write_test_image("beauty", Compression::ZIP1, 1.0);
+        let copy = |parts: &[PartCopy]| {
+            copy_parts(vec![Cursor::new(&first)], parts, Cursor::new(Vec::new()))
+        };
+
+        // reordering channels, duplicate names, missing parts
+        assert!(copy(&[PartCopy::new(0, 0).with_channel_name("R", "a")]).is_err());
+        assert!(copy(&[PartCopy::new(0, 0), PartCopy::new(0, 0)]).is_err());
+        assert!(copy(&[PartCopy::new(0, 1)]).is_err());
+        assert!(copy(&[PartCopy::new(0, 0), PartCopy::new(0, 0).with_name("copy")]).is_ok());
+    }
+}
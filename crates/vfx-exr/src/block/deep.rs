@@ -402,8 +402,9 @@ pub struct DeepUncompressedBlock {
     /// Layer index this block belongs to.
     pub layer_index: usize,
 
-    /// Y coordinate for scanline blocks, or tile coordinates for tiled.
-    pub y_coordinate: i32,
+    /// Position, size and resolution level of this block inside the layer.
+    /// The position is relative to the data window and starts at zero.
+    pub index: super::BlockIndex,
 
     /// The decompressed deep samples.
     pub samples: DeepSamples,
@@ -616,7 +617,9 @@ impl<R: super::reader::ChunksReader> Iterator for ParallelDeepBlockDecompressor<
 /// Decompress a single compressed chunk into a `DeepUncompressedBlock`.
 ///
 /// Helper function used by both sequential and parallel decompression.
-fn decompress_deep_chunk(
+/// Blocks at the right and bottom edge of the data window
+/// are decompressed with their actual, smaller size.
+pub(crate) fn decompress_deep_chunk(
     compressed: &crate::block::chunk::CompressedBlock,
     meta: &crate::meta::MetaData,
     layer_index: usize,
@@ -624,49 +627,49 @@ fn decompress_deep_chunk(
 ) -> Result<DeepUncompressedBlock> {
     use crate::block::chunk::CompressedBlock;
 
-    let header = &meta.headers[layer_index];
-
-    match compressed {
-        CompressedBlock::DeepScanLine(ref block) => {
-            let samples = decompress_deep_scanline_block(
-                block,
-                header.compression,
-                &header.channels,
-                header.layer_size.width(),
-                header.compression.scan_lines_per_block(),
-                pedantic,
-            )?;
-
-            Ok(DeepUncompressedBlock {
-                layer_index,
-                y_coordinate: block.y_coordinate,
-                samples,
-            })
-        }
-        CompressedBlock::DeepTile(ref block) => {
-            // For tiles, tile size comes from header
-            let tile_desc = match header.blocks {
-                crate::meta::BlockDescription::Tiles(ref tiles) => tiles,
-                _ => return Err(Error::invalid("deep tile block in non-tiled layer")),
-            };
-
-            let samples = decompress_deep_tile_block(
-                block,
-                header.compression,
-                &header.channels,
-                tile_desc.tile_size.width(),
-                tile_desc.tile_size.height(),
-                pedantic,
-            )?;
-
-            Ok(DeepUncompressedBlock {
-                layer_index,
-                y_coordinate: block.coordinates.tile_index.y() as i32,
-                samples,
-            })
-        }
-        _ => Err(Error::invalid("expected deep block, got flat block")),
-    }
+    let header = meta
+        .headers
+        .get(layer_index)
+        .ok_or(Error::invalid("chunk layer index"))?;
+
+    let tile_data_indices = header.get_block_data_indices(compressed)?;
+    let absolute_indices = header.get_absolute_block_pixel_coordinates(tile_data_indices)?;
+    absolute_indices.validate(Some(header.layer_size))?;
+
+    let index = super::BlockIndex {
+        layer: layer_index,
+        pixel_position: absolute_indices.position.to_usize("data indices start")?,
+        level: tile_data_indices.level_index,
+        pixel_size: absolute_indices.size,
+    };
+
+    let samples = match compressed {
+        CompressedBlock::DeepScanLine(ref block) => decompress_deep_scanline_block(
+            block,
+            header.compression,
+            &header.channels,
+            index.pixel_size.width(),
+            index.pixel_size.height(),
+            pedantic,
+        )?,
+
+        CompressedBlock::DeepTile(ref block) => decompress_deep_tile_block(
+            block,
+            header.compression,
+            &header.channels,
+            index.pixel_size.width(),
+            index.pixel_size.height(),
+            pedantic,
+        )?,
+
+        _ => return Err(Error::invalid("expected deep block, got flat block")),
+    };
+
+    Ok(DeepUncompressedBlock {
+        layer_index,
+        index,
+        samples,
+    })
 }
 
 /// Sequential deep block decompressor (fallback when rayon is disabled or unhelpful).
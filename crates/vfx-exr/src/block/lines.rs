@@ -49,10 +49,13 @@ pub struct LineIndex {
     pub level: Vec2<usize>,
 
     /// Position of the most left pixel of the row.
+    /// For subsampled channels, this is the position inside the subsampled
+    /// sample grid of the channel, not the position in the pixel grid.
     pub position: Vec2<usize>,
 
     /// The width of the line; the number of samples in this row,
     /// that is, the number of f16, f32, or u32 values.
+    /// Smaller than the block width for subsampled channels.
     pub sample_count: usize,
 }
 
@@ -60,6 +63,7 @@ impl LineIndex {
     /// Iterates the lines of this block index in interleaved fashion:
     /// For each line in this block, this iterator steps once through each channel.
     /// This is how lines are stored in a pixel data block.
+    /// Subsampled channels are skipped on lines that contain no samples for that channel.
     ///
     /// Does not check whether `self.layer_index`, `self.level`, `self.size` and `self.position` are valid indices.__
     // TODO be sure this cannot produce incorrect data, as this is not further checked but only handled with panics
@@ -75,55 +79,62 @@ impl LineIndex {
             width: usize,
             end_y: usize,
             x: usize,
-            channel_sizes: SmallVec<[usize; 8]>,
+            channel_sampling: SmallVec<[(Vec2<usize>, usize); 8]>,
             byte: usize,
             channel: usize,
             y: usize,
         }
 
-        // FIXME what about sub sampling??
-
         impl Iterator for LineIter {
             type Item = (Range<usize>, LineIndex);
             // TODO size hint?
 
             fn next(&mut self) -> Option<Self::Item> {
-                if self.y < self.end_y {
-                    // compute return value before incrementing
-                    let byte_len = self.channel_sizes[self.channel];
-                    let return_value = (
-                        (self.byte..self.byte + byte_len),
-                        LineIndex {
-                            channel: self.channel,
-                            layer: self.layer,
-                            level: self.level,
-                            position: Vec2(self.x, self.y),
-                            sample_count: self.width,
-                        },
-                    );
+                while self.y < self.end_y {
+                    let (sampling, bytes_per_sample) = self.channel_sampling[self.channel];
+                    let channel = self.channel;
+                    let y = self.y;
 
                     {
                         // increment indices
-                        self.byte += byte_len;
                         self.channel += 1;
 
-                        if self.channel == self.channel_sizes.len() {
+                        if self.channel == self.channel_sampling.len() {
                             self.channel = 0;
                             self.y += 1;
                         }
                     }
 
-                    Some(return_value)
-                } else {
-                    None
+                    // this line contains no samples for this channel
+                    if y % sampling.y() != 0 {
+                        continue;
+                    }
+
+                    let sample_count = self.width / sampling.x();
+                    let byte_len = sample_count * bytes_per_sample;
+                    let return_value = (
+                        (self.byte..self.byte + byte_len),
+                        LineIndex {
+                            channel,
+                            layer: self.layer,
+                            level: self.level,
+                            position: Vec2(self.x / sampling.x(), y / sampling.y()),
+                            sample_count,
+                        },
+                    );
+
+                    self.byte += byte_len;
+                    return Some(return_value);
                 }
+
+                None
             }
         }
 
-        let channel_line_sizes: SmallVec<[usize; 8]> = channels
+        let channel_sampling: SmallVec<[(Vec2<usize>, usize); 8]> = channels
             .list
             .iter()
-            .map(move |channel| block.pixel_size.0 * channel.sample_type.bytes_per_sample()) // FIXME is it fewer samples per tile or just fewer tiles for sampled images???
+            .map(|channel| (channel.sampling, channel.sample_type.bytes_per_sample()))
             .collect();
 
         LineIter {
@@ -132,7 +143,7 @@ impl LineIndex {
             width: block.pixel_size.0,
             x: block.pixel_position.0,
             end_y: block.pixel_position.y() + block.pixel_size.height(),
-            channel_sizes: channel_line_sizes,
+            channel_sampling,
 
             byte: 0,
             channel: 0,
@@ -0,0 +1,423 @@
+//! Read as much as possible from damaged files, for example files that were truncated while writing.
+//!
+//! Every offset table entry is checked against the chunk it points to.
+//! Missing, zeroed or invalid offsets are rebuilt by scanning the chunks in the file.
+//! Blocks that cannot be found or decompressed are filled with a constant value
+//! and reported, instead of aborting the whole read.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::{Read, Seek, SeekFrom};
+
+use half::f16;
+
+use crate::block::chunk::{Chunk, TileCoordinates};
+use crate::block::{BlockIndex, UncompressedBlock};
+use crate::error::{Error, Result};
+use crate::io::{Data, PeekRead, Tracking};
+use crate::meta::attribute::SampleType;
+use crate::meta::header::Header;
+use crate::meta::MetaData;
+
+/// What was damaged in a recovered file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecoveryReport {
+    /// Number of blocks that were requested from the file.
+    pub block_count: usize,
+
+    /// Number of offset table entries that were missing or invalid,
+    /// but whose chunk was found by scanning the file.
+    pub rebuilt_offset_count: usize,
+
+    /// Blocks that could not be found or decompressed, and were filled instead.
+    /// Sorted by header, then in increasing y order.
+    pub lost_blocks: Vec<BlockIndex>,
+}
+
+impl RecoveryReport {
+    /// Whether the file could be read without any repair.
+    pub fn is_intact(&self) -> bool {
+        self.rebuilt_offset_count == 0 && self.lost_blocks.is_empty()
+    }
+}
+
+/// Decode the meta data of a possibly damaged file, and locate all chunks that are still intact.
+/// Only the meta data must be readable, all pixel data may be missing.
+/// Deep data is not supported.
+#[derive(Debug)]
+pub struct RecoveryReader<R> {
+    meta_data: MetaData,
+
+    /// Byte position of each block, for each header, in increasing y order.
+    offsets: Vec<Vec<Option<usize>>>,
+
+    rebuilt_offset_count: usize,
+    remaining_bytes: PeekRead<Tracking<R>>,
+}
+
+impl<R: Read + Seek> RecoveryReader<R> {
+    /// Read the meta data and check the offset tables, rebuilding them where necessary.
+    /// The source is assumed to be buffered.
+    pub fn read_from_buffered(mut read: R) -> Result<Self> {
+        let file_size = usize::try_from(read.seek(SeekFrom::End(0))?)?;
+        read.seek(SeekFrom::Start(0))?;
+
+        let mut remaining_bytes = PeekRead::new(Tracking::new(read));
+        let meta_data =
+            MetaData::read_validated_from_buffered_peekable(&mut remaining_bytes, false)?;
+
+        if meta_data.headers.iter().any(|header| header.deep) {
+            return Err(Error::unsupported("recovering deep data"));
+        }
+
+        let chunk_count: usize = meta_data
+            .headers
+            .iter()
+            .map(|header| header.chunk_count)
+            .sum();
+        let chunks_start = remaining_bytes.byte_position() + chunk_count * u64::BYTE_SIZE;
+
+        // a truncated offset table leaves the remaining offsets unknown
+        let offsets = meta_data
+            .headers
+            .iter()
+            .map(|header| {
+                (0..header.chunk_count)
+                    .map(|_| {
+                        u64::read_le(&mut remaining_bytes)
+                            .ok()
+                            .and_then(|offset| usize::try_from(offset).ok())
+                            .filter(|&offset| offset >= chunks_start && offset < file_size)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let block_indices: Vec<HashMap<TileCoordinates, usize>> = meta_data
+            .headers
+            .iter()
+            .map(|header| {
+                header
+                    .blocks_increasing_y_order()
+                    .enumerate()
+                    .map(|(index, tile)| (tile.location, index))
+                    .collect()
+            })
+            .collect();
+
+        let mut reader = Self {
+            meta_data,
+            offsets,
+            rebuilt_offset_count: 0,
+            remaining_bytes,
+        };
+
+        reader.validate_offsets(&block_indices);
+
+        if reader.offsets.iter().flatten().any(Option::is_none) {
+            reader.scan_chunks(&block_indices, chunks_start, file_size);
+        }
+
+        Ok(reader)
+    }
+
+    /// The decoded exr meta data from the file.
+    pub fn meta_data(&self) -> &MetaData {
+        &self.meta_data
+    }
+
+    /// The decoded exr headers from the file.
+    pub fn headers(&self) -> &[Header] {
+        &self.meta_data.headers
+    }
+
+    /// Number of offsets that had to be found by scanning the file.
+    pub fn rebuilt_offset_count(&self) -> usize {
+        self.rebuilt_offset_count
+    }
+
+    /// Prepare to decompress the blocks accepted by the filter.
+    /// Lost blocks are replaced by blocks where every sample has the fill value.
+    pub fn filter_blocks(
+        self,
+        fill_value: f32,
+        mut filter: impl FnMut(&MetaData, TileCoordinates, BlockIndex) -> bool,
+    ) -> Result<RecoveredBlocks<R>> {
+        let mut blocks = Vec::new();
+
+        for (header_index, header) in self.meta_data.headers.iter().enumerate() {
+            for (block_index, tile) in header.blocks_increasing_y_order().enumerate() {
+                let data_indices = header.get_absolute_block_pixel_coordinates(tile.location)?;
+
+                let block = BlockIndex {
+                    layer: header_index,
+                    level: tile.location.level_index,
+                    pixel_position: data_indices.position.to_usize("data indices start")?,
+                    pixel_size: data_indices.size,
+                };
+
+                if filter(&self.meta_data, tile.location, block) {
+                    blocks.push((block_index, block));
+                }
+            }
+        }
+
+        Ok(RecoveredBlocks {
+            report: RecoveryReport {
+                block_count: blocks.len(),
+                rebuilt_offset_count: self.rebuilt_offset_count,
+                lost_blocks: Vec::new(),
+            },
+            remaining_blocks: blocks.into_iter(),
+            fill_value,
+            reader: self,
+        })
+    }
+
+    /// Discard all offsets that do not point to the chunk of their block.
+    fn validate_offsets(&mut self, block_indices: &[HashMap<TileCoordinates, usize>]) {
+        for header_index in 0..self.offsets.len() {
+            for block_index in 0..self.offsets[header_index].len() {
+                if let Some(position) = self.offsets[header_index][block_index] {
+                    let location = self
+                        .chunk_at(position)
+                        .ok()
+                        .and_then(|chunk| self.locate(block_indices, &chunk));
+
+                    if location != Some((header_index, block_index)) {
+                        self.offsets[header_index][block_index] = None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Read the chunks one after another, starting at the first chunk, and remember their position.
+    /// Damaged sections are skipped by continuing at the next valid offset from the offset tables.
+    fn scan_chunks(
+        &mut self,
+        block_indices: &[HashMap<TileCoordinates, usize>],
+        chunks_start: usize,
+        file_size: usize,
+    ) {
+        let mut known_offsets: Vec<usize> =
+            self.offsets.iter().flatten().flatten().copied().collect();
+        known_offsets.sort_unstable();
+
+        let mut position = chunks_start;
+        while position < file_size {
+            let location = self
+                .chunk_at(position)
+                .ok()
+                .and_then(|chunk| self.locate(block_indices, &chunk));
+
+            if let Some((header_index, block_index)) = location {
+                let offset = &mut self.offsets[header_index][block_index];
+                if offset.is_none() {
+                    *offset = Some(position);
+                    self.rebuilt_offset_count += 1;
+                }
+
+                position = self.remaining_bytes.byte_position();
+            } else {
+                match known_offsets.iter().find(|&&offset| offset > position) {
+                    Some(&next) => position = next,
+                    None => break,
+                }
+            }
+        }
+    }
+
+    fn chunk_at(&mut self, position: usize) -> Result<Chunk> {
+        self.remaining_bytes.skip_to(position)?;
+        Chunk::read(&mut self.remaining_bytes, &self.meta_data)
+    }
+
+    /// The header index and block index of a chunk, if the chunk belongs to a block of the image.
+    fn locate(
+        &self,
+        block_indices: &[HashMap<TileCoordinates, usize>],
+        chunk: &Chunk,
+    ) -> Option<(usize, usize)> {
+        let header = self.meta_data.headers.get(chunk.layer_index)?;
+        let coordinates = header
+            .get_block_data_indices(&chunk.compressed_block)
+            .ok()?;
+
+        let block_index = *block_indices.get(chunk.layer_index)?.get(&coordinates)?;
+        Some((chunk.layer_index, block_index))
+    }
+}
+
+/// Decompresses the requested blocks of a damaged file, one after another.
+/// Every requested block is returned, lost blocks are filled with a constant value.
+/// Call `into_report` after iterating to find out which blocks were lost.
+#[derive(Debug)]
+pub struct RecoveredBlocks<R> {
+    reader: RecoveryReader<R>,
+    remaining_blocks: std::vec::IntoIter<(usize, BlockIndex)>,
+    fill_value: f32,
+    report: RecoveryReport,
+}
+
+impl<R: Read + Seek> RecoveredBlocks<R> {
+    /// The decoded exr meta data from the file.
+    pub fn meta_data(&self) -> &MetaData {
+        &self.reader.meta_data
+    }
+
+    /// The number of blocks that were requested.
+    pub fn block_count(&self) -> usize {
+        self.report.block_count
+    }
+
+    /// What was damaged in the blocks that have been returned so far.
+    pub fn into_report(self) -> RecoveryReport {
+        self.report
+    }
+
+    fn decompress(&mut self, block_index: usize, block: BlockIndex) -> Option<UncompressedBlock> {
+        let position = self.reader.offsets[block.layer][block_index]?;
+        let chunk = self.reader.chunk_at(position).ok()?;
+        let decompressed =
+            UncompressedBlock::decompress_chunk(chunk, &self.reader.meta_data, false).ok()?;
+
+        Some(decompressed).filter(|decompressed| decompressed.index == block)
+    }
+}
+
+impl<R: Read + Seek> ExactSizeIterator for RecoveredBlocks<R> {}
+impl<R: Read + Seek> Iterator for RecoveredBlocks<R> {
+    type Item = UncompressedBlock;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (block_index, block) = self.remaining_blocks.next()?;
+
+        Some(self.decompress(block_index, block).unwrap_or_else(|| {
+            self.report.lost_blocks.push(block);
+            let header = &self.reader.meta_data.headers[block.layer];
+            filled_block(header, block, self.fill_value)
+        }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.remaining_blocks.size_hint()
+    }
+}
+
+/// A block where every sample has the same value.
+fn filled_block(header: &Header, index: BlockIndex, value: f32) -> UncompressedBlock {
+    UncompressedBlock::from_lines(&header.channels, index, |line| {
+        match header.channels.list[line.location.channel].sample_type {
+            SampleType::F16 => line.write_samples(|_| f16::from_f32(value)),
+            SampleType::F32 => line.write_samples(|_| value),
+            SampleType::U32 => line.write_samples(|_| value as u32),
+        }
+        .expect("writing line bytes failed");
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prelude::*;
+    use std::io::Cursor;
+
+    fn test_image(blocks: Blocks) -> Vec<u8> {
+        let size = Vec2(32, 64);
+        let channels = AnyChannels::sort(smallvec::smallvec![
+            AnyChannel::new(
+                "R",
+                FlatSamples::F32((0..size.area()).map(|i| i as f32 + 1.0).collect())
+            ),
+            AnyChannel::new(
+                "G",
+                FlatSamples::F16(
+                    (0..size.area())
+                        .map(|i| f16::from_f32((i % 100) as f32 + 1.0))
+                        .collect()
+                )
+            ),
+        ]);
+
+        let layer = Layer::new(
+            size,
+            LayerAttributes::named("render"),
+            Encoding {
+                compression: Compression::ZIP16,
+                blocks,
+                line_order: LineOrder::Increasing,
+            },
+            channels,
+        );
+
+        let mut bytes = Vec::new();
+        Image::from_layer(layer)
+            .write()
+            .to_buffered(Cursor::new(&mut bytes))
+            .unwrap();
+        bytes
+    }
+
+    fn recover(bytes: &[u8]) -> (Vec<UncompressedBlock>, RecoveryReport) {
+        let reader = RecoveryReader::read_from_buffered(Cursor::new(bytes)).unwrap();
+        let mut blocks = reader.filter_blocks(-1.0, |_, _, _| true).unwrap();
+        let decompressed = blocks.by_ref().collect();
+        (decompressed, blocks.into_report())
+    }
+
+    fn offset_table_range(bytes: &[u8]) -> std::ops::Range<usize> {
+        let mut read = PeekRead::new(Tracking::new(Cursor::new(bytes)));
+        let meta = MetaData::read_validated_from_buffered_peekable(&mut read, false).unwrap();
+        let start = read.byte_position();
+        let count: usize = meta.headers.iter().map(|header| header.chunk_count).sum();
+        start..start + count * u64::BYTE_SIZE
+    }
+
+    #[test]
+    fn intact_file_needs_no_repair() {
+        let bytes = test_image(Blocks::ScanLines);
+        let (blocks, report) = recover(&bytes);
+        assert!(report.is_intact());
+        assert_eq!(blocks.len(), report.block_count);
+    }
+
+    #[test]
+    fn rebuilds_zeroed_offset_tables() {
+        for blocks in [Blocks::ScanLines, Blocks::Tiles(Vec2(16, 16))] {
+            let original = test_image(blocks);
+            let (expected, _) = recover(&original);
+
+            let mut damaged = original.clone();
+            for byte in &mut damaged[offset_table_range(&original)] {
+                *byte = 0;
+            }
+
+            let (recovered, report) = recover(&damaged);
+            assert!(report.lost_blocks.is_empty());
+            assert_eq!(report.rebuilt_offset_count, report.block_count);
+            assert_eq!(recovered, expected);
+        }
+    }
+
+    #[test]
+    fn fills_truncated_blocks() {
+        let original = test_image(Blocks::ScanLines);
+        let (expected, _) = recover(&original);
+
+        // the last chunk is cut in half, and the offset tables are lost
+        let mut damaged = original[..original.len() - 40].to_vec();
+        for byte in &mut damaged[offset_table_range(&original)] {
+            *byte = 0;
+        }
+
+        let (recovered, report) = recover(&damaged);
+        assert_eq!(report.lost_blocks.len(), 1);
+        assert_eq!(report.lost_blocks[0].pixel_position, Vec2(0, 48));
+        assert_eq!(recovered[..3], expected[..3]);
+
+        let filled = &recovered[3];
+        assert_eq!(filled.index, expected[3].index);
+        assert_eq!(&filled.data[..2], &f16::from_f32(-1.0).to_ne_bytes());
+    }
+}
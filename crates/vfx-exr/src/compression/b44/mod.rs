@@ -241,7 +241,6 @@ struct ChannelData {
     y_sampling: usize,
     sample_type: SampleType,
     quantize_linearly: bool,
-    samples_per_pixel: usize,
 }
 
 // Safe implementation using lebe crate for native endian copy.
@@ -272,7 +271,7 @@ pub fn decompress(
 ) -> Result<ByteVec> {
     debug_assert_eq!(
         expected_byte_size,
-        rectangle.size.area() * channels.bytes_per_pixel,
+        channels.byte_size_of_section(rectangle),
         "expected byte size does not match header" // TODO compute instead of passing argument?
     );
 
@@ -290,16 +289,13 @@ pub fn decompress(
         let channel = ChannelData {
             tmp_start_index: tmp_read_index,
             tmp_end_index: tmp_read_index,
-            resolution: channel.subsampled_resolution(rectangle.size),
+            resolution: channel.subsampled_section_size(rectangle),
             y_sampling: channel.sampling.y(),
             sample_type: channel.sample_type,
             quantize_linearly: channel.quantize_linearly,
-            samples_per_pixel: channel.sampling.area(),
         };
 
-        tmp_read_index += channel.resolution.area()
-            * channel.samples_per_pixel
-            * channel.sample_type.bytes_per_sample();
+        tmp_read_index += channel.resolution.area() * channel.sample_type.bytes_per_sample();
 
         channel_data.push(channel);
     }
@@ -317,7 +313,7 @@ pub fn decompress(
         debug_assert_eq!(remaining_le, compressed_le.len() - in_i);
 
         // Compute information for current channel.
-        let sample_count = channel.resolution.area() * channel.samples_per_pixel;
+        let sample_count = channel.resolution.area();
         let byte_count = sample_count * channel.sample_type.bytes_per_sample();
 
         // Sample types that does not support B44 compression (u32 and f32) are raw copied.
@@ -345,8 +341,8 @@ pub fn decompress(
         // Increase buffer to get new uncompressed datas.
         tmp.resize(tmp.len() + byte_count, 0);
 
-        let x_sample_count = channel.resolution.x() * channel.samples_per_pixel;
-        let y_sample_count = channel.resolution.y() * channel.samples_per_pixel;
+        let x_sample_count = channel.resolution.x();
+        let y_sample_count = channel.resolution.y();
 
         let bytes_per_sample = size_of::<u16>();
 
@@ -445,7 +441,7 @@ pub fn decompress(
             }
 
             // Find data location in temporary buffer.
-            let x_sample_count = channel.resolution.x() * channel.samples_per_pixel;
+            let x_sample_count = channel.resolution.x();
             let bytes_per_line = x_sample_count * channel.sample_type.bytes_per_sample();
             let next_tmp_end_index = channel.tmp_end_index + bytes_per_line;
             let channel_bytes = &tmp[channel.tmp_end_index..next_tmp_end_index];
@@ -507,9 +503,9 @@ pub fn compress(
 
     let mut tmp_end_index = 0;
     for channel in &channels.list {
-        let number_samples = channel.subsampled_resolution(rectangle.size);
+        let number_samples = channel.subsampled_section_size(rectangle);
 
-        let sample_count = channel.subsampled_resolution(rectangle.size).area();
+        let sample_count = number_samples.area();
         let byte_count = sample_count * channel.sample_type.bytes_per_sample();
 
         let channel = ChannelData {
@@ -519,7 +515,6 @@ pub fn compress(
             resolution: number_samples,
             sample_type: channel.sample_type,
             quantize_linearly: channel.quantize_linearly,
-            samples_per_pixel: channel.sampling.area(),
         };
 
         tmp_end_index += byte_count;
@@ -538,7 +533,7 @@ pub fn compress(
                 continue;
             }
 
-            let x_sample_count = channel.resolution.x() * channel.samples_per_pixel;
+            let x_sample_count = channel.resolution.x();
             let bytes_per_line = x_sample_count * channel.sample_type.bytes_per_sample();
             let next_tmp_end_index = channel.tmp_end_index + bytes_per_line;
             let target = &mut tmp[channel.tmp_end_index..next_tmp_end_index];
@@ -571,7 +566,20 @@ pub fn compress(
     }
 
     // Generate a whole buffer that we will crop to proper size once compression is done.
-    let mut b44_compressed = vec![0; std::cmp::max(2048, uncompressed_le.len())];
+    // Padding small or subsampled half channels to 4x4 blocks can make the
+    // output larger than the input, so reserve the worst case per channel.
+    let max_compressed_size: usize = channel_data
+        .iter()
+        .map(|channel| match channel.sample_type {
+            SampleType::F16 => {
+                channel.resolution.x().div_ceil(BLOCK_SAMPLE_COUNT)
+                    * channel.resolution.y().div_ceil(BLOCK_SAMPLE_COUNT)
+                    * 14
+            }
+            _ => channel.tmp_end_index - channel.tmp_start_index,
+        })
+        .sum();
+    let mut b44_compressed = vec![0; std::cmp::max(2048, max_compressed_size)];
     let mut b44_end = 0; // Buffer byte index for storing next compressed values.
 
     for channel in &channel_data {
@@ -593,8 +601,8 @@ pub fn compress(
         debug_assert_eq!(channel.sample_type, SampleType::F16);
         debug_assert_eq!(channel.sample_type.bytes_per_sample(), size_of::<u16>());
 
-        let x_sample_count = channel.resolution.x() * channel.samples_per_pixel;
-        let y_sample_count = channel.resolution.y() * channel.samples_per_pixel;
+        let x_sample_count = channel.resolution.x();
+        let y_sample_count = channel.resolution.y();
 
         let x_byte_count = x_sample_count * size_of::<u16>();
         let cd_start = channel.tmp_start_index;
@@ -794,6 +802,29 @@ mod test {
         assert_eq!(decompressed.len(), 24);
     }
 
+    #[test]
+    fn roundtrip_noise_f16_padding_exceeds_input() {
+        let channel = ChannelDescription {
+            sample_type: SampleType::F16,
+            name: Default::default(),
+            quantize_linearly: false,
+            sampling: Vec2(1, 1),
+        };
+
+        // Each single line channel is padded to 4x4 blocks,
+        // so the compressed data is larger than the uncompressed data.
+        let channels = ChannelList::new((0..200).map(|_| channel.clone()).collect());
+
+        let rectangle = IntegerBounds {
+            position: Vec2(0, 0),
+            size: Vec2(8, 1),
+        };
+
+        let (pixel_bytes, _, decompressed) = test_roundtrip_noise_with(channels, rectangle);
+        assert_eq!(pixel_bytes.len(), 3200);
+        assert_eq!(decompressed.len(), 3200);
+    }
+
     #[test]
     fn roundtrip_noise_f32() {
         let channel = ChannelDescription {
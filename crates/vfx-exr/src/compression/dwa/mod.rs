@@ -0,0 +1,1474 @@
+//! Lossy DCT-based compression for f16 and f32 channels, as used by `DWAA` and `DWAB`.
+// see https://github.com/AcademySoftwareFoundation/openexr/blob/main/src/lib/OpenEXR/ImfDwaCompressor.cpp
+
+// This compressor is based on source code that was contributed to
+// OpenEXR by DreamWorks Animation LLC.
+
+//  Each channel of a block is classified by a set of rules, which match the
+//  suffix of the channel name (the part after the last dot) and its sample type.
+//  Channels are then compressed with one of three schemes:
+//
+//  - LOSSY_DCT: The values are converted to a perceptual, non-linear
+//    representation, split into 8x8 blocks, transformed with a DCT and
+//    quantized. If a red, green and blue channel share the same layer prefix,
+//    they are first converted to Y'CbCr. The DC coefficients are compressed
+//    with zlib, the run-length encoded AC coefficients with huffman coding.
+//  - RLE: The bytes of each sample are split into planes, run-length
+//    encoded and then compressed with zlib. Used for alpha channels.
+//  - UNKNOWN: All other channels are stored losslessly with zlib.
+//
+//  The rules used for classification are stored in every block,
+//  so that files written with custom rules can be decoded.
+
+mod table;
+
+use super::optimize_bytes::*;
+use super::piz::huffman;
+use super::{mod_p, rle, zip, ByteVec};
+use crate::error::{usize_to_i32, Error, Result};
+use crate::math::Vec2;
+use crate::meta::attribute::{ChannelList, IntegerBounds, SampleType};
+use half::f16;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use table::{TO_LINEAR, TO_NONLINEAR};
+
+/// The compression level used if none is specified in the header.
+pub const DEFAULT_LEVEL: f32 = 45.0;
+
+/// The newest version of the block layout. Version 2 stores the classification rules.
+const VERSION: u64 = 2;
+
+/// Number of `u64` values at the start of each compressed block.
+const SIZE_COUNT: usize = 11;
+
+// indices into the table of sizes at the start of each block
+const VERSION_INDEX: usize = 0;
+const UNKNOWN_UNCOMPRESSED_SIZE: usize = 1;
+const UNKNOWN_COMPRESSED_SIZE: usize = 2;
+const AC_COMPRESSED_SIZE: usize = 3;
+const DC_COMPRESSED_SIZE: usize = 4;
+const RLE_COMPRESSED_SIZE: usize = 5;
+const RLE_UNCOMPRESSED_SIZE: usize = 6;
+const RLE_RAW_SIZE: usize = 7;
+const AC_UNCOMPRESSED_COUNT: usize = 8;
+const DC_UNCOMPRESSED_COUNT: usize = 9;
+const AC_COMPRESSION: usize = 10;
+
+// how the ac coefficients are compressed
+const AC_STATIC_HUFFMAN: u64 = 0;
+const AC_DEFLATE: u64 = 1;
+
+/// Marks the end of a block, or, combined with a count in the low byte, a run of zeroes.
+const AC_RUN_MARKER: u16 = 0xff00;
+
+/// Position of each natural-order coefficient in the zig-zag order.
+const ZIG_ZAG: [usize; 64] = [
+    0, 1, 5, 6, 14, 15, 27, 28, //
+    2, 4, 7, 13, 16, 26, 29, 42, //
+    3, 8, 12, 17, 25, 30, 41, 43, //
+    9, 11, 18, 24, 31, 40, 44, 53, //
+    10, 19, 23, 32, 39, 45, 52, 54, //
+    20, 22, 33, 38, 46, 51, 55, 60, //
+    21, 34, 37, 47, 50, 56, 59, 61, //
+    35, 36, 48, 49, 57, 58, 62, 63,
+];
+
+// the generic JPEG quantization tables, normalized by their smallest value when used
+const QUANT_TABLE_Y: [u16; 64] = [
+    16, 11, 10, 16, 24, 40, 51, 61, //
+    12, 12, 14, 19, 26, 58, 60, 55, //
+    14, 13, 16, 24, 40, 57, 69, 56, //
+    14, 17, 22, 29, 51, 87, 80, 62, //
+    18, 22, 37, 56, 68, 109, 103, 77, //
+    24, 35, 55, 64, 81, 104, 113, 92, //
+    49, 64, 78, 87, 103, 121, 120, 101, //
+    72, 92, 95, 98, 112, 100, 103, 99,
+];
+
+const QUANT_TABLE_Y_MIN: u16 = 10;
+
+const QUANT_TABLE_CBCR: [u16; 64] = [
+    17, 18, 24, 47, 99, 99, 99, 99, //
+    18, 21, 26, 66, 99, 99, 99, 99, //
+    24, 26, 56, 99, 99, 99, 99, 99, //
+    47, 66, 99, 99, 99, 99, 99, 99, //
+    99, 99, 99, 99, 99, 99, 99, 99, //
+    99, 99, 99, 99, 99, 99, 99, 99, //
+    99, 99, 99, 99, 99, 99, 99, 99, //
+    99, 99, 99, 99, 99, 99, 99, 99,
+];
+
+const QUANT_TABLE_CBCR_MIN: u16 = 17;
+
+/// How the samples of a channel are compressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scheme {
+    Unknown,
+    LossyDct,
+    Rle,
+}
+
+impl Scheme {
+    fn from_bits(bits: u8) -> Result<Self> {
+        match bits {
+            0 => Ok(Scheme::Unknown),
+            1 => Ok(Scheme::LossyDct),
+            2 => Ok(Scheme::Rle),
+            _ => Err(Error::invalid("dwa channel compression scheme")),
+        }
+    }
+
+    fn to_bits(self) -> u8 {
+        match self {
+            Scheme::Unknown => 0,
+            Scheme::LossyDct => 1,
+            Scheme::Rle => 2,
+        }
+    }
+}
+
+/// A rule that decides how a channel is compressed, based on the channel name and type.
+#[derive(Debug, Clone, PartialEq)]
+struct Classifier {
+    suffix: &'static [u8],
+    scheme: Scheme,
+    sample_type: SampleType,
+
+    /// The index of this channel in an RGB triple, which will be converted to Y'CbCr.
+    csc_index: Option<usize>,
+
+    case_insensitive: bool,
+}
+
+/// A classifier read from a file, which may contain any suffix.
+#[derive(Debug, Clone, PartialEq)]
+struct OwnedClassifier {
+    suffix: Vec<u8>,
+    scheme: Scheme,
+    sample_type: SampleType,
+    csc_index: Option<usize>,
+    case_insensitive: bool,
+}
+
+const fn rule(
+    suffix: &'static [u8],
+    scheme: Scheme,
+    sample_type: SampleType,
+    csc_index: Option<usize>,
+    case_insensitive: bool,
+) -> Classifier {
+    Classifier {
+        suffix,
+        scheme,
+        sample_type,
+        csc_index,
+        case_insensitive,
+    }
+}
+
+/// The rules used when writing new files.
+const DEFAULT_RULES: [Classifier; 15] = [
+    rule(b"R", Scheme::LossyDct, SampleType::F16, Some(0), false),
+    rule(b"R", Scheme::LossyDct, SampleType::F32, Some(0), false),
+    rule(b"G", Scheme::LossyDct, SampleType::F16, Some(1), false),
+    rule(b"G", Scheme::LossyDct, SampleType::F32, Some(1), false),
+    rule(b"B", Scheme::LossyDct, SampleType::F16, Some(2), false),
+    rule(b"B", Scheme::LossyDct, SampleType::F32, Some(2), false),
+    rule(b"Y", Scheme::LossyDct, SampleType::F16, None, false),
+    rule(b"Y", Scheme::LossyDct, SampleType::F32, None, false),
+    rule(b"BY", Scheme::LossyDct, SampleType::F16, None, false),
+    rule(b"BY", Scheme::LossyDct, SampleType::F32, None, false),
+    rule(b"RY", Scheme::LossyDct, SampleType::F16, None, false),
+    rule(b"RY", Scheme::LossyDct, SampleType::F32, None, false),
+    rule(b"A", Scheme::Rle, SampleType::U32, None, false),
+    rule(b"A", Scheme::Rle, SampleType::F16, None, false),
+    rule(b"A", Scheme::Rle, SampleType::F32, None, false),
+];
+
+/// The rules implied by blocks of version 1, which do not store their rules.
+const LEGACY_RULES: [Classifier; 14] = [
+    rule(b"r", Scheme::LossyDct, SampleType::F16, Some(0), true),
+    rule(b"red", Scheme::LossyDct, SampleType::F16, Some(0), true),
+    rule(b"g", Scheme::LossyDct, SampleType::F16, Some(1), true),
+    rule(b"grn", Scheme::LossyDct, SampleType::F16, Some(1), true),
+    rule(b"green", Scheme::LossyDct, SampleType::F16, Some(1), true),
+    rule(b"b", Scheme::LossyDct, SampleType::F16, Some(2), true),
+    rule(b"blu", Scheme::LossyDct, SampleType::F16, Some(2), true),
+    rule(b"blue", Scheme::LossyDct, SampleType::F16, Some(2), true),
+    rule(b"y", Scheme::LossyDct, SampleType::F16, None, true),
+    rule(b"by", Scheme::LossyDct, SampleType::F16, None, true),
+    rule(b"ry", Scheme::LossyDct, SampleType::F16, None, true),
+    rule(b"a", Scheme::Rle, SampleType::U32, None, true),
+    rule(b"a", Scheme::Rle, SampleType::F16, None, true),
+    rule(b"a", Scheme::Rle, SampleType::F32, None, true),
+];
+
+impl Classifier {
+    fn to_owned(&self) -> OwnedClassifier {
+        OwnedClassifier {
+            suffix: self.suffix.to_vec(),
+            scheme: self.scheme,
+            sample_type: self.sample_type,
+            csc_index: self.csc_index,
+            case_insensitive: self.case_insensitive,
+        }
+    }
+}
+
+impl OwnedClassifier {
+    fn matches(&self, suffix: &[u8], sample_type: SampleType) -> bool {
+        sample_type == self.sample_type
+            && if self.case_insensitive {
+                suffix.eq_ignore_ascii_case(&self.suffix)
+            } else {
+                suffix == self.suffix.as_slice()
+            }
+    }
+
+    fn byte_size(&self) -> usize {
+        self.suffix.len() + 1 + 2
+    }
+
+    fn write(&self, out: &mut Vec<u8>) {
+        let csc_bits = self.csc_index.map_or(0, |index| index as u8 + 1);
+
+        out.extend_from_slice(&self.suffix);
+        out.push(0);
+        out.push(
+            ((csc_bits & 15) << 4)
+                | ((self.scheme.to_bits() & 3) << 2)
+                | (self.case_insensitive as u8),
+        );
+        out.push(sample_type_to_bits(self.sample_type));
+    }
+
+    fn read(bytes: &mut &[u8]) -> Result<Self> {
+        let suffix_length = bytes
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or_else(|| Error::invalid("dwa channel rule"))?;
+
+        if bytes.len() < suffix_length + 3 {
+            return Err(Error::invalid("dwa channel rule"));
+        }
+
+        let suffix = bytes[..suffix_length].to_vec();
+        let flags = bytes[suffix_length + 1];
+        let sample_type = sample_type_from_bits(bytes[suffix_length + 2])?;
+        *bytes = &bytes[suffix_length + 3..];
+
+        let csc_index = match flags >> 4 {
+            0 => None,
+            index @ 1..=3 => Some(index as usize - 1),
+            _ => return Err(Error::invalid("dwa channel rule color index")),
+        };
+
+        Ok(OwnedClassifier {
+            suffix,
+            scheme: Scheme::from_bits((flags >> 2) & 3)?,
+            sample_type,
+            csc_index,
+            case_insensitive: flags & 1 != 0,
+        })
+    }
+}
+
+fn sample_type_to_bits(sample_type: SampleType) -> u8 {
+    match sample_type {
+        SampleType::U32 => 0,
+        SampleType::F16 => 1,
+        SampleType::F32 => 2,
+    }
+}
+
+fn sample_type_from_bits(bits: u8) -> Result<SampleType> {
+    match bits {
+        0 => Ok(SampleType::U32),
+        1 => Ok(SampleType::F16),
+        2 => Ok(SampleType::F32),
+        _ => Err(Error::invalid("dwa channel rule sample type")),
+    }
+}
+
+/// The part of the channel name after the last dot.
+fn name_suffix(name: &[u8]) -> &[u8] {
+    match name.iter().rposition(|&byte| byte == b'.') {
+        Some(dot) => &name[dot + 1..],
+        None => name,
+    }
+}
+
+/// The part of the channel name before the last dot.
+fn name_prefix(name: &[u8]) -> &[u8] {
+    match name.iter().rposition(|&byte| byte == b'.') {
+        Some(dot) => &name[..dot],
+        None => &[],
+    }
+}
+
+/// Whether renaming the channels from `old` to `new` keeps the classification of
+/// every channel under any set of rules, so that existing blocks still decode.
+/// The rules are stored in each block and match the suffix, so every suffix must stay
+/// the same. Channels converted to Y'CbCr together are grouped by prefix, and the groups
+/// are processed in prefix order, so the grouping and the order of the groups must stay too.
+pub fn keeps_classification(old: &ChannelList, new: &ChannelList) -> bool {
+    fn groups(channels: &ChannelList) -> Vec<Vec<usize>> {
+        let mut by_prefix: BTreeMap<&[u8], Vec<usize>> = BTreeMap::new();
+        for (index, channel) in channels.list.iter().enumerate() {
+            by_prefix.entry(name_prefix(channel.name.bytes())).or_default().push(index);
+        }
+        by_prefix.into_values().collect()
+    }
+
+    old.list.len() == new.list.len()
+        && old
+            .list
+            .iter()
+            .zip(&new.list)
+            .all(|(old, new)| name_suffix(old.name.bytes()) == name_suffix(new.name.bytes()))
+        && groups(old) == groups(new)
+}
+
+/// The samples of a single channel within the block.
+#[derive(Debug)]
+struct ChannelData {
+    scheme: Scheme,
+    sample_type: SampleType,
+    resolution: Vec2<usize>,
+    y_sampling: usize,
+
+    /// Little-endian samples, line by line, without any other channels in between.
+    planar: Vec<u8>,
+}
+
+impl ChannelData {
+    fn line_byte_size(&self) -> usize {
+        self.resolution.width() * self.sample_type.bytes_per_sample()
+    }
+}
+
+/// Decide how each channel is compressed, and find RGB triples that
+/// can be converted to Y'CbCr together. Returns the channel indices of all triples.
+fn classify(
+    channels: &ChannelList,
+    rules: &[OwnedClassifier],
+    area: IntegerBounds,
+) -> Result<(Vec<ChannelData>, Vec<[usize; 3]>)> {
+    let mut channel_data = Vec::with_capacity(channels.list.len());
+    let mut rgb_by_prefix: BTreeMap<&[u8], [Option<usize>; 3]> = BTreeMap::new();
+
+    for (index, channel) in channels.list.iter().enumerate() {
+        let name = channel.name.bytes();
+        let suffix = name_suffix(name);
+        let mut scheme = Scheme::Unknown;
+
+        // later rules override earlier ones
+        for rule in rules {
+            if rule.matches(suffix, channel.sample_type) {
+                scheme = rule.scheme;
+
+                if let Some(csc_index) = rule.csc_index {
+                    rgb_by_prefix.entry(name_prefix(name)).or_default()[csc_index] = Some(index);
+                }
+            }
+        }
+
+        channel_data.push(ChannelData {
+            scheme,
+            sample_type: channel.sample_type,
+            resolution: channel.subsampled_section_size(area),
+            y_sampling: channel.sampling.y(),
+            planar: Vec::new(),
+        });
+    }
+
+    let mut rgb_sets = Vec::new();
+    for indices in rgb_by_prefix.values() {
+        if let [Some(r), Some(g), Some(b)] = *indices {
+            let resolution = channel_data[r].resolution;
+            if channel_data[g].resolution != resolution || channel_data[b].resolution != resolution
+            {
+                return Err(Error::unsupported(
+                    "dwa color channels with different sampling",
+                ));
+            }
+
+            rgb_sets.push([r, g, b]);
+        }
+    }
+
+    Ok((channel_data, rgb_sets))
+}
+
+/// All rules that match at least one of the channels.
+fn relevant_rules(channels: &ChannelList) -> Vec<OwnedClassifier> {
+    DEFAULT_RULES
+        .iter()
+        .map(Classifier::to_owned)
+        .filter(|rule| {
+            channels
+                .list
+                .iter()
+                .any(|channel| rule.matches(name_suffix(channel.name.bytes()), channel.sample_type))
+        })
+        .collect()
+}
+
+pub fn compress(
+    channels: &ChannelList,
+    bytes_ne: ByteVec,
+    area: IntegerBounds,
+    level: f32,
+) -> Result<ByteVec> {
+    if bytes_ne.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let bytes_le = super::convert_current_to_little_endian(bytes_ne, channels, area)?;
+    let rules = relevant_rules(channels);
+    let (mut channel_data, rgb_sets) = classify(channels, &rules, area)?;
+
+    // split the lines into one contiguous buffer per channel
+    {
+        let mut remaining = bytes_le.as_slice();
+        for y in area.position.y()..area.end().y() {
+            for channel in &mut channel_data {
+                if mod_p(y, usize_to_i32(channel.y_sampling, "sampling")?) != 0 {
+                    continue;
+                }
+
+                let line_size = channel.line_byte_size();
+                if remaining.len() < line_size {
+                    return Err(Error::invalid("not enough data"));
+                }
+
+                let (line, rest) = remaining.split_at(line_size);
+                channel.planar.extend_from_slice(line);
+                remaining = rest;
+            }
+        }
+    }
+
+    let quantization_error = level.max(0.0) / 100_000.0;
+    let mut ac = Vec::new();
+    let mut dc = Vec::new();
+    let mut processed = vec![false; channel_data.len()];
+
+    for &rgb in &rgb_sets {
+        let planes = [
+            &channel_data[rgb[0]],
+            &channel_data[rgb[1]],
+            &channel_data[rgb[2]],
+        ];
+
+        encode_lossy(&planes, quantization_error, &mut ac, &mut dc);
+        for &index in &rgb {
+            processed[index] = true;
+        }
+    }
+
+    for (index, channel) in channel_data.iter().enumerate() {
+        if channel.scheme == Scheme::LossyDct && !processed[index] {
+            encode_lossy(&[channel], quantization_error, &mut ac, &mut dc);
+        }
+    }
+
+    let mut unknown = Vec::new();
+    let mut rle_raw = Vec::new();
+
+    for channel in &channel_data {
+        match channel.scheme {
+            Scheme::LossyDct => {}
+            Scheme::Unknown => unknown.extend_from_slice(&channel.planar),
+
+            // split the bytes of each sample into planes
+            Scheme::Rle => {
+                let sample_size = channel.sample_type.bytes_per_sample();
+                for byte_index in 0..sample_size {
+                    rle_raw.extend(
+                        channel
+                            .planar
+                            .chunks_exact(sample_size)
+                            .map(|sample| sample[byte_index]),
+                    );
+                }
+            }
+        }
+    }
+
+    let mut sizes = [0_u64; SIZE_COUNT];
+    sizes[VERSION_INDEX] = VERSION;
+    sizes[AC_COMPRESSION] = AC_STATIC_HUFFMAN;
+
+    let unknown_compressed = if unknown.is_empty() {
+        Vec::new()
+    } else {
+        zip::compress_zip_raw(&unknown)
+    };
+
+    let ac_compressed = huffman::compress(&ac)?;
+
+    let dc_compressed = if dc.is_empty() {
+        Vec::new()
+    } else {
+        let mut dc_bytes: Vec<u8> = dc.iter().flat_map(|value| value.to_le_bytes()).collect();
+        separate_bytes_fragments(&mut dc_bytes);
+        samples_to_differences(&mut dc_bytes);
+        zip::compress_zip_raw(&dc_bytes)
+    };
+
+    let (rle_uncompressed_size, rle_compressed) = if rle_raw.is_empty() {
+        (0, Vec::new())
+    } else {
+        let rle = rle::compress_rle_raw(&rle_raw);
+        (rle.len(), zip::compress_zip_raw(&rle))
+    };
+
+    sizes[UNKNOWN_UNCOMPRESSED_SIZE] = unknown.len() as u64;
+    sizes[UNKNOWN_COMPRESSED_SIZE] = unknown_compressed.len() as u64;
+    sizes[AC_COMPRESSED_SIZE] = ac_compressed.len() as u64;
+    sizes[DC_COMPRESSED_SIZE] = dc_compressed.len() as u64;
+    sizes[RLE_COMPRESSED_SIZE] = rle_compressed.len() as u64;
+    sizes[RLE_UNCOMPRESSED_SIZE] = rle_uncompressed_size as u64;
+    sizes[RLE_RAW_SIZE] = rle_raw.len() as u64;
+    sizes[AC_UNCOMPRESSED_COUNT] = ac.len() as u64;
+    sizes[DC_UNCOMPRESSED_COUNT] = dc.len() as u64;
+
+    let rules_size = 2 + rules.iter().map(OwnedClassifier::byte_size).sum::<usize>();
+    let mut out = Vec::with_capacity(
+        SIZE_COUNT * 8
+            + rules_size
+            + unknown_compressed.len()
+            + ac_compressed.len()
+            + dc_compressed.len()
+            + rle_compressed.len(),
+    );
+
+    for size in &sizes {
+        out.extend_from_slice(&size.to_le_bytes());
+    }
+
+    out.extend_from_slice(
+        &u16::try_from(rules_size)
+            .map_err(|_| Error::invalid("too many dwa channel rules"))?
+            .to_le_bytes(),
+    );
+
+    for rule in &rules {
+        rule.write(&mut out);
+    }
+
+    out.extend_from_slice(&unknown_compressed);
+    out.extend_from_slice(&ac_compressed);
+    out.extend_from_slice(&dc_compressed);
+    out.extend_from_slice(&rle_compressed);
+
+    Ok(out)
+}
+
+pub fn decompress(
+    channels: &ChannelList,
+    bytes_le: ByteVec,
+    area: IntegerBounds,
+    expected_byte_size: usize,
+    pedantic: bool,
+) -> Result<ByteVec> {
+    if bytes_le.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut remaining = bytes_le.as_slice();
+    let mut sizes = [0_usize; SIZE_COUNT];
+
+    for size in &mut sizes {
+        *size = usize::try_from(read_u64(&mut remaining)?)
+            .map_err(|_| Error::invalid("dwa data size"))?;
+    }
+
+    let version = sizes[VERSION_INDEX];
+    if version > VERSION as usize {
+        return Err(Error::unsupported(format!("dwa version {}", version)));
+    }
+
+    let rules: Vec<OwnedClassifier> = if version == 2 {
+        let rules_size = usize::from(read_u16(&mut remaining)?);
+        if rules_size < 2 || rules_size - 2 > remaining.len() {
+            return Err(Error::invalid("dwa channel rules size"));
+        }
+
+        let (mut rule_bytes, rest) = remaining.split_at(rules_size - 2);
+        remaining = rest;
+
+        let mut rules = Vec::new();
+        while !rule_bytes.is_empty() {
+            rules.push(OwnedClassifier::read(&mut rule_bytes)?);
+        }
+
+        rules
+    } else {
+        LEGACY_RULES.iter().map(Classifier::to_owned).collect()
+    };
+
+    let (mut channel_data, rgb_sets) = classify(channels, &rules, area)?;
+
+    let mut take = |size: usize| -> Result<&[u8]> {
+        if size > remaining.len() {
+            return Err(Error::invalid("not enough data"));
+        }
+
+        let (bytes, rest) = remaining.split_at(size);
+        remaining = rest;
+        Ok(bytes)
+    };
+
+    let unknown_compressed = take(sizes[UNKNOWN_COMPRESSED_SIZE])?;
+    let ac_compressed = take(sizes[AC_COMPRESSED_SIZE])?;
+    let dc_compressed = take(sizes[DC_COMPRESSED_SIZE])?;
+    let rle_compressed = take(sizes[RLE_COMPRESSED_SIZE])?;
+
+    if pedantic && !remaining.is_empty() {
+        return Err(Error::invalid("too much data"));
+    }
+
+    // the total number of bytes of all channels in this block limits all allocations
+    let max_byte_size = expected_byte_size;
+
+    let unknown = if sizes[UNKNOWN_UNCOMPRESSED_SIZE] > 0 {
+        if sizes[UNKNOWN_UNCOMPRESSED_SIZE] > max_byte_size {
+            return Err(Error::invalid("dwa unknown data size"));
+        }
+
+        let unknown =
+            zip::decompress_zip_raw(unknown_compressed, sizes[UNKNOWN_UNCOMPRESSED_SIZE])?;
+        if unknown.len() != sizes[UNKNOWN_UNCOMPRESSED_SIZE] {
+            return Err(Error::invalid("dwa unknown data size"));
+        }
+
+        unknown
+    } else {
+        Vec::new()
+    };
+
+    let ac_count = sizes[AC_UNCOMPRESSED_COUNT];
+    if ac_count > max_byte_size * 32 {
+        return Err(Error::invalid("dwa ac coefficient count"));
+    }
+
+    let ac: Vec<u16> = if ac_compressed.is_empty() {
+        Vec::new()
+    } else {
+        match sizes[AC_COMPRESSION] as u64 {
+            AC_STATIC_HUFFMAN => huffman::decompress(ac_compressed, ac_count)?,
+            AC_DEFLATE => {
+                let bytes = zip::decompress_zip_raw(ac_compressed, ac_count * 2)?;
+                bytes
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                    .collect()
+            }
+            _ => return Err(Error::invalid("dwa ac compression method")),
+        }
+    };
+
+    if ac.len() != ac_count {
+        return Err(Error::invalid("dwa ac coefficient count"));
+    }
+
+    let dc_count = sizes[DC_UNCOMPRESSED_COUNT];
+    if dc_count > max_byte_size {
+        return Err(Error::invalid("dwa dc coefficient count"));
+    }
+
+    let dc: Vec<u16> = if dc_count > 0 {
+        let mut bytes = zip::decompress_zip_raw(dc_compressed, dc_count * 2)?;
+        if bytes.len() != dc_count * 2 {
+            return Err(Error::invalid("dwa dc coefficient count"));
+        }
+
+        differences_to_samples(&mut bytes);
+        interleave_byte_blocks(&mut bytes);
+
+        bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let rle_raw = if sizes[RLE_RAW_SIZE] > 0 {
+        if sizes[RLE_RAW_SIZE] > max_byte_size {
+            return Err(Error::invalid("dwa rle data size"));
+        }
+
+        let rle = zip::decompress_zip_raw(rle_compressed, sizes[RLE_UNCOMPRESSED_SIZE])?;
+        let raw = rle::decompress_rle_raw(&rle, sizes[RLE_RAW_SIZE], pedantic)?;
+        if raw.len() != sizes[RLE_RAW_SIZE] {
+            return Err(Error::invalid("dwa rle data size"));
+        }
+
+        raw
+    } else {
+        Vec::new()
+    };
+
+    // distribute the uncompressed buffers to the channels
+    {
+        let mut unknown = unknown.as_slice();
+        let mut rle_raw = rle_raw.as_slice();
+
+        for channel in &mut channel_data {
+            let sample_count = channel.resolution.area();
+            let sample_size = channel.sample_type.bytes_per_sample();
+            let byte_size = sample_count * sample_size;
+
+            match channel.scheme {
+                Scheme::LossyDct => {}
+
+                Scheme::Unknown => {
+                    if unknown.len() < byte_size {
+                        return Err(Error::invalid("dwa unknown data size"));
+                    }
+
+                    let (bytes, rest) = unknown.split_at(byte_size);
+                    channel.planar = bytes.to_vec();
+                    unknown = rest;
+                }
+
+                Scheme::Rle => {
+                    if rle_raw.len() < byte_size {
+                        return Err(Error::invalid("dwa rle data size"));
+                    }
+
+                    let (planes, rest) = rle_raw.split_at(byte_size);
+                    rle_raw = rest;
+
+                    let mut planar = vec![0_u8; byte_size];
+                    for (byte_index, plane) in planes.chunks_exact(sample_count).enumerate() {
+                        for (sample, &byte) in planar.chunks_exact_mut(sample_size).zip(plane) {
+                            sample[byte_index] = byte;
+                        }
+                    }
+
+                    channel.planar = planar;
+                }
+            }
+        }
+    }
+
+    let mut ac = AcReader { values: &ac };
+    let mut dc = dc.as_slice();
+    let mut processed = vec![false; channel_data.len()];
+
+    for &rgb in &rgb_sets {
+        let mut decoded = decode_lossy(
+            &[
+                (&channel_data[rgb[0]]).into(),
+                (&channel_data[rgb[1]]).into(),
+                (&channel_data[rgb[2]]).into(),
+            ],
+            &mut ac,
+            &mut dc,
+        )?;
+
+        for (&index, planar) in rgb.iter().zip(decoded.drain(..)) {
+            channel_data[index].planar = planar;
+            processed[index] = true;
+        }
+    }
+
+    for index in 0..channel_data.len() {
+        if channel_data[index].scheme == Scheme::LossyDct && !processed[index] {
+            let mut decoded = decode_lossy(&[(&channel_data[index]).into()], &mut ac, &mut dc)?;
+            channel_data[index].planar = decoded.remove(0);
+        }
+    }
+
+    if pedantic && (!ac.values.is_empty() || !dc.is_empty()) {
+        return Err(Error::invalid("too much data"));
+    }
+
+    // interleave the channels line by line
+    let mut out = Vec::with_capacity(expected_byte_size);
+    let mut line_offsets = vec![0_usize; channel_data.len()];
+
+    for y in area.position.y()..area.end().y() {
+        for (channel, offset) in channel_data.iter().zip(line_offsets.iter_mut()) {
+            if mod_p(y, usize_to_i32(channel.y_sampling, "sampling")?) != 0 {
+                continue;
+            }
+
+            let line_size = channel.line_byte_size();
+            let line = channel
+                .planar
+                .get(*offset..*offset + line_size)
+                .ok_or_else(|| Error::invalid("not enough data"))?;
+
+            out.extend_from_slice(line);
+            *offset += line_size;
+        }
+    }
+
+    super::convert_little_endian_to_current(out, channels, area)
+}
+
+fn read_u64(bytes: &mut &[u8]) -> Result<u64> {
+    if bytes.len() < 8 {
+        return Err(Error::invalid("not enough data"));
+    }
+
+    let (value, rest) = bytes.split_at(8);
+    *bytes = rest;
+
+    let mut array = [0_u8; 8];
+    array.copy_from_slice(value);
+    Ok(u64::from_le_bytes(array))
+}
+
+fn read_u16(bytes: &mut &[u8]) -> Result<u16> {
+    if bytes.len() < 2 {
+        return Err(Error::invalid("not enough data"));
+    }
+
+    let value = u16::from_le_bytes([bytes[0], bytes[1]]);
+    *bytes = &bytes[2..];
+    Ok(value)
+}
+
+/// The properties of a lossy channel required to decode it.
+#[derive(Debug, Clone, Copy)]
+struct LossyChannel {
+    sample_type: SampleType,
+    resolution: Vec2<usize>,
+}
+
+impl From<&ChannelData> for LossyChannel {
+    fn from(channel: &ChannelData) -> Self {
+        LossyChannel {
+            sample_type: channel.sample_type,
+            resolution: channel.resolution,
+        }
+    }
+}
+
+/// Consumes the run-length encoded ac coefficients.
+#[derive(Debug)]
+struct AcReader<'s> {
+    values: &'s [u16],
+}
+
+impl AcReader<'_> {
+    /// Fill the ac coefficients of a zig-zag ordered block.
+    /// Returns the index of the last coefficient that was stored explicitly, or zero.
+    fn read_block(&mut self, block: &mut [u16; 64]) -> Result<usize> {
+        let mut last_non_zero = 0;
+        let mut index = 1;
+
+        for value in &mut block[1..] {
+            *value = 0;
+        }
+
+        while index < 64 {
+            let (&value, rest) = self
+                .values
+                .split_first()
+                .ok_or_else(|| Error::invalid("not enough dwa ac coefficients"))?;
+
+            self.values = rest;
+
+            if value == AC_RUN_MARKER {
+                index = 64;
+            } else if value >> 8 == 0xff {
+                index += usize::from(value & 0xff);
+            } else {
+                last_non_zero = index;
+                block[index] = value;
+                index += 1;
+            }
+        }
+
+        Ok(last_non_zero)
+    }
+}
+
+/// Dequantize and inverse transform one or three channels.
+/// Returns the little-endian planar samples of each channel.
+fn decode_lossy(
+    channels: &[LossyChannel],
+    ac: &mut AcReader<'_>,
+    dc: &mut &[u16],
+) -> Result<Vec<Vec<u8>>> {
+    let resolution = channels[0].resolution;
+    let (width, height) = (resolution.width(), resolution.height());
+
+    let blocks_x = (width + 7) / 8;
+    let blocks_y = (height + 7) / 8;
+    let block_count = blocks_x * blocks_y;
+
+    if dc.len() < block_count * channels.len() {
+        return Err(Error::invalid("not enough dwa dc coefficients"));
+    }
+
+    let (dc_values, rest) = dc.split_at(block_count * channels.len());
+    *dc = rest;
+
+    // the non-linear half values of all channels, before conversion to linear values
+    let mut nonlinear = vec![vec![0_u16; width * height]; channels.len()];
+    let mut zig_zag = [0_u16; 64];
+    let mut blocks = vec![[0_f32; 64]; channels.len()];
+
+    for block_y in 0..blocks_y {
+        for block_x in 0..blocks_x {
+            let block_index = block_y * blocks_x + block_x;
+
+            // if all channels have no ac coefficients, only a single value needs to be computed
+            let mut block_is_constant = true;
+
+            for (channel_index, block) in blocks.iter_mut().enumerate() {
+                zig_zag[0] = dc_values[channel_index * block_count + block_index];
+
+                let last_non_zero = ac.read_block(&mut zig_zag)?;
+
+                if last_non_zero == 0 {
+                    block[0] = f16::from_bits(zig_zag[0]).to_f32();
+                    inverse_dct_dc_only(block);
+                } else {
+                    block_is_constant = false;
+
+                    for (value, &zig_zag_index) in block.iter_mut().zip(ZIG_ZAG.iter()) {
+                        *value = f16::from_bits(zig_zag[zig_zag_index]).to_f32();
+                    }
+
+                    inverse_dct(block, untransformed_rows(last_non_zero));
+                }
+            }
+
+            if let [y, cb, cr] = blocks.as_mut_slice() {
+                if block_is_constant {
+                    ycbcr_to_rgb(&mut y[0], &mut cb[0], &mut cr[0]);
+                } else {
+                    for ((y, cb), cr) in y.iter_mut().zip(cb.iter_mut()).zip(cr.iter_mut()) {
+                        ycbcr_to_rgb(y, cb, cr);
+                    }
+                }
+            }
+
+            let max_x = (width - block_x * 8).min(8);
+            let max_y = (height - block_y * 8).min(8);
+
+            for (block, samples) in blocks.iter().zip(nonlinear.iter_mut()) {
+                for y in 0..max_y {
+                    let line_start = (block_y * 8 + y) * width + block_x * 8;
+                    let line = &mut samples[line_start..line_start + max_x];
+
+                    if block_is_constant {
+                        let value = f16::from_f32(block[0]).to_bits();
+                        for sample in line {
+                            *sample = value;
+                        }
+                    } else {
+                        for (sample, &value) in line.iter_mut().zip(&block[y * 8..]) {
+                            *sample = f16::from_f32(value).to_bits();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(channels
+        .iter()
+        .zip(nonlinear)
+        .map(|(channel, samples)| {
+            let linear = samples.into_iter().map(|bits| TO_LINEAR[usize::from(bits)]);
+
+            match channel.sample_type {
+                SampleType::F32 => linear
+                    .flat_map(|bits| f16::from_bits(bits).to_f32().to_le_bytes())
+                    .collect(),
+
+                // u32 channels are never lossy compressed by the default rules
+                _ => linear.flat_map(u16::to_le_bytes).collect(),
+            }
+        })
+        .collect())
+}
+
+/// Transform and quantize one or three channels,
+/// appending the coefficients to the ac and dc buffers.
+fn encode_lossy(
+    channels: &[&ChannelData],
+    quantization_error: f32,
+    ac: &mut Vec<u16>,
+    dc: &mut Vec<u16>,
+) {
+    let resolution = channels[0].resolution;
+    let (width, height) = (resolution.width(), resolution.height());
+
+    let blocks_x = (width + 7) / 8;
+    let blocks_y = (height + 7) / 8;
+    let block_count = blocks_x * blocks_y;
+
+    // the non-linear representation of all samples, as f32 values
+    let nonlinear: Vec<Vec<f32>> = channels
+        .iter()
+        .map(|channel| {
+            let half_bits: Vec<u16> = match channel.sample_type {
+                SampleType::F32 => channel
+                    .planar
+                    .chunks_exact(4)
+                    .map(|bytes| {
+                        let value = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+
+                        // clamp instead of producing infinity, which would be zeroed later
+                        let max = f16::MAX.to_f32();
+                        f16::from_f32(value.max(-max).min(max)).to_bits()
+                    })
+                    .collect(),
+
+                _ => channel
+                    .planar
+                    .chunks_exact(2)
+                    .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+                    .collect(),
+            };
+
+            half_bits
+                .into_iter()
+                .map(|bits| f16::from_bits(TO_NONLINEAR[usize::from(bits)]).to_f32())
+                .collect()
+        })
+        .collect();
+
+    let dc_start = dc.len();
+    dc.resize(dc_start + block_count * channels.len(), 0);
+
+    let mut blocks = vec![[0_f32; 64]; channels.len()];
+    let mut quantized = [f16::ZERO; 64];
+    let mut zig_zag = [0_u16; 64];
+
+    for block_y in 0..blocks_y {
+        for block_x in 0..blocks_x {
+            for (block, samples) in blocks.iter_mut().zip(&nonlinear) {
+                // mirror the image at the edges to fill partial blocks
+                for y in 0..8 {
+                    let source_y = mirror(block_y * 8 + y, height);
+
+                    for x in 0..8 {
+                        let source_x = mirror(block_x * 8 + x, width);
+                        block[y * 8 + x] = samples[source_y * width + source_x];
+                    }
+                }
+            }
+
+            if let [r, g, b] = blocks.as_mut_slice() {
+                for ((r, g), b) in r.iter_mut().zip(g.iter_mut()).zip(b.iter_mut()) {
+                    rgb_to_ycbcr(r, g, b);
+                }
+            }
+
+            for (channel_index, block) in blocks.iter_mut().enumerate() {
+                forward_dct(block);
+
+                let (table, table_min) = if channel_index == 0 {
+                    (&QUANT_TABLE_Y, QUANT_TABLE_Y_MIN)
+                } else {
+                    (&QUANT_TABLE_CBCR, QUANT_TABLE_CBCR_MIN)
+                };
+
+                for ((coefficient, &value), &quantization) in
+                    quantized.iter_mut().zip(block.iter()).zip(table.iter())
+                {
+                    let tolerance =
+                        quantization_error * f32::from(quantization) / f32::from(table_min);
+                    *coefficient = quantize(f16::from_f32(value), tolerance);
+                }
+
+                for (coefficient, &zig_zag_index) in quantized.iter().zip(ZIG_ZAG.iter()) {
+                    zig_zag[zig_zag_index] = coefficient.to_bits();
+                }
+
+                dc[dc_start + channel_index * block_count + block_y * blocks_x + block_x] =
+                    zig_zag[0];
+
+                write_ac_block(&zig_zag, ac);
+            }
+        }
+    }
+}
+
+/// Reflect coordinates beyond the edge back into the image, repeating the last sample.
+fn mirror(position: usize, size: usize) -> usize {
+    if position < size {
+        position
+    } else {
+        (2 * size).saturating_sub(position + 1)
+    }
+}
+
+/// Run-length encode the zeroes in the ac coefficients of a zig-zag ordered block.
+fn write_ac_block(block: &[u16; 64], ac: &mut Vec<u16>) {
+    let mut index = 1;
+
+    while index < 64 {
+        if block[index] != 0 {
+            ac.push(block[index]);
+            index += 1;
+            continue;
+        }
+
+        let run_length = block[index..]
+            .iter()
+            .take_while(|&&value| value == 0)
+            .count();
+
+        if run_length == 1 {
+            ac.push(0);
+        } else if index + run_length == 64 {
+            ac.push(AC_RUN_MARKER);
+        } else {
+            ac.push(AC_RUN_MARKER | run_length as u16);
+        }
+
+        index += run_length;
+    }
+}
+
+/// Find the value with the fewest set bits that is within the tolerance of the original value,
+/// as values with few bits compress better.
+fn quantize(value: f16, tolerance: f32) -> f16 {
+    let bits = value.to_bits();
+    if !value.is_finite() || bits & 0x7fff == 0 {
+        return value;
+    }
+
+    let original = value.to_f32();
+    let within_tolerance = |magnitude: u16| {
+        (f16::from_bits(magnitude | (bits & 0x8000)).to_f32() - original).abs() < tolerance
+    };
+
+    if (0.0 - original).abs() < tolerance {
+        return f16::ZERO;
+    }
+
+    // find the range of magnitudes within the tolerance, which are sorted by value
+    let magnitude = bits & 0x7fff;
+
+    let mut low = magnitude;
+    let mut step = 0x4000_u16;
+    while step > 0 {
+        if step <= low && within_tolerance(low - step) {
+            low -= step;
+        }
+
+        step /= 2;
+    }
+
+    let mut high = magnitude;
+    let mut step = 0x4000_u16;
+    while step > 0 {
+        if high + step < 0x7c00 && within_tolerance(high + step) {
+            high += step;
+        }
+
+        step /= 2;
+    }
+
+    // the value with the fewest bits is the common prefix of the range,
+    // followed by the highest differing bit
+    let fewest_bits = if low == high {
+        low
+    } else {
+        let differing_bit = 1_u16 << (15 - (low ^ high).leading_zeros());
+        let prefix = high & !(differing_bit | (differing_bit - 1));
+
+        if low == prefix {
+            low
+        } else {
+            prefix | differing_bit
+        }
+    };
+
+    f16::from_bits(fewest_bits | (bits & 0x8000))
+}
+
+fn rgb_to_ycbcr(r: &mut f32, g: &mut f32, b: &mut f32) {
+    let (red, green, blue) = (*r, *g, *b);
+    *r = 0.2126 * red + 0.7152 * green + 0.0722 * blue;
+    *g = -0.1146 * red - 0.3854 * green + 0.5000 * blue;
+    *b = 0.5000 * red - 0.4542 * green - 0.0458 * blue;
+}
+
+fn ycbcr_to_rgb(y: &mut f32, cb: &mut f32, cr: &mut f32) {
+    let (luma, blue, red) = (*y, *cb, *cr);
+    *y = luma + 1.5747 * red;
+    *cb = luma - 0.1873 * blue - 0.4682 * red;
+    *cr = luma + 1.8556 * blue;
+}
+
+/// The inverse transform of a block that only has a dc coefficient.
+fn inverse_dct_dc_only(block: &mut [f32; 64]) {
+    let value = block[0] * 3.535536e-01 * 3.535536e-01;
+    for sample in block.iter_mut() {
+        *sample = value;
+    }
+}
+
+/// The cosine constants of the 1D inverse transform.
+/// These intentionally use the same approximation of pi as the reference implementation.
+#[allow(clippy::approx_constant)]
+fn inverse_dct_constants() -> [f32; 7] {
+    let pi = 3.14159_f32;
+    [
+        0.5 * (pi / 4.0).cos(),
+        0.5 * (pi / 16.0).cos(),
+        0.5 * (pi / 8.0).cos(),
+        0.5 * (3.0 * pi / 16.0).cos(),
+        0.5 * (5.0 * pi / 16.0).cos(),
+        0.5 * (3.0 * pi / 8.0).cos(),
+        0.5 * (7.0 * pi / 16.0).cos(),
+    ]
+}
+
+/// 1D inverse transform of eight values with the given stride.
+/// The order of operations matches the reference implementation bit by bit.
+#[inline]
+fn inverse_dct_1d(data: &mut [f32; 64], start: usize, stride: usize, constants: &[f32; 7]) {
+    let [a, b, c, d, e, f, g] = *constants;
+    let x = |index: usize| data[start + index * stride];
+
+    let alpha = [c * x(2), f * x(2), c * x(6), f * x(6)];
+
+    let beta = [
+        b * x(1) + d * x(3) + e * x(5) + g * x(7),
+        d * x(1) - g * x(3) - b * x(5) - e * x(7),
+        e * x(1) - b * x(3) + g * x(5) + d * x(7),
+        g * x(1) - e * x(3) + d * x(5) - b * x(7),
+    ];
+
+    let theta = [
+        a * (x(0) + x(4)),
+        alpha[0] + alpha[3],
+        alpha[1] - alpha[2],
+        a * (x(0) - x(4)),
+    ];
+
+    let gamma = [
+        theta[0] + theta[1],
+        theta[3] + theta[2],
+        theta[3] - theta[2],
+        theta[0] - theta[1],
+    ];
+
+    let result = [
+        gamma[0] + beta[0],
+        gamma[1] + beta[1],
+        gamma[2] + beta[2],
+        gamma[3] + beta[3],
+        gamma[3] - beta[3],
+        gamma[2] - beta[2],
+        gamma[1] - beta[1],
+        gamma[0] - beta[0],
+    ];
+
+    for (index, value) in result.iter().enumerate() {
+        data[start + index * stride] = *value;
+    }
+}
+
+/// The number of trailing rows that are skipped by the row pass of the inverse transform,
+/// based on the zig-zag index of the last non-zero coefficient.
+///
+/// The reference decoder skips rows in pairs, so a row that contains
+/// only the first coefficient of an otherwise empty pair is left untransformed.
+/// This is not an exact inverse, but it is required to decode identical pixels.
+fn untransformed_rows(last_non_zero: usize) -> usize {
+    let zero_rows = match last_non_zero {
+        0..=1 => 7,
+        2 => 6,
+        3..=8 => 5,
+        9 => 4,
+        10..=19 => 3,
+        20 => 2,
+        21..=34 => 1,
+        _ => 0,
+    };
+
+    if zero_rows % 2 == 1 && zero_rows < 7 {
+        zero_rows + 1
+    } else {
+        zero_rows
+    }
+}
+
+/// Inverse 2D transform, skipping the row pass for the specified number of trailing rows.
+fn inverse_dct(block: &mut [f32; 64], untransformed_rows: usize) {
+    let constants = inverse_dct_constants();
+
+    for row in 0..8 - untransformed_rows {
+        inverse_dct_1d(block, row * 8, 1, &constants);
+    }
+
+    for column in 0..8 {
+        inverse_dct_1d(block, column, 8, &constants);
+    }
+}
+
+/// Orthonormal 2D DCT, the inverse of `inverse_dct`.
+fn forward_dct(block: &mut [f32; 64]) {
+    let mut basis = [[0_f32; 8]; 8];
+    for (frequency, row) in basis.iter_mut().enumerate() {
+        let scale = if frequency == 0 {
+            std::f32::consts::FRAC_1_SQRT_2 * 0.5
+        } else {
+            0.5
+        };
+
+        for (position, value) in row.iter_mut().enumerate() {
+            let angle = (2 * position + 1) as f32 * frequency as f32 * std::f32::consts::PI / 16.0;
+            *value = scale * angle.cos();
+        }
+    }
+
+    let transform = |data: &mut [f32; 64], start: usize, stride: usize| {
+        let mut input = [0_f32; 8];
+        for (index, value) in input.iter_mut().enumerate() {
+            *value = data[start + index * stride];
+        }
+
+        for (frequency, row) in basis.iter().enumerate() {
+            data[start + frequency * stride] = row
+                .iter()
+                .zip(input.iter())
+                .map(|(basis, value)| basis * value)
+                .sum();
+        }
+    };
+
+    for row in 0..8 {
+        transform(block, row * 8, 1);
+    }
+
+    for column in 0..8 {
+        transform(block, column, 8);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::meta::attribute::ChannelDescription;
+
+    fn roundtrip(channels: ChannelList, size: Vec2<usize>, samples: &[u8]) -> Vec<u8> {
+        let area = IntegerBounds::from_dimensions(size);
+        let compressed = compress(&channels, samples.to_vec(), area, DEFAULT_LEVEL).unwrap();
+        decompress(&channels, compressed, area, samples.len(), true).unwrap()
+    }
+
+    #[test]
+    fn roundtrip_rgb_is_close() {
+        let channels = ChannelList::new(smallvec![
+            ChannelDescription::new("B", SampleType::F16, false),
+            ChannelDescription::new("G", SampleType::F16, false),
+            ChannelDescription::new("R", SampleType::F16, false),
+        ]);
+
+        let size = Vec2(19, 11);
+        let mut samples = Vec::new();
+        let mut expected = Vec::new();
+
+        for y in 0..size.height() {
+            for channel in 0..3 {
+                for x in 0..size.width() {
+                    let value = (x as f32 * 0.05 + y as f32 * 0.03 + channel as f32 * 0.2).sin()
+                        * 0.5
+                        + 0.5;
+                    samples.extend_from_slice(&f16::from_f32(value).to_ne_bytes());
+                    expected.push(value);
+                }
+            }
+        }
+
+        let decompressed = roundtrip(channels, size, &samples);
+        assert_eq!(decompressed.len(), samples.len());
+
+        for (bytes, expected) in decompressed.chunks_exact(2).zip(expected) {
+            let value = f16::from_ne_bytes([bytes[0], bytes[1]]).to_f32();
+            assert!((value - expected).abs() < 0.02, "{} != {}", value, expected);
+        }
+    }
+
+    #[test]
+    fn roundtrip_lossless_channels() {
+        let channels = ChannelList::new(smallvec![
+            ChannelDescription::new("A", SampleType::F16, false),
+            ChannelDescription::new("id", SampleType::U32, false),
+            ChannelDescription::new("Z", SampleType::F32, false),
+        ]);
+
+        let size = Vec2(7, 5);
+        let mut samples = Vec::new();
+
+        for y in 0..size.height() {
+            for x in 0..size.width() {
+                samples.extend_from_slice(&f16::from_f32((x / 3) as f32).to_ne_bytes());
+            }
+            for x in 0..size.width() {
+                samples.extend_from_slice(&((x * y) as u32).to_ne_bytes());
+            }
+            for x in 0..size.width() {
+                samples.extend_from_slice(&(x as f32 * 1.37 - y as f32).to_ne_bytes());
+            }
+        }
+
+        assert_eq!(roundtrip(channels, size, &samples), samples);
+    }
+
+    #[test]
+    fn quantize_stays_within_tolerance() {
+        for bits in (0..0x7c00_u16)
+            .step_by(7)
+            .chain((0x8000..0xfc00).step_by(11))
+        {
+            let value = f16::from_bits(bits);
+            let tolerance = 0.001 + value.to_f32().abs() * 0.01;
+            let quantized = quantize(value, tolerance);
+
+            assert!((quantized.to_f32() - value.to_f32()).abs() < tolerance.max(f32::EPSILON));
+            assert!(quantized.to_bits().count_ones() <= bits.count_ones());
+        }
+    }
+
+    #[test]
+    fn ac_run_length_roundtrip() {
+        let mut block = [0_u16; 64];
+        block[1] = 0x3c00;
+        block[5] = 0x1234;
+        block[6] = 0x8000;
+        block[40] = 0x0001;
+
+        let mut ac = Vec::new();
+        write_ac_block(&block, &mut ac);
+
+        let mut decoded = [0_u16; 64];
+        let last = AcReader { values: &ac }.read_block(&mut decoded).unwrap();
+
+        assert_eq!(last, 40);
+        assert_eq!(&decoded[1..], &block[1..]);
+    }
+
+    #[test]
+    fn classifier_roundtrip() {
+        for rule in DEFAULT_RULES.iter().chain(LEGACY_RULES.iter()) {
+            let rule = rule.to_owned();
+            let mut bytes = Vec::new();
+            rule.write(&mut bytes);
+            assert_eq!(bytes.len(), rule.byte_size());
+
+            let mut remaining = bytes.as_slice();
+            assert_eq!(OwnedClassifier::read(&mut remaining).unwrap(), rule);
+            assert!(remaining.is_empty());
+        }
+    }
+}
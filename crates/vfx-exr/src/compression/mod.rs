@@ -3,6 +3,7 @@
 
 // private modules make non-breaking changes easier
 mod b44;
+mod dwa;
 mod piz;
 mod pxr24;
 mod rle;
@@ -117,24 +118,31 @@ pub enum Compression {
     /// Only supported for flat images, not for deep data.
     B44A, // TODO collapse with B44
 
-    /// __This lossy compression is not yet supported by this implementation.__
-    // lossy DCT based compression, in blocks
-    // of 32 scanlines. More efficient for partial buffer access.
-    DWAA(Option<f32>), // TODO does this have a default value? make this non optional? default Compression Level setting is 45.0
+    /// This is a lossy compression method for f16 and f32 images,
+    /// based on the discrete cosine transform, similar to JPEG.
+    /// The pixels are compressed in blocks of 32 scanlines,
+    /// which is more efficient for partial buffer access.
+    ///
+    /// Only channels named `R`, `G`, `B`, `Y`, `RY` and `BY` are compressed lossily.
+    /// Alpha channels are run-length encoded, and all other channels are stored losslessly.
+    ///
+    /// The optional value is the compression level, where higher values
+    /// result in smaller files with lower quality. Defaults to 45.
+    /// Only supported for flat images, not for deep data.
+    DWAA(Option<f32>), // TODO make this non optional? default Compression Level setting is 45.0
 
-    /// __This lossy compression is not yet supported by this implementation.__
-    // lossy DCT based compression, in blocks
-    // of 256 scanlines. More efficient space
-    // wise and faster to decode full frames
-    // than DWAA_COMPRESSION.
+    /// Like `DWAA`, but compresses blocks of 256 scanlines.
+    /// More efficient space wise and faster to decode full frames than `DWAA`.
     DWAB(Option<f32>), // TODO collapse with DWAA. default Compression Level setting is 45.0
 
-    /// __This lossy compression is not yet supported by this implementation.__
-    // High-Throughput JPEG 2000 (32 lines)
+    /// High-Throughput JPEG 2000, compressing blocks of 32 scanlines.
+    /// __Not yet supported by this implementation:__ reading and writing
+    /// compressed blocks returns `Error::NotSupported`.
     HTJ2K32,
 
-    /// __This lossy compression is not yet supported by this implementation.__
-    // High-Throughput JPEG 2000 (256 lines)
+    /// Like `HTJ2K32`, but compresses blocks of 256 scanlines.
+    /// __Not yet supported by this implementation:__ reading and writing
+    /// compressed blocks returns `Error::NotSupported`.
     HTJ2K256,
 }
 
@@ -227,12 +235,13 @@ impl Compression {
                 pixel_section,
                 true,
             ),
-            _ => {
-                return Err(Error::unsupported(format!(
-                    "yet unimplemented compression method: {}",
-                    self
-                )))
-            }
+            DWAA(level) | DWAB(level) => dwa::compress(
+                &header.channels,
+                uncompressed_native_endian.clone(),
+                pixel_section,
+                level.unwrap_or(dwa::DEFAULT_LEVEL),
+            ),
+            HTJ2K32 | HTJ2K256 => return Err(htj2k_unsupported(self)),
         };
 
         let compressed_little_endian = compressed_little_endian
@@ -270,7 +279,7 @@ impl Compression {
             assert!(self.supports_deep_data())
         }
 
-        let expected_byte_size = pixel_section.size.area() * header.channels.bytes_per_pixel; // FIXME this needs to account for subsampling anywhere
+        let expected_byte_size = header.channels.byte_size_of_section(pixel_section);
 
         // note: always true where self == Uncompressed
         if compressed_le.len() == expected_byte_size {
@@ -324,12 +333,14 @@ impl Compression {
                     expected_byte_size,
                     pedantic,
                 ),
-                _ => {
-                    return Err(Error::unsupported(format!(
-                        "yet unimplemented compression method: {}",
-                        self
-                    )))
-                }
+                DWAA(_) | DWAB(_) => dwa::decompress(
+                    &header.channels,
+                    compressed_le,
+                    pixel_section,
+                    expected_byte_size,
+                    pedantic,
+                ),
+                HTJ2K32 | HTJ2K256 => return Err(htj2k_unsupported(self)),
             };
 
             // map all errors to compression errors
@@ -377,6 +388,18 @@ impl Compression {
         }
     }
 
+    /// Whether compressed blocks still decode correctly if the channels are renamed
+    /// from `old` to `new`, which list the same channels in the same order.
+    /// DWA chooses the scheme of each channel by its name, so its blocks only stay valid
+    /// if every channel keeps its suffix and the channels keep their layer grouping.
+    pub(crate) fn supports_channel_renames(self, old: &ChannelList, new: &ChannelList) -> bool {
+        use self::Compression::*;
+        match self {
+            DWAA(_) | DWAB(_) => dwa::keeps_classification(old, new),
+            Uncompressed | RLE | ZIP1 | ZIP16 | PIZ | PXR24 | B44 | B44A | HTJ2K32 | HTJ2K256 => true,
+        }
+    }
+
     /// Most compression methods will reconstruct the exact pixel bytes,
     /// but some might throw away unimportant data for specific types of samples.
     pub fn is_lossless_for(self, sample_type: SampleType) -> bool {
@@ -464,7 +487,7 @@ fn reverse_block_endianness(
                 continue;
             }
 
-            let sample_count = rectangle.size.width() / channel.sampling.x();
+            let sample_count = channel.subsampled_section_size(rectangle).width();
 
             match channel.sample_type {
                 SampleType::F16 => {
@@ -1137,6 +1160,14 @@ pub mod deep {
     }
 }
 
+/// The error for HTJ2K blocks, which need the ISO/IEC 15444-15 block coder.
+fn htj2k_unsupported(compression: Compression) -> Error {
+    Error::unsupported(format!(
+        "{} compression (the HTJ2K block coder is not implemented)",
+        compression
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -2,7 +2,7 @@
 //! based on the PIZ image format, customized for OpenEXR.
 // inspired by  https://github.com/AcademySoftwareFoundation/openexr/blob/master/OpenEXR/IlmImf/ImfPizCompressor.cpp
 
-mod huffman;
+pub(super) mod huffman;
 mod wavelet;
 
 use crate::compression::{mod_p, ByteVec, Bytes};
@@ -29,14 +29,11 @@ pub fn decompress(
     channels: &ChannelList,
     compressed_le: ByteVec,
     rectangle: IntegerBounds,
-    expected_byte_size: usize, // TODO remove expected byte size as it can be computed with `rectangle.size.area() * channels.bytes_per_pixel`
+    expected_byte_size: usize, // TODO remove expected byte size as it can be computed with `channels.byte_size_of_section(rectangle)`
     pedantic: bool,
 ) -> Result<ByteVec> {
     let expected_u16_count = expected_byte_size / 2;
-    debug_assert_eq!(
-        expected_byte_size,
-        rectangle.size.area() * channels.bytes_per_pixel
-    );
+    debug_assert_eq!(expected_byte_size, channels.byte_size_of_section(rectangle));
     debug_assert!(!channels.list.is_empty());
 
     if compressed_le.is_empty() {
@@ -85,7 +82,7 @@ pub fn decompress(
                     tmp_start_index: tmp_read_index,
                     tmp_end_index: tmp_read_index,
                     y_sampling: channel.sampling.y(),
-                    resolution: channel.subsampled_resolution(rectangle.size),
+                    resolution: channel.subsampled_section_size(rectangle),
                     samples_per_pixel: channel.sample_type.bytes_per_sample()
                         / SampleType::F16.bytes_per_sample(),
                 };
@@ -183,7 +180,7 @@ pub fn compress(
             .list
             .iter()
             .map(|channel| {
-                let number_samples = channel.subsampled_resolution(rectangle.size);
+                let number_samples = channel.subsampled_section_size(rectangle);
                 let byte_size =
                     channel.sample_type.bytes_per_sample() / SampleType::F16.bytes_per_sample();
                 let byte_count = byte_size * number_samples.area();
@@ -42,17 +42,21 @@ pub fn compress(channels: &ChannelList, bytes_ne: ByteVec, area: IntegerBounds)
 
     let mut remaining_bytes_ne = bytes_ne.as_slice();
 
-    let bytes_per_pixel: usize = channels
+    let encoded_byte_size: usize = channels
         .list
         .iter()
-        .map(|channel| match channel.sample_type {
-            SampleType::F16 => 2,
-            SampleType::F32 => 3,
-            SampleType::U32 => 4,
+        .map(|channel| {
+            let bytes_per_sample = match channel.sample_type {
+                SampleType::F16 => 2,
+                SampleType::F32 => 3,
+                SampleType::U32 => 4,
+            };
+
+            bytes_per_sample * channel.subsampled_section_size(area).area()
         })
         .sum();
 
-    let mut encoded_be = vec![0_u8; bytes_per_pixel * area.size.area()];
+    let mut encoded_be = vec![0_u8; encoded_byte_size];
 
     {
         let mut write = encoded_be.as_mut_slice();
@@ -64,7 +68,7 @@ pub fn compress(channels: &ChannelList, bytes_ne: ByteVec, area: IntegerBounds)
                     continue;
                 }
 
-                let sample_count_x = channel.subsampled_resolution(area.size).0;
+                let sample_count_x = channel.subsampled_section_size(area).width();
 
                 // this apparently can't be a closure in Rust 1.43 due to borrowing ambiguity
                 macro_rules! split_off_write_slice {
@@ -181,7 +185,7 @@ pub fn decompress(
                 continue;
             }
 
-            let sample_count_x = channel.subsampled_resolution(area.size).0;
+            let sample_count_x = channel.subsampled_section_size(area).width();
             let mut read_sample_line = || {
                 if sample_count_x > encoded_be.len() {
                     return Err(Error::invalid("not enough data"));
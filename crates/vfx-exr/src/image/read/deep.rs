@@ -26,8 +26,8 @@
 //!  │
 //!  ├── MetaData (header with deep=true)
 //!  │
-//!  ├── DeepScanLine blocks (compressed)
-//!  │    ├── y_coordinate
+//!  ├── DeepScanLine / DeepTile blocks (compressed)
+//!  │    ├── y_coordinate / tile_coordinates
 //!  │    ├── packed_offset_table (cumulative sample counts)
 //!  │    └── sample_data (interleaved channels, little-endian)
 //!  │
@@ -40,10 +40,10 @@
 //!
 //! # Block Merging
 //!
-//! Deep scanline files contain multiple blocks (typically 1-32 lines each).
+//! Deep files contain multiple blocks (scan line blocks of typically 1-32 lines each, or tiles).
 //! [`merge_deep_blocks()`] combines them into a single [`DeepSamples`]:
 //!
-//! 1. Collect all blocks and sort by y-coordinate
+//! 1. Collect all blocks of the full resolution level and sort by position
 //! 2. Build combined cumulative offset table (adding prefix sums)
 //! 3. Allocate output channel arrays
 //! 4. Copy sample data from each block at correct offsets
@@ -91,17 +91,14 @@
 use std::io::{BufReader, Read, Seek};
 use std::path::Path;
 
-use crate::block::chunk::CompressedBlock;
-use crate::block::deep::{
-    decompress_deep_scanline_block, decompress_deep_tile_block,
-    SequentialDeepBlockDecompressor,
-};
+use crate::block::deep::{decompress_deep_chunk, SequentialDeepBlockDecompressor};
 #[cfg(feature = "rayon")]
 use crate::block::deep::ParallelDeepBlockDecompressor;
 use crate::block::reader::Reader;
 use crate::error::{Error, Result};
 use crate::image::deep::DeepSamples;
 use crate::image::{AnyChannel, AnyChannels, Blocks, Encoding, Image, Layer};
+use crate::math::Vec2;
 use crate::meta::header::Header;
 use crate::meta::BlockDescription;
 use smallvec::SmallVec;
@@ -346,7 +343,7 @@ impl ReadDeepImage<AllLayers> {
         let chunks_reader = reader.all_chunks(self.pedantic)?;
 
         // Group blocks by layer
-        let mut layer_blocks: Vec<Vec<(usize, DeepSamples)>> = vec![Vec::new(); meta.headers.len()];
+        let mut layer_blocks: Vec<Vec<(Vec2<usize>, DeepSamples)>> = vec![Vec::new(); meta.headers.len()];
 
         for chunk_result in chunks_reader {
             let chunk = chunk_result?;
@@ -356,48 +353,11 @@ impl ReadDeepImage<AllLayers> {
                 continue;
             }
 
-            let header = &meta.headers[layer_idx];
-            let width = header.layer_size.width();
-            let height = header.layer_size.height();
-
-            match chunk.compressed_block {
-                CompressedBlock::DeepScanLine(ref deep_block) => {
-                    let y = deep_block.y_coordinate as usize;
-                    let block_height = header
-                        .compression
-                        .scan_lines_per_block()
-                        .min(height.saturating_sub(y));
-
-                    let samples = decompress_deep_scanline_block(
-                        deep_block,
-                        header.compression,
-                        &header.channels,
-                        width,
-                        block_height,
-                        self.pedantic,
-                    )?;
-
-                    layer_blocks[layer_idx].push((y, samples));
-                }
-                CompressedBlock::DeepTile(ref deep_block) => {
-                    let tile_size = match header.blocks {
-                        BlockDescription::Tiles(desc) => desc.tile_size,
-                        _ => return Err(Error::invalid("deep tile in scanline image")),
-                    };
-
-                    let samples = decompress_deep_tile_block(
-                        deep_block,
-                        header.compression,
-                        &header.channels,
-                        tile_size.width(),
-                        tile_size.height(),
-                        self.pedantic,
-                    )?;
-
-                    let y = deep_block.coordinates.tile_index.y() * tile_size.height();
-                    layer_blocks[layer_idx].push((y, samples));
-                }
-                _ => {}
+            let block = decompress_deep_chunk(&chunk.compressed_block, &meta, layer_idx, self.pedantic)?;
+
+            // only the full resolution level is loaded
+            if block.index.level == Vec2(0, 0) {
+                layer_blocks[layer_idx].push((block.index.pixel_position, block.samples));
             }
         }
 
@@ -407,7 +367,7 @@ impl ReadDeepImage<AllLayers> {
         for layer_idx in deep_indices {
             let header = &meta.headers[layer_idx];
             let mut blocks = std::mem::take(&mut layer_blocks[layer_idx]);
-            blocks.sort_by_key(|(y, _)| *y);
+            blocks.sort_by_key(|(position, _)| (position.y(), position.x()));
 
             let merged = merge_deep_blocks(
                 blocks,
@@ -497,9 +457,9 @@ fn read_deep_layer_internal<R: Read + Seek>(
         decompress_blocks_sequential(chunks_reader, layer_index, pedantic)?
     };
 
-    // Sort by y coordinate and merge
+    // Sort by position and merge
     let mut blocks = blocks;
-    blocks.sort_by_key(|(y, _)| *y);
+    blocks.sort_by_key(|(position, _)| (position.y(), position.x()));
     let merged = merge_deep_blocks(blocks, width, height)?;
 
     Ok(build_deep_layer(&meta.headers[layer_index], merged))
@@ -511,7 +471,7 @@ fn decompress_blocks_parallel<R: crate::block::reader::ChunksReader>(
     chunks: R,
     layer_index: usize,
     pedantic: bool,
-) -> Result<Vec<(usize, DeepSamples)>> {
+) -> Result<Vec<(Vec2<usize>, DeepSamples)>> {
     let decompressor = match ParallelDeepBlockDecompressor::new(chunks, pedantic) {
         Ok(d) => d,
         Err(chunks) => {
@@ -523,10 +483,11 @@ fn decompress_blocks_parallel<R: crate::block::reader::ChunksReader>(
     let mut blocks = Vec::new();
     for block_result in decompressor {
         let block = block_result?;
-        if block.layer_index != layer_index {
+        // only the full resolution level is loaded
+        if block.layer_index != layer_index || block.index.level != Vec2(0, 0) {
             continue;
         }
-        blocks.push((block.y_coordinate as usize, block.samples));
+        blocks.push((block.index.pixel_position, block.samples));
     }
     Ok(blocks)
 }
@@ -536,16 +497,17 @@ fn decompress_blocks_sequential<R: crate::block::reader::ChunksReader>(
     chunks: R,
     layer_index: usize,
     pedantic: bool,
-) -> Result<Vec<(usize, DeepSamples)>> {
+) -> Result<Vec<(Vec2<usize>, DeepSamples)>> {
     let decompressor = SequentialDeepBlockDecompressor::new(chunks, pedantic);
 
     let mut blocks = Vec::new();
     for block_result in decompressor {
         let block = block_result?;
-        if block.layer_index != layer_index {
+        // only the full resolution level is loaded
+        if block.layer_index != layer_index || block.index.level != Vec2(0, 0) {
             continue;
         }
-        blocks.push((block.y_coordinate as usize, block.samples));
+        blocks.push((block.index.pixel_position, block.samples));
     }
     Ok(blocks)
 }
@@ -585,11 +547,11 @@ fn build_deep_layer(header: &Header, samples: DeepSamples) -> Layer<AnyChannels<
     }
 }
 
-/// Merge multiple deep scanline blocks into a single full-image [`DeepSamples`].
+/// Merge multiple deep scanline or tile blocks into a single full-image [`DeepSamples`].
 ///
 /// # Algorithm
 ///
-/// Deep scanline files store data in blocks (typically 1-32 lines each).
+/// Deep files store data in blocks (scan line blocks of typically 1-32 lines each, or tiles).
 /// This function combines them:
 ///
 /// 1. **Build offset table**: Create `combined_offsets[total_pixels + 1]` with leading 0
@@ -612,11 +574,11 @@ fn build_deep_layer(header: &Header, samples: DeepSamples) -> Layer<AnyChannels<
 ///
 /// # Arguments
 ///
-/// * `blocks` - Vec of (y_offset, DeepSamples) pairs, one per block
+/// * `blocks` - Vec of (pixel position, DeepSamples) pairs, one per block
 /// * `total_width` - Full image width
 /// * `total_height` - Full image height
 fn merge_deep_blocks(
-    blocks: Vec<(usize, DeepSamples)>,
+    blocks: Vec<(Vec2<usize>, DeepSamples)>,
     total_width: usize,
     total_height: usize,
 ) -> Result<DeepSamples> {
@@ -624,7 +586,11 @@ fn merge_deep_blocks(
         return Ok(DeepSamples::new(total_width, total_height));
     }
 
-    if blocks.len() == 1 {
+    let covers_image = |(position, block): &(Vec2<usize>, DeepSamples)| {
+        *position == Vec2(0, 0) && block.width == total_width && block.height == total_height
+    };
+
+    if blocks.len() == 1 && covers_image(&blocks[0]) {
         let (_, samples) = blocks.into_iter().next().unwrap();
         return Ok(samples);
     }
@@ -634,15 +600,15 @@ fn merge_deep_blocks(
     let mut combined_offsets = vec![0u32; total_pixels + 1];
 
     // Calculate sample counts from all blocks
-    for (y, block) in &blocks {
+    for (position, block) in &blocks {
         for row in 0..block.height {
-            let image_y = y + row;
+            let image_y = position.y() + row;
             if image_y >= total_height {
                 break;
             }
 
-            for col in 0..block.width.min(total_width) {
-                let pixel_idx = image_y * total_width + col;
+            for col in 0..block.width.min(total_width.saturating_sub(position.x())) {
+                let pixel_idx = image_y * total_width + position.x() + col;
                 combined_offsets[pixel_idx + 1] = block.sample_count(col, row) as u32;
             }
         }
@@ -721,7 +687,7 @@ fn merge_deep_blocks(
 
 /// Merge F16 channel data.
 fn merge_channel_f16(
-    blocks: &[(usize, DeepSamples)],
+    blocks: &[(Vec2<usize>, DeepSamples)],
     ch_idx: usize,
     total_width: usize,
     total_height: usize,
@@ -730,7 +696,7 @@ fn merge_channel_f16(
 ) {
     use crate::image::deep::DeepChannelData;
 
-    for (y, block) in blocks {
+    for (position, block) in blocks {
         let src = match block.channels.get(ch_idx) {
             Some(DeepChannelData::F16(v)) => v,
             _ => continue,
@@ -738,7 +704,7 @@ fn merge_channel_f16(
 
         copy_block_samples(
             block,
-            *y,
+            *position,
             src,
             total_width,
             total_height,
@@ -750,7 +716,7 @@ fn merge_channel_f16(
 
 /// Merge F32 channel data.
 fn merge_channel_f32(
-    blocks: &[(usize, DeepSamples)],
+    blocks: &[(Vec2<usize>, DeepSamples)],
     ch_idx: usize,
     total_width: usize,
     total_height: usize,
@@ -759,7 +725,7 @@ fn merge_channel_f32(
 ) {
     use crate::image::deep::DeepChannelData;
 
-    for (y, block) in blocks {
+    for (position, block) in blocks {
         let src = match block.channels.get(ch_idx) {
             Some(DeepChannelData::F32(v)) => v,
             _ => continue,
@@ -767,7 +733,7 @@ fn merge_channel_f32(
 
         copy_block_samples(
             block,
-            *y,
+            *position,
             src,
             total_width,
             total_height,
@@ -779,7 +745,7 @@ fn merge_channel_f32(
 
 /// Merge U32 channel data.
 fn merge_channel_u32(
-    blocks: &[(usize, DeepSamples)],
+    blocks: &[(Vec2<usize>, DeepSamples)],
     ch_idx: usize,
     total_width: usize,
     total_height: usize,
@@ -788,7 +754,7 @@ fn merge_channel_u32(
 ) {
     use crate::image::deep::DeepChannelData;
 
-    for (y, block) in blocks {
+    for (position, block) in blocks {
         let src = match block.channels.get(ch_idx) {
             Some(DeepChannelData::U32(v)) => v,
             _ => continue,
@@ -796,7 +762,7 @@ fn merge_channel_u32(
 
         copy_block_samples(
             block,
-            *y,
+            *position,
             src,
             total_width,
             total_height,
@@ -809,7 +775,7 @@ fn merge_channel_u32(
 /// Copy samples from a block to the combined output.
 fn copy_block_samples<T: Copy>(
     block: &DeepSamples,
-    y_offset: usize,
+    position: Vec2<usize>,
     src: &[T],
     total_width: usize,
     total_height: usize,
@@ -817,13 +783,13 @@ fn copy_block_samples<T: Copy>(
     output: &mut [T],
 ) {
     for row in 0..block.height {
-        let image_y = y_offset + row;
+        let image_y = position.y() + row;
         if image_y >= total_height {
             break;
         }
 
-        for col in 0..block.width.min(total_width) {
-            let pixel_idx = image_y * total_width + col;
+        for col in 0..block.width.min(total_width.saturating_sub(position.x())) {
+            let pixel_idx = image_y * total_width + position.x() + col;
             let block_pixel_idx = row * block.width + col;
             let count = block.sample_count(col, row);
 
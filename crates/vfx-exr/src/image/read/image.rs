@@ -3,6 +3,7 @@
 
 use crate::block::chunk::TileCoordinates;
 use crate::block::reader::ChunksReader;
+use crate::block::recover::{RecoveryReader, RecoveryReport};
 use crate::block::{BlockIndex, UncompressedBlock};
 use crate::error::{Result, UnitResult};
 use crate::image::*;
@@ -167,6 +168,63 @@ where
 
         Ok(image_collector.into_image())
     }
+
+    /// Read as much as possible of a damaged exr file, for example a file that was truncated while writing.
+    /// Missing offset tables are rebuilt by scanning the file.
+    /// Pixel blocks that cannot be recovered are filled with `fill_value`,
+    /// and are listed in the returned report.
+    /// Only the meta data must be intact. Deep data cannot be recovered.
+    /// Always decompresses sequentially.
+    #[must_use]
+    pub fn recover_from_file<Layers>(
+        self,
+        path: impl AsRef<Path>,
+        fill_value: f32,
+    ) -> Result<(Image<Layers>, RecoveryReport)>
+    where
+        for<'s> L: ReadLayers<'s, Layers = Layers>,
+    {
+        self.recover_from_buffered(BufReader::new(std::fs::File::open(path)?), fill_value)
+    }
+
+    /// Read as much as possible of a damaged exr image from a buffered reader.
+    /// See [`ReadImage::recover_from_file`].
+    #[must_use]
+    pub fn recover_from_buffered<Layers>(
+        mut self,
+        buffered: impl Read + Seek,
+        fill_value: f32,
+    ) -> Result<(Image<Layers>, RecoveryReport)>
+    where
+        for<'s> L: ReadLayers<'s, Layers = Layers>,
+    {
+        let Self {
+            ref mut on_progress,
+            ref mut read_layers,
+            ..
+        } = self;
+
+        let chunks_reader = RecoveryReader::read_from_buffered(buffered)?;
+        let layers_reader = read_layers.create_layers_reader(chunks_reader.headers())?;
+        let mut image_collector =
+            ImageWithAttributesReader::new(chunks_reader.headers(), layers_reader)?;
+
+        let mut blocks = chunks_reader.filter_blocks(fill_value, |meta, tile, block| {
+            image_collector.filter_block(meta, tile, block)
+        })?;
+
+        let total_blocks = blocks.block_count() as f64;
+        let mut decompressed_blocks = 0;
+
+        while let Some(block) = blocks.next() {
+            on_progress(decompressed_blocks as f64 / total_blocks);
+            image_collector.read_block(&blocks.meta_data().headers, block)?;
+            decompressed_blocks += 1;
+        }
+
+        on_progress(1.0);
+        Ok((image_collector.into_image(), blocks.into_report()))
+    }
 }
 
 /// Processes blocks from a file and collects them into a complete `Image`.
@@ -30,6 +30,9 @@
 //!     Note: Currently does not support deep data, and currently fails
 //!     if any layer in the image contains deep data.
 //!
+//! To read only one layer, a subset of its channels, a pixel region or a single
+//! resolution level without decoding the rest of the file, use [`region::read_region`].
+//!
 
 // The following three stages are internally used to read an image.
 // 1. `ReadImage` - The specification. Contains everything the user wants to tell us about loading an image.
@@ -47,6 +50,7 @@ pub mod deep;
 pub mod image;
 pub mod layers;
 pub mod levels;
+pub mod region;
 pub mod samples;
 pub mod specific_channels;
 
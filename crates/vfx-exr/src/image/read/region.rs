@@ -0,0 +1,521 @@
+//! Random access reads of a single layer, channel subset, pixel region and resolution level.
+//!
+//! The [`read()`](super::read) builder decodes every chunk of the selected layers and levels.
+//! For large multi-part files, where only one layer or one crop is required,
+//! this reader instead uses the chunk offset tables to seek directly
+//! to the chunks that overlap the requested region of the requested level,
+//! and decompresses only those.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use vfx_exr::image::read::region::read_region;
+//! use vfx_exr::math::Vec2;
+//! use vfx_exr::meta::attribute::IntegerBounds;
+//!
+//! // the diffuse AOV of part 3, a 256x256 crop of the first mip level
+//! let image = read_region(3)
+//!     .channels(["diffuse.R", "diffuse.G", "diffuse.B"])
+//!     .region(IntegerBounds::new(Vec2(512, 512), Vec2(256, 256)))
+//!     .level(Vec2(1, 1))
+//!     .from_file("render.exr")?;
+//!
+//! assert_eq!(image.layer_data.size, Vec2(256, 256));
+//! # Ok::<(), vfx_exr::error::Error>(())
+//! ```
+
+use crate::block::lines::LineRef;
+use crate::block::reader::ChunksReader;
+use crate::block::{BlockIndex, UncompressedBlock};
+use crate::error::{Error, Result, UnitResult};
+use crate::image::*;
+use crate::math::Vec2;
+use crate::meta::attribute::{IntegerBounds, LevelMode, Text};
+use crate::meta::header::Header;
+use crate::meta::{mip_map_levels, rip_map_levels, BlockDescription};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+
+/// Specify a part of a flat layer to read.
+/// Create it using [`read_region`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadRegion {
+    /// The index of the layer (the part) in the file.
+    pub layer_index: usize,
+
+    /// The names of the channels to read. `None` reads all channels.
+    pub channels: Option<Vec<Text>>,
+
+    /// The pixel rectangle to read, in the coordinates of the data window.
+    /// Resolution levels share the origin of the data window.
+    /// Clipped to the data window of the level. `None` reads the whole level.
+    pub region: Option<IntegerBounds>,
+
+    /// The mip or rip level index to read. `(0, 0)` is the full resolution.
+    pub level: Vec2<usize>,
+
+    /// Whether to abort on invalid offset tables instead of skipping the checks.
+    pub pedantic: bool,
+
+    /// Whether to decompress the selected chunks on multiple threads.
+    /// Defaults to `true` if the `rayon` feature is enabled.
+    pub parallel: bool,
+}
+
+/// Read all channels of the full resolution level of the specified layer.
+/// Narrow the selection down using [`ReadRegion::channels`],
+/// [`ReadRegion::region`] and [`ReadRegion::level`].
+pub fn read_region(layer_index: usize) -> ReadRegion {
+    ReadRegion {
+        layer_index,
+        channels: None,
+        region: None,
+        level: Vec2(0, 0),
+        pedantic: false,
+        parallel: cfg!(feature = "rayon"),
+    }
+}
+
+impl ReadRegion {
+    /// Read only the channels with the specified names.
+    /// Fails if the layer does not contain one of these channels.
+    pub fn channels<T: Into<Text>>(self, names: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            channels: Some(names.into_iter().map(Into::into).collect()),
+            ..self
+        }
+    }
+
+    /// Read only the pixels inside this rectangle, in the coordinates of the data window.
+    /// The position must be a multiple of the subsampling of each selected channel.
+    pub fn region(self, region: IntegerBounds) -> Self {
+        Self {
+            region: Some(region),
+            ..self
+        }
+    }
+
+    /// Read this mip or rip level instead of the full resolution.
+    /// Mip map levels are specified as `Vec2(level, level)`.
+    pub fn level(self, level: Vec2<usize>) -> Self {
+        Self { level, ..self }
+    }
+
+    /// Abort if the offset tables are invalid.
+    pub fn pedantic(self) -> Self {
+        Self {
+            pedantic: true,
+            ..self
+        }
+    }
+
+    /// Decompress the selected chunks on the current thread only.
+    pub fn non_parallel(self) -> Self {
+        Self {
+            parallel: false,
+            ..self
+        }
+    }
+
+    /// Read the selection from a file.
+    pub fn from_file(
+        self,
+        path: impl AsRef<Path>,
+    ) -> Result<Image<Layer<AnyChannels<FlatSamples>>>> {
+        self.from_unbuffered(File::open(path)?)
+    }
+
+    /// Buffer the reader and then read the selection from it.
+    pub fn from_unbuffered(
+        self,
+        unbuffered: impl Read + Seek,
+    ) -> Result<Image<Layer<AnyChannels<FlatSamples>>>> {
+        self.from_buffered(BufReader::new(unbuffered))
+    }
+
+    /// Read the selection from a buffered byte source.
+    /// Only the chunks that overlap the selection are read and decompressed.
+    pub fn from_buffered(
+        self,
+        buffered: impl Read + Seek,
+    ) -> Result<Image<Layer<AnyChannels<FlatSamples>>>> {
+        let reader = crate::block::read(buffered, self.pedantic)?;
+
+        let header = reader
+            .headers()
+            .get(self.layer_index)
+            .cloned()
+            .ok_or_else(|| Error::invalid("layer index out of range"))?;
+
+        let mut collector = RegionReader::new(&self, &header)?;
+        let (layer_index, level, pedantic) = (self.layer_index, self.level, self.pedantic);
+        let local_region = collector.local_region;
+
+        let chunks = reader.filter_chunks(pedantic, |_, tile, block: BlockIndex| {
+            block.layer == layer_index
+                && tile.level_index == level
+                && IntegerBounds::new(block.pixel_position.to_i32(), block.pixel_size)
+                    .intersection(local_region)
+                    .is_some()
+        })?;
+
+        if self.parallel {
+            #[cfg(not(feature = "rayon"))]
+            return Err(Error::unsupported(
+                "parallel decompression requires the rayon feature",
+            ));
+
+            #[cfg(feature = "rayon")]
+            chunks.decompress_parallel(pedantic, |meta, block| {
+                collector.read_block(&meta.headers[layer_index], block)
+            })?;
+        } else {
+            chunks.decompress_sequential(pedantic, |meta, block| {
+                collector.read_block(&meta.headers[layer_index], block)
+            })?;
+        }
+
+        Ok(collector.into_image(&header))
+    }
+}
+
+/// Accumulates the selected samples of the decompressed blocks.
+#[derive(Debug)]
+struct RegionReader {
+    /// The selected rectangle, relative to the data window origin.
+    local_region: IntegerBounds,
+
+    /// For each selected channel: its index in the header and its samples.
+    channels: Vec<(usize, AnyChannel<FlatSamples>)>,
+}
+
+impl RegionReader {
+    fn new(read: &ReadRegion, header: &Header) -> Result<Self> {
+        if header.deep {
+            return Err(Error::unsupported("region reads of deep data"));
+        }
+
+        let level_size = level_size(header, read.level)?;
+        let data_window = IntegerBounds::new(header.own_attributes.layer_position, level_size);
+
+        let region = match read.region {
+            Some(region) => region
+                .intersection(data_window)
+                .ok_or_else(|| Error::invalid("region does not overlap the data window"))?,
+            None => data_window,
+        };
+
+        let local_region = IntegerBounds::new(
+            region.position - header.own_attributes.layer_position,
+            region.size,
+        );
+
+        let selected: Vec<usize> = match &read.channels {
+            None => (0..header.channels.list.len()).collect(),
+            Some(names) => names
+                .iter()
+                .map(|name| {
+                    header
+                        .channels
+                        .find_index_of_channel(name)
+                        .ok_or_else(|| Error::invalid("channel not found in layer"))
+                })
+                .collect::<Result<_>>()?,
+        };
+
+        let mut channels = Vec::with_capacity(selected.len());
+        for index in selected {
+            let channel = &header.channels.list[index];
+            let sampling = channel.sampling;
+
+            if local_region.position.x() % sampling.x() as i32 != 0
+                || local_region.position.y() % sampling.y() as i32 != 0
+            {
+                return Err(Error::invalid(
+                    "region position must be a multiple of the channel subsampling",
+                ));
+            }
+
+            let resolution = Vec2(
+                local_region.size.x().div_ceil(sampling.x()),
+                local_region.size.y().div_ceil(sampling.y()),
+            );
+
+            let sample_data = match channel.sample_type {
+                SampleType::F16 => FlatSamples::F16(vec![f16::ZERO; resolution.area()]),
+                SampleType::F32 => FlatSamples::F32(vec![0.0; resolution.area()]),
+                SampleType::U32 => FlatSamples::U32(vec![0; resolution.area()]),
+            };
+
+            channels.push((
+                index,
+                AnyChannel {
+                    name: channel.name.clone(),
+                    sample_data,
+                    quantize_linearly: channel.quantize_linearly,
+                    sampling,
+                },
+            ));
+        }
+
+        // the channels of a layer must be sorted by name
+        channels.sort_by(|(_, a), (_, b)| a.name.cmp(&b.name));
+
+        Ok(Self {
+            local_region,
+            channels,
+        })
+    }
+
+    fn read_block(&mut self, header: &Header, block: UncompressedBlock) -> UnitResult {
+        let region_position = self.local_region.position.to_usize("region position")?;
+        let region_size = self.local_region.size;
+
+        for line in block.lines(&header.channels) {
+            let Some((_, channel)) = self
+                .channels
+                .iter_mut()
+                .find(|(index, _)| *index == line.location.channel)
+            else {
+                continue;
+            };
+
+            let sampling = channel.sampling;
+            let sample_start = Vec2(
+                region_position.x() / sampling.x(),
+                region_position.y() / sampling.y(),
+            );
+            let resolution = Vec2(
+                region_size.x().div_ceil(sampling.x()),
+                region_size.y().div_ceil(sampling.y()),
+            );
+
+            let position = line.location.position;
+            if position.y() < sample_start.y() || position.y() >= sample_start.y() + resolution.y() {
+                continue;
+            }
+
+            let start_x = position.x().max(sample_start.x());
+            let end_x = (position.x() + line.location.sample_count)
+                .min(sample_start.x() + resolution.x());
+            if start_x >= end_x {
+                continue;
+            }
+
+            let bytes_per_sample = line.value.len() / line.location.sample_count;
+            let section = LineRef {
+                location: crate::block::lines::LineIndex {
+                    position: Vec2(start_x, position.y()),
+                    sample_count: end_x - start_x,
+                    ..line.location
+                },
+                value: &line.value
+                    [(start_x - position.x()) * bytes_per_sample..(end_x - position.x()) * bytes_per_sample],
+            };
+
+            let target_start = (position.y() - sample_start.y()) * resolution.x()
+                + (start_x - sample_start.x());
+            let target = target_start..target_start + section.location.sample_count;
+
+            match &mut channel.sample_data {
+                FlatSamples::F16(samples) => section.read_samples_into_slice(&mut samples[target])?,
+                FlatSamples::F32(samples) => section.read_samples_into_slice(&mut samples[target])?,
+                FlatSamples::U32(samples) => section.read_samples_into_slice(&mut samples[target])?,
+            }
+        }
+
+        Ok(())
+    }
+
+    fn into_image(self, header: &Header) -> Image<Layer<AnyChannels<FlatSamples>>> {
+        let mut attributes = header.own_attributes.clone();
+        attributes.layer_position = header.own_attributes.layer_position + self.local_region.position;
+
+        Image {
+            attributes: header.shared_attributes.clone(),
+            layer_data: Layer {
+                channel_data: AnyChannels {
+                    list: self.channels.into_iter().map(|(_, channel)| channel).collect(),
+                },
+                attributes,
+                size: self.local_region.size,
+                encoding: Encoding {
+                    compression: header.compression,
+                    line_order: header.line_order,
+                    blocks: match header.blocks {
+                        BlockDescription::ScanLines => Blocks::ScanLines,
+                        BlockDescription::Tiles(tiles) => Blocks::Tiles(tiles.tile_size),
+                    },
+                },
+            },
+        }
+    }
+}
+
+/// The resolution of the specified level, or an error if the header has no such level.
+fn level_size(header: &Header, level: Vec2<usize>) -> Result<Vec2<usize>> {
+    let size = match header.blocks {
+        BlockDescription::Tiles(tiles) => match tiles.level_mode {
+            LevelMode::Singular => (level == Vec2(0, 0)).then_some(header.layer_size),
+            LevelMode::MipMap => mip_map_levels(tiles.rounding_mode, header.layer_size)
+                .find(|&(index, _)| Vec2(index, index) == level)
+                .map(|(_, size)| size),
+            LevelMode::RipMap => rip_map_levels(tiles.rounding_mode, header.layer_size)
+                .find(|&(index, _)| index == level)
+                .map(|(_, size)| size),
+        },
+        BlockDescription::ScanLines => (level == Vec2(0, 0)).then_some(header.layer_size),
+    };
+
+    size.ok_or_else(|| Error::invalid("resolution level not found in layer"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::math::RoundingMode;
+    use crate::meta::attribute::LineOrder;
+    use crate::meta::header::LayerAttributes;
+    use crate::image::read::read;
+    use crate::prelude::*;
+    use std::io::Cursor;
+
+    fn gradient_layer(name: &str, size: Vec2<usize>, blocks: Blocks, position: Vec2<i32>) -> Layer<AnyChannels<FlatSamples>> {
+        let channel = |name: &str, offset: f32| {
+            AnyChannel::new(
+                name,
+                FlatSamples::F32(
+                    (0..size.area())
+                        .map(|i| (i % size.0) as f32 + (i / size.0) as f32 * 1000.0 + offset)
+                        .collect(),
+                ),
+            )
+        };
+
+        Layer::new(
+            size,
+            LayerAttributes::named(name).with_position(position),
+            Encoding {
+                compression: Compression::ZIP1,
+                blocks,
+                line_order: LineOrder::Increasing,
+            },
+            AnyChannels::sort(smallvec::smallvec![channel("B", 0.25), channel("G", 0.5), channel("R", 0.75)]),
+        )
+    }
+
+    fn write(layers: Layers<AnyChannels<FlatSamples>>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        Image::from_layers(ImageAttributes::new(IntegerBounds::from_dimensions((64, 64))), layers)
+            .write()
+            .to_buffered(Cursor::new(&mut bytes))
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn read_region_of_second_part() {
+        let size = Vec2(40, 30);
+        let bytes = write(smallvec::smallvec![
+            gradient_layer("first", size, Blocks::ScanLines, Vec2(0, 0)),
+            gradient_layer("second", size, Blocks::Tiles(Vec2(8, 8)), Vec2(-3, 5)),
+        ]);
+
+        let image = read_region(1)
+            .channels(["R", "B"])
+            .region(IntegerBounds::new(Vec2(10, 12), Vec2(9, 7)))
+            .non_parallel()
+            .from_buffered(Cursor::new(&bytes))
+            .unwrap();
+
+        let layer = &image.layer_data;
+        assert_eq!(layer.size, Vec2(9, 7));
+        assert_eq!(layer.attributes.layer_position, Vec2(10, 12));
+        assert_eq!(layer.channel_data.list.len(), 2);
+        assert_eq!(layer.channel_data.list[0].name, Text::from("B"));
+
+        let full = read()
+            .no_deep_data()
+            .largest_resolution_level()
+            .all_channels()
+            .all_layers()
+            .all_attributes()
+            .from_buffered(Cursor::new(&bytes))
+            .unwrap();
+
+        let full_red = &full.layer_data[1].channel_data.list[2].sample_data;
+        let region_red = &layer.channel_data.list[1].sample_data;
+        for y in 0..7 {
+            for x in 0..9 {
+                // the region starts at (13, 7) inside the data window
+                assert_eq!(
+                    region_red.value_by_flat_index(y * 9 + x),
+                    full_red.value_by_flat_index((y + 7) * size.0 + x + 13)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn read_region_is_clipped_to_data_window() {
+        let size = Vec2(16, 16);
+        let bytes = write(smallvec::smallvec![gradient_layer("only", size, Blocks::ScanLines, Vec2(0, 0))]);
+
+        let image = read_region(0)
+            .region(IntegerBounds::new(Vec2(-4, 12), Vec2(8, 8)))
+            .from_buffered(Cursor::new(&bytes))
+            .unwrap();
+
+        assert_eq!(image.layer_data.size, Vec2(4, 4));
+        assert_eq!(image.layer_data.attributes.layer_position, Vec2(0, 12));
+
+        let disjoint = read_region(0)
+            .region(IntegerBounds::new(Vec2(100, 100), Vec2(8, 8)))
+            .from_buffered(Cursor::new(&bytes));
+        assert!(disjoint.is_err());
+
+        assert!(read_region(1).from_buffered(Cursor::new(&bytes)).is_err());
+        assert!(read_region(0).channels(["Z"]).from_buffered(Cursor::new(&bytes)).is_err());
+    }
+
+    #[test]
+    fn read_region_of_mip_level() {
+        let size = Vec2(32, 20);
+        let levels = mip_map_levels(RoundingMode::Down, size)
+            .map(|(index, level_size)| {
+                FlatSamples::F32(vec![index as f32; level_size.area()])
+            })
+            .collect();
+
+        let layer = Layer::new(
+            size,
+            LayerAttributes::named("mips"),
+            Encoding {
+                compression: Compression::RLE,
+                blocks: Blocks::Tiles(Vec2(8, 8)),
+                line_order: LineOrder::Increasing,
+            },
+            AnyChannels::sort(smallvec::smallvec![AnyChannel::new(
+                "Y",
+                Levels::Mip { rounding_mode: RoundingMode::Down, level_data: levels }
+            )]),
+        );
+
+        let mut bytes = Vec::new();
+        Image::from_layer(layer).write().to_buffered(Cursor::new(&mut bytes)).unwrap();
+
+        let image = read_region(0)
+            .level(Vec2(2, 2))
+            .from_buffered(Cursor::new(&bytes))
+            .unwrap();
+
+        assert_eq!(image.layer_data.size, Vec2(8, 5));
+        match &image.layer_data.channel_data.list[0].sample_data {
+            FlatSamples::F32(values) => assert!(values.iter().all(|&value| value == 2.0)),
+            _ => panic!("unexpected sample type"),
+        }
+
+        assert!(read_region(0).level(Vec2(1, 2)).from_buffered(Cursor::new(&bytes)).is_err());
+    }
+}
@@ -172,6 +172,10 @@ ReadChannels<'s> for CollectPixels<InnerChannels, Pixel, PixelStorage, CreatePix
     fn create_channels_reader(&'s self, header: &Header) -> Result<Self::Reader> {
         if header.deep { return Err(Error::invalid("`SpecificChannels` does not support deep data yet")) }
 
+        if header.channels.list.iter().any(|channel| channel.sampling != Vec2(1, 1)) {
+            return Err(Error::unsupported("`SpecificChannels` does not support subsampled channels, use `AnyChannels` instead"))
+        }
+
         let pixel_reader = self.read_channels.create_recursive_reader(&header.channels)?;
         let channel_descriptions = pixel_reader.get_descriptions().into_non_recursive();// TODO not call this twice
 
@@ -226,7 +230,6 @@ where
         );
 
         for (y_offset, line_bytes) in byte_lines.enumerate() {
-            // TODO sampling
             // this two-step copy method should be very cache friendly in theory, and also reduce sample_type lookup count
             self.pixel_reader
                 .read_pixels(line_bytes, &mut pixels, |px| px);
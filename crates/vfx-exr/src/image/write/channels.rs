@@ -98,7 +98,8 @@ where
         let channels = self
             .list
             .iter()
-            .map(|chan| chan.sample_data.create_samples_writer(header))
+            .zip(&header.channels.list)
+            .map(|(chan, description)| chan.sample_data.create_samples_writer(header, description))
             .collect();
 
         AnyChannelsWriter { channels }
@@ -3,18 +3,19 @@
 //! # Overview
 //!
 //! This module writes deep images (variable samples per pixel) to OpenEXR files.
-//! It provides both high-level image writing and low-level scanline writing.
+//! It provides high-level image writing, low-level scanline writing,
+//! and writing of tiled layers with multiple resolution levels.
 //!
 //! # Writing Pipeline
 //!
 //! ```text
-//! DeepImage / DeepSamples
+//! DeepImage / DeepSamples / Levels<DeepSamples>
 //!         │
-//!         ├── Build Header (deep=true, BlockType=DeepScanLine)
+//!         ├── Build Header (deep=true, BlockType=DeepScanLine or DeepTile)
 //!         │
-//!         ├── For each scanline block:
-//!         │    ├── extract_single_line() → line DeepSamples
-//!         │    ├── compress_deep_scanline_block()
+//!         ├── For each scanline block or tile, in header line order:
+//!         │    ├── extract_block_samples() → block DeepSamples
+//!         │    ├── compress_deep_scanline_block() / compress_deep_tile_block()
 //!         │    └── write CompressedBlock
 //!         │
 //!         └── Write offset table
@@ -22,17 +23,20 @@
 //!
 //! # Key Design Decisions
 //!
-//! ## Single-Line Blocks
+//! ## Block Extraction
 //!
-//! This implementation writes one scanline per block. While OpenEXR supports
-//! multi-line blocks (up to `compression.scan_lines_per_block()`), single-line
-//! has advantages:
+//! Each block is extracted from the full resolution level into its own
+//! [`DeepSamples`], with cumulative counts that start at zero for the block.
+//! Scan line blocks have `compression.scan_lines_per_block()` lines.
+//! Tiles at the right and bottom edge of the data window are cut off,
+//! as required by the OpenEXR file layout.
 //!
-//! - Simpler offset table extraction (no cross-line prefix sum adjustments)
-//! - Better streaming support (can write as data becomes available)
-//! - Compatibility with all readers
+//! ## Data Window
 //!
-//! The tradeoff is slightly larger file size due to per-block overhead.
+//! The position of the data window is taken from the layer attributes.
+//! Scan line block coordinates are written relative to the origin of the
+//! file, while all [`DeepSamples`] are indexed from the top left
+//! corner of the data window.
 //!
 //! ## Channel Data Layout
 //!
@@ -66,6 +70,9 @@
 //! # Ok::<(), vfx_exr::error::Error>(())
 //! ```
 //!
+//! Tiled layers with explicit resolution levels are written with
+//! [`write_deep_levels_to_file`], using a [`Header`] that describes the layout.
+//!
 //! # Compression Support
 //!
 //! All standard compressions work with deep data:
@@ -84,22 +91,27 @@ use std::io::{BufWriter, Seek, Write};
 use std::path::Path;
 
 use crate::block::chunk::{Chunk, CompressedBlock};
-use crate::block::deep::compress_deep_scanline_block;
+use crate::block::deep::{compress_deep_scanline_block, compress_deep_tile_block};
 use crate::block::writer::{ChunkWriter, ChunksWriter};
 use crate::compression::Compression;
-use crate::error::UnitResult;
+use crate::error::{usize_to_i32, Error, UnitResult};
 use crate::image::deep::DeepSamples;
-use crate::image::{AnyChannels, Image, ImageAttributes, Layer, LayerAttributes};
-use crate::math::Vec2;
-use crate::meta::attribute::{ChannelDescription, ChannelList, IntegerBounds, LineOrder};
+use crate::image::{AnyChannels, Blocks, Image, ImageAttributes, Layer, LayerAttributes, Levels};
+use crate::math::{RoundingMode, Vec2};
+use crate::meta::attribute::{
+    ChannelDescription, ChannelList, IntegerBounds, LevelMode, LineOrder, TileDescription,
+};
 use crate::meta::header::{Header, ImageAttributes as HeaderImageAttributes};
-use crate::meta::{BlockDescription, Headers, MetaData};
+use crate::meta::{compute_chunk_count, mip_map_levels, rip_map_levels, BlockDescription, Headers, MetaData};
 
 /// Type alias for a deep image with any channels.
 pub type DeepImage = Image<Layer<AnyChannels<DeepSamples>>>;
 
 /// Write a deep image to a file.
 ///
+/// The scan line or tile layout of the layer encoding is preserved,
+/// as well as the data window position and the display window.
+///
 /// # Arguments
 /// * `path` - Output file path
 /// * `image` - The deep image to write
@@ -149,14 +161,30 @@ pub fn write_deep_image_to_buffered<W: Write + Seek>(
             .collect(),
     );
 
-    write_deep_scanlines_to_buffered(
-        write,
+    let blocks = match layer.encoding.blocks {
+        Blocks::ScanLines => BlockDescription::ScanLines,
+        Blocks::Tiles(tile_size) => BlockDescription::Tiles(TileDescription {
+            tile_size,
+            level_mode: LevelMode::Singular,
+            rounding_mode: RoundingMode::Down,
+        }),
+    };
+
+    let line_order = match layer.encoding.line_order {
+        LineOrder::Unspecified => LineOrder::Increasing,
+        line_order => line_order,
+    };
+
+    let header = deep_header(
         samples,
         &channel_list,
         compression,
         Some(&image.attributes),
         Some(&layer.attributes),
     )
+    .with_encoding(compression, blocks, line_order);
+
+    write_deep_blocks_to_buffered(write, header, &[(Vec2(0, 0), samples)])
 }
 
 /// Write deep scanline data to a file.
@@ -180,6 +208,78 @@ pub fn write_deep_scanlines_to_file(
     })
 }
 
+/// Write all resolution levels of a deep layer to a file.
+///
+/// The `header` specifies the layout of the file: compression,
+/// scan lines or tiles (including tile size, level mode and rounding mode),
+/// line order, data window position, display window, and attributes.
+/// Use [`Header::new`] and its builder methods to create it.
+///
+/// The deep specific header fields (`deep`, `deep_data_version`,
+/// `max_samples_per_pixel` and `chunk_count`) are computed from `levels`.
+/// Each level must have the resolution that the header requires for it.
+/// For scan line headers, `levels` must be [`Levels::Singular`].
+///
+/// # Example
+/// ```no_run
+/// use vfx_exr::image::deep::DeepSamples;
+/// use vfx_exr::image::Levels;
+/// use vfx_exr::image::write::deep::write_deep_levels_to_file;
+/// use vfx_exr::math::{RoundingMode, Vec2};
+/// use vfx_exr::meta::attribute::*;
+/// use vfx_exr::meta::header::Header;
+/// use vfx_exr::meta::BlockDescription;
+/// use vfx_exr::compression::Compression;
+///
+/// let samples: DeepSamples = todo!();
+/// let channels: ChannelList = todo!();
+///
+/// let tiles = BlockDescription::Tiles(TileDescription {
+///     tile_size: Vec2(32, 32),
+///     level_mode: LevelMode::Singular,
+///     rounding_mode: RoundingMode::Down,
+/// });
+///
+/// let header = Header::new(Text::from("deep"), Vec2(samples.width, samples.height), channels.list)
+///     .with_encoding(Compression::ZIP1, tiles, LineOrder::Increasing)
+///     .with_position(Vec2(-16, 8));
+///
+/// write_deep_levels_to_file("tiled.exr", header, &Levels::Singular(samples))?;
+/// # Ok::<(), vfx_exr::error::Error>(())
+/// ```
+pub fn write_deep_levels_to_file(
+    path: impl AsRef<Path>,
+    header: Header,
+    levels: &Levels<DeepSamples>,
+) -> UnitResult {
+    crate::io::attempt_delete_file_on_write_error(path.as_ref(), move |write| {
+        write_deep_levels_to_buffered(BufWriter::new(write), header, levels)
+    })
+}
+
+/// Write all resolution levels of a deep layer to a buffered writer.
+/// See [`write_deep_levels_to_file`].
+pub fn write_deep_levels_to_buffered<W: Write + Seek>(
+    write: W,
+    header: Header,
+    levels: &Levels<DeepSamples>,
+) -> UnitResult {
+    let level_indices: Vec<Vec2<usize>> = match levels {
+        Levels::Singular(_) => vec![Vec2(0, 0)],
+        Levels::Mip { level_data, .. } => (0..level_data.len()).map(|i| Vec2(i, i)).collect(),
+        Levels::Rip { level_data, .. } => (0..level_data.level_count.y())
+            .flat_map(|y| (0..level_data.level_count.x()).map(move |x| Vec2(x, y)))
+            .collect(),
+    };
+
+    let level_samples = level_indices
+        .into_iter()
+        .map(|level| Ok((level, levels.get_level(level)?)))
+        .collect::<crate::error::Result<Vec<_>>>()?;
+
+    write_deep_blocks_to_buffered(write, header, &level_samples)
+}
+
 /// Write deep scanline data to a buffered writer.
 fn write_deep_scanlines_to_buffered<W: Write + Seek>(
     write: W,
@@ -189,18 +289,27 @@ fn write_deep_scanlines_to_buffered<W: Write + Seek>(
     image_attrs: Option<&ImageAttributes>,
     layer_attrs: Option<&LayerAttributes>,
 ) -> UnitResult {
-    let width = samples.width;
-    let height = samples.height;
-    let data_size = Vec2(width, height);
+    let header = deep_header(samples, channels, compression, image_attrs, layer_attrs)
+        .with_encoding(compression, BlockDescription::ScanLines, LineOrder::Increasing);
+
+    write_deep_blocks_to_buffered(write, header, &[(Vec2(0, 0), samples)])
+}
 
-    // Calculate max samples per pixel for header
-    let max_samples = samples.max_samples_per_pixel();
+/// Build a scan line header for deep data, using the attributes of an existing image if present.
+fn deep_header(
+    samples: &DeepSamples,
+    channels: &ChannelList,
+    compression: Compression,
+    image_attrs: Option<&ImageAttributes>,
+    layer_attrs: Option<&LayerAttributes>,
+) -> Header {
+    let data_size = Vec2(samples.width, samples.height);
+    let blocks = BlockDescription::ScanLines;
 
-    // Build header for deep scanline data
-    let header = Header {
+    Header {
         channels: channels.clone(),
         compression,
-        blocks: BlockDescription::ScanLines,
+        blocks,
         line_order: LineOrder::Increasing,
         layer_size: data_size,
         shared_attributes: image_attrs
@@ -218,100 +327,158 @@ fn write_deep_scanlines_to_buffered<W: Write + Seek>(
                 display_window: IntegerBounds::new((0, 0), data_size),
                 other: Default::default(),
             }),
-        own_attributes: {
-            let mut attrs = layer_attrs.cloned().unwrap_or_default();
-            // Deep files require a layer name
-            if attrs.layer_name.is_none() {
-                attrs.layer_name = Some(crate::meta::attribute::Text::new_or_panic("deep"));
-            }
-            attrs
-        },
+        own_attributes: layer_attrs.cloned().unwrap_or_default(),
 
-        // Deep data specific
+        // filled in when writing
         deep: true,
         deep_data_version: Some(1),
-        max_samples_per_pixel: Some(max_samples as usize),
+        max_samples_per_pixel: None,
+        chunk_count: compute_chunk_count(compression, data_size, blocks),
+    }
+}
 
-        // Multi-part
-        chunk_count: calculate_chunk_count(height, compression),
+/// Write the blocks of all levels of a single deep layer.
+/// Completes the deep specific fields of the header and validates the level resolutions.
+fn write_deep_blocks_to_buffered<W: Write + Seek>(
+    write: W,
+    mut header: Header,
+    levels: &[(Vec2<usize>, &DeepSamples)],
+) -> UnitResult {
+    let expected_levels: Vec<(Vec2<usize>, Vec2<usize>)> = match header.blocks {
+        BlockDescription::ScanLines => vec![(Vec2(0, 0), header.layer_size)],
+        BlockDescription::Tiles(tiles) => match tiles.level_mode {
+            LevelMode::Singular => vec![(Vec2(0, 0), header.layer_size)],
+            LevelMode::MipMap => mip_map_levels(tiles.rounding_mode, header.layer_size)
+                .map(|(index, size)| (Vec2(index, index), size))
+                .collect(),
+            LevelMode::RipMap => rip_map_levels(tiles.rounding_mode, header.layer_size).collect(),
+        },
     };
 
+    if expected_levels.len() != levels.len() {
+        return Err(Error::invalid("deep level count does not match the header level mode"));
+    }
+
+    for (level, size) in &expected_levels {
+        let samples = levels
+            .iter()
+            .find(|(index, _)| index == level)
+            .map(|(_, samples)| samples)
+            .ok_or(Error::invalid("deep level missing"))?;
+
+        if Vec2(samples.width, samples.height) != *size {
+            return Err(Error::invalid("deep level resolution does not match the header"));
+        }
+
+        if samples.sample_offsets.len() != size.area() || samples.channels.len() != header.channels.list.len() {
+            return Err(Error::invalid("deep samples do not match the header"));
+        }
+    }
+
+    let max_samples = levels
+        .iter()
+        .map(|(_, samples)| samples.max_samples_per_pixel())
+        .max()
+        .unwrap_or(0);
+
+    header.deep = true;
+    header.deep_data_version = Some(1);
+    header.max_samples_per_pixel = Some(max_samples as usize);
+    header.chunk_count = compute_chunk_count(header.compression, header.layer_size, header.blocks);
+
+    // Deep files require a layer name
+    if header.own_attributes.layer_name.is_none() {
+        header.own_attributes.layer_name = Some(crate::meta::attribute::Text::new_or_panic("deep"));
+    }
+
     let headers: Headers = smallvec::smallvec![header];
 
     // Write the file
     crate::block::writer::write_chunks_with(write, headers, true, |meta, chunk_writer| {
-        write_deep_chunks(chunk_writer, &meta, samples, channels, compression)
+        write_deep_chunks(chunk_writer, &meta, levels)
     })
 }
 
-/// Calculate chunk count for deep scanlines.
-fn calculate_chunk_count(height: usize, compression: Compression) -> usize {
-    let lines_per_block = compression.scan_lines_per_block();
-    (height + lines_per_block - 1) / lines_per_block
-}
-
-/// Write deep scanline chunks to the writer.
+/// Write deep scan line or tile chunks to the writer, in the line order of the header.
 fn write_deep_chunks<W: Write + Seek>(
     writer: &mut ChunkWriter<W>,
     meta: &MetaData,
-    samples: &DeepSamples,
-    channels: &ChannelList,
-    compression: Compression,
+    levels: &[(Vec2<usize>, &DeepSamples)],
 ) -> UnitResult {
     let header = &meta.headers[0];
-    let height = header.layer_size.height();
-    let lines_per_block = compression.scan_lines_per_block();
 
-    let mut block_idx = 0;
-    let mut y = 0;
+    for (chunk_index, tile) in header.enumerate_ordered_blocks() {
+        let level = tile.location.level_index;
+        let samples = levels
+            .iter()
+            .find(|(index, _)| *index == level)
+            .map(|(_, samples)| *samples)
+            .ok_or(Error::invalid("deep level missing"))?;
 
-    while y < height {
-        let block_height = lines_per_block.min(height - y);
+        let bounds = header.get_absolute_block_pixel_coordinates(tile.location)?;
+        let position = bounds.position.to_usize("block position")?;
 
         // Extract samples for this block
-        let block_samples = extract_block_samples(samples, y, block_height, channels);
-
-        // Compress to deep scanline block
-        let compressed =
-            compress_deep_scanline_block(&block_samples, compression, channels, y as i32)?;
+        let block_samples = extract_block_samples(samples, position, bounds.size, &header.channels);
+
+        let compressed_block = match header.blocks {
+            BlockDescription::ScanLines => {
+                let y = usize_to_i32(position.y(), "block y coordinate")?
+                    + header.own_attributes.layer_position.y();
+
+                CompressedBlock::DeepScanLine(compress_deep_scanline_block(
+                    &block_samples,
+                    header.compression,
+                    &header.channels,
+                    y,
+                )?)
+            }
 
-        let chunk = Chunk {
-            layer_index: 0,
-            compressed_block: CompressedBlock::DeepScanLine(compressed),
+            BlockDescription::Tiles(_) => CompressedBlock::DeepTile(compress_deep_tile_block(
+                &block_samples,
+                header.compression,
+                &header.channels,
+                tile.location,
+            )?),
         };
 
-        writer.write_chunk(block_idx, chunk)?;
-
-        block_idx += 1;
-        y += block_height;
+        writer.write_chunk(
+            chunk_index,
+            Chunk {
+                layer_index: 0,
+                compressed_block,
+            },
+        )?;
     }
 
     Ok(())
 }
 
-/// Extract a subset of samples for a specific block (y range).
+/// Extract the samples of a rectangular block from a full level.
 fn extract_block_samples(
     samples: &DeepSamples,
-    y_start: usize,
-    block_height: usize,
+    position: Vec2<usize>,
+    size: Vec2<usize>,
     channels: &ChannelList,
 ) -> DeepSamples {
     let width = samples.width;
 
     // For single-line blocks (common case), optimize
-    if block_height == 1 {
-        return extract_single_line(samples, y_start, channels);
+    if position.x() == 0 && size == Vec2(width, 1) {
+        return extract_single_line(samples, position.y(), channels);
     }
 
-    // Multi-line block extraction
-    let mut block = DeepSamples::new(width, block_height);
+    let rows = position.y()..position.y() + size.height();
+    let columns = position.x()..position.x() + size.width();
+
+    let mut block = DeepSamples::new(size.width(), size.height());
 
     // Calculate cumulative counts for the block
-    let mut cumulative: Vec<u32> = Vec::with_capacity(width * block_height);
+    let mut cumulative: Vec<u32> = Vec::with_capacity(size.area());
     let mut total = 0u32;
 
-    for y in y_start..(y_start + block_height) {
-        for x in 0..width {
+    for y in rows.clone() {
+        for x in columns.clone() {
             let count = samples.sample_count(x, y);
             total += count as u32;
             cumulative.push(total);
@@ -335,15 +502,14 @@ fn extract_block_samples(
         let dst_data = &mut block.channels[ch_idx];
 
         let mut dst_idx = 0;
-        for y in y_start..(y_start + block_height) {
-            for x in 0..width {
-                let count = samples.sample_count(x, y);
-                if count > 0 {
-                    let (src_start, _) = samples.sample_range(y * width + x);
-                    copy_channel_samples(src_data, dst_data, src_start, dst_idx, count);
-                    dst_idx += count;
-                }
-            }
+        for y in rows.clone() {
+            // the samples of a row inside the block are contiguous
+            let (src_start, _) = samples.sample_range(y * width + columns.start);
+            let (_, src_end) = samples.sample_range(y * width + columns.end - 1);
+            let count = src_end - src_start;
+
+            copy_channel_samples(src_data, dst_data, src_start, dst_idx, count);
+            dst_idx += count;
         }
     }
 
@@ -633,4 +799,173 @@ mod test {
             }
         }
     }
+
+    /// Deep samples with a varying number of samples per pixel and distinct values.
+    fn patterned_samples(width: usize, height: usize, channels: &ChannelList) -> DeepSamples {
+        let mut samples = DeepSamples::new(width, height);
+
+        let mut total = 0;
+        let cumulative = (0..width * height)
+            .map(|i| {
+                total += ((i * 7 + i / width * 3) % 4) as u32;
+                total
+            })
+            .collect();
+
+        samples.set_cumulative_counts(cumulative).unwrap();
+        samples.allocate_channels(channels);
+
+        for (ch_idx, ch) in samples.channels.iter_mut().enumerate() {
+            match ch {
+                crate::image::deep::DeepChannelData::F32(v) => {
+                    for (i, val) in v.iter_mut().enumerate() {
+                        *val = (i * 3 + ch_idx) as f32 * 0.25;
+                    }
+                }
+                crate::image::deep::DeepChannelData::U32(v) => {
+                    for (i, val) in v.iter_mut().enumerate() {
+                        *val = (i * 5 + ch_idx) as u32;
+                    }
+                }
+                crate::image::deep::DeepChannelData::F16(v) => {
+                    for (i, val) in v.iter_mut().enumerate() {
+                        *val = half::f16::from_f32((i % 512) as f32);
+                    }
+                }
+            }
+        }
+
+        samples
+    }
+
+    fn read_back(buffer: Vec<u8>) -> DeepImage {
+        crate::image::read::deep::read_deep()
+            .all_channels()
+            .first_valid_layer()
+            .all_attributes()
+            .from_buffered(std::io::Cursor::new(buffer))
+            .expect("failed to read back")
+    }
+
+    #[test]
+    fn roundtrip_deep_tiles_with_offset_windows() {
+        let channels = ChannelList::new(smallvec::smallvec![
+            ChannelDescription::named("A", SampleType::F16),
+            ChannelDescription::named("Z", SampleType::F32),
+            ChannelDescription::named("id", SampleType::U32),
+        ]);
+
+        let samples = patterned_samples(37, 23, &channels);
+        let display_window = IntegerBounds::new((-20, -10), (64, 48));
+
+        for compression in [Compression::Uncompressed, Compression::RLE, Compression::ZIP1] {
+            let tiles = BlockDescription::Tiles(TileDescription {
+                tile_size: Vec2(16, 8),
+                level_mode: LevelMode::Singular,
+                rounding_mode: RoundingMode::Down,
+            });
+
+            let header = Header::new("beauty".into(), Vec2(37, 23), channels.list.clone())
+                .with_encoding(compression, tiles, LineOrder::Increasing)
+                .with_position(Vec2(-7, 5))
+                .with_display_window(display_window);
+
+            let mut buffer = std::io::Cursor::new(Vec::new());
+            write_deep_levels_to_buffered(&mut buffer, header, &Levels::Singular(samples.clone()))
+                .expect("write should succeed");
+
+            let image = read_back(buffer.into_inner());
+            let layer = &image.layer_data;
+
+            assert_eq!(layer.encoding.blocks, Blocks::Tiles(Vec2(16, 8)));
+            assert_eq!(layer.encoding.compression, compression);
+            assert_eq!(layer.attributes.layer_position, Vec2(-7, 5));
+            assert_eq!(image.attributes.display_window, display_window);
+            assert_eq!(layer.channel_data.list[0].sample_data, samples);
+
+            // writing the image again preserves the layout
+            let mut buffer = std::io::Cursor::new(Vec::new());
+            write_deep_image_to_buffered(&mut buffer, &image, compression).expect("rewrite");
+
+            let rewritten = read_back(buffer.into_inner());
+            assert_eq!(rewritten.layer_data.encoding.blocks, Blocks::Tiles(Vec2(16, 8)));
+            assert_eq!(rewritten.layer_data.attributes.layer_position, Vec2(-7, 5));
+            assert_eq!(rewritten.attributes.display_window, display_window);
+            assert_eq!(rewritten.layer_data.channel_data.list[0].sample_data, samples);
+        }
+    }
+
+    #[test]
+    fn roundtrip_deep_scanlines_with_offset_window() {
+        let channels = ChannelList::new(smallvec::smallvec![
+            ChannelDescription::named("R", SampleType::F32),
+            ChannelDescription::named("Z", SampleType::F32),
+        ]);
+
+        let samples = patterned_samples(9, 14, &channels);
+
+        let header = Header::new("deep".into(), Vec2(9, 14), channels.list.clone())
+            .with_encoding(Compression::ZIP1, BlockDescription::ScanLines, LineOrder::Decreasing)
+            .with_position(Vec2(3, -4));
+
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        write_deep_levels_to_buffered(&mut buffer, header, &Levels::Singular(samples.clone()))
+            .expect("write should succeed");
+
+        let image = read_back(buffer.into_inner());
+        assert_eq!(image.layer_data.encoding.blocks, Blocks::ScanLines);
+        assert_eq!(image.layer_data.attributes.layer_position, Vec2(3, -4));
+        assert_eq!(image.layer_data.channel_data.list[0].sample_data, samples);
+    }
+
+    #[test]
+    fn write_deep_mip_levels() {
+        let channels = ChannelList::new(smallvec::smallvec![ChannelDescription::named(
+            "Z",
+            SampleType::F32
+        )]);
+
+        let level_sizes: Vec<Vec2<usize>> =
+            mip_map_levels(RoundingMode::Down, Vec2(20, 12)).map(|(_, size)| size).collect();
+
+        let level_data: Vec<DeepSamples> = level_sizes
+            .iter()
+            .map(|size| patterned_samples(size.width(), size.height(), &channels))
+            .collect();
+
+        let tiles = BlockDescription::Tiles(TileDescription {
+            tile_size: Vec2(8, 8),
+            level_mode: LevelMode::MipMap,
+            rounding_mode: RoundingMode::Down,
+        });
+
+        let header = Header::new("deep".into(), Vec2(20, 12), channels.list.clone())
+            .with_encoding(Compression::RLE, tiles, LineOrder::Increasing);
+
+        let levels = Levels::Mip {
+            rounding_mode: RoundingMode::Down,
+            level_data: level_data.clone(),
+        };
+
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        write_deep_levels_to_buffered(&mut buffer, header.clone(), &levels).expect("write");
+
+        let buffer = buffer.into_inner();
+        let meta = MetaData::read_from_buffered(buffer.as_slice(), false).unwrap();
+        assert_eq!(meta.headers[0].chunk_count, header.chunk_count);
+        assert!(meta.headers[0].deep);
+
+        // the reader loads the full resolution level
+        let image = read_back(buffer);
+        assert_eq!(image.layer_data.channel_data.list[0].sample_data, level_data[0]);
+
+        // levels that do not match the header are rejected
+        let missing_level = Levels::Mip {
+            rounding_mode: RoundingMode::Down,
+            level_data: level_data[..1].to_vec(),
+        };
+
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        assert!(write_deep_levels_to_buffered(&mut buffer, header, &missing_level).is_err());
+    }
 }
@@ -3,7 +3,7 @@
 use crate::block::lines::LineRefMut;
 use crate::image::{FlatSamples, Levels, RipMaps};
 use crate::math::{RoundingMode, Vec2};
-use crate::meta::attribute::{LevelMode, SampleType, TileDescription};
+use crate::meta::attribute::{ChannelDescription, LevelMode, SampleType, TileDescription};
 use crate::meta::header::Header;
 use crate::meta::{
     mip_map_indices, mip_map_levels, rip_map_indices, rip_map_levels, BlockDescription,
@@ -25,7 +25,11 @@ pub trait WritableSamples<'slf> {
     type Writer: SamplesWriter;
 
     /// Create a temporary writer for this sample storage
-    fn create_samples_writer(&'slf self, header: &Header) -> Self::Writer;
+    fn create_samples_writer(
+        &'slf self,
+        header: &Header,
+        channel: &ChannelDescription,
+    ) -> Self::Writer;
 }
 
 /// Enable an image with this single level sample grid to be written to a file.
@@ -69,9 +73,13 @@ impl<'samples> WritableSamples<'samples> for FlatSamples {
     }
 
     type Writer = FlatSamplesWriter<'samples>; //&'s FlatSamples;
-    fn create_samples_writer(&'samples self, header: &Header) -> Self::Writer {
+    fn create_samples_writer(
+        &'samples self,
+        header: &Header,
+        channel: &ChannelDescription,
+    ) -> Self::Writer {
         FlatSamplesWriter {
-            resolution: header.layer_size,
+            resolution: channel.subsampled_resolution(header.layer_size),
             samples: self,
         }
     }
@@ -157,7 +165,11 @@ where
     }
 
     type Writer = LevelsWriter<LevelSamples::Writer>;
-    fn create_samples_writer(&'samples self, header: &Header) -> Self::Writer {
+    fn create_samples_writer(
+        &'samples self,
+        header: &Header,
+        channel: &ChannelDescription,
+    ) -> Self::Writer {
         let rounding = match header.blocks {
             BlockDescription::Tiles(TileDescription { rounding_mode, .. }) => Some(rounding_mode),
             BlockDescription::ScanLines => None,
@@ -165,9 +177,9 @@ where
 
         LevelsWriter {
             levels: match self {
-                Levels::Singular(level) => {
-                    Levels::Singular(level.create_level_writer(header.layer_size))
-                }
+                Levels::Singular(level) => Levels::Singular(
+                    level.create_level_writer(channel.subsampled_resolution(header.layer_size)),
+                ),
                 Levels::Mip {
                     level_data,
                     rounding_mode,
@@ -757,6 +757,18 @@ impl ChannelList {
         })
     }
 
+    /// The number of bytes that the samples of all channels inside the specified pixel section
+    /// require when stored without compression, respecting subsampling.
+    pub fn byte_size_of_section(&self, section: IntegerBounds) -> usize {
+        self.list
+            .iter()
+            .map(|channel| {
+                channel.subsampled_section_size(section).area()
+                    * channel.sample_type.bytes_per_sample()
+            })
+            .sum()
+    }
+
     /// Return the index of the channel with the exact name, case sensitive, or none.
     /// Potentially uses less than linear time.
     pub fn find_index_of_channel(&self, exact_name: &Text) -> Option<usize> {
@@ -904,6 +916,25 @@ impl IntegerBounds {
         self.end() - Vec2(1, 1)
     }
 
+    /// Returns the rectangle covered by both rectangles,
+    /// or `None` if they do not overlap.
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        let start = Vec2(
+            self.position.x().max(other.position.x()),
+            self.position.y().max(other.position.y()),
+        );
+        let end = Vec2(
+            self.end().x().min(other.end().x()),
+            self.end().y().min(other.end().y()),
+        );
+
+        if start.x() >= end.x() || start.y() >= end.y() {
+            return None;
+        }
+
+        Some(Self::new(start, (end - start).to_usize("intersection size").ok()?))
+    }
+
     /// Validate this instance.
     pub fn validate(&self, max_size: Option<Vec2<usize>>) -> UnitResult {
         if let Some(max_size) = max_size {
@@ -1111,6 +1142,29 @@ impl ChannelDescription {
         dimensions / self.sampling
     }
 
+    /// The number of samples this channel has inside the specified pixel section, respecting subsampling.
+    /// Only pixels with coordinates that are a multiple of the sampling rate contain a sample,
+    /// so a single scan line may contain no samples at all.
+    pub fn subsampled_section_size(&self, section: IntegerBounds) -> Vec2<usize> {
+        fn sample_count(start: i32, length: usize, sampling: usize) -> usize {
+            let sampling = sampling as i64;
+            let start = i64::from(start);
+            let end = start + length as i64;
+
+            let first = start + (-start).rem_euclid(sampling);
+            if first >= end {
+                0
+            } else {
+                ((end - 1 - first) / sampling + 1) as usize
+            }
+        }
+
+        Vec2(
+            sample_count(section.position.x(), section.size.width(), self.sampling.x()),
+            sample_count(section.position.y(), section.size.height(), self.sampling.y()),
+        )
+    }
+
     /// Number of bytes this would consume in an exr file.
     pub fn byte_size(&self) -> usize {
         self.name.null_terminated_byte_size()
@@ -1175,10 +1229,12 @@ impl ChannelDescription {
             return Err(Error::invalid("zero sampling factor"));
         }
 
-        if strict && !allow_sampling && self.sampling != Vec2(1, 1) {
-            return Err(Error::invalid(
-                "subsampling is only allowed in flat scan line images",
-            ));
+        if !allow_sampling && self.sampling != Vec2(1, 1) {
+            return Err(if strict {
+                Error::invalid("subsampling is only allowed in flat scan line images")
+            } else {
+                Error::unsupported("subsampling in deep or tiled images")
+            });
         }
 
         if data_window.position.x() % self.sampling.x() as i32 != 0
@@ -1197,13 +1253,6 @@ impl ChannelDescription {
             ));
         }
 
-        if self.sampling != Vec2(1, 1) {
-            // TODO this must only be implemented in the crate::image module and child modules,
-            //      should not be too difficult
-
-            return Err(Error::unsupported("channel subsampling not supported yet"));
-        }
-
         Ok(())
     }
 }
@@ -2631,5 +2680,38 @@ mod test {
             // C: 6×2 = 12 (rows 0,3)
             assert_eq!(pixels.len(), 66, "mixed sampling should total 66 samples");
         }
+
+        #[test]
+        fn section_size_matches_indices() {
+            let channels = ChannelList::new(smallvec::smallvec![
+                make_channel("A", Vec2(1, 1)),
+                make_channel("B", Vec2(2, 2)),
+                make_channel("C", Vec2(3, 1)),
+            ]);
+
+            // single scan lines, as used by uncompressed and zip1 blocks, and negative positions
+            for &(position, size) in &[
+                (Vec2(0, 0), Vec2(6, 1)),
+                (Vec2(0, 1), Vec2(6, 1)),
+                (Vec2(-6, -3), Vec2(12, 5)),
+                (Vec2(0, 16), Vec2(6, 16)),
+            ] {
+                let bounds = IntegerBounds::new(position, size);
+
+                for channel in &channels.list {
+                    let expected = channels
+                        .pixel_section_indices(bounds)
+                        .filter(|(other, _, _)| other.name == channel.name)
+                        .count();
+
+                    assert_eq!(channel.subsampled_section_size(bounds).area(), expected);
+                }
+
+                assert_eq!(
+                    channels.byte_size_of_section(bounds),
+                    channels.pixel_section_indices(bounds).count() * 4
+                );
+            }
+        }
     }
 }
@@ -0,0 +1,366 @@
+//! Edit the headers of an existing file without touching its pixel data.
+//!
+//! The headers are rewritten and the offset tables are shifted by the change in header size,
+//! while all compressed chunks are copied byte by byte.
+//! If the new headers fit into the space of the old headers, the file is updated in place.
+//! Remaining space is filled with a padding attribute, which is removed again on the next edit.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::error::{usize_to_u64, Error, Result, UnitResult};
+use crate::io::{Data, PeekRead, Tracking};
+use crate::meta::attribute::{AttributeValue, Text};
+use crate::meta::header::Header;
+use crate::meta::{Headers, MetaData, OffsetTables};
+
+/// Name of the attribute that fills unused header space after an in-place edit.
+pub const PADDING_ATTRIBUTE_NAME: &str = "headerPadding";
+
+/// Type name of the padding attribute. Other readers keep it as an opaque attribute.
+const PADDING_ATTRIBUTE_KIND: &str = "opaque";
+
+/// How `edit_headers_in_file` stored the edited headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderUpdate {
+    /// The new headers fit into the old header space, only the headers were overwritten.
+    InPlace,
+
+    /// The new headers are larger, the file was rewritten with shifted offset tables.
+    Rewritten,
+}
+
+/// Edit the headers of a file, keeping all compressed pixel chunks unchanged.
+///
+/// The closure may change any attribute that does not affect how the pixels are stored.
+/// Changing channels, compression, blocks, line order, the data window or the number of headers
+/// is an error, as it would require re-encoding the pixels.
+///
+/// If the new headers fit into the old header space, only the headers are overwritten.
+/// Otherwise, the file is rewritten next to the original and then moved over it.
+pub fn edit_headers_in_file(
+    path: impl AsRef<Path>,
+    edit: impl FnOnce(&mut Headers) -> UnitResult,
+) -> Result<HeaderUpdate> {
+    let path = path.as_ref();
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+
+    let layout = FileLayout::read(BufReader::new(&mut file))?;
+    let headers = layout.edited_headers(edit)?;
+
+    if let Some(bytes) = padded_header_bytes(&headers, layout.header_byte_size)? {
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&bytes)?;
+        file.flush()?;
+        return Ok(HeaderUpdate::InPlace);
+    }
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| Error::invalid("file path"))?
+        .to_string_lossy();
+
+    let temporary_path = path.with_file_name(format!(".{}.tmp", file_name));
+
+    let result = (|| {
+        let mut write = BufWriter::new(File::create(&temporary_path)?);
+        layout.write_with_headers(&headers, BufReader::new(&mut file), &mut write)?;
+        write.flush()?;
+        Ok(())
+    })();
+
+    if let Err(error) = result {
+        let _ = std::fs::remove_file(&temporary_path);
+        return Err(error);
+    }
+
+    drop(file);
+    std::fs::rename(&temporary_path, path)?;
+    Ok(HeaderUpdate::Rewritten)
+}
+
+/// Copy an image to a new destination with edited headers, keeping all compressed pixel chunks unchanged.
+/// See `edit_headers_in_file` for the changes allowed in the closure.
+/// The source is assumed to be buffered.
+pub fn edit_headers(
+    mut read: impl Read + Seek,
+    mut write: impl Write,
+    edit: impl FnOnce(&mut Headers) -> UnitResult,
+) -> UnitResult {
+    let layout = FileLayout::read(&mut read)?;
+    let headers = layout.edited_headers(edit)?;
+    layout.write_with_headers(&headers, read, &mut write)
+}
+
+/// The meta data and offset tables of a file, and where its chunks begin.
+#[derive(Debug)]
+struct FileLayout {
+    meta_data: MetaData,
+    offset_tables: OffsetTables,
+
+    /// Bytes from the start of the file to the end of the headers.
+    header_byte_size: usize,
+
+    /// Byte position of the first chunk, directly after the offset tables.
+    chunks_start: usize,
+}
+
+impl FileLayout {
+    fn read(read: impl Read) -> Result<Self> {
+        let mut read = PeekRead::new(Tracking::new(read));
+        let meta_data = MetaData::read_unvalidated_from_buffered_peekable(&mut read, false)?;
+        let offset_tables = MetaData::read_offset_tables(&mut read, &meta_data.headers)?;
+
+        let table_byte_size: usize = offset_tables
+            .iter()
+            .map(|table| table.len() * u64::BYTE_SIZE)
+            .sum();
+        let chunks_start = read.byte_position();
+
+        Ok(FileLayout {
+            header_byte_size: chunks_start - table_byte_size,
+            chunks_start,
+            meta_data,
+            offset_tables,
+        })
+    }
+
+    /// Applies the edit to a copy of the headers, without any padding attribute.
+    fn edited_headers(&self, edit: impl FnOnce(&mut Headers) -> UnitResult) -> Result<Headers> {
+        let mut headers = self.meta_data.headers.clone();
+        for header in &mut headers {
+            remove_padding(header);
+        }
+
+        edit(&mut headers)?;
+
+        if headers.len() != self.meta_data.headers.len() {
+            return Err(Error::invalid(
+                "editing headers must not add or remove headers",
+            ));
+        }
+
+        for (old, new) in self.meta_data.headers.iter().zip(&headers) {
+            let same_layout = old.channels == new.channels
+                && old.compression == new.compression
+                && old.blocks == new.blocks
+                && old.line_order == new.line_order
+                && old.deep == new.deep
+                && old.data_window() == new.data_window();
+
+            if !same_layout {
+                return Err(Error::invalid(
+                    "editing headers must not change how the pixels are stored",
+                ));
+            }
+        }
+
+        Ok(headers)
+    }
+
+    /// Writes the new headers and shifted offset tables, then copies all chunks from the source.
+    fn write_with_headers(
+        &self,
+        headers: &[Header],
+        mut read: impl Read + Seek,
+        write: &mut impl Write,
+    ) -> UnitResult {
+        let mut header_bytes = Vec::with_capacity(self.header_byte_size);
+        MetaData::write_validating_to_buffered(&mut header_bytes, headers, false)?;
+
+        let new_size = usize_to_u64(header_bytes.len(), "header size")?;
+        let old_size = usize_to_u64(self.header_byte_size, "header size")?;
+        write.write_all(&header_bytes)?;
+
+        for table in &self.offset_tables {
+            for &offset in table {
+                let shifted = (offset + new_size)
+                    .checked_sub(old_size)
+                    .ok_or_else(|| Error::invalid("offset table"))?;
+
+                u64::write_le(shifted, write)?;
+            }
+        }
+
+        read.seek(SeekFrom::Start(usize_to_u64(
+            self.chunks_start,
+            "chunk position",
+        )?))?;
+        std::io::copy(&mut read, write)?;
+        Ok(())
+    }
+}
+
+/// Serializes the headers with a padding attribute so that they fill exactly `available` bytes.
+/// Returns `None` if the headers are too large to fit.
+fn padded_header_bytes(headers: &Headers, available: usize) -> Result<Option<Vec<u8>>> {
+    let serialize = |headers: &Headers| -> Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(available);
+        MetaData::write_validating_to_buffered(&mut bytes, headers, false)?;
+        Ok(bytes)
+    };
+
+    let bytes = serialize(headers)?;
+    if bytes.len() == available {
+        return Ok(Some(bytes));
+    }
+
+    let mut padded = headers.clone();
+    let last = padded
+        .last_mut()
+        .ok_or_else(|| Error::invalid("no headers"))?;
+    last.own_attributes.other.insert(padding_name(), padding(0));
+
+    let bytes = serialize(&padded)?;
+    if bytes.len() > available {
+        return Ok(None);
+    }
+
+    let last = padded
+        .last_mut()
+        .ok_or_else(|| Error::invalid("no headers"))?;
+    last.own_attributes
+        .other
+        .insert(padding_name(), padding(available - bytes.len()));
+
+    let bytes = serialize(&padded)?;
+    debug_assert_eq!(bytes.len(), available, "header padding size bug");
+    Ok(Some(bytes))
+}
+
+fn padding_name() -> Text {
+    Text::from(PADDING_ATTRIBUTE_NAME)
+}
+
+fn padding(byte_count: usize) -> AttributeValue {
+    AttributeValue::Custom {
+        kind: Text::from(PADDING_ATTRIBUTE_KIND),
+        bytes: smallvec::smallvec![0; byte_count],
+    }
+}
+
+fn remove_padding(header: &mut Header) {
+    header.own_attributes.other.remove(&padding_name());
+    header.shared_attributes.other.remove(&padding_name());
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::image::read::read;
+    use crate::prelude::*;
+    use std::io::Cursor;
+
+    fn test_image() -> Vec<u8> {
+        let size = Vec2(24, 40);
+        let channels = AnyChannels::sort(smallvec::smallvec![
+            AnyChannel::new(
+                "G",
+                FlatSamples::F16((0..size.area()).map(|i| f16::from_f32(i as f32)).collect())
+            ),
+            AnyChannel::new(
+                "Z",
+                FlatSamples::F32((0..size.area()).map(|i| i as f32 * 0.5).collect())
+            ),
+        ]);
+
+        let layer = Layer::new(
+            size,
+            LayerAttributes::named("render"),
+            Encoding {
+                compression: Compression::PIZ,
+                ..Encoding::default()
+            },
+            channels,
+        );
+
+        let mut bytes = Vec::new();
+        Image::from_layer(layer)
+            .write()
+            .to_buffered(Cursor::new(&mut bytes))
+            .unwrap();
+        bytes
+    }
+
+    fn read_image(bytes: &[u8]) -> Image<Layers<AnyChannels<FlatSamples>>> {
+        read()
+            .no_deep_data()
+            .largest_resolution_level()
+            .all_channels()
+            .all_layers()
+            .all_attributes()
+            .from_buffered(Cursor::new(bytes))
+            .unwrap()
+    }
+
+    #[test]
+    fn rewrite_keeps_pixels() {
+        let original = test_image();
+        let mut edited = Vec::new();
+
+        edit_headers(Cursor::new(&original), &mut edited, |headers| {
+            headers[0].own_attributes.owner =
+                Some(Text::from("a very long owner name to grow the header"));
+            Ok(())
+        })
+        .unwrap();
+
+        assert!(edited.len() > original.len());
+
+        let before = read_image(&original);
+        let after = read_image(&edited);
+        assert_eq!(
+            after.layer_data[0].attributes.owner,
+            Some(Text::from("a very long owner name to grow the header"))
+        );
+        assert_eq!(
+            before.layer_data[0].channel_data,
+            after.layer_data[0].channel_data
+        );
+    }
+
+    #[test]
+    fn edit_in_place_and_rewrite_file() {
+        let original = test_image();
+        let path = std::env::temp_dir().join("vfx_exr_edit_headers_test.exr");
+        std::fs::write(&path, &original).unwrap();
+
+        let update = edit_headers_in_file(&path, |headers| {
+            headers[0].own_attributes.comments = Some(Text::from(
+                "a comment that does not fit into the old header",
+            ));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(update, HeaderUpdate::Rewritten);
+
+        // shorter headers are padded to the old size
+        let update = edit_headers_in_file(&path, |headers| {
+            headers[0].own_attributes.comments = Some(Text::from("short"));
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(update, HeaderUpdate::InPlace);
+
+        let edited = std::fs::read(&path).unwrap();
+        let image = read_image(&edited);
+        assert_eq!(
+            image.layer_data[0].attributes.comments,
+            Some(Text::from("short"))
+        );
+        assert_eq!(
+            image.layer_data[0].channel_data,
+            read_image(&original).layer_data[0].channel_data
+        );
+
+        // layout changes are rejected
+        let result = edit_headers_in_file(&path, |headers| {
+            headers[0].compression = Compression::ZIP16;
+            Ok(())
+        });
+        assert!(result.is_err());
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -1305,6 +1305,169 @@ impl Header {
     }
 }
 
+impl Header {
+    /// Set an attribute by its name, as it would be read from a file.
+    /// Standard attributes are stored in their corresponding field and must have their standard type.
+    /// Attributes that describe how the pixels are stored, like `channels` or `dataWindow`, cannot be set.
+    pub fn set_attribute(&mut self, name: Text, value: AttributeValue) -> UnitResult {
+        use crate::meta::attribute::AttributeValue::*;
+        use crate::meta::header::standard_names as name;
+
+        let layer = &mut self.own_attributes;
+        let image = &mut self.shared_attributes;
+
+        match (name.as_slice(), value) {
+            (slice, _) if name::PIXEL_LAYOUT.contains(&slice) => {
+                return Err(Error::invalid(format!(
+                    "attribute `{}` describes the pixel layout and cannot be set",
+                    name
+                )))
+            }
+
+            (name::NAME, Text(value)) => layer.layer_name = Some(value),
+            (name::WINDOW_CENTER, FloatVec2(value)) => layer.screen_window_center = value,
+            (name::WINDOW_WIDTH, F32(value)) => layer.screen_window_width = value,
+            (name::WHITE_LUMINANCE, F32(value)) => layer.white_luminance = Some(value),
+            (name::ADOPTED_NEUTRAL, FloatVec2(value)) => layer.adopted_neutral = Some(value),
+            (name::RENDERING_TRANSFORM, Text(value)) => {
+                layer.rendering_transform_name = Some(value)
+            }
+            (name::LOOK_MOD_TRANSFORM, Text(value)) => {
+                layer.look_modification_transform_name = Some(value)
+            }
+            (name::X_DENSITY, F32(value)) => layer.horizontal_density = Some(value),
+            (name::OWNER, Text(value)) => layer.owner = Some(value),
+            (name::COMMENTS, Text(value)) => layer.comments = Some(value),
+            (name::CAPTURE_DATE, Text(value)) => layer.capture_date = Some(value),
+            (name::UTC_OFFSET, F32(value)) => layer.utc_offset = Some(value),
+            (name::LONGITUDE, F32(value)) => layer.longitude = Some(value),
+            (name::LATITUDE, F32(value)) => layer.latitude = Some(value),
+            (name::ALTITUDE, F32(value)) => layer.altitude = Some(value),
+            (name::FOCUS, F32(value)) => layer.focus = Some(value),
+            (name::EXPOSURE_TIME, F32(value)) => layer.exposure = Some(value),
+            (name::APERTURE, F32(value)) => layer.aperture = Some(value),
+            (name::ISO_SPEED, F32(value)) => layer.iso_speed = Some(value),
+            (name::ENVIRONMENT_MAP, EnvironmentMap(value)) => layer.environment_map = Some(value),
+            (name::KEY_CODE, KeyCode(value)) => layer.film_key_code = Some(value),
+            (name::WRAP_MODES, Text(value)) => layer.wrap_mode_name = Some(value),
+            (name::FRAMES_PER_SECOND, Rational(value)) => layer.frames_per_second = Some(value),
+            (name::MULTI_VIEW, TextVector(value)) => layer.multi_view_names = Some(value),
+            (name::WORLD_TO_CAMERA, Matrix4x4(value)) => layer.world_to_camera = Some(value),
+            (name::WORLD_TO_NDC, Matrix4x4(value)) => {
+                layer.world_to_normalized_device = Some(value)
+            }
+            (name::DEEP_IMAGE_STATE, Rational(value)) => layer.deep_image_state = Some(value),
+            (name::ORIGINAL_DATA_WINDOW, IntegerBounds(value)) => {
+                layer.original_data_window = Some(value)
+            }
+            (name::PREVIEW, Preview(value)) => layer.preview = Some(value),
+            (name::VIEW, Text(value)) => layer.view_name = Some(value),
+            (name::NEAR, F32(value)) => layer.near_clip_plane = Some(value),
+            (name::FAR, F32(value)) => layer.far_clip_plane = Some(value),
+            (name::FOV_X, F32(value)) => layer.horizontal_field_of_view = Some(value),
+            (name::FOV_Y, F32(value)) => layer.vertical_field_of_view = Some(value),
+            (name::SOFTWARE, Text(value)) => layer.software_name = Some(value),
+
+            (name::DISPLAY_WINDOW, IntegerBounds(value)) => image.display_window = value,
+            (name::PIXEL_ASPECT, F32(value)) => image.pixel_aspect = value,
+            (name::TIME_CODE, TimeCode(value)) => image.time_code = Some(value),
+            (name::CHROMATICITIES, Chromaticities(value)) => image.chromaticities = Some(value),
+
+            (slice, _) if name::ALL.contains(&slice) => {
+                return Err(Error::invalid(format!(
+                    "attribute `{}` has an unexpected type",
+                    name
+                )))
+            }
+
+            // like when reading, these must be the same for all headers
+            (_, value @ Chromaticities(_)) | (_, value @ TimeCode(_)) => {
+                layer.other.remove(&name);
+                image.other.insert(name, value);
+            }
+
+            (_, value) => {
+                image.other.remove(&name);
+                layer.other.insert(name, value);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Remove an attribute by its name. Returns whether the attribute was present.
+    /// Standard attributes without an optional value are reset to their default.
+    /// Attributes that describe how the pixels are stored cannot be removed.
+    pub fn remove_attribute(&mut self, name: &TextSlice) -> Result<bool> {
+        use crate::meta::header::standard_names as name;
+
+        let layer = &mut self.own_attributes;
+        let image = &mut self.shared_attributes;
+
+        let removed = match name {
+            slice if name::PIXEL_LAYOUT.contains(&slice) || slice == name::DISPLAY_WINDOW => {
+                return Err(Error::invalid(format!(
+                    "attribute `{}` is required and cannot be removed",
+                    Text::from_slice_unchecked(name)
+                )))
+            }
+
+            name::NAME => layer.layer_name.take().is_some(),
+            name::WINDOW_CENTER => {
+                layer.screen_window_center = LayerAttributes::default().screen_window_center;
+                true
+            }
+            name::WINDOW_WIDTH => {
+                layer.screen_window_width = LayerAttributes::default().screen_window_width;
+                true
+            }
+            name::WHITE_LUMINANCE => layer.white_luminance.take().is_some(),
+            name::ADOPTED_NEUTRAL => layer.adopted_neutral.take().is_some(),
+            name::RENDERING_TRANSFORM => layer.rendering_transform_name.take().is_some(),
+            name::LOOK_MOD_TRANSFORM => layer.look_modification_transform_name.take().is_some(),
+            name::X_DENSITY => layer.horizontal_density.take().is_some(),
+            name::OWNER => layer.owner.take().is_some(),
+            name::COMMENTS => layer.comments.take().is_some(),
+            name::CAPTURE_DATE => layer.capture_date.take().is_some(),
+            name::UTC_OFFSET => layer.utc_offset.take().is_some(),
+            name::LONGITUDE => layer.longitude.take().is_some(),
+            name::LATITUDE => layer.latitude.take().is_some(),
+            name::ALTITUDE => layer.altitude.take().is_some(),
+            name::FOCUS => layer.focus.take().is_some(),
+            name::EXPOSURE_TIME => layer.exposure.take().is_some(),
+            name::APERTURE => layer.aperture.take().is_some(),
+            name::ISO_SPEED => layer.iso_speed.take().is_some(),
+            name::ENVIRONMENT_MAP => layer.environment_map.take().is_some(),
+            name::KEY_CODE => layer.film_key_code.take().is_some(),
+            name::WRAP_MODES => layer.wrap_mode_name.take().is_some(),
+            name::FRAMES_PER_SECOND => layer.frames_per_second.take().is_some(),
+            name::MULTI_VIEW => layer.multi_view_names.take().is_some(),
+            name::WORLD_TO_CAMERA => layer.world_to_camera.take().is_some(),
+            name::WORLD_TO_NDC => layer.world_to_normalized_device.take().is_some(),
+            name::DEEP_IMAGE_STATE => layer.deep_image_state.take().is_some(),
+            name::ORIGINAL_DATA_WINDOW => layer.original_data_window.take().is_some(),
+            name::PREVIEW => layer.preview.take().is_some(),
+            name::VIEW => layer.view_name.take().is_some(),
+            name::NEAR => layer.near_clip_plane.take().is_some(),
+            name::FAR => layer.far_clip_plane.take().is_some(),
+            name::FOV_X => layer.horizontal_field_of_view.take().is_some(),
+            name::FOV_Y => layer.vertical_field_of_view.take().is_some(),
+            name::SOFTWARE => layer.software_name.take().is_some(),
+
+            name::PIXEL_ASPECT => {
+                image.pixel_aspect = 1.0;
+                true
+            }
+            name::TIME_CODE => image.time_code.take().is_some(),
+            name::CHROMATICITIES => image.chromaticities.take().is_some(),
+
+            _ => layer.other.remove(name).is_some() | image.other.remove(name).is_some(),
+        };
+
+        Ok(removed)
+    }
+}
+
 /// Collection of required attribute names.
 pub mod standard_names {
     macro_rules! define_required_attribute_names {
@@ -1373,6 +1536,20 @@ pub mod standard_names {
         FOV_Y: b"fieldOfViewVertical",
         SOFTWARE: b"software"
     }
+
+    /// The names of the attributes that describe how the pixels are stored in the file.
+    pub const PIXEL_LAYOUT: &[&[u8]] = &[
+        TILES,
+        BLOCK_TYPE,
+        DEEP_DATA_VERSION,
+        CHUNKS,
+        MAX_SAMPLES,
+        CHANNELS,
+        COMPRESSION,
+        DATA_WINDOW,
+        LINE_ORDER,
+        DWA_COMPRESSION_LEVEL,
+    ];
 }
 
 impl Default for LayerAttributes {
@@ -1495,6 +1672,26 @@ mod tests {
         }
     }
 
+    #[test]
+    fn set_and_remove_attributes() {
+        let mut header = make_test_header(8, 8, BlockDescription::ScanLines);
+
+        header.set_attribute(Text::from("owner"), AttributeValue::Text(Text::from("comp"))).unwrap();
+        header.set_attribute(Text::from("shot"), AttributeValue::Text(Text::from("sh010"))).unwrap();
+        assert_eq!(header.own_attributes.owner, Some(Text::from("comp")));
+        assert!(header.own_attributes.other.contains_key(&Text::from("shot")));
+
+        // standard attributes need their standard type, layout attributes are protected
+        assert!(header.set_attribute(Text::from("owner"), AttributeValue::F32(1.0)).is_err());
+        assert!(header.set_attribute(Text::from("compression"), AttributeValue::Compression(Compression::ZIP1)).is_err());
+
+        assert!(header.remove_attribute(b"owner").unwrap());
+        assert!(header.remove_attribute(b"shot").unwrap());
+        assert!(!header.remove_attribute(b"shot").unwrap());
+        assert_eq!(header.own_attributes.owner, None);
+        assert!(header.remove_attribute(b"dataWindow").is_err());
+    }
+
     // Tests for ordered_block_indices() - see DEAD_CODE_ANALYSIS.md item #5
     mod ordered_block_indices_tests {
         use super::*;
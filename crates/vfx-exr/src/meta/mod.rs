@@ -3,6 +3,7 @@
 //! Browse the `vfx_exr::image` module to get started with the high-level interface.
 
 pub mod attribute;
+pub mod edit;
 pub mod header;
 
 use self::attribute::*;
@@ -504,7 +505,8 @@ impl MetaData {
             // start as low as possible, later increasing if required
             has_long_names: false,
 
-            is_single_layer_and_tiled: !is_multilayer && first_header_has_tiles,
+            // deep tiles are identified by the header type attribute, not the single tile flag
+            is_single_layer_and_tiled: !is_multilayer && first_header_has_tiles && !deep,
             has_multiple_layers: is_multilayer,
             has_deep_data: deep,
         };
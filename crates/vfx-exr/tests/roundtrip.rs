@@ -78,11 +78,18 @@ fn round_trip_rgba_file(path: &Path, file: &[u8]) -> Result<()> {
     // these files are known to be invalid, because they do not contain any rgb channels
     let blacklist = [
         Path::new("../../test/assets-exr/valid/openexr/LuminanceChroma/Garden.exr"),
+        Path::new("../../test/assets-exr/valid/openexr/LuminanceChroma/CrissyField.exr"),
+        Path::new("../../test/assets-exr/valid/openexr/LuminanceChroma/Flowers.exr"),
+        Path::new("../../test/assets-exr/valid/openexr/LuminanceChroma/MtTamNorth.exr"),
+        Path::new("../../test/assets-exr/valid/openexr/LuminanceChroma/StarField.exr"),
+        Path::new("../../test/assets-exr/valid/openexr/Chromaticities/Rec709_YC.exr"),
+        Path::new("../../test/assets-exr/valid/openexr/Chromaticities/XYZ_YC.exr"),
         Path::new("../../test/assets-exr/valid/openexr/MultiView/Fog.exr"),
         Path::new("../../test/assets-exr/valid/openexr/TestImages/GrayRampsDiagonal.exr"),
         Path::new("../../test/assets-exr/valid/openexr/TestImages/GrayRampsHorizontal.exr"),
         Path::new("../../test/assets-exr/valid/openexr/TestImages/WideFloatRange.exr"),
         Path::new("../../test/assets-exr/valid/openexr/IlmfmlmflmTest/v1.7.test.tiled.exr"),
+        Path::new("../../test/assets-exr/valid/openexr/IlmfmlmflmTest/comp_b44.exr"),
     ];
 
     if blacklist.contains(&path) {
@@ -370,6 +377,40 @@ fn roundtrip_b44a() {
     test_mixed_roundtrip_with_compression(Compression::B44A)
 }
 
+#[test]
+fn roundtrip_dwaa() {
+    test_mixed_roundtrip_with_compression(Compression::DWAA(None))
+}
+
+#[test]
+fn roundtrip_dwab() {
+    test_mixed_roundtrip_with_compression(Compression::DWAB(Some(90.0)))
+}
+
+#[test]
+fn htj2k_is_reported_as_unsupported() {
+    for compression in [Compression::HTJ2K32, Compression::HTJ2K256] {
+        let image = Image::from_encoded_channels(
+            (2, 2),
+            Encoding {
+                compression,
+                ..Encoding::default()
+            },
+            SpecificChannels::rgb(PixelVec::new(
+                Vec2(2, 2),
+                vec![(0.5_f32, 0.25_f32, 1.0_f32); 4],
+            )),
+        );
+
+        let result = image.write().to_buffered(Cursor::new(Vec::new()));
+        assert!(
+            matches!(result, Err(Error::NotSupported(_))),
+            "{} should not be writable yet",
+            compression
+        );
+    }
+}
+
 #[test]
 fn roundtrip_piz() {
     test_mixed_roundtrip_with_compression(Compression::PIZ)
@@ -380,6 +421,109 @@ fn roundtrip_uncompressed() {
     test_mixed_roundtrip_with_compression(Compression::Uncompressed)
 }
 
+#[test]
+fn roundtrip_subsampled_luminance_chroma() {
+    let size = Vec2(8, 6);
+    let chroma_size = size / Vec2(2, 2);
+
+    let luminance: Vec<f16> = (0..size.area())
+        .map(|index| f16::from_f32(index as f32 * 0.25))
+        .collect();
+    let chroma = |offset: f32| -> Vec<f16> {
+        (0..chroma_size.area())
+            .map(|index| f16::from_f32(index as f32 * 0.125 - offset))
+            .collect()
+    };
+
+    let subsampled = |name: &str, samples: Vec<f16>| AnyChannel {
+        name: name.into(),
+        sample_data: FlatSamples::F16(samples),
+        quantize_linearly: true,
+        sampling: Vec2(2, 2),
+    };
+
+    let channels = AnyChannels::sort(smallvec::smallvec![
+        subsampled("BY", chroma(1.0)),
+        subsampled("RY", chroma(0.5)),
+        AnyChannel::new("Y", FlatSamples::F16(luminance)),
+    ]);
+
+    for &compression in &[
+        Compression::Uncompressed,
+        Compression::RLE,
+        Compression::ZIP1,
+        Compression::ZIP16,
+        Compression::PIZ,
+        Compression::PXR24,
+        Compression::B44,
+        Compression::B44A,
+        Compression::DWAA(None),
+    ] {
+        let image = Image::from_encoded_channels(
+            size,
+            Encoding {
+                compression,
+                blocks: Blocks::ScanLines, // subsampling is not allowed in tiled images
+                line_order: LineOrder::Increasing,
+            },
+            channels.clone(),
+        );
+
+        let mut file_bytes = Vec::new();
+        image
+            .write()
+            .non_parallel()
+            .to_buffered(Cursor::new(&mut file_bytes))
+            .unwrap();
+
+        let image2 = read()
+            .no_deep_data()
+            .largest_resolution_level()
+            .all_channels()
+            .first_valid_layer()
+            .all_attributes()
+            .non_parallel()
+            .from_buffered(Cursor::new(&file_bytes))
+            .unwrap();
+
+        let list = &image2.layer_data.channel_data.list;
+        assert_eq!(list[0].sampling, Vec2(2, 2), "{}", compression);
+        assert_eq!(list[0].sample_data.len(), chroma_size.area(), "{}", compression);
+        assert_eq!(list[2].sample_data.len(), size.area(), "{}", compression);
+
+        // the ramps are too steep for the lossy methods to be compared with a small tolerance
+        if !compression.may_loose_data() {
+            image.assert_equals_result(&image2);
+        }
+    }
+}
+
+#[test]
+fn read_subsampled_luminance_chroma_files() {
+    for path in &[
+        "../../test/assets-exr/valid/openexr/LuminanceChroma/Flowers.exr", // b44
+        "../../test/assets-exr/valid/openexr/LuminanceChroma/MtTamNorth.exr", // piz
+    ] {
+        let image = read_all_flat_layers_from_file(path).unwrap();
+        let layer = &image.layer_data[0];
+        let channels = &layer.channel_data.list;
+
+        let names: Vec<String> = channels.iter().map(|c| c.name.to_string()).collect();
+        assert_eq!(names, ["BY", "RY", "Y"], "{}", path);
+
+        let chroma_size = layer.size / Vec2(2, 2);
+        assert_eq!(channels[0].sampling, Vec2(2, 2));
+        assert_eq!(channels[0].sample_data.len(), chroma_size.area());
+        assert_eq!(channels[1].sample_data.len(), chroma_size.area());
+        assert_eq!(channels[2].sample_data.len(), layer.size.area());
+
+        // the luminance of a photograph should never be completely dark or negative
+        let luminance: Vec<f32> = channels[2].sample_data.values_as_f32().collect();
+        assert!(luminance.iter().all(|y| y.is_finite() && *y >= 0.0), "{}", path);
+        assert!(luminance.iter().sum::<f32>() > 0.0, "{}", path);
+    }
+}
+
 fn test_mixed_roundtrip_with_compression(compression: Compression) {
     let original_pixels: [(f16, f32, f32); 4] = [
         (0.0.to_f16(), -1.1, std::f32::consts::PI),
@@ -0,0 +1,711 @@
+//! EXIF metadata parsing and serialization.
+//!
+//! EXIF data is a small TIFF structure: a header, IFD0 with the image tags,
+//! and pointers to the Exif and GPS sub-IFDs. JPEG stores it in an APP1
+//! segment, PNG in an `eXIf` chunk, HEIF in an `Exif` item, and TIFF files
+//! carry the same IFDs directly.
+//!
+//! # Attribute Names
+//!
+//! Tags follow the naming used by the other readers:
+//!
+//! | IFD | Key | Example |
+//! |-----|-----|---------|
+//! | IFD0 | plain TIFF name | `Make`, `Model`, `Orientation`, `DateTime` |
+//! | Exif | `Exif:` prefix | `Exif:ExposureTime`, `Exif:FNumber`, `Exif:ISOSpeedRatings` |
+//! | GPS | `GPS:` prefix | `GPS:Latitude`, `GPS:LatitudeRef`, `GPS:Altitude` |
+//!
+//! Values keep their EXIF types: rationals become [`AttrValue::URational`]
+//! or [`AttrValue::Rational`], counts of one become scalars and longer
+//! arrays become lists. `GPS:Latitude` and `GPS:Longitude` are decimal
+//! degrees ([`AttrValue::Double`], unsigned; the hemisphere is in the `Ref`
+//! tag). Only the tags in the table below are read and written; MakerNotes,
+//! thumbnails and resolution tags (owned by the format readers) are skipped.
+//!
+//! When writing, rational tags also accept floats, so
+//! `attrs.set("Exif:FNumber", AttrValue::Float(2.8))` works.
+//!
+//! # Example
+//!
+//! ```rust
+//! use vfx_io::attrs::{exif, Attrs, AttrValue};
+//!
+//! let mut attrs = Attrs::new();
+//! attrs.set("Make", AttrValue::Str("Canon".into()));
+//! attrs.set("Exif:ExposureTime", AttrValue::URational(1, 125));
+//!
+//! let blob = exif::write_exif(&attrs).unwrap();
+//! let mut parsed = Attrs::new();
+//! exif::read_exif(&blob, &mut parsed).unwrap();
+//! assert_eq!(parsed.get_str("Make"), Some("Canon"));
+//! assert_eq!(parsed.get_urational("Exif:ExposureTime"), Some((1, 125)));
+//! ```
+
+use super::{AttrValue, Attrs};
+use crate::{IoError, IoResult};
+
+// ============================================================================
+// Tag Table
+// ============================================================================
+
+/// The IFD a tag lives in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Group {
+    /// IFD0 (main image tags).
+    Image,
+    /// Exif sub-IFD.
+    Exif,
+    /// GPS sub-IFD.
+    Gps,
+}
+
+/// How a tag is stored, and how it maps to an [`AttrValue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Byte,
+    Ascii,
+    Short,
+    Rational,
+    SRational,
+    /// Four ASCII digits stored as UNDEFINED (`ExifVersion`).
+    Version,
+    /// UNDEFINED with an 8-byte character code prefix (`UserComment`).
+    Comment,
+    /// Three rationals (degrees, minutes, seconds) as decimal degrees.
+    Degrees,
+}
+
+/// Pointer to the Exif sub-IFD.
+pub(crate) const EXIF_IFD: u16 = 0x8769;
+/// Pointer to the GPS sub-IFD.
+pub(crate) const GPS_IFD: u16 = 0x8825;
+
+/// Supported tags, ascending within each group.
+const TAGS: &[(Group, u16, &str, Kind)] = &[
+    (Group::Image, 270, "ImageDescription", Kind::Ascii),
+    (Group::Image, 271, "Make", Kind::Ascii),
+    (Group::Image, 272, "Model", Kind::Ascii),
+    (Group::Image, 274, "Orientation", Kind::Short),
+    (Group::Image, 305, "Software", Kind::Ascii),
+    (Group::Image, 306, "DateTime", Kind::Ascii),
+    (Group::Image, 315, "Artist", Kind::Ascii),
+    (Group::Image, 33432, "Copyright", Kind::Ascii),
+    (Group::Exif, 33434, "ExposureTime", Kind::Rational),
+    (Group::Exif, 33437, "FNumber", Kind::Rational),
+    (Group::Exif, 34850, "ExposureProgram", Kind::Short),
+    (Group::Exif, 34855, "ISOSpeedRatings", Kind::Short),
+    (Group::Exif, 36864, "ExifVersion", Kind::Version),
+    (Group::Exif, 36867, "DateTimeOriginal", Kind::Ascii),
+    (Group::Exif, 36868, "DateTimeDigitized", Kind::Ascii),
+    (Group::Exif, 36880, "OffsetTime", Kind::Ascii),
+    (Group::Exif, 36881, "OffsetTimeOriginal", Kind::Ascii),
+    (Group::Exif, 37377, "ShutterSpeedValue", Kind::SRational),
+    (Group::Exif, 37378, "ApertureValue", Kind::Rational),
+    (Group::Exif, 37379, "BrightnessValue", Kind::SRational),
+    (Group::Exif, 37380, "ExposureBiasValue", Kind::SRational),
+    (Group::Exif, 37381, "MaxApertureValue", Kind::Rational),
+    (Group::Exif, 37382, "SubjectDistance", Kind::Rational),
+    (Group::Exif, 37383, "MeteringMode", Kind::Short),
+    (Group::Exif, 37384, "LightSource", Kind::Short),
+    (Group::Exif, 37385, "Flash", Kind::Short),
+    (Group::Exif, 37386, "FocalLength", Kind::Rational),
+    (Group::Exif, 37510, "UserComment", Kind::Comment),
+    (Group::Exif, 37521, "SubSecTimeOriginal", Kind::Ascii),
+    (Group::Exif, 40960, "FlashpixVersion", Kind::Version),
+    (Group::Exif, 40961, "ColorSpace", Kind::Short),
+    (Group::Exif, 41486, "FocalPlaneXResolution", Kind::Rational),
+    (Group::Exif, 41487, "FocalPlaneYResolution", Kind::Rational),
+    (Group::Exif, 41488, "FocalPlaneResolutionUnit", Kind::Short),
+    (Group::Exif, 41495, "SensingMethod", Kind::Short),
+    (Group::Exif, 41985, "CustomRendered", Kind::Short),
+    (Group::Exif, 41986, "ExposureMode", Kind::Short),
+    (Group::Exif, 41987, "WhiteBalance", Kind::Short),
+    (Group::Exif, 41988, "DigitalZoomRatio", Kind::Rational),
+    (Group::Exif, 41989, "FocalLengthIn35mmFilm", Kind::Short),
+    (Group::Exif, 41990, "SceneCaptureType", Kind::Short),
+    (Group::Exif, 42016, "ImageUniqueID", Kind::Ascii),
+    (Group::Exif, 42032, "CameraOwnerName", Kind::Ascii),
+    (Group::Exif, 42033, "BodySerialNumber", Kind::Ascii),
+    (Group::Exif, 42034, "LensSpecification", Kind::Rational),
+    (Group::Exif, 42035, "LensMake", Kind::Ascii),
+    (Group::Exif, 42036, "LensModel", Kind::Ascii),
+    (Group::Exif, 42037, "LensSerialNumber", Kind::Ascii),
+    (Group::Gps, 0, "VersionID", Kind::Byte),
+    (Group::Gps, 1, "LatitudeRef", Kind::Ascii),
+    (Group::Gps, 2, "Latitude", Kind::Degrees),
+    (Group::Gps, 3, "LongitudeRef", Kind::Ascii),
+    (Group::Gps, 4, "Longitude", Kind::Degrees),
+    (Group::Gps, 5, "AltitudeRef", Kind::Byte),
+    (Group::Gps, 6, "Altitude", Kind::Rational),
+    (Group::Gps, 7, "TimeStamp", Kind::Rational),
+    (Group::Gps, 8, "Satellites", Kind::Ascii),
+    (Group::Gps, 12, "SpeedRef", Kind::Ascii),
+    (Group::Gps, 13, "Speed", Kind::Rational),
+    (Group::Gps, 16, "ImgDirectionRef", Kind::Ascii),
+    (Group::Gps, 17, "ImgDirection", Kind::Rational),
+    (Group::Gps, 18, "MapDatum", Kind::Ascii),
+    (Group::Gps, 29, "DateStamp", Kind::Ascii),
+];
+
+/// Attribute key for a tag name.
+fn key(group: Group, name: &str) -> String {
+    match group {
+        Group::Image => name.to_string(),
+        Group::Exif => format!("Exif:{}", name),
+        Group::Gps => format!("GPS:{}", name),
+    }
+}
+
+/// Tag numbers of a group, for readers that look tags up one by one.
+pub(crate) fn tags(group: Group) -> impl Iterator<Item = u16> {
+    TAGS.iter().filter(move |t| t.0 == group).map(|t| t.1)
+}
+
+// ============================================================================
+// Field Values
+// ============================================================================
+
+/// A raw IFD field value.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Field {
+    Byte(Vec<u8>),
+    Ascii(String),
+    Short(Vec<u16>),
+    Long(Vec<u32>),
+    Rational(Vec<(u32, u32)>),
+    Undefined(Vec<u8>),
+    SRational(Vec<(i32, i32)>),
+}
+
+impl Field {
+    /// TIFF field type code.
+    fn type_code(&self) -> u16 {
+        match self {
+            Field::Byte(_) => 1,
+            Field::Ascii(_) => 2,
+            Field::Short(_) => 3,
+            Field::Long(_) => 4,
+            Field::Rational(_) => 5,
+            Field::Undefined(_) => 7,
+            Field::SRational(_) => 10,
+        }
+    }
+
+    /// Value count and little-endian bytes.
+    fn encode(&self) -> (u32, Vec<u8>) {
+        let bytes: Vec<u8> = match self {
+            Field::Byte(v) | Field::Undefined(v) => v.clone(),
+            Field::Ascii(s) => s.bytes().chain([0]).collect(),
+            Field::Short(v) => v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            Field::Long(v) => v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            Field::Rational(v) => v
+                .iter()
+                .flat_map(|&(n, d)| [n.to_le_bytes(), d.to_le_bytes()].concat())
+                .collect(),
+            Field::SRational(v) => v
+                .iter()
+                .flat_map(|&(n, d)| [n.to_le_bytes(), d.to_le_bytes()].concat())
+                .collect(),
+        };
+        let size = match self {
+            Field::Short(_) => 2,
+            Field::Long(_) => 4,
+            Field::Rational(_) | Field::SRational(_) => 8,
+            _ => 1,
+        };
+        ((bytes.len() / size) as u32, bytes)
+    }
+}
+
+/// Returns a single value as a scalar, several as a list.
+fn scalar_or_list(mut values: Vec<AttrValue>) -> Option<AttrValue> {
+    match values.len() {
+        0 => None,
+        1 => values.pop(),
+        _ => Some(AttrValue::List(values)),
+    }
+}
+
+/// Converts a field to its attribute value.
+fn to_attr(kind: Kind, field: &Field) -> Option<AttrValue> {
+    // Some TIFF decoders widen short BYTE arrays to LONG
+    let widened;
+    let field = match (kind, field) {
+        (Kind::Byte | Kind::Version | Kind::Comment, Field::Long(v))
+            if v.iter().all(|&x| x <= 0xFF) =>
+        {
+            widened = Field::Byte(v.iter().map(|&x| x as u8).collect());
+            &widened
+        }
+        _ => field,
+    };
+    match (kind, field) {
+        (Kind::Degrees, Field::Rational(v)) if v.len() == 3 => {
+            let part = |(n, d): (u32, u32)| if d == 0 { 0.0 } else { n as f64 / d as f64 };
+            let degrees = part(v[0]) + part(v[1]) / 60.0 + part(v[2]) / 3600.0;
+            Some(AttrValue::Double(degrees))
+        }
+        (Kind::Version, Field::Undefined(b) | Field::Byte(b)) => {
+            std::str::from_utf8(b).ok().map(|s| AttrValue::Str(s.to_string()))
+        }
+        (Kind::Comment, Field::Undefined(b) | Field::Byte(b)) if b.len() >= 8 => {
+            // Only ASCII and unspecified character codes are decoded
+            let (code, text) = b.split_at(8);
+            if code.starts_with(b"ASCII") || code.iter().all(|&c| c == 0) {
+                let text = String::from_utf8_lossy(text);
+                let text = text.trim_end_matches(['\0', ' ']);
+                Some(AttrValue::Str(text.to_string()))
+            } else {
+                Some(AttrValue::Bytes(b.clone()))
+            }
+        }
+        (_, Field::Ascii(s)) => Some(AttrValue::Str(s.clone())),
+        (_, Field::Byte(b) | Field::Undefined(b)) => match b.as_slice() {
+            [] => None,
+            [v] => Some(AttrValue::UInt(*v as u32)),
+            _ => Some(AttrValue::Bytes(b.clone())),
+        },
+        (_, Field::Short(v)) => {
+            scalar_or_list(v.iter().map(|&x| AttrValue::UInt(x as u32)).collect())
+        }
+        (_, Field::Long(v)) => scalar_or_list(v.iter().map(|&x| AttrValue::UInt(x)).collect()),
+        (_, Field::Rational(v)) => {
+            scalar_or_list(v.iter().map(|&(n, d)| AttrValue::URational(n, d)).collect())
+        }
+        (_, Field::SRational(v)) => {
+            scalar_or_list(v.iter().map(|&(n, d)| AttrValue::Rational(n, d)).collect())
+        }
+    }
+}
+
+/// Flattens a scalar or list attribute into its elements.
+fn elements(value: &AttrValue) -> Vec<&AttrValue> {
+    match value {
+        AttrValue::List(items) => items.iter().collect(),
+        v => vec![v],
+    }
+}
+
+/// Unsigned integer elements of an attribute.
+fn uints(value: &AttrValue) -> Option<Vec<u32>> {
+    elements(value)
+        .into_iter()
+        .map(|v| match v {
+            AttrValue::UInt(x) => Some(*x),
+            AttrValue::Int(x) => u32::try_from(*x).ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Approximates a non-negative value as an unsigned rational.
+fn to_urational(v: f64) -> Option<(u32, u32)> {
+    if !v.is_finite() || v < 0.0 || v > u32::MAX as f64 {
+        return None;
+    }
+    if v.fract() == 0.0 {
+        return Some((v as u32, 1));
+    }
+    // Exposure times are conventionally 1/N
+    let inverse = 1.0 / v;
+    if v < 1.0 && (inverse - inverse.round()).abs() < 1e-6 {
+        return Some((1, inverse.round() as u32));
+    }
+    let scale = if v < 4.0e5 { 10_000 } else { 1 };
+    let n = (v * scale as f64).round() as u32;
+    let g = gcd(n, scale);
+    Some((n / g, scale / g))
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a.max(1) } else { gcd(b, a % b) }
+}
+
+/// Approximates a value as a signed rational.
+fn to_srational(v: f64) -> Option<(i32, i32)> {
+    if !v.is_finite() || v.abs() > i32::MAX as f64 / 10_000.0 {
+        return None;
+    }
+    if v.fract() == 0.0 {
+        return Some((v as i32, 1));
+    }
+    let n = (v * 10_000.0).round() as i32;
+    let g = gcd(n.unsigned_abs(), 10_000) as i32;
+    Some((n / g, 10_000 / g))
+}
+
+/// Converts an attribute value to a field of the tag's kind.
+fn to_field(kind: Kind, value: &AttrValue) -> Option<Field> {
+    match kind {
+        Kind::Ascii => value.as_str().map(|s| Field::Ascii(s.to_string())),
+        Kind::Short => uints(value)?
+            .into_iter()
+            .map(|x| u16::try_from(x).ok())
+            .collect::<Option<Vec<_>>>()
+            .map(Field::Short),
+        Kind::Byte => match value {
+            AttrValue::Bytes(b) => Some(Field::Byte(b.clone())),
+            v => uints(v)?
+                .into_iter()
+                .map(|x| u8::try_from(x).ok())
+                .collect::<Option<Vec<_>>>()
+                .map(Field::Byte),
+        },
+        Kind::Rational => elements(value)
+            .into_iter()
+            .map(|v| match v {
+                AttrValue::URational(n, d) => Some((*n, *d)),
+                v => to_urational(v.as_f64()?),
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Field::Rational),
+        Kind::SRational => elements(value)
+            .into_iter()
+            .map(|v| match v {
+                AttrValue::Rational(n, d) => Some((*n, *d)),
+                v => to_srational(v.as_f64()?),
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Field::SRational),
+        Kind::Version => match value.as_str()? {
+            s if s.len() == 4 && s.is_ascii() => Some(Field::Undefined(s.as_bytes().to_vec())),
+            _ => None,
+        },
+        Kind::Comment => match value {
+            AttrValue::Str(s) => {
+                let mut bytes = b"ASCII\0\0\0".to_vec();
+                bytes.extend_from_slice(s.as_bytes());
+                Some(Field::Undefined(bytes))
+            }
+            AttrValue::Bytes(b) => Some(Field::Undefined(b.clone())),
+            _ => None,
+        },
+        Kind::Degrees => match value {
+            AttrValue::List(_) => to_field(Kind::Rational, value),
+            v => {
+                let v = v.as_f64()?.abs();
+                let degrees = v.trunc();
+                let minutes = ((v - degrees) * 60.0).trunc();
+                let seconds = ((v - degrees) * 60.0 - minutes) * 60.0;
+                Some(Field::Rational(vec![
+                    (degrees as u32, 1),
+                    (minutes as u32, 1),
+                    to_urational((seconds * 10_000.0).round() / 10_000.0)?,
+                ]))
+            }
+        },
+    }
+}
+
+/// Stores a field in `attrs` if it is a supported tag.
+pub(crate) fn set_field(attrs: &mut Attrs, group: Group, tag: u16, field: &Field) {
+    let Some(&(_, _, name, kind)) = TAGS.iter().find(|t| t.0 == group && t.1 == tag) else {
+        return;
+    };
+    if let Some(value) = to_attr(kind, field) {
+        attrs.set(key(group, name), value);
+    }
+}
+
+/// Collects the fields of a group present in `attrs`, in tag order.
+///
+/// Adds the mandatory `ExifVersion` / `GPSVersionID` when the group is
+/// otherwise non-empty.
+pub(crate) fn fields(attrs: &Attrs, group: Group) -> Vec<(u16, Field)> {
+    let mut fields: Vec<(u16, Field)> = TAGS
+        .iter()
+        .filter(|t| t.0 == group)
+        .filter_map(|&(_, tag, name, kind)| {
+            let value = attrs.get(&key(group, name))?;
+            Some((tag, to_field(kind, value)?))
+        })
+        .collect();
+
+    let version = match group {
+        Group::Exif => Some((36864, Field::Undefined(b"0232".to_vec()))),
+        Group::Gps => Some((0, Field::Byte(vec![2, 3, 0, 0]))),
+        Group::Image => None,
+    };
+    if let Some((tag, field)) = version {
+        if !fields.is_empty() && !fields.iter().any(|f| f.0 == tag) {
+            fields.push((tag, field));
+        }
+    }
+    fields.sort_by_key(|f| f.0);
+    fields
+}
+
+// ============================================================================
+// Reading
+// ============================================================================
+
+/// Byte-order aware view of a TIFF-structured buffer.
+struct IfdReader<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+impl IfdReader<'_> {
+    fn u16_at(&self, pos: usize) -> Option<u16> {
+        let b: [u8; 2] = self.data.get(pos..pos + 2)?.try_into().ok()?;
+        Some(if self.big_endian { u16::from_be_bytes(b) } else { u16::from_le_bytes(b) })
+    }
+
+    fn u32_at(&self, pos: usize) -> Option<u32> {
+        let b: [u8; 4] = self.data.get(pos..pos + 4)?.try_into().ok()?;
+        Some(if self.big_endian { u32::from_be_bytes(b) } else { u32::from_le_bytes(b) })
+    }
+
+    /// Reads the entries of the IFD at `offset`.
+    fn entries(&self, offset: usize) -> IoResult<Vec<(u16, Field)>> {
+        let truncated = || IoError::InvalidFile("EXIF IFD truncated".into());
+        let count = self.u16_at(offset).ok_or_else(truncated)? as usize;
+        let mut entries = Vec::with_capacity(count);
+        for i in 0..count {
+            let at = offset + 2 + i * 12;
+            let tag = self.u16_at(at).ok_or_else(truncated)?;
+            let field_type = self.u16_at(at + 2).ok_or_else(truncated)?;
+            let n = self.u32_at(at + 4).ok_or_else(truncated)? as usize;
+            let size = match field_type {
+                1 | 2 | 7 => 1,
+                3 => 2,
+                4 | 13 => 4,
+                5 | 10 => 8,
+                // Signed integers and floats are not used by supported tags
+                _ => continue,
+            };
+            let Some(len) = n.checked_mul(size) else { continue };
+            let start = if len <= 4 {
+                at + 8
+            } else {
+                self.u32_at(at + 8).ok_or_else(truncated)? as usize
+            };
+            // Skip entries pointing outside the buffer
+            let Some(b) = self.data.get(start..start.saturating_add(len)) else { continue };
+            let field = match field_type {
+                1 => Field::Byte(b.to_vec()),
+                2 => {
+                    let end = b.iter().position(|&c| c == 0).unwrap_or(b.len());
+                    Field::Ascii(String::from_utf8_lossy(&b[..end]).trim_end().to_string())
+                }
+                3 => Field::Short((0..n).filter_map(|k| self.u16_at(start + k * 2)).collect()),
+                4 | 13 => Field::Long((0..n).filter_map(|k| self.u32_at(start + k * 4)).collect()),
+                5 => Field::Rational(
+                    (0..n)
+                        .filter_map(|k| {
+                            Some((self.u32_at(start + k * 8)?, self.u32_at(start + k * 8 + 4)?))
+                        })
+                        .collect(),
+                ),
+                10 => Field::SRational(
+                    (0..n)
+                        .filter_map(|k| {
+                            let n = self.u32_at(start + k * 8)? as i32;
+                            Some((n, self.u32_at(start + k * 8 + 4)? as i32))
+                        })
+                        .collect(),
+                ),
+                _ => Field::Undefined(b.to_vec()),
+            };
+            entries.push((tag, field));
+        }
+        Ok(entries)
+    }
+}
+
+/// Parses TIFF-structured EXIF data into `attrs`.
+///
+/// `data` starts at the TIFF header (`II*\0` or `MM\0*`), i.e. after the
+/// `Exif\0\0` prefix of a JPEG APP1 segment.
+pub fn read_exif(data: &[u8], attrs: &mut Attrs) -> IoResult<()> {
+    let big_endian = match data.get(..4) {
+        Some([b'I', b'I', 42, 0]) => false,
+        Some([b'M', b'M', 0, 42]) => true,
+        _ => return Err(IoError::InvalidFile("missing EXIF TIFF header".into())),
+    };
+    let reader = IfdReader { data, big_endian };
+    let ifd0 = reader
+        .u32_at(4)
+        .ok_or_else(|| IoError::InvalidFile("EXIF header truncated".into()))?;
+
+    let mut pending = vec![(Group::Image, ifd0 as usize)];
+    let mut visited = Vec::new();
+    while let Some((group, offset)) = pending.pop() {
+        if visited.contains(&offset) {
+            continue;
+        }
+        visited.push(offset);
+        for (tag, field) in reader.entries(offset)? {
+            match (group, tag, &field) {
+                (Group::Image, EXIF_IFD, Field::Long(v)) if !v.is_empty() => {
+                    pending.push((Group::Exif, v[0] as usize));
+                }
+                (Group::Image, GPS_IFD, Field::Long(v)) if !v.is_empty() => {
+                    pending.push((Group::Gps, v[0] as usize));
+                }
+                _ => set_field(attrs, group, tag, &field),
+            }
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Writing
+// ============================================================================
+
+/// Serialized size of an IFD including out-of-line values.
+fn ifd_size(fields: &[(u16, Field)]) -> usize {
+    let data: usize = fields
+        .iter()
+        .map(|(_, f)| f.encode().1.len())
+        .filter(|&len| len > 4)
+        .map(|len| len + len % 2)
+        .sum();
+    2 + fields.len() * 12 + 4 + data
+}
+
+/// Appends an IFD (with a zero next-IFD link) and its values to `out`.
+fn write_ifd(out: &mut Vec<u8>, fields: &[(u16, Field)]) {
+    let mut data_at = out.len() + 2 + fields.len() * 12 + 4;
+    let mut data = Vec::new();
+    out.extend_from_slice(&(fields.len() as u16).to_le_bytes());
+    for (tag, field) in fields {
+        let (count, bytes) = field.encode();
+        out.extend_from_slice(&tag.to_le_bytes());
+        out.extend_from_slice(&field.type_code().to_le_bytes());
+        out.extend_from_slice(&count.to_le_bytes());
+        if bytes.len() <= 4 {
+            let mut inline = [0u8; 4];
+            inline[..bytes.len()].copy_from_slice(&bytes);
+            out.extend_from_slice(&inline);
+        } else {
+            out.extend_from_slice(&(data_at as u32).to_le_bytes());
+            data_at += bytes.len() + bytes.len() % 2;
+            data.extend_from_slice(&bytes);
+            if bytes.len() % 2 == 1 {
+                data.push(0);
+            }
+        }
+    }
+    out.extend_from_slice(&0u32.to_le_bytes());
+    out.extend_from_slice(&data);
+}
+
+/// Serializes the EXIF attributes of `attrs` as little-endian TIFF data.
+///
+/// Returns `None` if `attrs` has no supported EXIF attributes. The result
+/// starts at the TIFF header; JPEG writers prepend `Exif\0\0`.
+pub fn write_exif(attrs: &Attrs) -> Option<Vec<u8>> {
+    let mut ifd0 = fields(attrs, Group::Image);
+    let exif = fields(attrs, Group::Exif);
+    let gps = fields(attrs, Group::Gps);
+    if ifd0.is_empty() && exif.is_empty() && gps.is_empty() {
+        return None;
+    }
+
+    // Pointer values are patched once the IFD0 size is known
+    if !exif.is_empty() {
+        ifd0.push((EXIF_IFD, Field::Long(vec![0])));
+    }
+    if !gps.is_empty() {
+        ifd0.push((GPS_IFD, Field::Long(vec![0])));
+    }
+    ifd0.sort_by_key(|f| f.0);
+    let exif_at = 8 + ifd_size(&ifd0);
+    let gps_at = exif_at + if exif.is_empty() { 0 } else { ifd_size(&exif) };
+    for (tag, field) in &mut ifd0 {
+        match *tag {
+            EXIF_IFD => *field = Field::Long(vec![exif_at as u32]),
+            GPS_IFD => *field = Field::Long(vec![gps_at as u32]),
+            _ => {}
+        }
+    }
+
+    let mut out = b"II*\0".to_vec();
+    out.extend_from_slice(&8u32.to_le_bytes());
+    write_ifd(&mut out, &ifd0);
+    if !exif.is_empty() {
+        write_ifd(&mut out, &exif);
+    }
+    if !gps.is_empty() {
+        write_ifd(&mut out, &gps);
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Attrs {
+        let mut attrs = Attrs::new();
+        attrs.set("Make", AttrValue::Str("Canon".into()));
+        attrs.set("Model", AttrValue::Str("EOS R5".into()));
+        attrs.set("Orientation", AttrValue::UInt(6));
+        attrs.set("Exif:ExposureTime", AttrValue::URational(1, 250));
+        attrs.set("Exif:FNumber", AttrValue::Float(2.8));
+        attrs.set("Exif:ISOSpeedRatings", AttrValue::UInt(400));
+        attrs.set("Exif:FocalLength", AttrValue::URational(35, 1));
+        attrs.set("Exif:ExposureBiasValue", AttrValue::Rational(-2, 3));
+        attrs.set("Exif:DateTimeOriginal", AttrValue::Str("2024:05:01 10:20:30".into()));
+        attrs.set("Exif:UserComment", AttrValue::Str("plate A".into()));
+        attrs.set("GPS:LatitudeRef", AttrValue::Str("N".into()));
+        attrs.set("GPS:Latitude", AttrValue::Double(51.5007));
+        attrs.set("GPS:LongitudeRef", AttrValue::Str("W".into()));
+        attrs.set("GPS:Longitude", AttrValue::Double(0.1246));
+        attrs.set("GPS:Altitude", AttrValue::URational(1234, 100));
+        attrs
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let blob = write_exif(&sample()).unwrap();
+        let mut attrs = Attrs::new();
+        read_exif(&blob, &mut attrs).unwrap();
+
+        assert_eq!(attrs.get_str("Make"), Some("Canon"));
+        assert_eq!(attrs.get_str("Model"), Some("EOS R5"));
+        assert_eq!(attrs.get_u32("Orientation"), Some(6));
+        assert_eq!(attrs.get_urational("Exif:ExposureTime"), Some((1, 250)));
+        assert_eq!(attrs.get_urational("Exif:FNumber"), Some((14, 5)));
+        assert_eq!(attrs.get_u32("Exif:ISOSpeedRatings"), Some(400));
+        assert_eq!(attrs.get_urational("Exif:FocalLength"), Some((35, 1)));
+        assert_eq!(attrs.get_rational("Exif:ExposureBiasValue"), Some((-2, 3)));
+        assert_eq!(attrs.get_str("Exif:DateTimeOriginal"), Some("2024:05:01 10:20:30"));
+        assert_eq!(attrs.get_str("Exif:UserComment"), Some("plate A"));
+        assert_eq!(attrs.get_str("Exif:ExifVersion"), Some("0232"));
+        assert_eq!(attrs.get_str("GPS:LatitudeRef"), Some("N"));
+        assert!((attrs.get_f64("GPS:Latitude").unwrap() - 51.5007).abs() < 1e-6);
+        assert!((attrs.get_f64("GPS:Longitude").unwrap() - 0.1246).abs() < 1e-6);
+        assert_eq!(attrs.get_urational("GPS:Altitude"), Some((1234, 100)));
+        assert_eq!(attrs.get_bytes("GPS:VersionID"), Some(&[2, 3, 0, 0][..]));
+    }
+
+    #[test]
+    fn test_big_endian() {
+        // MM header, IFD0 with Make = "Nikon" (out of line) and Orientation = 3
+        let mut data = b"MM\0*\0\0\0\x08".to_vec();
+        data.extend_from_slice(&[0, 2]);
+        data.extend_from_slice(&[0x01, 0x0F, 0, 2, 0, 0, 0, 6, 0, 0, 0, 38]);
+        data.extend_from_slice(&[0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, 3, 0, 0]);
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        data.extend_from_slice(b"Nikon\0");
+
+        let mut attrs = Attrs::new();
+        read_exif(&data, &mut attrs).unwrap();
+        assert_eq!(attrs.get_str("Make"), Some("Nikon"));
+        assert_eq!(attrs.get_u32("Orientation"), Some(3));
+    }
+
+    #[test]
+    fn test_empty_and_invalid() {
+        let mut attrs = Attrs::new();
+        attrs.set("ImageWidth", AttrValue::UInt(64));
+        assert!(write_exif(&attrs).is_none());
+
+        assert!(read_exif(b"JUNK", &mut attrs).is_err());
+        // IFD offset past the end
+        assert!(read_exif(b"II*\0\xff\0\0\0", &mut attrs).is_err());
+    }
+}
@@ -9,6 +9,8 @@
 //! The attribute system consists of:
 //! - [`AttrValue`] - Typed metadata value (string, int, rational, bytes, etc.)
 //! - [`Attrs`] - Container mapping string keys to typed values
+//! - [`exif`] - EXIF IFD parsing and serialization (`Exif:*`, `GPS:*`)
+//! - [`xmp`] - XMP packet parsing and serialization (`XMP:*`)
 //!
 //! # Example
 //!
@@ -47,6 +49,8 @@
 //! - Full EXIF type support (rationals, bytes, datetime)
 
 mod value;
+pub mod exif;
+pub mod xmp;
 
 pub use value::AttrValue;
 
@@ -0,0 +1,392 @@
+//! XMP (Extensible Metadata Platform) packet parsing and serialization.
+//!
+//! XMP is RDF/XML embedded in JPEG APP1 segments, PNG `iTXt` chunks, TIFF
+//! tag 700 and HEIF `mime` items. Simple properties are stored as
+//! `XMP:<prefix>:<name>` string attributes, using the namespace prefix from
+//! the packet (`XMP:dc:creator`, `XMP:xmp:Rating`, `XMP:photoshop:City`).
+//!
+//! - `rdf:Seq` / `rdf:Bag` arrays become [`AttrValue::List`] of strings
+//! - `rdf:Alt` language alternatives keep the `x-default` entry only
+//! - Nested structures (`rdf:parseType="Resource"`, qualifiers) are skipped
+//!
+//! Prefixes outside the well-known set keep their namespace URI in an
+//! `XMP:xmlns:<prefix>` attribute so they can be written back.
+//!
+//! # Example
+//!
+//! ```rust
+//! use vfx_io::attrs::{xmp, Attrs, AttrValue};
+//!
+//! let mut attrs = Attrs::new();
+//! attrs.set("XMP:xmp:Rating", AttrValue::Str("4".into()));
+//!
+//! let packet = xmp::write_xmp(&attrs).unwrap();
+//! let mut parsed = Attrs::new();
+//! xmp::read_xmp(&packet, &mut parsed).unwrap();
+//! assert_eq!(parsed.get_str("XMP:xmp:Rating"), Some("4"));
+//! ```
+
+use std::collections::BTreeMap;
+
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use super::{AttrValue, Attrs};
+use crate::{IoError, IoResult};
+
+/// Namespace URIs of the common XMP schemas.
+const NAMESPACES: &[(&str, &str)] = &[
+    ("aux", "http://ns.adobe.com/exif/1.0/aux/"),
+    ("crs", "http://ns.adobe.com/camera-raw-settings/1.0/"),
+    ("dc", "http://purl.org/dc/elements/1.1/"),
+    ("exif", "http://ns.adobe.com/exif/1.0/"),
+    ("exifEX", "http://cipa.jp/exif/1.0/"),
+    ("GPano", "http://ns.google.com/photos/1.0/panorama/"),
+    ("Iptc4xmpCore", "http://iptc.org/std/Iptc4xmpCore/1.0/xmlns/"),
+    ("photoshop", "http://ns.adobe.com/photoshop/1.0/"),
+    ("tiff", "http://ns.adobe.com/tiff/1.0/"),
+    ("xmp", "http://ns.adobe.com/xap/1.0/"),
+    ("xmpMM", "http://ns.adobe.com/xap/1.0/mm/"),
+    ("xmpRights", "http://ns.adobe.com/xap/1.0/rights/"),
+];
+
+/// Language alternative properties, written as `rdf:Alt`.
+const ALT_PROPERTIES: &[&str] =
+    &["dc:title", "dc:description", "dc:rights", "xmpRights:UsageTerms"];
+
+/// Unordered array properties, written as `rdf:Bag`.
+const BAG_PROPERTIES: &[&str] = &["dc:subject", "photoshop:SupplementalCategories"];
+
+/// Attribute key prefix for XMP properties.
+const PREFIX: &str = "XMP:";
+
+/// Attribute key prefix for custom namespace URIs.
+const XMLNS_PREFIX: &str = "XMP:xmlns:";
+
+// ============================================================================
+// Reading
+// ============================================================================
+
+/// Qualified element or attribute name as written in the packet.
+fn qname(name: &[u8]) -> String {
+    String::from_utf8_lossy(name).into_owned()
+}
+
+/// Stores the attributes of an `rdf:Description` as simple properties.
+fn description_attrs(e: &BytesStart, attrs: &mut Attrs) -> IoResult<()> {
+    for attr in e.attributes().flatten() {
+        let name = qname(attr.key.as_ref());
+        let value = attr
+            .unescape_value()
+            .map_err(|e| IoError::DecodeError(format!("XMP attribute: {}", e)))?;
+        if let Some(prefix) = name.strip_prefix("xmlns:") {
+            if !NAMESPACES.iter().any(|ns| ns.0 == prefix) {
+                attrs.set(format!("{}{}", XMLNS_PREFIX, prefix), AttrValue::Str(value.into()));
+            }
+        } else if name.contains(':') && !name.starts_with("rdf:") && !name.starts_with("xml:") {
+            attrs.set(format!("{}{}", PREFIX, name), AttrValue::Str(value.into()));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the value of an attribute of `e`.
+fn attr_value(e: &BytesStart, key: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|a| a.key.as_ref() == key)
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+/// Parses an XMP packet into `attrs`.
+///
+/// Accepts a full packet (with `<?xpacket?>` and `x:xmpmeta` wrappers) or a
+/// bare `rdf:RDF` element.
+pub fn read_xmp(packet: &str, attrs: &mut Attrs) -> IoResult<()> {
+    // Text is trimmed per property; trimming events would eat spaces around entities
+    let mut xml = Reader::from_str(packet);
+
+    // Open elements, the current property's text and array items
+    let mut path: Vec<String> = Vec::new();
+    let mut text = String::new();
+    let mut items: Vec<String> = Vec::new();
+    let mut default_item = None;
+
+    // A property is a child of a top-level rdf:Description
+    let in_description = |path: &[String]| {
+        path.len() >= 2
+            && path[path.len() - 1] == "rdf:Description"
+            && path[path.len() - 2] == "rdf:RDF"
+    };
+
+    loop {
+        let event = xml
+            .read_event()
+            .map_err(|e| IoError::DecodeError(format!("XMP parse error: {}", e)))?;
+        match event {
+            Event::Start(e) => {
+                let name = qname(e.name().as_ref());
+                if name == "rdf:Description" && path.last().is_some_and(|p| p == "rdf:RDF") {
+                    description_attrs(&e, attrs)?;
+                }
+                if name == "rdf:li" && attr_value(&e, b"xml:lang").as_deref() == Some("x-default")
+                {
+                    default_item = Some(items.len());
+                }
+                if in_description(&path) {
+                    items.clear();
+                    default_item = None;
+                }
+                path.push(name);
+                text.clear();
+            }
+            Event::Empty(e) => {
+                let name = qname(e.name().as_ref());
+                if name == "rdf:Description" && path.last().is_some_and(|p| p == "rdf:RDF") {
+                    description_attrs(&e, attrs)?;
+                } else if in_description(&path) {
+                    if let Some(resource) = attr_value(&e, b"rdf:resource") {
+                        attrs.set(format!("{}{}", PREFIX, name), AttrValue::Str(resource));
+                    }
+                }
+            }
+            Event::Text(e) => {
+                let decoded = e
+                    .decode()
+                    .map_err(|e| IoError::DecodeError(format!("XMP text: {}", e)))?;
+                text.push_str(&decoded);
+            }
+            Event::GeneralRef(e) => {
+                if let Ok(Some(c)) = e.resolve_char_ref() {
+                    text.push(c);
+                } else {
+                    let entity = match &*e {
+                        b"amp" => '&',
+                        b"lt" => '<',
+                        b"gt" => '>',
+                        b"quot" => '"',
+                        b"apos" => '\'',
+                        _ => continue,
+                    };
+                    text.push(entity);
+                }
+            }
+            Event::End(_) => {
+                let Some(name) = path.pop() else { continue };
+                let depth = path.len();
+                if name == "rdf:li"
+                    && depth >= 4
+                    && matches!(path[depth - 1].as_str(), "rdf:Seq" | "rdf:Bag" | "rdf:Alt")
+                    && in_description(&path[..depth - 2])
+                {
+                    // Structured items have no text of their own
+                    if !text.trim().is_empty() {
+                        items.push(text.trim().to_string());
+                    }
+                } else if in_description(&path) && name != "rdf:Description" {
+                    let value = if items.is_empty() {
+                        (!text.trim().is_empty()).then(|| AttrValue::Str(text.trim().to_string()))
+                    } else if ALT_PROPERTIES.contains(&name.as_str()) || default_item.is_some() {
+                        let item = items[default_item.unwrap_or(0).min(items.len() - 1)].clone();
+                        Some(AttrValue::Str(item))
+                    } else {
+                        Some(AttrValue::List(items.drain(..).map(AttrValue::Str).collect()))
+                    };
+                    if let Some(value) = value {
+                        attrs.set(format!("{}{}", PREFIX, name), value);
+                    }
+                    items.clear();
+                    default_item = None;
+                }
+                text.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Writing
+// ============================================================================
+
+/// Formats a scalar attribute as XMP text.
+fn value_text(value: &AttrValue) -> Option<String> {
+    Some(match value {
+        AttrValue::Str(s) => s.clone(),
+        AttrValue::Bool(b) => if *b { "True" } else { "False" }.to_string(),
+        AttrValue::Int(v) => v.to_string(),
+        AttrValue::UInt(v) => v.to_string(),
+        AttrValue::Int64(v) => v.to_string(),
+        AttrValue::UInt64(v) => v.to_string(),
+        AttrValue::Float(v) => v.to_string(),
+        AttrValue::Double(v) => v.to_string(),
+        AttrValue::Rational(n, d) => format!("{}/{}", n, d),
+        AttrValue::URational(n, d) => format!("{}/{}", n, d),
+        _ => return None,
+    })
+}
+
+/// Serializes the `XMP:*` attributes of `attrs` as an XMP packet.
+///
+/// Returns `None` if there are no XMP properties with a known namespace.
+pub fn write_xmp(attrs: &Attrs) -> Option<String> {
+    let namespace = |prefix: &str| -> Option<String> {
+        NAMESPACES
+            .iter()
+            .find(|ns| ns.0 == prefix)
+            .map(|ns| ns.1.to_string())
+            .or_else(|| attrs.get_str(&format!("{}{}", XMLNS_PREFIX, prefix)).map(String::from))
+    };
+
+    // Sorted for stable output
+    let mut properties = BTreeMap::new();
+    let mut namespaces = BTreeMap::new();
+    for (key, value) in attrs.iter() {
+        let Some(name) = key.strip_prefix(PREFIX) else { continue };
+        let Some((prefix, _)) = name.split_once(':') else { continue };
+        if prefix == "xmlns" {
+            continue;
+        }
+        let Some(uri) = namespace(prefix) else { continue };
+        namespaces.insert(prefix.to_string(), uri);
+        properties.insert(name.to_string(), value);
+    }
+    if properties.is_empty() {
+        return None;
+    }
+
+    let mut out = String::new();
+    out.push_str("<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n");
+    out.push_str("<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n");
+    out.push_str(" <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n");
+    out.push_str("  <rdf:Description rdf:about=\"\"");
+    for (prefix, uri) in &namespaces {
+        out.push_str(&format!("\n    xmlns:{}=\"{}\"", prefix, escape(uri.as_str())));
+    }
+    out.push_str(">\n");
+
+    for (name, value) in properties {
+        let line = match value {
+            AttrValue::List(items) => {
+                let container = if BAG_PROPERTIES.contains(&name.as_str()) { "Bag" } else { "Seq" };
+                let mut line = format!("   <{}>\n    <rdf:{}>\n", name, container);
+                for item in items.iter().filter_map(value_text) {
+                    line.push_str(&format!("     <rdf:li>{}</rdf:li>\n", escape(item.as_str())));
+                }
+                line.push_str(&format!("    </rdf:{}>\n   </{}>\n", container, name));
+                line
+            }
+            v => {
+                let Some(text) = value_text(v) else { continue };
+                let text = escape(text.as_str()).into_owned();
+                if ALT_PROPERTIES.contains(&name.as_str()) {
+                    let item = format!("<rdf:li xml:lang=\"x-default\">{}</rdf:li>", text);
+                    format!(
+                        "   <{0}>\n    <rdf:Alt>\n     {1}\n    </rdf:Alt>\n   </{0}>\n",
+                        name, item
+                    )
+                } else {
+                    format!("   <{0}>{1}</{0}>\n", name, text)
+                }
+            }
+        };
+        out.push_str(&line);
+    }
+
+    out.push_str("  </rdf:Description>\n </rdf:RDF>\n</x:xmpmeta>\n");
+    out.push_str("<?xpacket end=\"w\"?>");
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACKET: &str = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+    xmlns:dc="http://purl.org/dc/elements/1.1/"
+    xmlns:drone-dji="http://www.dji.com/drone-dji/1.0/"
+    xmp:Rating="5"
+    drone-dji:GimbalYawDegree="-90.5">
+   <dc:title>
+    <rdf:Alt>
+     <rdf:li xml:lang="de">Platte</rdf:li>
+     <rdf:li xml:lang="x-default">Plate &amp; Clean</rdf:li>
+    </rdf:Alt>
+   </dc:title>
+   <dc:creator>
+    <rdf:Seq>
+     <rdf:li>Ann</rdf:li>
+     <rdf:li>Bo</rdf:li>
+    </rdf:Seq>
+   </dc:creator>
+   <xmp:CreatorTool>vfx-rs</xmp:CreatorTool>
+   <xmpMM:History xmlns:xmpMM="http://ns.adobe.com/xap/1.0/mm/">
+    <rdf:Seq>
+     <rdf:li rdf:parseType="Resource"><stEvt:action>saved</stEvt:action></rdf:li>
+    </rdf:Seq>
+   </xmpMM:History>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#;
+
+    #[test]
+    fn test_read() {
+        let mut attrs = Attrs::new();
+        read_xmp(PACKET, &mut attrs).unwrap();
+
+        assert_eq!(attrs.get_str("XMP:xmp:Rating"), Some("5"));
+        assert_eq!(attrs.get_str("XMP:drone-dji:GimbalYawDegree"), Some("-90.5"));
+        assert_eq!(attrs.get_str("XMP:dc:title"), Some("Plate & Clean"));
+        assert_eq!(attrs.get_str("XMP:xmp:CreatorTool"), Some("vfx-rs"));
+        let creators = attrs.get("XMP:dc:creator").and_then(|v| v.as_list()).unwrap();
+        assert_eq!(creators.len(), 2);
+        assert_eq!(creators[1].as_str(), Some("Bo"));
+        assert_eq!(
+            attrs.get_str("XMP:xmlns:drone-dji"),
+            Some("http://www.dji.com/drone-dji/1.0/")
+        );
+        // Structured values are skipped, not flattened
+        assert!(attrs.get("XMP:stEvt:action").is_none());
+        assert!(attrs.get("XMP:xmpMM:History").is_none());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut attrs = Attrs::new();
+        read_xmp(PACKET, &mut attrs).unwrap();
+        attrs.set("XMP:exif:FNumber", AttrValue::URational(28, 10));
+        attrs.set("XMP:unknown:Thing", AttrValue::Str("dropped".into()));
+
+        let packet = write_xmp(&attrs).unwrap();
+        let mut parsed = Attrs::new();
+        read_xmp(&packet, &mut parsed).unwrap();
+
+        for key in [
+            "XMP:xmp:Rating",
+            "XMP:dc:title",
+            "XMP:xmp:CreatorTool",
+            "XMP:drone-dji:GimbalYawDegree",
+        ] {
+            assert_eq!(parsed.get_str(key), attrs.get_str(key), "{}", key);
+        }
+        assert_eq!(parsed.get("XMP:dc:creator"), attrs.get("XMP:dc:creator"));
+        assert_eq!(parsed.get_str("XMP:exif:FNumber"), Some("28/10"));
+        assert!(parsed.get("XMP:unknown:Thing").is_none());
+    }
+
+    #[test]
+    fn test_empty_and_invalid() {
+        let mut attrs = Attrs::new();
+        attrs.set("Make", AttrValue::Str("Canon".into()));
+        assert!(write_xmp(&attrs).is_none());
+        assert!(read_xmp("<rdf:RDF><rdf:Description></rdf:RDF>", &mut attrs).is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! BC1 color block encoder, also used for the color half of BC3.
+
+use super::{BcQuality, Block, dist2, fit_line, least_squares};
+
+/// Second-endpoint weight of each palette index in 4-color mode.
+const WEIGHTS4: [f32; 4] = [0.0, 1.0, 1.0 / 3.0, 2.0 / 3.0];
+/// Second-endpoint weight of each palette index in 3-color mode.
+const WEIGHTS3: [f32; 3] = [0.0, 1.0, 0.5];
+
+/// Endpoints and indices for an unordered endpoint pair `a`, `b`.
+struct Fit {
+    a: u16,
+    b: u16,
+    indices: [u8; 16],
+    err: f32,
+}
+
+/// Encodes a BC1 block, using 3-color mode for cutout alpha.
+///
+/// Pixels with alpha below 0.5 become transparent black.
+pub(super) fn encode_bc1(block: &Block, quality: BcQuality) -> [u8; 8] {
+    let transparent = block.map(|p| p[3] < 0.5);
+    if !transparent.iter().any(|&t| t) {
+        return encode_color(block, quality, true);
+    }
+    let used = transparent.map(|t| !t);
+    let fit = fit_palette(&to_points(block), &used, &WEIGHTS3, quality);
+    pack3(&fit, &transparent)
+}
+
+/// Encodes the RGB of a block as a 4-color BC1 block.
+///
+/// With `allow_3color`, [`BcQuality::High`] also tries 3-color mode.
+pub(super) fn encode_color(block: &Block, quality: BcQuality, allow_3color: bool) -> [u8; 8] {
+    let points = to_points(block);
+    if points.iter().all(|p| p[..3] == points[0][..3]) {
+        return solid(&points[0]);
+    }
+    let used = [true; 16];
+    let four = fit_palette(&points, &used, &WEIGHTS4, quality);
+    if allow_3color && quality == BcQuality::High {
+        let three = fit_palette(&points, &used, &WEIGHTS3, quality);
+        if three.err < four.err {
+            return pack3(&three, &[false; 16]);
+        }
+    }
+    pack4(&four)
+}
+
+/// Encodes a solid color, choosing endpoints whose 1/3 blend lands
+/// closest to it (565 endpoints alone can be off by a few codes).
+fn solid(color: &[f32; 4]) -> [u8; 8] {
+    let best_pair = |v: f32, bits: u32| -> (u16, u16) {
+        let expand = |c: u16| if bits == 5 { (c << 3) | (c >> 2) } else { (c << 2) | (c >> 4) } as f32;
+        let max = (1u16 << bits) - 1;
+        let mut best = (0, 0, f32::MAX);
+        for a in 0..=max {
+            for b in 0..=max {
+                let err = ((2.0 * expand(a) + expand(b)) / 3.0 - v).abs();
+                if err < best.2 {
+                    best = (a, b, err);
+                }
+            }
+        }
+        (best.0, best.1)
+    };
+    let (r0, r1) = best_pair(color[0], 5);
+    let (g0, g1) = best_pair(color[1], 6);
+    let (b0, b1) = best_pair(color[2], 5);
+    let fit = Fit {
+        a: (r0 << 11) | (g0 << 5) | b0,
+        b: (r1 << 11) | (g1 << 5) | b1,
+        indices: [2; 16],
+        err: 0.0,
+    };
+    pack4(&fit)
+}
+
+/// RGB scaled to 0-255.
+fn to_points(block: &Block) -> [[f32; 4]; 16] {
+    block.map(|p| {
+        [
+            p[0].clamp(0.0, 1.0) * 255.0,
+            p[1].clamp(0.0, 1.0) * 255.0,
+            p[2].clamp(0.0, 1.0) * 255.0,
+            0.0,
+        ]
+    })
+}
+
+fn to565(c: &[f32; 4]) -> u16 {
+    let q = |v: f32, max: f32| (v.clamp(0.0, 255.0) * max / 255.0).round() as u16;
+    (q(c[0], 31.0) << 11) | (q(c[1], 63.0) << 5) | q(c[2], 31.0)
+}
+
+fn expand565(c: u16) -> [f32; 4] {
+    let r = (c >> 11) & 31;
+    let g = (c >> 5) & 63;
+    let b = c & 31;
+    [
+        ((r << 3) | (r >> 2)) as f32,
+        ((g << 2) | (g >> 4)) as f32,
+        ((b << 3) | (b >> 2)) as f32,
+        0.0,
+    ]
+}
+
+/// Fits endpoints to the `used` pixels for the given palette weights.
+fn fit_palette(
+    points: &[[f32; 4]; 16],
+    used: &[bool; 16],
+    weights: &[f32],
+    quality: BcQuality,
+) -> Fit {
+    let subset: Vec<[f32; 4]> = points
+        .iter()
+        .zip(used)
+        .filter(|(_, u)| **u)
+        .map(|(p, _)| *p)
+        .collect();
+    let mut best = Fit {
+        a: 0,
+        b: 0,
+        indices: [0; 16],
+        err: f32::MAX,
+    };
+    if subset.is_empty() {
+        best.err = 0.0;
+        return best;
+    }
+
+    let (mut e0, mut e1) = fit_line(&subset, 3);
+    for _ in 0..quality.iterations() {
+        let (a, b) = (to565(&e0), to565(&e1));
+        let (ca, cb) = (expand565(a), expand565(b));
+        let palette: Vec<[f32; 4]> = weights
+            .iter()
+            .map(|&w| std::array::from_fn(|c| ca[c] * (1.0 - w) + cb[c] * w))
+            .collect();
+
+        let mut indices = [0u8; 16];
+        let mut err = 0.0;
+        for i in (0..16).filter(|&i| used[i]) {
+            let (idx, e) = palette
+                .iter()
+                .enumerate()
+                .map(|(k, p)| (k, dist2(&points[i], p, 3)))
+                .fold((0, f32::MAX), |acc, x| if x.1 < acc.1 { x } else { acc });
+            indices[i] = idx as u8;
+            err += e;
+        }
+        if err < best.err {
+            best = Fit { a, b, indices, err };
+        }
+
+        let w: Vec<f32> = (0..16)
+            .filter(|&i| used[i])
+            .map(|i| weights[indices[i] as usize])
+            .collect();
+        match least_squares(&subset, &w, 3) {
+            Some((n0, n1)) => (e0, e1) = (n0, n1),
+            None => break,
+        }
+    }
+    best
+}
+
+/// Packs a 4-color block (`c0 > c1`).
+fn pack4(fit: &Fit) -> [u8; 8] {
+    let (c0, c1, indices) = if fit.a >= fit.b {
+        let indices = if fit.a == fit.b { [0; 16] } else { fit.indices };
+        (fit.a, fit.b, indices)
+    } else {
+        (fit.b, fit.a, fit.indices.map(|i| [1, 0, 3, 2][i as usize]))
+    };
+    pack(c0, c1, &indices)
+}
+
+/// Packs a 3-color block (`c0 <= c1`); `transparent` pixels use index 3.
+fn pack3(fit: &Fit, transparent: &[bool; 16]) -> [u8; 8] {
+    let (c0, c1, mut indices) = if fit.a <= fit.b {
+        (fit.a, fit.b, fit.indices)
+    } else {
+        (fit.b, fit.a, fit.indices.map(|i| [1, 0, 2][i as usize]))
+    };
+    for (idx, &t) in indices.iter_mut().zip(transparent) {
+        if t {
+            *idx = 3;
+        }
+    }
+    pack(c0, c1, &indices)
+}
+
+fn pack(c0: u16, c1: u16, indices: &[u8; 16]) -> [u8; 8] {
+    let bits = indices
+        .iter()
+        .enumerate()
+        .fold(0u32, |acc, (i, &v)| acc | ((v as u32) << (2 * i)));
+    let mut out = [0u8; 8];
+    out[0..2].copy_from_slice(&c0.to_le_bytes());
+    out[2..4].copy_from_slice(&c1.to_le_bytes());
+    out[4..8].copy_from_slice(&bits.to_le_bytes());
+    out
+}
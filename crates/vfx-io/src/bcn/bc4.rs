@@ -0,0 +1,92 @@
+//! BC4 single-channel block encoder, also used for BC3 alpha and BC5.
+
+use super::BcQuality;
+
+/// Decoded palette for endpoints `r0`, `r1` (0-255).
+fn palette(r0: u8, r1: u8) -> [f32; 8] {
+    let (a, b) = (r0 as f32, r1 as f32);
+    let mut p = [a, b, 0.0, 0.0, 0.0, 0.0, 0.0, 255.0];
+    if r0 > r1 {
+        for (k, v) in p.iter_mut().enumerate().skip(2) {
+            *v = ((8 - k) as f32 * a + (k - 1) as f32 * b) / 7.0;
+        }
+    } else {
+        for (k, v) in p.iter_mut().enumerate().take(6).skip(2) {
+            *v = ((6 - k) as f32 * a + (k - 1) as f32 * b) / 5.0;
+        }
+    }
+    p
+}
+
+/// Best indices and total squared error for a pair of endpoints.
+fn evaluate(values: &[f32; 16], r0: u8, r1: u8) -> (f32, [u8; 16]) {
+    let palette = palette(r0, r1);
+    let mut indices = [0u8; 16];
+    let mut err = 0.0;
+    for (idx, &v) in indices.iter_mut().zip(values) {
+        let (k, e) = palette
+            .iter()
+            .enumerate()
+            .map(|(k, p)| (k, (p - v) * (p - v)))
+            .fold((0, f32::MAX), |acc, x| if x.1 < acc.1 { x } else { acc });
+        *idx = k as u8;
+        err += e;
+    }
+    (err, indices)
+}
+
+/// Encodes 16 values in 0-1 as a BC4 block.
+pub(super) fn encode(values: &[f32; 16], quality: BcQuality) -> [u8; 8] {
+    let values = values.map(|v| v.clamp(0.0, 1.0) * 255.0);
+    let lo = values.iter().copied().fold(f32::MAX, f32::min).round() as i32;
+    let hi = values.iter().copied().fold(f32::MIN, f32::max).round() as i32;
+
+    // 8-value mode needs r0 > r1; a solid block uses r0 == r1 and index 0
+    let mut candidates = vec![(hi, lo)];
+    let radius = match quality {
+        BcQuality::Fast => 0,
+        BcQuality::Normal => 1,
+        BcQuality::High => 3,
+    };
+    if radius > 0 && hi > lo {
+        for d0 in -radius..=radius {
+            for d1 in -radius..=radius {
+                candidates.push((hi + d0, lo + d1));
+            }
+        }
+    }
+
+    // 6-value mode (r0 <= r1) with exact 0 and 255 for the extremes
+    if quality != BcQuality::Fast {
+        let inner: Vec<f32> = values
+            .iter()
+            .copied()
+            .filter(|&v| v > 0.5 && v < 254.5)
+            .collect();
+        if !inner.is_empty() {
+            let lo6 = inner.iter().copied().fold(f32::MAX, f32::min).round() as i32;
+            let hi6 = inner.iter().copied().fold(f32::MIN, f32::max).round() as i32;
+            candidates.push((lo6, hi6));
+        }
+    }
+
+    let mut best = (f32::MAX, 0u8, 0u8, [0u8; 16]);
+    for (r0, r1) in candidates {
+        let (r0, r1) = (r0.clamp(0, 255) as u8, r1.clamp(0, 255) as u8);
+        let (err, indices) = evaluate(&values, r0, r1);
+        if err < best.0 {
+            best = (err, r0, r1, indices);
+        }
+    }
+
+    let (_, r0, r1, indices) = best;
+    let bits = indices
+        .iter()
+        .enumerate()
+        .fold(0u64, |acc, (i, &v)| acc | ((v as u64) << (3 * i)));
+    let mut out = [0u8; 8];
+    out[0] = r0;
+    out[1] = r1;
+    out[2..8].copy_from_slice(&bits.to_le_bytes()[..6]);
+    out
+}
@@ -0,0 +1,103 @@
+//! BC6H unsigned half-float encoder using mode 11 (one region, 10-bit
+//! endpoints, 4-bit indices).
+
+use super::{BcQuality, BitWriter, Block, fit_line, least_squares};
+
+const WEIGHTS4: [u32; 16] = [0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64];
+
+/// Half-float bits of a non-negative finite value.
+fn half_bits(v: f32) -> f32 {
+    let v = if v.is_nan() {
+        0.0
+    } else {
+        v.clamp(0.0, 65504.0)
+    };
+    half::f16::from_f32(v).to_bits() as f32
+}
+
+/// Unquantizes a 10-bit unsigned endpoint to the 16-bit interpolation range.
+fn unquantize(code: u32) -> u32 {
+    match code {
+        0 => 0,
+        1023 => 0xFFFF,
+        c => c * 64 + 32,
+    }
+}
+
+/// Closest 10-bit code to a value in the interpolation range.
+fn quantize(x: f32) -> u32 {
+    let ideal = (x - 32.0) / 64.0;
+    [ideal.floor(), ideal.ceil(), 0.0, 1023.0]
+        .into_iter()
+        .map(|c| c.clamp(0.0, 1023.0) as u32)
+        .min_by_key(|&c| (unquantize(c) as f32 - x).abs() as u32)
+        .unwrap_or(0)
+}
+
+/// Encodes a block (linear RGB) as a BC6H UF16 block.
+pub(super) fn encode(block: &Block, quality: BcQuality) -> [u8; 16] {
+    // Targets in half bits, and in the pre-scale interpolation range
+    let targets: Vec<[f32; 4]> = block
+        .iter()
+        .map(|p| [half_bits(p[0]), half_bits(p[1]), half_bits(p[2]), 0.0])
+        .collect();
+    let points: Vec<[f32; 4]> = targets.iter().map(|t| t.map(|v| v * 64.0 / 31.0)).collect();
+
+    let mut best = ([[0u32; 3]; 2], [0u8; 16], f32::MAX);
+    let (mut e0, mut e1) = fit_line(&points, 3);
+    for _ in 0..quality.iterations() {
+        let c0: [u32; 3] = std::array::from_fn(|c| quantize(e0[c]));
+        let c1: [u32; 3] = std::array::from_fn(|c| quantize(e1[c]));
+        let palette: Vec<[f32; 3]> = WEIGHTS4
+            .iter()
+            .map(|&w| {
+                std::array::from_fn(|c| {
+                    let v = ((64 - w) * unquantize(c0[c]) + w * unquantize(c1[c]) + 32) >> 6;
+                    ((v * 31) >> 6) as f32
+                })
+            })
+            .collect();
+
+        let mut indices = [0u8; 16];
+        let mut err = 0.0;
+        for (idx, t) in indices.iter_mut().zip(&targets) {
+            let (k, e) = palette
+                .iter()
+                .enumerate()
+                .map(|(k, p)| (k, (0..3).map(|c| (p[c] - t[c]).powi(2)).sum::<f32>()))
+                .fold((0, f32::MAX), |acc, x| if x.1 < acc.1 { x } else { acc });
+            *idx = k as u8;
+            err += e;
+        }
+        if err < best.2 {
+            best = ([c0, c1], indices, err);
+        }
+
+        let w: Vec<f32> = indices
+            .iter()
+            .map(|&i| WEIGHTS4[i as usize] as f32 / 64.0)
+            .collect();
+        match least_squares(&points, &w, 3) {
+            Some((n0, n1)) => (e0, e1) = (n0, n1),
+            None => break,
+        }
+    }
+
+    let (mut codes, mut indices, _) = best;
+    if indices[0] > 7 {
+        codes.swap(0, 1);
+        indices = indices.map(|i| 15 - i);
+    }
+
+    let mut w = BitWriter::new();
+    w.put(0b00011, 5);
+    for endpoint in codes {
+        for code in endpoint {
+            w.put(code, 10);
+        }
+    }
+    for (i, &idx) in indices.iter().enumerate() {
+        w.put(idx as u32, if i == 0 { 3 } else { 4 });
+    }
+    w.finish()
+}
@@ -0,0 +1,235 @@
+//! BC7 block encoder using the single-subset modes 4, 5 and 6.
+
+use super::{BcQuality, BitWriter, Block, dist2, fit_line, least_squares};
+
+const WEIGHTS2: [u32; 4] = [0, 21, 43, 64];
+const WEIGHTS3: [u32; 8] = [0, 9, 18, 27, 37, 46, 55, 64];
+const WEIGHTS4: [u32; 16] = [0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64];
+
+fn weights(index_bits: u32) -> &'static [u32] {
+    match index_bits {
+        2 => &WEIGHTS2,
+        3 => &WEIGHTS3,
+        _ => &WEIGHTS4,
+    }
+}
+
+/// Expands an `n`-bit endpoint (including any p-bit) to 8 bits.
+fn expand(code: u32, n: u32) -> u32 {
+    let v = code << (8 - n);
+    v | (v >> n)
+}
+
+/// A fitted endpoint pair for a set of channels.
+struct Fit {
+    /// Endpoint codes per channel, without p-bits.
+    codes: [[u32; 4]; 2],
+    /// Endpoint p-bits (mode 6 only).
+    pbits: [u32; 2],
+    indices: [u8; 16],
+    err: f32,
+}
+
+/// Quantizes one endpoint to `bits`-bit codes, choosing the best p-bit
+/// when `pbit` is set. Returns codes, p-bit and expanded 8-bit values.
+fn quantize(e: &[f32; 4], dims: usize, bits: u32, pbit: bool) -> ([u32; 4], u32, [u32; 4]) {
+    let total = bits + pbit as u32;
+    let max = (1u32 << bits) - 1;
+    let mut best = ([0u32; 4], 0u32, [0u32; 4], f32::MAX);
+    for p in 0..=(pbit as u32) {
+        let mut codes = [0u32; 4];
+        let mut values = [0u32; 4];
+        let mut err = 0.0;
+        for c in 0..dims {
+            let ideal = e[c].clamp(0.0, 255.0) * max as f32 / 255.0;
+            let mut pick = (0, 0, f32::MAX);
+            for code in [ideal.floor() as u32, ideal.ceil() as u32] {
+                let code = code.min(max);
+                let full = if pbit { (code << 1) | p } else { code };
+                let v = expand(full, total);
+                let d = (v as f32 - e[c]).abs();
+                if d < pick.2 {
+                    pick = (code, v, d);
+                }
+            }
+            codes[c] = pick.0;
+            values[c] = pick.1;
+            err += pick.2 * pick.2;
+        }
+        if err < best.3 {
+            best = (codes, p, values, err);
+        }
+    }
+    (best.0, best.1, best.2)
+}
+
+/// Fits one endpoint pair to `chans` of the block (values 0-255).
+fn fit(
+    block: &Block,
+    chans: &[usize],
+    bits: u32,
+    pbit: bool,
+    index_bits: u32,
+    quality: BcQuality,
+) -> Fit {
+    let dims = chans.len();
+    let points: Vec<[f32; 4]> = block
+        .iter()
+        .map(|p| std::array::from_fn(|c| if c < dims { p[chans[c]] } else { 0.0 }))
+        .collect();
+    let table = weights(index_bits);
+
+    let mut best = Fit {
+        codes: [[0; 4]; 2],
+        pbits: [0; 2],
+        indices: [0; 16],
+        err: f32::MAX,
+    };
+    let (mut e0, mut e1) = fit_line(&points, dims);
+    for _ in 0..quality.iterations() {
+        let (q0, p0, x0) = quantize(&e0, dims, bits, pbit);
+        let (q1, p1, x1) = quantize(&e1, dims, bits, pbit);
+        let palette: Vec<[f32; 4]> = table
+            .iter()
+            .map(|&w| std::array::from_fn(|c| (((64 - w) * x0[c] + w * x1[c] + 32) >> 6) as f32))
+            .collect();
+
+        let mut indices = [0u8; 16];
+        let mut err = 0.0;
+        for (idx, p) in indices.iter_mut().zip(&points) {
+            let (k, e) = palette
+                .iter()
+                .enumerate()
+                .map(|(k, q)| (k, dist2(p, q, dims)))
+                .fold((0, f32::MAX), |acc, x| if x.1 < acc.1 { x } else { acc });
+            *idx = k as u8;
+            err += e;
+        }
+        if err < best.err {
+            best = Fit {
+                codes: [q0, q1],
+                pbits: [p0, p1],
+                indices,
+                err,
+            };
+        }
+
+        let w: Vec<f32> = indices
+            .iter()
+            .map(|&i| table[i as usize] as f32 / 64.0)
+            .collect();
+        match least_squares(&points, &w, dims) {
+            Some((n0, n1)) => (e0, e1) = (n0, n1),
+            None => break,
+        }
+    }
+
+    // The anchor (first) index must have its top bit clear
+    let top = (1u8 << index_bits) - 1;
+    if best.indices[0] > top / 2 {
+        best.codes.swap(0, 1);
+        best.pbits.swap(0, 1);
+        best.indices = best.indices.map(|i| top - i);
+    }
+    best
+}
+
+/// Writes indices with a one-bit-shorter anchor.
+fn put_indices(w: &mut BitWriter, indices: &[u8; 16], bits: u32) {
+    for (i, &idx) in indices.iter().enumerate() {
+        w.put(idx as u32, if i == 0 { bits - 1 } else { bits });
+    }
+}
+
+/// Swaps alpha with the color channel selected by `rotation` (1 = R, 2 = G, 3 = B).
+fn rotate(block: &Block, rotation: usize) -> Block {
+    let mut out = *block;
+    if rotation > 0 {
+        for px in &mut out {
+            px.swap(3, rotation - 1);
+        }
+    }
+    out
+}
+
+fn mode6(block: &Block, quality: BcQuality) -> (f32, [u8; 16]) {
+    let f = fit(block, &[0, 1, 2, 3], 7, true, 4, quality);
+    let mut w = BitWriter::new();
+    w.put(1 << 6, 7);
+    for c in 0..4 {
+        w.put(f.codes[0][c], 7);
+        w.put(f.codes[1][c], 7);
+    }
+    w.put(f.pbits[0], 1);
+    w.put(f.pbits[1], 1);
+    put_indices(&mut w, &f.indices, 4);
+    (f.err, w.finish())
+}
+
+fn mode5(block: &Block, rotation: usize, quality: BcQuality) -> (f32, [u8; 16]) {
+    let rotated = rotate(block, rotation);
+    let color = fit(&rotated, &[0, 1, 2], 7, false, 2, quality);
+    let alpha = fit(&rotated, &[3], 8, false, 2, quality);
+    let mut w = BitWriter::new();
+    w.put(1 << 5, 6);
+    w.put(rotation as u32, 2);
+    for c in 0..3 {
+        w.put(color.codes[0][c], 7);
+        w.put(color.codes[1][c], 7);
+    }
+    w.put(alpha.codes[0][0], 8);
+    w.put(alpha.codes[1][0], 8);
+    put_indices(&mut w, &color.indices, 2);
+    put_indices(&mut w, &alpha.indices, 2);
+    (color.err + alpha.err, w.finish())
+}
+
+fn mode4(block: &Block, rotation: usize, index_mode: u32, quality: BcQuality) -> (f32, [u8; 16]) {
+    let rotated = rotate(block, rotation);
+    let (color_bits, alpha_bits) = if index_mode == 0 { (2, 3) } else { (3, 2) };
+    let color = fit(&rotated, &[0, 1, 2], 5, false, color_bits, quality);
+    let alpha = fit(&rotated, &[3], 6, false, alpha_bits, quality);
+    let mut w = BitWriter::new();
+    w.put(1 << 4, 5);
+    w.put(rotation as u32, 2);
+    w.put(index_mode, 1);
+    for c in 0..3 {
+        w.put(color.codes[0][c], 5);
+        w.put(color.codes[1][c], 5);
+    }
+    w.put(alpha.codes[0][0], 6);
+    w.put(alpha.codes[1][0], 6);
+    // The 2-bit index set comes first
+    let (first, second) = if index_mode == 0 {
+        (&color, &alpha)
+    } else {
+        (&alpha, &color)
+    };
+    put_indices(&mut w, &first.indices, 2);
+    put_indices(&mut w, &second.indices, 3);
+    (color.err + alpha.err, w.finish())
+}
+
+/// Encodes a block (RGBA 0-1) as BC7.
+pub(super) fn encode(block: &Block, quality: BcQuality) -> [u8; 16] {
+    let block: Block = block.map(|p| p.map(|v| v.clamp(0.0, 1.0) * 255.0));
+
+    let mut best = mode6(&block, quality);
+    let mut consider = |candidate: (f32, [u8; 16])| {
+        if candidate.0 < best.0 {
+            best = candidate;
+        }
+    };
+    match quality {
+        BcQuality::Fast => {}
+        BcQuality::Normal => consider(mode5(&block, 0, quality)),
+        BcQuality::High => {
+            for rotation in 0..4 {
+                consider(mode5(&block, rotation, quality));
+                consider(mode4(&block, rotation, 0, quality));
+                consider(mode4(&block, rotation, 1, quality));
+            }
+        }
+    }
+    best.1
+}
@@ -0,0 +1,218 @@
+//! Reference BCn decoder for tests, written from the format specs
+//! independently of the encoders. Covers the BC7 and BC6H modes the
+//! encoders emit.
+
+use super::BcFormat;
+
+struct Bits {
+    bits: u128,
+    pos: u32,
+}
+
+impl Bits {
+    fn new(block: &[u8]) -> Self {
+        Self {
+            bits: u128::from_le_bytes(block.try_into().unwrap()),
+            pos: 0,
+        }
+    }
+
+    fn get(&mut self, count: u32) -> u32 {
+        let v = (self.bits >> self.pos) as u32 & ((1u64 << count) - 1) as u32;
+        self.pos += count;
+        v
+    }
+}
+
+fn bc1(block: &[u8], always_four: bool) -> [[f32; 4]; 16] {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let rgb = |c: u16| {
+        let (r, g, b) = ((c >> 11) & 31, (c >> 5) & 63, c & 31);
+        [
+            ((r << 3) | (r >> 2)) as f32,
+            ((g << 2) | (g >> 4)) as f32,
+            ((b << 3) | (b >> 2)) as f32,
+        ]
+    };
+    let (a, b) = (rgb(c0), rgb(c1));
+    let mix = |wa: f32, wb: f32| {
+        std::array::from_fn::<f32, 3, _>(|i| (a[i] * wa + b[i] * wb) / (wa + wb))
+    };
+    let palette: [[f32; 4]; 4] = if c0 > c1 || always_four {
+        let (p2, p3) = (mix(2.0, 1.0), mix(1.0, 2.0));
+        [
+            [a[0], a[1], a[2], 1.0],
+            [b[0], b[1], b[2], 1.0],
+            [p2[0], p2[1], p2[2], 1.0],
+            [p3[0], p3[1], p3[2], 1.0],
+        ]
+    } else {
+        let p2 = mix(1.0, 1.0);
+        [
+            [a[0], a[1], a[2], 1.0],
+            [b[0], b[1], b[2], 1.0],
+            [p2[0], p2[1], p2[2], 1.0],
+            [0.0; 4],
+        ]
+    };
+    let bits = u32::from_le_bytes(block[4..8].try_into().unwrap());
+    std::array::from_fn(|i| {
+        let p = palette[((bits >> (2 * i)) & 3) as usize];
+        [p[0] / 255.0, p[1] / 255.0, p[2] / 255.0, p[3]]
+    })
+}
+
+fn bc4(block: &[u8]) -> [f32; 16] {
+    let (r0, r1) = (block[0] as f32, block[1] as f32);
+    let mut palette = [r0, r1, 0.0, 0.0, 0.0, 0.0, 0.0, 255.0];
+    if block[0] > block[1] {
+        for i in 1..7 {
+            palette[i + 1] = ((7 - i) as f32 * r0 + i as f32 * r1) / 7.0;
+        }
+    } else {
+        for i in 1..5 {
+            palette[i + 1] = ((5 - i) as f32 * r0 + i as f32 * r1) / 5.0;
+        }
+    }
+    let mut raw = [0u8; 8];
+    raw[..6].copy_from_slice(&block[2..8]);
+    let bits = u64::from_le_bytes(raw);
+    std::array::from_fn(|i| palette[((bits >> (3 * i)) & 7) as usize] / 255.0)
+}
+
+fn bc7_weights(bits: u32) -> &'static [u32] {
+    match bits {
+        2 => &[0, 21, 43, 64],
+        3 => &[0, 9, 18, 27, 37, 46, 55, 64],
+        _ => &[0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64],
+    }
+}
+
+fn bc7_indices(r: &mut Bits, bits: u32) -> [u32; 16] {
+    std::array::from_fn(|i| r.get(if i == 0 { bits - 1 } else { bits }))
+}
+
+fn bc7(block: &[u8]) -> [[f32; 4]; 16] {
+    let mode = block[0].trailing_zeros();
+    let mut r = Bits::new(block);
+    r.get(mode + 1);
+    let unq = |v: u32, n: u32| {
+        let v = v << (8 - n);
+        v | (v >> n)
+    };
+    let lerp = |a: u32, b: u32, w: u32| (((64 - w) * a + w * b + 32) >> 6) as f32 / 255.0;
+
+    match mode {
+        6 => {
+            let mut e = [[0u32; 4]; 2];
+            for c in 0..4 {
+                e[0][c] = r.get(7);
+                e[1][c] = r.get(7);
+            }
+            let (p0, p1) = (r.get(1), r.get(1));
+            let e0 = e[0].map(|v| (v << 1) | p0);
+            let e1 = e[1].map(|v| (v << 1) | p1);
+            let idx = bc7_indices(&mut r, 4);
+            let w = bc7_weights(4);
+            std::array::from_fn(|i| std::array::from_fn(|c| lerp(e0[c], e1[c], w[idx[i] as usize])))
+        }
+        4 | 5 => {
+            let rotation = r.get(2);
+            let index_mode = if mode == 4 { r.get(1) } else { 0 };
+            let (cbits, abits) = if mode == 4 { (5, 6) } else { (7, 8) };
+            let mut e = [[0u32; 4]; 2];
+            for c in 0..3 {
+                e[0][c] = unq(r.get(cbits), cbits);
+                e[1][c] = unq(r.get(cbits), cbits);
+            }
+            e[0][3] = unq(r.get(abits), abits);
+            e[1][3] = unq(r.get(abits), abits);
+            let (first, second) = if mode == 4 { (2, 3) } else { (2, 2) };
+            let set1 = bc7_indices(&mut r, first);
+            let set2 = bc7_indices(&mut r, second);
+            let ((ci, cb), (ai, ab)) = if index_mode == 0 {
+                ((set1, first), (set2, second))
+            } else {
+                ((set2, second), (set1, first))
+            };
+            std::array::from_fn(|i| {
+                let mut px: [f32; 4] = std::array::from_fn(|c| {
+                    if c < 3 {
+                        lerp(e[0][c], e[1][c], bc7_weights(cb)[ci[i] as usize])
+                    } else {
+                        lerp(e[0][3], e[1][3], bc7_weights(ab)[ai[i] as usize])
+                    }
+                });
+                if rotation > 0 {
+                    px.swap(3, rotation as usize - 1);
+                }
+                px
+            })
+        }
+        other => panic!("BC7 mode {} not covered by the test decoder", other),
+    }
+}
+
+fn bc6h(block: &[u8]) -> [[f32; 4]; 16] {
+    let mut r = Bits::new(block);
+    assert_eq!(r.get(5), 0b00011, "test decoder covers BC6H mode 11 only");
+    let mut e = [[0u32; 3]; 2];
+    for endpoint in &mut e {
+        for v in endpoint.iter_mut() {
+            *v = r.get(10);
+        }
+    }
+    let unq = |v: u32| match v {
+        0 => 0,
+        1023 => 0xFFFF,
+        v => ((v << 16) + 0x8000) >> 10,
+    };
+    let w = bc7_weights(4);
+    std::array::from_fn(|i| {
+        let idx = r.get(if i == 0 { 3 } else { 4 });
+        let mut px = [1.0f32; 4];
+        for c in 0..3 {
+            let v =
+                ((64 - w[idx as usize]) * unq(e[0][c]) + w[idx as usize] * unq(e[1][c]) + 32) >> 6;
+            px[c] = half::f16::from_bits(((v * 31) >> 6) as u16).to_f32();
+        }
+        px
+    })
+}
+
+/// Decodes a BCn surface to RGBA f32.
+pub(crate) fn decode(data: &[u8], width: u32, height: u32, format: BcFormat) -> Vec<f32> {
+    let (w, h) = (width as usize, height as usize);
+    let bw = w.div_ceil(4);
+    let size = format.block_bytes();
+    let mut out = vec![0.0f32; w * h * 4];
+    for (n, block) in data.chunks_exact(size).enumerate() {
+        let pixels: [[f32; 4]; 16] = match format {
+            BcFormat::Bc1 => bc1(block, false),
+            BcFormat::Bc3 => {
+                let alpha = bc4(&block[..8]);
+                let mut px = bc1(&block[8..], true);
+                for (p, a) in px.iter_mut().zip(alpha) {
+                    p[3] = a;
+                }
+                px
+            }
+            BcFormat::Bc4 => bc4(block).map(|v| [v, v, v, 1.0]),
+            BcFormat::Bc5 => {
+                let (red, green) = (bc4(&block[..8]), bc4(&block[8..]));
+                std::array::from_fn(|i| [red[i], green[i], 0.0, 1.0])
+            }
+            BcFormat::Bc6h => bc6h(block),
+            BcFormat::Bc7 => bc7(block),
+        };
+        let (bx, by) = (n % bw, n / bw);
+        for (i, px) in pixels.iter().enumerate() {
+            let (x, y) = (bx * 4 + i % 4, by * 4 + i / 4);
+            if x < w && y < h {
+                out[(y * w + x) * 4..][..4].copy_from_slice(px);
+            }
+        }
+    }
+    out
+}
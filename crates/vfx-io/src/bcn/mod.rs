@@ -0,0 +1,755 @@
+//! BCn block compression for GPU texture containers.
+//!
+//! Pure Rust encoders for the block-compressed formats used by DDS and
+//! KTX2, plus [`GpuTexture`], the surface layout (mip chains, cubemap
+//! faces, array layers) shared by both writers.
+//!
+//! # Formats
+//!
+//! | Format | Channels | Bytes/block | Notes |
+//! |--------|----------|-------------|-------|
+//! | BC1 | RGB + 1-bit A | 8 | 3-color mode for cutout alpha |
+//! | BC3 | RGBA | 16 | BC1 color + BC4 alpha |
+//! | BC4 | R | 8 | |
+//! | BC5 | RG | 16 | Two BC4 blocks, for normal maps |
+//! | BC6H | RGB half | 16 | Unsigned, single region (mode 11) |
+//! | BC7 | RGBA | 16 | Single subset modes 4, 5 and 6 |
+//!
+//! Partitioned BC7 modes and the BC6H delta modes are not produced; every
+//! block is still valid and decodes with any conforming decoder.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use vfx_io::bcn::{compress, BcFormat, BcQuality};
+//!
+//! let blocks = compress(&image, BcFormat::Bc7, BcQuality::High)?;
+//! assert_eq!(blocks.len(), BcFormat::Bc7.compressed_size(image.width, image.height));
+//! ```
+
+mod bc1;
+mod bc4;
+mod bc6h;
+mod bc7;
+#[cfg(test)]
+pub(crate) mod decode;
+
+use crate::imagebuf::ImageBuf;
+use crate::imagebufalgo::{MipmapOptions, make_texture};
+use crate::{ImageData, IoError, IoResult};
+
+// ============================================================================
+// Formats
+// ============================================================================
+
+/// Block-compressed texture format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BcFormat {
+    /// BC1/DXT1 - RGB with 1-bit alpha.
+    Bc1,
+    /// BC3/DXT5 - RGBA with interpolated alpha.
+    Bc3,
+    /// BC4 - single channel (red).
+    Bc4,
+    /// BC5 - two channels (red, green).
+    Bc5,
+    /// BC6H - unsigned half-float RGB.
+    Bc6h,
+    /// BC7 - high quality RGBA.
+    Bc7,
+}
+
+impl BcFormat {
+    /// Bytes per 4x4 block.
+    pub fn block_bytes(&self) -> usize {
+        match self {
+            BcFormat::Bc1 | BcFormat::Bc4 => 8,
+            _ => 16,
+        }
+    }
+
+    /// Size in bytes of a compressed `width` x `height` surface.
+    pub fn compressed_size(&self, width: u32, height: u32) -> usize {
+        let bw = width.div_ceil(4).max(1) as usize;
+        let bh = height.div_ceil(4).max(1) as usize;
+        bw * bh * self.block_bytes()
+    }
+
+    /// Returns true for the HDR format (BC6H).
+    pub fn is_hdr(&self) -> bool {
+        matches!(self, BcFormat::Bc6h)
+    }
+
+    /// Parses a format name such as "bc7" or "dxt5".
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "bc1" | "dxt1" => Some(BcFormat::Bc1),
+            "bc3" | "dxt5" => Some(BcFormat::Bc3),
+            "bc4" | "ati1" => Some(BcFormat::Bc4),
+            "bc5" | "ati2" => Some(BcFormat::Bc5),
+            "bc6h" | "bc6" => Some(BcFormat::Bc6h),
+            "bc7" => Some(BcFormat::Bc7),
+            _ => None,
+        }
+    }
+}
+
+/// Encoder quality preset.
+///
+/// Higher presets spend more time on endpoint refinement and, for BC7,
+/// try more block modes and channel rotations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BcQuality {
+    /// Principal-axis endpoints without refinement. BC7 uses mode 6 only.
+    Fast,
+    /// One least-squares refinement pass; BC7 also tries mode 5.
+    #[default]
+    Normal,
+    /// Extra refinement passes; BC7 searches modes 4, 5 and 6 with all
+    /// rotations.
+    High,
+}
+
+impl BcQuality {
+    /// Parses "fast", "normal" or "high".
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "fast" | "low" => Some(BcQuality::Fast),
+            "normal" | "default" | "medium" => Some(BcQuality::Normal),
+            "high" | "best" | "slow" => Some(BcQuality::High),
+            _ => None,
+        }
+    }
+
+    /// Number of least-squares refinement passes.
+    fn iterations(&self) -> usize {
+        match self {
+            BcQuality::Fast => 1,
+            BcQuality::Normal => 2,
+            BcQuality::High => 6,
+        }
+    }
+}
+
+// ============================================================================
+// Compression
+// ============================================================================
+
+/// A 4x4 block of RGBA pixels in raster order.
+pub(crate) type Block = [[f32; 4]; 16];
+
+/// Compresses an image into a stream of BCn blocks.
+///
+/// Blocks are stored row by row; partial blocks at the right and bottom
+/// edges repeat the last column/row. Gray input is expanded to RGB and
+/// missing alpha is opaque. LDR formats clamp to 0-1; BC6H clamps
+/// negatives to zero and large values to the half-float range.
+pub fn compress(image: &ImageData, format: BcFormat, quality: BcQuality) -> IoResult<Vec<u8>> {
+    if image.width == 0 || image.height == 0 {
+        return Err(IoError::EncodeError(
+            "cannot compress an empty image".into(),
+        ));
+    }
+    let blocks = extract_blocks(image);
+    let encode = |block: &Block| -> Vec<u8> {
+        match format {
+            BcFormat::Bc1 => bc1::encode_bc1(block, quality).to_vec(),
+            BcFormat::Bc3 => {
+                let mut out = bc4::encode(&channel(block, 3), quality).to_vec();
+                out.extend_from_slice(&bc1::encode_color(block, quality, false));
+                out
+            }
+            BcFormat::Bc4 => bc4::encode(&channel(block, 0), quality).to_vec(),
+            BcFormat::Bc5 => {
+                let mut out = bc4::encode(&channel(block, 0), quality).to_vec();
+                out.extend_from_slice(&bc4::encode(&channel(block, 1), quality));
+                out
+            }
+            BcFormat::Bc6h => bc6h::encode(block, quality).to_vec(),
+            BcFormat::Bc7 => bc7::encode(block, quality).to_vec(),
+        }
+    };
+
+    #[cfg(feature = "rayon")]
+    let out: Vec<u8> = {
+        use rayon::prelude::*;
+        blocks.par_iter().flat_map_iter(encode).collect()
+    };
+    #[cfg(not(feature = "rayon"))]
+    let out: Vec<u8> = blocks.iter().flat_map(encode).collect();
+
+    Ok(out)
+}
+
+/// Image pixels as RGBA: gray is expanded to RGB, missing alpha is opaque.
+fn rgba_pixels(image: &ImageData) -> Vec<[f32; 4]> {
+    let nc = (image.channels as usize).max(1);
+    image
+        .to_f32()
+        .chunks_exact(nc)
+        .map(|p| match nc {
+            1 => [p[0], p[0], p[0], 1.0],
+            2 => [p[0], p[0], p[0], p[1]],
+            3 => [p[0], p[1], p[2], 1.0],
+            _ => [p[0], p[1], p[2], p[3]],
+        })
+        .collect()
+}
+
+/// Splits an image into 4x4 RGBA blocks.
+fn extract_blocks(image: &ImageData) -> Vec<Block> {
+    let (w, h) = (image.width as usize, image.height as usize);
+    let pixels = rgba_pixels(image);
+
+    let mut blocks = Vec::with_capacity(w.div_ceil(4) * h.div_ceil(4));
+    for by in 0..h.div_ceil(4) {
+        for bx in 0..w.div_ceil(4) {
+            let mut block = [[0.0; 4]; 16];
+            for (i, px) in block.iter_mut().enumerate() {
+                let x = (bx * 4 + i % 4).min(w - 1);
+                let y = (by * 4 + i / 4).min(h - 1);
+                *px = pixels[y * w + x];
+            }
+            blocks.push(block);
+        }
+    }
+    blocks
+}
+
+/// One channel of a block, clamped to 0-1.
+fn channel(block: &Block, c: usize) -> [f32; 16] {
+    block.map(|px| px[c].clamp(0.0, 1.0))
+}
+
+/// Squared distance over the first `dims` channels.
+pub(crate) fn dist2(a: &[f32; 4], b: &[f32; 4], dims: usize) -> f32 {
+    (0..dims).map(|c| (a[c] - b[c]) * (a[c] - b[c])).sum()
+}
+
+/// Endpoints of the best-fit line through `points`.
+///
+/// Finds the principal axis by power iteration on the covariance matrix
+/// and returns the extreme projections of the points onto it.
+pub(crate) fn fit_line(points: &[[f32; 4]], dims: usize) -> ([f32; 4], [f32; 4]) {
+    let n = points.len() as f32;
+    let mut mean = [0.0f32; 4];
+    for p in points {
+        for c in 0..dims {
+            mean[c] += p[c] / n;
+        }
+    }
+
+    let mut cov = [[0.0f32; 4]; 4];
+    for p in points {
+        for i in 0..dims {
+            for j in 0..dims {
+                cov[i][j] += (p[i] - mean[i]) * (p[j] - mean[j]);
+            }
+        }
+    }
+    // Start from the covariance row of the widest channel, which cannot be
+    // orthogonal to the principal axis (a range vector can be, when
+    // channels are anti-correlated)
+    let widest = (0..dims).fold(0, |k, c| if cov[c][c] > cov[k][k] { c } else { k });
+    let mut axis = cov[widest];
+    for _ in 0..8 {
+        let mut next = [0.0f32; 4];
+        for i in 0..dims {
+            next[i] = (0..dims).map(|j| cov[i][j] * axis[j]).sum();
+        }
+        let len = next.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if len < 1e-12 {
+            break;
+        }
+        axis = next.map(|v| v / len);
+    }
+    let len2: f32 = axis.iter().map(|v| v * v).sum();
+    if len2 < 1e-12 {
+        return (mean, mean);
+    }
+
+    let (mut tmin, mut tmax) = (f32::MAX, f32::MIN);
+    for p in points {
+        let t: f32 = (0..dims).map(|c| (p[c] - mean[c]) * axis[c]).sum::<f32>() / len2;
+        tmin = tmin.min(t);
+        tmax = tmax.max(t);
+    }
+    let mut e0 = [0.0f32; 4];
+    let mut e1 = [0.0f32; 4];
+    for c in 0..dims {
+        e0[c] = mean[c] + axis[c] * tmin;
+        e1[c] = mean[c] + axis[c] * tmax;
+    }
+    (e0, e1)
+}
+
+/// Least-squares endpoints for given palette weights.
+///
+/// `weights[i]` is the fraction of the second endpoint in pixel `i`'s
+/// palette entry. Returns `None` when the system is degenerate (all
+/// pixels on one weight).
+pub(crate) fn least_squares(
+    points: &[[f32; 4]],
+    weights: &[f32],
+    dims: usize,
+) -> Option<([f32; 4], [f32; 4])> {
+    let (mut aa, mut ab, mut bb) = (0.0f32, 0.0f32, 0.0f32);
+    let mut ax = [0.0f32; 4];
+    let mut bx = [0.0f32; 4];
+    for (p, &w) in points.iter().zip(weights) {
+        let a = 1.0 - w;
+        aa += a * a;
+        ab += a * w;
+        bb += w * w;
+        for c in 0..dims {
+            ax[c] += a * p[c];
+            bx[c] += w * p[c];
+        }
+    }
+    let det = aa * bb - ab * ab;
+    if det.abs() < 1e-6 {
+        return None;
+    }
+    let mut e0 = [0.0f32; 4];
+    let mut e1 = [0.0f32; 4];
+    for c in 0..dims {
+        e0[c] = (bb * ax[c] - ab * bx[c]) / det;
+        e1[c] = (aa * bx[c] - ab * ax[c]) / det;
+    }
+    Some((e0, e1))
+}
+
+/// Little-endian bit writer for 128-bit blocks.
+pub(crate) struct BitWriter {
+    bits: u128,
+    pos: u32,
+}
+
+impl BitWriter {
+    pub(crate) fn new() -> Self {
+        Self { bits: 0, pos: 0 }
+    }
+
+    /// Appends the low `count` bits of `value`.
+    pub(crate) fn put(&mut self, value: u32, count: u32) {
+        let mask = if count == 32 {
+            u32::MAX
+        } else {
+            (1u32 << count) - 1
+        };
+        self.bits |= ((value & mask) as u128) << self.pos;
+        self.pos += count;
+    }
+
+    pub(crate) fn finish(self) -> [u8; 16] {
+        debug_assert_eq!(self.pos, 128);
+        self.bits.to_le_bytes()
+    }
+}
+
+// ============================================================================
+// Surface encoding
+// ============================================================================
+
+/// Pixel layout of an encoded texture surface, shared by the DDS and KTX2
+/// writers.
+#[cfg(any(feature = "dds", feature = "ktx"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SurfaceFormat {
+    R8,
+    Rg8,
+    Rgba8,
+    #[cfg_attr(not(feature = "dds"), allow(dead_code))]
+    Bgra8,
+    Rgba16Float,
+    Rgba32Float,
+    Bc(BcFormat),
+}
+
+#[cfg(any(feature = "dds", feature = "ktx"))]
+impl SurfaceFormat {
+    /// Bytes per pixel, or per 4x4 block for compressed formats.
+    pub(crate) fn block_bytes(&self) -> usize {
+        match self {
+            SurfaceFormat::R8 => 1,
+            SurfaceFormat::Rg8 => 2,
+            SurfaceFormat::Rgba8 | SurfaceFormat::Bgra8 => 4,
+            SurfaceFormat::Rgba16Float => 8,
+            SurfaceFormat::Rgba32Float => 16,
+            SurfaceFormat::Bc(bc) => bc.block_bytes(),
+        }
+    }
+
+    /// Encodes one surface.
+    pub(crate) fn encode(&self, image: &ImageData, quality: BcQuality) -> IoResult<Vec<u8>> {
+        let unorm = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let pixels = || rgba_pixels(image).into_iter();
+        let out = match self {
+            SurfaceFormat::Bc(bc) => return compress(image, *bc, quality),
+            SurfaceFormat::R8 => pixels().map(|p| unorm(p[0])).collect(),
+            SurfaceFormat::Rg8 => pixels().flat_map(|p| [unorm(p[0]), unorm(p[1])]).collect(),
+            SurfaceFormat::Rgba8 => pixels().flat_map(|p| p.map(unorm)).collect(),
+            SurfaceFormat::Bgra8 => pixels()
+                .flat_map(|p| [unorm(p[2]), unorm(p[1]), unorm(p[0]), unorm(p[3])])
+                .collect(),
+            SurfaceFormat::Rgba16Float => pixels()
+                .flat_map(|p| {
+                    p.into_iter()
+                        .flat_map(|v| half::f16::from_f32(v).to_le_bytes())
+                })
+                .collect(),
+            SurfaceFormat::Rgba32Float => pixels()
+                .flat_map(|p| p.into_iter().flat_map(f32::to_le_bytes))
+                .collect(),
+        };
+        Ok(out)
+    }
+}
+
+// ============================================================================
+// Texture layout
+// ============================================================================
+
+/// How the surfaces of a [`GpuTexture`] are arranged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextureLayout {
+    /// A single 2D image.
+    #[default]
+    Texture2D,
+    /// Six faces (+X, -X, +Y, -Y, +Z, -Z) per cube; a multiple of six
+    /// surfaces makes a cubemap array.
+    Cubemap,
+    /// A 2D texture array.
+    Array,
+}
+
+/// Surfaces of a GPU texture: one mip chain per face or array layer.
+///
+/// `surfaces[layer][level]`, where layers are cubemap faces (in +X, -X,
+/// +Y, -Y, +Z, -Z order, cube by cube) or array elements. All layers
+/// share the level 0 size and mip count.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::bcn::GpuTexture;
+/// use vfx_io::imagebufalgo::MipmapOptions;
+///
+/// let tex = GpuTexture::cubemap(faces)?.with_mipmaps(&MipmapOptions::default())?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct GpuTexture {
+    /// Surface arrangement.
+    pub layout: TextureLayout,
+    /// Mip chains, `surfaces[layer][level]`.
+    pub surfaces: Vec<Vec<ImageData>>,
+}
+
+impl GpuTexture {
+    /// A 2D texture without mipmaps.
+    pub fn from_image(image: ImageData) -> Self {
+        Self {
+            layout: TextureLayout::Texture2D,
+            surfaces: vec![vec![image]],
+        }
+    }
+
+    /// A cubemap (or cubemap array) from faces in +X, -X, +Y, -Y, +Z, -Z order.
+    pub fn cubemap(faces: Vec<ImageData>) -> IoResult<Self> {
+        if faces.is_empty() || faces.len() % 6 != 0 {
+            return Err(IoError::InvalidFile(format!(
+                "cubemap needs a multiple of 6 faces, got {}",
+                faces.len()
+            )));
+        }
+        if faces.iter().any(|f| f.width != f.height) {
+            return Err(IoError::InvalidFile("cubemap faces must be square".into()));
+        }
+        Self::with_layout(TextureLayout::Cubemap, faces)
+    }
+
+    /// A 2D texture array.
+    pub fn array(layers: Vec<ImageData>) -> IoResult<Self> {
+        if layers.is_empty() {
+            return Err(IoError::MissingData("texture array has no layers".into()));
+        }
+        Self::with_layout(TextureLayout::Array, layers)
+    }
+
+    fn with_layout(layout: TextureLayout, images: Vec<ImageData>) -> IoResult<Self> {
+        let (w, h) = (images[0].width, images[0].height);
+        if images.iter().any(|i| i.width != w || i.height != h) {
+            return Err(IoError::InvalidFile("texture layers differ in size".into()));
+        }
+        Ok(Self {
+            layout,
+            surfaces: images.into_iter().map(|i| vec![i]).collect(),
+        })
+    }
+
+    /// Replaces each layer's mip chain with a full chain generated from
+    /// level 0 by [`make_texture`].
+    pub fn with_mipmaps(mut self, options: &MipmapOptions) -> IoResult<Self> {
+        for chain in &mut self.surfaces {
+            let base = ImageBuf::from_image_data(&chain[0]);
+            *chain = make_texture(&base, options)
+                .iter()
+                .map(|level| level.to_image_data())
+                .collect::<IoResult<Vec<_>>>()?;
+        }
+        Ok(self)
+    }
+
+    /// Level 0 width.
+    pub fn width(&self) -> u32 {
+        self.surfaces
+            .first()
+            .and_then(|c| c.first())
+            .map_or(0, |i| i.width)
+    }
+
+    /// Level 0 height.
+    pub fn height(&self) -> u32 {
+        self.surfaces
+            .first()
+            .and_then(|c| c.first())
+            .map_or(0, |i| i.height)
+    }
+
+    /// Number of mip levels.
+    pub fn mip_count(&self) -> u32 {
+        self.surfaces.first().map_or(0, |c| c.len() as u32)
+    }
+
+    /// Number of array elements (cubes for cubemaps).
+    pub fn array_size(&self) -> u32 {
+        match self.layout {
+            TextureLayout::Cubemap => self.surfaces.len() as u32 / 6,
+            _ => self.surfaces.len() as u32,
+        }
+    }
+
+    /// Checks that every layer has the same mip chain.
+    #[cfg(any(feature = "dds", feature = "ktx"))]
+    pub(crate) fn validate(&self) -> IoResult<()> {
+        let levels = self.mip_count();
+        if levels == 0 || self.width() == 0 || self.height() == 0 {
+            return Err(IoError::MissingData("texture has no surfaces".into()));
+        }
+        for chain in &self.surfaces {
+            if chain.len() as u32 != levels {
+                return Err(IoError::InvalidFile(
+                    "texture layers differ in mip count".into(),
+                ));
+            }
+            for (level, image) in chain.iter().enumerate() {
+                let w = (self.width() >> level).max(1);
+                let h = (self.height() >> level).max(1);
+                if image.width != w || image.height != h {
+                    return Err(IoError::InvalidFile(format!(
+                        "mip level {} is {}x{}, expected {}x{}",
+                        level, image.width, image.height, w, h
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Mip generation settings for the texture writers: clamped edges and
+/// straight alpha.
+#[cfg(any(feature = "dds", feature = "ktx"))]
+pub(crate) fn default_mip_options(
+    filter: crate::imagebufalgo::MipmapFilter,
+    srgb: bool,
+) -> MipmapOptions {
+    MipmapOptions {
+        filter,
+        srgb,
+        premultiply_alpha: false,
+        wrap: crate::imagebuf::WrapMode::Clamp,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A diagonal gradient with a hard edge on a block boundary. Single
+    /// subset modes fit a line per block, so every channel follows x + y.
+    fn test_image(w: u32, h: u32, alpha: bool) -> ImageData {
+        let mut data = Vec::with_capacity((w * h * 4) as usize);
+        for y in 0..h {
+            for x in 0..w {
+                let t = (x + y) as f32 / (w + h - 2) as f32;
+                let edge = if x >= 8 { 0.8 } else { 0.1 };
+                let a = if alpha { 1.0 - t * 0.75 } else { 1.0 };
+                data.extend_from_slice(&[t, t * 0.5 + 0.25, edge, a]);
+            }
+        }
+        ImageData::from_f32(w, h, 4, data)
+    }
+
+    fn rmse(a: &[f32], b: &[f32], nc: usize, channels: &[usize]) -> f32 {
+        let mut sum = 0.0;
+        let mut n = 0;
+        for (pa, pb) in a.chunks_exact(nc).zip(b.chunks_exact(4)) {
+            for &c in channels {
+                sum += (pa[c] - pb[c]).powi(2);
+                n += 1;
+            }
+        }
+        (sum / n as f32).sqrt()
+    }
+
+    #[test]
+    fn test_compressed_size() {
+        assert_eq!(BcFormat::Bc1.compressed_size(16, 16), 128);
+        assert_eq!(BcFormat::Bc7.compressed_size(5, 3), 2 * 16);
+        assert_eq!(BcFormat::Bc4.compressed_size(1, 1), 8);
+        assert_eq!(BcFormat::from_name("DXT5"), Some(BcFormat::Bc3));
+        assert_eq!(BcQuality::from_name("high"), Some(BcQuality::High));
+    }
+
+    #[test]
+    fn test_ldr_roundtrip() {
+        let cases: [(BcFormat, &[usize], f32); 5] = [
+            (BcFormat::Bc1, &[0, 1, 2], 0.02),
+            (BcFormat::Bc3, &[0, 1, 2, 3], 0.02),
+            (BcFormat::Bc4, &[0], 0.015),
+            (BcFormat::Bc5, &[0, 1], 0.015),
+            (BcFormat::Bc7, &[0, 1, 2, 3], 0.01),
+        ];
+        for (format, channels, limit) in cases {
+            let image = test_image(13, 10, format != BcFormat::Bc1);
+            let src = image.to_f32();
+            for quality in [BcQuality::Fast, BcQuality::Normal, BcQuality::High] {
+                let data = compress(&image, format, quality).unwrap();
+                assert_eq!(data.len(), format.compressed_size(13, 10));
+                let out = decode::decode(&data, 13, 10, format);
+                let err = rmse(&src, &out, 4, channels);
+                assert!(err < limit, "{:?} {:?}: rmse {}", format, quality, err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_quality_presets_improve_bc7() {
+        let image = test_image(32, 32, true);
+        let src = image.to_f32();
+        let err = |q| {
+            let out = decode::decode(
+                &compress(&image, BcFormat::Bc7, q).unwrap(),
+                32,
+                32,
+                BcFormat::Bc7,
+            );
+            rmse(&src, &out, 4, &[0, 1, 2, 3])
+        };
+        assert!(err(BcQuality::High) <= err(BcQuality::Fast));
+    }
+
+    #[test]
+    fn test_bc1_cutout_alpha() {
+        let mut data = Vec::new();
+        for i in 0..16 {
+            let a = if i % 3 == 0 { 0.0 } else { 1.0 };
+            data.extend_from_slice(&[0.2, 0.6, 0.9, a]);
+        }
+        let image = ImageData::from_f32(4, 4, 4, data);
+        let out = decode::decode(
+            &compress(&image, BcFormat::Bc1, BcQuality::Normal).unwrap(),
+            4,
+            4,
+            BcFormat::Bc1,
+        );
+        for i in 0..16 {
+            let expected = if i % 3 == 0 { 0.0 } else { 1.0 };
+            assert_eq!(out[i * 4 + 3], expected);
+            if expected == 1.0 {
+                assert!((out[i * 4 + 1] - 0.6).abs() < 0.02);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bc6h_roundtrip() {
+        let mut data = Vec::new();
+        for y in 0..8 {
+            for x in 0..8 {
+                let t = (x + y) as f32;
+                data.extend_from_slice(&[1.0 + t * 0.2, 0.25 + 0.02 * t, 12.0 - t * 0.5]);
+            }
+        }
+        let image = ImageData::from_f32(8, 8, 3, data.clone());
+        for quality in [BcQuality::Fast, BcQuality::Normal, BcQuality::High] {
+            let out = decode::decode(
+                &compress(&image, BcFormat::Bc6h, quality).unwrap(),
+                8,
+                8,
+                BcFormat::Bc6h,
+            );
+            let worst = data
+                .chunks_exact(3)
+                .zip(out.chunks_exact(4))
+                .flat_map(|(s, d)| (0..3).map(move |c| (s[c] - d[c]).abs() / s[c]))
+                .fold(0.0f32, f32::max);
+            assert!(worst < 0.05, "{:?}: relative error {}", quality, worst);
+        }
+    }
+
+    #[test]
+    fn test_anticorrelated_channels() {
+        // Red rises while green falls: the principal axis is (1, -1)
+        let data: Vec<f32> = (0..16)
+            .flat_map(|i| {
+                let t = i as f32 / 15.0;
+                [t, 1.0 - t, 0.25, 1.0]
+            })
+            .collect();
+        let image = ImageData::from_f32(4, 4, 4, data);
+        let src = image.to_f32();
+        // BC1 has four colors for a full 0-1 ramp; collapsing to the mean
+        // would be ~0.33
+        for (format, limit) in [(BcFormat::Bc1, 0.09), (BcFormat::Bc7, 0.01)] {
+            for quality in [BcQuality::Fast, BcQuality::High] {
+                let out = decode::decode(&compress(&image, format, quality).unwrap(), 4, 4, format);
+                let err = rmse(&src, &out, 4, &[0, 1, 2]);
+                assert!(err < limit, "{:?} {:?} rmse {}", format, quality, err);
+            }
+        }
+    }
+
+    #[test]
+    fn test_solid_blocks_are_exact() {
+        let image = ImageData::from_u8(4, 4, 4, [64u8, 128, 192, 255].repeat(16));
+        let src = image.to_f32();
+        for format in [BcFormat::Bc3, BcFormat::Bc7] {
+            let out = decode::decode(
+                &compress(&image, format, BcQuality::Normal).unwrap(),
+                4,
+                4,
+                format,
+            );
+            assert!(rmse(&src, &out, 4, &[0, 1, 2, 3]) < 0.005, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_gpu_texture_layouts() {
+        let face = ImageData::from_f32(8, 8, 4, vec![0.5; 256]);
+        let cube = GpuTexture::cubemap(vec![face.clone(); 6]).unwrap();
+        assert_eq!(cube.array_size(), 1);
+        assert!(GpuTexture::cubemap(vec![face.clone(); 5]).is_err());
+
+        let tex = GpuTexture::array(vec![face.clone(); 3])
+            .unwrap()
+            .with_mipmaps(&MipmapOptions::default())
+            .unwrap();
+        assert_eq!(tex.mip_count(), 4);
+        assert_eq!(tex.array_size(), 3);
+        assert!(tex.surfaces.iter().all(|chain| chain.len() == 4));
+        assert_eq!(tex.surfaces[2][3].width, 1);
+    }
+}
@@ -0,0 +1,1044 @@
+//! Windows Bitmap (BMP) format support.
+//!
+//! Provides reading and writing of BMP/DIB files, still common for
+//! reference boards, scans and older tools.
+//!
+//! # Overview
+//!
+//! The reader handles the common header versions and pixel layouts:
+//! - `BITMAPCOREHEADER` (OS/2), `BITMAPINFOHEADER`, V2-V5 headers
+//! - 1, 4 and 8-bit palette images, with RLE4/RLE8 compression
+//! - 16-bit (555/565), 24-bit and 32-bit direct color
+//! - Channel masks (`BI_BITFIELDS`, `BI_ALPHABITFIELDS`), including alpha
+//! - Bottom-up and top-down row order
+//!
+//! Palette images with only gray entries are read as one channel.
+//! For 32-bit images without an alpha mask, the fourth byte is used as alpha
+//! only if it is non-zero somewhere, as many writers leave it zeroed.
+//!
+//! The writer stores 1-channel images as 8-bit with a gray palette
+//! (optionally RLE8 compressed), RGB as 24-bit and images with alpha as
+//! 32-bit with a V4 header and channel masks.
+//!
+//! # Examples
+//!
+//! Simple usage:
+//! ```ignore
+//! use vfx_io::bmp;
+//!
+//! let image = bmp::read("board.bmp")?;
+//! bmp::write("output.bmp", &image)?;
+//! ```
+//!
+//! With options:
+//! ```ignore
+//! use vfx_io::bmp::{BmpWriter, BmpWriterOptions};
+//! use vfx_io::FormatWriter;
+//!
+//! let writer = BmpWriter::with_options(BmpWriterOptions { rle: true });
+//! writer.write("mask.bmp", &image)?;
+//! ```
+
+use crate::{AttrValue, FormatReader, FormatWriter, ImageData, IoError, IoResult, Metadata, PixelData, PixelFormat};
+use std::io::Write;
+use std::path::Path;
+
+/// BMP file magic bytes.
+const BMP_MAGIC: &[u8; 2] = b"BM";
+
+/// Size of the file header preceding the DIB header.
+const FILE_HEADER_SIZE: usize = 14;
+
+/// Size of `BITMAPCOREHEADER`.
+const CORE_HEADER_SIZE: usize = 12;
+
+/// Size of `BITMAPINFOHEADER`.
+const INFO_HEADER_SIZE: usize = 40;
+
+/// Size of `BITMAPV4HEADER`.
+const V4_HEADER_SIZE: usize = 108;
+
+/// Compression types.
+const BI_RGB: u32 = 0;
+const BI_RLE8: u32 = 1;
+const BI_RLE4: u32 = 2;
+const BI_BITFIELDS: u32 = 3;
+const BI_ALPHABITFIELDS: u32 = 6;
+
+/// `LCS_sRGB` color space tag for V4 headers.
+const LCS_SRGB: u32 = 0x7352_4742;
+
+/// Largest RLE image read, in pixels.
+const MAX_RLE_PIXELS: usize = 16384 * 16384;
+
+// ============================================================================
+// Reader Options
+// ============================================================================
+
+/// Options for reading BMP files.
+///
+/// Currently minimal - BMP reading is mostly automatic.
+#[derive(Debug, Clone, Default)]
+pub struct BmpReaderOptions {
+    /// Reserved for future use.
+    _reserved: (),
+}
+
+// ============================================================================
+// Writer Options
+// ============================================================================
+
+/// Options for writing BMP files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::bmp::{BmpWriter, BmpWriterOptions};
+/// use vfx_io::FormatWriter;
+///
+/// let writer = BmpWriter::with_options(BmpWriterOptions { rle: true });
+/// writer.write("mask.bmp", &image)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BmpWriterOptions {
+    /// Use RLE8 compression for 1-channel images. Default: false.
+    /// Color images are always stored uncompressed.
+    pub rle: bool,
+}
+
+// ============================================================================
+// Header
+// ============================================================================
+
+/// Parsed file and DIB header.
+#[derive(Debug, Clone)]
+struct BmpHeader {
+    pixel_offset: usize,
+    header_size: usize,
+    width: usize,
+    height: usize,
+    top_down: bool,
+    bits: u16,
+    compression: u32,
+    x_ppm: i32,
+    y_ppm: i32,
+    colors_used: usize,
+    /// Red, green, blue and alpha masks for direct color images.
+    masks: [u32; 4],
+    /// Byte offset of the palette.
+    palette_offset: usize,
+    /// Bytes per palette entry (3 for core headers, 4 otherwise).
+    palette_entry_size: usize,
+}
+
+impl BmpHeader {
+    fn parse(data: &[u8]) -> IoResult<Self> {
+        if data.len() < FILE_HEADER_SIZE + CORE_HEADER_SIZE || &data[0..2] != BMP_MAGIC {
+            return Err(IoError::InvalidFile("BMP magic not found".into()));
+        }
+
+        let pixel_offset = read_u32(data, 10) as usize;
+        let header_size = read_u32(data, 14) as usize;
+        if data.len() < FILE_HEADER_SIZE + header_size.min(INFO_HEADER_SIZE) {
+            return Err(IoError::InvalidFile("BMP header truncated".into()));
+        }
+
+        if header_size == CORE_HEADER_SIZE {
+            let width = read_u16(data, 18) as usize;
+            let height = read_u16(data, 20) as i16;
+            let bits = read_u16(data, 24);
+            return Ok(Self {
+                pixel_offset,
+                header_size,
+                width,
+                height: height.unsigned_abs() as usize,
+                top_down: height < 0,
+                bits,
+                compression: BI_RGB,
+                x_ppm: 0,
+                y_ppm: 0,
+                colors_used: 0,
+                masks: default_masks(bits),
+                palette_offset: FILE_HEADER_SIZE + header_size,
+                palette_entry_size: 3,
+            });
+        }
+
+        if header_size < INFO_HEADER_SIZE {
+            return Err(IoError::InvalidFile(format!("unsupported BMP header size: {}", header_size)));
+        }
+
+        let width = read_i32(data, 18);
+        let height = read_i32(data, 22);
+        let bits = read_u16(data, 28);
+        let compression = read_u32(data, 30);
+
+        if width <= 0 || height == 0 || height == i32::MIN {
+            return Err(IoError::InvalidFile(format!("invalid BMP dimensions: {}x{}", width, height)));
+        }
+
+        // Masks are part of V2+ headers, or follow an INFO header
+        let mut masks = default_masks(bits);
+        let mut palette_offset = FILE_HEADER_SIZE + header_size;
+        if compression == BI_BITFIELDS || compression == BI_ALPHABITFIELDS {
+            let count = if compression == BI_ALPHABITFIELDS || header_size >= 56 { 4 } else { 3 };
+            let start = FILE_HEADER_SIZE + INFO_HEADER_SIZE;
+            if data.len() < start + count * 4 {
+                return Err(IoError::InvalidFile("BMP channel masks truncated".into()));
+            }
+            masks = [0; 4];
+            for (i, mask) in masks.iter_mut().enumerate().take(count) {
+                *mask = read_u32(data, start + i * 4);
+            }
+            if header_size == INFO_HEADER_SIZE {
+                palette_offset += count * 4;
+            }
+        } else if header_size >= 56 && bits == 32 && data.len() >= FILE_HEADER_SIZE + 56 {
+            // V3+ headers may declare an alpha mask for plain 32-bit images
+            let alpha = read_u32(data, FILE_HEADER_SIZE + 52);
+            if alpha != 0 {
+                masks[3] = alpha;
+            }
+        }
+
+        Ok(Self {
+            pixel_offset,
+            header_size,
+            width: width as usize,
+            height: height.unsigned_abs() as usize,
+            top_down: height < 0,
+            bits,
+            compression,
+            x_ppm: read_i32(data, 38),
+            y_ppm: read_i32(data, 42),
+            colors_used: read_u32(data, 46) as usize,
+            masks,
+            palette_offset,
+            palette_entry_size: 4,
+        })
+    }
+
+    /// Bytes per stored row, padded to 4 bytes.
+    fn row_stride(&self) -> usize {
+        (self.bits as usize * self.width).div_ceil(32) * 4
+    }
+
+    /// Bytes of uncompressed pixel data, checked against overflow.
+    fn pixel_data_size(&self) -> IoResult<usize> {
+        (self.bits as usize)
+            .checked_mul(self.width)
+            .and_then(|bits| (bits.div_ceil(32) * 4).checked_mul(self.height))
+            .ok_or_else(|| {
+                IoError::InvalidFile(format!("BMP dimensions too large: {}x{}", self.width, self.height))
+            })
+    }
+
+    /// Maps a stored row to an image row.
+    fn image_row(&self, row: usize) -> usize {
+        if self.top_down {
+            row
+        } else {
+            self.height - 1 - row
+        }
+    }
+
+    fn compression_name(&self) -> &'static str {
+        match self.compression {
+            BI_RGB => "none",
+            BI_RLE8 => "rle8",
+            BI_RLE4 => "rle4",
+            BI_BITFIELDS => "bitfields",
+            BI_ALPHABITFIELDS => "alphabitfields",
+            _ => "unknown",
+        }
+    }
+}
+
+/// Default channel masks for uncompressed direct color images.
+fn default_masks(bits: u16) -> [u32; 4] {
+    match bits {
+        16 => [0x7C00, 0x03E0, 0x001F, 0],
+        24 | 32 => [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0],
+        _ => [0; 4],
+    }
+}
+
+// ============================================================================
+// BmpReader
+// ============================================================================
+
+/// BMP file reader.
+///
+/// Implements [`FormatReader`] for reading BMP files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::bmp::BmpReader;
+/// use vfx_io::FormatReader;
+///
+/// let reader = BmpReader::new();
+/// let image = reader.read("board.bmp")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct BmpReader {
+    #[allow(dead_code)]
+    options: BmpReaderOptions,
+}
+
+impl BmpReader {
+    /// Creates a new reader with default options.
+    pub fn new() -> Self {
+        Self::with_options(BmpReaderOptions::default())
+    }
+
+    /// Internal read implementation.
+    fn read_impl(&self, data: &[u8]) -> IoResult<ImageData> {
+        let header = BmpHeader::parse(data)?;
+        if header.pixel_offset > data.len() {
+            return Err(IoError::InvalidFile("BMP pixel offset out of range".into()));
+        }
+
+        let mut image = match header.bits {
+            1 | 4 | 8 => self.read_indexed(data, &header)?,
+            16 | 24 | 32 => self.read_direct(data, &header)?,
+            bits => return Err(IoError::UnsupportedBitDepth(format!("BMP {} bits per pixel", bits))),
+        };
+
+        image.metadata = self.read_metadata(&header);
+        Ok(image)
+    }
+
+    /// Reads palette images, uncompressed or RLE.
+    fn read_indexed(&self, data: &[u8], header: &BmpHeader) -> IoResult<ImageData> {
+        let palette = self.read_palette(data, header)?;
+        let pixels = &data[header.pixel_offset..];
+        let (width, height) = (header.width, header.height);
+
+        // Validate the size before allocating, it comes straight from the header
+        let pixel_count = width
+            .checked_mul(height)
+            .ok_or_else(|| IoError::InvalidFile(format!("BMP dimensions too large: {}x{}", width, height)))?;
+        if header.compression == BI_RGB {
+            if pixels.len() < header.pixel_data_size()? {
+                return Err(IoError::InvalidFile("BMP pixel data truncated".into()));
+            }
+        } else if pixel_count > MAX_RLE_PIXELS {
+            // A few RLE bytes can describe any size, so there is no data to check against
+            return Err(IoError::InvalidFile(format!("BMP RLE image too large: {}x{}", width, height)));
+        }
+        let mut indices = vec![0u8; pixel_count];
+
+        match (header.compression, header.bits) {
+            (BI_RGB, bits) => {
+                let stride = header.row_stride();
+                let per_byte = 8 / bits as usize;
+                let mask = ((1u16 << bits) - 1) as u8;
+                for row in 0..height {
+                    let src = &pixels[row * stride..];
+                    let dst = &mut indices[header.image_row(row) * width..][..width];
+                    for (x, index) in dst.iter_mut().enumerate() {
+                        let byte = src[x / per_byte];
+                        let shift = 8 - bits as usize * (x % per_byte + 1);
+                        *index = (byte >> shift) & mask;
+                    }
+                }
+            }
+            (BI_RLE8, 8) => decode_rle(pixels, header, &mut indices, false)?,
+            (BI_RLE4, 4) => decode_rle(pixels, header, &mut indices, true)?,
+            (compression, bits) => {
+                return Err(IoError::UnsupportedFeature(format!(
+                    "BMP compression {} with {} bits per pixel",
+                    compression, bits
+                )))
+            }
+        }
+
+        let gray = palette.iter().all(|c| c[0] == c[1] && c[1] == c[2]);
+        let channels = if gray { 1 } else { 3 };
+        let mut out = Vec::with_capacity(width * height * channels);
+        for &index in &indices {
+            let color = palette.get(index as usize).copied().unwrap_or([0, 0, 0]);
+            out.extend_from_slice(&color[..channels]);
+        }
+
+        Ok(ImageData::from_u8(width as u32, height as u32, channels as u32, out))
+    }
+
+    /// Reads the color table as RGB triples.
+    fn read_palette(&self, data: &[u8], header: &BmpHeader) -> IoResult<Vec<[u8; 3]>> {
+        let max_colors = 1usize << header.bits;
+        let count = if header.colors_used == 0 {
+            max_colors
+        } else {
+            header.colors_used.min(max_colors)
+        };
+
+        // Some writers declare more colors than fit before the pixel data
+        let available = header.pixel_offset.saturating_sub(header.palette_offset) / header.palette_entry_size;
+        let count = count.min(available);
+        if count == 0 {
+            return Err(IoError::InvalidFile("BMP palette missing".into()));
+        }
+
+        Ok((0..count)
+            .map(|i| {
+                let entry = header.palette_offset + i * header.palette_entry_size;
+                [data[entry + 2], data[entry + 1], data[entry]]
+            })
+            .collect())
+    }
+
+    /// Reads 16, 24 and 32-bit direct color images.
+    fn read_direct(&self, data: &[u8], header: &BmpHeader) -> IoResult<ImageData> {
+        match header.compression {
+            BI_RGB | BI_BITFIELDS | BI_ALPHABITFIELDS => {}
+            compression => {
+                return Err(IoError::UnsupportedFeature(format!("BMP compression {}", compression)))
+            }
+        }
+        if header.bits == 24 && header.compression != BI_RGB {
+            return Err(IoError::InvalidFile("BMP channel masks on 24-bit image".into()));
+        }
+
+        let (width, height) = (header.width, header.height);
+        let stride = header.row_stride();
+        let pixels = &data[header.pixel_offset..];
+        if pixels.len() < header.pixel_data_size()? {
+            return Err(IoError::InvalidFile("BMP pixel data truncated".into()));
+        }
+
+        let bytes_per_pixel = header.bits as usize / 8;
+        let read_pixel = |row: usize, x: usize| -> u32 {
+            let p = &pixels[row * stride + x * bytes_per_pixel..];
+            match bytes_per_pixel {
+                2 => u16::from_le_bytes([p[0], p[1]]) as u32,
+                3 => u32::from_le_bytes([p[0], p[1], p[2], 0]),
+                _ => u32::from_le_bytes([p[0], p[1], p[2], p[3]]),
+            }
+        };
+
+        let mut masks = header.masks;
+
+        // Plain 32-bit images often carry alpha in the unused byte
+        if header.bits == 32 && masks[3] == 0 && header.compression == BI_RGB {
+            let has_alpha = (0..height).any(|row| (0..width).any(|x| read_pixel(row, x) >> 24 != 0));
+            if has_alpha {
+                masks[3] = 0xFF00_0000;
+            }
+        }
+
+        let channels = if masks[3] != 0 { 4 } else { 3 };
+        let fields: Vec<MaskField> = masks[..channels].iter().map(|&m| MaskField::new(m)).collect();
+        let wide = fields.iter().any(|f| f.bits > 8);
+        let mut out = Vec::with_capacity(width * height * channels);
+
+        for y in 0..height {
+            let row = if header.top_down { y } else { height - 1 - y };
+            for x in 0..width {
+                let value = read_pixel(row, x);
+                for field in &fields {
+                    out.push(field.extract(value, if wide { 16 } else { 8 }));
+                }
+            }
+        }
+
+        if wide {
+            return Ok(ImageData {
+                width: width as u32,
+                height: height as u32,
+                channels: channels as u32,
+                format: PixelFormat::U16,
+                data: PixelData::U16(out.into_iter().map(|v| v as u16).collect()),
+                metadata: Metadata::default(),
+            });
+        }
+
+        let out = out.into_iter().map(|v| v as u8).collect();
+        Ok(ImageData::from_u8(width as u32, height as u32, channels as u32, out))
+    }
+
+    /// Builds metadata from the header.
+    fn read_metadata(&self, header: &BmpHeader) -> Metadata {
+        let mut metadata = Metadata {
+            colorspace: Some("sRGB".to_string()),
+            ..Metadata::default()
+        };
+        metadata.attrs.set("BMP:BitsPerPixel", AttrValue::UInt(header.bits as u32));
+        metadata.attrs.set("BMP:Compression", AttrValue::Str(header.compression_name().into()));
+        metadata.attrs.set("BMP:HeaderSize", AttrValue::UInt(header.header_size as u32));
+
+        if header.x_ppm > 0 && header.y_ppm > 0 {
+            let x_dpi = (header.x_ppm as f64 * 0.0254) as f32;
+            let y_dpi = (header.y_ppm as f64 * 0.0254) as f32;
+            metadata.attrs.set("XResolution", AttrValue::Float(x_dpi));
+            metadata.attrs.set("YResolution", AttrValue::Float(y_dpi));
+            metadata.attrs.set("ResolutionUnit", AttrValue::Str("dpi".into()));
+            if (x_dpi - y_dpi).abs() < f32::EPSILON {
+                metadata.dpi = Some(x_dpi);
+            }
+        }
+
+        metadata
+    }
+}
+
+impl Default for BmpReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatReader<BmpReaderOptions> for BmpReader {
+    /// Returns "BMP".
+    fn format_name(&self) -> &'static str {
+        "BMP"
+    }
+
+    /// Returns `["bmp", "dib"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["bmp", "dib"]
+    }
+
+    /// Checks for BMP magic bytes (BM).
+    fn can_read(&self, header: &[u8]) -> bool {
+        is_bmp_header(header)
+    }
+
+    /// Reads a BMP file from disk.
+    fn read<P: AsRef<Path>>(&self, path: P) -> IoResult<ImageData> {
+        let data = std::fs::read(path.as_ref())?;
+        self.read_impl(&data)
+    }
+
+    /// Reads a BMP from a byte slice.
+    fn read_from_memory(&self, data: &[u8]) -> IoResult<ImageData> {
+        self.read_impl(data)
+    }
+
+    /// Creates reader with custom options.
+    fn with_options(options: BmpReaderOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// A channel mask split into shift and bit count.
+struct MaskField {
+    shift: u32,
+    bits: u32,
+}
+
+impl MaskField {
+    fn new(mask: u32) -> Self {
+        if mask == 0 {
+            return Self { shift: 0, bits: 0 };
+        }
+        let shift = mask.trailing_zeros();
+        Self {
+            shift,
+            bits: (mask >> shift).trailing_ones(),
+        }
+    }
+
+    /// Extracts the field and scales it to `out_bits`.
+    fn extract(&self, value: u32, out_bits: u32) -> u32 {
+        let out_max = (1u32 << out_bits) - 1;
+        if self.bits == 0 {
+            return out_max;
+        }
+        let max = ((1u64 << self.bits) - 1) as u32;
+        let v = (value >> self.shift) & max;
+        ((v as u64 * out_max as u64 + max as u64 / 2) / max as u64) as u32
+    }
+}
+
+/// Decodes RLE8 or RLE4 pixel data into top-down indices.
+///
+/// Pixels skipped by delta or end-of-line codes keep index 0.
+fn decode_rle(src: &[u8], header: &BmpHeader, indices: &mut [u8], nibbles: bool) -> IoResult<()> {
+    let (width, height) = (header.width, header.height);
+    let (mut x, mut row) = (0usize, 0usize);
+    let mut i = 0usize;
+
+    let mut put = |x: usize, row: usize, value: u8| {
+        if x < width && row < height {
+            indices[header.image_row(row) * width + x] = value;
+        }
+    };
+
+    while i + 1 < src.len() && row < height {
+        let count = src[i] as usize;
+        let value = src[i + 1];
+        i += 2;
+
+        if count > 0 {
+            // Encoded run, RLE4 alternates the two nibbles
+            for k in 0..count {
+                let v = if nibbles {
+                    if k % 2 == 0 { value >> 4 } else { value & 0x0F }
+                } else {
+                    value
+                };
+                put(x, row, v);
+                x += 1;
+            }
+            continue;
+        }
+
+        match value {
+            0 => {
+                x = 0;
+                row += 1;
+            }
+            1 => break,
+            2 => {
+                if i + 1 >= src.len() {
+                    break;
+                }
+                x += src[i] as usize;
+                row += src[i + 1] as usize;
+                i += 2;
+            }
+            n => {
+                // Absolute run, padded to a 16-bit boundary
+                let n = n as usize;
+                let bytes = if nibbles { n.div_ceil(2) } else { n };
+                if i + bytes > src.len() {
+                    return Err(IoError::InvalidFile("BMP RLE data truncated".into()));
+                }
+                for k in 0..n {
+                    let v = if nibbles {
+                        let byte = src[i + k / 2];
+                        if k % 2 == 0 { byte >> 4 } else { byte & 0x0F }
+                    } else {
+                        src[i + k]
+                    };
+                    put(x, row, v);
+                    x += 1;
+                }
+                i += bytes + bytes % 2;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// BmpWriter
+// ============================================================================
+
+/// BMP file writer.
+///
+/// Implements [`FormatWriter`] for writing BMP files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::bmp::BmpWriter;
+/// use vfx_io::FormatWriter;
+///
+/// let writer = BmpWriter::new();
+/// writer.write("board.bmp", &image)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct BmpWriter {
+    options: BmpWriterOptions,
+}
+
+impl BmpWriter {
+    /// Creates a new writer with default options.
+    pub fn new() -> Self {
+        Self::with_options(BmpWriterOptions::default())
+    }
+
+    /// Internal write implementation.
+    fn write_impl<W: Write>(&self, mut writer: W, image: &ImageData) -> IoResult<()> {
+        let width = image.width as usize;
+        let height = image.height as usize;
+        let channels = image.channels as usize;
+        if width == 0 || height == 0 || width > i32::MAX as usize || height > i32::MAX as usize {
+            return Err(IoError::EncodeError(format!("invalid BMP dimensions: {}x{}", width, height)));
+        }
+        if !(1..=4).contains(&channels) {
+            return Err(IoError::EncodeError(format!("unsupported channels: {}", channels)));
+        }
+
+        let samples = image.to_u8();
+        let has_alpha = channels == 2 || channels == 4;
+        let (bits, header_size) = match channels {
+            1 => (8u16, INFO_HEADER_SIZE),
+            3 => (24, INFO_HEADER_SIZE),
+            _ => (32, V4_HEADER_SIZE),
+        };
+        let palette_size = if bits == 8 { 256 * 4 } else { 0 };
+        let rle = bits == 8 && self.options.rle;
+
+        // Bottom-up rows
+        let stride = (bits as usize * width).div_ceil(32) * 4;
+        let mut rows = vec![0u8; stride * height];
+        for y in 0..height {
+            let dst = &mut rows[(height - 1 - y) * stride..][..stride];
+            let src = &samples[y * width * channels..][..width * channels];
+            for (x, px) in src.chunks_exact(channels).enumerate() {
+                match channels {
+                    1 => dst[x] = px[0],
+                    2 => dst[x * 4..x * 4 + 4].copy_from_slice(&[px[0], px[0], px[0], px[1]]),
+                    3 => dst[x * 3..x * 3 + 3].copy_from_slice(&[px[2], px[1], px[0]]),
+                    _ => dst[x * 4..x * 4 + 4].copy_from_slice(&[px[2], px[1], px[0], px[3]]),
+                }
+            }
+        }
+
+        let pixels = if rle {
+            encode_rle8(&rows, stride, width, height)
+        } else {
+            rows
+        };
+
+        let pixel_offset = FILE_HEADER_SIZE + header_size + palette_size;
+        let file_size = pixel_offset + pixels.len();
+        let (x_ppm, y_ppm) = resolution_from_metadata(&image.metadata);
+
+        let mut out = Vec::with_capacity(file_size);
+        out.extend_from_slice(BMP_MAGIC);
+        out.extend_from_slice(&(file_size as u32).to_le_bytes());
+        out.extend_from_slice(&[0; 4]);
+        out.extend_from_slice(&(pixel_offset as u32).to_le_bytes());
+
+        out.extend_from_slice(&(header_size as u32).to_le_bytes());
+        out.extend_from_slice(&(width as i32).to_le_bytes());
+        out.extend_from_slice(&(height as i32).to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&bits.to_le_bytes());
+        let compression = if rle {
+            BI_RLE8
+        } else if has_alpha {
+            BI_BITFIELDS
+        } else {
+            BI_RGB
+        };
+        out.extend_from_slice(&compression.to_le_bytes());
+        out.extend_from_slice(&(pixels.len() as u32).to_le_bytes());
+        out.extend_from_slice(&x_ppm.to_le_bytes());
+        out.extend_from_slice(&y_ppm.to_le_bytes());
+        let colors: u32 = if bits == 8 { 256 } else { 0 };
+        out.extend_from_slice(&colors.to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes());
+
+        if header_size == V4_HEADER_SIZE {
+            for mask in [0x00FF_0000u32, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000] {
+                out.extend_from_slice(&mask.to_le_bytes());
+            }
+            out.extend_from_slice(&LCS_SRGB.to_le_bytes());
+            // Endpoints and gamma are unused for sRGB
+            out.extend_from_slice(&[0; 48]);
+        }
+
+        if bits == 8 {
+            for i in 0..=255u8 {
+                out.extend_from_slice(&[i, i, i, 0]);
+            }
+        }
+
+        out.extend_from_slice(&pixels);
+        writer.write_all(&out)?;
+        Ok(())
+    }
+}
+
+impl Default for BmpWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatWriter<BmpWriterOptions> for BmpWriter {
+    /// Returns "BMP".
+    fn format_name(&self) -> &'static str {
+        "BMP"
+    }
+
+    /// Returns `["bmp", "dib"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["bmp", "dib"]
+    }
+
+    /// Writes a BMP file to disk.
+    fn write<P: AsRef<Path>>(&self, path: P, image: &ImageData) -> IoResult<()> {
+        let file = std::fs::File::create(path.as_ref())?;
+        self.write_impl(std::io::BufWriter::new(file), image)
+    }
+
+    /// Writes a BMP to a byte vector.
+    fn write_to_memory(&self, image: &ImageData) -> IoResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.write_impl(&mut buffer, image)?;
+        Ok(buffer)
+    }
+
+    /// Creates writer with custom options.
+    fn with_options(options: BmpWriterOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Encodes bottom-up 8-bit rows with RLE8.
+fn encode_rle8(rows: &[u8], stride: usize, width: usize, height: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(rows.len() / 2);
+
+    for row in 0..height {
+        let line = &rows[row * stride..][..width];
+        let mut i = 0usize;
+        while i < width {
+            let mut run = 1usize;
+            while i + run < width && run < 255 && line[i + run] == line[i] {
+                run += 1;
+            }
+            if run >= 2 {
+                out.extend_from_slice(&[run as u8, line[i]]);
+                i += run;
+                continue;
+            }
+
+            // Literal sequence until the next run of 2
+            let start = i;
+            while i < width && i - start < 255 && !(i + 1 < width && line[i] == line[i + 1]) {
+                i += 1;
+            }
+            let literal = &line[start..i];
+            if literal.len() < 3 {
+                // Absolute mode needs at least 3 pixels
+                for &v in literal {
+                    out.extend_from_slice(&[1, v]);
+                }
+            } else {
+                out.extend_from_slice(&[0, literal.len() as u8]);
+                out.extend_from_slice(literal);
+                if literal.len() % 2 == 1 {
+                    out.push(0);
+                }
+            }
+        }
+
+        // End of line, or end of bitmap on the last row
+        out.extend_from_slice(&[0, if row + 1 == height { 1 } else { 0 }]);
+    }
+
+    out
+}
+
+/// Returns the resolution in pixels per meter from metadata.
+fn resolution_from_metadata(metadata: &Metadata) -> (i32, i32) {
+    let x = metadata.attrs.get("XResolution").and_then(|v| v.as_f32()).or(metadata.dpi);
+    let y = metadata.attrs.get("YResolution").and_then(|v| v.as_f32()).or(metadata.dpi);
+    let to_ppm = |dpi: Option<f32>| dpi.map(|d| (d / 0.0254).round() as i32).unwrap_or(0);
+    (to_ppm(x), to_ppm(y))
+}
+
+// ============================================================================
+// Convenience Functions
+// ============================================================================
+
+/// Reads a BMP file with default options.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::bmp;
+///
+/// let image = bmp::read("board.bmp")?;
+/// ```
+pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
+    BmpReader::new().read(path)
+}
+
+/// Writes a BMP file with default options (uncompressed).
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::bmp;
+///
+/// bmp::write("output.bmp", &image)?;
+/// ```
+pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
+    BmpWriter::new().write(path, image)
+}
+
+/// Checks the magic bytes and reserved fields of a BMP file header.
+pub fn is_bmp_header(header: &[u8]) -> bool {
+    header.len() >= 2
+        && &header[0..2] == BMP_MAGIC
+        && (header.len() < 10 || header[6..10] == [0, 0, 0, 0])
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn read_i32(data: &[u8], offset: usize) -> i32 {
+    read_u32(data, offset) as i32
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgba_image() -> ImageData {
+        let data: Vec<u8> = (0..5 * 3 * 4).map(|i| (i * 7) as u8).collect();
+        ImageData::from_u8(5, 3, 4, data)
+    }
+
+    /// Builds an INFO header file around a palette and pixel data.
+    fn build_bmp(width: i32, height: i32, bits: u16, compression: u32, palette: &[[u8; 4]], pixels: &[u8]) -> Vec<u8> {
+        let offset = 14 + 40 + palette.len() * 4;
+        let mut out = Vec::new();
+        out.extend_from_slice(b"BM");
+        out.extend_from_slice(&((offset + pixels.len()) as u32).to_le_bytes());
+        out.extend_from_slice(&[0; 4]);
+        out.extend_from_slice(&(offset as u32).to_le_bytes());
+        out.extend_from_slice(&40u32.to_le_bytes());
+        out.extend_from_slice(&width.to_le_bytes());
+        out.extend_from_slice(&height.to_le_bytes());
+        out.extend_from_slice(&1u16.to_le_bytes());
+        out.extend_from_slice(&bits.to_le_bytes());
+        out.extend_from_slice(&compression.to_le_bytes());
+        out.extend_from_slice(&(pixels.len() as u32).to_le_bytes());
+        out.extend_from_slice(&[0; 8]);
+        out.extend_from_slice(&(palette.len() as u32).to_le_bytes());
+        out.extend_from_slice(&[0; 4]);
+        for entry in palette {
+            out.extend_from_slice(entry);
+        }
+        out.extend_from_slice(pixels);
+        out
+    }
+
+    /// Tests RGB, RGBA and gray roundtrips.
+    #[test]
+    fn test_roundtrip() {
+        for channels in 1..=4u32 {
+            let data: Vec<u8> = (0..7 * 3 * channels).map(|i| (i * 11) as u8).collect();
+            let image = ImageData::from_u8(7, 3, channels, data.clone());
+
+            let bytes = BmpWriter::new().write_to_memory(&image).expect("Write failed");
+            let loaded = BmpReader::new().read_from_memory(&bytes).expect("Read failed");
+
+            assert_eq!((loaded.width, loaded.height), (7, 3));
+            if channels == 2 {
+                // Gray + alpha is stored as RGBA
+                assert_eq!(loaded.channels, 4);
+                let loaded = loaded.to_u8();
+                assert_eq!(loaded[0..4], [data[0], data[0], data[0], data[1]]);
+            } else {
+                assert_eq!(loaded.channels, channels);
+                assert_eq!(loaded.to_u8(), data);
+            }
+        }
+    }
+
+    /// Tests RLE8 roundtrip.
+    #[test]
+    fn test_rle8_roundtrip() {
+        let mut data = vec![0u8; 40 * 4];
+        for (i, v) in data.iter_mut().enumerate() {
+            *v = if i % 40 < 20 { 9 } else { (i * 3) as u8 };
+        }
+        let image = ImageData::from_u8(40, 4, 1, data.clone());
+
+        let writer = BmpWriter::with_options(BmpWriterOptions { rle: true });
+        let bytes = writer.write_to_memory(&image).expect("Write failed");
+        let loaded = BmpReader::new().read_from_memory(&bytes).expect("Read failed");
+
+        assert_eq!(loaded.metadata.attrs.get("BMP:Compression").and_then(|v| v.as_str()), Some("rle8"));
+        assert_eq!(loaded.to_u8(), data);
+    }
+
+    /// Tests RLE4 with delta and absolute runs.
+    #[test]
+    fn test_rle4() {
+        let palette = [[0, 0, 255, 0], [0, 255, 0, 0], [255, 0, 0, 0]];
+        // Bottom row: run of 4 alternating 1/2, then absolute 0,1,2
+        // Top row: delta to x=2, run of 2 of index 1
+        let pixels = [4, 0x12, 0, 3, 0x01, 0x20, 0, 0, 0, 2, 2, 0, 2, 0x11, 0, 1];
+        let bytes = build_bmp(7, 2, 4, BI_RLE4, &palette, &pixels);
+        let image = BmpReader::new().read_from_memory(&bytes).expect("Read failed");
+
+        assert_eq!(image.channels, 3);
+        let data = image.to_u8();
+        let color = |x: usize, y: usize| &data[(y * 7 + x) * 3..][..3];
+        assert_eq!(color(0, 1), [0, 255, 0]);
+        assert_eq!(color(1, 1), [0, 0, 255]);
+        assert_eq!(color(4, 1), [255, 0, 0]);
+        assert_eq!(color(6, 1), [0, 0, 255]);
+        assert_eq!(color(1, 0), [255, 0, 0]);
+        assert_eq!(color(2, 0), [0, 255, 0]);
+        assert_eq!(color(3, 0), [0, 255, 0]);
+    }
+
+    /// Tests 1-bit top-down images and 16-bit 565 bitfields.
+    #[test]
+    fn test_1bit_and_565() {
+        let palette = [[0, 0, 0, 0], [255, 255, 255, 0]];
+        let pixels = [0b1010_0000, 0, 0, 0, 0b0100_0000, 0, 0, 0];
+        let image = BmpReader::new()
+            .read_from_memory(&build_bmp(3, -2, 1, BI_RGB, &palette, &pixels))
+            .expect("Read failed");
+        assert_eq!(image.channels, 1);
+        assert_eq!(image.to_u8(), [255, 0, 255, 0, 255, 0]);
+
+        let mut bytes = build_bmp(1, 1, 16, BI_BITFIELDS, &[], &[0x1F, 0xF8, 0, 0]);
+        let masks: Vec<u8> = [0xF800u32, 0x07E0, 0x001F].iter().flat_map(|m| m.to_le_bytes()).collect();
+        bytes.splice(54..54, masks);
+        bytes[10] += 12;
+        let image = BmpReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(image.to_u8(), [255, 0, 255]);
+    }
+
+    /// Tests that zero alpha bytes in 32-bit images are ignored.
+    #[test]
+    fn test_32bit_alpha_detection() {
+        let opaque = build_bmp(1, 1, 32, BI_RGB, &[], &[1, 2, 3, 0]);
+        let image = BmpReader::new().read_from_memory(&opaque).expect("Read failed");
+        assert_eq!(image.channels, 3);
+        assert_eq!(image.to_u8(), [3, 2, 1]);
+
+        let bytes = BmpWriter::new().write_to_memory(&rgba_image()).expect("Write failed");
+        let image = BmpReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(image.channels, 4);
+        assert_eq!(image.to_u8(), rgba_image().to_u8());
+    }
+
+    /// Tests that huge header dimensions fail before any allocation.
+    #[test]
+    fn test_huge_dimensions() {
+        let palette = [[0, 0, 0, 0], [255, 255, 255, 0]];
+        for (bits, compression) in [(8, BI_RGB), (8, BI_RLE8), (4, BI_RLE4), (24, BI_RGB)] {
+            let bytes = build_bmp(1 << 30, 1 << 30, bits, compression, &palette, &[0, 1]);
+            let result = BmpReader::new().read_from_memory(&bytes);
+            assert!(matches!(result, Err(IoError::InvalidFile(_))), "{} bits, compression {}", bits, compression);
+        }
+    }
+
+    /// Tests magic byte detection.
+    #[test]
+    fn test_can_read() {
+        let reader = BmpReader::new();
+        assert!(reader.can_read(b"BM\x46\0\0\0\0\0\0\0\x36\0"));
+        assert!(!reader.can_read(b"BM\x46\0\0\0\x01\0\0\0\x36\0"));
+        assert!(!reader.can_read(&[0x89, 0x50, 0x4E, 0x47]));
+    }
+}
@@ -16,6 +16,12 @@
 //!   metadata.xml       (optional)
 //! ```
 //!
+//! # Decoding
+//!
+//! Raw frames are developed by the [`dng`](crate::dng) decoder (lossless
+//! JPEG, linearization, demosaic, camera to ACES). Frames without a raw
+//! image, such as already-rendered TIFFs, are read with the TIFF reader.
+//!
 //! # Example
 //!
 //! ```rust,no_run
@@ -34,6 +40,7 @@
 
 use std::path::{Path, PathBuf};
 
+use crate::dng::{self, DngReader, DngReaderOptions};
 use crate::sequence::{FrameRange, Sequence, scan_dir};
 use crate::tiff::{TiffReader, TiffReaderOptions};
 use crate::traits::FormatReader;
@@ -104,8 +111,7 @@ impl CinemaDng {
         })?;
 
         let first_path = dng_seq.frame_path(range.start());
-        let reader = TiffReader::new();
-        let first_frame = reader.read(&first_path)?;
+        let first_frame = CinemaDngReader::new().read_path(&first_path)?;
 
         Ok(Self {
             dir,
@@ -200,8 +206,10 @@ impl CinemaDng {
 /// Options for reading CinemaDNG frames.
 #[derive(Debug, Clone, Default)]
 pub struct CinemaDngReaderOptions {
-    /// TIFF reader options.
+    /// TIFF reader options, used for frames without raw data.
     pub tiff_options: TiffReaderOptions,
+    /// DNG raw development options.
+    pub dng_options: DngReaderOptions,
 }
 
 /// CinemaDNG frame reader.
@@ -210,6 +218,7 @@ pub struct CinemaDngReaderOptions {
 #[derive(Debug, Clone)]
 pub struct CinemaDngReader {
     tiff_reader: TiffReader,
+    dng_reader: DngReader,
 }
 
 impl CinemaDngReader {
@@ -217,6 +226,7 @@ impl CinemaDngReader {
     pub fn new() -> Self {
         Self {
             tiff_reader: TiffReader::new(),
+            dng_reader: DngReader::new(),
         }
     }
 
@@ -224,6 +234,17 @@ impl CinemaDngReader {
     pub fn with_options(options: CinemaDngReaderOptions) -> Self {
         Self {
             tiff_reader: TiffReader::with_options(options.tiff_options),
+            dng_reader: DngReader::with_options(options.dng_options),
+        }
+    }
+
+    /// Reads one DNG file, developing raw data when present.
+    fn read_path(&self, path: &Path) -> IoResult<ImageData> {
+        let data = std::fs::read(path)?;
+        if dng::is_dng(&data) {
+            self.dng_reader.read_from_memory(&data)
+        } else {
+            self.tiff_reader.read_from_memory(&data)
         }
     }
 
@@ -253,8 +274,7 @@ impl CinemaDngReader {
             )));
         }
 
-        let path = cdng.frame_path(frame);
-        self.tiff_reader.read(&path)
+        self.read_path(&cdng.frame_path(frame))
     }
 
     /// Reads a range of frames.
@@ -0,0 +1,1101 @@
+//! Kodak Cineon format support.
+//!
+//! The predecessor of DPX, still produced by older film scanners and
+//! some film-out vendors. Samples are printing density, usually 10-bit
+//! log; `vfx_transfer::cineon` converts them to linear.
+//!
+//! # Features
+//!
+//! - 8, 10, 12, 16-bit gray and RGB
+//! - All packing modes (bit-packed, byte/word/long-word aligned, either justification)
+//! - Big-endian and little-endian files
+//! - Image origination and motion picture film headers
+//! - Memory read/write support
+//!
+//! # Quick Start
+//!
+//! ```ignore
+//! use vfx_io::cineon;
+//!
+//! let image = cineon::read("scan.0001.cin")?;
+//! cineon::write("output.0001.cin", &image)?;
+//! ```
+//!
+//! # Metadata
+//!
+//! Header fields use the same attribute names as [`crate::dpx`], so
+//! metadata survives DPX <-> Cineon conversion. `Descriptor` and `Packing`
+//! are reported in DPX terms (6 = luma, 50 = RGB; 0 = packed, 1 = filled
+//! method A, 2 = filled method B); the original Cineon values are kept as
+//! `Cineon:Packing` and `Cineon:Orientation`.
+//!
+//! # Format Details
+//!
+//! - Magic: 0x802A5FD7 (stored big-endian, byte-swapped in little-endian files)
+//! - Header: 1024-byte generic header + 1024-byte motion picture film header
+//! - Data: pixel-interleaved, each scan line starts on a 32-bit boundary
+
+use crate::{AttrValue, FormatReader, FormatWriter, ImageData, IoError, IoResult, Metadata, PixelData, PixelFormat};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use tracing::debug;
+
+// === Constants ===
+
+/// Cineon magic number.
+const MAGIC: u32 = 0x802A_5FD7;
+
+/// Generic header size (file + image + data format + origination).
+const GENERIC_HEADER_SIZE: u32 = 1024;
+
+/// Motion picture film header size.
+const FILM_HEADER_SIZE: u32 = 1024;
+
+/// Total header size written by [`CineonWriter`].
+const HEADER_SIZE: u32 = GENERIC_HEADER_SIZE + FILM_HEADER_SIZE;
+
+/// Maximum quantity (printing density) for the highest code value.
+const MAX_DENSITY: f32 = 2.048;
+
+// === Reader Options ===
+
+/// Options for reading Cineon files.
+///
+/// Currently empty but reserved for future options.
+#[derive(Debug, Clone, Default)]
+pub struct CineonReaderOptions {
+    /// Reserved for future use.
+    _reserved: (),
+}
+
+// === Writer Options ===
+
+/// Options for writing Cineon files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::cineon::CineonWriterOptions;
+///
+/// let options = CineonWriterOptions {
+///     bit_depth: 10,
+///     big_endian: true,
+/// };
+/// ```
+#[derive(Debug, Clone)]
+pub struct CineonWriterOptions {
+    /// Bits per sample: 8, 10, 12 or 16. Default: 10.
+    pub bit_depth: u8,
+    /// Write big-endian (the standard). Default: true.
+    pub big_endian: bool,
+}
+
+impl Default for CineonWriterOptions {
+    fn default() -> Self {
+        Self {
+            bit_depth: 10,
+            big_endian: true,
+        }
+    }
+}
+
+// === Cineon Header ===
+
+/// Image origination header data (offsets 712-1023).
+#[derive(Debug, Clone, Default)]
+struct OriginationHeader {
+    /// X offset in pixels.
+    x_offset: i32,
+    /// Y offset in pixels.
+    y_offset: i32,
+    /// Source image filename.
+    source_filename: String,
+    /// Source creation date ("yyyy:mm:dd").
+    source_date: String,
+    /// Source creation time ("hh:mm:ss").
+    source_time: String,
+    /// Input device name.
+    input_device: String,
+    /// Input device model.
+    input_model: String,
+    /// Input device serial.
+    input_serial: String,
+    /// Input device pitch in samples per mm.
+    pitch: (f32, f32),
+    /// Image gamma.
+    gamma: f32,
+}
+
+/// Motion picture film header data (offsets 1024-2047).
+#[derive(Debug, Clone, Default)]
+struct FilmHeader {
+    /// Film manufacturer ID code.
+    film_mfg_id: u8,
+    /// Film type.
+    film_type: u8,
+    /// Offset in perforations.
+    perf_offset: u8,
+    /// Film prefix.
+    prefix: u32,
+    /// Film count.
+    count: u32,
+    /// Film format (e.g., "Academy").
+    format: String,
+    /// Frame position in sequence.
+    frame_position: u32,
+    /// Frame rate (fps).
+    frame_rate: f32,
+    /// Frame identification.
+    frame_id: String,
+    /// Slate information.
+    slate_info: String,
+}
+
+/// Parsed Cineon file header.
+#[derive(Debug, Clone)]
+struct CineonHeader {
+    /// True if big-endian.
+    is_big_endian: bool,
+    /// Offset to image data.
+    image_offset: u32,
+    /// Industry header length (0 if absent).
+    industry_size: u32,
+    /// Total file size.
+    file_size: u32,
+    /// Creation date ("yyyy:mm:dd").
+    date: String,
+    /// Creation time ("hh:mm:ss").
+    time: String,
+    /// Orientation (0 = left-right, top-bottom).
+    orientation: u8,
+    /// Number of channels.
+    channels: u32,
+    /// Bits per sample.
+    bit_depth: u8,
+    /// Image width in pixels.
+    width: u32,
+    /// Image height in pixels.
+    height: u32,
+    /// Highest code value of the first channel.
+    max_code: f32,
+    /// Image label.
+    label: String,
+    /// Data interleave (0 = pixel).
+    interleave: u8,
+    /// Packing (0 = bit-packed, 1-6 = aligned, odd = left justified).
+    packing: u8,
+    /// End-of-line padding in bytes.
+    eol_padding: u32,
+    /// Origination header.
+    origination: OriginationHeader,
+    /// Film header.
+    film: FilmHeader,
+}
+
+/// Bounds-checked, endian-aware field access.
+struct Fields<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+impl Fields<'_> {
+    fn bytes(&self, offset: usize, len: usize) -> IoResult<&[u8]> {
+        self.data
+            .get(offset..offset + len)
+            .ok_or_else(|| IoError::DecodeError("Cineon header truncated".into()))
+    }
+
+    fn u8(&self, offset: usize) -> IoResult<u8> {
+        Ok(self.bytes(offset, 1)?[0])
+    }
+
+    fn u32(&self, offset: usize) -> IoResult<u32> {
+        let b: [u8; 4] = self.bytes(offset, 4)?.try_into().unwrap_or_default();
+        Ok(if self.big_endian { u32::from_be_bytes(b) } else { u32::from_le_bytes(b) })
+    }
+
+    fn i32(&self, offset: usize) -> IoResult<i32> {
+        Ok(self.u32(offset)? as i32)
+    }
+
+    fn f32(&self, offset: usize) -> IoResult<f32> {
+        Ok(f32::from_bits(self.u32(offset)?))
+    }
+
+    fn string(&self, offset: usize, len: usize) -> IoResult<String> {
+        let buf = self.bytes(offset, len)?;
+        if buf[0] == 0xFF {
+            return Ok(String::new());
+        }
+        let end = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+        Ok(String::from_utf8_lossy(&buf[..end]).trim().to_string())
+    }
+}
+
+impl CineonHeader {
+    /// Parses a Cineon header from the start of a file.
+    fn parse(data: &[u8]) -> IoResult<Self> {
+        if data.len() < 4 {
+            return Err(IoError::DecodeError("Cineon header truncated".into()));
+        }
+        let is_big_endian = match u32::from_be_bytes([data[0], data[1], data[2], data[3]]) {
+            MAGIC => true,
+            m if m.swap_bytes() == MAGIC => false,
+            m => return Err(IoError::DecodeError(format!("invalid Cineon magic: 0x{:08X}", m))),
+        };
+        let f = Fields { data, big_endian: is_big_endian };
+
+        // File information header
+        let image_offset = f.u32(4)?;
+        let industry_size = f.u32(12)?;
+        let file_size = f.u32(20)?;
+        let date = f.string(132, 12)?;
+        let time = f.string(144, 12)?;
+
+        // Image information header; channel 0 defines the image
+        let orientation = f.u8(192)?;
+        let channels = f.u8(193)? as u32;
+        let bit_depth = f.u8(198)?;
+        let width = f.u32(200)?;
+        let height = f.u32(204)?;
+        let max_code = f.f32(216)?;
+
+        for c in 1..channels.min(8) as usize {
+            let base = 196 + c * 28;
+            if f.u8(base + 2)? != bit_depth || f.u32(base + 4)? != width || f.u32(base + 8)? != height {
+                return Err(IoError::UnsupportedFeature(
+                    "Cineon channels with differing size or bit depth".into(),
+                ));
+            }
+        }
+
+        let label = f.string(452, 200)?;
+
+        // Image data format information
+        let interleave = f.u8(680)?;
+        let packing = f.u8(681)?;
+        // Often left unset (all ones), which means no padding
+        let eol_padding = Some(f.u32(684)?).filter(|&v| is_set_u32(v)).unwrap_or(0);
+
+        let origination = OriginationHeader {
+            x_offset: f.i32(712)?,
+            y_offset: f.i32(716)?,
+            source_filename: f.string(720, 100)?,
+            source_date: f.string(820, 12)?,
+            source_time: f.string(832, 12)?,
+            input_device: f.string(844, 64)?,
+            input_model: f.string(908, 32)?,
+            input_serial: f.string(940, 32)?,
+            pitch: (f.f32(972)?, f.f32(976)?),
+            gamma: f.f32(980)?,
+        };
+
+        // The motion picture film header is optional
+        let film = if industry_size >= FILM_HEADER_SIZE && data.len() >= HEADER_SIZE as usize {
+            FilmHeader {
+                film_mfg_id: f.u8(1024)?,
+                film_type: f.u8(1025)?,
+                perf_offset: f.u8(1026)?,
+                prefix: f.u32(1028)?,
+                count: f.u32(1032)?,
+                format: f.string(1036, 32)?,
+                frame_position: f.u32(1068)?,
+                frame_rate: f.f32(1072)?,
+                frame_id: f.string(1076, 32)?,
+                slate_info: f.string(1108, 200)?,
+            }
+        } else {
+            FilmHeader::default()
+        };
+
+        if width == 0 || height == 0 || channels == 0 || channels > 8 {
+            return Err(IoError::DecodeError(format!(
+                "invalid Cineon dimensions: {}x{}x{}",
+                width, height, channels
+            )));
+        }
+
+        Ok(Self {
+            is_big_endian,
+            image_offset,
+            industry_size,
+            file_size,
+            date,
+            time,
+            orientation,
+            channels,
+            bit_depth,
+            width,
+            height,
+            max_code,
+            label,
+            interleave,
+            packing,
+            eol_padding,
+            origination,
+            film,
+        })
+    }
+}
+
+/// Returns true if a header value is set (Cineon marks unset fields with all ones).
+fn is_set_u32(v: u32) -> bool {
+    v != 0 && v != 0xFFFF_FFFF
+}
+
+/// Returns true if a float header value is set.
+fn is_set_f32(v: f32) -> bool {
+    v.is_finite() && v > 0.0
+}
+
+/// Returns true if an 8-bit header value is set.
+fn is_set_u8(v: u8) -> bool {
+    v != 0xFF
+}
+
+// === CineonReader ===
+
+/// Cineon format reader.
+///
+/// Reads Cineon files with automatic endianness detection. Samples are
+/// returned as F32 code values normalized to [0, 1].
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::cineon::CineonReader;
+/// use vfx_io::FormatReader;
+///
+/// let reader = CineonReader::new();
+/// let image = reader.read("scan.cin")?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CineonReader {
+    #[allow(dead_code)]
+    options: CineonReaderOptions,
+}
+
+impl CineonReader {
+    /// Creates a new Cineon reader with default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Internal read implementation.
+    fn read_impl(&self, data: &[u8]) -> IoResult<ImageData> {
+        let header = CineonHeader::parse(data)?;
+
+        if header.interleave != 0 {
+            return Err(IoError::UnsupportedFeature(format!(
+                "Cineon interleave {} (only pixel interleave is supported)",
+                header.interleave
+            )));
+        }
+        if !(1..=16).contains(&header.bit_depth) {
+            return Err(IoError::UnsupportedBitDepth(format!("Cineon {} bit", header.bit_depth)));
+        }
+
+        let raster = data
+            .get(header.image_offset as usize..)
+            .ok_or_else(|| IoError::DecodeError("Cineon image offset beyond end of file".into()))?;
+        let mut pixels = unpack(raster, &header)?;
+
+        // Normalize to the highest code value of the bit depth
+        let max = ((1u32 << header.bit_depth) - 1) as f32;
+        for v in &mut pixels {
+            *v /= max;
+        }
+
+        let (width, channels) = (header.width as usize, header.channels as usize);
+        match header.orientation {
+            0 => {}
+            1..=3 => reorient(&mut pixels, width, channels, header.orientation),
+            o => debug!("Cineon: transposed orientation {} is not applied", o),
+        }
+
+        Ok(ImageData {
+            width: header.width,
+            height: header.height,
+            channels: header.channels,
+            format: PixelFormat::F32,
+            data: PixelData::F32(pixels),
+            metadata: read_metadata(&header),
+        })
+    }
+}
+
+impl FormatReader<CineonReaderOptions> for CineonReader {
+    fn format_name(&self) -> &'static str {
+        "Cineon"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["cin"]
+    }
+
+    fn can_read(&self, header: &[u8]) -> bool {
+        is_cineon_header(header)
+    }
+
+    fn read<P: AsRef<Path>>(&self, path: P) -> IoResult<ImageData> {
+        let data = std::fs::read(path.as_ref())?;
+        self.read_impl(&data)
+    }
+
+    fn read_from_memory(&self, data: &[u8]) -> IoResult<ImageData> {
+        self.read_impl(data)
+    }
+
+    fn with_options(options: CineonReaderOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Builds metadata, using the DPX attribute names.
+fn read_metadata(header: &CineonHeader) -> Metadata {
+    let mut metadata = Metadata {
+        colorspace: Some("log".to_string()),
+        ..Metadata::default()
+    };
+    let attrs = &mut metadata.attrs;
+
+    attrs.set("Format", AttrValue::Str("Cineon".to_string()));
+    attrs.set("ImageWidth", AttrValue::UInt(header.width));
+    attrs.set("ImageHeight", AttrValue::UInt(header.height));
+    attrs.set("BitDepth", AttrValue::UInt(header.bit_depth as u32));
+    attrs.set("Channels", AttrValue::UInt(header.channels));
+    attrs.set("Endian", AttrValue::Str(if header.is_big_endian { "BE" } else { "LE" }.to_string()));
+    attrs.set("ImageOffset", AttrValue::UInt(header.image_offset));
+    attrs.set("FileSize", AttrValue::UInt(header.file_size));
+
+    // Descriptor and packing in DPX terms
+    let descriptor = match header.channels {
+        1 => 6,
+        3 => 50,
+        4 => 51,
+        _ => 0,
+    };
+    let packing = match header.packing {
+        0 => 0,
+        p if p % 2 == 1 => 1,
+        _ => 2,
+    };
+    attrs.set("Descriptor", AttrValue::UInt(descriptor));
+    attrs.set("Transfer", AttrValue::UInt(1)); // Printing density
+    attrs.set("Colorimetric", AttrValue::UInt(1));
+    attrs.set("Packing", AttrValue::UInt(packing));
+    attrs.set("Cineon:Packing", AttrValue::UInt(header.packing as u32));
+    attrs.set("Cineon:Orientation", AttrValue::UInt(header.orientation as u32));
+    if is_set_f32(header.max_code) {
+        attrs.set("Cineon:MaxCodeValue", AttrValue::Float(header.max_code));
+    }
+
+    if !header.date.is_empty() {
+        let date_time = format!("{} {}", header.date, header.time.get(..8).unwrap_or(&header.time));
+        attrs.set("DateTime", AttrValue::Str(date_time.trim().to_string()));
+    }
+    if !header.label.is_empty() {
+        attrs.set("ImageDescription", AttrValue::Str(header.label.clone()));
+    }
+
+    // Origination header
+    let o = &header.origination;
+    if o.x_offset != 0 && o.x_offset != -1 {
+        attrs.set("XOffset", AttrValue::Int(o.x_offset));
+    }
+    if o.y_offset != 0 && o.y_offset != -1 {
+        attrs.set("YOffset", AttrValue::Int(o.y_offset));
+    }
+    if !o.source_filename.is_empty() {
+        attrs.set("SourceFilename", AttrValue::Str(o.source_filename.clone()));
+    }
+    if !o.source_date.is_empty() {
+        let date = format!("{} {}", o.source_date, o.source_time);
+        attrs.set("SourceDate", AttrValue::Str(date.trim().to_string()));
+    }
+    if !o.input_device.is_empty() {
+        attrs.set("InputDevice", AttrValue::Str(o.input_device.clone()));
+    }
+    if !o.input_model.is_empty() {
+        attrs.set("Cineon:InputModel", AttrValue::Str(o.input_model.clone()));
+    }
+    if !o.input_serial.is_empty() {
+        attrs.set("InputSerial", AttrValue::Str(o.input_serial.clone()));
+    }
+    if is_set_f32(o.pitch.0) && is_set_f32(o.pitch.1) {
+        attrs.set("Cineon:XInputPitch", AttrValue::Float(o.pitch.0));
+        attrs.set("Cineon:YInputPitch", AttrValue::Float(o.pitch.1));
+    }
+    if is_set_f32(o.gamma) {
+        attrs.set("Gamma", AttrValue::Float(o.gamma));
+    }
+
+    // Motion picture film header
+    if header.industry_size >= FILM_HEADER_SIZE {
+        let film = &header.film;
+        if is_set_u8(film.film_mfg_id) {
+            attrs.set("Cineon:FilmMfgId", AttrValue::UInt(film.film_mfg_id as u32));
+        }
+        if is_set_u8(film.film_type) {
+            attrs.set("Cineon:FilmType", AttrValue::UInt(film.film_type as u32));
+        }
+        if is_set_u8(film.perf_offset) {
+            attrs.set("Cineon:PerfOffset", AttrValue::UInt(film.perf_offset as u32));
+        }
+        if is_set_u32(film.prefix) {
+            attrs.set("Cineon:Prefix", AttrValue::UInt(film.prefix));
+        }
+        if is_set_u32(film.count) {
+            attrs.set("Cineon:Count", AttrValue::UInt(film.count));
+        }
+        if !film.format.is_empty() {
+            attrs.set("FilmFormat", AttrValue::Str(film.format.clone()));
+        }
+        if is_set_u32(film.frame_position) {
+            attrs.set("FramePosition", AttrValue::UInt(film.frame_position));
+        }
+        if is_set_f32(film.frame_rate) {
+            attrs.set("FrameRate", AttrValue::Float(film.frame_rate));
+        }
+        if !film.frame_id.is_empty() {
+            attrs.set("FrameId", AttrValue::Str(film.frame_id.clone()));
+        }
+        if !film.slate_info.is_empty() {
+            attrs.set("SlateInfo", AttrValue::Str(film.slate_info.clone()));
+        }
+    }
+
+    metadata
+}
+
+// === CineonWriter ===
+
+/// Cineon format writer.
+///
+/// Writes gray (1 channel) or RGB; alpha and extra channels are dropped.
+/// 10-bit data uses packing 5 (three samples per 32-bit word, left
+/// justified), other depths are byte or 16-bit aligned.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::cineon::CineonWriter;
+/// use vfx_io::FormatWriter;
+///
+/// let writer = CineonWriter::new();
+/// writer.write("output.cin", &image)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CineonWriter {
+    options: CineonWriterOptions,
+}
+
+impl CineonWriter {
+    /// Creates a new Cineon writer with default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Internal write implementation to any Write sink.
+    fn write_impl<W: Write>(&self, writer: &mut W, image: &ImageData) -> IoResult<()> {
+        let bit_depth = self.options.bit_depth;
+        if !matches!(bit_depth, 8 | 10 | 12 | 16) {
+            return Err(IoError::UnsupportedBitDepth(format!("Cineon {} bit", bit_depth)));
+        }
+        let src_channels = image.channels as usize;
+        if image.width == 0 || image.height == 0 || src_channels == 0 {
+            return Err(IoError::EncodeError("Cineon image has no pixels".into()));
+        }
+        let channels = if src_channels >= 3 { 3 } else { 1 };
+        if channels != src_channels {
+            debug!("Cineon: writing {} of {} channels", channels, src_channels);
+        }
+
+        // 10-bit in 32-bit words, otherwise byte or 16-bit aligned, all left justified
+        let packing = match bit_depth {
+            8 => 1,
+            10 => 5,
+            _ => 3,
+        };
+
+        let max = ((1u32 << bit_depth) - 1) as f32;
+        let src = image.to_f32();
+        let codes: Vec<u32> = src
+            .chunks_exact(src_channels)
+            .flat_map(|px| px[..channels].iter().map(|&v| (v.clamp(0.0, 1.0) * max).round() as u32))
+            .collect();
+
+        let is_be = self.options.big_endian;
+        let mut raster = Vec::new();
+        for line in codes.chunks_exact(image.width as usize * channels) {
+            pack_line(&mut raster, line, bit_depth, packing, is_be);
+        }
+
+        let header = self.build_header(image, channels, packing, raster.len() as u32);
+        writer.write_all(&header).map_err(|e| IoError::EncodeError(e.to_string()))?;
+        writer.write_all(&raster).map_err(|e| IoError::EncodeError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Builds the 2048-byte header, filling fields from DPX-style attributes.
+    fn build_header(&self, image: &ImageData, channels: usize, packing: u8, raster_size: u32) -> Vec<u8> {
+        let is_be = self.options.big_endian;
+        let bit_depth = self.options.bit_depth;
+        let attrs = &image.metadata.attrs;
+        let get_str = |key: &str| attrs.get(key).and_then(|v| v.as_str()).unwrap_or("");
+        let get_u32 = |key: &str| attrs.get(key).and_then(|v| v.as_u32());
+        let get_f32 = |key: &str| attrs.get(key).and_then(|v| v.as_f32());
+
+        let mut h = vec![0u8; HEADER_SIZE as usize];
+        let put_u32 = |h: &mut [u8], offset: usize, v: u32| {
+            let b = if is_be { v.to_be_bytes() } else { v.to_le_bytes() };
+            h[offset..offset + 4].copy_from_slice(&b);
+        };
+        let put_str = |h: &mut [u8], offset: usize, len: usize, s: &str| {
+            let n = s.len().min(len - 1);
+            h[offset..offset + n].copy_from_slice(&s.as_bytes()[..n]);
+        };
+
+        // Unset fields are all ones
+        h[712..1024].fill(0xFF);
+        h[1024..].fill(0xFF);
+
+        // File information header
+        put_u32(&mut h, 0, MAGIC);
+        put_u32(&mut h, 4, HEADER_SIZE);
+        put_u32(&mut h, 8, GENERIC_HEADER_SIZE);
+        put_u32(&mut h, 12, FILM_HEADER_SIZE);
+        put_u32(&mut h, 16, 0);
+        put_u32(&mut h, 20, HEADER_SIZE + raster_size);
+        put_str(&mut h, 24, 8, "V4.5");
+        let (date, time) = split_date_time(get_str("DateTime"));
+        put_str(&mut h, 132, 12, date);
+        put_str(&mut h, 144, 12, time);
+
+        // Image information header
+        h[192] = 0; // Left to right, top to bottom
+        h[193] = channels as u8;
+        for c in 0..8 {
+            let base = 196 + c * 28;
+            if c < channels {
+                h[base] = 0; // Universal metric
+                h[base + 1] = if channels == 1 { 0 } else { c as u8 + 1 };
+                h[base + 2] = bit_depth;
+                put_u32(&mut h, base + 4, image.width);
+                put_u32(&mut h, base + 8, image.height);
+                put_u32(&mut h, base + 12, 0f32.to_bits());
+                put_u32(&mut h, base + 16, 0f32.to_bits());
+                put_u32(&mut h, base + 20, (((1u32 << bit_depth) - 1) as f32).to_bits());
+                put_u32(&mut h, base + 24, MAX_DENSITY.to_bits());
+            } else {
+                h[base..base + 28].fill(0xFF);
+            }
+        }
+        put_str(&mut h, 452, 200, get_str("ImageDescription"));
+
+        // Image data format information
+        h[680] = 0; // Pixel interleave
+        h[681] = packing;
+        h[682] = 0; // Unsigned
+        h[683] = 0; // Positive image sense
+        put_u32(&mut h, 684, 0);
+        put_u32(&mut h, 688, 0);
+
+        // Image origination header
+        let offset = |key: &str| attrs.get(key).and_then(|v| v.as_i32()).unwrap_or(0);
+        put_u32(&mut h, 712, offset("XOffset") as u32);
+        put_u32(&mut h, 716, offset("YOffset") as u32);
+        let zero_strings = [(720, 100), (820, 12), (832, 12), (844, 64), (908, 32), (940, 32)];
+        for (start, len) in zero_strings {
+            h[start..start + len].fill(0);
+        }
+        put_str(&mut h, 720, 100, get_str("SourceFilename"));
+        let (src_date, src_time) = split_date_time(get_str("SourceDate"));
+        put_str(&mut h, 820, 12, src_date);
+        put_str(&mut h, 832, 12, src_time);
+        put_str(&mut h, 844, 64, get_str("InputDevice"));
+        put_str(&mut h, 908, 32, get_str("Cineon:InputModel"));
+        put_str(&mut h, 940, 32, get_str("InputSerial"));
+        if let (Some(x), Some(y)) = (get_f32("Cineon:XInputPitch"), get_f32("Cineon:YInputPitch")) {
+            put_u32(&mut h, 972, x.to_bits());
+            put_u32(&mut h, 976, y.to_bits());
+        }
+        if let Some(gamma) = get_f32("Gamma") {
+            put_u32(&mut h, 980, gamma.to_bits());
+        }
+
+        // Motion picture film header
+        if let Some(v) = get_u32("Cineon:FilmMfgId") {
+            h[1024] = v as u8;
+        }
+        if let Some(v) = get_u32("Cineon:FilmType") {
+            h[1025] = v as u8;
+        }
+        if let Some(v) = get_u32("Cineon:PerfOffset") {
+            h[1026] = v as u8;
+        }
+        if let Some(v) = get_u32("Cineon:Prefix") {
+            put_u32(&mut h, 1028, v);
+        }
+        if let Some(v) = get_u32("Cineon:Count") {
+            put_u32(&mut h, 1032, v);
+        }
+        for (start, len) in [(1036, 32), (1076, 32), (1108, 200)] {
+            h[start..start + len].fill(0);
+        }
+        put_str(&mut h, 1036, 32, get_str("FilmFormat"));
+        if let Some(v) = get_u32("FramePosition") {
+            put_u32(&mut h, 1068, v);
+        }
+        if let Some(v) = get_f32("FrameRate") {
+            put_u32(&mut h, 1072, v.to_bits());
+        }
+        put_str(&mut h, 1076, 32, get_str("FrameId"));
+        put_str(&mut h, 1108, 200, get_str("SlateInfo"));
+
+        h
+    }
+}
+
+impl FormatWriter<CineonWriterOptions> for CineonWriter {
+    fn format_name(&self) -> &'static str {
+        "Cineon"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["cin"]
+    }
+
+    fn write<P: AsRef<Path>>(&self, path: P, image: &ImageData) -> IoResult<()> {
+        let file = File::create(path.as_ref())?;
+        let mut writer = BufWriter::new(file);
+        self.write_impl(&mut writer, image)?;
+        writer.flush().map_err(|e| IoError::EncodeError(e.to_string()))?;
+        Ok(())
+    }
+
+    fn write_to_memory(&self, image: &ImageData) -> IoResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.write_impl(&mut buffer, image)?;
+        Ok(buffer)
+    }
+
+    fn with_options(options: CineonWriterOptions) -> Self {
+        Self { options }
+    }
+}
+
+// === Convenience Functions ===
+
+/// Reads a Cineon file from the given path.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::cineon;
+///
+/// let image = cineon::read("scan.0001.cin")?;
+/// ```
+pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
+    CineonReader::new().read(path)
+}
+
+/// Writes an image to a Cineon file.
+///
+/// Uses default options (10-bit, big-endian).
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::cineon;
+///
+/// cineon::write("output.0001.cin", &image)?;
+/// ```
+pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
+    CineonWriter::new().write(path, image)
+}
+
+/// Checks for the Cineon magic number in either byte order.
+pub fn is_cineon_header(header: &[u8]) -> bool {
+    header.len() >= 4
+        && (header[..4] == MAGIC.to_be_bytes() || header[..4] == MAGIC.to_le_bytes())
+}
+
+// === Internal Functions ===
+
+/// Container size in bits for a packing mode and bit depth.
+///
+/// Packings 1-6 align samples to 8, 16 or 32-bit boundaries; a sample
+/// wider than the boundary spans several units.
+fn container_bits(packing: u8, bit_depth: u8) -> u32 {
+    let unit = match packing {
+        1 | 2 => 8,
+        3 | 4 => 16,
+        _ => 32,
+    };
+    unit * (bit_depth as u32).div_ceil(unit)
+}
+
+/// Reads one container of up to 32 bits.
+fn read_container(data: &[u8], bits: u32, big_endian: bool) -> u32 {
+    match bits {
+        8 => data[0] as u32,
+        16 if big_endian => u16::from_be_bytes([data[0], data[1]]) as u32,
+        16 => u16::from_le_bytes([data[0], data[1]]) as u32,
+        _ if big_endian => u32::from_be_bytes([data[0], data[1], data[2], data[3]]),
+        _ => u32::from_le_bytes([data[0], data[1], data[2], data[3]]),
+    }
+}
+
+/// Unpacks all scan lines to raw code values.
+fn unpack(raster: &[u8], header: &CineonHeader) -> IoResult<Vec<f32>> {
+    let bits = header.bit_depth as u32;
+    let per_line = header.width as usize * header.channels as usize;
+    let mask = (1u32 << bits) - 1;
+    let big_endian = header.is_big_endian;
+    let truncated = || IoError::DecodeError("Cineon image data truncated".into());
+
+    // Check the raster size before allocating, the dimensions come from the header
+    let min_line_bytes = if header.packing == 0 {
+        (per_line * bits as usize).div_ceil(32) * 4
+    } else {
+        let container = container_bits(header.packing, header.bit_depth);
+        per_line.div_ceil((container / bits) as usize) * (container / 8) as usize
+    };
+    let samples = per_line.checked_mul(header.height as usize).ok_or_else(truncated)?;
+    match min_line_bytes.checked_mul(header.height as usize) {
+        Some(size) if size <= raster.len() => {}
+        _ => return Err(truncated()),
+    }
+
+    let mut out = Vec::with_capacity(samples);
+    let mut pos = 0usize;
+
+    for _ in 0..header.height {
+        if header.packing == 0 {
+            // Bit stream in 32-bit words, most significant bit first
+            let line_bytes = (per_line * bits as usize).div_ceil(32) * 4;
+            let line = raster.get(pos..pos + line_bytes).ok_or_else(truncated)?;
+            let (mut acc, mut n_bits, mut word) = (0u64, 0u32, 0usize);
+            for _ in 0..per_line {
+                if n_bits < bits {
+                    acc = (acc << 32) | read_container(&line[word * 4..], 32, big_endian) as u64;
+                    n_bits += 32;
+                    word += 1;
+                }
+                n_bits -= bits;
+                out.push(((acc >> n_bits) as u32 & mask) as f32);
+            }
+            pos += line_bytes;
+        } else {
+            let container = container_bits(header.packing, header.bit_depth);
+            let per_container = (container / bits) as usize;
+            let left_justified = header.packing % 2 == 1;
+            let bytes = (container / 8) as usize;
+            let count = per_line.div_ceil(per_container);
+            let line_bytes = (count * bytes).div_ceil(4) * 4;
+            let line = raster.get(pos..pos + count * bytes).ok_or_else(truncated)?;
+
+            for (i, chunk) in line.chunks_exact(bytes).enumerate() {
+                let value = read_container(chunk, container, big_endian);
+                let n = per_container.min(per_line - i * per_container);
+                for j in 0..n as u32 {
+                    let shift = if left_justified {
+                        container - (j + 1) * bits
+                    } else {
+                        (per_container as u32 - 1 - j) * bits
+                    };
+                    out.push(((value >> shift) & mask) as f32);
+                }
+            }
+            // Lines start on 32-bit boundaries
+            pos += line_bytes.min(raster.len() - pos);
+        }
+        pos += header.eol_padding as usize;
+    }
+
+    Ok(out)
+}
+
+/// Packs one scan line of code values, padded to a 32-bit boundary.
+fn pack_line(out: &mut Vec<u8>, line: &[u32], bit_depth: u8, packing: u8, big_endian: bool) {
+    let bits = bit_depth as u32;
+    let container = container_bits(packing, bit_depth);
+    let per_container = (container / bits) as usize;
+    let start = out.len();
+
+    for group in line.chunks(per_container) {
+        let mut value = 0u32;
+        for (j, &code) in group.iter().enumerate() {
+            value |= code << (container - (j as u32 + 1) * bits);
+        }
+        match container {
+            8 => out.push(value as u8),
+            16 if big_endian => out.extend_from_slice(&(value as u16).to_be_bytes()),
+            16 => out.extend_from_slice(&(value as u16).to_le_bytes()),
+            _ if big_endian => out.extend_from_slice(&value.to_be_bytes()),
+            _ => out.extend_from_slice(&value.to_le_bytes()),
+        }
+    }
+
+    let len = out.len() - start;
+    out.resize(start + len.div_ceil(4) * 4, 0);
+}
+
+/// Flips the image for orientations 1-3 (bottom-up and/or right-to-left).
+fn reorient(pixels: &mut [f32], width: usize, channels: usize, orientation: u8) {
+    let row_len = width * channels;
+    if orientation & 1 != 0 {
+        let height = pixels.len() / row_len;
+        for y in 0..height / 2 {
+            let (top, bottom) = pixels.split_at_mut((height - 1 - y) * row_len);
+            top[y * row_len..(y + 1) * row_len].swap_with_slice(&mut bottom[..row_len]);
+        }
+    }
+    if orientation & 2 != 0 {
+        for row in pixels.chunks_exact_mut(row_len) {
+            for x in 0..width / 2 {
+                let (a, b) = (x * channels, (width - 1 - x) * channels);
+                for c in 0..channels {
+                    row.swap(a + c, b + c);
+                }
+            }
+        }
+    }
+}
+
+/// Splits "YYYY:MM:DD HH:MM:SS" into date and time parts.
+fn split_date_time(s: &str) -> (&str, &str) {
+    match s.split_once(' ') {
+        Some((date, time)) => (date.trim(), time.trim()),
+        None => (s.trim(), ""),
+    }
+}
+
+// === Tests ===
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(width: u32, height: u32, channels: u32) -> ImageData {
+        let data = (0..width * height * channels)
+            .map(|i| (i % 1024) as f32 / 1023.0)
+            .collect();
+        ImageData::from_f32(width, height, channels, data)
+    }
+
+    #[test]
+    fn test_roundtrip_bit_depths() {
+        for bit_depth in [8u8, 10, 12, 16] {
+            for big_endian in [true, false] {
+                let image = gradient(7, 5, 3);
+                let writer = CineonWriter::with_options(CineonWriterOptions { bit_depth, big_endian });
+                let bytes = writer.write_to_memory(&image).expect("write failed");
+
+                let loaded = CineonReader::new().read_from_memory(&bytes).expect("read failed");
+                assert_eq!((loaded.width, loaded.height, loaded.channels), (7, 5, 3));
+
+                let tolerance = 0.51 / ((1u32 << bit_depth) - 1) as f32;
+                for (a, b) in loaded.to_f32().iter().zip(image.to_f32()) {
+                    assert!((a - b).abs() <= tolerance, "{} bit: {} vs {}", bit_depth, a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_10bit_packing() {
+        // One gray line of 4 samples: 3 in the first word, 1 in the second
+        let image = ImageData::from_f32(4, 1, 1, vec![1.0, 0.0, 1.0, 1.0]);
+        let bytes = CineonWriter::new().write_to_memory(&image).expect("write failed");
+        assert_eq!(bytes.len(), HEADER_SIZE as usize + 8);
+        let data = &bytes[HEADER_SIZE as usize..];
+        assert_eq!(u32::from_be_bytes([data[0], data[1], data[2], data[3]]), (1023 << 22) | (1023 << 2));
+        assert_eq!(u32::from_be_bytes([data[4], data[5], data[6], data[7]]), 1023 << 22);
+    }
+
+    #[test]
+    fn test_metadata_roundtrip() {
+        let mut image = gradient(4, 2, 3);
+        let attrs = &mut image.metadata.attrs;
+        attrs.set("FrameRate", AttrValue::Float(24.0));
+        attrs.set("FramePosition", AttrValue::UInt(1001));
+        attrs.set("FilmFormat", AttrValue::Str("Academy".into()));
+        attrs.set("InputDevice", AttrValue::Str("Northlight".into()));
+        attrs.set("DateTime", AttrValue::Str("1998:04:01 12:30:00".into()));
+        attrs.set("Cineon:FilmType", AttrValue::UInt(2));
+
+        let bytes = CineonWriter::new().write_to_memory(&image).expect("write failed");
+        let loaded = CineonReader::new().read_from_memory(&bytes).expect("read failed");
+        let attrs = &loaded.metadata.attrs;
+
+        assert_eq!(attrs.get("Format").and_then(|v| v.as_str()), Some("Cineon"));
+        assert_eq!(attrs.get("Descriptor").and_then(|v| v.as_u32()), Some(50));
+        assert_eq!(attrs.get("Packing").and_then(|v| v.as_u32()), Some(1));
+        assert_eq!(attrs.get("Cineon:Packing").and_then(|v| v.as_u32()), Some(5));
+        assert_eq!(attrs.get("FrameRate").and_then(|v| v.as_f32()), Some(24.0));
+        assert_eq!(attrs.get("FramePosition").and_then(|v| v.as_u32()), Some(1001));
+        assert_eq!(attrs.get("FilmFormat").and_then(|v| v.as_str()), Some("Academy"));
+        assert_eq!(attrs.get("InputDevice").and_then(|v| v.as_str()), Some("Northlight"));
+        assert_eq!(attrs.get("DateTime").and_then(|v| v.as_str()), Some("1998:04:01 12:30:00"));
+        assert_eq!(attrs.get("Cineon:FilmType").and_then(|v| v.as_u32()), Some(2));
+        assert!(attrs.get("Gamma").is_none());
+        assert!(attrs.get("Cineon:Prefix").is_none());
+    }
+
+    #[test]
+    fn test_bit_packed_and_orientation() {
+        // Gray 10-bit, packing 0, bottom-to-top: 2x2 samples in one word per line
+        let mut data = CineonWriter::new()
+            .write_to_memory(&ImageData::from_f32(2, 2, 1, vec![0.0; 4]))
+            .expect("write failed");
+        data[192] = 1;
+        data[681] = 0;
+        data.truncate(HEADER_SIZE as usize);
+        data.extend_from_slice(&((100u32 << 22) | (200 << 12)).to_be_bytes());
+        data.extend_from_slice(&((300u32 << 22) | (400 << 12)).to_be_bytes());
+
+        let image = CineonReader::new().read_from_memory(&data).expect("read failed");
+        let codes: Vec<u32> = image.to_f32().iter().map(|v| (v * 1023.0).round() as u32).collect();
+        assert_eq!(codes, [300, 400, 100, 200]);
+    }
+
+    #[test]
+    fn test_unset_eol_padding() {
+        let image = gradient(5, 3, 3);
+        let mut bytes = CineonWriter::new().write_to_memory(&image).expect("write failed");
+        bytes[684..688].copy_from_slice(&[0xFF; 4]);
+
+        let loaded = CineonReader::new().read_from_memory(&bytes).expect("read failed");
+        let expected = CineonReader::new()
+            .read_from_memory(&CineonWriter::new().write_to_memory(&image).expect("write failed"))
+            .expect("read failed");
+        assert_eq!(loaded.to_f32(), expected.to_f32());
+    }
+
+    #[test]
+    fn test_huge_dimensions() {
+        let mut bytes = CineonWriter::new()
+            .write_to_memory(&gradient(2, 2, 3))
+            .expect("write failed");
+        for c in 0..3 {
+            let base = 196 + c * 28;
+            bytes[base + 4..base + 8].copy_from_slice(&0x4000_0000u32.to_be_bytes());
+            bytes[base + 8..base + 12].copy_from_slice(&0x4000_0000u32.to_be_bytes());
+        }
+
+        let result = CineonReader::new().read_from_memory(&bytes);
+        assert!(matches!(result, Err(IoError::DecodeError(_))));
+    }
+
+    #[test]
+    fn test_can_read() {
+        let reader = CineonReader::new();
+        assert!(reader.can_read(&[0x80, 0x2A, 0x5F, 0xD7]));
+        assert!(reader.can_read(&[0xD7, 0x5F, 0x2A, 0x80]));
+        assert!(!reader.can_read(b"SDPX"));
+        assert!(!reader.can_read(&[0x80, 0x2A]));
+    }
+}
@@ -9,6 +9,8 @@
 //! - Support for 2D textures, cube maps, and texture arrays
 //! - Mipmap chain access
 //! - Automatic BC decompression to RGBA
+//! - Write BC1/BC3/BC4/BC5/BC6H/BC7 and uncompressed textures with mip
+//!   chains, cube maps and arrays (see [`DdsWriter`])
 //!
 //! # Example
 //!
@@ -24,10 +26,15 @@
 //! # Ok::<(), vfx_io::IoError>(())
 //! ```
 
+use crate::bcn::{
+    default_mip_options, BcFormat, BcQuality, GpuTexture, SurfaceFormat, TextureLayout,
+};
+use crate::imagebufalgo::MipmapFilter;
+use crate::traits::FormatWriter;
 use crate::{ImageData, IoError, IoResult};
 use ddsfile::Dds;
 use image_dds::{dds_image_format, ImageFormat, Surface};
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{BufReader, Cursor};
 use std::path::Path;
 
@@ -259,6 +266,274 @@ pub fn read_all_mips<P: AsRef<Path>>(path: P) -> IoResult<Vec<ImageData>> {
     Ok(mips)
 }
 
+// ============================================================================
+// Writer
+// ============================================================================
+
+const DDSD_CAPS: u32 = 0x1;
+const DDSD_HEIGHT: u32 = 0x2;
+const DDSD_WIDTH: u32 = 0x4;
+const DDSD_PITCH: u32 = 0x8;
+const DDSD_PIXELFORMAT: u32 = 0x1000;
+const DDSD_MIPMAPCOUNT: u32 = 0x2_0000;
+const DDSD_LINEARSIZE: u32 = 0x8_0000;
+const DDPF_FOURCC: u32 = 0x4;
+const DDSCAPS_COMPLEX: u32 = 0x8;
+const DDSCAPS_TEXTURE: u32 = 0x1000;
+const DDSCAPS_MIPMAP: u32 = 0x40_0000;
+const DDSCAPS2_CUBEMAP_ALL_FACES: u32 = 0xFE00;
+const D3D10_RESOURCE_DIMENSION_TEXTURE2D: u32 = 3;
+const D3D10_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+
+impl DdsFormat {
+    /// Surface encoding and DXGI format code for writing.
+    fn encoding(&self, srgb: bool) -> IoResult<(SurfaceFormat, u32)> {
+        let pick = |linear: u32, encoded: u32| if srgb { encoded } else { linear };
+        Ok(match self {
+            DdsFormat::R8 => (SurfaceFormat::R8, 61),
+            DdsFormat::Rg8 => (SurfaceFormat::Rg8, 49),
+            DdsFormat::Rgba8 => (SurfaceFormat::Rgba8, pick(28, 29)),
+            DdsFormat::Bgra8 => (SurfaceFormat::Bgra8, pick(87, 91)),
+            DdsFormat::Rgba16Float => (SurfaceFormat::Rgba16Float, 10),
+            DdsFormat::Rgba32Float => (SurfaceFormat::Rgba32Float, 2),
+            DdsFormat::Bc1 => (SurfaceFormat::Bc(BcFormat::Bc1), pick(71, 72)),
+            DdsFormat::Bc3 => (SurfaceFormat::Bc(BcFormat::Bc3), pick(77, 78)),
+            DdsFormat::Bc4 => (SurfaceFormat::Bc(BcFormat::Bc4), 80),
+            DdsFormat::Bc5 => (SurfaceFormat::Bc(BcFormat::Bc5), 83),
+            DdsFormat::Bc6h => (SurfaceFormat::Bc(BcFormat::Bc6h), 95),
+            DdsFormat::Bc7 => (SurfaceFormat::Bc(BcFormat::Bc7), pick(98, 99)),
+            DdsFormat::Bc2 | DdsFormat::Unknown => {
+                return Err(IoError::UnsupportedFeature(format!(
+                    "writing {:?} DDS",
+                    self
+                )));
+            }
+        })
+    }
+}
+
+impl From<BcFormat> for DdsFormat {
+    fn from(format: BcFormat) -> Self {
+        match format {
+            BcFormat::Bc1 => DdsFormat::Bc1,
+            BcFormat::Bc3 => DdsFormat::Bc3,
+            BcFormat::Bc4 => DdsFormat::Bc4,
+            BcFormat::Bc5 => DdsFormat::Bc5,
+            BcFormat::Bc6h => DdsFormat::Bc6h,
+            BcFormat::Bc7 => DdsFormat::Bc7,
+        }
+    }
+}
+
+/// Options for writing DDS files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::bcn::BcQuality;
+/// use vfx_io::dds::{DdsFormat, DdsWriter, DdsWriterOptions};
+///
+/// let writer = DdsWriter::with_options(DdsWriterOptions {
+///     format: DdsFormat::Bc5,
+///     quality: BcQuality::High,
+///     ..Default::default()
+/// });
+/// writer.write("normal.dds", &image)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct DdsWriterOptions {
+    /// Pixel format. BC2 cannot be written. Default: BC7.
+    pub format: DdsFormat,
+    /// BCn encoder effort. Default: [`BcQuality::Normal`].
+    pub quality: BcQuality,
+    /// Generate a full mip chain when writing a single image. Default: true.
+    pub mipmaps: bool,
+    /// Filter for generated mip levels. Default: bilinear.
+    pub mip_filter: MipmapFilter,
+    /// Data is sRGB-encoded: selects the `_SRGB` DXGI variant where one
+    /// exists and filters mips in linear light. Default: false.
+    pub srgb: bool,
+}
+
+impl Default for DdsWriterOptions {
+    fn default() -> Self {
+        Self {
+            format: DdsFormat::Bc7,
+            quality: BcQuality::Normal,
+            mipmaps: true,
+            mip_filter: MipmapFilter::default(),
+            srgb: false,
+        }
+    }
+}
+
+/// DDS texture writer.
+///
+/// Always writes the DX10 header extension, so every format, cube map
+/// and array size is described the same way.
+#[derive(Debug, Clone, Default)]
+pub struct DdsWriter {
+    options: DdsWriterOptions,
+}
+
+impl DdsWriter {
+    /// Creates a new writer with default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a writer with custom options.
+    pub fn with_options(options: DdsWriterOptions) -> Self {
+        Self { options }
+    }
+
+    /// Writes a texture with its existing mip chains and layout.
+    pub fn write_texture<P: AsRef<Path>>(&self, path: P, texture: &GpuTexture) -> IoResult<()> {
+        let data = self.write_texture_to_memory(texture)?;
+        fs::write(path.as_ref(), data)?;
+        Ok(())
+    }
+
+    /// Encodes a texture with its existing mip chains and layout.
+    pub fn write_texture_to_memory(&self, texture: &GpuTexture) -> IoResult<Vec<u8>> {
+        texture.validate()?;
+        let (surface, dxgi) = self.options.format.encoding(self.options.srgb)?;
+        let (width, height) = (texture.width(), texture.height());
+        let levels = texture.mip_count();
+        let cubemap = texture.layout == TextureLayout::Cubemap;
+
+        let mut flags = DDSD_CAPS | DDSD_HEIGHT | DDSD_WIDTH | DDSD_PIXELFORMAT;
+        let pitch = match surface {
+            SurfaceFormat::Bc(bc) => {
+                flags |= DDSD_LINEARSIZE;
+                bc.compressed_size(width, height)
+            }
+            _ => {
+                flags |= DDSD_PITCH;
+                width as usize * surface.block_bytes()
+            }
+        };
+        let mut caps = DDSCAPS_TEXTURE;
+        if levels > 1 {
+            flags |= DDSD_MIPMAPCOUNT;
+            caps |= DDSCAPS_COMPLEX | DDSCAPS_MIPMAP;
+        }
+        if cubemap || texture.surfaces.len() > 1 {
+            caps |= DDSCAPS_COMPLEX;
+        }
+
+        let mut out = Vec::new();
+        let mut put = |v: u32| out.extend_from_slice(&v.to_le_bytes());
+        put(u32::from_le_bytes(*b"DDS "));
+        put(124);
+        put(flags);
+        put(height);
+        put(width);
+        put(pitch as u32);
+        put(0); // depth
+        put(levels);
+        for _ in 0..11 {
+            put(0);
+        }
+        // Pixel format: defer to the DX10 header
+        put(32);
+        put(DDPF_FOURCC);
+        put(u32::from_le_bytes(*b"DX10"));
+        for _ in 0..5 {
+            put(0);
+        }
+        put(caps);
+        put(if cubemap {
+            DDSCAPS2_CUBEMAP_ALL_FACES
+        } else {
+            0
+        });
+        for _ in 0..3 {
+            put(0);
+        }
+        // DX10 header
+        put(dxgi);
+        put(D3D10_RESOURCE_DIMENSION_TEXTURE2D);
+        put(if cubemap {
+            D3D10_RESOURCE_MISC_TEXTURECUBE
+        } else {
+            0
+        });
+        put(texture.array_size());
+        put(0); // alpha mode unknown
+
+        for chain in &texture.surfaces {
+            for level in chain {
+                out.extend_from_slice(&surface.encode(level, self.options.quality)?);
+            }
+        }
+        tracing::debug!(
+            "DDS: {}x{} {:?}, {} levels, {} surfaces, {} bytes",
+            width,
+            height,
+            self.options.format,
+            levels,
+            texture.surfaces.len(),
+            out.len()
+        );
+        Ok(out)
+    }
+
+    /// Wraps a single image, adding mipmaps if enabled.
+    fn texture(&self, image: &ImageData) -> IoResult<GpuTexture> {
+        let texture = GpuTexture::from_image(image.clone());
+        if !self.options.mipmaps {
+            return Ok(texture);
+        }
+        texture.with_mipmaps(&default_mip_options(
+            self.options.mip_filter,
+            self.options.srgb,
+        ))
+    }
+}
+
+impl FormatWriter<DdsWriterOptions> for DdsWriter {
+    /// Returns "DDS".
+    fn format_name(&self) -> &'static str {
+        "DDS"
+    }
+
+    /// Returns `["dds"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["dds"]
+    }
+
+    /// Writes a 2D texture, with a mip chain if enabled.
+    fn write<P: AsRef<Path>>(&self, path: P, image: &ImageData) -> IoResult<()> {
+        self.write_texture(path, &self.texture(image)?)
+    }
+
+    /// Encodes a 2D texture, with a mip chain if enabled.
+    fn write_to_memory(&self, image: &ImageData) -> IoResult<Vec<u8>> {
+        self.write_texture_to_memory(&self.texture(image)?)
+    }
+
+    /// Creates writer with custom options.
+    fn with_options(options: DdsWriterOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Writes an image as a mipmapped BC7 DDS file.
+pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
+    DdsWriter::new().write(path, image)
+}
+
+/// Writes a texture (cube map, array or prebuilt mip chain) with the
+/// given options.
+pub fn write_texture<P: AsRef<Path>>(
+    path: P,
+    texture: &GpuTexture,
+    options: DdsWriterOptions,
+) -> IoResult<()> {
+    DdsWriter::with_options(options).write_texture(path, texture)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -272,4 +547,81 @@ mod tests {
         assert!(DdsFormat::Rgba32Float.is_hdr());
         assert!(!DdsFormat::Bc3.is_hdr());
     }
+
+    fn gradient(w: u32, h: u32) -> ImageData {
+        let mut data = Vec::with_capacity((w * h * 4) as usize);
+        for y in 0..h {
+            for x in 0..w {
+                let t = (x + y) as f32 / (w + h) as f32;
+                data.extend_from_slice(&[t, 1.0 - t, 0.25, 1.0]);
+            }
+        }
+        ImageData::from_f32(w, h, 4, data)
+    }
+
+    fn u32_at(data: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_write_header() {
+        let data = DdsWriter::new().write_to_memory(&gradient(32, 16)).unwrap();
+        assert_eq!(&data[0..4], b"DDS ");
+        assert_eq!(u32_at(&data, 12), 16);
+        assert_eq!(u32_at(&data, 16), 32);
+        assert_eq!(u32_at(&data, 28), 6);
+        assert_eq!(&data[84..88], b"DX10");
+        assert_eq!(u32_at(&data, 128), 98);
+        // 32x16, 16x8, 8x4, 4x2, 2x1, 1x1 in 4x4 blocks
+        let blocks = 32 + 8 + 2 + 1 + 1 + 1;
+        assert_eq!(data.len(), 148 + blocks * 16);
+    }
+
+    #[test]
+    fn test_write_cubemap_header() {
+        let faces = (0..6).map(|_| gradient(8, 8)).collect();
+        let texture = GpuTexture::cubemap(faces).unwrap();
+        let writer = DdsWriter::with_options(DdsWriterOptions {
+            format: DdsFormat::Rgba8,
+            ..Default::default()
+        });
+        let data = writer.write_texture_to_memory(&texture).unwrap();
+        assert_eq!(u32_at(&data, 112) & DDSCAPS2_CUBEMAP_ALL_FACES, DDSCAPS2_CUBEMAP_ALL_FACES);
+        assert_eq!(u32_at(&data, 136), D3D10_RESOURCE_MISC_TEXTURECUBE);
+        assert_eq!(u32_at(&data, 140), 1);
+        assert_eq!(data.len(), 148 + 6 * 8 * 8 * 4);
+
+        let info = read_info_from_memory(&data).unwrap();
+        assert!(info.is_cubemap);
+        assert_eq!(info.format, DdsFormat::Rgba8);
+    }
+
+    #[test]
+    fn test_write_read_roundtrip() {
+        let image = gradient(32, 32);
+        let src = image.to_f32();
+        for format in [DdsFormat::Rgba16Float, DdsFormat::Bc1, DdsFormat::Bc3, DdsFormat::Bc7] {
+            let writer = DdsWriter::with_options(DdsWriterOptions {
+                format,
+                ..Default::default()
+            });
+            let data = writer.write_to_memory(&image).unwrap();
+            let info = read_info_from_memory(&data).unwrap();
+            assert_eq!(info.format, format);
+            assert_eq!(info.mip_count, 6);
+
+            let back = read_from_memory(&data).unwrap().to_f32();
+            let err = src.iter().zip(&back).map(|(a, b)| (a - b).abs()).fold(0.0f32, f32::max);
+            assert!(err < 0.06, "{:?} max error {}", format, err);
+        }
+    }
+
+    #[test]
+    fn test_write_bc2_unsupported() {
+        let writer = DdsWriter::with_options(DdsWriterOptions {
+            format: DdsFormat::Bc2,
+            ..Default::default()
+        });
+        assert!(writer.write_to_memory(&gradient(4, 4)).is_err());
+    }
 }
@@ -34,6 +34,28 @@ pub enum Format {
     ArriRaw,
     /// RED REDCODE format (.r3d).
     RedCode,
+    /// Windows Bitmap format.
+    Bmp,
+    /// Truevision TGA format.
+    Tga,
+    /// Kodak Cineon format.
+    Cineon,
+    /// Netpbm formats (PBM, PGM, PPM, PAM).
+    Pnm,
+    /// Portable Float Map.
+    Pfm,
+    /// Adobe Digital Negative camera raw.
+    Dng,
+    /// SGI image format (.sgi, .rgb).
+    Sgi,
+    /// Softimage PIC format.
+    Softimage,
+    /// Maya IFF format.
+    Iff,
+    /// Wavefront RLA / 3ds Max RPF format.
+    Rla,
+    /// YUV4MPEG2 video frames.
+    Y4m,
     /// Unknown/unsupported format.
     Unknown,
 }
@@ -45,15 +67,23 @@ impl Format {
     pub fn detect<P: AsRef<Path>>(path: P) -> IoResult<Self> {
         let path = path.as_ref();
         
+        let from_extension = Self::from_extension(path);
+
         // Try magic bytes first
         if let Ok(format) = Self::from_magic_bytes(path) {
-            if format != Format::Unknown {
+            // DNG is a TIFF, told apart by its extension
+            if format == Format::Tiff && from_extension == Format::Dng {
+                return Ok(Format::Dng);
+            }
+            // TGA has no magic, so a known extension wins over the header check
+            let weak = format == Format::Tga && !matches!(from_extension, Format::Tga | Format::Unknown);
+            if format != Format::Unknown && !weak {
                 return Ok(format);
             }
         }
         
         // Fall back to extension
-        Ok(Self::from_extension(path))
+        Ok(from_extension)
     }
     
     /// Detects format from file extension only.
@@ -79,7 +109,8 @@ impl Format {
             "png" => Format::Png,
             "jpg" | "jpeg" => Format::Jpeg,
             "tif" | "tiff" => Format::Tiff,
-            "dpx" | "cineon" => Format::Dpx,
+            "dpx" => Format::Dpx,
+            "cin" | "cineon" => Format::Cineon,
             "hdr" | "pic" | "rgbe" | "radiance" => Format::Hdr,
             "heif" | "heic" | "hif" => Format::Heif,
             "webp" => Format::WebP,
@@ -87,6 +118,16 @@ impl Format {
             "jp2" | "j2k" | "j2c" | "jpx" | "jpeg2000" => Format::Jp2,
             "ari" | "arriraw" => Format::ArriRaw,
             "r3d" | "redcode" => Format::RedCode,
+            "bmp" | "dib" => Format::Bmp,
+            "tga" | "targa" | "tpic" | "icb" | "vda" | "vst" => Format::Tga,
+            "pnm" | "pbm" | "pgm" | "ppm" | "pam" | "netpbm" => Format::Pnm,
+            "pfm" => Format::Pfm,
+            "dng" => Format::Dng,
+            "sgi" | "rgb" | "rgba" | "bw" | "int" | "inta" => Format::Sgi,
+            "softimage" => Format::Softimage,
+            "iff" | "tdi" => Format::Iff,
+            "rla" | "rpf" => Format::Rla,
+            "y4m" | "yuv4mpeg" => Format::Y4m,
             _ => Format::Unknown,
         }
     }
@@ -94,7 +135,7 @@ impl Format {
     /// Detects format from file magic bytes.
     pub fn from_magic_bytes<P: AsRef<Path>>(path: P) -> IoResult<Self> {
         let mut file = File::open(path)?;
-        let mut header = [0u8; 12];  // 12 bytes needed for HEIF/JP2 detection
+        let mut header = [0u8; 28];  // 12 bytes needed for HEIF/JP2, 18 for TGA, 28 for RLA
         
         let bytes_read = file.read(&mut header)?;
         if bytes_read < 4 {
@@ -125,14 +166,14 @@ impl Format {
             return Format::Jpeg;
         }
         
-        // TIFF: II (little-endian) or MM (big-endian)
+        // TIFF: II (little-endian) or MM (big-endian), 42 or BigTIFF 43
         if bytes.len() >= 4 {
             // Little-endian TIFF
-            if bytes[0..4] == [0x49, 0x49, 0x2A, 0x00] {
+            if bytes[0..4] == [0x49, 0x49, 0x2A, 0x00] || bytes[0..4] == [0x49, 0x49, 0x2B, 0x00] {
                 return Format::Tiff;
             }
             // Big-endian TIFF
-            if bytes[0..4] == [0x4D, 0x4D, 0x00, 0x2A] {
+            if bytes[0..4] == [0x4D, 0x4D, 0x00, 0x2A] || bytes[0..4] == [0x4D, 0x4D, 0x00, 0x2B] {
                 return Format::Tiff;
             }
             // DPX big-endian: SDPX
@@ -189,6 +230,58 @@ impl Format {
             return Format::RedCode;
         }
 
+        // BMP: "BM" with zeroed reserved fields
+        if bytes[0..2] == [b'B', b'M'] && (bytes.len() < 10 || bytes[6..10] == [0, 0, 0, 0]) {
+            return Format::Bmp;
+        }
+
+        // Cineon: 0x802A5FD7 in either byte order
+        if bytes[0..4] == [0x80, 0x2A, 0x5F, 0xD7] || bytes[0..4] == [0xD7, 0x5F, 0x2A, 0x80] {
+            return Format::Cineon;
+        }
+
+        // PFM: "PF" or "Pf" followed by whitespace
+        if bytes.len() >= 3 && bytes[0] == b'P' && matches!(bytes[1], b'F' | b'f') && bytes[2].is_ascii_whitespace() {
+            return Format::Pfm;
+        }
+
+        // Netpbm: "P1" to "P7" followed by whitespace
+        if bytes.len() >= 3 && bytes[0] == b'P' && (b'1'..=b'7').contains(&bytes[1]) && bytes[2].is_ascii_whitespace() {
+            return Format::Pnm;
+        }
+
+        // SGI: 474 (big-endian), storage 0 or 1, 1 or 2 bytes per channel
+        if bytes[0..2] == [0x01, 0xDA] && bytes[2] <= 1 && matches!(bytes[3], 1 | 2) {
+            return Format::Sgi;
+        }
+
+        // Softimage PIC: 0x5380F634
+        if bytes[0..4] == [0x53, 0x80, 0xF6, 0x34] {
+            return Format::Softimage;
+        }
+
+        // Maya IFF: "FOR4" form of type "CIMG"
+        if bytes.len() >= 12 && &bytes[0..4] == b"FOR4" && &bytes[8..12] == b"CIMG" {
+            return Format::Iff;
+        }
+
+        // Y4M: "YUV4MPEG2"
+        if bytes.starts_with(b"YUV4MPEG2") {
+            return Format::Y4m;
+        }
+
+        // RLA: 0xFFFE revision at offset 26
+        #[cfg(feature = "rla")]
+        if crate::rla::is_rla_header(bytes) {
+            return Format::Rla;
+        }
+
+        // TGA: no magic, check the header fields for consistency
+        #[cfg(feature = "tga")]
+        if crate::tga::is_tga_header(bytes) {
+            return Format::Tga;
+        }
+
         Format::Unknown
     }
     
@@ -207,6 +300,17 @@ impl Format {
             Format::Jp2 => "jp2",
             Format::ArriRaw => "ari",
             Format::RedCode => "r3d",
+            Format::Bmp => "bmp",
+            Format::Tga => "tga",
+            Format::Cineon => "cin",
+            Format::Pnm => "pnm",
+            Format::Pfm => "pfm",
+            Format::Dng => "dng",
+            Format::Sgi => "sgi",
+            Format::Softimage => "pic",
+            Format::Iff => "iff",
+            Format::Rla => "rla",
+            Format::Y4m => "y4m",
             Format::Unknown => "",
         }
     }
@@ -226,18 +330,29 @@ impl Format {
             Format::Jp2 => "image/jp2",
             Format::ArriRaw => "image/x-arri-raw",
             Format::RedCode => "image/x-red-r3d",
+            Format::Bmp => "image/bmp",
+            Format::Tga => "image/x-tga",
+            Format::Cineon => "image/cineon",
+            Format::Pnm => "image/x-portable-anymap",
+            Format::Pfm => "image/x-portable-floatmap",
+            Format::Dng => "image/x-adobe-dng",
+            Format::Sgi => "image/x-sgi",
+            Format::Softimage => "image/x-softimage-pic",
+            Format::Iff => "image/x-iff",
+            Format::Rla => "image/x-rla",
+            Format::Y4m => "video/x-yuv4mpeg",
             Format::Unknown => "application/octet-stream",
         }
     }
     
     /// Returns true if this format supports HDR/float data.
     pub fn supports_hdr(&self) -> bool {
-        matches!(self, Format::Exr | Format::Tiff | Format::Hdr | Format::Heif | Format::Avif | Format::ArriRaw | Format::RedCode)
+        matches!(self, Format::Exr | Format::Tiff | Format::Hdr | Format::Heif | Format::Avif | Format::ArriRaw | Format::RedCode | Format::Pfm | Format::Dng | Format::Rla)
     }
     
     /// Returns true if this format supports alpha channel.
     pub fn supports_alpha(&self) -> bool {
-        matches!(self, Format::Exr | Format::Png | Format::Tiff | Format::Heif | Format::WebP | Format::Avif | Format::Jp2)
+        matches!(self, Format::Exr | Format::Png | Format::Tiff | Format::Heif | Format::WebP | Format::Avif | Format::Jp2 | Format::Bmp | Format::Tga | Format::Pnm | Format::Sgi | Format::Softimage | Format::Iff | Format::Rla)
     }
 }
 
@@ -257,6 +372,17 @@ mod tests {
         assert_eq!(Format::from_extension("test.dpx"), Format::Dpx);
         assert_eq!(Format::from_extension("test.hdr"), Format::Hdr);
         assert_eq!(Format::from_extension("test.pic"), Format::Hdr);
+        assert_eq!(Format::from_extension("test.bmp"), Format::Bmp);
+        assert_eq!(Format::from_extension("test.TGA"), Format::Tga);
+        assert_eq!(Format::from_extension("test.cin"), Format::Cineon);
+        assert_eq!(Format::from_extension("test.ppm"), Format::Pnm);
+        assert_eq!(Format::from_extension("test.pam"), Format::Pnm);
+        assert_eq!(Format::from_extension("test.pfm"), Format::Pfm);
+        assert_eq!(Format::from_extension("frame_0001.DNG"), Format::Dng);
+        assert_eq!(Format::from_extension("test.rgb"), Format::Sgi);
+        assert_eq!(Format::from_extension("test.iff"), Format::Iff);
+        assert_eq!(Format::from_extension("test.rpf"), Format::Rla);
+        assert_eq!(Format::from_extension("clip.y4m"), Format::Y4m);
         assert_eq!(Format::from_extension("test.unknown"), Format::Unknown);
     }
 
@@ -281,7 +407,11 @@ mod tests {
         // TIFF big-endian
         let tiff_be = [0x4D, 0x4D, 0x00, 0x2A, 0x00, 0x00, 0x00, 0x08];
         assert_eq!(Format::from_bytes(&tiff_be), Format::Tiff);
-        
+
+        // BigTIFF little-endian
+        let bigtiff = [0x49, 0x49, 0x2B, 0x00, 0x08, 0x00, 0x00, 0x00];
+        assert_eq!(Format::from_bytes(&bigtiff), Format::Tiff);
+
         // DPX big-endian
         let dpx_be = [0x53, 0x44, 0x50, 0x58, 0x00, 0x00, 0x00, 0x00];
         assert_eq!(Format::from_bytes(&dpx_be), Format::Dpx);
@@ -290,6 +420,42 @@ mod tests {
         let hdr = [b'#', b'?', b'R', b'A', b'D', b'I', b'A', b'N'];
         assert_eq!(Format::from_bytes(&hdr), Format::Hdr);
         
+        // Cineon magic, both byte orders
+        assert_eq!(Format::from_bytes(&[0x80, 0x2A, 0x5F, 0xD7, 0, 0, 8, 0]), Format::Cineon);
+        assert_eq!(Format::from_bytes(&[0xD7, 0x5F, 0x2A, 0x80, 0, 8, 0, 0]), Format::Cineon);
+
+        // Netpbm and PFM magic
+        assert_eq!(Format::from_bytes(b"P5\n16 16\n255\n"), Format::Pnm);
+        assert_eq!(Format::from_bytes(b"PF\n16 16\n-1.0\n"), Format::Pfm);
+
+        // BMP magic
+        let bmp = [b'B', b'M', 0x46, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x36, 0x00];
+        assert_eq!(Format::from_bytes(&bmp), Format::Bmp);
+
+        // SGI, Softimage PIC and Maya IFF magic
+        assert_eq!(Format::from_bytes(&[0x01, 0xDA, 0x01, 0x01, 0x00, 0x03]), Format::Sgi);
+        assert_eq!(Format::from_bytes(&[0x53, 0x80, 0xF6, 0x34, 0x40, 0x6D]), Format::Softimage);
+        assert_eq!(Format::from_bytes(b"FOR4\0\0\0\x40CIMG"), Format::Iff);
+        assert_eq!(Format::from_bytes(b"YUV4MPEG2 W16 H16"), Format::Y4m);
+
+        // RLA header: 0xFFFE revision, 10x10 windows
+        #[cfg(feature = "rla")]
+        {
+            let mut rla = [0u8; 28];
+            for offset in [2, 6, 10, 14] {
+                rla[offset + 1] = 9;
+            }
+            rla[26..28].copy_from_slice(&[0xFF, 0xFE]);
+            assert_eq!(Format::from_bytes(&rla), Format::Rla);
+        }
+
+        // TGA header: 24-bit true-color, 4x4
+        #[cfg(feature = "tga")]
+        {
+            let tga = [0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 4, 0, 24, 0];
+            assert_eq!(Format::from_bytes(&tga), Format::Tga);
+        }
+
         // Unknown
         let unknown = [0x00, 0x00, 0x00, 0x00];
         assert_eq!(Format::from_bytes(&unknown), Format::Unknown);
@@ -0,0 +1,301 @@
+//! DNG camera color: camera-native RGB to XYZ D50 and ACES AP0.
+//!
+//! Implements the DNG 1.6 "Mapping Camera Color Space to CIE XYZ Space"
+//! chapter for three-color cameras. When two calibrations are present the
+//! one whose illuminant is closest to D65 is used; the matrices are not
+//! interpolated by correlated color temperature.
+
+use super::ifd::tag;
+
+/// 3x3 matrix, row-major.
+pub(crate) type Mat3 = [[f64; 3]; 3];
+
+const IDENTITY: Mat3 = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+/// CIE XYZ of the D50 white point (Y = 1).
+const D50: [f64; 3] = [0.96422, 1.0, 0.82521];
+
+/// CIE XYZ of the ACES white point (Y = 1).
+const ACES_WHITE: [f64; 3] = [0.95265, 1.0, 1.00883];
+
+/// XYZ to ACES AP0 (SMPTE ST 2065-1).
+const XYZ_TO_AP0: Mat3 = [
+    [1.0498110175, 0.0, -0.0000974845],
+    [-0.4959030231, 1.3733130458, 0.0982400361],
+    [0.0, 0.0, 0.9912520182],
+];
+
+/// Bradford cone response matrix.
+const BRADFORD: Mat3 = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+pub(crate) fn mul(a: &Mat3, b: &Mat3) -> Mat3 {
+    let mut m = [[0.0; 3]; 3];
+    for (r, row) in m.iter_mut().enumerate() {
+        for (c, v) in row.iter_mut().enumerate() {
+            *v = (0..3).map(|k| a[r][k] * b[k][c]).sum();
+        }
+    }
+    m
+}
+
+pub(crate) fn mul_vec(m: &Mat3, v: [f64; 3]) -> [f64; 3] {
+    [
+        m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+        m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+        m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+    ]
+}
+
+pub(crate) fn inverse(m: &Mat3) -> Option<Mat3> {
+    let det = m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0]);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv = 1.0 / det;
+    Some([
+        [
+            (m[1][1] * m[2][2] - m[1][2] * m[2][1]) * inv,
+            (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inv,
+            (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inv,
+        ],
+        [
+            (m[1][2] * m[2][0] - m[1][0] * m[2][2]) * inv,
+            (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inv,
+            (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inv,
+        ],
+        [
+            (m[1][0] * m[2][1] - m[1][1] * m[2][0]) * inv,
+            (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inv,
+            (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inv,
+        ],
+    ])
+}
+
+fn diag(v: [f64; 3]) -> Mat3 {
+    [[v[0], 0.0, 0.0], [0.0, v[1], 0.0], [0.0, 0.0, v[2]]]
+}
+
+fn mat3(values: &[f64]) -> Option<Mat3> {
+    (values.len() == 9).then(|| {
+        [
+            [values[0], values[1], values[2]],
+            [values[3], values[4], values[5]],
+            [values[6], values[7], values[8]],
+        ]
+    })
+}
+
+/// Bradford chromatic adaptation from one white to another.
+fn bradford(src: [f64; 3], dst: [f64; 3]) -> Mat3 {
+    let s = mul_vec(&BRADFORD, src);
+    let d = mul_vec(&BRADFORD, dst);
+    let scale = diag([d[0] / s[0], d[1] / s[1], d[2] / s[2]]);
+    let inv = inverse(&BRADFORD).unwrap_or(IDENTITY);
+    mul(&inv, &mul(&scale, &BRADFORD))
+}
+
+/// Approximate color temperature of an EXIF LightSource code.
+fn illuminant_kelvin(code: u32) -> f64 {
+    match code {
+        17 | 3 => 2856.0,  // Standard light A, tungsten
+        18 => 4874.0,      // Standard light B
+        19 => 6774.0,      // Standard light C
+        23 => 5003.0,      // D50
+        20 => 5503.0,      // D55
+        21 | 10 => 6504.0, // D65, cloudy
+        22 => 7504.0,      // D75
+        24 => 3200.0,      // ISO studio tungsten
+        12 => 6430.0,      // Daylight fluorescent
+        13 => 5020.0,      // Day white fluorescent
+        14 => 4230.0,      // Cool white fluorescent
+        15 => 3450.0,      // White fluorescent
+        16 => 2940.0,      // Warm white fluorescent
+        11 => 7500.0,      // Shade
+        2 => 4150.0,       // Fluorescent
+        _ => 5500.0,       // Daylight, flash, unknown
+    }
+}
+
+/// Camera color calibration read from DNG tags.
+#[derive(Debug, Clone)]
+pub(crate) struct CameraProfile {
+    /// XYZ to reference camera (ColorMatrix).
+    color_matrix: Option<Mat3>,
+    /// White-balanced reference camera to XYZ D50 (ForwardMatrix).
+    forward_matrix: Option<Mat3>,
+    /// Individual camera to reference camera (CameraCalibration).
+    calibration: Mat3,
+    /// Analog gains (AnalogBalance).
+    analog_balance: [f64; 3],
+    /// Camera-native value of the scene white.
+    pub neutral: [f64; 3],
+}
+
+impl CameraProfile {
+    /// Reads the profile through a tag lookup, or `None` for cameras
+    /// that are not three-color.
+    pub fn from_tags(lookup: impl Fn(u16) -> Option<Vec<f64>>) -> Option<Self> {
+        let kelvin = |t| {
+            lookup(t)
+                .and_then(|v| v.first().copied())
+                .map(|c| illuminant_kelvin(c as u32))
+        };
+        let dist1 = kelvin(tag::CALIBRATION_ILLUMINANT_1).map(|k| (k - 6504.0).abs());
+        let dist2 = kelvin(tag::CALIBRATION_ILLUMINANT_2).map(|k| (k - 6504.0).abs());
+        let use_second = lookup(tag::COLOR_MATRIX_2).is_some()
+            && matches!((dist1, dist2), (Some(a), Some(b)) if b < a);
+
+        let (cm, fm, cc) = if use_second {
+            (
+                tag::COLOR_MATRIX_2,
+                tag::FORWARD_MATRIX_2,
+                tag::CAMERA_CALIBRATION_2,
+            )
+        } else {
+            (
+                tag::COLOR_MATRIX_1,
+                tag::FORWARD_MATRIX_1,
+                tag::CAMERA_CALIBRATION_1,
+            )
+        };
+
+        let color_matrix = lookup(cm).and_then(|v| mat3(&v));
+        let forward_matrix = lookup(fm).and_then(|v| mat3(&v));
+        if color_matrix.is_none() && forward_matrix.is_none() {
+            return None;
+        }
+        let calibration = lookup(cc).and_then(|v| mat3(&v)).unwrap_or(IDENTITY);
+        let analog_balance = match lookup(tag::ANALOG_BALANCE) {
+            Some(v) if v.len() == 3 => [v[0], v[1], v[2]],
+            _ => [1.0; 3],
+        };
+
+        let mut profile = Self {
+            color_matrix,
+            forward_matrix,
+            calibration,
+            analog_balance,
+            neutral: [1.0; 3],
+        };
+        profile.neutral = match (lookup(tag::AS_SHOT_NEUTRAL), lookup(tag::AS_SHOT_WHITE_XY)) {
+            (Some(n), _) if n.len() == 3 && n.iter().all(|&v| v > 0.0) => [n[0], n[1], n[2]],
+            (_, Some(xy)) if xy.len() == 2 && xy[1] > 0.0 => profile.neutral_for_xy(xy[0], xy[1]),
+            _ => [1.0; 3],
+        };
+        Some(profile)
+    }
+
+    /// AnalogBalance * CameraCalibration.
+    fn ab_cc(&self) -> Mat3 {
+        mul(&diag(self.analog_balance), &self.calibration)
+    }
+
+    /// Camera neutral for a white given as xy chromaticity.
+    fn neutral_for_xy(&self, x: f64, y: f64) -> [f64; 3] {
+        let Some(cm) = self.color_matrix else {
+            return [1.0; 3];
+        };
+        let xyz = [x / y, 1.0, (1.0 - x - y) / y];
+        let n = mul_vec(&mul(&self.ab_cc(), &cm), xyz);
+        let max = n.iter().cloned().fold(f64::MIN, f64::max);
+        if max > 0.0 {
+            [n[0] / max, n[1] / max, n[2] / max]
+        } else {
+            [1.0; 3]
+        }
+    }
+
+    /// Camera-native RGB to XYZ with a D50 white.
+    pub fn camera_to_xyz_d50(&self) -> Option<Mat3> {
+        let ab_cc_inv = inverse(&self.ab_cc())?;
+
+        if let Some(fm) = self.forward_matrix {
+            let reference = mul_vec(&ab_cc_inv, self.neutral);
+            if reference.iter().any(|&v| v <= 0.0) {
+                return None;
+            }
+            let balance = diag([1.0 / reference[0], 1.0 / reference[1], 1.0 / reference[2]]);
+            return Some(mul(&fm, &mul(&balance, &ab_cc_inv)));
+        }
+
+        let cm = self.color_matrix?;
+        let camera_to_xyz = inverse(&mul(&self.ab_cc(), &cm))?;
+        let white = mul_vec(&camera_to_xyz, self.neutral);
+        if white[1] <= 0.0 {
+            return None;
+        }
+        // Scale so the neutral maps to Y = 1, then adapt its white to D50
+        let normalized = camera_to_xyz.map(|row| row.map(|v| v / white[1]));
+        let white = white.map(|v| v / white[1]);
+        Some(mul(&bradford(white, D50), &normalized))
+    }
+
+    /// Camera-native RGB to ACES2065-1 (AP0, ACES white).
+    pub fn camera_to_aces(&self) -> Option<Mat3> {
+        let to_d50 = self.camera_to_xyz_d50()?;
+        Some(mul(&XYZ_TO_AP0, &mul(&bradford(D50, ACES_WHITE), &to_d50)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup_from(tags: Vec<(u16, Vec<f64>)>) -> impl Fn(u16) -> Option<Vec<f64>> {
+        move |t| tags.iter().find(|(k, _)| *k == t).map(|(_, v)| v.clone())
+    }
+
+    fn assert_white(m: &Mat3, neutral: [f64; 3]) {
+        let aces = mul_vec(m, neutral);
+        for v in aces {
+            assert!((v - 1.0).abs() < 1e-3, "neutral maps to {:?}", aces);
+        }
+    }
+
+    #[test]
+    fn test_forward_matrix_neutral_is_aces_white() {
+        // Forward matrix that maps (1,1,1) to D50, as the spec requires
+        let fm = vec![0.6, 0.3, 0.06422, 0.25, 0.7, 0.05, 0.0, 0.1, 0.72521];
+        let profile = CameraProfile::from_tags(lookup_from(vec![
+            (tag::FORWARD_MATRIX_1, fm),
+            (tag::AS_SHOT_NEUTRAL, vec![0.5, 1.0, 0.7]),
+        ]))
+        .unwrap();
+        assert_white(&profile.camera_to_aces().unwrap(), profile.neutral);
+    }
+
+    #[test]
+    fn test_color_matrix_neutral_is_aces_white() {
+        let cm = vec![0.9, -0.3, -0.05, -0.4, 1.2, 0.2, -0.05, 0.1, 0.6];
+        let profile = CameraProfile::from_tags(lookup_from(vec![
+            (tag::COLOR_MATRIX_1, cm),
+            (tag::AS_SHOT_NEUTRAL, vec![0.45, 1.0, 0.62]),
+        ]))
+        .unwrap();
+        assert_white(&profile.camera_to_aces().unwrap(), profile.neutral);
+    }
+
+    #[test]
+    fn test_picks_illuminant_closest_to_d65() {
+        let profile = CameraProfile::from_tags(lookup_from(vec![
+            (tag::CALIBRATION_ILLUMINANT_1, vec![17.0]),
+            (tag::CALIBRATION_ILLUMINANT_2, vec![21.0]),
+            (
+                tag::COLOR_MATRIX_1,
+                vec![2.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 2.0],
+            ),
+            (
+                tag::COLOR_MATRIX_2,
+                vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+            ),
+        ]))
+        .unwrap();
+        assert_eq!(profile.color_matrix, Some(IDENTITY));
+    }
+}
@@ -0,0 +1,288 @@
+//! Minimal TIFF IFD parsing for DNG.
+//!
+//! Reads the IFD chain and SubIFDs into tag maps. Only what the DNG
+//! decoder needs: numeric arrays, rationals, strings and raw bytes.
+
+use std::collections::HashMap;
+
+use crate::{IoError, IoResult};
+
+/// TIFF tags used by the DNG decoder.
+pub(crate) mod tag {
+    pub const NEW_SUBFILE_TYPE: u16 = 254;
+    pub const IMAGE_WIDTH: u16 = 256;
+    pub const IMAGE_LENGTH: u16 = 257;
+    pub const BITS_PER_SAMPLE: u16 = 258;
+    pub const COMPRESSION: u16 = 259;
+    pub const PHOTOMETRIC: u16 = 262;
+    pub const MAKE: u16 = 271;
+    pub const MODEL: u16 = 272;
+    pub const STRIP_OFFSETS: u16 = 273;
+    pub const ORIENTATION: u16 = 274;
+    pub const SAMPLES_PER_PIXEL: u16 = 277;
+    pub const ROWS_PER_STRIP: u16 = 278;
+    pub const STRIP_BYTE_COUNTS: u16 = 279;
+    pub const PLANAR_CONFIG: u16 = 284;
+    pub const SOFTWARE: u16 = 305;
+    pub const DATE_TIME: u16 = 306;
+    pub const ARTIST: u16 = 315;
+    pub const TILE_WIDTH: u16 = 322;
+    pub const TILE_LENGTH: u16 = 323;
+    pub const TILE_OFFSETS: u16 = 324;
+    pub const TILE_BYTE_COUNTS: u16 = 325;
+    pub const SUB_IFDS: u16 = 330;
+    pub const CFA_REPEAT_PATTERN_DIM: u16 = 33421;
+    pub const CFA_PATTERN: u16 = 33422;
+    pub const DNG_VERSION: u16 = 50706;
+    pub const UNIQUE_CAMERA_MODEL: u16 = 50708;
+    pub const CFA_PLANE_COLOR: u16 = 50710;
+    pub const CFA_LAYOUT: u16 = 50711;
+    pub const LINEARIZATION_TABLE: u16 = 50712;
+    pub const BLACK_LEVEL_REPEAT_DIM: u16 = 50713;
+    pub const BLACK_LEVEL: u16 = 50714;
+    pub const BLACK_LEVEL_DELTA_H: u16 = 50715;
+    pub const BLACK_LEVEL_DELTA_V: u16 = 50716;
+    pub const WHITE_LEVEL: u16 = 50717;
+    pub const DEFAULT_CROP_ORIGIN: u16 = 50719;
+    pub const DEFAULT_CROP_SIZE: u16 = 50720;
+    pub const COLOR_MATRIX_1: u16 = 50721;
+    pub const COLOR_MATRIX_2: u16 = 50722;
+    pub const CAMERA_CALIBRATION_1: u16 = 50723;
+    pub const CAMERA_CALIBRATION_2: u16 = 50724;
+    pub const ANALOG_BALANCE: u16 = 50727;
+    pub const AS_SHOT_NEUTRAL: u16 = 50728;
+    pub const AS_SHOT_WHITE_XY: u16 = 50729;
+    pub const BASELINE_EXPOSURE: u16 = 50730;
+    pub const CALIBRATION_ILLUMINANT_1: u16 = 50778;
+    pub const CALIBRATION_ILLUMINANT_2: u16 = 50779;
+    pub const ACTIVE_AREA: u16 = 50829;
+    pub const FORWARD_MATRIX_1: u16 = 50964;
+    pub const FORWARD_MATRIX_2: u16 = 50965;
+    pub const TIME_CODES: u16 = 51043;
+    pub const FRAME_RATE: u16 = 51044;
+}
+
+/// One IFD entry, with its value bytes resolved.
+#[derive(Debug, Clone)]
+pub(crate) struct Entry {
+    /// TIFF field type (1 = BYTE ... 12 = DOUBLE).
+    field_type: u16,
+    /// Number of values.
+    count: usize,
+    /// Raw value bytes, in file byte order.
+    bytes: Vec<u8>,
+}
+
+/// A parsed IFD.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Ifd {
+    entries: HashMap<u16, Entry>,
+    big_endian: bool,
+}
+
+/// Size in bytes of one value of a TIFF field type.
+fn type_size(field_type: u16) -> usize {
+    match field_type {
+        1 | 2 | 6 | 7 => 1,
+        3 | 8 => 2,
+        4 | 9 | 11 | 13 => 4,
+        5 | 10 | 12 => 8,
+        _ => 0,
+    }
+}
+
+impl Ifd {
+    fn u16_at(&self, b: &[u8]) -> u16 {
+        let b = [b[0], b[1]];
+        if self.big_endian {
+            u16::from_be_bytes(b)
+        } else {
+            u16::from_le_bytes(b)
+        }
+    }
+
+    fn u32_at(&self, b: &[u8]) -> u32 {
+        let b = [b[0], b[1], b[2], b[3]];
+        if self.big_endian {
+            u32::from_be_bytes(b)
+        } else {
+            u32::from_le_bytes(b)
+        }
+    }
+
+    /// Returns true if the tag is present.
+    pub fn has(&self, tag: u16) -> bool {
+        self.entries.contains_key(&tag)
+    }
+
+    /// Returns all values of a numeric tag as f64.
+    pub fn f64s(&self, tag: u16) -> Option<Vec<f64>> {
+        let e = self.entries.get(&tag)?;
+        let size = type_size(e.field_type);
+        if size == 0 || e.field_type == 2 {
+            return None;
+        }
+        let values = e
+            .bytes
+            .chunks_exact(size)
+            .take(e.count)
+            .map(|b| match e.field_type {
+                1 | 7 => b[0] as f64,
+                6 => b[0] as i8 as f64,
+                3 => self.u16_at(b) as f64,
+                8 => self.u16_at(b) as i16 as f64,
+                4 | 13 => self.u32_at(b) as f64,
+                9 => self.u32_at(b) as i32 as f64,
+                5 => {
+                    let d = self.u32_at(&b[4..]);
+                    if d == 0 {
+                        0.0
+                    } else {
+                        self.u32_at(b) as f64 / d as f64
+                    }
+                }
+                10 => {
+                    let d = self.u32_at(&b[4..]) as i32;
+                    if d == 0 {
+                        0.0
+                    } else {
+                        self.u32_at(b) as i32 as f64 / d as f64
+                    }
+                }
+                11 => f32::from_bits(self.u32_at(b)) as f64,
+                _ => {
+                    let v = if self.big_endian {
+                        u64::from_be_bytes(b.try_into().unwrap_or_default())
+                    } else {
+                        u64::from_le_bytes(b.try_into().unwrap_or_default())
+                    };
+                    f64::from_bits(v)
+                }
+            })
+            .collect();
+        Some(values)
+    }
+
+    /// Returns the first value of a numeric tag.
+    pub fn f64(&self, tag: u16) -> Option<f64> {
+        self.f64s(tag)?.first().copied()
+    }
+
+    /// Returns all values of an integer tag.
+    pub fn u32s(&self, tag: u16) -> Option<Vec<u32>> {
+        Some(self.f64s(tag)?.into_iter().map(|v| v as u32).collect())
+    }
+
+    /// Returns the first value of an integer tag.
+    pub fn u32(&self, tag: u16) -> Option<u32> {
+        self.f64(tag).map(|v| v as u32)
+    }
+
+    /// Returns an ASCII tag, trimmed of trailing NULs.
+    pub fn string(&self, tag: u16) -> Option<String> {
+        let e = self.entries.get(&tag)?;
+        let end = e
+            .bytes
+            .iter()
+            .position(|&b| b == 0)
+            .unwrap_or(e.bytes.len());
+        let s = String::from_utf8_lossy(&e.bytes[..end]).trim().to_string();
+        (!s.is_empty()).then_some(s)
+    }
+
+    /// Returns the raw bytes of a tag.
+    pub fn bytes(&self, tag: u16) -> Option<&[u8]> {
+        self.entries.get(&tag).map(|e| e.bytes.as_slice())
+    }
+}
+
+/// A parsed TIFF/DNG file: the main IFD chain plus all SubIFDs.
+#[derive(Debug, Clone)]
+pub(crate) struct TiffFile {
+    /// All IFDs, IFD0 first.
+    pub ifds: Vec<Ifd>,
+    /// True if the file is big-endian ("MM").
+    pub big_endian: bool,
+}
+
+impl TiffFile {
+    /// Parses the IFD structure of a TIFF file.
+    pub fn parse(data: &[u8]) -> IoResult<Self> {
+        let big_endian = match data.get(..4) {
+            Some([b'I', b'I', 42, 0]) => false,
+            Some([b'M', b'M', 0, 42]) => true,
+            _ => return Err(IoError::InvalidFile("not a TIFF/DNG file".into())),
+        };
+        let probe = Ifd {
+            entries: HashMap::new(),
+            big_endian,
+        };
+
+        let first = data
+            .get(4..8)
+            .ok_or_else(|| IoError::InvalidFile("TIFF header truncated".into()))?;
+
+        let mut ifds = Vec::new();
+        let mut pending = vec![probe.u32_at(first) as usize];
+        let mut visited = Vec::new();
+
+        while let Some(offset) = pending.pop() {
+            if offset == 0 || visited.contains(&offset) || visited.len() > 64 {
+                continue;
+            }
+            visited.push(offset);
+
+            let (ifd, next) = Self::parse_ifd(data, offset, big_endian)?;
+            if next != 0 {
+                pending.push(next);
+            }
+            if let Some(subs) = ifd.u32s(tag::SUB_IFDS) {
+                pending.extend(subs.into_iter().rev().map(|o| o as usize));
+            }
+            ifds.push(ifd);
+        }
+
+        Ok(Self { ifds, big_endian })
+    }
+
+    fn parse_ifd(data: &[u8], offset: usize, big_endian: bool) -> IoResult<(Ifd, usize)> {
+        let truncated = || IoError::InvalidFile("TIFF IFD truncated".into());
+        let mut ifd = Ifd {
+            entries: HashMap::new(),
+            big_endian,
+        };
+
+        let count = ifd.u16_at(data.get(offset..offset + 2).ok_or_else(truncated)?) as usize;
+        let table = data
+            .get(offset + 2..offset + 2 + count * 12 + 4)
+            .ok_or_else(truncated)?;
+
+        for raw in table[..count * 12].chunks_exact(12) {
+            let tag = ifd.u16_at(raw);
+            let field_type = ifd.u16_at(&raw[2..]);
+            let n = ifd.u32_at(&raw[4..]) as usize;
+            let size = type_size(field_type).max(1) * n;
+            let bytes = if size <= 4 {
+                raw[8..8 + size].to_vec()
+            } else {
+                let at = ifd.u32_at(&raw[8..]) as usize;
+                match data.get(at..at.saturating_add(size)) {
+                    Some(b) => b.to_vec(),
+                    // Skip entries pointing outside the file
+                    None => continue,
+                }
+            };
+            ifd.entries.insert(
+                tag,
+                Entry {
+                    field_type,
+                    count: n,
+                    bytes,
+                },
+            );
+        }
+
+        let next = ifd.u32_at(&table[count * 12..]) as usize;
+        Ok((ifd, next))
+    }
+}
@@ -0,0 +1,499 @@
+//! Lossless JPEG (ITU T.81 process 14) decoder.
+//!
+//! DNG stores compressed raw tiles as lossless Huffman JPEG ("LJPEG-92").
+//! Supports predictors 1-7, point transform, restart intervals and any
+//! number of components, all with 1x1 sampling as used by DNG.
+//!
+//! The decoded samples are returned row by row with components
+//! interleaved, which is exactly the sample order of the DNG tile.
+
+use crate::{IoError, IoResult};
+
+/// Huffman table in the canonical form of T.81 Annex F.
+#[derive(Debug, Clone, Default)]
+struct HuffmanTable {
+    /// Largest code of each length (1-16), -1 if none.
+    max_code: [i32; 17],
+    /// Index into `values` of the first code of each length, minus that code.
+    offset: [i32; 17],
+    values: Vec<u8>,
+}
+
+impl HuffmanTable {
+    fn new(counts: &[u8; 16], values: Vec<u8>) -> Self {
+        let mut table = Self {
+            max_code: [-1; 17],
+            offset: [0; 17],
+            values,
+        };
+        let mut code = 0i32;
+        let mut index = 0i32;
+        for len in 1..=16 {
+            let n = counts[len - 1] as i32;
+            if n > 0 {
+                table.offset[len] = index - code;
+                code += n;
+                index += n;
+                table.max_code[len] = code - 1;
+            }
+            code <<= 1;
+        }
+        table
+    }
+
+    fn decode(&self, bits: &mut BitReader) -> IoResult<u8> {
+        let mut code = 0i32;
+        for len in 1..=16 {
+            code = (code << 1) | bits.bit() as i32;
+            if code <= self.max_code[len] {
+                let index = (code + self.offset[len]) as usize;
+                return self.values.get(index).copied().ok_or_else(|| {
+                    IoError::DecodeError("invalid lossless JPEG Huffman code".into())
+                });
+            }
+        }
+        Err(IoError::DecodeError(
+            "invalid lossless JPEG Huffman code".into(),
+        ))
+    }
+}
+
+/// Bit reader over entropy-coded data, handling byte stuffing and markers.
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    buffer: u64,
+    count: u32,
+    /// Set once a marker is reached; zeros are fed from then on.
+    marker: bool,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8], pos: usize) -> Self {
+        Self {
+            data,
+            pos,
+            buffer: 0,
+            count: 0,
+            marker: false,
+        }
+    }
+
+    fn fill(&mut self) {
+        while self.count <= 56 {
+            let mut byte = 0u8;
+            if !self.marker && self.pos < self.data.len() {
+                byte = self.data[self.pos];
+                if byte == 0xFF {
+                    match self.data.get(self.pos + 1) {
+                        Some(0x00) => self.pos += 2,
+                        _ => {
+                            self.marker = true;
+                            byte = 0;
+                        }
+                    }
+                } else {
+                    self.pos += 1;
+                }
+            }
+            self.buffer |= (byte as u64) << (56 - self.count);
+            self.count += 8;
+        }
+    }
+
+    fn bit(&mut self) -> u32 {
+        self.bits(1)
+    }
+
+    fn bits(&mut self, n: u32) -> u32 {
+        if n == 0 {
+            return 0;
+        }
+        if self.count < n {
+            self.fill();
+        }
+        let v = (self.buffer >> (64 - n)) as u32;
+        self.buffer <<= n;
+        self.count -= n;
+        v
+    }
+
+    /// Skips to the next restart marker and resets the bit buffer.
+    fn restart(&mut self) -> IoResult<()> {
+        self.buffer = 0;
+        self.count = 0;
+        self.marker = false;
+        while self.pos + 1 < self.data.len() {
+            if self.data[self.pos] == 0xFF && (0xD0..=0xD7).contains(&self.data[self.pos + 1]) {
+                self.pos += 2;
+                return Ok(());
+            }
+            self.pos += 1;
+        }
+        Err(IoError::DecodeError(
+            "lossless JPEG restart marker not found".into(),
+        ))
+    }
+}
+
+/// Decodes a lossless JPEG stream into its samples.
+pub(crate) fn decode(data: &[u8]) -> IoResult<Vec<u16>> {
+    let invalid = |msg: &str| IoError::DecodeError(format!("lossless JPEG: {}", msg));
+    if data.get(..2) != Some(&[0xFF, 0xD8]) {
+        return Err(invalid("missing SOI marker"));
+    }
+
+    let mut tables: [Option<HuffmanTable>; 4] = Default::default();
+    let mut precision = 0u32;
+    let (mut width, mut height) = (0usize, 0usize);
+    let mut component_ids: Vec<u8> = Vec::new();
+    let mut restart_interval = 0usize;
+    let mut pos = 2;
+
+    loop {
+        // Find the next marker
+        while pos < data.len() && data[pos] != 0xFF {
+            pos += 1;
+        }
+        while pos < data.len() && data[pos] == 0xFF {
+            pos += 1;
+        }
+        let marker = *data.get(pos).ok_or_else(|| invalid("missing SOS marker"))?;
+        pos += 1;
+        let len = u16::from_be_bytes([
+            *data.get(pos).ok_or_else(|| invalid("truncated segment"))?,
+            *data
+                .get(pos + 1)
+                .ok_or_else(|| invalid("truncated segment"))?,
+        ]) as usize;
+        let segment = data
+            .get(pos + 2..pos + len)
+            .ok_or_else(|| invalid("truncated segment"))?;
+
+        match marker {
+            0xC3 => {
+                precision = segment[0] as u32;
+                height = u16::from_be_bytes([segment[1], segment[2]]) as usize;
+                width = u16::from_be_bytes([segment[3], segment[4]]) as usize;
+                let n = segment[5] as usize;
+                for c in 0..n {
+                    let spec = segment
+                        .get(6 + c * 3..9 + c * 3)
+                        .ok_or_else(|| invalid("truncated SOF3"))?;
+                    if spec[1] != 0x11 {
+                        return Err(IoError::UnsupportedFeature(
+                            "lossless JPEG with subsampling".into(),
+                        ));
+                    }
+                    component_ids.push(spec[0]);
+                }
+            }
+            0xC0..=0xC2 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF => {
+                return Err(IoError::UnsupportedFeature("lossy JPEG in DNG".into()));
+            }
+            0xC4 => {
+                let mut p = 0;
+                while p + 17 <= segment.len() {
+                    let class_id = segment[p];
+                    let mut counts = [0u8; 16];
+                    counts.copy_from_slice(&segment[p + 1..p + 17]);
+                    let total: usize = counts.iter().map(|&c| c as usize).sum();
+                    let values = segment
+                        .get(p + 17..p + 17 + total)
+                        .ok_or_else(|| invalid("truncated DHT"))?
+                        .to_vec();
+                    tables[(class_id & 3) as usize] = Some(HuffmanTable::new(&counts, values));
+                    p += 17 + total;
+                }
+            }
+            0xDD => {
+                restart_interval = u16::from_be_bytes([segment[0], segment[1]]) as usize;
+            }
+            0xDA => {
+                let n = segment[0] as usize;
+                let mut selectors = Vec::with_capacity(n);
+                for c in 0..n {
+                    let id = segment[1 + c * 2];
+                    let table = (segment[2 + c * 2] >> 4) as usize;
+                    let index = component_ids.iter().position(|&i| i == id).unwrap_or(c);
+                    selectors.push((index, table & 3));
+                }
+                let predictor = segment[1 + n * 2] as u32;
+                let point_transform = (segment[3 + n * 2] & 0x0F) as u32;
+                if width == 0 || height == 0 || component_ids.is_empty() {
+                    return Err(invalid("missing SOF3 before SOS"));
+                }
+                if n != component_ids.len() {
+                    return Err(IoError::UnsupportedFeature(
+                        "non-interleaved lossless JPEG scans".into(),
+                    ));
+                }
+
+                let huffman: Vec<&HuffmanTable> = selectors
+                    .iter()
+                    .map(|&(_, t)| {
+                        tables[t]
+                            .as_ref()
+                            .ok_or_else(|| invalid("missing Huffman table"))
+                    })
+                    .collect::<IoResult<_>>()?;
+                let frame = Frame {
+                    width,
+                    height,
+                    components: n,
+                    precision,
+                    predictor,
+                    point_transform,
+                    restart_interval,
+                };
+                return decode_scan(data, pos + len, &frame, &huffman);
+            }
+            0xD9 => return Err(invalid("missing SOS marker")),
+            _ => {}
+        }
+        pos += len;
+    }
+}
+
+/// Scan parameters.
+struct Frame {
+    width: usize,
+    height: usize,
+    components: usize,
+    precision: u32,
+    predictor: u32,
+    point_transform: u32,
+    restart_interval: usize,
+}
+
+/// Decodes the entropy-coded scan.
+fn decode_scan(
+    data: &[u8],
+    start: usize,
+    frame: &Frame,
+    huffman: &[&HuffmanTable],
+) -> IoResult<Vec<u16>> {
+    let nc = frame.components;
+    let row_len = frame.width * nc;
+    let mut out = vec![0u16; row_len * frame.height];
+    let mut bits = BitReader::new(data, start);
+    let initial = 1i32 << (frame.precision - frame.point_transform - 1).min(15);
+    let mask = 0xFFFFi32;
+
+    // Rows since the last restart; prediction restarts like the first row
+    let restart_rows = if frame.restart_interval > 0 {
+        (frame.restart_interval / frame.width).max(1)
+    } else {
+        usize::MAX
+    };
+    let mut rows_since_restart = 0usize;
+
+    for y in 0..frame.height {
+        if rows_since_restart == restart_rows {
+            bits.restart()?;
+            rows_since_restart = 0;
+        }
+        let first_row = rows_since_restart == 0;
+
+        for x in 0..frame.width {
+            for (c, table) in huffman.iter().enumerate() {
+                let ssss = table.decode(&mut bits)? as u32;
+                let diff = match ssss {
+                    0 => 0,
+                    16 => 32768,
+                    _ => {
+                        let v = bits.bits(ssss) as i32;
+                        if v < 1 << (ssss - 1) {
+                            v - (1 << ssss) + 1
+                        } else {
+                            v
+                        }
+                    }
+                };
+
+                let i = y * row_len + x * nc + c;
+                let ra = || out[i - nc] as i32;
+                let rb = || out[i - row_len] as i32;
+                let rc = || out[i - row_len - nc] as i32;
+                let pred = if first_row {
+                    if x == 0 { initial } else { ra() }
+                } else if x == 0 {
+                    rb()
+                } else {
+                    match frame.predictor {
+                        1 => ra(),
+                        2 => rb(),
+                        3 => rc(),
+                        4 => ra() + rb() - rc(),
+                        5 => ra() + ((rb() - rc()) >> 1),
+                        6 => rb() + ((ra() - rc()) >> 1),
+                        7 => (ra() + rb()) >> 1,
+                        _ => ra(),
+                    }
+                };
+                out[i] = ((pred + diff) & mask) as u16;
+            }
+        }
+        rows_since_restart += 1;
+    }
+
+    if frame.point_transform > 0 {
+        for v in &mut out {
+            *v <<= frame.point_transform;
+        }
+    }
+    Ok(out)
+}
+
+/// Encodes samples as lossless JPEG with predictor 1 (test helper).
+///
+/// Uses a single Huffman table where category `n` has a code of
+/// `n + 1` bits, which is simple and valid for any input.
+#[cfg(test)]
+pub(crate) fn encode(
+    samples: &[u16],
+    width: usize,
+    height: usize,
+    components: usize,
+    precision: u8,
+) -> Vec<u8> {
+    let mut out = vec![0xFF, 0xD8];
+
+    // SOF3
+    let mut sof = vec![precision];
+    sof.extend_from_slice(&(height as u16).to_be_bytes());
+    sof.extend_from_slice(&(width as u16).to_be_bytes());
+    sof.push(components as u8);
+    for c in 0..components {
+        sof.extend_from_slice(&[c as u8 + 1, 0x11, 0]);
+    }
+    push_segment(&mut out, 0xC3, &sof);
+
+    // DHT: categories 0-16 with lengths 1..=16, 17 codes would overflow,
+    // so category 16 shares length 16 with category 15.
+    let mut counts = [1u8; 16];
+    counts[15] = 2;
+    let mut dht = vec![0x00];
+    dht.extend_from_slice(&counts);
+    dht.extend((0..=16).map(|n| n as u8));
+    push_segment(&mut out, 0xC4, &dht);
+
+    // SOS
+    let mut sos = vec![components as u8];
+    for c in 0..components {
+        sos.extend_from_slice(&[c as u8 + 1, 0x00]);
+    }
+    sos.extend_from_slice(&[1, 0, 0]);
+    push_segment(&mut out, 0xDA, &sos);
+
+    // Canonical codes for the table above: (code, length) per category
+    let mut codes = Vec::with_capacity(17);
+    let mut code = 0u32;
+    for (len, &n) in counts.iter().enumerate() {
+        for _ in 0..n {
+            codes.push((code, len as u32 + 1));
+            code += 1;
+        }
+        code <<= 1;
+    }
+
+    let mut writer = BitWriter::default();
+    let row_len = width * components;
+    let initial = 1i32 << (precision - 1);
+    for y in 0..height {
+        for x in 0..width {
+            for c in 0..components {
+                let i = y * row_len + x * components + c;
+                let pred = match (y, x) {
+                    (0, 0) => initial,
+                    (_, 0) => samples[i - row_len] as i32,
+                    _ => samples[i - components] as i32,
+                };
+                let mut diff = (samples[i] as i32 - pred) & 0xFFFF;
+                if diff >= 32768 {
+                    diff -= 65536;
+                }
+                let ssss = if diff == 0 {
+                    0
+                } else {
+                    32 - diff.unsigned_abs().leading_zeros()
+                };
+                let (code, len) = codes[ssss as usize];
+                writer.put(code, len);
+                if ssss > 0 && ssss < 16 {
+                    let v = if diff < 0 { diff - 1 } else { diff };
+                    writer.put(v as u32 & ((1 << ssss) - 1), ssss);
+                }
+            }
+        }
+    }
+    out.extend(writer.finish());
+    out.extend_from_slice(&[0xFF, 0xD9]);
+    out
+}
+
+#[cfg(test)]
+fn push_segment(out: &mut Vec<u8>, marker: u8, payload: &[u8]) {
+    out.extend_from_slice(&[0xFF, marker]);
+    out.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+    out.extend_from_slice(payload);
+}
+
+#[cfg(test)]
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    count: u32,
+}
+
+#[cfg(test)]
+impl BitWriter {
+    fn put(&mut self, value: u32, len: u32) {
+        self.acc = (self.acc << len) | value as u64;
+        self.count += len;
+        while self.count >= 8 {
+            self.count -= 8;
+            let byte = (self.acc >> self.count) as u8;
+            self.bytes.push(byte);
+            if byte == 0xFF {
+                self.bytes.push(0);
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            let pad = 8 - self.count;
+            self.put((1 << pad) - 1, pad);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_two_components() {
+        let (w, h, nc) = (5, 4, 2);
+        let samples: Vec<u16> = (0..w * h * nc).map(|i| ((i * 977) % 4096) as u16).collect();
+        let jpeg = encode(&samples, w, h, nc, 12);
+
+        assert_eq!(decode(&jpeg).expect("decode failed"), samples);
+    }
+
+    #[test]
+    fn test_roundtrip_16bit_extremes() {
+        let samples = vec![0u16, 65535, 0, 32768, 1, 65534];
+        let jpeg = encode(&samples, 3, 2, 1, 16);
+        assert_eq!(decode(&jpeg).expect("decode failed"), samples);
+    }
+
+    #[test]
+    fn test_rejects_baseline_jpeg() {
+        let data = [0xFF, 0xD8, 0xFF, 0xC0, 0x00, 0x02];
+        assert!(decode(&data).is_err());
+    }
+}
@@ -0,0 +1,1059 @@
+//! DNG (Adobe Digital Negative) raw decoding.
+//!
+//! Decodes the raw image of a DNG file (and of every CinemaDNG frame)
+//! into a developed, scene-linear image.
+//!
+//! # Pipeline
+//!
+//! 1. Pick the full-resolution raw IFD (CFA or LinearRaw), including SubIFDs
+//! 2. Decode uncompressed or lossless JPEG (LJPEG-92) strips and tiles
+//! 3. Apply `LinearizationTable`, subtract `BlackLevel` (with repeat pattern
+//!    and row/column deltas), scale by `WhiteLevel` to 0..1
+//! 4. Crop to `ActiveArea` and demosaic the CFA via [`imagebufalgo::demosaic`]
+//! 5. Convert camera-native RGB to the requested output
+//! 6. Crop to `DefaultCropOrigin` / `DefaultCropSize`
+//!
+//! # Output
+//!
+//! - [`DngOutput::Mosaic`]: normalized single-channel CFA mosaic, the Bayer
+//!   layout is reported in `DNG:CFAPattern`
+//! - [`DngOutput::CameraNative`]: demosaiced camera RGB, optionally white
+//!   balanced by `AsShotNeutral`
+//! - [`DngOutput::AcesLinear`]: scene-linear ACES2065-1 from `ForwardMatrix`
+//!   or `ColorMatrix` (default)
+//!
+//! `BaselineExposure` is reported as metadata but not applied.
+//!
+//! # Limitations
+//!
+//! - Only 2x2 Bayer CFAs (no X-Trans or other layouts)
+//! - Lossy JPEG and JPEG XL compressed DNGs are not supported
+//! - Camera matrices are taken from the calibration illuminant closest
+//!   to D65, without temperature interpolation
+//!
+//! # Example
+//!
+//! ```ignore
+//! use vfx_io::dng::{DngOutput, DngReader, DngReaderOptions};
+//! use vfx_io::FormatReader;
+//!
+//! let aces = vfx_io::dng::read("frame.dng")?;
+//!
+//! let reader = DngReader::with_options(DngReaderOptions {
+//!     output: DngOutput::CameraNative,
+//!     ..Default::default()
+//! });
+//! let camera = reader.read("frame.dng")?;
+//! ```
+//!
+//! [`imagebufalgo::demosaic`]: crate::imagebufalgo::demosaic
+
+mod color;
+mod ifd;
+mod ljpeg;
+
+use std::path::Path;
+
+use tracing::debug;
+
+use self::color::CameraProfile;
+use self::ifd::{Ifd, TiffFile, tag};
+use crate::imagebuf::ImageBuf;
+use crate::imagebufalgo::{BayerPattern, DemosaicAlgorithm, demosaic};
+use crate::{AttrValue, FormatReader, ImageData, IoError, IoResult, Metadata};
+
+/// Photometric interpretation of a CFA raw.
+const PHOTOMETRIC_CFA: u32 = 32803;
+/// Photometric interpretation of a linear (already demosaiced) raw.
+const PHOTOMETRIC_LINEAR_RAW: u32 = 34892;
+
+// ============================================================================
+// Reader Options
+// ============================================================================
+
+/// What the DNG reader produces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DngOutput {
+    /// Linearized, black-subtracted CFA mosaic (1 channel, 0..1).
+    Mosaic,
+    /// Demosaiced camera-native RGB.
+    CameraNative,
+    /// Scene-linear ACES2065-1 (AP0).
+    #[default]
+    AcesLinear,
+}
+
+/// Options for reading DNG files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::dng::{DngReader, DngReaderOptions};
+/// use vfx_io::imagebufalgo::DemosaicAlgorithm;
+/// use vfx_io::FormatReader;
+///
+/// let reader = DngReader::with_options(DngReaderOptions {
+///     demosaic: DemosaicAlgorithm::Bilinear,
+///     crop: false,
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct DngReaderOptions {
+    /// Output representation (default: ACES2065-1).
+    pub output: DngOutput,
+    /// Demosaicing algorithm for CFA raws (default: VNG).
+    pub demosaic: DemosaicAlgorithm,
+    /// Divide camera-native output by `AsShotNeutral` (default: true).
+    ///
+    /// ACES output is always white balanced through the camera matrices.
+    pub white_balance: bool,
+    /// Apply the DNG default crop (default: true).
+    pub crop: bool,
+}
+
+impl Default for DngReaderOptions {
+    fn default() -> Self {
+        Self {
+            output: DngOutput::AcesLinear,
+            demosaic: DemosaicAlgorithm::default(),
+            white_balance: true,
+            crop: true,
+        }
+    }
+}
+
+// ============================================================================
+// DngReader
+// ============================================================================
+
+/// DNG raw reader.
+///
+/// Implements [`FormatReader`] for DNG camera raw files.
+#[derive(Debug, Clone)]
+pub struct DngReader {
+    options: DngReaderOptions,
+}
+
+impl DngReader {
+    /// Creates a new reader with default options.
+    pub fn new() -> Self {
+        Self::with_options(DngReaderOptions::default())
+    }
+
+    /// Decodes a DNG file from memory.
+    fn read_impl(&self, data: &[u8]) -> IoResult<ImageData> {
+        let file = TiffFile::parse(data)?;
+        let ifd0 = file
+            .ifds
+            .first()
+            .ok_or_else(|| IoError::InvalidFile("DNG has no IFD".into()))?;
+        if !ifd0.has(tag::DNG_VERSION) {
+            return Err(IoError::InvalidFile(
+                "not a DNG file (missing DNGVersion)".into(),
+            ));
+        }
+        let raw = find_raw_ifd(&file).ok_or_else(|| {
+            IoError::UnsupportedFeature("DNG without a CFA or LinearRaw image".into())
+        })?;
+
+        // Color and camera tags may live in the raw IFD or in IFD0
+        let lookup = |t: u16| raw.f64s(t).or_else(|| ifd0.f64s(t));
+
+        let width = raw.u32(tag::IMAGE_WIDTH).unwrap_or(0) as usize;
+        let height = raw.u32(tag::IMAGE_LENGTH).unwrap_or(0) as usize;
+        let spp = raw.u32(tag::SAMPLES_PER_PIXEL).unwrap_or(1) as usize;
+        let bits = raw.u32(tag::BITS_PER_SAMPLE).unwrap_or(16);
+        if width == 0 || height == 0 || spp == 0 {
+            return Err(IoError::InvalidFile("DNG raw image has no size".into()));
+        }
+        debug!("DNG raw: {}x{} spp={} bits={}", width, height, spp, bits);
+
+        let samples = decode_samples(data, raw, file.big_endian, width, height, spp, bits)?;
+
+        // Active area (top, left, bottom, right)
+        let (top, left, bottom, right) = match raw.u32s(tag::ACTIVE_AREA) {
+            Some(a) if a.len() == 4 => (
+                a[0] as usize,
+                a[1] as usize,
+                (a[2] as usize).min(height),
+                (a[3] as usize).min(width),
+            ),
+            _ => (0, 0, height, width),
+        };
+        if top >= bottom || left >= right {
+            return Err(IoError::InvalidFile("DNG ActiveArea is empty".into()));
+        }
+        let (aw, ah) = (right - left, bottom - top);
+
+        let levels = Levels::from_ifd(raw, spp, bits, aw, ah);
+        let mut pixels = vec![0.0f32; aw * ah * spp];
+        for y in 0..ah {
+            for x in 0..aw {
+                for s in 0..spp {
+                    let v = samples[((y + top) * width + x + left) * spp + s];
+                    pixels[(y * aw + x) * spp + s] = levels.normalize(v, x, y, s);
+                }
+            }
+        }
+
+        // Default crop, relative to the active area
+        let crop = if self.options.crop {
+            default_crop(raw, aw, ah)
+        } else {
+            (0, 0, aw, ah)
+        };
+
+        let photometric = raw.u32(tag::PHOTOMETRIC).unwrap_or(0);
+        let mut metadata = Metadata::default();
+        let mut channels = spp;
+
+        if photometric == PHOTOMETRIC_CFA {
+            let grid = cfa_grid(raw)?;
+            if self.options.output == DngOutput::Mosaic {
+                let (cx, cy, _, _) = crop;
+                let pattern = bayer_name(&grid, cx, cy);
+                metadata
+                    .attrs
+                    .set("DNG:CFAPattern", AttrValue::Str(pattern));
+            } else {
+                let pattern =
+                    BayerPattern::from_str(&bayer_name(&grid, 0, 0)).ok_or_else(|| {
+                        IoError::UnsupportedFeature("DNG CFA is not a Bayer pattern".into())
+                    })?;
+                metadata
+                    .attrs
+                    .set("DNG:CFAPattern", AttrValue::Str(bayer_name(&grid, 0, 0)));
+                let mosaic = ImageData::from_f32(aw as u32, ah as u32, 1, pixels);
+                let rgb = demosaic(
+                    &ImageBuf::from_image_data(&mosaic),
+                    pattern,
+                    self.options.demosaic,
+                );
+                pixels = rgb.to_image_data()?.to_f32();
+                channels = 3;
+            }
+        }
+
+        // Color conversion of three-channel output
+        metadata.colorspace = Some("raw".into());
+        if channels == 3 && self.options.output != DngOutput::Mosaic {
+            let profile = CameraProfile::from_tags(lookup);
+            let neutral = profile.as_ref().map(|p| p.neutral).unwrap_or([1.0; 3]);
+            let aces = match self.options.output {
+                DngOutput::AcesLinear => profile.as_ref().and_then(|p| p.camera_to_aces()),
+                _ => None,
+            };
+            if let Some(m) = aces {
+                apply_matrix(&mut pixels, &m);
+                metadata.colorspace = Some("ACES2065-1".into());
+            } else {
+                if self.options.output == DngOutput::AcesLinear {
+                    debug!("DNG has no usable camera matrices, returning camera-native RGB");
+                }
+                if self.options.white_balance {
+                    let gains = neutral.map(|n| if n > 0.0 { 1.0 / n } else { 1.0 });
+                    apply_matrix(
+                        &mut pixels,
+                        &[
+                            [gains[0], 0.0, 0.0],
+                            [0.0, gains[1], 0.0],
+                            [0.0, 0.0, gains[2]],
+                        ],
+                    );
+                }
+                metadata.colorspace = Some("camera".into());
+            }
+            metadata.attrs.set(
+                "DNG:AsShotNeutral",
+                AttrValue::List(
+                    neutral
+                        .iter()
+                        .map(|&v| AttrValue::Float(v as f32))
+                        .collect(),
+                ),
+            );
+        }
+
+        // Final crop
+        let (cx, cy, cw, ch) = crop;
+        if (cx, cy, cw, ch) != (0, 0, aw, ah) {
+            let mut cropped = Vec::with_capacity(cw * ch * channels);
+            for y in cy..cy + ch {
+                let start = (y * aw + cx) * channels;
+                cropped.extend_from_slice(&pixels[start..start + cw * channels]);
+            }
+            pixels = cropped;
+        }
+
+        set_metadata(&mut metadata, ifd0, raw, &levels, bits);
+        let mut image = ImageData::from_f32(cw as u32, ch as u32, channels as u32, pixels);
+        image.metadata = metadata;
+        Ok(image)
+    }
+}
+
+impl Default for DngReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatReader<DngReaderOptions> for DngReader {
+    /// Returns "DNG".
+    fn format_name(&self) -> &'static str {
+        "DNG"
+    }
+
+    /// Returns `["dng"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["dng"]
+    }
+
+    /// Checks for a TIFF header with a DNGVersion tag.
+    fn can_read(&self, header: &[u8]) -> bool {
+        is_dng(header)
+    }
+
+    /// Reads and develops a DNG file from disk.
+    fn read<P: AsRef<Path>>(&self, path: P) -> IoResult<ImageData> {
+        let data = std::fs::read(path.as_ref())?;
+        self.read_impl(&data)
+    }
+
+    /// Reads and develops a DNG file from a byte slice.
+    fn read_from_memory(&self, data: &[u8]) -> IoResult<ImageData> {
+        self.read_impl(data)
+    }
+
+    /// Creates reader with custom options.
+    fn with_options(options: DngReaderOptions) -> Self {
+        Self { options }
+    }
+}
+
+// ============================================================================
+// Convenience Functions
+// ============================================================================
+
+/// Reads a DNG file as scene-linear ACES2065-1.
+///
+/// Convenience wrapper around [`DngReader`]. For camera-native or mosaic
+/// output, use [`DngReader::with_options`].
+pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
+    DngReader::new().read(path)
+}
+
+/// Returns true if `data` is a TIFF file whose first IFD carries a
+/// DNGVersion tag.
+///
+/// Needs the whole IFD0 to be present, so pass the file rather than
+/// just its first bytes when possible.
+pub fn is_dng(data: &[u8]) -> bool {
+    TiffFile::parse(data)
+        .ok()
+        .and_then(|f| f.ifds.first().map(|ifd| ifd.has(tag::DNG_VERSION)))
+        .unwrap_or(false)
+}
+
+// ============================================================================
+// Raw Decoding
+// ============================================================================
+
+/// Finds the full-resolution raw IFD, preferring the largest one.
+fn find_raw_ifd(file: &TiffFile) -> Option<&Ifd> {
+    file.ifds
+        .iter()
+        .filter(|ifd| ifd.u32(tag::NEW_SUBFILE_TYPE).unwrap_or(0) == 0)
+        .filter(|ifd| {
+            matches!(
+                ifd.u32(tag::PHOTOMETRIC),
+                Some(PHOTOMETRIC_CFA) | Some(PHOTOMETRIC_LINEAR_RAW)
+            )
+        })
+        .max_by_key(|ifd| {
+            ifd.u32(tag::IMAGE_WIDTH).unwrap_or(0) as u64
+                * ifd.u32(tag::IMAGE_LENGTH).unwrap_or(0) as u64
+        })
+}
+
+/// Decodes all strips or tiles into one `width * height * spp` buffer.
+fn decode_samples(
+    data: &[u8],
+    ifd: &Ifd,
+    big_endian: bool,
+    width: usize,
+    height: usize,
+    spp: usize,
+    bits: u32,
+) -> IoResult<Vec<u16>> {
+    if bits == 0 || bits > 16 {
+        return Err(IoError::UnsupportedBitDepth(format!(
+            "DNG with {}-bit samples",
+            bits
+        )));
+    }
+    if spp > 1 && ifd.u32(tag::PLANAR_CONFIG).unwrap_or(1) != 1 {
+        return Err(IoError::UnsupportedFeature("planar DNG raw data".into()));
+    }
+    let compression = ifd.u32(tag::COMPRESSION).unwrap_or(1);
+
+    let (tile_w, tile_h, offsets, counts) = if ifd.has(tag::TILE_OFFSETS) {
+        (
+            ifd.u32(tag::TILE_WIDTH).unwrap_or(width as u32) as usize,
+            ifd.u32(tag::TILE_LENGTH).unwrap_or(height as u32) as usize,
+            ifd.u32s(tag::TILE_OFFSETS).unwrap_or_default(),
+            ifd.u32s(tag::TILE_BYTE_COUNTS).unwrap_or_default(),
+        )
+    } else {
+        (
+            width,
+            (ifd.u32(tag::ROWS_PER_STRIP).unwrap_or(height as u32) as usize).min(height),
+            ifd.u32s(tag::STRIP_OFFSETS).unwrap_or_default(),
+            ifd.u32s(tag::STRIP_BYTE_COUNTS).unwrap_or_default(),
+        )
+    };
+    if tile_w == 0 || tile_h == 0 {
+        return Err(IoError::InvalidFile("DNG tile size is zero".into()));
+    }
+    let across = width.div_ceil(tile_w);
+    let down = height.div_ceil(tile_h);
+    if offsets.len() < across * down {
+        return Err(IoError::MissingData("DNG strip/tile offsets".into()));
+    }
+
+    let mut out = vec![0u16; width * height * spp];
+    let row_len = tile_w * spp;
+    for (i, &offset) in offsets.iter().take(across * down).enumerate() {
+        let offset = offset as usize;
+        let count = counts
+            .get(i)
+            .map(|&c| c as usize)
+            .unwrap_or(data.len().saturating_sub(offset));
+        let chunk = data
+            .get(offset..offset.saturating_add(count).min(data.len()))
+            .ok_or_else(|| IoError::DecodeError("DNG tile outside of file".into()))?;
+
+        let tile = match compression {
+            1 => unpack(chunk, bits, big_endian, row_len, tile_h),
+            7 => ljpeg::decode(chunk)?,
+            c => {
+                return Err(IoError::UnsupportedFeature(format!(
+                    "DNG compression {}",
+                    c
+                )));
+            }
+        };
+
+        // Copy, clipping tiles that extend past the image edges
+        let (tx, ty) = ((i % across) * tile_w, (i / across) * tile_h);
+        let copy_w = tile_w.min(width - tx) * spp;
+        for row in 0..tile_h.min(height - ty) {
+            let src = row * row_len;
+            let Some(src) = tile.get(src..src + copy_w) else {
+                break;
+            };
+            let dst = ((ty + row) * width + tx) * spp;
+            out[dst..dst + copy_w].copy_from_slice(src);
+        }
+    }
+    Ok(out)
+}
+
+/// Unpacks uncompressed samples. Rows start on byte boundaries.
+fn unpack(chunk: &[u8], bits: u32, big_endian: bool, row_len: usize, rows: usize) -> Vec<u16> {
+    match bits {
+        8 => chunk.iter().map(|&b| b as u16).collect(),
+        16 => chunk
+            .chunks_exact(2)
+            .map(|b| {
+                if big_endian {
+                    u16::from_be_bytes([b[0], b[1]])
+                } else {
+                    u16::from_le_bytes([b[0], b[1]])
+                }
+            })
+            .collect(),
+        _ => {
+            let row_bytes = (row_len * bits as usize).div_ceil(8);
+            let mut out = Vec::with_capacity(row_len * rows);
+            for row in chunk.chunks(row_bytes).take(rows) {
+                let mut acc = 0u32;
+                let mut count = 0u32;
+                let mut bytes = row.iter();
+                for _ in 0..row_len {
+                    while count < bits {
+                        acc = (acc << 8) | *bytes.next().unwrap_or(&0) as u32;
+                        count += 8;
+                    }
+                    count -= bits;
+                    out.push(((acc >> count) & ((1 << bits) - 1)) as u16);
+                }
+            }
+            out
+        }
+    }
+}
+
+// ============================================================================
+// Linearization
+// ============================================================================
+
+/// Linearization, black and white levels of the raw image.
+struct Levels {
+    table: Option<Vec<f32>>,
+    /// BlackLevel, `repeat_rows * repeat_cols * spp` values.
+    black: Vec<f32>,
+    repeat_rows: usize,
+    repeat_cols: usize,
+    delta_h: Vec<f32>,
+    delta_v: Vec<f32>,
+    white: Vec<f32>,
+    spp: usize,
+}
+
+impl Levels {
+    fn from_ifd(ifd: &Ifd, spp: usize, bits: u32, width: usize, height: usize) -> Self {
+        let as_f32 = |v: Vec<f64>| v.into_iter().map(|x| x as f32).collect::<Vec<f32>>();
+        let (repeat_rows, repeat_cols) = match ifd.u32s(tag::BLACK_LEVEL_REPEAT_DIM) {
+            Some(d) if d.len() == 2 && d[0] > 0 && d[1] > 0 => (d[0] as usize, d[1] as usize),
+            _ => (1, 1),
+        };
+        let n = repeat_rows * repeat_cols * spp;
+        let mut black = ifd.f64s(tag::BLACK_LEVEL).map(as_f32).unwrap_or_default();
+        if black.len() < n {
+            // A single value applies to every sample
+            let fill = black.first().copied().unwrap_or(0.0);
+            black.resize(n, fill);
+        }
+        let mut delta_h = ifd
+            .f64s(tag::BLACK_LEVEL_DELTA_H)
+            .map(as_f32)
+            .unwrap_or_default();
+        delta_h.resize(width, 0.0);
+        let mut delta_v = ifd
+            .f64s(tag::BLACK_LEVEL_DELTA_V)
+            .map(as_f32)
+            .unwrap_or_default();
+        delta_v.resize(height, 0.0);
+
+        let mut white = ifd.f64s(tag::WHITE_LEVEL).map(as_f32).unwrap_or_default();
+        let default_white = ((1u32 << bits) - 1) as f32;
+        let fill = white.first().copied().unwrap_or(default_white);
+        white.resize(spp, fill);
+
+        Self {
+            table: ifd
+                .f64s(tag::LINEARIZATION_TABLE)
+                .map(as_f32)
+                .filter(|t| !t.is_empty()),
+            black,
+            repeat_rows,
+            repeat_cols,
+            delta_h,
+            delta_v,
+            white,
+            spp,
+        }
+    }
+
+    /// Black level of a sample at active-area position (x, y).
+    fn black_at(&self, x: usize, y: usize, s: usize) -> f32 {
+        let i = ((y % self.repeat_rows) * self.repeat_cols + x % self.repeat_cols) * self.spp + s;
+        self.black[i] + self.delta_h[x] + self.delta_v[y]
+    }
+
+    /// Maps a stored value to 0..1.
+    fn normalize(&self, v: u16, x: usize, y: usize, s: usize) -> f32 {
+        let linear = match &self.table {
+            Some(t) => t[(v as usize).min(t.len() - 1)],
+            None => v as f32,
+        };
+        let black = self.black_at(x, y, s);
+        let range = self.white[s] - black;
+        if range <= 0.0 {
+            return 0.0;
+        }
+        ((linear - black) / range).clamp(0.0, 1.0)
+    }
+}
+
+/// Default crop as (x, y, width, height) within the active area.
+fn default_crop(ifd: &Ifd, width: usize, height: usize) -> (usize, usize, usize, usize) {
+    let origin = ifd.f64s(tag::DEFAULT_CROP_ORIGIN).unwrap_or_default();
+    let size = ifd.f64s(tag::DEFAULT_CROP_SIZE).unwrap_or_default();
+    if origin.len() != 2 || size.len() != 2 {
+        return (0, 0, width, height);
+    }
+    let x = (origin[0].max(0.0) as usize).min(width - 1);
+    let y = (origin[1].max(0.0) as usize).min(height - 1);
+    let w = (size[0].max(1.0) as usize).min(width - x);
+    let h = (size[1].max(1.0) as usize).min(height - y);
+    (x, y, w, h)
+}
+
+// ============================================================================
+// CFA
+// ============================================================================
+
+/// Reads the 2x2 CFA as color indices (0 = R, 1 = G, 2 = B), row-major.
+fn cfa_grid(ifd: &Ifd) -> IoResult<[u8; 4]> {
+    let unsupported = |what: &str| IoError::UnsupportedFeature(format!("DNG CFA: {}", what));
+    if ifd.u32(tag::SAMPLES_PER_PIXEL).unwrap_or(1) != 1 {
+        return Err(unsupported("more than one sample per pixel"));
+    }
+    if ifd.u32(tag::CFA_LAYOUT).unwrap_or(1) != 1 {
+        return Err(unsupported("non-rectangular layout"));
+    }
+    let dims = ifd
+        .u32s(tag::CFA_REPEAT_PATTERN_DIM)
+        .unwrap_or_else(|| vec![2, 2]);
+    if dims != [2, 2] {
+        return Err(unsupported(&format!("{:?} repeat pattern", dims)));
+    }
+    let pattern = ifd
+        .bytes(tag::CFA_PATTERN)
+        .ok_or_else(|| IoError::MissingData("DNG CFAPattern".into()))?;
+    let planes = ifd.bytes(tag::CFA_PLANE_COLOR).unwrap_or(&[0, 1, 2]);
+    if pattern.len() < 4 {
+        return Err(unsupported("truncated pattern"));
+    }
+
+    let mut grid = [0u8; 4];
+    for (g, &p) in grid.iter_mut().zip(pattern) {
+        *g = *planes.get(p as usize).unwrap_or(&p);
+        if *g > 2 {
+            return Err(unsupported("colors other than red, green and blue"));
+        }
+    }
+    Ok(grid)
+}
+
+/// Names the Bayer layout starting at (x, y) of the CFA grid, e.g. "RGGB".
+fn bayer_name(grid: &[u8; 4], x: usize, y: usize) -> String {
+    [(0, 0), (1, 0), (0, 1), (1, 1)]
+        .iter()
+        .map(|&(dx, dy)| match grid[((y + dy) % 2) * 2 + (x + dx) % 2] {
+            0 => 'R',
+            1 => 'G',
+            _ => 'B',
+        })
+        .collect()
+}
+
+/// Multiplies interleaved RGB pixels by a 3x3 matrix.
+fn apply_matrix(pixels: &mut [f32], m: &color::Mat3) {
+    for px in pixels.chunks_exact_mut(3) {
+        let rgb = color::mul_vec(m, [px[0] as f64, px[1] as f64, px[2] as f64]);
+        px[0] = rgb[0] as f32;
+        px[1] = rgb[1] as f32;
+        px[2] = rgb[2] as f32;
+    }
+}
+
+// ============================================================================
+// Metadata
+// ============================================================================
+
+/// Fills camera, DNG and CinemaDNG attributes.
+fn set_metadata(metadata: &mut Metadata, ifd0: &Ifd, raw: &Ifd, levels: &Levels, bits: u32) {
+    let attrs = &mut metadata.attrs;
+    let string = |t| raw.string(t).or_else(|| ifd0.string(t));
+    let number = |t| raw.f64(t).or_else(|| ifd0.f64(t));
+
+    attrs.set("Format", AttrValue::Str("DNG".into()));
+    attrs.set("BitDepth", AttrValue::UInt(bits));
+    for (key, t) in [
+        ("Make", tag::MAKE),
+        ("Model", tag::MODEL),
+        ("Software", tag::SOFTWARE),
+        ("Artist", tag::ARTIST),
+        ("DateTime", tag::DATE_TIME),
+        ("DNG:UniqueCameraModel", tag::UNIQUE_CAMERA_MODEL),
+    ] {
+        if let Some(v) = string(t) {
+            attrs.set(key, AttrValue::Str(v));
+        }
+    }
+    if let Some(v) = ifd0.u32(tag::ORIENTATION) {
+        attrs.set("Orientation", AttrValue::UInt(v));
+    }
+    if let Some(v) = ifd0.u32s(tag::DNG_VERSION) {
+        let version: Vec<String> = v.iter().map(|n| n.to_string()).collect();
+        attrs.set("DNG:Version", AttrValue::Str(version.join(".")));
+    }
+    if let Some(v) = number(tag::BASELINE_EXPOSURE) {
+        attrs.set("DNG:BaselineExposure", AttrValue::Float(v as f32));
+    }
+    attrs.set("DNG:BlackLevel", AttrValue::Float(levels.black[0]));
+    attrs.set("DNG:WhiteLevel", AttrValue::Float(levels.white[0]));
+
+    // CinemaDNG frame rate and SMPTE timecode
+    if let Some(v) = number(tag::FRAME_RATE) {
+        attrs.set("FrameRate", AttrValue::Float(v as f32));
+    }
+    if let Some(tc) = raw
+        .bytes(tag::TIME_CODES)
+        .or_else(|| ifd0.bytes(tag::TIME_CODES))
+    {
+        if tc.len() >= 4 {
+            let bcd = |b: u8| (b >> 4) * 10 + (b & 0x0F);
+            attrs.set(
+                "Timecode",
+                AttrValue::Str(format!(
+                    "{:02}:{:02}:{:02}:{:02}",
+                    bcd(tc[3] & 0x3F),
+                    bcd(tc[2] & 0x7F),
+                    bcd(tc[1] & 0x7F),
+                    bcd(tc[0] & 0x3F)
+                )),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// TIFF field values for the test builder.
+    enum Value {
+        Byte(Vec<u8>),
+        Short(Vec<u16>),
+        Long(Vec<u32>),
+        Rational(Vec<(u32, u32)>),
+        SRational(Vec<(i32, i32)>),
+        Ascii(&'static str),
+    }
+
+    /// Builds a little-endian single-IFD DNG with one strip.
+    fn build_dng(strip: &[u8], tags: Vec<(u16, Value)>) -> Vec<u8> {
+        let mut tags = tags;
+        tags.push((tag::DNG_VERSION, Value::Byte(vec![1, 4, 0, 0])));
+        tags.push((tag::STRIP_OFFSETS, Value::Long(vec![8])));
+        tags.push((
+            tag::STRIP_BYTE_COUNTS,
+            Value::Long(vec![strip.len() as u32]),
+        ));
+        tags.sort_by_key(|(t, _)| *t);
+
+        let mut out = b"II*\0".to_vec();
+        let ifd_offset = 8 + strip.len() as u32;
+        out.extend_from_slice(&ifd_offset.to_le_bytes());
+        out.extend_from_slice(strip);
+
+        let mut extra = Vec::new();
+        let extra_base = ifd_offset as usize + 2 + tags.len() * 12 + 4;
+        let mut table = (tags.len() as u16).to_le_bytes().to_vec();
+        for (t, value) in &tags {
+            let (field_type, count, bytes): (u16, usize, Vec<u8>) = match value {
+                Value::Byte(v) => (1, v.len(), v.clone()),
+                Value::Short(v) => (3, v.len(), v.iter().flat_map(|x| x.to_le_bytes()).collect()),
+                Value::Long(v) => (4, v.len(), v.iter().flat_map(|x| x.to_le_bytes()).collect()),
+                Value::Rational(v) => (
+                    5,
+                    v.len(),
+                    v.iter()
+                        .flat_map(|(n, d)| [n.to_le_bytes(), d.to_le_bytes()].concat())
+                        .collect(),
+                ),
+                Value::SRational(v) => (
+                    10,
+                    v.len(),
+                    v.iter()
+                        .flat_map(|(n, d)| [n.to_le_bytes(), d.to_le_bytes()].concat())
+                        .collect(),
+                ),
+                Value::Ascii(s) => (2, s.len() + 1, [s.as_bytes(), &[0]].concat()),
+            };
+            table.extend_from_slice(&t.to_le_bytes());
+            table.extend_from_slice(&field_type.to_le_bytes());
+            table.extend_from_slice(&(count as u32).to_le_bytes());
+            if bytes.len() <= 4 {
+                let mut inline = bytes.clone();
+                inline.resize(4, 0);
+                table.extend_from_slice(&inline);
+            } else {
+                table.extend_from_slice(&((extra_base + extra.len()) as u32).to_le_bytes());
+                extra.extend_from_slice(&bytes);
+            }
+        }
+        table.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&table);
+        out.extend_from_slice(&extra);
+        out
+    }
+
+    /// Tags of a 16-bit RGGB CFA raw.
+    fn cfa_tags(width: u16, height: u16) -> Vec<(u16, Value)> {
+        vec![
+            (tag::NEW_SUBFILE_TYPE, Value::Long(vec![0])),
+            (tag::IMAGE_WIDTH, Value::Short(vec![width])),
+            (tag::IMAGE_LENGTH, Value::Short(vec![height])),
+            (tag::BITS_PER_SAMPLE, Value::Short(vec![16])),
+            (tag::COMPRESSION, Value::Short(vec![1])),
+            (tag::PHOTOMETRIC, Value::Short(vec![PHOTOMETRIC_CFA as u16])),
+            (tag::SAMPLES_PER_PIXEL, Value::Short(vec![1])),
+            (tag::ROWS_PER_STRIP, Value::Short(vec![height])),
+            (tag::CFA_REPEAT_PATTERN_DIM, Value::Short(vec![2, 2])),
+            (tag::CFA_PATTERN, Value::Byte(vec![0, 1, 1, 2])),
+            (tag::MAKE, Value::Ascii("Test")),
+            (tag::BLACK_LEVEL, Value::Short(vec![64])),
+            (tag::WHITE_LEVEL, Value::Short(vec![1087])),
+        ]
+    }
+
+    fn mosaic_reader() -> DngReader {
+        DngReader::with_options(DngReaderOptions {
+            output: DngOutput::Mosaic,
+            ..Default::default()
+        })
+    }
+
+    fn to_bytes(samples: &[u16]) -> Vec<u8> {
+        samples.iter().flat_map(|v| v.to_le_bytes()).collect()
+    }
+
+    #[test]
+    fn test_uncompressed_mosaic_levels() {
+        let samples: Vec<u16> = (0..16).map(|i| 64 + i * 64).collect();
+        let dng = build_dng(&to_bytes(&samples), cfa_tags(4, 4));
+        assert!(is_dng(&dng));
+
+        let image = mosaic_reader().read_from_memory(&dng).expect("read failed");
+        assert_eq!((image.width, image.height, image.channels), (4, 4, 1));
+        let pixels = image.to_f32();
+        for (i, v) in pixels.iter().enumerate() {
+            let expected = (i as f32 * 64.0 / 1023.0).min(1.0);
+            assert!(
+                (v - expected).abs() < 1e-5,
+                "pixel {}: {} != {}",
+                i,
+                v,
+                expected
+            );
+        }
+        let attrs = &image.metadata.attrs;
+        assert_eq!(
+            attrs.get("DNG:CFAPattern").and_then(|v| v.as_str()),
+            Some("RGGB")
+        );
+        assert_eq!(attrs.get("Make").and_then(|v| v.as_str()), Some("Test"));
+        assert_eq!(
+            attrs.get("DNG:Version").and_then(|v| v.as_str()),
+            Some("1.4.0.0")
+        );
+    }
+
+    #[test]
+    fn test_lossless_jpeg_matches_uncompressed() {
+        let samples: Vec<u16> = (0..64).map(|i| 64 + ((i * 37) % 1000) as u16).collect();
+        let plain = build_dng(&to_bytes(&samples), cfa_tags(8, 8));
+
+        // DNG encoders commonly pack two CFA columns per JPEG pixel
+        let jpeg = ljpeg::encode(&samples, 4, 8, 2, 16);
+        let mut tags = cfa_tags(8, 8);
+        tags.retain(|(t, _)| *t != tag::COMPRESSION);
+        tags.push((tag::COMPRESSION, Value::Short(vec![7])));
+        let compressed = build_dng(&jpeg, tags);
+
+        let a = mosaic_reader()
+            .read_from_memory(&plain)
+            .expect("plain read failed");
+        let b = mosaic_reader()
+            .read_from_memory(&compressed)
+            .expect("LJPEG read failed");
+        assert_eq!(a.to_f32(), b.to_f32());
+    }
+
+    #[test]
+    fn test_linearization_and_black_pattern() {
+        // 10-bit packed samples through a table that doubles values
+        let samples: Vec<u16> = vec![10, 20, 30, 40];
+        let mut packed = Vec::new();
+        let mut acc = 0u64;
+        for &s in &samples {
+            acc = (acc << 10) | s as u64;
+        }
+        for i in (0..5).rev() {
+            packed.push((acc >> (i * 8)) as u8);
+        }
+        let mut tags = cfa_tags(4, 1);
+        tags.retain(|(t, _)| {
+            ![tag::BITS_PER_SAMPLE, tag::BLACK_LEVEL, tag::WHITE_LEVEL].contains(t)
+        });
+        tags.push((tag::BITS_PER_SAMPLE, Value::Short(vec![10])));
+        tags.push((
+            tag::LINEARIZATION_TABLE,
+            Value::Short((0..1024).map(|v| v * 2).collect()),
+        ));
+        tags.push((tag::BLACK_LEVEL_REPEAT_DIM, Value::Short(vec![1, 2])));
+        tags.push((tag::BLACK_LEVEL, Value::Rational(vec![(0, 1), (20, 1)])));
+        tags.push((tag::WHITE_LEVEL, Value::Short(vec![120])));
+        let dng = build_dng(&packed, tags);
+
+        let pixels = mosaic_reader()
+            .read_from_memory(&dng)
+            .expect("read failed")
+            .to_f32();
+        let expected = [20.0 / 120.0, 20.0 / 100.0, 60.0 / 120.0, 60.0 / 100.0];
+        for (v, e) in pixels.iter().zip(expected) {
+            assert!((v - e).abs() < 1e-5, "{} != {}", v, e);
+        }
+    }
+
+    #[test]
+    fn test_default_crop_shifts_pattern() {
+        let samples = vec![64u16; 16];
+        let mut tags = cfa_tags(4, 4);
+        tags.push((tag::DEFAULT_CROP_ORIGIN, Value::Short(vec![1, 0])));
+        tags.push((tag::DEFAULT_CROP_SIZE, Value::Short(vec![2, 3])));
+        let dng = build_dng(&to_bytes(&samples), tags);
+
+        let image = mosaic_reader().read_from_memory(&dng).expect("read failed");
+        assert_eq!((image.width, image.height), (2, 3));
+        assert_eq!(
+            image
+                .metadata
+                .attrs
+                .get("DNG:CFAPattern")
+                .and_then(|v| v.as_str()),
+            Some("GRBG")
+        );
+    }
+
+    #[test]
+    fn test_aces_output_maps_neutral_to_gray() {
+        // Uniform scene at the as-shot white: R = 0.5, G = 1.0, B = 0.7 of 0.25
+        let neutral = [0.5, 1.0, 0.7];
+        let code = |c: usize| 64 + (0.25 * neutral[c] * 1023.0) as u16;
+        let samples: Vec<u16> = (0..36)
+            .map(|i| {
+                let (x, y) = (i % 6, i / 6);
+                code(match (x % 2, y % 2) {
+                    (0, 0) => 0,
+                    (1, 1) => 2,
+                    _ => 1,
+                })
+            })
+            .collect();
+        let mut tags = cfa_tags(6, 6);
+        tags.push((
+            tag::AS_SHOT_NEUTRAL,
+            Value::Rational(vec![(500, 1000), (1000, 1000), (700, 1000)]),
+        ));
+        tags.push((
+            tag::FORWARD_MATRIX_1,
+            Value::SRational(vec![
+                (6000, 10000),
+                (3000, 10000),
+                (642, 10000),
+                (2500, 10000),
+                (7000, 10000),
+                (500, 10000),
+                (0, 10000),
+                (1000, 10000),
+                (7252, 10000),
+            ]),
+        ));
+        let dng = build_dng(&to_bytes(&samples), tags);
+
+        let reader = DngReader::with_options(DngReaderOptions {
+            demosaic: DemosaicAlgorithm::Bilinear,
+            ..Default::default()
+        });
+        let image = reader.read_from_memory(&dng).expect("read failed");
+        assert_eq!(image.channels, 3);
+        assert_eq!(image.metadata.colorspace.as_deref(), Some("ACES2065-1"));
+        for v in image.to_f32() {
+            assert!((v - 0.25).abs() < 0.005, "expected gray 0.25, got {}", v);
+        }
+
+        // Camera-native output, white balanced, is gray as well
+        let reader = DngReader::with_options(DngReaderOptions {
+            output: DngOutput::CameraNative,
+            demosaic: DemosaicAlgorithm::Bilinear,
+            ..Default::default()
+        });
+        let image = reader.read_from_memory(&dng).expect("read failed");
+        assert_eq!(image.metadata.colorspace.as_deref(), Some("camera"));
+        for v in image.to_f32() {
+            assert!((v - 0.25).abs() < 0.005, "expected gray 0.25, got {}", v);
+        }
+    }
+
+    #[test]
+    fn test_rejects_plain_tiff() {
+        let mut dng = build_dng(&[0u8; 4], cfa_tags(2, 1));
+        // Rename the DNGVersion tag so the file is a plain TIFF
+        let pos = dng
+            .windows(2)
+            .position(|w| w == tag::DNG_VERSION.to_le_bytes())
+            .unwrap();
+        dng[pos..pos + 2].copy_from_slice(&1u16.to_le_bytes());
+        assert!(!is_dng(&dng));
+        assert!(DngReader::new().read_from_memory(&dng).is_err());
+    }
+
+    /// Tests that the registry develops DNGs instead of reading them as TIFF.
+    #[test]
+    fn test_registry_prefers_dng_over_tiff() {
+        let samples: Vec<u16> = (0..16).map(|i| 64 + i * 32).collect();
+        let dng = build_dng(&to_bytes(&samples), cfa_tags(4, 4));
+        let expected = DngReader::new().read_from_memory(&dng).expect("read failed");
+        assert_eq!(expected.channels, 3);
+
+        let registry = crate::registry::FormatRegistry::global();
+        for hint in [None, Some("dng"), Some("tif")] {
+            let image = registry.read_from_memory(&dng, hint).expect("registry read failed");
+            assert_eq!((image.width, image.height, image.channels), (4, 4, 3));
+            assert_eq!(image.to_f32(), expected.to_f32(), "hint {:?}", hint);
+        }
+
+        let path = std::env::temp_dir().join("vfx_io_dng_registry_test.dng");
+        std::fs::write(&path, &dng).expect("write failed");
+        let image = registry.read(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(image.expect("registry read failed").to_f32(), expected.to_f32());
+    }
+
+    /// Tests that registry stream reads see past the TIFF header.
+    #[test]
+    fn test_registry_reads_dng_streams() {
+        let samples: Vec<u16> = (0..16).map(|i| 64 + i * 32).collect();
+        let dng = build_dng(&to_bytes(&samples), cfa_tags(4, 4));
+        let expected = DngReader::new().read_from_memory(&dng).expect("read failed");
+
+        let registry = crate::registry::FormatRegistry::global();
+        let mut stream = std::io::Cursor::new(dng);
+        let image = registry.read_from(&mut stream, None).expect("registry read failed");
+        assert_eq!((image.width, image.height, image.channels), (4, 4, 3));
+        assert_eq!(image.to_f32(), expected.to_f32());
+
+        stream.set_position(0);
+        let image = registry
+            .read_subimage_from(&mut stream, Some("tif"), 0, 0)
+            .expect("registry read failed");
+        assert_eq!(image.to_f32(), expected.to_f32());
+    }
+
+    #[test]
+    fn test_timecode_metadata() {
+        let mut tags = cfa_tags(2, 2);
+        tags.push((
+            tag::TIME_CODES,
+            Value::Byte(vec![0x12, 0x34, 0x56, 0x01, 0, 0, 0, 0]),
+        ));
+        tags.push((tag::FRAME_RATE, Value::SRational(vec![(24000, 1001)])));
+        let dng = build_dng(&to_bytes(&[64, 64, 64, 64]), tags);
+
+        let image = mosaic_reader().read_from_memory(&dng).expect("read failed");
+        let attrs = &image.metadata.attrs;
+        assert_eq!(
+            attrs.get("Timecode").and_then(|v| v.as_str()),
+            Some("01:56:34:12")
+        );
+        let fps = attrs.get("FrameRate").and_then(|v| v.as_f32()).unwrap();
+        assert!((fps - 23.976).abs() < 0.001);
+    }
+}
@@ -221,6 +221,13 @@ impl ExrReader {
         use vfx_exr::prelude::*;
         use vfx_exr::math::Vec2;
 
+        let meta = vfx_exr::meta::MetaData::read_from_buffered(Cursor::new(data), false)
+            .map_err(|e| IoError::DecodeError(format!("EXR decode error: {}", e)))?;
+
+        if meta.headers.first().is_some_and(|header| is_luminance_chroma(&header.channels)) {
+            return self.read_luminance_chroma(data);
+        }
+
         // Read first RGBA layer using builder pattern
         let image = read()
             .no_deep_data()
@@ -274,12 +281,34 @@ impl ExrReader {
         Ok(result)
     }
 
+    /// Reads the first layer of a luminance/chroma file (`Y`, `RY`, `BY`) as RGBA.
+    fn read_luminance_chroma(&self, data: &[u8]) -> IoResult<ImageData> {
+        use vfx_exr::prelude::*;
+
+        let image = read()
+            .no_deep_data()
+            .largest_resolution_level()
+            .all_channels()
+            .first_valid_layer()
+            .all_attributes()
+            .from_buffered(Cursor::new(data))
+            .map_err(|e| IoError::DecodeError(format!("EXR decode error: {}", e)))?;
+
+        let weights = exr_luminance_weights(image.attributes.chromaticities.as_ref());
+        let layer = image_layer_from_exr(0, &image.layer_data);
+
+        let mut result = luminance_chroma_to_rgba(&layer, weights)?;
+        result.metadata.colorspace = Some("linear".to_string());
+        self.extract_metadata(data, &mut result.metadata)?;
+
+        Ok(result)
+    }
+
     /// Reads all layers and channels from an EXR file.
     ///
     /// Note: deep data is not supported by the `exr` crate, so deep layers will fail to load.
     pub fn read_layers<P: AsRef<Path>>(&self, path: P) -> IoResult<LayeredImage> {
         use vfx_exr::image::read::read_all_flat_layers_from_file;
-        use vfx_exr::image::FlatSamples;
 
         let path = path.as_ref();
         let data = std::fs::read(path)?;
@@ -296,55 +325,7 @@ impl ExrReader {
         self.extract_metadata(&data, &mut layered.metadata)?;
 
         for (idx, layer) in image.layer_data.iter().enumerate() {
-            let width = layer.size.width() as u32;
-            let height = layer.size.height() as u32;
-            let mut channels = Vec::with_capacity(layer.channel_data.list.len());
-
-            for channel in layer.channel_data.list.iter() {
-                let name = channel.name.to_string();
-                let sampling = (channel.sampling.0, channel.sampling.1);
-                let quantize_linearly = channel.quantize_linearly;
-
-                let (sample_type, samples) = match &channel.sample_data {
-                    FlatSamples::F16(values) => {
-                        let mut data = Vec::with_capacity(values.len());
-                        for value in values {
-                            data.push(f32::from(*value));
-                        }
-                        (ChannelSampleType::F16, ChannelSamples::F32(data))
-                    }
-                    FlatSamples::F32(values) => {
-                        (ChannelSampleType::F32, ChannelSamples::F32(values.clone()))
-                    }
-                    FlatSamples::U32(values) => {
-                        (ChannelSampleType::U32, ChannelSamples::U32(values.clone()))
-                    }
-                };
-
-                let kind = channel_kind_from_name(&name, sample_type);
-                channels.push(ImageChannel {
-                    name,
-                    kind,
-                    sample_type,
-                    samples,
-                    sampling,
-                    quantize_linearly,
-                });
-            }
-
-            let name = layer
-                .attributes
-                .layer_name
-                .as_ref()
-                .map(|n| n.to_string())
-                .unwrap_or_else(|| format!("Layer{}", idx));
-
-            layered.layers.push(ImageLayer {
-                name,
-                width,
-                height,
-                channels,
-            });
+            layered.layers.push(image_layer_from_exr(idx, layer));
         }
 
         Ok(layered)
@@ -451,7 +432,7 @@ impl ExrReader {
             for (name, value) in &header.shared_attributes.other {
                 metadata.attrs.set(
                     format!("{}EXR:{}", prefix, name),
-                    AttrValue::Str(format!("{:?}", value)),
+                    other_attribute_value(value),
                 );
             }
 
@@ -466,7 +447,7 @@ impl ExrReader {
             for (name, value) in &header.own_attributes.other {
                 metadata.attrs.set(
                     format!("{}Layer:{}", prefix, name),
-                    AttrValue::Str(format!("{:?}", value)),
+                    other_attribute_value(value),
                 );
             }
         }
@@ -475,6 +456,75 @@ impl ExrReader {
     }
 }
 
+/// Converts a custom EXR attribute for [`Metadata`]. Text is kept as is,
+/// so that string attributes like Cryptomatte manifests stay readable.
+fn other_attribute_value(value: &vfx_exr::meta::attribute::AttributeValue) -> AttrValue {
+    use vfx_exr::meta::attribute::AttributeValue;
+
+    match value {
+        AttributeValue::Text(text) => AttrValue::Str(text.to_string()),
+        other => AttrValue::Str(format!("{:?}", other)),
+    }
+}
+
+/// Converts a flat exr layer into an [`ImageLayer`], keeping subsampled channels as they are.
+fn image_layer_from_exr(
+    idx: usize,
+    layer: &vfx_exr::image::Layer<vfx_exr::image::AnyChannels<vfx_exr::image::FlatSamples>>,
+) -> ImageLayer {
+    use vfx_exr::image::FlatSamples;
+
+    let width = layer.size.width() as u32;
+    let height = layer.size.height() as u32;
+    let mut channels = Vec::with_capacity(layer.channel_data.list.len());
+
+    for channel in layer.channel_data.list.iter() {
+        let name = channel.name.to_string();
+        let sampling = (channel.sampling.0, channel.sampling.1);
+        let quantize_linearly = channel.quantize_linearly;
+
+        let (sample_type, samples) = match &channel.sample_data {
+            FlatSamples::F16(values) => {
+                let mut data = Vec::with_capacity(values.len());
+                for value in values {
+                    data.push(f32::from(*value));
+                }
+                (ChannelSampleType::F16, ChannelSamples::F32(data))
+            }
+            FlatSamples::F32(values) => {
+                (ChannelSampleType::F32, ChannelSamples::F32(values.clone()))
+            }
+            FlatSamples::U32(values) => {
+                (ChannelSampleType::U32, ChannelSamples::U32(values.clone()))
+            }
+        };
+
+        let kind = channel_kind_from_name(&name, sample_type);
+        channels.push(ImageChannel {
+            name,
+            kind,
+            sample_type,
+            samples,
+            sampling,
+            quantize_linearly,
+        });
+    }
+
+    let name = layer
+        .attributes
+        .layer_name
+        .as_ref()
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| format!("Layer{}", idx));
+
+    ImageLayer {
+        name,
+        width,
+        height,
+        channels,
+    }
+}
+
 impl Default for ExrReader {
     fn default() -> Self {
         Self::new()
@@ -515,6 +565,327 @@ impl FormatReader<ExrReaderOptions> for ExrReader {
     }
 }
 
+// ============================================================================
+// Luminance / Chroma
+// ============================================================================
+
+/// Low-pass filter used to reconstruct subsampled chroma,
+/// matching the filter of the OpenEXR `RgbaInputFile`.
+///
+/// The coefficients are applied to the known samples at
+/// offsets -13, -11, ..., 11, 13 around the missing sample.
+const CHROMA_FILTER: [f32; 14] = [
+    0.002128, -0.007540, 0.019597, -0.043159, 0.087929, -0.186077, 0.627123, 0.627123,
+    -0.186077, 0.087929, -0.043159, 0.019597, -0.007540, 0.002128,
+];
+
+/// Returns whether a channel list stores luminance (`Y`) instead of `R`, `G`, `B`.
+fn is_luminance_chroma(channels: &vfx_exr::meta::attribute::ChannelList) -> bool {
+    let has = |name: &str| channels.list.iter().any(|channel| channel.name.eq(name));
+    has("Y") && !has("R") && !has("G") && !has("B")
+}
+
+/// Luminance weights for the chromaticities of an exr header,
+/// falling back to Rec. 709 primaries if the header has none.
+fn exr_luminance_weights(
+    chromaticities: Option<&vfx_exr::meta::attribute::Chromaticities>,
+) -> [f32; 3] {
+    match chromaticities {
+        Some(c) => luminance_weights(
+            [c.red.0, c.red.1],
+            [c.green.0, c.green.1],
+            [c.blue.0, c.blue.1],
+            [c.white.0, c.white.1],
+        ),
+        None => luminance_weights([0.64, 0.33], [0.30, 0.60], [0.15, 0.06], [0.3127, 0.3290]),
+    }
+}
+
+/// Computes the luminance weights of an RGB color space.
+///
+/// The weights are the `Y` row of the RGB to XYZ matrix
+/// defined by the given primaries and white point (CIE xy),
+/// normalized to sum to one. Rec. 709 primaries yield
+/// approximately `[0.2126, 0.7152, 0.0722]`.
+pub fn luminance_weights(red: [f32; 2], green: [f32; 2], blue: [f32; 2], white: [f32; 2]) -> [f32; 3] {
+    let z = |xy: [f32; 2]| 1.0 - xy[0] - xy[1];
+
+    // solve [xr xg xb; yr yg yb; zr zg zb] * s = white XYZ (with Y = 1)
+    let m = [
+        [red[0], green[0], blue[0]],
+        [red[1], green[1], blue[1]],
+        [z(red), z(green), z(blue)],
+    ];
+    let w = [white[0] / white[1], 1.0, z(white) / white[1]];
+
+    let det = |m: [[f32; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+
+    let d = det(m);
+    let mut y = [0.0f32; 3];
+
+    for (column, weight) in y.iter_mut().enumerate() {
+        // cramer's rule
+        let mut replaced = m;
+        for row in 0..3 {
+            replaced[row][column] = w[row];
+        }
+        *weight = m[1][column] * det(replaced) / d;
+    }
+
+    let sum = y[0] + y[1] + y[2];
+    [y[0] / sum, y[1] / sum, y[2] / sum]
+}
+
+/// Reconstructs RGBA pixels from a luminance/chroma layer.
+///
+/// The layer must contain a full resolution `Y` channel and may contain
+/// `RY` and `BY` chroma channels (sampled at every pixel or at every
+/// second pixel in both directions) plus an `A` channel.
+/// Without chroma channels, the result is grayscale.
+///
+/// Subsampled chroma is reconstructed like the OpenEXR `RgbaInputFile` does it:
+/// missing samples are interpolated with a separable low-pass filter,
+/// converted to RGB using the `weights` (see [`luminance_weights`]),
+/// and pixels that became much more saturated than their neighbours
+/// are desaturated again. Intermediate values are rounded to f16.
+///
+/// Returns a four channel image.
+pub fn luminance_chroma_to_rgba(layer: &ImageLayer, weights: [f32; 3]) -> IoResult<ImageData> {
+    let width = layer.width as usize;
+    let height = layer.height as usize;
+    let pixel_count = width * height;
+
+    let find = |name: &str| layer.channels.iter().find(|channel| channel.name == name);
+    let full_resolution = |channel: &ImageChannel| -> IoResult<Vec<f32>> {
+        let samples = channel.samples.to_f32();
+        if channel.sampling != (1, 1) || samples.len() < pixel_count {
+            return Err(IoError::DecodeError(format!(
+                "channel '{}' is not a full resolution channel", channel.name
+            )));
+        }
+        Ok(samples)
+    };
+
+    let luminance = find("Y")
+        .ok_or_else(|| IoError::DecodeError(format!("layer '{}' has no Y channel", layer.name)))?;
+
+    let luminance = full_resolution(luminance)?;
+    let alpha = find("A").map(full_resolution).transpose()?;
+    let alpha_at = |index: usize| alpha.as_ref().map_or(1.0, |alpha| alpha[index]);
+
+    let mut pixels = Vec::with_capacity(pixel_count * 4);
+
+    match (find("RY"), find("BY")) {
+        (None, None) => {
+            for (index, &y) in luminance.iter().take(pixel_count).enumerate() {
+                pixels.extend_from_slice(&[y, y, y, alpha_at(index)]);
+            }
+        }
+
+        (Some(ry), Some(by)) if ry.sampling == (1, 1) && by.sampling == (1, 1) => {
+            let ry = full_resolution(ry)?;
+            let by = full_resolution(by)?;
+
+            for index in 0..pixel_count {
+                let [r, g, b] = luminance_chroma_to_rgb(luminance[index], ry[index], by[index], weights);
+                pixels.extend_from_slice(&[r, g, b, alpha_at(index)]);
+            }
+        }
+
+        (Some(ry), Some(by)) if ry.sampling == (2, 2) && by.sampling == (2, 2) => {
+            let samples_x = width.div_ceil(2);
+            let samples_y = height.div_ceil(2);
+
+            for channel in [ry, by] {
+                if channel.samples.len() < samples_x * samples_y {
+                    return Err(IoError::DecodeError(format!(
+                        "channel '{}' has too few samples", channel.name
+                    )));
+                }
+            }
+
+            // one virtual line above and below the image is required to fix the saturation
+            let ry = reconstruct_chroma(&ry.samples.to_f32(), width, height);
+            let by = reconstruct_chroma(&by.samples.to_f32(), width, height);
+
+            let rgb_line = |line: usize| -> Vec<[f32; 3]> {
+                // line index zero is the virtual line above the image
+                let y = match line {
+                    0 => 0,
+                    line if line > height => last_even(height),
+                    line => line - 1,
+                };
+
+                (0..width)
+                    .map(|x| luminance_chroma_to_rgb(luminance[y * width + x], ry[line][x], by[line][x], weights))
+                    .collect()
+            };
+
+            let mut above = rgb_line(0);
+            let mut current = rgb_line(1);
+
+            for y in 0..height {
+                let below = rgb_line(y + 2);
+                let fixed = fix_saturation([&above, &current, &below], weights);
+
+                for (x, [r, g, b]) in fixed.into_iter().enumerate() {
+                    pixels.extend_from_slice(&[r, g, b, alpha_at(y * width + x)]);
+                }
+
+                above = std::mem::replace(&mut current, below);
+            }
+        }
+
+        (Some(ry), Some(by)) => {
+            return Err(IoError::DecodeError(format!(
+                "unsupported chroma sampling rates {:?} and {:?}", ry.sampling, by.sampling
+            )))
+        }
+
+        _ => return Err(IoError::DecodeError("layer has only one of RY and BY".into())),
+    }
+
+    Ok(ImageData::from_f32(layer.width, layer.height, 4, pixels))
+}
+
+/// Rounds a value to the nearest f16, like storing it in a half pixel would.
+fn round_to_f16(value: f32) -> f32 {
+    half::f16::from_f32(value).to_f32()
+}
+
+/// The largest even index smaller than `length`.
+fn last_even(length: usize) -> usize {
+    length.saturating_sub(1) & !1
+}
+
+/// Clamps a line or column to the image, where indices past
+/// the end are replaced by the last sampled (even) index.
+fn clamp_sampled(index: isize, length: usize) -> usize {
+    if index < 0 {
+        0
+    } else if index as usize >= length {
+        last_even(length)
+    } else {
+        index as usize
+    }
+}
+
+/// Applies [`CHROMA_FILTER`] to the samples around `index`.
+fn filter_chroma(index: isize, sample: impl Fn(usize) -> f32, length: usize) -> f32 {
+    let sum: f32 = CHROMA_FILTER
+        .iter()
+        .enumerate()
+        .map(|(tap, coefficient)| {
+            let position = index - 13 + 2 * tap as isize;
+            coefficient * sample(clamp_sampled(position, length))
+        })
+        .sum();
+
+    round_to_f16(sum)
+}
+
+/// Reconstructs a chroma channel sampled at every second pixel to full resolution.
+///
+/// Returns `height + 2` lines: the first and last line lie outside of the image.
+fn reconstruct_chroma(samples: &[f32], width: usize, height: usize) -> Vec<Vec<f32>> {
+    let samples_x = width.div_ceil(2);
+
+    // lines with samples, filtered horizontally
+    let sampled_lines: Vec<Vec<f32>> = samples
+        .chunks_exact(samples_x)
+        .take(height.div_ceil(2))
+        .map(|line| {
+            (0..width)
+                .map(|x| {
+                    if x % 2 == 0 {
+                        line[x / 2]
+                    } else {
+                        filter_chroma(x as isize, |x| line[x / 2], width)
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    // all lines, filtered vertically
+    (-1..=height as isize)
+        .map(|y| {
+            if y.rem_euclid(2) == 0 {
+                sampled_lines[clamp_sampled(y, height) / 2].clone()
+            } else {
+                (0..width)
+                    .map(|x| filter_chroma(y, |y| sampled_lines[y / 2][x], height))
+                    .collect()
+            }
+        })
+        .collect()
+}
+
+/// Converts a luminance/chroma sample to RGB.
+fn luminance_chroma_to_rgb(y: f32, ry: f32, by: f32, weights: [f32; 3]) -> [f32; 3] {
+    if ry == 0.0 && by == 0.0 {
+        return [y, y, y];
+    }
+
+    let r = (ry + 1.0) * y;
+    let b = (by + 1.0) * y;
+    let g = (y - r * weights[0] - b * weights[2]) / weights[1];
+
+    [round_to_f16(r), round_to_f16(g), round_to_f16(b)]
+}
+
+/// Desaturates the pixels of the `lines[1]` that are considerably
+/// more saturated than their neighbours in the lines above and below,
+/// which removes color fringes introduced by the chroma reconstruction.
+fn fix_saturation(lines: [&[[f32; 3]]; 3], weights: [f32; 3]) -> Vec<[f32; 3]> {
+    let saturation = |[r, g, b]: [f32; 3]| {
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        if max > 0.0 { 1.0 - min / max } else { 0.0 }
+    };
+
+    let luminance = |[r, g, b]: [f32; 3]| r * weights[0] + g * weights[1] + b * weights[2];
+
+    let [above, current, below] = lines;
+    let last = current.len().saturating_sub(1);
+
+    current
+        .iter()
+        .enumerate()
+        .map(|(x, &rgb)| {
+            let (left, right) = (x.saturating_sub(1), (x + 1).min(last));
+
+            let neighbours = saturation(above[left]) + saturation(above[right])
+                + saturation(below[left]) + saturation(below[right]);
+
+            let mean = (0.25 * neighbours).min(1.0);
+            let max = (1.0 - (1.0 - mean) * 0.25).min(1.0);
+            let s = saturation(rgb);
+
+            if s <= max {
+                return rgb;
+            }
+
+            // desaturate
+            let factor = max / s;
+            let brightest = rgb[0].max(rgb[1]).max(rgb[2]);
+            let mut out = rgb.map(|c| round_to_f16((brightest - (brightest - c) * factor).max(0.0)));
+
+            let luminance_out = luminance(out);
+            if luminance_out > 0.0 {
+                let scale = luminance(rgb) / luminance_out;
+                out = out.map(|c| round_to_f16(c * scale));
+            }
+
+            out
+        })
+        .collect()
+}
+
 // ============================================================================
 // ExrWriter
 // ============================================================================
@@ -629,6 +1000,15 @@ impl ExrWriter {
 
     /// Internal multi-layer write implementation.
     fn write_layers_impl(&self, image: &LayeredImage) -> IoResult<Vec<u8>> {
+        self.write_layers_with_attributes(image, |_, _| {})
+    }
+
+    /// Writes layers, letting `set_attributes` adjust the attributes of each part.
+    fn write_layers_with_attributes(
+        &self,
+        image: &LayeredImage,
+        mut set_attributes: impl FnMut(usize, &mut vfx_exr::meta::header::LayerAttributes),
+    ) -> IoResult<Vec<u8>> {
         use vfx_exr::image::FlatSamples;
         use vfx_exr::meta::attribute::Text;
         use vfx_exr::meta::header::ImageAttributes;
@@ -726,12 +1106,10 @@ impl ExrWriter {
 
                 let channels = AnyChannels::sort(list);
 
-                let layer = Layer::new(
-                    (width, height),
-                    LayerAttributes::named(name.as_str()),
-                    encoding,
-                    channels,
-                );
+                let mut attributes = LayerAttributes::named(name.as_str());
+                set_attributes(idx, &mut attributes);
+
+                let layer = Layer::new((width, height), attributes, encoding, channels);
                 layers.push(layer);
             }
 
@@ -874,16 +1252,145 @@ pub fn num_layers<P: AsRef<Path>>(path: P) -> IoResult<usize> {
     Ok(meta.headers.len())
 }
 
+/// Gets the number of layers in an EXR file held in memory.
+pub fn num_layers_from_memory(data: &[u8]) -> IoResult<usize> {
+    let meta = vfx_exr::meta::MetaData::read_from_buffered(Cursor::new(data), false)
+        .map_err(|e| IoError::DecodeError(format!("EXR metadata read failed: {}", e)))?;
+    Ok(meta.headers.len())
+}
+
+/// Selects the part of an EXR file to decode with [`read_part`].
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::exr::{self, ExrPartRequest};
+///
+/// let request = ExrPartRequest::layer(3)
+///     .with_channels(["diffuse.R", "diffuse.G", "diffuse.B"])
+///     .with_region(512, 512, 256, 256);
+/// let part = exr::read_part("render.exr", &request)?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExrPartRequest {
+    /// Layer (part) index, 0-based.
+    pub layer: usize,
+    /// Channel names to decode. `None` decodes all channels.
+    pub channels: Option<Vec<String>>,
+    /// Pixel rectangle `(x, y, width, height)` in data window coordinates.
+    /// `None` decodes the whole level.
+    pub region: Option<(i32, i32, u32, u32)>,
+    /// Mip or rip level `(x, y)`. `(0, 0)` is the full resolution.
+    pub level: (usize, usize),
+}
+
+impl ExrPartRequest {
+    /// Requests all channels of the full resolution level of a layer.
+    pub fn layer(layer: usize) -> Self {
+        Self {
+            layer,
+            ..Self::default()
+        }
+    }
+
+    /// Decodes only the named channels.
+    pub fn with_channels<S: Into<String>>(mut self, channels: impl IntoIterator<Item = S>) -> Self {
+        self.channels = Some(channels.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Decodes only the pixels inside this rectangle.
+    pub fn with_region(mut self, x: i32, y: i32, width: u32, height: u32) -> Self {
+        self.region = Some((x, y, width, height));
+        self
+    }
+
+    /// Decodes a mip level (`(level, level)`) or rip level instead of the full resolution.
+    pub fn with_level(mut self, x: usize, y: usize) -> Self {
+        self.level = (x, y);
+        self
+    }
+}
+
+/// A decoded part of an EXR layer, as requested with [`ExrPartRequest`].
+#[derive(Debug, Clone)]
+pub struct ExrPart {
+    /// The selected channels of the decoded rectangle.
+    pub layer: ImageLayer,
+    /// Data window x coordinate of the first decoded pixel.
+    /// Differs from the requested region if it was clipped to the data window.
+    pub x: i32,
+    /// Data window y coordinate of the first decoded pixel.
+    pub y: i32,
+}
+
+/// Decodes a channel subset, pixel region and resolution level of one layer.
+///
+/// Seeks to the chunks that overlap the request using the offset tables of
+/// the file, so only those chunks are read and decompressed. The region is
+/// clipped to the data window of the level; a region outside of it is an error.
+/// Luminance/chroma channels are returned as stored, without reconstruction.
+pub fn read_part<P: AsRef<Path>>(path: P, request: &ExrPartRequest) -> IoResult<ExrPart> {
+    let file = std::fs::File::open(path.as_ref())?;
+    read_part_from(std::io::BufReader::new(file), request)
+}
+
+/// Decodes part of one layer from an EXR file held in memory.
+///
+/// See [`read_part`].
+pub fn read_part_from_memory(data: &[u8], request: &ExrPartRequest) -> IoResult<ExrPart> {
+    read_part_from(Cursor::new(data), request)
+}
+
+fn read_part_from<R: std::io::Read + std::io::Seek>(source: R, request: &ExrPartRequest) -> IoResult<ExrPart> {
+    use vfx_exr::image::read::region::read_region;
+    use vfx_exr::math::Vec2;
+    use vfx_exr::meta::attribute::{IntegerBounds, Text};
+
+    let mut reader = read_region(request.layer).level(Vec2(request.level.0, request.level.1));
+
+    if let Some(channels) = &request.channels {
+        let names = channels
+            .iter()
+            .map(|name| {
+                Text::new_or_none(name).ok_or_else(|| {
+                    IoError::DecodeError(format!("invalid EXR channel name: {}", name))
+                })
+            })
+            .collect::<IoResult<Vec<_>>>()?;
+        reader = reader.channels(names);
+    }
+
+    if let Some((x, y, width, height)) = request.region {
+        reader = reader.region(IntegerBounds::new(
+            Vec2(x, y),
+            Vec2(width as usize, height as usize),
+        ));
+    }
+
+    let image = reader
+        .from_buffered(source)
+        .map_err(|e| IoError::DecodeError(format!("EXR decode error: {}", e)))?;
+
+    let position = image.layer_data.attributes.layer_position;
+    Ok(ExrPart {
+        layer: image_layer_from_exr(request.layer, &image.layer_data),
+        x: position.x(),
+        y: position.y(),
+    })
+}
+
 /// Reads a specific layer from an EXR file by index.
 ///
 /// Returns the layer as ImageData (first RGBA/RGB channels found).
-/// For full channel access, use `read_layers()` and index into the result.
+/// Only the chunks of the requested layer and level are decoded.
+/// For full channel access, use [`read_part`].
 ///
 /// # Arguments
 ///
 /// * `path` - Path to EXR file
 /// * `layer_idx` - Layer index (0-based)
-/// * `_miplevel` - Mip level (currently ignored, reserved for future use)
+/// * `miplevel` - Mip level (0 is the full resolution)
 ///
 /// # Example
 ///
@@ -893,14 +1400,41 @@ pub fn num_layers<P: AsRef<Path>>(path: P) -> IoResult<usize> {
 /// let layer = exr::read_layer("multipart.exr", 1, 0)?;
 /// println!("Layer 1: {}x{}", layer.width, layer.height);
 /// ```
-pub fn read_layer<P: AsRef<Path>>(path: P, layer_idx: usize, _miplevel: usize) -> IoResult<ImageData> {
-    let layered = read_layers(&path)?;
-    
-    let layer = layered.layers.get(layer_idx)
-        .ok_or_else(|| IoError::DecodeError(
-            format!("Layer index {} out of range (file has {} layers)", layer_idx, layered.layers.len())
-        ))?;
-    
+pub fn read_layer<P: AsRef<Path>>(path: P, layer_idx: usize, miplevel: usize) -> IoResult<ImageData> {
+    let part = read_part(&path, &ExrPartRequest::layer(layer_idx).with_level(miplevel, miplevel))?;
+    layer_to_image_data(&part.layer, layer_idx, || {
+        vfx_exr::meta::MetaData::read_from_file(&path, false)
+    })
+}
+
+/// Reads a specific layer from an EXR file held in memory.
+///
+/// See [`read_layer`].
+pub fn read_layer_from_memory(data: &[u8], layer_idx: usize, miplevel: usize) -> IoResult<ImageData> {
+    let part = read_part_from_memory(data, &ExrPartRequest::layer(layer_idx).with_level(miplevel, miplevel))?;
+    layer_to_image_data(&part.layer, layer_idx, || {
+        vfx_exr::meta::MetaData::read_from_buffered(Cursor::new(data), false)
+    })
+}
+
+/// Converts a decoded layer to RGB(A), reading the header only for luminance/chroma layers.
+fn layer_to_image_data<F>(layer: &ImageLayer, layer_idx: usize, read_meta: F) -> IoResult<ImageData>
+where
+    F: FnOnce() -> vfx_exr::error::Result<vfx_exr::meta::MetaData>,
+{
+    // Luminance/chroma layers need their chroma reconstructed
+    if layer.channels.iter().any(|ch| ch.name == "RY" || ch.name == "BY") {
+        let meta = read_meta()
+            .map_err(|e| IoError::DecodeError(format!("EXR decode error: {}", e)))?;
+
+        let chromaticities = meta.headers.get(layer_idx)
+            .and_then(|header| header.shared_attributes.chromaticities.as_ref());
+
+        let mut result = luminance_chroma_to_rgba(layer, exr_luminance_weights(chromaticities))?;
+        result.metadata.colorspace = Some("linear".to_string());
+        return Ok(result);
+    }
+
     // Convert ImageLayer to ImageData (RGBA)
     let width = layer.width;
     let height = layer.height;
@@ -970,43 +1504,828 @@ pub fn read_layer<P: AsRef<Path>>(path: P, layer_idx: usize, _miplevel: usize) -
 }
 
 // ============================================================================
-// Deep EXR Support
+// Multi-View
 // ============================================================================
 
-// Re-export deep data types and functions from our standalone module
-pub use crate::exr_deep::{
-    DeepChannelData, DeepExrStats, DeepSamples, SampleType as DeepSampleType,
-    is_deep_exr, probe_deep_exr,
-};
+/// How [`write_views`] stores the views of a multi-view file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViewLayout {
+    /// One part with a `multiView` attribute. Channels insert the view name
+    /// before the last component (`R` becomes `right.R`, `diffuse.R` becomes
+    /// `diffuse.right.R`), except channels of the default view without a layer
+    /// prefix, which keep their names.
+    #[default]
+    SinglePart,
+    /// One part per view, each part named after its view and tagged with a `view` attribute.
+    MultiPart,
+}
 
-// DeepData import kept for function signature (returns stub error)
-use crate::deepdata::DeepData;
-// TypeDesc removed - was only used in full deep read implementation
+/// The views of a multi-view EXR file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExrViews {
+    /// All view names. The first one is the default view.
+    /// Empty if the file is not a multi-view file.
+    pub views: Vec<String>,
+    /// The `view` attribute of each part, for multi-part files.
+    pub part_views: Vec<Option<String>>,
+}
 
-/// Reads a deep EXR file and converts to vfx-io DeepData format.
+impl ExrViews {
+    /// The default view, which is the first one of the `multiView` attribute.
+    pub fn default_view(&self) -> Option<&str> {
+        self.views.first().map(String::as_str)
+    }
+}
+
+/// Reads the views of an EXR file from its headers.
 ///
-/// Reads the first deep layer from the file and converts the exrs SoA format
-/// to vfx-io's OIIO-compatible AoS format.
+/// Views are taken from the `multiView` attribute, followed by the `view`
+/// attributes of the parts that are not listed there.
+pub fn read_views<P: AsRef<Path>>(path: P) -> IoResult<ExrViews> {
+    let meta = vfx_exr::meta::MetaData::read_from_file(path.as_ref(), false)
+        .map_err(|e| IoError::DecodeError(format!("EXR metadata read failed: {}", e)))?;
+
+    let mut views: Vec<String> = meta
+        .headers
+        .iter()
+        .find_map(|header| header.own_attributes.multi_view_names.as_ref())
+        .map(|names| names.iter().map(|name| name.to_string()).collect())
+        .unwrap_or_default();
+
+    let part_views: Vec<Option<String>> = meta
+        .headers
+        .iter()
+        .map(|header| header.own_attributes.view_name.as_ref().map(|name| name.to_string()))
+        .collect();
+
+    for view in part_views.iter().flatten() {
+        if !views.contains(view) {
+            views.push(view.clone());
+        }
+    }
+
+    Ok(ExrViews { views, part_views })
+}
+
+/// Lists the views of an EXR file, default view first.
+pub fn list_views<P: AsRef<Path>>(path: P) -> IoResult<Vec<String>> {
+    Ok(read_views(path)?.views)
+}
+
+/// Returns the view a channel of a single-part multi-view file belongs to.
+///
+/// Follows the OpenEXR naming rules: a name without periods belongs to the
+/// default view, a name whose second to last component is a view name belongs
+/// to that view, and any other name belongs to no view and is shared by all views.
+pub fn view_of_channel<'v>(channel: &str, views: &'v [String]) -> Option<&'v str> {
+    let components: Vec<&str> = channel.split('.').collect();
+    if components.len() == 1 {
+        return views.first().map(String::as_str);
+    }
+
+    let candidate = components[components.len() - 2];
+    views.iter().map(String::as_str).find(|view| *view == candidate)
+}
+
+/// Returns the name under which a channel of `view` is stored in a single-part multi-view file.
+///
+/// Like OpenEXR's `insertViewName`, only channels of the default view (the
+/// first one of `views`) without periods keep their name. Prefixed channels
+/// always get the view component, since `diffuse.R` would be shared by all views.
+pub fn channel_in_view(channel: &str, view: &str, views: &[String]) -> String {
+    if views.first().map(String::as_str) == Some(view) && !channel.contains('.') {
+        return channel.to_string();
+    }
+
+    match channel.rsplit_once('.') {
+        Some((prefix, base)) => format!("{}.{}.{}", prefix, view, base),
+        None => format!("{}.{}", view, channel),
+    }
+}
+
+/// Removes the view component from the name of a channel stored in a single-part multi-view file.
+fn channel_without_view(channel: &str, view: &str) -> String {
+    let mut components: Vec<&str> = channel.split('.').collect();
+    if components.len() >= 2 && components[components.len() - 2] == view {
+        components.remove(components.len() - 2);
+    }
+    components.join(".")
+}
+
+/// Reads all layers of one view of a multi-view EXR file.
+///
+/// For single-part files, only the channels of the view and the channels
+/// shared by all views are decoded, and the view component is removed from
+/// their names, so every view reads with the same channel names. For
+/// multi-part files, the parts of the view and the parts without a `view`
+/// attribute are decoded.
 ///
 /// # Example
 ///
 /// ```ignore
 /// use vfx_io::exr;
 ///
-/// let deep = exr::read_deep("deep_render.exr")?;
-/// println!("{}x{} pixels, {} total samples",
-///     deep.pixels() / height as i64, height,
-///     deep.all_samples().iter().map(|&s| s as u64).sum::<u64>());
+/// let left = exr::read_view("stereo.exr", "left")?;
 /// ```
-pub fn read_deep<P: AsRef<Path>>(_path: P) -> IoResult<DeepData> {
-    // Read SoA deep samples and convert to AoS for the public DeepData API.
-    let (samples, channels) = crate::exr_deep::read_deep_exr(_path)?;
-    crate::exr_deep::deep_samples_to_deepdata(&samples, &channels)
-}
+pub fn read_view<P: AsRef<Path>>(path: P, view: &str) -> IoResult<LayeredImage> {
+    let path = path.as_ref();
+    let info = read_views(path)?;
+
+    if !info.views.iter().any(|name| name == view) {
+        return Err(IoError::DecodeError(format!(
+            "EXR has no view named '{}' (views: {})",
+            view,
+            info.views.join(", ")
+        )));
+    }
 
-/// Writes DeepData to a deep EXR file.
-///
-/// Converts vfx-io's OIIO-compatible AoS format to exrs SoA format and writes.
+    let meta = vfx_exr::meta::MetaData::read_from_file(path, false)
+        .map_err(|e| IoError::DecodeError(format!("EXR metadata read failed: {}", e)))?;
+
+    let mut layered = LayeredImage {
+        layers: Vec::new(),
+        metadata: Metadata::default(),
+    };
+    layered.metadata.colorspace = Some("linear".to_string());
+
+    for (index, header) in meta.headers.iter().enumerate() {
+        if header.deep {
+            continue;
+        }
+
+        match (&info.part_views[index], &header.own_attributes.multi_view_names) {
+            (Some(part_view), _) => {
+                if part_view == view {
+                    layered.layers.push(read_part(path, &ExrPartRequest::layer(index))?.layer);
+                }
+            }
+            (None, Some(_)) => {
+                let channels: Vec<String> = header
+                    .channels
+                    .list
+                    .iter()
+                    .map(|channel| channel.name.to_string())
+                    .filter(|name| view_of_channel(name, &info.views).is_none_or(|v| v == view))
+                    .collect();
+
+                if channels.is_empty() {
+                    continue;
+                }
+
+                let mut layer = read_part(path, &ExrPartRequest::layer(index).with_channels(channels))?.layer;
+                for channel in &mut layer.channels {
+                    channel.name = channel_without_view(&channel.name, view);
+                }
+                layered.layers.push(layer);
+            }
+            (None, None) => {
+                layered.layers.push(read_part(path, &ExrPartRequest::layer(index))?.layer);
+            }
+        }
+    }
+
+    Ok(layered)
+}
+
+/// Writes the views of a stereo or multi-view image.
+///
+/// The first view is the default view. With [`ViewLayout::SinglePart`], all
+/// view layers must have the same size.
+/// Several render passes of a view can be stored as channels with a layer
+/// prefix, like `diffuse.R`.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::exr::{self, ViewLayout};
+///
+/// exr::write_views("stereo.exr", &[("left", &left), ("right", &right)], ViewLayout::SinglePart)?;
+/// ```
+pub fn write_views<P: AsRef<Path>>(
+    path: P,
+    views: &[(&str, &ImageLayer)],
+    layout: ViewLayout,
+) -> IoResult<()> {
+    ExrWriter::new().write_views(path, views, layout)
+}
+
+impl ExrWriter {
+    /// Writes the views of a stereo or multi-view image. See [`write_views`].
+    pub fn write_views<P: AsRef<Path>>(
+        &self,
+        path: P,
+        views: &[(&str, &ImageLayer)],
+        layout: ViewLayout,
+    ) -> IoResult<()> {
+        use vfx_exr::meta::attribute::Text;
+
+        let view_names: Vec<String> = views.iter().map(|(name, _)| name.to_string()).collect();
+        let mut texts = Vec::with_capacity(view_names.len());
+        for (index, name) in view_names.iter().enumerate() {
+            if name.is_empty() || name.contains('.') || view_names[..index].contains(name) {
+                return Err(IoError::EncodeError(format!(
+                    "EXR encode error: invalid or duplicate view name '{}'",
+                    name
+                )));
+            }
+            texts.push(Text::new_or_none(name).ok_or_else(|| {
+                IoError::EncodeError(format!("EXR encode error: unsupported view name '{}'", name))
+            })?);
+        }
+
+        let (first_name, first_layer) = views.first().ok_or_else(|| {
+            IoError::EncodeError("EXR encode error: no views provided".into())
+        })?;
+
+        let data = match layout {
+            ViewLayout::SinglePart => {
+                let mut channels = Vec::new();
+                for (view, layer) in views {
+                    if (layer.width, layer.height) != (first_layer.width, first_layer.height) {
+                        return Err(IoError::EncodeError(format!(
+                            "EXR encode error: view '{}' is {}x{}, but view '{}' is {}x{}",
+                            view, layer.width, layer.height, first_name, first_layer.width, first_layer.height
+                        )));
+                    }
+                    for channel in &layer.channels {
+                        let mut channel = channel.clone();
+                        channel.name = channel_in_view(&channel.name, view, &view_names);
+                        channels.push(channel);
+                    }
+                }
+
+                let image = LayeredImage {
+                    layers: vec![ImageLayer {
+                        name: if first_layer.name.is_empty() {
+                            first_name.to_string()
+                        } else {
+                            first_layer.name.clone()
+                        },
+                        width: first_layer.width,
+                        height: first_layer.height,
+                        channels,
+                    }],
+                    metadata: Metadata::default(),
+                };
+
+                self.write_layers_with_attributes(&image, |_, attributes| {
+                    attributes.multi_view_names = Some(texts.clone());
+                })?
+            }
+            ViewLayout::MultiPart => {
+                let image = LayeredImage {
+                    layers: views
+                        .iter()
+                        .map(|(view, layer)| ImageLayer {
+                            name: view.to_string(),
+                            ..(*layer).clone()
+                        })
+                        .collect(),
+                    metadata: Metadata::default(),
+                };
+
+                self.write_layers_with_attributes(&image, |index, attributes| {
+                    attributes.view_name = Some(texts[index].clone());
+                })?
+            }
+        };
+
+        std::fs::write(path.as_ref(), data)?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Chunk Copy
+// ============================================================================
+
+/// A part to copy with [`copy_parts`], without decoding its pixels.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::exr::{self, ExrPartCopy};
+///
+/// // merge the beauty of one render with the second part of another
+/// exr::copy_parts(
+///     &["beauty.exr", "aovs.exr"],
+///     &[
+///         ExrPartCopy::new(0, 0).with_name("beauty"),
+///         ExrPartCopy::new(1, 1).with_channel_name("Z", "depth.Z"),
+///     ],
+///     "merged.exr",
+/// )?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExrPartCopy {
+    /// Index of the source file.
+    pub source: usize,
+    /// Index of the part in the source file.
+    pub part: usize,
+    /// New part name, if any.
+    pub name: Option<String>,
+    /// Channel renames as (old, new) pairs.
+    pub channel_names: Vec<(String, String)>,
+}
+
+impl ExrPartCopy {
+    /// Copies a part of a source file unchanged.
+    pub fn new(source: usize, part: usize) -> Self {
+        Self {
+            source,
+            part,
+            ..Self::default()
+        }
+    }
+
+    /// Renames the part.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Renames a channel of the part.
+    ///
+    /// The new name must keep the channel at the same position in the
+    /// alphabetically sorted channel list, as the compressed data is not reordered.
+    pub fn with_channel_name(mut self, old: impl Into<String>, new: impl Into<String>) -> Self {
+        self.channel_names.push((old.into(), new.into()));
+        self
+    }
+}
+
+/// Name and channels of an EXR part, read from its header.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExrPartInfo {
+    /// Part name, empty for unnamed parts.
+    pub name: String,
+    /// Channel names in file order.
+    pub channels: Vec<String>,
+}
+
+/// Reads the name and channels of each part of an EXR file, without decoding pixels.
+pub fn read_part_infos<P: AsRef<Path>>(path: P) -> IoResult<Vec<ExrPartInfo>> {
+    let meta = vfx_exr::meta::MetaData::read_from_file(path.as_ref(), false)
+        .map_err(|e| IoError::DecodeError(format!("EXR metadata read failed: {}", e)))?;
+
+    Ok(meta
+        .headers
+        .iter()
+        .map(|header| ExrPartInfo {
+            name: header
+                .own_attributes
+                .layer_name
+                .as_ref()
+                .map(|name| name.to_string())
+                .unwrap_or_default(),
+            channels: header
+                .channels
+                .list
+                .iter()
+                .map(|channel| channel.name.to_string())
+                .collect(),
+        })
+        .collect())
+}
+
+/// Copies parts of EXR files into a new file without decoding any pixels.
+///
+/// Merges, splits and renames parts while the compressed chunks stay
+/// bit-identical, so lossy DWA or B44 data is not compressed again.
+/// Several parts result in a multi-part file, where part names must be unique.
+pub fn copy_parts<S: AsRef<Path>, P: AsRef<Path>>(
+    sources: &[S],
+    parts: &[ExrPartCopy],
+    output: P,
+) -> IoResult<()> {
+    use vfx_exr::block::copy::{copy_parts_to_file, PartCopy};
+    use vfx_exr::meta::attribute::Text;
+
+    let text = |name: &str| {
+        Text::new_or_none(name)
+            .ok_or_else(|| IoError::EncodeError(format!("EXR encode error: unsupported name '{}'", name)))
+    };
+
+    let mut copies = Vec::with_capacity(parts.len());
+    for part in parts {
+        let mut copy = PartCopy::new(part.source, part.part);
+        if let Some(name) = &part.name {
+            copy = copy.with_name(text(name)?);
+        }
+        for (old, new) in &part.channel_names {
+            copy = copy.with_channel_name(text(old)?, text(new)?);
+        }
+        copies.push(copy);
+    }
+
+    copy_parts_to_file(sources, &copies, output.as_ref())
+        .map_err(|e| IoError::EncodeError(format!("EXR chunk copy failed: {}", e)))
+}
+
+// ============================================================================
+// Header Editing
+// ============================================================================
+
+pub use vfx_exr::meta::edit::HeaderUpdate;
+
+/// Attribute changes for [`edit_header`], applied without decoding any pixels.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::exr::{self, ExrHeaderEdit};
+/// use vfx_io::AttrValue;
+///
+/// let edit = ExrHeaderEdit::new()
+///     .set("owner", AttrValue::Str("comp".into()))
+///     .set("shot", AttrValue::Str("sh010".into()))
+///     .remove("comments");
+/// exr::edit_header("render.exr", &edit)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ExrHeaderEdit {
+    /// Part to edit, or all parts if `None`.
+    pub part: Option<usize>,
+    /// Attributes to set, by EXR attribute name.
+    pub set: Vec<(String, AttrValue)>,
+    /// Attributes to remove, by EXR attribute name.
+    pub remove: Vec<String>,
+}
+
+impl ExrHeaderEdit {
+    /// Creates an empty edit for all parts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only edits the specified part.
+    pub fn part(mut self, part: usize) -> Self {
+        self.part = Some(part);
+        self
+    }
+
+    /// Sets an attribute.
+    pub fn set(mut self, name: impl Into<String>, value: AttrValue) -> Self {
+        self.set.push((name.into(), value));
+        self
+    }
+
+    /// Removes an attribute.
+    pub fn remove(mut self, name: impl Into<String>) -> Self {
+        self.remove.push(name.into());
+        self
+    }
+}
+
+/// Edits the attributes of an EXR file without decoding its pixels.
+///
+/// The headers are rewritten and the compressed chunks are copied unchanged.
+/// When the new headers fit into the old header space, only the headers are
+/// overwritten. Standard attributes are converted to their EXR type, like
+/// `chromaticities` from a list of eight floats or `timeCode` from `hh:mm:ss:ff`.
+/// Attributes that describe the pixel layout, like `channels`, cannot be edited.
+pub fn edit_header<P: AsRef<Path>>(path: P, edit: &ExrHeaderEdit) -> IoResult<HeaderUpdate> {
+    use vfx_exr::meta::attribute::Text;
+
+    let mut values = Vec::with_capacity(edit.set.len());
+    for (name, value) in &edit.set {
+        let text = Text::new_or_none(name)
+            .ok_or_else(|| IoError::EncodeError(format!("EXR encode error: unsupported name '{}'", name)))?;
+        values.push((text, exr_attribute_value(name, value)?));
+    }
+
+    vfx_exr::meta::edit::edit_headers_in_file(path.as_ref(), |headers| {
+        if let Some(part) = edit.part {
+            if part >= headers.len() {
+                return Err(vfx_exr::error::Error::Invalid(format!("part index {}", part).into()));
+            }
+        }
+
+        for (index, header) in headers.iter_mut().enumerate() {
+            if edit.part.is_some_and(|part| part != index) {
+                continue;
+            }
+
+            for name in &edit.remove {
+                header.remove_attribute(name.as_bytes())?;
+            }
+
+            for (name, value) in &values {
+                header.set_attribute(name.clone(), value.clone())?;
+            }
+        }
+
+        Ok(())
+    })
+    .map_err(|e| IoError::EncodeError(format!("EXR header edit failed: {}", e)))
+}
+
+/// Converts an attribute value to the EXR type of the named attribute.
+fn exr_attribute_value(name: &str, value: &AttrValue) -> IoResult<vfx_exr::meta::attribute::AttributeValue> {
+    use vfx_exr::math::Vec2;
+    use vfx_exr::meta::attribute::{AttributeValue, Chromaticities, Text, TimeCode};
+
+    let invalid = || IoError::EncodeError(format!("EXR encode error: invalid value for attribute '{}'", name));
+    let text = |value: &str| Text::new_or_none(value).ok_or_else(invalid);
+
+    let float = |value: &AttrValue| match value {
+        AttrValue::Float(v) => Some(*v),
+        AttrValue::Double(v) => Some(*v as f32),
+        AttrValue::Int(v) => Some(*v as f32),
+        AttrValue::UInt(v) => Some(*v as f32),
+        AttrValue::Str(v) => v.trim().parse().ok(),
+        _ => None,
+    };
+
+    let floats = |value: &AttrValue| -> Option<Vec<f32>> {
+        match value {
+            AttrValue::List(items) => items.iter().map(float).collect(),
+            AttrValue::Str(v) => v.split(',').map(|item| item.trim().parse().ok()).collect(),
+            _ => None,
+        }
+    };
+
+    let rational = |value: &AttrValue| -> Option<(i32, u32)> {
+        match value {
+            AttrValue::Rational(n, d) => Some((*n, u32::try_from(*d).ok()?)),
+            AttrValue::URational(n, d) => Some((i32::try_from(*n).ok()?, *d)),
+            AttrValue::Int(v) => Some((*v, 1)),
+            AttrValue::Str(v) => {
+                let (n, d) = v.split_once('/').unwrap_or((v.as_str(), "1"));
+                Some((n.trim().parse().ok()?, d.trim().parse().ok()?))
+            }
+            _ => None,
+        }
+    };
+
+    let value = match name {
+        "name" | "owner" | "comments" | "capDate" | "software" | "view" | "wrapmodes"
+        | "renderingTransform" | "lookModTransform" => AttributeValue::Text(text(&match value {
+            AttrValue::Str(v) => v.clone(),
+            other => other.to_string(),
+        })?),
+
+        "pixelAspectRatio" | "screenWindowWidth" | "whiteLuminance" | "xDensity" | "utcOffset"
+        | "longitude" | "latitude" | "altitude" | "focus" | "expTime" | "aperture" | "isoSpeed"
+        | "near" | "far" | "fieldOfViewHorizontal" | "fieldOfViewVertical" => {
+            AttributeValue::F32(float(value).ok_or_else(invalid)?)
+        }
+
+        "screenWindowCenter" | "adoptedNeutral" => match floats(value).as_deref() {
+            Some(&[x, y]) => AttributeValue::FloatVec2(Vec2(x, y)),
+            _ => return Err(invalid()),
+        },
+
+        "framesPerSecond" | "deepImageState" => AttributeValue::Rational(rational(value).ok_or_else(invalid)?),
+
+        "worldToCamera" | "worldToNDC" => {
+            let matrix = floats(value).ok_or_else(invalid)?;
+            AttributeValue::Matrix4x4(matrix.try_into().map_err(|_| invalid())?)
+        }
+
+        "chromaticities" => match floats(value).as_deref() {
+            Some(&[rx, ry, gx, gy, bx, by, wx, wy]) => AttributeValue::Chromaticities(Chromaticities {
+                red: Vec2(rx, ry),
+                green: Vec2(gx, gy),
+                blue: Vec2(bx, by),
+                white: Vec2(wx, wy),
+            }),
+            _ => return Err(invalid()),
+        },
+
+        "multiView" => match value {
+            AttrValue::List(items) => AttributeValue::TextVector(
+                items.iter().map(|item| text(&item.to_string())).collect::<IoResult<_>>()?,
+            ),
+            AttrValue::Str(v) => AttributeValue::TextVector(
+                v.split(',').map(|item| text(item.trim())).collect::<IoResult<_>>()?,
+            ),
+            _ => return Err(invalid()),
+        },
+
+        "timeCode" => {
+            let parts: Vec<u8> = match value {
+                AttrValue::Str(v) => v.split(':').map(|part| part.trim().parse().ok()).collect::<Option<_>>(),
+                _ => None,
+            }
+            .ok_or_else(invalid)?;
+
+            match parts.as_slice() {
+                &[hours, minutes, seconds, frame] => AttributeValue::TimeCode(TimeCode {
+                    hours,
+                    minutes,
+                    seconds,
+                    frame,
+                    ..TimeCode::default()
+                }),
+                _ => return Err(invalid()),
+            }
+        }
+
+        _ => match value {
+            AttrValue::Str(v) => AttributeValue::Text(text(v)?),
+            AttrValue::Bool(v) => AttributeValue::I32(*v as i32),
+            AttrValue::Int(v) => AttributeValue::I32(*v),
+            AttrValue::UInt(v) => AttributeValue::I32(i32::try_from(*v).map_err(|_| invalid())?),
+            AttrValue::Float(v) => AttributeValue::F32(*v),
+            AttrValue::Double(v) => AttributeValue::F64(*v),
+            AttrValue::Rational(..) | AttrValue::URational(..) => {
+                AttributeValue::Rational(rational(value).ok_or_else(invalid)?)
+            }
+            AttrValue::List(items) if items.iter().all(|item| matches!(item, AttrValue::Str(_))) => {
+                AttributeValue::TextVector(
+                    items.iter().map(|item| text(&item.to_string())).collect::<IoResult<_>>()?,
+                )
+            }
+            AttrValue::List(_) => match floats(value).ok_or_else(invalid)?.as_slice() {
+                &[x, y] => AttributeValue::FloatVec2(Vec2(x, y)),
+                &[x, y, z] => AttributeValue::FloatVec3((x, y, z)),
+                matrix if matrix.len() == 9 => AttributeValue::Matrix3x3(matrix.try_into().map_err(|_| invalid())?),
+                matrix if matrix.len() == 16 => AttributeValue::Matrix4x4(matrix.try_into().map_err(|_| invalid())?),
+                _ => return Err(invalid()),
+            },
+            _ => return Err(invalid()),
+        },
+    };
+
+    Ok(value)
+}
+
+// ============================================================================
+// Recovery
+// ============================================================================
+
+/// A block of pixels that could not be recovered from a damaged EXR file.
+///
+/// The position is relative to the data window of the resolution level.
+/// Scanline files lose whole scanline blocks, tiled files lose single tiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExrLostBlock {
+    /// Part (layer) index.
+    pub part: usize,
+    /// Resolution level (x, y), `(0, 0)` is the full resolution.
+    pub level: (usize, usize),
+    /// Left edge of the block.
+    pub x: usize,
+    /// Top edge of the block.
+    pub y: usize,
+    /// Width of the block in pixels.
+    pub width: usize,
+    /// Height of the block in pixels.
+    pub height: usize,
+}
+
+/// Damage found while reading an EXR file in recovery mode.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExrRecoveryReport {
+    /// Number of pixel blocks that were read.
+    pub blocks: usize,
+    /// Offset table entries that were missing or invalid and were rebuilt by scanning the file.
+    pub rebuilt_offsets: usize,
+    /// Blocks that could not be found or decoded, and were filled instead.
+    pub lost_blocks: Vec<ExrLostBlock>,
+}
+
+impl ExrRecoveryReport {
+    /// Returns true if the file needed no repair.
+    pub fn is_intact(&self) -> bool {
+        self.rebuilt_offsets == 0 && self.lost_blocks.is_empty()
+    }
+
+    fn from_exr(report: vfx_exr::block::recover::RecoveryReport) -> Self {
+        let lost_blocks = report
+            .lost_blocks
+            .iter()
+            .map(|block| ExrLostBlock {
+                part: block.layer,
+                level: (block.level.x(), block.level.y()),
+                x: block.pixel_position.x(),
+                y: block.pixel_position.y(),
+                width: block.pixel_size.width(),
+                height: block.pixel_size.height(),
+            })
+            .collect();
+
+        Self {
+            blocks: report.block_count,
+            rebuilt_offsets: report.rebuilt_offset_count,
+            lost_blocks,
+        }
+    }
+}
+
+/// Reads all layers of a damaged EXR file, for example a render that was
+/// interrupted while writing.
+///
+/// Zeroed or truncated offset tables are rebuilt by scanning the chunks, and
+/// every block that can still be decoded is read. Lost blocks are filled with
+/// `fill_value` and listed in the report. Only the headers must be intact.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::exr;
+///
+/// let (image, report) = exr::recover_layers("interrupted.exr", 0.0)?;
+/// for block in &report.lost_blocks {
+///     println!("lost {}x{} at {},{}", block.width, block.height, block.x, block.y);
+/// }
+/// ```
+pub fn recover_layers<P: AsRef<Path>>(path: P, fill_value: f32) -> IoResult<(LayeredImage, ExrRecoveryReport)> {
+    use vfx_exr::prelude::*;
+
+    let path = path.as_ref();
+    let data = std::fs::read(path)?;
+
+    let (image, report) = read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .all_channels()
+        .all_layers()
+        .all_attributes()
+        .recover_from_buffered(Cursor::new(&data), fill_value)
+        .map_err(|e| IoError::DecodeError(format!("EXR recovery failed: {}", e)))?;
+
+    let mut layered = LayeredImage {
+        layers: Vec::new(),
+        metadata: Metadata::default(),
+    };
+
+    layered.metadata.colorspace = Some("linear".to_string());
+    ExrReader::new().extract_metadata(&data, &mut layered.metadata)?;
+
+    for (idx, layer) in image.layer_data.iter().enumerate() {
+        layered.layers.push(image_layer_from_exr(idx, layer));
+    }
+
+    Ok((layered, ExrRecoveryReport::from_exr(report)))
+}
+
+/// Checks every pixel block of an EXR file, including all resolution levels.
+///
+/// Returns an error only if the headers cannot be read. Damaged offset
+/// tables and lost blocks are listed in the report.
+pub fn check<P: AsRef<Path>>(path: P) -> IoResult<ExrRecoveryReport> {
+    use vfx_exr::block::recover::RecoveryReader;
+
+    let file = std::io::BufReader::new(std::fs::File::open(path)?);
+    let mut blocks = RecoveryReader::read_from_buffered(file)
+        .and_then(|reader| reader.filter_blocks(0.0, |_, _, _| true))
+        .map_err(|e| IoError::DecodeError(format!("EXR check failed: {}", e)))?;
+
+    blocks.by_ref().for_each(drop);
+    Ok(ExrRecoveryReport::from_exr(blocks.into_report()))
+}
+
+// ============================================================================
+// Deep EXR Support
+// ============================================================================
+
+// Re-export deep data types and functions from our standalone module
+pub use crate::exr_deep::{
+    DeepChannelData, DeepExrLayout, DeepExrStats, DeepSamples, SampleType as DeepSampleType,
+    is_deep_exr, probe_deep_exr,
+};
+
+// DeepData import kept for function signature (returns stub error)
+use crate::deepdata::DeepData;
+// TypeDesc removed - was only used in full deep read implementation
+
+/// Reads a deep EXR file and converts to vfx-io DeepData format.
+///
+/// Reads the first deep layer from the file and converts the exrs SoA format
+/// to vfx-io's OIIO-compatible AoS format.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::exr;
+///
+/// let deep = exr::read_deep("deep_render.exr")?;
+/// println!("{}x{} pixels, {} total samples",
+///     deep.pixels() / height as i64, height,
+///     deep.all_samples().iter().map(|&s| s as u64).sum::<u64>());
+/// ```
+pub fn read_deep<P: AsRef<Path>>(_path: P) -> IoResult<DeepData> {
+    // Read SoA deep samples and convert to AoS for the public DeepData API.
+    let (samples, channels) = crate::exr_deep::read_deep_exr(_path)?;
+    crate::exr_deep::deep_samples_to_deepdata(&samples, &channels)
+}
+
+/// Reads a deep EXR file together with its block layout and windows.
+///
+/// Pass the layout to [`write_deep_with_layout`] to write the modified data
+/// back with the same tiling and windows. The pixel count of the returned
+/// `DeepData` matches `layout.data_window.size`.
+pub fn read_deep_with_layout<P: AsRef<Path>>(path: P) -> IoResult<(DeepData, DeepExrLayout)> {
+    let (samples, channels, layout) = crate::exr_deep::read_deep_exr_with_layout(path)?;
+    let deep = crate::exr_deep::deep_samples_to_deepdata(&samples, &channels)?;
+    Ok((deep, layout))
+}
+
+/// Writes DeepData to a deep EXR file.
+///
+/// Converts vfx-io's OIIO-compatible AoS format to exrs SoA format and writes.
 ///
 /// # Arguments
 ///
@@ -1055,6 +2374,29 @@ pub fn write_deep_with_compression<P: AsRef<Path>>(
     crate::exr_deep::write_deep_exr(path, &samples, &channels, exr_compression)
 }
 
+/// Writes DeepData to a deep EXR file with the given block layout and windows.
+///
+/// The image dimensions are taken from `layout.data_window`.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::exr;
+///
+/// let (deep, layout) = exr::read_deep_with_layout("deep_tiled.exr")?;
+/// // ... modify samples ...
+/// exr::write_deep_with_layout("deep_tiled_out.exr", &deep, &layout)?;
+/// ```
+pub fn write_deep_with_layout<P: AsRef<Path>>(
+    path: P,
+    deep: &DeepData,
+    layout: &DeepExrLayout,
+) -> IoResult<()> {
+    let size = layout.data_window.size;
+    let (samples, channels) = crate::exr_deep::deepdata_to_deep_samples(deep, size.0, size.1)?;
+    crate::exr_deep::write_deep_exr_with_layout(path, &samples, &channels, layout)
+}
+
 // is_deep_exr and probe_deep_exr are re-exported from exr_deep module above
 
 // ============================================================================
@@ -1388,6 +2730,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_deep_exr_tiled_layout_roundtrip() {
+        use crate::deepdata::DeepData;
+        use vfx_core::TypeDesc;
+        use vfx_exr::math::{RoundingMode, Vec2};
+        use vfx_exr::meta::attribute::{IntegerBounds, LevelMode, TileDescription};
+
+        let (width, height) = (13usize, 9usize);
+        let pixels = width * height;
+        let deep = DeepData::new(pixels as i64, &[TypeDesc::FLOAT, TypeDesc::UINT32], &["Z", "id"]);
+        let counts: Vec<u32> = (0..pixels as u32).map(|i| i % 3).collect();
+        deep.set_all_samples(&counts);
+        for pixel in 0..pixels {
+            for sample in 0..counts[pixel] as usize {
+                deep.set_deep_value_f32(pixel as i64, 0, sample, pixel as f32 + sample as f32 * 0.25);
+                deep.set_deep_value_u32(pixel as i64, 1, sample, (pixel * 10 + sample) as u32);
+            }
+        }
+
+        for level_mode in [LevelMode::Singular, LevelMode::MipMap] {
+            let layout = DeepExrLayout {
+                data_window: IntegerBounds::new(Vec2(-5, 7), Vec2(width, height)),
+                display_window: IntegerBounds::new(Vec2(-10, 0), Vec2(32, 24)),
+                tiles: Some(TileDescription {
+                    tile_size: Vec2(4, 4),
+                    level_mode,
+                    rounding_mode: RoundingMode::Down,
+                }),
+                compression: vfx_exr::prelude::Compression::ZIP1,
+            };
+
+            let temp_path = std::env::temp_dir()
+                .join(format!("vfx_io_deep_layout_{:?}.exr", level_mode));
+            write_deep_with_layout(&temp_path, &deep, &layout).expect("write failed");
+            let (loaded, loaded_layout) = read_deep_with_layout(&temp_path).expect("read failed");
+            let _ = std::fs::remove_file(&temp_path);
+
+            assert_eq!(loaded_layout, layout);
+            assert_eq!(loaded.all_samples(), deep.all_samples());
+            let id = (0..loaded.channels()).find(|&c| loaded.channelname(c) == "id").unwrap();
+            let z = (0..loaded.channels()).find(|&c| loaded.channelname(c) == "Z").unwrap();
+            for pixel in 0..pixels {
+                for sample in 0..counts[pixel] as usize {
+                    assert_eq!(loaded.deep_value(pixel as i64, z, sample), deep.deep_value(pixel as i64, 0, sample));
+                    assert_eq!(
+                        loaded.deep_value_uint(pixel as i64, id, sample),
+                        deep.deep_value_uint(pixel as i64, 1, sample)
+                    );
+                }
+            }
+        }
+    }
+
     /// Tests Bug #9: num_layers and read_layer for multipart EXR.
     #[test]
     fn test_num_layers_and_read_layer() {
@@ -1493,4 +2888,361 @@ mod tests {
 
         let _ = std::fs::remove_file(&temp_path);
     }
+
+    #[test]
+    fn test_read_part_region_and_channels() {
+        let (width, height) = (24u32, 16u32);
+        let channel = |name: &str, offset: f32| ImageChannel {
+            name: name.to_string(),
+            kind: ChannelKind::Generic,
+            sample_type: ChannelSampleType::F32,
+            samples: ChannelSamples::F32(
+                (0..width * height)
+                    .map(|i| (i % width) as f32 + (i / width) as f32 * 100.0 + offset)
+                    .collect(),
+            ),
+            sampling: (1, 1),
+            quantize_linearly: false,
+        };
+
+        let layered = LayeredImage {
+            layers: vec![
+                ImageLayer {
+                    name: "beauty".to_string(),
+                    width,
+                    height,
+                    channels: vec![channel("R", 0.0), channel("G", 0.0), channel("B", 0.0)],
+                },
+                ImageLayer {
+                    name: "aovs".to_string(),
+                    width,
+                    height,
+                    channels: vec![channel("depth", 0.5), channel("id", 0.25), channel("mask", 0.75)],
+                },
+            ],
+            metadata: Metadata::default(),
+        };
+
+        let temp_path = std::env::temp_dir().join("vfx_io_exr_read_part_test.exr");
+        write_layers(&temp_path, &layered).expect("Write multipart failed");
+
+        let request = ExrPartRequest::layer(1)
+            .with_channels(["mask", "depth"])
+            .with_region(5, 3, 6, 4);
+        let part = read_part(&temp_path, &request).expect("read_part failed");
+
+        assert_eq!((part.x, part.y), (5, 3));
+        assert_eq!((part.layer.width, part.layer.height), (6, 4));
+        let names: Vec<&str> = part.layer.channels.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["depth", "mask"]);
+        // first pixel of the region, second row
+        assert_eq!(part.layer.channels[1].samples.get_f32(6), Some(5.0 + 400.0 + 0.75));
+
+        // the region is clipped to the data window
+        let clipped = read_part(&temp_path, &ExrPartRequest::layer(0).with_region(20, -2, 10, 4))
+            .expect("clipped read_part failed");
+        assert_eq!((clipped.x, clipped.y), (20, 0));
+        assert_eq!((clipped.layer.width, clipped.layer.height), (4, 2));
+
+        // missing channels and levels are errors
+        assert!(read_part(&temp_path, &ExrPartRequest::layer(1).with_channels(["Z"])).is_err());
+        assert!(read_layer(&temp_path, 0, 1).is_err());
+
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
+    #[test]
+    fn test_view_channel_names() {
+        let views = vec!["left".to_string(), "right".to_string()];
+
+        assert_eq!(view_of_channel("R", &views), Some("left"));
+        assert_eq!(view_of_channel("right.R", &views), Some("right"));
+        assert_eq!(view_of_channel("diffuse.left.G", &views), Some("left"));
+        assert_eq!(view_of_channel("diffuse.G", &views), None);
+
+        assert_eq!(channel_in_view("R", "left", &views), "R");
+        assert_eq!(channel_in_view("diffuse.R", "left", &views), "diffuse.left.R");
+        assert_eq!(channel_in_view("R", "right", &views), "right.R");
+        assert_eq!(channel_in_view("diffuse.R", "right", &views), "diffuse.right.R");
+        assert_eq!(channel_without_view("diffuse.right.R", "right"), "diffuse.R");
+    }
+
+    #[test]
+    fn test_stereo_views_roundtrip() {
+        let (width, height) = (8u32, 4u32);
+        // beauty RGB plus a prefixed AOV at twice the value
+        let view_layer = |value: f32| ImageLayer {
+            name: String::new(),
+            width,
+            height,
+            channels: [("R", value), ("G", value), ("B", value), ("diffuse.R", value * 2.0)]
+                .iter()
+                .map(|&(name, value)| ImageChannel {
+                    name: name.to_string(),
+                    kind: ChannelKind::Color,
+                    sample_type: ChannelSampleType::F32,
+                    samples: ChannelSamples::F32(vec![value; (width * height) as usize]),
+                    sampling: (1, 1),
+                    quantize_linearly: false,
+                })
+                .collect(),
+        };
+
+        let left = view_layer(0.25);
+        let right = view_layer(0.75);
+
+        for layout in [ViewLayout::SinglePart, ViewLayout::MultiPart] {
+            let temp_path =
+                std::env::temp_dir().join(format!("vfx_io_exr_views_{:?}.exr", layout));
+            write_views(&temp_path, &[("left", &left), ("right", &right)], layout)
+                .expect("Write views failed");
+
+            assert_eq!(list_views(&temp_path).unwrap(), ["left", "right"]);
+
+            for (view, value) in [("left", 0.25), ("right", 0.75)] {
+                let image = read_view(&temp_path, view).expect("read_view failed");
+                assert_eq!(image.layers.len(), 1);
+
+                let channels = &image.layers[0].channels;
+                let mut names: Vec<&str> = channels.iter().map(|c| c.name.as_str()).collect();
+                names.sort_unstable();
+                assert_eq!(names, ["B", "G", "R", "diffuse.R"], "{:?} {}", layout, view);
+
+                let sample = |name: &str| {
+                    channels.iter().find(|c| c.name == name).and_then(|c| c.samples.get_f32(0))
+                };
+                assert_eq!(sample("R"), Some(value), "{:?} {}", layout, view);
+                assert_eq!(sample("diffuse.R"), Some(value * 2.0), "{:?} {}", layout, view);
+            }
+
+            assert!(read_view(&temp_path, "center").is_err());
+            let _ = std::fs::remove_file(&temp_path);
+        }
+
+        // a single part holds all views at one size
+        let small = ImageLayer { width: 4, ..view_layer(0.5) };
+        let temp_path = std::env::temp_dir().join("vfx_io_exr_views_mismatch.exr");
+        assert!(write_views(&temp_path, &[("left", &left), ("right", &small)], ViewLayout::SinglePart).is_err());
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
+    #[test]
+    fn test_copy_parts_merge_and_split() {
+        let layer = |name: &str, value: f32| ImageLayer {
+            name: name.to_string(),
+            width: 16,
+            height: 8,
+            channels: ["R", "Z"]
+                .iter()
+                .map(|channel| ImageChannel {
+                    name: channel.to_string(),
+                    kind: ChannelKind::Generic,
+                    sample_type: ChannelSampleType::F32,
+                    samples: ChannelSamples::F32(vec![value; 16 * 8]),
+                    sampling: (1, 1),
+                    quantize_linearly: false,
+                })
+                .collect(),
+        };
+
+        let dir = std::env::temp_dir();
+        let first = dir.join("vfx_io_exr_copy_first.exr");
+        let second = dir.join("vfx_io_exr_copy_second.exr");
+        let merged = dir.join("vfx_io_exr_copy_merged.exr");
+        let split = dir.join("vfx_io_exr_copy_split.exr");
+
+        let single = |layer: ImageLayer| LayeredImage {
+            layers: vec![layer],
+            metadata: Metadata::default(),
+        };
+        write_layers(&first, &single(layer("a", 0.5))).unwrap();
+        write_layers(&second, &single(layer("b", 2.0))).unwrap();
+
+        copy_parts(
+            &[&first, &second],
+            &[
+                ExrPartCopy::new(0, 0).with_name("beauty"),
+                ExrPartCopy::new(1, 0).with_channel_name("Z", "depth"),
+            ],
+            &merged,
+        )
+        .expect("merge failed");
+
+        let infos = read_part_infos(&merged).unwrap();
+        assert_eq!(infos[0].name, "beauty");
+        assert_eq!(infos[1].name, "b");
+        assert_eq!(infos[1].channels, ["R", "depth"]);
+        let layered = read_layers(&merged).unwrap();
+        assert_eq!(layered.layers[1].channels[1].name, "depth");
+        assert_eq!(layered.layers[1].channels[1].samples.get_f32(3), Some(2.0));
+
+        copy_parts(&[&merged], &[ExrPartCopy::new(0, 1)], &split).expect("split failed");
+        let layered = read_layers(&split).unwrap();
+        assert_eq!(layered.layers.len(), 1);
+        assert_eq!(layered.layers[0].channels[0].samples.get_f32(0), Some(2.0));
+
+        for path in [first, second, merged, split] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[test]
+    fn test_edit_header_attributes() {
+        let layered = LayeredImage {
+            layers: vec![ImageLayer {
+                name: "beauty".to_string(),
+                width: 8,
+                height: 8,
+                channels: vec![ImageChannel {
+                    name: "Y".to_string(),
+                    kind: ChannelKind::Generic,
+                    sample_type: ChannelSampleType::F32,
+                    samples: ChannelSamples::F32((0..64).map(|i| i as f32).collect()),
+                    sampling: (1, 1),
+                    quantize_linearly: false,
+                }],
+            }],
+            metadata: Metadata::default(),
+        };
+
+        let temp_path = std::env::temp_dir().join("vfx_io_exr_edit_header_test.exr");
+        write_layers(&temp_path, &layered).unwrap();
+
+        let edit = ExrHeaderEdit::new()
+            .set("owner", AttrValue::Str("comp".into()))
+            .set("shot", AttrValue::Str("sh010".into()))
+            .set("frame", AttrValue::Int(1001))
+            .set("chromaticities", AttrValue::Str("0.64,0.33,0.3,0.6,0.15,0.06,0.3127,0.329".into()));
+        edit_header(&temp_path, &edit).expect("edit failed");
+
+        let meta = vfx_exr::meta::MetaData::read_from_file(&temp_path, false).unwrap();
+        let header = &meta.headers[0];
+        assert_eq!(header.own_attributes.owner.as_ref().map(|t| t.to_string()).as_deref(), Some("comp"));
+        assert_eq!(header.shared_attributes.chromaticities.map(|c| c.white.0), Some(0.3127));
+
+        let update = edit_header(&temp_path, &ExrHeaderEdit::new().remove("shot").remove("owner")).unwrap();
+        assert_eq!(update, HeaderUpdate::InPlace);
+
+        let part = read_part(&temp_path, &ExrPartRequest::layer(0)).unwrap();
+        assert_eq!(part.layer.channels[0].samples.get_f32(63), Some(63.0));
+
+        assert!(edit_header(&temp_path, &ExrHeaderEdit::new().remove("channels")).is_err());
+        assert!(edit_header(&temp_path, &ExrHeaderEdit::new().set("expTime", AttrValue::Str("fast".into()))).is_err());
+
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
+    #[test]
+    fn test_recover_truncated_file() {
+        let layered = LayeredImage {
+            layers: vec![ImageLayer {
+                name: "beauty".to_string(),
+                width: 16,
+                height: 64,
+                channels: vec![ImageChannel {
+                    name: "Y".to_string(),
+                    kind: ChannelKind::Generic,
+                    sample_type: ChannelSampleType::F32,
+                    samples: ChannelSamples::F32((0..1024).map(|i| i as f32).collect()),
+                    sampling: (1, 1),
+                    quantize_linearly: false,
+                }],
+            }],
+            metadata: Metadata::default(),
+        };
+
+        let temp_path = std::env::temp_dir().join("vfx_io_exr_recover_test.exr");
+        write_layers(&temp_path, &layered).unwrap();
+        assert!(check(&temp_path).unwrap().is_intact());
+
+        let bytes = std::fs::read(&temp_path).unwrap();
+        std::fs::write(&temp_path, &bytes[..bytes.len() - 16]).unwrap();
+
+        // the last chunk in the file is cut off
+        let report = check(&temp_path).unwrap();
+        assert_eq!(report.lost_blocks.len(), 1);
+        let lost = report.lost_blocks[0];
+
+        let (recovered, report) = recover_layers(&temp_path, -1.0).unwrap();
+        assert_eq!(report.lost_blocks, [lost]);
+        let samples = &recovered.layers[0].channels[0].samples;
+        for y in 0..64 {
+            let expected = if (lost.y..lost.y + lost.height).contains(&y) { -1.0 } else { (y * 16) as f32 };
+            assert_eq!(samples.get_f32(y * 16), Some(expected));
+        }
+
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
+    /// Luminance weights of Rec. 709 primaries match the well known coefficients.
+    #[test]
+    fn test_luminance_weights_rec709() {
+        let weights = exr_luminance_weights(None);
+        assert!((weights[0] - 0.2126).abs() < 1e-3);
+        assert!((weights[1] - 0.7152).abs() < 1e-3);
+        assert!((weights[2] - 0.0722).abs() < 1e-3);
+    }
+
+    /// Uniform subsampled chroma reconstructs the original color.
+    #[test]
+    fn test_luminance_chroma_uniform() {
+        let weights = exr_luminance_weights(None);
+        let (r, g, b) = (0.5f32, 0.25f32, 0.125f32);
+        let y = round_to_f16(r * weights[0] + g * weights[1] + b * weights[2]);
+        let (ry, by) = (round_to_f16((r - y) / y), round_to_f16((b - y) / y));
+
+        let channel = |name: &str, value: f32, sampling: (usize, usize)| {
+            let count = (8 / sampling.0) * (6 / sampling.1);
+            ImageChannel {
+                name: name.to_string(),
+                kind: ChannelKind::Generic,
+                sample_type: ChannelSampleType::F16,
+                samples: ChannelSamples::F32(vec![value; count]),
+                sampling,
+                quantize_linearly: false,
+            }
+        };
+
+        let layer = ImageLayer {
+            name: "yc".to_string(),
+            width: 8,
+            height: 6,
+            channels: vec![
+                channel("BY", by, (2, 2)),
+                channel("RY", ry, (2, 2)),
+                channel("Y", y, (1, 1)),
+            ],
+        };
+
+        let image = luminance_chroma_to_rgba(&layer, weights).expect("reconstruct");
+        assert_eq!(image.channels, 4);
+
+        for pixel in image.to_f32().chunks_exact(4) {
+            assert!((pixel[0] - r).abs() < 2e-3, "{:?}", pixel);
+            assert!((pixel[1] - g).abs() < 2e-3, "{:?}", pixel);
+            assert!((pixel[2] - b).abs() < 2e-3, "{:?}", pixel);
+            assert_eq!(pixel[3], 1.0);
+        }
+    }
+
+    /// Files storing `Y`, `RY` and `BY` channels are read as RGBA.
+    #[test]
+    fn test_read_luminance_chroma_file() {
+        let path = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../test/assets-exr/valid/openexr/LuminanceChroma/Flowers.exr");
+
+        let image = read(&path).expect("read luminance/chroma exr");
+        assert_eq!(image.channels, 4);
+
+        let pixels = image.to_f32();
+        assert_eq!(pixels.len(), (image.width * image.height * 4) as usize);
+        assert!(pixels.iter().all(|value| value.is_finite()));
+
+        // the image is colorful, so the reconstructed channels must differ
+        let differing = pixels.chunks_exact(4).filter(|p| p[0] != p[1] || p[1] != p[2]).count();
+        assert!(differing > 0);
+
+        let layer = read_layer(&path, 0, 0).expect("read layer");
+        assert_eq!(layer.to_f32(), pixels);
+    }
 }
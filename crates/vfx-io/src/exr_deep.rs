@@ -7,6 +7,8 @@ use crate::deepdata::DeepData;
 use half::f16;
 use std::path::Path;
 use vfx_core::TypeDesc;
+use vfx_exr::meta::attribute::{ChannelList, IntegerBounds, TileDescription};
+use vfx_exr::meta::BlockDescription;
 
 // Re-export vfx-exr deep types for direct use
 pub use vfx_exr::image::deep::{
@@ -272,6 +274,45 @@ impl DeepSamples {
         max
     }
 
+    /// Resamples to a new size, copying all samples of the nearest source pixel.
+    pub fn resample_nearest(&self, width: usize, height: usize) -> Self {
+        let sources: Vec<usize> = (0..width * height)
+            .map(|idx| {
+                let x = (idx % width) * self.width / width.max(1);
+                let y = (idx / width) * self.height / height.max(1);
+                y * self.width + x
+            })
+            .collect();
+
+        let mut sample_offsets = Vec::with_capacity(sources.len());
+        let mut total = 0u32;
+        for &src in &sources {
+            total += self.sample_count_at_index(src) as u32;
+            sample_offsets.push(total);
+        }
+
+        fn gather<T: Copy>(values: &[T], ranges: &[(usize, usize)]) -> Vec<T> {
+            ranges.iter().flat_map(|&(start, end)| values[start..end].iter().copied()).collect()
+        }
+        let ranges: Vec<(usize, usize)> = sources.iter().map(|&src| self.sample_range(src)).collect();
+        let channels = self
+            .channels
+            .iter()
+            .map(|ch| match ch {
+                DeepChannelData::F16(v) => DeepChannelData::F16(gather(v, &ranges)),
+                DeepChannelData::F32(v) => DeepChannelData::F32(gather(v, &ranges)),
+                DeepChannelData::U32(v) => DeepChannelData::U32(gather(v, &ranges)),
+            })
+            .collect();
+
+        Self {
+            sample_offsets,
+            channels,
+            width,
+            height,
+        }
+    }
+
     /// Validate that channel data lengths match total_samples.
     pub fn validate(&self) -> IoResult<()> {
         let total = self.total_samples();
@@ -307,32 +348,112 @@ pub struct DeepChannelDesc {
 // Read/Write and conversions
 // ============================================================================
 
+/// Block layout and windows of a deep EXR layer.
+///
+/// Returned by [`read_deep_exr_with_layout`] and accepted by
+/// [`write_deep_exr_with_layout`], so that a read → modify → write round trip
+/// keeps tiling, resolution levels and windows of the original file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeepExrLayout {
+    /// Position and size of the pixels stored in the file.
+    /// The size must match the dimensions of the written samples.
+    pub data_window: IntegerBounds,
+    /// Position and size of the visible image area.
+    pub display_window: IntegerBounds,
+    /// Tile size and resolution levels, or `None` for scan lines.
+    pub tiles: Option<TileDescription>,
+    /// Compression method; deep data supports Uncompressed, RLE and ZIP1.
+    pub compression: vfx_exr::meta::attribute::Compression,
+}
+
+impl DeepExrLayout {
+    /// Scan line layout with both windows at the origin.
+    pub fn scan_lines(
+        width: usize,
+        height: usize,
+        compression: vfx_exr::meta::attribute::Compression,
+    ) -> Self {
+        let window = IntegerBounds::from_dimensions((width, height));
+        Self {
+            data_window: window,
+            display_window: window,
+            tiles: None,
+            compression,
+        }
+    }
+
+    /// Tiled layout with both windows at the origin.
+    pub fn tiles(
+        width: usize,
+        height: usize,
+        tiles: TileDescription,
+        compression: vfx_exr::meta::attribute::Compression,
+    ) -> Self {
+        Self {
+            tiles: Some(tiles),
+            ..Self::scan_lines(width, height, compression)
+        }
+    }
+}
+
 /// Reads a deep EXR file.
 ///
 /// Returns deep samples and channel descriptions.
 pub fn read_deep_exr<P: AsRef<Path>>(path: P) -> IoResult<(DeepSamples, Vec<DeepChannelDesc>)> {
+    let (samples, channels, _) = read_deep_exr_with_layout(path)?;
+    Ok((samples, channels))
+}
+
+/// Reads a deep EXR file together with its block layout and windows.
+///
+/// Only the full resolution level is returned for mip and rip mapped files.
+pub fn read_deep_exr_with_layout<P: AsRef<Path>>(
+    path: P,
+) -> IoResult<(DeepSamples, Vec<DeepChannelDesc>, DeepExrLayout)> {
     use vfx_exr::image::read::deep::read_first_deep_layer_from_file;
-    
+
     let image = read_first_deep_layer_from_file(path.as_ref())
         .map_err(|e| IoError::DecodeError(format!("Deep EXR read failed: {}", e)))?;
-    
+
+    // The image only knows the tile size; the level mode lives in the header.
+    let meta = vfx_exr::meta::MetaData::read_from_file(path.as_ref(), false)
+        .map_err(|e| IoError::DecodeError(format!("EXR probe failed: {}", e)))?;
+    deep_exr_from_image(&image, &meta)
+}
+
+/// Reads a deep EXR file held in memory.
+///
+/// Returns deep samples and channel descriptions.
+pub fn read_deep_exr_from_memory(data: &[u8]) -> IoResult<(DeepSamples, Vec<DeepChannelDesc>)> {
+    use std::io::Cursor;
+
+    let image = read_exr_deep_builder()
+        .all_channels()
+        .first_valid_layer()
+        .all_attributes()
+        .from_buffered(Cursor::new(data))
+        .map_err(|e| IoError::DecodeError(format!("Deep EXR read failed: {}", e)))?;
+    let meta = vfx_exr::meta::MetaData::read_from_buffered(Cursor::new(data), false)
+        .map_err(|e| IoError::DecodeError(format!("EXR probe failed: {}", e)))?;
+    let (samples, channels, _) = deep_exr_from_image(&image, &meta)?;
+    Ok((samples, channels))
+}
+
+/// Extracts samples, channel descriptions and layout from a decoded deep image.
+fn deep_exr_from_image(
+    image: &DeepImage,
+    meta: &vfx_exr::meta::MetaData,
+) -> IoResult<(DeepSamples, Vec<DeepChannelDesc>, DeepExrLayout)> {
+    let header = meta
+        .headers
+        .iter()
+        .find(|h| h.deep)
+        .ok_or_else(|| IoError::DecodeError("EXR has no deep layers".into()))?;
+
     let layer = &image.layer_data;
     let exr_samples = &layer.channel_data.list[0].sample_data;
-    
-    // Convert vfx-exr DeepSamples to our format
-    let mut samples = DeepSamples::new(exr_samples.width, exr_samples.height);
-    samples.sample_offsets = exr_samples.sample_offsets.clone();
-    
-    // Convert channels
-    for exr_channel in &exr_samples.channels {
-        let channel = match exr_channel {
-            ExrDeepChannelData::F16(v) => DeepChannelData::F16(v.clone()),
-            ExrDeepChannelData::F32(v) => DeepChannelData::F32(v.clone()),
-            ExrDeepChannelData::U32(v) => DeepChannelData::U32(v.clone()),
-        };
-        samples.channels.push(channel);
-    }
-    
+    let samples = from_exr_samples(exr_samples);
+
     // Build channel descriptions
     let channels: Vec<DeepChannelDesc> = layer.channel_data.list.iter()
         .map(|ch| DeepChannelDesc {
@@ -345,8 +466,18 @@ pub fn read_deep_exr<P: AsRef<Path>>(path: P) -> IoResult<(DeepSamples, Vec<Deep
             },
         })
         .collect();
-    
-    Ok((samples, channels))
+
+    let layout = DeepExrLayout {
+        data_window: header.data_window(),
+        display_window: header.shared_attributes.display_window,
+        tiles: match header.blocks {
+            BlockDescription::Tiles(tiles) => Some(tiles),
+            BlockDescription::ScanLines => None,
+        },
+        compression: header.compression,
+    };
+
+    Ok((samples, channels, layout))
 }
 
 /// Converts DeepSamples (SoA) into DeepData (AoS).
@@ -569,9 +700,27 @@ pub fn write_deep_exr<P: AsRef<Path>>(
     channels: &[DeepChannelDesc],
     compression: vfx_exr::meta::attribute::Compression,
 ) -> IoResult<()> {
-    use vfx_exr::meta::attribute::{ChannelDescription, ChannelList, SampleType as ExrSampleType, Text};
-    use vfx_exr::image::write::deep::write_deep_scanlines_to_file;
-    use vfx_exr::prelude::SmallVec;
+    let layout = DeepExrLayout::scan_lines(samples.width, samples.height, compression);
+    write_deep_exr_with_layout(path, samples, channels, &layout)
+}
+
+/// Writes deep samples to an EXR file with the given block layout and windows.
+///
+/// The size of `layout.data_window` must match the sample dimensions.
+/// Mip and rip levels are generated from the full resolution samples by
+/// copying the samples of the nearest source pixel, as deep samples cannot be
+/// filtered without knowing how they compose.
+pub fn write_deep_exr_with_layout<P: AsRef<Path>>(
+    path: P,
+    samples: &DeepSamples,
+    channels: &[DeepChannelDesc],
+    layout: &DeepExrLayout,
+) -> IoResult<()> {
+    use vfx_exr::image::write::deep::write_deep_levels_to_file;
+    use vfx_exr::image::{Levels, RipMaps};
+    use vfx_exr::meta::attribute::{LevelMode, LineOrder, Text};
+    use vfx_exr::math::Vec2;
+    use vfx_exr::meta::{compute_level_count, mip_map_levels, rip_map_levels};
 
     if channels.len() != samples.channels.len() {
         return Err(IoError::InvalidFile(format!(
@@ -580,6 +729,68 @@ pub fn write_deep_exr<P: AsRef<Path>>(
             samples.channels.len()
         )));
     }
+    let size = layout.data_window.size;
+    if size != Vec2(samples.width, samples.height) {
+        return Err(IoError::InvalidFile(format!(
+            "data window {}x{} != sample dimensions {}x{}",
+            size.0, size.1, samples.width, samples.height
+        )));
+    }
+    if !layout.compression.supports_deep_data() {
+        return Err(IoError::UnsupportedFeature(format!(
+            "Deep EXR does not support {} compression",
+            layout.compression
+        )));
+    }
+
+    let blocks = match layout.tiles {
+        Some(tiles) => BlockDescription::Tiles(tiles),
+        None => BlockDescription::ScanLines,
+    };
+    let header = vfx_exr::meta::header::Header::new(
+        Text::from("deep"),
+        size,
+        exr_channel_list(channels)?.list,
+    )
+    .with_encoding(layout.compression, blocks, LineOrder::Increasing)
+    .with_position(layout.data_window.position)
+    .with_display_window(layout.display_window);
+
+    let level = |level_size: Vec2<usize>| {
+        to_exr_samples(&samples.resample_nearest(level_size.0, level_size.1))
+    };
+    let levels = match layout.tiles.map(|tiles| (tiles.level_mode, tiles.rounding_mode)) {
+        None | Some((LevelMode::Singular, _)) => Levels::Singular(to_exr_samples(samples)),
+        Some((LevelMode::MipMap, rounding_mode)) => Levels::Mip {
+            rounding_mode,
+            level_data: mip_map_levels(rounding_mode, size)
+                .map(|(_, level_size)| level(level_size))
+                .collect(),
+        },
+        Some((LevelMode::RipMap, rounding_mode)) => Levels::Rip {
+            rounding_mode,
+            level_data: RipMaps {
+                map_data: rip_map_levels(rounding_mode, size)
+                    .map(|(_, level_size)| level(level_size))
+                    .collect(),
+                level_count: Vec2(
+                    compute_level_count(rounding_mode, size.0),
+                    compute_level_count(rounding_mode, size.1),
+                ),
+            },
+        },
+    };
+
+    write_deep_levels_to_file(path, header, &levels)
+        .map_err(|e| IoError::EncodeError(format!("Deep EXR write failed: {}", e)))?;
+
+    Ok(())
+}
+
+/// Builds the vfx-exr channel list for the given deep channel descriptions.
+fn exr_channel_list(channels: &[DeepChannelDesc]) -> IoResult<ChannelList> {
+    use vfx_exr::meta::attribute::{ChannelDescription, SampleType as ExrSampleType, Text};
+    use vfx_exr::prelude::SmallVec;
 
     let mut list: SmallVec<[ChannelDescription; 5]> = SmallVec::new();
     for ch in channels {
@@ -600,28 +811,40 @@ pub fn write_deep_exr<P: AsRef<Path>>(
             sampling: vfx_exr::math::Vec2(1, 1),
         });
     }
-    let channel_list = ChannelList::new(list);
+    Ok(ChannelList::new(list))
+}
 
-    // Convert vfx-io DeepSamples to vfx-exr DeepSamples for writing.
-    let mut exr_samples = vfx_exr::image::deep::DeepSamples {
+/// Converts vfx-io DeepSamples to vfx-exr DeepSamples for writing.
+fn to_exr_samples(samples: &DeepSamples) -> ExrDeepSamples {
+    ExrDeepSamples {
         sample_offsets: samples.sample_offsets.clone(),
-        channels: Vec::with_capacity(samples.channels.len()),
+        channels: samples
+            .channels
+            .iter()
+            .map(|ch| match ch {
+                DeepChannelData::F16(v) => ExrDeepChannelData::F16(v.clone()),
+                DeepChannelData::F32(v) => ExrDeepChannelData::F32(v.clone()),
+                DeepChannelData::U32(v) => ExrDeepChannelData::U32(v.clone()),
+            })
+            .collect(),
         width: samples.width,
         height: samples.height,
-    };
-    for ch in &samples.channels {
-        let converted = match ch {
-            DeepChannelData::F16(v) => ExrDeepChannelData::F16(v.clone()),
-            DeepChannelData::F32(v) => ExrDeepChannelData::F32(v.clone()),
-            DeepChannelData::U32(v) => ExrDeepChannelData::U32(v.clone()),
-        };
-        exr_samples.channels.push(converted);
     }
+}
 
-    write_deep_scanlines_to_file(path, &exr_samples, &channel_list, compression)
-        .map_err(|e| IoError::EncodeError(format!("Deep EXR write failed: {}", e)))?;
-
-    Ok(())
+/// Converts vfx-exr DeepSamples to vfx-io DeepSamples.
+fn from_exr_samples(exr_samples: &ExrDeepSamples) -> DeepSamples {
+    let mut samples = DeepSamples::new(exr_samples.width, exr_samples.height);
+    samples.sample_offsets = exr_samples.sample_offsets.clone();
+    for exr_channel in &exr_samples.channels {
+        let channel = match exr_channel {
+            ExrDeepChannelData::F16(v) => DeepChannelData::F16(v.clone()),
+            ExrDeepChannelData::F32(v) => DeepChannelData::F32(v.clone()),
+            ExrDeepChannelData::U32(v) => DeepChannelData::U32(v.clone()),
+        };
+        samples.channels.push(channel);
+    }
+    samples
 }
 
 // ============================================================================
@@ -704,6 +927,21 @@ mod tests {
         assert_eq!(samples.max_samples_per_pixel(), 3);
     }
 
+    #[test]
+    fn test_resample_nearest() {
+        let mut samples = DeepSamples::new(2, 2);
+        samples.set_cumulative_counts(vec![2, 2, 5, 6]).unwrap();
+        samples.channels.push(DeepChannelData::U32(vec![0, 1, 2, 3, 4, 5]));
+
+        let half = samples.resample_nearest(1, 1);
+        assert_eq!(half.sample_offsets, vec![2]);
+        assert_eq!(half.channels[0], DeepChannelData::U32(vec![0, 1]));
+
+        let wide = samples.resample_nearest(4, 1);
+        assert_eq!(wide.sample_offsets, vec![2, 4, 4, 4]);
+        assert_eq!(wide.channels[0], DeepChannelData::U32(vec![0, 1, 0, 1]));
+    }
+
     #[test]
     fn test_deep_channel_data() {
         let mut channel = DeepChannelData::F32(vec![1.0, 2.0, 3.0]);
@@ -39,6 +39,13 @@
 //! **Note**: Gain Map HDR (SDR base + gain map for adaptive HDR, iPhone/ISO 21496-1)
 //! is not currently supported - only NCLX metadata is extracted.
 //!
+//! # Metadata
+//!
+//! EXIF and XMP items are read into and written from `Exif:*`, `GPS:*` and
+//! `XMP:*` attributes (see [`crate::attrs::exif`] and [`crate::attrs::xmp`]).
+//! An ICC colour profile is kept as `ICCProfile` bytes and written back as a
+//! `prof` property (see [`crate::icc`]).
+//!
 //! # Example
 //!
 //! ```ignore
@@ -59,7 +66,7 @@
 use libheif_rs::{
     ColorSpace, HeifContext, LibHeif, RgbChroma,
     CompressionFormat, EncoderQuality, Image as HeifImage,
-    ColorProfileNCLX,
+    ColorProfileNCLX, ColorProfileRaw, ColorProfileType,
 };
 
 use crate::{IoError, IoResult};
@@ -70,6 +77,13 @@ use crate::ImageData;
 #[cfg(feature = "heif")]
 use crate::{PixelData, PixelFormat, Metadata};
 
+#[cfg(feature = "heif")]
+use crate::attrs::{exif, xmp};
+#[cfg(feature = "heif")]
+use crate::icc;
+#[cfg(feature = "heif")]
+use crate::{AttrValue, Attrs};
+
 /// NCLX transfer characteristics (CICP / ITU-T H.273).
 ///
 /// Defines the electro-optical transfer function (EOTF) for the image.
@@ -277,7 +291,8 @@ pub fn read_heif<P: AsRef<Path>>(path: P) -> IoResult<(ImageData, Option<HdrMeta
     }
 
     let mut metadata = Metadata::default();
-    
+    read_heif_metadata(&handle, &mut metadata.attrs);
+
     // Set colorspace hint based on HDR metadata
     if let Some(ref hdr) = hdr_meta {
         let cs_name = match (&hdr.primaries, &hdr.transfer) {
@@ -305,6 +320,36 @@ pub fn read_heif<P: AsRef<Path>>(path: P) -> IoResult<(ImageData, Option<HdrMeta
     Ok((image_data, hdr_meta))
 }
 
+/// Reads the EXIF and XMP metadata items and the ICC profile of an image
+/// into attributes.
+#[cfg(feature = "heif")]
+fn read_heif_metadata(handle: &libheif_rs::ImageHandle, attrs: &mut Attrs) {
+    if let Some(profile) = handle.color_profile_raw() {
+        icc::set_profile(attrs, profile.data);
+    }
+    for id in handle.metadata_block_ids(b"Exif") {
+        let Ok(data) = handle.metadata(id) else { continue };
+        // Exif items start with a 4-byte offset to the TIFF header
+        let Some(skip) = data.get(..4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]])) else {
+            continue;
+        };
+        if let Some(tiff) = data.get(4 + skip as usize..) {
+            attrs.set("ExifSize", AttrValue::UInt(tiff.len() as u32));
+            let _ = exif::read_exif(tiff, attrs);
+        }
+    }
+    for id in handle.metadata_block_ids(b"mime") {
+        if handle.metadata_content_type(id) != Some("application/rdf+xml") {
+            continue;
+        }
+        let Ok(data) = handle.metadata(id) else { continue };
+        attrs.set("XMPSize", AttrValue::UInt(data.len() as u32));
+        if let Ok(packet) = std::str::from_utf8(&data) {
+            let _ = xmp::read_xmp(packet, attrs);
+        }
+    }
+}
+
 /// Extract NCLX color profile metadata from image handle.
 #[cfg(feature = "heif")]
 fn extract_nclx_metadata(handle: &libheif_rs::ImageHandle, bit_depth: u8) -> Option<HdrMetadata> {
@@ -488,6 +533,12 @@ pub fn write_heif<P: AsRef<Path>>(
         }
     }
 
+    if let Some(profile) = icc::profile(&image.metadata.attrs) {
+        let raw = ColorProfileRaw::new(ColorProfileType::PROF, profile.to_vec());
+        heif_image.set_color_profile_raw(&raw)
+            .map_err(|e| IoError::EncodeError(format!("Failed to set ICC profile: {}", e)))?;
+    }
+
     // Create context and encode
     let mut ctx = HeifContext::new()
         .map_err(|e| IoError::EncodeError(format!("Failed to create context: {}", e)))?;
@@ -500,9 +551,19 @@ pub fn write_heif<P: AsRef<Path>>(
     encoder.set_quality(EncoderQuality::Lossy(85))
         .map_err(|e| IoError::EncodeError(format!("Failed to set quality: {}", e)))?;
 
-    ctx.encode_image(&heif_image, &mut encoder, None)
+    let handle = ctx.encode_image(&heif_image, &mut encoder, None)
         .map_err(|e| IoError::EncodeError(format!("HEIF encode error: {}", e)))?;
 
+    // libheif adds the Exif item's TIFF header offset itself
+    if let Some(data) = exif::write_exif(&image.metadata.attrs) {
+        ctx.add_exif_metadata(&handle, &data)
+            .map_err(|e| IoError::EncodeError(format!("HEIF EXIF error: {}", e)))?;
+    }
+    if let Some(packet) = xmp::write_xmp(&image.metadata.attrs) {
+        ctx.add_xmp_metadata(&handle, packet.as_bytes())
+            .map_err(|e| IoError::EncodeError(format!("HEIF XMP error: {}", e)))?;
+    }
+
     ctx.write_to_file(path_str)
         .map_err(|e| IoError::EncodeError(format!("HEIF write error: {}", e)))?;
 
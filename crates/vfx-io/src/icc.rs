@@ -0,0 +1,324 @@
+//! Embedded ICC profiles.
+//!
+//! Readers keep an embedded profile as raw bytes under the `ICCProfile`
+//! attribute (the OIIO name), next to `ICCProfileSize` and `ICCColorSpace`
+//! summaries. Writers embed the same bytes again, so a profile survives a
+//! read/write round-trip without being interpreted:
+//!
+//! | Format | Container |
+//! |--------|-----------|
+//! | JPEG | `ICC_PROFILE` APP2 segments, split at 65519 bytes |
+//! | PNG | `iCCP` chunk |
+//! | TIFF | tag 34675 |
+//! | HEIF | `prof`/`rICC` colour property |
+//! | WebP | `ICCP` chunk |
+//! | PSD | image resource 1039 |
+//!
+//! With the `icc` feature, profiles can also be used through `vfx-icc`:
+//! [`embed_profile`] stores a [`Profile`] for writing, and
+//! [`convert_to_working`] moves pixels from the embedded profile into a
+//! working space. JPEG, PNG and TIFF readers do the latter on read when
+//! their options carry an [`IccConvert`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! use vfx_io::icc::{self, IccConvert, StandardProfile};
+//! use vfx_io::jpeg::{JpegReader, JpegReaderOptions};
+//! use vfx_io::FormatReader;
+//!
+//! let mut options = JpegReaderOptions::default();
+//! options.icc_convert = Some(IccConvert::new(StandardProfile::AcesAp1));
+//! let image = JpegReader::with_options(options).read("photo.jpg")?;
+//!
+//! // Pixels are now ACEScg floats and carry the ACEScg profile
+//! assert!(icc::profile(&image.metadata.attrs).is_some());
+//! ```
+
+use crate::{AttrValue, Attrs};
+
+#[cfg(feature = "icc")]
+use crate::{ImageData, IoError, IoResult, PixelData, PixelFormat};
+
+#[cfg(feature = "icc")]
+pub use vfx_icc::{Intent, Profile, StandardProfile};
+
+/// Attribute holding the raw profile bytes.
+pub const ICC_PROFILE: &str = "ICCProfile";
+
+/// Signature opening every JPEG APP2 profile segment.
+pub(crate) const JPEG_SIGNATURE: &[u8; 12] = b"ICC_PROFILE\0";
+
+/// Profile bytes per APP2 segment: 65533 bytes of payload, less the
+/// signature and the two sequence bytes.
+const JPEG_CHUNK_SIZE: usize = 65533 - 14;
+
+/// Photoshop image resource ID for an ICC profile.
+#[cfg(feature = "psd")]
+pub(crate) const PSD_RESOURCE_ID: u16 = 1039;
+
+// ============================================================================
+// Attributes
+// ============================================================================
+
+/// Stores profile bytes and their size and colour space summaries.
+pub fn set_profile(attrs: &mut Attrs, data: Vec<u8>) {
+    attrs.set("ICCProfileSize", AttrValue::UInt(data.len() as u32));
+    if let Some(space) = data.get(16..20).and_then(|s| std::str::from_utf8(s).ok()) {
+        attrs.set("ICCColorSpace", AttrValue::Str(space.trim().to_string()));
+    }
+    attrs.set(ICC_PROFILE, AttrValue::Bytes(data));
+}
+
+/// Returns the embedded profile bytes, if any.
+pub fn profile(attrs: &Attrs) -> Option<&[u8]> {
+    attrs.get_bytes(ICC_PROFILE).filter(|data| !data.is_empty())
+}
+
+// ============================================================================
+// Container helpers
+// ============================================================================
+
+/// Splits a profile into JPEG APP2 segment payloads.
+///
+/// Returns `None` when the profile needs more than 255 segments.
+pub(crate) fn jpeg_segments(profile: &[u8]) -> Option<Vec<Vec<u8>>> {
+    let chunks: Vec<&[u8]> = profile.chunks(JPEG_CHUNK_SIZE).collect();
+    let total = u8::try_from(chunks.len()).ok()?;
+    Some(
+        chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                let mut segment = Vec::with_capacity(14 + chunk.len());
+                segment.extend_from_slice(JPEG_SIGNATURE);
+                segment.push(i as u8 + 1);
+                segment.push(total);
+                segment.extend_from_slice(chunk);
+                segment
+            })
+            .collect(),
+    )
+}
+
+/// Joins APP2 payloads (sequence number, total, data) in sequence order.
+pub(crate) fn join_jpeg_segments(chunks: &mut [(u8, u8, Vec<u8>)]) -> Vec<u8> {
+    chunks.sort_by_key(|(num, _, _)| *num);
+    chunks.iter().flat_map(|(_, _, data)| data.iter().copied()).collect()
+}
+
+/// Finds the profile in a PSD image resource section.
+#[cfg(feature = "psd")]
+pub(crate) fn psd_profile(resources: &[u8]) -> Option<&[u8]> {
+    let mut pos = 0;
+    while pos + 12 <= resources.len() {
+        if &resources[pos..pos + 4] != b"8BIM" {
+            return None;
+        }
+        let id = u16::from_be_bytes([resources[pos + 4], resources[pos + 5]]);
+        // Pascal name padded to an even length
+        let name_len = resources[pos + 6] as usize;
+        pos += 6 + (name_len + 2) / 2 * 2;
+        let size_bytes = resources.get(pos..pos + 4)?;
+        let size = u32::from_be_bytes(size_bytes.try_into().ok()?) as usize;
+        pos += 4;
+        let data = resources.get(pos..pos + size)?;
+        if id == PSD_RESOURCE_ID {
+            return Some(data);
+        }
+        pos += size + size % 2;
+    }
+    None
+}
+
+/// Builds a PSD image resource block holding a profile.
+#[cfg(feature = "psd")]
+pub(crate) fn psd_resource(profile: &[u8]) -> Vec<u8> {
+    let mut block = Vec::with_capacity(12 + profile.len() + 1);
+    block.extend_from_slice(b"8BIM");
+    block.extend_from_slice(&PSD_RESOURCE_ID.to_be_bytes());
+    block.extend_from_slice(&[0, 0]); // Empty name
+    block.extend_from_slice(&(profile.len() as u32).to_be_bytes());
+    block.extend_from_slice(profile);
+    if profile.len() % 2 == 1 {
+        block.push(0);
+    }
+    block
+}
+
+// ============================================================================
+// Colour management (vfx-icc)
+// ============================================================================
+
+/// Working-space conversion applied by readers.
+///
+/// Set on [`JpegReaderOptions`](crate::jpeg::JpegReaderOptions),
+/// [`PngReaderOptions`](crate::png::PngReaderOptions) or
+/// [`TiffReaderOptions`](crate::tiff::TiffReaderOptions).
+#[cfg(feature = "icc")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IccConvert {
+    /// Working space the pixels are converted into.
+    pub target: StandardProfile,
+    /// Rendering intent. Default: perceptual.
+    pub intent: Intent,
+}
+
+#[cfg(feature = "icc")]
+impl IccConvert {
+    /// Converts into `target` with the default intent.
+    pub fn new(target: StandardProfile) -> Self {
+        Self {
+            target,
+            intent: Intent::default(),
+        }
+    }
+}
+
+/// Stores a profile in metadata so writers embed it.
+#[cfg(feature = "icc")]
+pub fn embed_profile(attrs: &mut Attrs, profile: &Profile) -> IoResult<()> {
+    let data = profile
+        .to_icc()
+        .map_err(|e| IoError::EncodeError(format!("ICC profile: {e}")))?;
+    set_profile(attrs, data);
+    Ok(())
+}
+
+/// Converts pixels from the embedded profile into `target`.
+///
+/// The image becomes 32-bit float and carries `target` as its new
+/// profile; alpha and extra channels are left alone. Untagged images,
+/// images with fewer than three channels and non-RGB profiles are left
+/// unchanged, and `Ok(false)` is returned.
+#[cfg(feature = "icc")]
+pub fn convert_to_working(
+    image: &mut ImageData,
+    target: &Profile,
+    intent: Intent,
+) -> IoResult<bool> {
+    let Some(data) = profile(&image.metadata.attrs) else {
+        return Ok(false);
+    };
+    let source = Profile::from_icc(data)
+        .map_err(|e| IoError::DecodeError(format!("embedded ICC profile: {e}")))?;
+    if !source.is_rgb() || image.channels < 3 {
+        return Ok(false);
+    }
+    let transform = vfx_icc::Transform::new(&source, target, intent)
+        .map_err(|e| IoError::DecodeError(format!("ICC transform: {e}")))?;
+
+    let channels = image.channels as usize;
+    let mut samples = image.to_f32();
+    let mut rgb: Vec<[f32; 3]> = samples
+        .chunks_exact(channels)
+        .map(|px| [px[0], px[1], px[2]])
+        .collect();
+    transform.apply(&mut rgb);
+    for (px, out) in samples.chunks_exact_mut(channels).zip(&rgb) {
+        px[..3].copy_from_slice(out);
+    }
+
+    image.data = PixelData::F32(samples);
+    image.format = PixelFormat::F32;
+    embed_profile(&mut image.metadata.attrs, target)?;
+    Ok(true)
+}
+
+/// Applies a reader's conversion option, if set.
+#[cfg(feature = "icc")]
+pub(crate) fn convert_on_read(image: &mut ImageData, convert: Option<&IccConvert>) -> IoResult<()> {
+    if let Some(convert) = convert {
+        let target = Profile::from_standard(convert.target);
+        if convert_to_working(image, &target, convert.intent)? {
+            image.metadata.colorspace = Some(colorspace_name(convert.target).to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Colour space name recorded after converting into a standard profile.
+#[cfg(feature = "icc")]
+fn colorspace_name(standard: StandardProfile) -> &'static str {
+    match standard {
+        StandardProfile::Srgb => "sRGB",
+        StandardProfile::LinearSrgb => "linear",
+        StandardProfile::AdobeRgb => "Adobe RGB",
+        StandardProfile::DisplayP3 => "Display P3",
+        StandardProfile::DciP3 => "DCI-P3",
+        StandardProfile::AcesAp0 => "ACES2065-1",
+        StandardProfile::AcesAp1 => "ACEScg",
+        StandardProfile::Rec709 => "Rec.709",
+        StandardProfile::Rec2020 => "Rec.2020",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jpeg_segments() {
+        let profile: Vec<u8> = (0..150_000u32).map(|i| i as u8).collect();
+        let segments = jpeg_segments(&profile).unwrap();
+        assert_eq!(segments.len(), 3);
+        assert!(segments.iter().all(|s| s.len() <= 65533));
+
+        // Reassemble out of order
+        let mut chunks: Vec<(u8, u8, Vec<u8>)> = segments
+            .iter()
+            .rev()
+            .map(|s| (s[12], s[13], s[14..].to_vec()))
+            .collect();
+        assert!(chunks.iter().all(|c| c.1 == 3));
+        assert_eq!(join_jpeg_segments(&mut chunks), profile);
+
+        assert!(jpeg_segments(&vec![0; JPEG_CHUNK_SIZE * 256]).is_none());
+    }
+
+    #[cfg(feature = "psd")]
+    #[test]
+    fn test_psd_resource() {
+        let profile = vec![1, 2, 3];
+        let mut section = Vec::new();
+        // An unrelated named resource first
+        section.extend_from_slice(b"8BIM\x03\xED\x03abc\0\0\0\x02\xAA\xBB");
+        section.extend_from_slice(&psd_resource(&profile));
+        assert_eq!(psd_profile(&section), Some(&profile[..]));
+        assert_eq!(psd_profile(b"8BIM\x03\xED"), None);
+    }
+
+    #[test]
+    fn test_set_profile() {
+        let mut data = vec![0u8; 128];
+        data[16..20].copy_from_slice(b"RGB ");
+        let mut attrs = Attrs::new();
+        set_profile(&mut attrs, data);
+        assert_eq!(attrs.get_u32("ICCProfileSize"), Some(128));
+        assert_eq!(attrs.get_str("ICCColorSpace"), Some("RGB"));
+        assert_eq!(profile(&attrs).map(|p| p.len()), Some(128));
+    }
+
+    #[cfg(feature = "icc")]
+    #[test]
+    fn test_convert_to_working() {
+        let mut image = ImageData::from_u8(2, 1, 4, vec![255, 0, 0, 128, 128, 128, 128, 255]);
+        assert!(!convert_to_working(&mut image, &Profile::aces_ap1(), Intent::Perceptual)
+            .unwrap());
+
+        embed_profile(&mut image.metadata.attrs, &Profile::srgb()).unwrap();
+        let converted =
+            convert_to_working(&mut image, &Profile::linear_srgb(), Intent::Perceptual).unwrap();
+        assert!(converted);
+        assert_eq!(image.format, PixelFormat::F32);
+
+        let px = image.to_f32();
+        assert!((px[0] - 1.0).abs() < 0.01 && px[1].abs() < 0.01);
+        assert!((px[3] - 128.0 / 255.0).abs() < 1e-6);
+        // sRGB 128 is about 0.216 linear
+        assert!((px[4] - 0.216).abs() < 0.01);
+
+        let embedded = Profile::from_icc(profile(&image.metadata.attrs).unwrap()).unwrap();
+        assert!(embedded.is_rgb());
+    }
+}
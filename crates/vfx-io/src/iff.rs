@@ -0,0 +1,935 @@
+//! Maya IFF format support.
+//!
+//! Provides reading and writing of Maya IFF images (`FOR4`/`CIMG`), the
+//! native render output of Maya's software renderer.
+//!
+//! # Overview
+//!
+//! - Tiled RGB and RGBA, 8 and 16 bits per channel
+//! - Uncompressed and RLE tiles (per byte plane)
+//! - Z buffer (`ZBUF`) tiles, exposed as a `Z` channel through
+//!   [`read_layers`] and written back by [`write_layers`]
+//! - Tile rows are stored bottom-up and returned top-down
+//! - `AUTH` and `DATE` chunks map to `Artist` and `DateTime`
+//!
+//! Color samples are stored in reverse channel order (ABGR), big-endian.
+//! Compressed tiles hold one RLE stream per byte of that pixel layout; a
+//! tile whose data is as large as the raw pixels is uncompressed. Z values
+//! are returned as stored, without converting Maya's depth encoding.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use vfx_io::iff;
+//!
+//! let image = iff::read("beauty.iff")?;
+//!
+//! // Color plus depth
+//! let layered = iff::read_layers("beauty.iff")?;
+//! let z = layered.layers[0].channels.iter().find(|c| c.name == "Z");
+//! ```
+
+use crate::{
+    AttrValue, ChannelKind, ChannelSampleType, ChannelSamples, FormatReader, FormatWriter,
+    ImageChannel, ImageData, IoError, IoResult, LayeredImage, Metadata, PixelData, PixelFormat,
+};
+use std::io::Write;
+use std::path::Path;
+
+/// Header flag: RGB color.
+const FLAG_RGB: u32 = 0x01;
+
+/// Header flag: alpha.
+const FLAG_ALPHA: u32 = 0x02;
+
+/// Header flag: Z buffer.
+const FLAG_ZBUFFER: u32 = 0x04;
+
+/// Tile edge length used by the writer.
+const TILE_SIZE: usize = 64;
+
+/// Largest RLE image read, in pixels.
+const MAX_RLE_PIXELS: usize = 16384 * 16384;
+
+// ============================================================================
+// Reader Options
+// ============================================================================
+
+/// Options for reading Maya IFF files.
+///
+/// Currently minimal - IFF reading is mostly automatic.
+#[derive(Debug, Clone, Default)]
+pub struct IffReaderOptions {
+    /// Reserved for future use.
+    _reserved: (),
+}
+
+// ============================================================================
+// Writer Options
+// ============================================================================
+
+/// Options for writing Maya IFF files.
+///
+/// The sample size follows the image: 8-bit images are written with one
+/// byte per channel, everything else with two.
+#[derive(Debug, Clone)]
+pub struct IffWriterOptions {
+    /// Use RLE tiles where they are smaller than raw ones. Default: true.
+    pub rle: bool,
+}
+
+impl Default for IffWriterOptions {
+    fn default() -> Self {
+        Self { rle: true }
+    }
+}
+
+// ============================================================================
+// Header
+// ============================================================================
+
+/// Parsed `TBHD` chunk.
+#[derive(Debug, Clone)]
+struct IffHeader {
+    width: usize,
+    height: usize,
+    aspect: (u16, u16),
+    flags: u32,
+    bytes: usize,
+    rle: bool,
+}
+
+impl IffHeader {
+    fn parse(body: &[u8]) -> IoResult<Self> {
+        if body.len() < 24 {
+            return Err(IoError::InvalidFile("IFF TBHD chunk truncated".into()));
+        }
+        let header = Self {
+            width: read_u32(body, 0) as usize,
+            height: read_u32(body, 4) as usize,
+            aspect: (read_u16(body, 8), read_u16(body, 10)),
+            flags: read_u32(body, 12),
+            bytes: if read_u16(body, 16) == 0 { 1 } else { 2 },
+            rle: read_u32(body, 20) != 0,
+        };
+        if header.width == 0 || header.height == 0 {
+            return Err(IoError::InvalidFile(format!(
+                "invalid IFF dimensions: {}x{}",
+                header.width, header.height
+            )));
+        }
+        if header.color_channels() == 0 {
+            return Err(IoError::InvalidFile(format!(
+                "IFF flags {:#x} have no color channels",
+                header.flags
+            )));
+        }
+        Ok(header)
+    }
+
+    /// Returns the pixel count once the file is known to be large enough.
+    ///
+    /// Checked before allocating, the dimensions come from the header.
+    fn buffer_pixels(&self, file_size: usize) -> IoResult<usize> {
+        let too_large = || {
+            IoError::InvalidFile(format!("IFF dimensions too large: {}x{}", self.width, self.height))
+        };
+        let pixels = self.width.checked_mul(self.height).ok_or_else(too_large)?;
+        if self.rle {
+            // A few RLE bytes can describe any size, so there is no data to check against
+            if pixels > MAX_RLE_PIXELS {
+                return Err(too_large());
+            }
+            return Ok(pixels);
+        }
+        let mut pixel_bytes = self.color_channels() * self.bytes;
+        if self.flags & FLAG_ZBUFFER != 0 {
+            pixel_bytes += 4;
+        }
+        match pixels.checked_mul(pixel_bytes) {
+            Some(size) if size <= file_size => Ok(pixels),
+            _ => Err(IoError::InvalidFile("IFF pixel data truncated".into())),
+        }
+    }
+
+    /// Number of color channels: RGB, RGBA or alpha only.
+    fn color_channels(&self) -> usize {
+        let rgb = if self.flags & FLAG_RGB != 0 { 3 } else { 0 };
+        rgb + (self.flags & FLAG_ALPHA != 0) as usize
+    }
+}
+
+/// Decoded file contents.
+struct IffPixels {
+    image: ImageData,
+    z: Option<Vec<f32>>,
+}
+
+// ============================================================================
+// IffReader
+// ============================================================================
+
+/// Maya IFF file reader.
+///
+/// Implements [`FormatReader`] for reading the color channels of Maya IFF
+/// files; use [`IffReader::read_layers`] to include the Z buffer.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::iff::IffReader;
+/// use vfx_io::FormatReader;
+///
+/// let reader = IffReader::new();
+/// let image = reader.read("beauty.iff")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct IffReader {
+    #[allow(dead_code)]
+    options: IffReaderOptions,
+}
+
+impl IffReader {
+    /// Creates a new reader with default options.
+    pub fn new() -> Self {
+        Self::with_options(IffReaderOptions::default())
+    }
+
+    /// Reads color and Z as a single layer named `default`.
+    ///
+    /// Color channels are named `R`, `G`, `B`, `A` and the Z buffer `Z`.
+    pub fn read_layers<P: AsRef<Path>>(&self, path: P) -> IoResult<LayeredImage> {
+        let data = std::fs::read(path.as_ref())?;
+        self.read_layers_from_memory(&data)
+    }
+
+    /// Reads color and Z from a byte slice as a single layer.
+    pub fn read_layers_from_memory(&self, data: &[u8]) -> IoResult<LayeredImage> {
+        let IffPixels { image, z } = decode(data)?;
+        let mut layered = image.to_layered("default");
+        if let Some(z) = z {
+            layered.layers[0].channels.push(ImageChannel {
+                name: "Z".to_string(),
+                kind: ChannelKind::Depth,
+                sample_type: ChannelSampleType::F32,
+                samples: ChannelSamples::F32(z),
+                sampling: (1, 1),
+                quantize_linearly: false,
+            });
+        }
+        Ok(layered)
+    }
+}
+
+impl Default for IffReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatReader<IffReaderOptions> for IffReader {
+    /// Returns "Maya IFF".
+    fn format_name(&self) -> &'static str {
+        "Maya IFF"
+    }
+
+    /// Returns `["iff", "tdi"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["iff", "tdi"]
+    }
+
+    /// Checks for a `FOR4` form of type `CIMG`.
+    fn can_read(&self, header: &[u8]) -> bool {
+        is_iff_header(header)
+    }
+
+    /// Reads a Maya IFF file from disk.
+    fn read<P: AsRef<Path>>(&self, path: P) -> IoResult<ImageData> {
+        let data = std::fs::read(path.as_ref())?;
+        self.read_from_memory(&data)
+    }
+
+    /// Reads a Maya IFF from a byte slice.
+    fn read_from_memory(&self, data: &[u8]) -> IoResult<ImageData> {
+        let pixels = decode(data)?;
+        if pixels.image.channels == 0 {
+            return Err(IoError::MissingData("IFF file has no color channels".into()));
+        }
+        Ok(pixels.image)
+    }
+
+    /// Creates reader with custom options.
+    fn with_options(options: IffReaderOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Decodes the `CIMG` form into top-down color and Z.
+fn decode(data: &[u8]) -> IoResult<IffPixels> {
+    if !is_iff_header(data) {
+        return Err(IoError::InvalidFile("not a Maya IFF file".into()));
+    }
+    let form_end = (8 + read_u32(data, 4) as usize).min(data.len());
+
+    let mut header: Option<IffHeader> = None;
+    let mut metadata = Metadata {
+        colorspace: Some("sRGB".to_string()),
+        ..Metadata::default()
+    };
+    let mut color = Vec::new();
+    let mut depth = Vec::new();
+
+    for (tag, body) in chunks(&data[12..form_end]) {
+        match tag {
+            b"TBHD" => {
+                let parsed = IffHeader::parse(body)?;
+                let pixels = parsed.buffer_pixels(data.len())?;
+                color = vec![0u8; pixels * parsed.color_channels() * parsed.bytes];
+                if parsed.flags & FLAG_ZBUFFER != 0 {
+                    depth = vec![0u8; pixels * 4];
+                }
+                header = Some(parsed);
+            }
+            b"AUTH" => metadata.attrs.set("Artist", AttrValue::Str(read_text(body))),
+            b"DATE" => metadata.attrs.set("DateTime", AttrValue::Str(read_text(body))),
+            b"FOR4" if body.starts_with(b"TBMP") => {
+                let header = header
+                    .as_ref()
+                    .ok_or_else(|| IoError::InvalidFile("IFF tiles before TBHD".into()))?;
+                for (tile_tag, tile) in chunks(&body[4..]) {
+                    match tile_tag {
+                        b"RGBA" => {
+                            let pixel_bytes = header.color_channels() * header.bytes;
+                            decode_tile(tile, pixel_bytes, header, &mut color)?;
+                        }
+                        b"ZBUF" if !depth.is_empty() => decode_tile(tile, 4, header, &mut depth)?,
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let header = header.ok_or_else(|| IoError::InvalidFile("IFF TBHD chunk missing".into()))?;
+    let (width, height, channels) = (header.width, header.height, header.color_channels());
+
+    // Bottom-up rows of reversed, big-endian channels
+    let mut samples = vec![0u16; width * height * channels];
+    let pixel_bytes = channels * header.bytes;
+    for y in 0..height {
+        let src_row = &color[(height - 1 - y) * width * pixel_bytes..][..width * pixel_bytes];
+        for (x, px) in src_row.chunks_exact(pixel_bytes.max(1)).enumerate().take(width) {
+            for c in 0..channels {
+                let offset = (channels - 1 - c) * header.bytes;
+                let value = if header.bytes == 1 { px[offset] as u16 } else { read_u16(px, offset) };
+                samples[(y * width + x) * channels + c] = value;
+            }
+        }
+    }
+
+    let z = (!depth.is_empty()).then(|| {
+        let mut z = vec![0f32; width * height];
+        for y in 0..height {
+            let src_row = &depth[(height - 1 - y) * width * 4..][..width * 4];
+            for (x, value) in src_row.chunks_exact(4).enumerate() {
+                z[y * width + x] = f32::from_be_bytes([value[0], value[1], value[2], value[3]]);
+            }
+        }
+        z
+    });
+
+    metadata.attrs.set("IFF:BitsPerSample", AttrValue::UInt(header.bytes as u32 * 8));
+    metadata.attrs.set(
+        "IFF:Compression",
+        AttrValue::Str(if header.rle { "rle" } else { "none" }.into()),
+    );
+    if header.aspect.0 > 0 && header.aspect.1 > 0 {
+        let ratio = header.aspect.0 as f32 / header.aspect.1 as f32;
+        metadata.attrs.set("PixelAspectRatio", AttrValue::Float(ratio));
+    }
+
+    let mut image = if header.bytes == 1 {
+        let data = samples.into_iter().map(|v| v as u8).collect();
+        ImageData::from_u8(width as u32, height as u32, channels as u32, data)
+    } else {
+        let mut image = ImageData::new(width as u32, height as u32, channels as u32, PixelFormat::U16);
+        image.data = PixelData::U16(samples);
+        image
+    };
+    image.metadata = metadata;
+    Ok(IffPixels { image, z })
+}
+
+/// Copies one tile into a bottom-up image buffer of `pixel_bytes` pixels.
+fn decode_tile(body: &[u8], pixel_bytes: usize, header: &IffHeader, dest: &mut [u8]) -> IoResult<()> {
+    if body.len() < 8 || pixel_bytes == 0 {
+        return Err(IoError::InvalidFile("IFF tile truncated".into()));
+    }
+    let (xmin, ymin) = (read_u16(body, 0) as usize, read_u16(body, 2) as usize);
+    let (xmax, ymax) = (read_u16(body, 4) as usize, read_u16(body, 6) as usize);
+    if xmax < xmin || ymax < ymin || xmax >= header.width || ymax >= header.height {
+        return Err(IoError::InvalidFile(format!(
+            "IFF tile {},{}-{},{} outside the image",
+            xmin, ymin, xmax, ymax
+        )));
+    }
+    let (tile_width, tile_height) = (xmax - xmin + 1, ymax - ymin + 1);
+    let count = tile_width * tile_height;
+    let data = &body[8..];
+
+    let size = count * pixel_bytes;
+    let mut tile = vec![0u8; size];
+    if data.len() >= size {
+        tile.copy_from_slice(&data[..size]);
+    } else {
+        let mut pos = 0usize;
+        for plane in 0..pixel_bytes {
+            let values = decode_rle(data, &mut pos, count)?;
+            for (i, value) in values.into_iter().enumerate() {
+                tile[i * pixel_bytes + plane] = value;
+            }
+        }
+    }
+
+    let row_bytes = tile_width * pixel_bytes;
+    for (ty, src) in tile.chunks_exact(row_bytes).enumerate() {
+        let start = ((ymin + ty) * header.width + xmin) * pixel_bytes;
+        dest[start..start + row_bytes].copy_from_slice(src);
+    }
+    Ok(())
+}
+
+/// Decodes `count` bytes of RLE.
+///
+/// The low seven bits of a count byte give `n - 1`; with the high bit set
+/// the next byte repeats `n` times, otherwise `n` literal bytes follow.
+fn decode_rle(data: &[u8], pos: &mut usize, count: usize) -> IoResult<Vec<u8>> {
+    let truncated = || IoError::InvalidFile("IFF RLE data truncated".into());
+    let mut out = Vec::with_capacity(count);
+    while out.len() < count {
+        let control = *data.get(*pos).ok_or_else(truncated)?;
+        *pos += 1;
+        let n = (control & 0x7F) as usize + 1;
+        if control & 0x80 != 0 {
+            let value = *data.get(*pos).ok_or_else(truncated)?;
+            *pos += 1;
+            out.extend(std::iter::repeat_n(value, n));
+        } else {
+            out.extend_from_slice(data.get(*pos..*pos + n).ok_or_else(truncated)?);
+            *pos += n;
+        }
+    }
+    if out.len() > count {
+        return Err(IoError::InvalidFile("IFF RLE run overflows the tile".into()));
+    }
+    Ok(out)
+}
+
+// ============================================================================
+// IffWriter
+// ============================================================================
+
+/// Maya IFF file writer.
+///
+/// Implements [`FormatWriter`] for writing RGB and RGBA IFF files in
+/// 64x64 tiles. Gray images are expanded to RGB. Use
+/// [`IffWriter::write_layers`] to include a Z buffer.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::iff::IffWriter;
+/// use vfx_io::FormatWriter;
+///
+/// let writer = IffWriter::new();
+/// writer.write("beauty.iff", &image)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct IffWriter {
+    options: IffWriterOptions,
+}
+
+impl IffWriter {
+    /// Creates a new writer with default options.
+    pub fn new() -> Self {
+        Self::with_options(IffWriterOptions::default())
+    }
+
+    /// Writes a single layer with `R`, `G`, `B`, optional `A` and optional `Z`.
+    ///
+    /// Layered input is float, so color is written at 16 bits.
+    pub fn write_layers<P: AsRef<Path>>(&self, path: P, image: &LayeredImage) -> IoResult<()> {
+        let bytes = self.write_layers_to_memory(image)?;
+        std::fs::write(path.as_ref(), bytes)?;
+        Ok(())
+    }
+
+    /// Writes a layered image to a byte vector.
+    pub fn write_layers_to_memory(&self, image: &LayeredImage) -> IoResult<Vec<u8>> {
+        let layer = match image.layers.as_slice() {
+            [layer] => layer,
+            [] => return Err(IoError::MissingData("no layers to write".into())),
+            _ => return Err(IoError::EncodeError("IFF holds a single layer".into())),
+        };
+        let has = |name: &str| layer.channels.iter().any(|c| c.name == name);
+        let order: &[&str] = if has("A") { &["R", "G", "B", "A"] } else { &["R", "G", "B"] };
+        let mut color = layer.to_image_data_with_order(order)?;
+        color.metadata = image.metadata.clone();
+        let z = layer.channels.iter().find(|c| c.name == "Z").map(|c| c.samples.to_f32());
+
+        let mut buffer = Vec::new();
+        self.write_impl(&mut buffer, &color, z.as_deref())?;
+        Ok(buffer)
+    }
+
+    /// Internal write implementation.
+    fn write_impl<W: Write>(&self, mut writer: W, image: &ImageData, z: Option<&[f32]>) -> IoResult<()> {
+        let width = image.width as usize;
+        let height = image.height as usize;
+        let channels = image.channels as usize;
+        if width == 0 || height == 0 || width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(IoError::EncodeError(format!("invalid IFF dimensions: {}x{}", width, height)));
+        }
+        if !(1..=4).contains(&channels) {
+            return Err(IoError::EncodeError(format!("unsupported channels: {}", channels)));
+        }
+        if z.is_some_and(|z| z.len() != width * height) {
+            return Err(IoError::EncodeError("IFF Z buffer size does not match the image".into()));
+        }
+
+        let has_alpha = channels == 2 || channels == 4;
+        let out_channels = if has_alpha { 4 } else { 3 };
+        let bytes = if image.format == PixelFormat::U8 { 1 } else { 2 };
+        let samples: Vec<u16> = if bytes == 1 {
+            image.to_u8().into_iter().map(u16::from).collect()
+        } else {
+            image.to_u16()
+        };
+
+        // Bottom-up rows of reversed, big-endian channels
+        let pixel_bytes = out_channels * bytes;
+        let mut color = vec![0u8; width * height * pixel_bytes];
+        for (i, px) in samples.chunks_exact(channels).enumerate() {
+            let (x, y) = (i % width, height - 1 - i / width);
+            let rgba = match channels {
+                1 => [px[0], px[0], px[0], 0],
+                2 => [px[0], px[0], px[0], px[1]],
+                3 => [px[0], px[1], px[2], 0],
+                _ => [px[0], px[1], px[2], px[3]],
+            };
+            let dst = &mut color[(y * width + x) * pixel_bytes..][..pixel_bytes];
+            for (c, value) in rgba.iter().take(out_channels).enumerate() {
+                let offset = (out_channels - 1 - c) * bytes;
+                if bytes == 1 {
+                    dst[offset] = *value as u8;
+                } else {
+                    dst[offset..offset + 2].copy_from_slice(&value.to_be_bytes());
+                }
+            }
+        }
+        let depth = z.map(|z| {
+            let mut depth = vec![0u8; width * height * 4];
+            for (i, value) in z.iter().enumerate() {
+                let (x, y) = (i % width, height - 1 - i / width);
+                depth[(y * width + x) * 4..][..4].copy_from_slice(&value.to_be_bytes());
+            }
+            depth
+        });
+
+        let tiles_x = width.div_ceil(TILE_SIZE);
+        let tiles_y = height.div_ceil(TILE_SIZE);
+        let tiles = u16::try_from(tiles_x * tiles_y)
+            .map_err(|_| IoError::EncodeError("too many IFF tiles".into()))?;
+
+        let mut flags = FLAG_RGB;
+        if has_alpha {
+            flags |= FLAG_ALPHA;
+        }
+        if depth.is_some() {
+            flags |= FLAG_ZBUFFER;
+        }
+        let aspect = image
+            .metadata
+            .attrs
+            .get("PixelAspectRatio")
+            .and_then(|v| v.as_f32())
+            .and_then(to_ratio)
+            .unwrap_or((1, 1));
+
+        let mut tbhd = Vec::with_capacity(32);
+        tbhd.extend_from_slice(&(width as u32).to_be_bytes());
+        tbhd.extend_from_slice(&(height as u32).to_be_bytes());
+        tbhd.extend_from_slice(&aspect.0.to_be_bytes());
+        tbhd.extend_from_slice(&aspect.1.to_be_bytes());
+        tbhd.extend_from_slice(&flags.to_be_bytes());
+        tbhd.extend_from_slice(&(bytes as u16 - 1).to_be_bytes());
+        tbhd.extend_from_slice(&tiles.to_be_bytes());
+        tbhd.extend_from_slice(&(self.options.rle as u32).to_be_bytes());
+        tbhd.extend_from_slice(&[0; 8]);
+
+        let mut form = b"CIMG".to_vec();
+        push_chunk(&mut form, b"TBHD", &tbhd);
+        for (key, tag) in [("Artist", b"AUTH"), ("DateTime", b"DATE")] {
+            if let Some(text) = image.metadata.attrs.get(key).and_then(|v| v.as_str()) {
+                let mut body = text.as_bytes().to_vec();
+                body.push(0);
+                push_chunk(&mut form, tag, &body);
+            }
+        }
+
+        let mut tbmp = b"TBMP".to_vec();
+        let planes = [(b"RGBA", pixel_bytes, Some(&color)), (b"ZBUF", 4, depth.as_ref())];
+        for (tag, pixel_bytes, buffer) in planes {
+            let Some(buffer) = buffer else { continue };
+            for ty in 0..tiles_y {
+                for tx in 0..tiles_x {
+                    let (xmin, ymin) = (tx * TILE_SIZE, ty * TILE_SIZE);
+                    let xmax = (xmin + TILE_SIZE).min(width) - 1;
+                    let ymax = (ymin + TILE_SIZE).min(height) - 1;
+                    let body = self.encode_tile(buffer, width, pixel_bytes, [xmin, ymin, xmax, ymax]);
+                    push_chunk(&mut tbmp, tag, &body);
+                }
+            }
+        }
+        push_chunk(&mut form, b"FOR4", &tbmp);
+
+        let mut out = Vec::with_capacity(form.len() + 8);
+        push_chunk(&mut out, b"FOR4", &form);
+        writer.write_all(&out)?;
+        Ok(())
+    }
+
+    /// Builds a tile chunk body, RLE compressed when that is smaller.
+    fn encode_tile(&self, buffer: &[u8], width: usize, pixel_bytes: usize, bounds: [usize; 4]) -> Vec<u8> {
+        let [xmin, ymin, xmax, ymax] = bounds;
+        let mut body = Vec::new();
+        for value in bounds {
+            body.extend_from_slice(&(value as u16).to_be_bytes());
+        }
+
+        let mut raw = Vec::with_capacity((xmax - xmin + 1) * (ymax - ymin + 1) * pixel_bytes);
+        for y in ymin..=ymax {
+            raw.extend_from_slice(&buffer[(y * width + xmin) * pixel_bytes..(y * width + xmax + 1) * pixel_bytes]);
+        }
+
+        if self.options.rle {
+            let mut compressed = Vec::new();
+            for plane in 0..pixel_bytes {
+                let values: Vec<u8> = raw.iter().skip(plane).step_by(pixel_bytes).copied().collect();
+                encode_rle(&values, &mut compressed);
+            }
+            if compressed.len() < raw.len() {
+                body.extend_from_slice(&compressed);
+                return body;
+            }
+        }
+        body.extend_from_slice(&raw);
+        body
+    }
+}
+
+impl Default for IffWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatWriter<IffWriterOptions> for IffWriter {
+    /// Returns "Maya IFF".
+    fn format_name(&self) -> &'static str {
+        "Maya IFF"
+    }
+
+    /// Returns `["iff", "tdi"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["iff", "tdi"]
+    }
+
+    /// Writes a Maya IFF file to disk.
+    fn write<P: AsRef<Path>>(&self, path: P, image: &ImageData) -> IoResult<()> {
+        let file = std::fs::File::create(path.as_ref())?;
+        self.write_impl(std::io::BufWriter::new(file), image, None)
+    }
+
+    /// Writes a Maya IFF to a byte vector.
+    fn write_to_memory(&self, image: &ImageData) -> IoResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.write_impl(&mut buffer, image, None)?;
+        Ok(buffer)
+    }
+
+    /// Creates writer with custom options.
+    fn with_options(options: IffWriterOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Encodes bytes as RLE packets of at most 128 bytes.
+fn encode_rle(data: &[u8], out: &mut Vec<u8>) {
+    let len = data.len();
+    let mut x = 0usize;
+
+    while x < len {
+        let mut run = 1usize;
+        while x + run < len && run < 128 && data[x + run] == data[x] {
+            run += 1;
+        }
+        if run >= 2 {
+            out.push(0x80 | (run - 1) as u8);
+            out.push(data[x]);
+            x += run;
+            continue;
+        }
+
+        // Literal bytes until the next run of 2
+        let start = x;
+        while x < len && x - start < 128 && !(x + 1 < len && data[x] == data[x + 1]) {
+            x += 1;
+        }
+        out.push((x - start - 1) as u8);
+        out.extend_from_slice(&data[start..x]);
+    }
+}
+
+// ============================================================================
+// Convenience Functions
+// ============================================================================
+
+/// Reads the color channels of a Maya IFF file.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::iff;
+///
+/// let image = iff::read("beauty.iff")?;
+/// ```
+pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
+    IffReader::new().read(path)
+}
+
+/// Reads color and Z of a Maya IFF file as a single layer.
+pub fn read_layers<P: AsRef<Path>>(path: P) -> IoResult<LayeredImage> {
+    IffReader::new().read_layers(path)
+}
+
+/// Writes a Maya IFF file with default options (RLE tiles).
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::iff;
+///
+/// iff::write("output.iff", &image)?;
+/// ```
+pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
+    IffWriter::new().write(path, image)
+}
+
+/// Writes a single layer with color and an optional `Z` channel.
+pub fn write_layers<P: AsRef<Path>>(path: P, image: &LayeredImage) -> IoResult<()> {
+    IffWriter::new().write_layers(path, image)
+}
+
+/// Checks for a `FOR4` form of type `CIMG`.
+pub fn is_iff_header(h: &[u8]) -> bool {
+    h.len() >= 12 && &h[0..4] == b"FOR4" && &h[8..12] == b"CIMG"
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Iterates the chunks of a form body; bodies are padded to 4 bytes.
+fn chunks(mut data: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+    std::iter::from_fn(move || {
+        if data.len() < 8 {
+            return None;
+        }
+        let tag = &data[0..4];
+        let size = (read_u32(data, 4) as usize).min(data.len() - 8);
+        let body = &data[8..8 + size];
+        data = &data[(8 + size.next_multiple_of(4)).min(data.len())..];
+        Some((tag, body))
+    })
+}
+
+/// Appends a chunk, padding its body to 4 bytes.
+fn push_chunk(out: &mut Vec<u8>, tag: &[u8; 4], body: &[u8]) {
+    out.extend_from_slice(tag);
+    out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    out.extend_from_slice(body);
+    out.resize(out.len().next_multiple_of(4), 0);
+}
+
+/// Reads a NUL-terminated text chunk.
+fn read_text(body: &[u8]) -> String {
+    let end = body.iter().position(|&b| b == 0).unwrap_or(body.len());
+    String::from_utf8_lossy(&body[..end]).trim().to_string()
+}
+
+/// Converts a value to a 16-bit ratio with a fixed denominator.
+fn to_ratio(value: f32) -> Option<(u16, u16)> {
+    let num = (value * 1000.0).round();
+    (num > 0.0 && num <= u16::MAX as f32).then_some((num as u16, 1000))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_z(image: &ImageData, z: Vec<f32>) -> LayeredImage {
+        let mut layered = image.to_layered("default");
+        layered.layers[0].channels.push(ImageChannel {
+            name: "Z".into(),
+            kind: ChannelKind::Depth,
+            sample_type: ChannelSampleType::F32,
+            samples: ChannelSamples::F32(z),
+            sampling: (1, 1),
+            quantize_linearly: false,
+        });
+        layered
+    }
+
+    /// Tests 64x64 tiling with partial edge tiles for the RGBA and ZBUF chunks.
+    #[test]
+    fn test_tile_bounds() {
+        let (width, height) = (65usize, 130usize);
+        let data: Vec<u8> = (0..width * height)
+            .flat_map(|i| {
+                let (x, y) = ((i % width) as u8, (i / width) as u8);
+                [x, y, x ^ y, 255 - x]
+            })
+            .collect();
+        let image = ImageData::from_u8(width as u32, height as u32, 4, data);
+        let z: Vec<f32> = (0..width * height).map(|i| i as f32 * 0.25).collect();
+
+        let writer = IffWriter::with_options(IffWriterOptions { rle: false });
+        let bytes = writer.write_layers_to_memory(&with_z(&image, z.clone())).expect("Write failed");
+
+        let (_, form) = chunks(&bytes).next().expect("FOR4 missing");
+        let mut tbhd = None;
+        let mut tiles = Vec::new();
+        for (tag, body) in chunks(&form[4..]) {
+            match tag {
+                b"TBHD" => tbhd = Some(body),
+                b"FOR4" => tiles.extend(chunks(&body[4..])),
+                _ => {}
+            }
+        }
+        let tbhd = tbhd.expect("TBHD missing");
+        assert_eq!(read_u32(tbhd, 12), FLAG_RGB | FLAG_ALPHA | FLAG_ZBUFFER);
+        // Layers are written as 16-bit color
+        assert_eq!(read_u16(tbhd, 16), 1);
+        assert_eq!(read_u16(tbhd, 18), 6);
+
+        // Bottom-up tile rows; ZBUF tiles follow all RGBA tiles
+        let bounds = [
+            [0, 0, 63, 63],
+            [64, 0, 64, 63],
+            [0, 64, 63, 127],
+            [64, 64, 64, 127],
+            [0, 128, 63, 129],
+            [64, 128, 64, 129],
+        ];
+        assert_eq!(tiles.len(), 12);
+        for (i, (tag, body)) in tiles.iter().enumerate() {
+            let [xmin, ymin, xmax, ymax] = bounds[i % 6];
+            let (expected_tag, pixel_bytes) = if i < 6 { (b"RGBA", 8) } else { (b"ZBUF", 4) };
+            assert_eq!(*tag, expected_tag);
+            let found: Vec<u16> = (0..4).map(|k| read_u16(body, k * 2)).collect();
+            assert_eq!(found, [xmin, ymin, xmax, ymax]);
+            let pixels = (xmax - xmin + 1) as usize * (ymax - ymin + 1) as usize;
+            assert_eq!(body.len(), 8 + pixels * pixel_bytes);
+        }
+
+        let color = IffReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(color.to_u8(), image.to_u8());
+        let loaded = IffReader::new().read_layers_from_memory(&bytes).expect("Read failed");
+        assert_eq!(loaded.layers[0].channels[4].samples.to_f32(), z);
+    }
+
+    /// Tests 16-bit color with a Z buffer and an AUTH chunk through layers.
+    #[test]
+    fn test_layers_with_z() {
+        let data: Vec<u8> = (0..70 * 3 * 4).map(|i| if i % 280 < 140 { 90 } else { (i % 251) as u8 }).collect();
+        let mut image = ImageData::from_u8(70, 3, 4, data);
+        image.metadata.attrs.set("Artist", AttrValue::Str("lighting".into()));
+        let z: Vec<f32> = (0..70 * 3).map(|i| i as f32 * 0.5).collect();
+
+        let bytes = IffWriter::new().write_layers_to_memory(&with_z(&image, z.clone())).expect("Write failed");
+        let color = IffReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(color.format, PixelFormat::U16);
+        assert_eq!(color.to_u8(), image.to_u8());
+        assert_eq!(color.metadata.attrs.get("Artist").and_then(|v| v.as_str()), Some("lighting"));
+
+        let loaded = IffReader::new().read_layers_from_memory(&bytes).expect("Read failed");
+        let names: Vec<&str> = loaded.layers[0].channels.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["R", "G", "B", "A", "Z"]);
+        assert_eq!(loaded.layers[0].channels[4].samples.to_f32(), z);
+    }
+
+    /// Tests a hand-built RLE tile with reversed channels and bottom-up rows.
+    #[test]
+    fn test_rle_tile() {
+        let mut tbhd = Vec::new();
+        for v in [2u32, 2] {
+            tbhd.extend_from_slice(&v.to_be_bytes());
+        }
+        tbhd.extend_from_slice(&[0, 1, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1]);
+        // Planes B, G, R: B all 3, G all 2, R bottom row 10, top row 20
+        let mut tile = vec![0, 0, 0, 0, 0, 1, 0, 1];
+        tile.extend_from_slice(&[0x83, 3, 0x83, 2, 0x81, 10, 0x81, 20]);
+
+        let mut tbmp = b"TBMP".to_vec();
+        push_chunk(&mut tbmp, b"RGBA", &tile);
+        let mut form = b"CIMG".to_vec();
+        push_chunk(&mut form, b"TBHD", &tbhd);
+        push_chunk(&mut form, b"FOR4", &tbmp);
+        let mut data = Vec::new();
+        push_chunk(&mut data, b"FOR4", &form);
+
+        let image = IffReader::new().read_from_memory(&data).expect("Read failed");
+        assert_eq!(image.to_u8(), [20, 2, 3, 20, 2, 3, 10, 2, 3, 10, 2, 3]);
+    }
+
+    /// Tests that oversized or channel-less headers are rejected before allocating.
+    #[test]
+    fn test_invalid_headers() {
+        let image = ImageData::from_u8(2, 2, 3, vec![7; 12]);
+        for rle in [false, true] {
+            let writer = IffWriter::with_options(IffWriterOptions { rle });
+            let mut bytes = writer.write_to_memory(&image).expect("Write failed");
+            // TBHD body follows the form and chunk headers
+            bytes[20..28].copy_from_slice(&[0, 1, 0, 0, 0, 1, 0, 0]);
+            let result = IffReader::new().read_from_memory(&bytes);
+            assert!(matches!(result, Err(IoError::InvalidFile(_))), "rle={}", rle);
+        }
+
+        let mut bytes = IffWriter::new().write_to_memory(&image).expect("Write failed");
+        bytes[32..36].copy_from_slice(&FLAG_ZBUFFER.to_be_bytes());
+        let result = IffReader::new().read_from_memory(&bytes);
+        assert!(matches!(result, Err(IoError::InvalidFile(_))));
+    }
+
+    /// Tests header detection.
+    #[test]
+    fn test_can_read() {
+        let reader = IffReader::new();
+        assert!(reader.can_read(b"FOR4\0\0\0\x20CIMG"));
+        assert!(!reader.can_read(b"FORM\0\0\0\x20ILBM"));
+    }
+}
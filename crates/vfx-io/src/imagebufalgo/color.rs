@@ -10,6 +10,7 @@
 //! - [`colormatrixtransform`] - Apply 4x4 color matrix
 //! - [`rangecompress`] / [`rangeexpand`] - Nonlinear range remapping
 //! - [`srgb_to_linear`] / [`linear_to_srgb`] - sRGB gamma conversion
+//! - [`dci_xyz`] - P3 to DCI X'Y'Z' for digital cinema packages
 
 use crate::imagebuf::{ImageBuf, InitializePixels, WrapMode};
 use vfx_core::{ImageSpec, Roi3D};
@@ -895,6 +896,127 @@ fn linear_to_srgb_value(x: f32) -> f32 {
     }
 }
 
+// ============================================================================
+// DCI X'Y'Z' Encoding
+// ============================================================================
+
+/// DCI reference white luminance (cd/m²) of full-scale X'Y'Z' (SMPTE 428-1).
+const DCI_NORMALIZING_LUMINANCE: f32 = 52.37;
+
+/// Creator white point of P3 material converted to DCI X'Y'Z'.
+///
+/// The conversion does no chromatic adaptation, so RGB white reproduces
+/// this white on the projector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DciWhite {
+    /// P3-D65 (x 0.3127, y 0.3290).
+    #[default]
+    D65,
+    /// P3-D60, as used for ACES masters (x 0.32168, y 0.33767).
+    D60,
+    /// DCI white (x 0.314, y 0.351).
+    Dci,
+}
+
+impl DciWhite {
+    /// White point chromaticity.
+    pub fn xy(&self) -> (f32, f32) {
+        match self {
+            Self::D65 => vfx_primaries::D65_XY,
+            Self::D60 => vfx_primaries::D60_XY,
+            Self::Dci => vfx_primaries::DCI_XY,
+        }
+    }
+}
+
+/// Options for [`dci_xyz`].
+#[derive(Debug, Clone, Copy)]
+pub struct DciXyzOptions {
+    /// White point of the P3 input. Default: D65.
+    pub white: DciWhite,
+    /// Gamma of the input code values, or None for linear light.
+    /// Default: 2.6.
+    pub input_gamma: Option<f32>,
+    /// Luminance of RGB white in cd/m². Default: 48.
+    pub peak_luminance: f32,
+}
+
+impl Default for DciXyzOptions {
+    fn default() -> Self {
+        Self {
+            white: DciWhite::D65,
+            input_gamma: Some(2.6),
+            peak_luminance: 48.0,
+        }
+    }
+}
+
+/// Convert P3 RGB to DCI X'Y'Z' code values.
+///
+/// Linearizes the input, converts P3 with the given white to CIE XYZ,
+/// scales white to the peak luminance relative to 52.37 cd/m² and applies
+/// the 1/2.6 encoding gamma. Results are quantized to 12 bits and stored
+/// as `code / 4095` in a float image, ready for the DCI JPEG 2000
+/// profiles. Alpha and channels past the third are copied.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::imagebufalgo::{dci_xyz, DciXyzOptions};
+///
+/// let xyz = dci_xyz(&p3_d65, &DciXyzOptions::default(), None);
+/// ```
+pub fn dci_xyz(src: &ImageBuf, options: &DciXyzOptions, roi: Option<Roi3D>) -> ImageBuf {
+    let roi = roi.unwrap_or_else(|| src.roi());
+    let mut spec = src.spec().clone();
+    // Half floats cannot hold every 12-bit code
+    spec.format = vfx_core::DataFormat::F32;
+    let mut dst = ImageBuf::new(spec, InitializePixels::No);
+    dci_xyz_into(&mut dst, src, options, Some(roi));
+    dst
+}
+
+/// Convert P3 RGB to DCI X'Y'Z' into existing destination.
+pub fn dci_xyz_into(dst: &mut ImageBuf, src: &ImageBuf, options: &DciXyzOptions, roi: Option<Roi3D>) {
+    let roi = roi.unwrap_or_else(|| src.roi());
+    let nch = dst.nchannels() as usize;
+    if nch < 3 {
+        copy_pixels(dst, src, &roi);
+        return;
+    }
+
+    let primaries = vfx_primaries::Primaries {
+        w: options.white.xy(),
+        ..vfx_primaries::DCI_P3
+    };
+    let m = vfx_primaries::rgb_to_xyz_matrix(&primaries);
+    let scale = options.peak_luminance / DCI_NORMALIZING_LUMINANCE;
+    let encode = |v: f32| ((v * scale).clamp(0.0, 1.0).powf(1.0 / 2.6) * 4095.0).round() / 4095.0;
+
+    let mut pixel = vec![0.0f32; nch];
+
+    for z in roi.zbegin..roi.zend {
+        for y in roi.ybegin..roi.yend {
+            for x in roi.xbegin..roi.xend {
+                src.getpixel(x, y, z, &mut pixel, WrapMode::Black);
+
+                let mut rgb = [pixel[0], pixel[1], pixel[2]];
+                if let Some(gamma) = options.input_gamma {
+                    for v in rgb.iter_mut() {
+                        *v = v.max(0.0).powf(gamma);
+                    }
+                }
+                let xyz = m * vfx_math::Vec3::new(rgb[0], rgb[1], rgb[2]);
+                pixel[0] = encode(xyz.x);
+                pixel[1] = encode(xyz.y);
+                pixel[2] = encode(xyz.z);
+
+                dst.setpixel(x, y, z, &pixel);
+            }
+        }
+    }
+}
+
 // ============================================================================
 // Utility Functions
 // ============================================================================
@@ -1093,4 +1215,43 @@ mod tests {
         assert!((pixel[1] - 0.0).abs() < 0.001);
         assert!((pixel[2] - 1.0).abs() < 0.001);
     }
+
+    #[test]
+    fn test_dci_xyz_white() {
+        let spec = ImageSpec::new(10, 10, 3, vfx_core::DataFormat::F32);
+        let mut src = ImageBuf::new(spec, InitializePixels::No);
+        src.setpixel(0, 0, 0, &[1.0, 1.0, 1.0]);
+        src.setpixel(1, 0, 0, &[0.0, 0.0, 0.0]);
+
+        // DCI white encodes to the reference code values 3794/3960/3890
+        let options = DciXyzOptions { white: DciWhite::Dci, ..Default::default() };
+        let xyz = dci_xyz(&src, &options, None);
+        let mut pixel = [0.0f32; 3];
+        xyz.getpixel(0, 0, 0, &mut pixel, WrapMode::Black);
+        let codes: Vec<u32> = pixel.iter().map(|v| (v * 4095.0).round() as u32).collect();
+        assert_eq!(codes, [3794, 3960, 3890]);
+        xyz.getpixel(1, 0, 0, &mut pixel, WrapMode::Black);
+        assert_eq!(pixel, [0.0, 0.0, 0.0]);
+
+        // Y' of white depends only on luminance; D65 white has more blue
+        let d65 = dci_xyz(&src, &DciXyzOptions::default(), None);
+        let mut d65_pixel = [0.0f32; 3];
+        d65.getpixel(0, 0, 0, &mut d65_pixel, WrapMode::Black);
+        assert!((d65_pixel[1] - 3960.0 / 4095.0).abs() < 1e-6);
+        assert!(d65_pixel[2] > pixel[2]);
+
+        // Linear mid grey matches the same grey encoded with gamma 2.6
+        src.setpixel(2, 0, 0, &[0.18, 0.18, 0.18]);
+        src.setpixel(3, 0, 0, &[0.18f32.powf(1.0 / 2.6); 3]);
+        let linear = dci_xyz(&src, &DciXyzOptions { input_gamma: None, ..Default::default() }, None);
+        let gamma = dci_xyz(&src, &DciXyzOptions::default(), None);
+        let mut a = [0.0f32; 3];
+        let mut b = [0.0f32; 3];
+        linear.getpixel(2, 0, 0, &mut a, WrapMode::Black);
+        gamma.getpixel(3, 0, 0, &mut b, WrapMode::Black);
+        for c in 0..3 {
+            assert!((a[c] - b[c]).abs() <= 1.0 / 4095.0 + 1e-6);
+        }
+        assert_eq!(linear.spec().format, vfx_core::DataFormat::F32);
+    }
 }
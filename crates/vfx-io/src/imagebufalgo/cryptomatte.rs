@@ -0,0 +1,497 @@
+//! Cryptomatte decoding and matte extraction.
+//!
+//! Cryptomatte stores object, material or asset IDs as ranked pairs of ID and
+//! coverage channels: `CryptoObject00.R`/`.G`, `CryptoObject00.B`/`.A`,
+//! `CryptoObject01.R`/`.G` and so on. Names are hashed to IDs with
+//! MurmurHash3, and the manifest in the `cryptomatte/<key>/manifest` header
+//! attribute, or in the sidecar file named by `cryptomatte/<key>/manifest_file`,
+//! maps names back to IDs.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use vfx_io::exr;
+//! use vfx_io::imagebufalgo::{cryptomatte_matte, Cryptomatte};
+//!
+//! let image = exr::read_layers("render.exr")?;
+//! let cryptos = Cryptomatte::from_metadata(&image.metadata, Some(Path::new(".")))?;
+//! let crypto = &cryptos[0];
+//! let layer = crypto.find_layer(&image).unwrap();
+//! let matte = cryptomatte_matte(layer, crypto, &["hero*", "/props/chair"])?;
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+use crate::imagebuf::{ImageBuf, InitializePixels};
+use crate::{AttrValue, ChannelSamples, ChannelSampleType, ImageLayer, IoError, IoResult, LayeredImage, Metadata};
+use vfx_core::{DataFormat, ImageSpec};
+
+/// The hash method supported for names that are not in the manifest.
+pub const CRYPTOMATTE_HASH: &str = "MurmurHash3_32";
+
+/// A Cryptomatte layer, as described by the `cryptomatte/<key>/...` metadata.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cryptomatte {
+    /// Metadata key, the first 7 hex digits of the hashed layer name.
+    pub key: String,
+    /// Channel name prefix, e.g. `CryptoObject`.
+    pub name: String,
+    /// Hash method, normally `MurmurHash3_32`.
+    pub hash: String,
+    /// ID conversion, normally `uint32_to_float32`.
+    pub conversion: String,
+    /// Names and their IDs.
+    pub manifest: BTreeMap<String, u32>,
+}
+
+impl Cryptomatte {
+    /// Collects all Cryptomatte layers described in the metadata of an image.
+    ///
+    /// Metadata keys may carry a prefix, like `EXR:cryptomatte/<key>/name`.
+    /// Manifest sidecar files are resolved relative to `sidecar_dir`, and are
+    /// ignored if it is `None`. The layers are sorted by name.
+    pub fn from_metadata(metadata: &Metadata, sidecar_dir: Option<&Path>) -> IoResult<Vec<Self>> {
+        let mut fields: BTreeMap<&str, BTreeMap<&str, &str>> = BTreeMap::new();
+
+        for (key, value) in metadata.attrs.iter() {
+            let AttrValue::Str(value) = value else {
+                continue;
+            };
+            let Some((_, rest)) = key.split_once("cryptomatte/") else {
+                continue;
+            };
+            if let Some((crypto_key, field)) = rest.split_once('/') {
+                fields.entry(crypto_key).or_default().insert(field, value.as_str());
+            }
+        }
+
+        let mut cryptos = Vec::new();
+        for (key, fields) in fields {
+            let Some(&name) = fields.get("name") else {
+                continue;
+            };
+
+            let manifest = if let Some(json) = fields.get("manifest") {
+                parse_manifest(json)?
+            } else if let (Some(file), Some(dir)) = (fields.get("manifest_file"), sidecar_dir) {
+                let json = std::fs::read_to_string(dir.join(file))?;
+                parse_manifest(&json)?
+            } else {
+                BTreeMap::new()
+            };
+
+            cryptos.push(Cryptomatte {
+                key: key.to_string(),
+                name: name.to_string(),
+                hash: fields.get("hash").unwrap_or(&CRYPTOMATTE_HASH).to_string(),
+                conversion: fields.get("conversion").unwrap_or(&"uint32_to_float32").to_string(),
+                manifest,
+            });
+        }
+
+        cryptos.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(cryptos)
+    }
+
+    /// Returns the indices of the ID and coverage channels of each rank in a layer.
+    ///
+    /// Ranks are ordered from the most to the least covering ID.
+    pub fn rank_channels(&self, layer: &ImageLayer) -> Vec<(usize, usize)> {
+        let find = |name: String| layer.channels.iter().position(|c| c.name == name);
+        let mut ranks = Vec::new();
+
+        for index in 0.. {
+            let prefix = format!("{}{:02}", self.name, index);
+            let pairs = [("R", "G"), ("B", "A")];
+            let mut found = false;
+
+            for (id, coverage) in pairs {
+                if let (Some(id), Some(coverage)) = (
+                    find(format!("{}.{}", prefix, id)),
+                    find(format!("{}.{}", prefix, coverage)),
+                ) {
+                    ranks.push((id, coverage));
+                    found = true;
+                }
+            }
+
+            if !found {
+                break;
+            }
+        }
+
+        ranks
+    }
+
+    /// Finds the layer of an image that contains the ID channels.
+    pub fn find_layer<'a>(&self, image: &'a LayeredImage) -> Option<&'a ImageLayer> {
+        image.layers.iter().find(|layer| !self.rank_channels(layer).is_empty())
+    }
+
+    /// Returns the IDs for a list of names or wildcard patterns.
+    ///
+    /// Patterns are matched against the manifest, with `*` for any sequence
+    /// and `?` for a single character. Plain names missing from the manifest
+    /// are hashed, so mattes can be pulled without a manifest.
+    pub fn ids_matching(&self, patterns: &[&str]) -> Vec<u32> {
+        let mut ids = Vec::new();
+
+        for pattern in patterns {
+            if pattern.contains(['*', '?']) {
+                let pattern: Vec<char> = pattern.chars().collect();
+                ids.extend(
+                    self.manifest
+                        .iter()
+                        .filter(|(name, _)| wildcard_match(&pattern, &name.chars().collect::<Vec<_>>()))
+                        .map(|(_, &id)| id),
+                );
+            } else {
+                ids.push(self.manifest.get(*pattern).copied().unwrap_or_else(|| cryptomatte_hash(pattern)));
+            }
+        }
+
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Returns the manifest name of an ID.
+    pub fn name_of(&self, id: u32) -> Option<&str> {
+        self.manifest.iter().find(|(_, other)| **other == id).map(|(name, _)| name.as_str())
+    }
+}
+
+/// Hashes a name to a Cryptomatte ID.
+///
+/// This is MurmurHash3 with seed 0, where IDs that would be an infinite,
+/// NaN or denormal float have one exponent bit flipped.
+pub fn cryptomatte_hash(name: &str) -> u32 {
+    let hash = murmurhash3_32(name.as_bytes(), 0);
+    let exponent = (hash >> 23) & 0xff;
+
+    if exponent == 0 || exponent == 0xff {
+        hash ^ (1 << 23)
+    } else {
+        hash
+    }
+}
+
+/// MurmurHash3, x86 32-bit variant.
+pub fn murmurhash3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+
+    let mix = |k: u32| k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    let mut hash = seed;
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        hash ^= mix(k);
+        hash = hash.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let k = tail.iter().rev().fold(0u32, |k, &byte| (k << 8) | byte as u32);
+        hash ^= mix(k);
+    }
+
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^ (hash >> 16)
+}
+
+/// Parses a Cryptomatte manifest, a JSON object of names and hex IDs.
+pub fn parse_manifest(json: &str) -> IoResult<BTreeMap<String, u32>> {
+    let invalid = || IoError::DecodeError("Invalid Cryptomatte manifest".into());
+    let mut chars = json.chars().peekable();
+    let mut manifest = BTreeMap::new();
+
+    fn next_token(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<char> {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        chars.next()
+    }
+
+    if next_token(&mut chars) != Some('{') {
+        return Err(invalid());
+    }
+
+    loop {
+        let name = match next_token(&mut chars) {
+            Some('}') if manifest.is_empty() => break,
+            Some('"') => parse_json_string(&mut chars).ok_or_else(invalid)?,
+            _ => return Err(invalid()),
+        };
+
+        if next_token(&mut chars) != Some(':') || next_token(&mut chars) != Some('"') {
+            return Err(invalid());
+        }
+        let id = parse_json_string(&mut chars).ok_or_else(invalid)?;
+        let id = u32::from_str_radix(id.trim_start_matches("0x"), 16).map_err(|_| invalid())?;
+        manifest.insert(name, id);
+
+        match next_token(&mut chars) {
+            Some(',') => continue,
+            Some('}') => break,
+            _ => return Err(invalid()),
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// Parses the rest of a JSON string, after the opening quote.
+fn parse_json_string(chars: &mut impl Iterator<Item = char>) -> Option<String> {
+    let mut text = String::new();
+    let mut pending_surrogate = None;
+
+    loop {
+        let c = match chars.next()? {
+            '"' => return Some(text),
+            '\\' => match chars.next()? {
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'u' => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    let unit = u32::from_str_radix(&hex, 16).ok()?;
+
+                    if (0xd800..0xdc00).contains(&unit) {
+                        pending_surrogate = Some(unit);
+                        continue;
+                    }
+
+                    match pending_surrogate.take() {
+                        Some(high) => char::from_u32(0x10000 + ((high - 0xd800) << 10) + (unit - 0xdc00))?,
+                        None => char::from_u32(unit)?,
+                    }
+                }
+                other => other,
+            },
+            other => other,
+        };
+
+        text.push(c);
+    }
+}
+
+/// Matches text against a pattern with `*` and `?` wildcards.
+fn wildcard_match(pattern: &[char], text: &[char]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Returns the ID samples and coverage samples of each rank.
+fn rank_samples<'a>(layer: &'a ImageLayer, crypto: &Cryptomatte) -> IoResult<Vec<(&'a [f32], &'a [f32])>> {
+    let ranks = crypto.rank_channels(layer);
+    if ranks.is_empty() {
+        return Err(IoError::DecodeError(format!(
+            "No Cryptomatte channels for '{}' in layer '{}'",
+            crypto.name, layer.name
+        )));
+    }
+
+    ranks
+        .into_iter()
+        .map(|(id, coverage)| {
+            let (id, coverage) = (&layer.channels[id], &layer.channels[coverage]);
+            match (id.sample_type, &id.samples, &coverage.samples) {
+                (ChannelSampleType::F32, ChannelSamples::F32(ids), ChannelSamples::F32(coverage)) => {
+                    Ok((ids.as_slice(), coverage.as_slice()))
+                }
+                _ => Err(IoError::DecodeError(format!(
+                    "Cryptomatte channel '{}' must be 32-bit float",
+                    id.name
+                ))),
+            }
+        })
+        .collect()
+}
+
+/// Creates an alpha matte from the coverage of the given names or patterns.
+///
+/// The coverage of all matching IDs is summed over all ranks.
+/// The result has a single `A` channel.
+pub fn cryptomatte_matte(layer: &ImageLayer, crypto: &Cryptomatte, patterns: &[&str]) -> IoResult<ImageBuf> {
+    let ranks = rank_samples(layer, crypto)?;
+    let ids: HashSet<u32> = crypto.ids_matching(patterns).into_iter().collect();
+
+    let mut spec = ImageSpec::new(layer.width, layer.height, 1, DataFormat::F32);
+    spec.channel_names = vec!["A".to_string()];
+    spec.alpha_channel = 0;
+    let mut dst = ImageBuf::new(spec, InitializePixels::Yes);
+
+    for y in 0..layer.height {
+        for x in 0..layer.width {
+            let index = (y * layer.width + x) as usize;
+            let alpha: f32 = ranks
+                .iter()
+                .filter(|(id, _)| ids.contains(&id[index].to_bits()))
+                .map(|(_, coverage)| coverage[index])
+                .sum();
+
+            dst.setpixel(x as i32, y as i32, 0, &[alpha.min(1.0)]);
+        }
+    }
+
+    Ok(dst)
+}
+
+/// Returns a stable preview color for an ID.
+pub fn cryptomatte_id_color(id: u32) -> [f32; 3] {
+    [
+        (id & 0xff) as f32 / 255.0,
+        ((id >> 8) & 0xff) as f32 / 255.0,
+        ((id >> 16) & 0x7f) as f32 / 127.0,
+    ]
+}
+
+/// Creates an RGB preview where every ID has its own color, weighted by coverage.
+pub fn cryptomatte_preview(layer: &ImageLayer, crypto: &Cryptomatte) -> IoResult<ImageBuf> {
+    let ranks = rank_samples(layer, crypto)?;
+
+    let mut spec = ImageSpec::rgb(layer.width, layer.height);
+    spec.format = DataFormat::F32;
+    let mut dst = ImageBuf::new(spec, InitializePixels::Yes);
+
+    for y in 0..layer.height {
+        for x in 0..layer.width {
+            let index = (y * layer.width + x) as usize;
+            let mut color = [0.0f32; 3];
+
+            for (id, coverage) in &ranks {
+                let id_color = cryptomatte_id_color(id[index].to_bits());
+                for (value, id_value) in color.iter_mut().zip(id_color) {
+                    *value += id_value * coverage[index];
+                }
+            }
+
+            dst.setpixel(x as i32, y as i32, 0, &color);
+        }
+    }
+
+    Ok(dst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChannelKind, ImageChannel};
+
+    #[test]
+    fn test_murmurhash3() {
+        assert_eq!(murmurhash3_32(b"", 0), 0);
+        assert_eq!(murmurhash3_32(b"hello", 0), 0x248b_fa47);
+        assert_eq!(murmurhash3_32(b"The quick brown fox jumps over the lazy dog", 0), 0x2e4f_f723);
+    }
+
+    #[test]
+    fn test_hash_is_finite_float() {
+        for name in ["", "bunny", "/obj/geo1", "material_01"] {
+            let id = f32::from_bits(cryptomatte_hash(name));
+            assert!(id.is_normal() || id == 0.0, "{} -> {}", name, id);
+        }
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = parse_manifest(r#" {"bunny": "13851a76", "a \"b\" é": "0x00000001"} "#).unwrap();
+        assert_eq!(manifest["bunny"], 0x1385_1a76);
+        assert_eq!(manifest["a \"b\" é"], 1);
+        assert!(parse_manifest("{}").unwrap().is_empty());
+        assert!(parse_manifest("{\"a\": 1}").is_err());
+    }
+
+    #[test]
+    fn test_wildcards() {
+        let matches = |pattern: &str, text: &str| {
+            wildcard_match(&pattern.chars().collect::<Vec<_>>(), &text.chars().collect::<Vec<_>>())
+        };
+        assert!(matches("hero*", "hero_body"));
+        assert!(matches("*body", "hero_body"));
+        assert!(matches("h?ro*y", "hero_body"));
+        assert!(!matches("hero", "hero_body"));
+        assert!(matches("*", ""));
+    }
+
+    #[test]
+    fn test_matte_from_ranks() {
+        let hero = cryptomatte_hash("hero");
+        let prop = cryptomatte_hash("prop");
+        let channel = |name: &str, samples: Vec<f32>| ImageChannel {
+            name: name.to_string(),
+            kind: ChannelKind::Generic,
+            sample_type: ChannelSampleType::F32,
+            samples: ChannelSamples::F32(samples),
+            sampling: (1, 1),
+            quantize_linearly: false,
+        };
+        let id = |id: u32| f32::from_bits(id);
+
+        // two pixels: hero over prop, and prop over hero
+        let layer = ImageLayer {
+            name: "crypto".to_string(),
+            width: 2,
+            height: 1,
+            channels: vec![
+                channel("CryptoObject00.R", vec![id(hero), id(prop)]),
+                channel("CryptoObject00.G", vec![0.75, 0.6]),
+                channel("CryptoObject00.B", vec![id(prop), id(hero)]),
+                channel("CryptoObject00.A", vec![0.25, 0.4]),
+            ],
+        };
+
+        let mut metadata = Metadata::default();
+        metadata.attrs.set("EXR:cryptomatte/abcdef0/name", AttrValue::Str("CryptoObject".into()));
+        metadata.attrs.set(
+            "EXR:cryptomatte/abcdef0/manifest",
+            AttrValue::Str(format!("{{\"hero\":\"{:08x}\",\"prop\":\"{:08x}\"}}", hero, prop)),
+        );
+
+        let cryptos = Cryptomatte::from_metadata(&metadata, None).unwrap();
+        assert_eq!(cryptos.len(), 1);
+        assert_eq!(cryptos[0].rank_channels(&layer), [(0, 1), (2, 3)]);
+        assert_eq!(cryptos[0].name_of(prop), Some("prop"));
+
+        let matte = cryptomatte_matte(&layer, &cryptos[0], &["her*"]).unwrap();
+        let mut pixel = [0.0f32];
+        matte.getpixel(0, 0, 0, &mut pixel, crate::imagebuf::WrapMode::Clamp);
+        assert_eq!(pixel[0], 0.75);
+        matte.getpixel(1, 0, 0, &mut pixel, crate::imagebuf::WrapMode::Clamp);
+        assert_eq!(pixel[0], 0.4);
+
+        let matte = cryptomatte_matte(&layer, &cryptos[0], &["hero", "prop"]).unwrap();
+        matte.getpixel(1, 0, 0, &mut pixel, crate::imagebuf::WrapMode::Clamp);
+        assert_eq!(pixel[0], 1.0);
+    }
+}
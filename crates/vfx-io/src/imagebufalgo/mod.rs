@@ -14,6 +14,7 @@
 //! - [`stats`] - Statistics and analysis (histogram, compare, min/max)
 //! - [`ocio`] - OCIO color conversion (colorconvert, ociodisplay, ociolook)
 //! - [`fft`] - Fast Fourier Transform operations
+//! - [`cryptomatte`] - Cryptomatte manifests, mattes and previews
 //!
 //! # Example
 //!
@@ -48,6 +49,7 @@ pub mod warp;
 pub mod demosaic;
 pub mod texture;
 pub mod fillholes;
+pub mod cryptomatte;
 
 #[cfg(feature = "text")]
 pub mod text;
@@ -73,6 +75,7 @@ pub use color::{
     colormatrixtransform,
     rangecompress, rangeexpand,
     srgb_to_linear, linear_to_srgb,
+    dci_xyz, DciWhite, DciXyzOptions,
 };
 
 // Compositing operations
@@ -172,6 +175,14 @@ pub use fillholes::{
     FillHolesOptions,
 };
 
+// Cryptomatte operations
+pub use cryptomatte::{
+    cryptomatte_matte, cryptomatte_preview,
+    cryptomatte_hash, cryptomatte_id_color,
+    murmurhash3_32, parse_manifest,
+    Cryptomatte,
+};
+
 // Text rendering (optional)
 #[cfg(feature = "text")]
 pub use text::{
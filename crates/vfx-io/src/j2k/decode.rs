@@ -0,0 +1,943 @@
+//! Reference JPEG 2000 decoder for the writer's tests.
+//!
+//! Written from ISO/IEC 15444-1 independently of the encoder's tier-1 and
+//! wavelet code: contexts come from direct neighbour scans and the inverse
+//! transforms reflect indices instead of padding lines. Only the tile,
+//! precinct and packet-order geometry is shared. It handles what the writer
+//! produces: one quality layer, the default code-block style and no
+//! component subsampling.
+
+use super::t2::{self, Rect, ResolutionGeom};
+use super::ProgressionOrder;
+use crate::{IoError, IoResult};
+
+/// A decoded image: interleaved unsigned samples at `precision` bits.
+pub(crate) struct Decoded {
+    pub precision: u8,
+    pub samples: Vec<i32>,
+}
+
+impl Decoded {
+    pub fn to_u8(&self) -> Vec<u8> {
+        self.samples.iter().map(|&v| v as u8).collect()
+    }
+
+    pub fn to_f32(&self) -> Vec<f32> {
+        let max = ((1u32 << self.precision) - 1) as f32;
+        self.samples.iter().map(|&v| v as f32 / max).collect()
+    }
+}
+
+fn invalid(msg: &str) -> IoError {
+    IoError::DecodeError(format!("JPEG 2000: {}", msg))
+}
+
+// ============================================================================
+// Marker Parsing
+// ============================================================================
+
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> IoResult<&'a [u8]> {
+        let out = self.data.get(self.pos..self.pos + n).ok_or_else(|| invalid("truncated"))?;
+        self.pos += n;
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> IoResult<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> IoResult<u16> {
+        Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> IoResult<u32> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+#[derive(Default)]
+struct Header {
+    width: u32,
+    height: u32,
+    tile: (u32, u32),
+    components: usize,
+    precision: u8,
+    order: u8,
+    mct: bool,
+    levels: u8,
+    code_block: (u8, u8),
+    precincts: Option<Vec<(u8, u8)>>,
+    reversible: bool,
+    guard_bits: u8,
+    /// (exponent, mantissa) per band in QCD order.
+    steps: Vec<(u8, u16)>,
+    /// (resolutions, components, order) per POC entry.
+    pocs: Vec<(std::ops::Range<usize>, std::ops::Range<usize>, u8)>,
+}
+
+impl Header {
+    fn parse(&mut self, marker: u16, body: &[u8]) -> IoResult<()> {
+        let mut r = Cursor { data: body, pos: 0 };
+        match marker {
+            0xFF51 => {
+                r.u16()?;
+                self.width = r.u32()?;
+                self.height = r.u32()?;
+                let (x0, y0) = (r.u32()?, r.u32()?);
+                self.tile = (r.u32()?, r.u32()?);
+                let (tx0, ty0) = (r.u32()?, r.u32()?);
+                if x0 | y0 | tx0 | ty0 != 0 {
+                    return Err(invalid("image and tile origins must be 0"));
+                }
+                self.components = r.u16()? as usize;
+                for c in 0..self.components {
+                    let ssiz = r.u8()?;
+                    if ssiz & 0x80 != 0 || r.u8()? != 1 || r.u8()? != 1 {
+                        return Err(invalid("signed or subsampled components"));
+                    }
+                    if c == 0 {
+                        self.precision = (ssiz & 0x7F) + 1;
+                    }
+                }
+            }
+            0xFF52 => {
+                let scod = r.u8()?;
+                self.order = r.u8()?;
+                if r.u16()? != 1 {
+                    return Err(invalid("one quality layer expected"));
+                }
+                self.mct = r.u8()? != 0;
+                self.levels = r.u8()?;
+                self.code_block = (r.u8()? + 2, r.u8()? + 2);
+                if r.u8()? != 0 {
+                    return Err(invalid("code-block style"));
+                }
+                self.reversible = r.u8()? == 1;
+                if scod & 1 != 0 {
+                    let sizes = (0..=self.levels)
+                        .map(|_| r.u8().map(|b| (b & 0x0F, b >> 4)))
+                        .collect::<IoResult<_>>()?;
+                    self.precincts = Some(sizes);
+                }
+            }
+            0xFF5C => {
+                let sqcd = r.u8()?;
+                self.guard_bits = sqcd >> 5;
+                while r.pos < body.len() {
+                    let step = match sqcd & 0x1F {
+                        0 => (r.u8()? >> 3, 0),
+                        2 => {
+                            let v = r.u16()?;
+                            ((v >> 11) as u8, v & 0x7FF)
+                        }
+                        _ => return Err(invalid("quantization style")),
+                    };
+                    self.steps.push(step);
+                }
+            }
+            0xFF5F => {
+                while r.pos < body.len() {
+                    let (rs, cs) = (r.u8()? as usize, r.u8()? as usize);
+                    r.u16()?;
+                    let (re, ce) = (r.u8()? as usize, r.u8()? as usize);
+                    self.pocs.push((rs..re, cs..ce, r.u8()?));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Step size and magnitude bit-planes of band `index` (E.1).
+    fn quantizer(&self, index: usize, orient: u8) -> (f64, u32) {
+        let gain = [0, 1, 1, 2][orient as usize];
+        let (exponent, mantissa) = self.steps[index];
+        let range = self.precision as i32 + gain;
+        let step = 2f64.powi(range - exponent as i32) * (1.0 + mantissa as f64 / 2048.0);
+        let planes = self.guard_bits as u32 + exponent as u32 - 1;
+        if self.reversible {
+            (1.0, planes)
+        } else {
+            (step, planes)
+        }
+    }
+}
+
+fn order_from_code(code: u8) -> IoResult<ProgressionOrder> {
+    Ok(match code {
+        0 => ProgressionOrder::Lrcp,
+        1 => ProgressionOrder::Rlcp,
+        2 => ProgressionOrder::Rpcl,
+        3 => ProgressionOrder::Pcrl,
+        4 => ProgressionOrder::Cprl,
+        _ => return Err(invalid("progression order")),
+    })
+}
+
+/// Decodes a raw codestream.
+pub(crate) fn decode(stream: &[u8]) -> IoResult<Decoded> {
+    let mut r = Cursor { data: stream, pos: 0 };
+    if r.u16()? != 0xFF4F {
+        return Err(invalid("missing SOC"));
+    }
+    let mut header = Header::default();
+    let mut tiles: Vec<Vec<u8>> = Vec::new();
+
+    loop {
+        let start = r.pos;
+        match r.u16()? {
+            0xFF90 => {
+                r.u16()?;
+                let index = r.u16()? as usize;
+                let psot = r.u32()? as usize;
+                r.u16()?;
+                if r.u16()? != 0xFF93 {
+                    return Err(invalid("expected SOD"));
+                }
+                let body = r.take(start + psot - r.pos)?;
+                if tiles.len() <= index {
+                    tiles.resize(index + 1, Vec::new());
+                }
+                tiles[index].extend_from_slice(body);
+            }
+            0xFFD9 => break,
+            marker => {
+                let len = r.u16()? as usize;
+                let body = r.take(len - 2)?;
+                header.parse(marker, body)?;
+            }
+        }
+    }
+
+    let (w, h) = (header.width as usize, header.height as usize);
+    let nc = header.components;
+    let mut samples = vec![0; w * h * nc];
+    let tiles_x = header.width.div_ceil(header.tile.0);
+    for (index, data) in tiles.iter().enumerate() {
+        let (tx, ty) = (index as u32 % tiles_x, index as u32 / tiles_x);
+        let rect = Rect::new(
+            tx * header.tile.0,
+            ty * header.tile.1,
+            ((tx + 1) * header.tile.0).min(header.width),
+            ((ty + 1) * header.tile.1).min(header.height),
+        );
+        let planes = decode_tile(&header, rect, data)?;
+        let tw = rect.width() as usize;
+        for (c, plane) in planes.iter().enumerate() {
+            for (i, &v) in plane.iter().enumerate() {
+                let (x, y) = (rect.x0 as usize + i % tw, rect.y0 as usize + i / tw);
+                samples[(y * w + x) * nc + c] = v;
+            }
+        }
+    }
+
+    Ok(Decoded { precision: header.precision, samples })
+}
+
+// ============================================================================
+// Tier 2
+// ============================================================================
+
+#[derive(Clone, Default)]
+struct BlockData {
+    data: Vec<u8>,
+    passes: usize,
+    missing_msbs: u32,
+}
+
+/// Packet header bit reader; a byte after 0xFF carries only 7 bits.
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    current: u8,
+    left: u8,
+}
+
+impl BitReader<'_> {
+    fn bit(&mut self) -> IoResult<u32> {
+        if self.left == 0 {
+            let previous = if self.pos > 0 { self.data[self.pos - 1] } else { 0 };
+            self.current = *self.data.get(self.pos).ok_or_else(|| invalid("truncated packet header"))?;
+            self.pos += 1;
+            self.left = if previous == 0xFF { 7 } else { 8 };
+        }
+        self.left -= 1;
+        Ok(((self.current >> self.left) & 1) as u32)
+    }
+
+    fn bits(&mut self, n: u32) -> IoResult<u32> {
+        let mut v = 0;
+        for _ in 0..n {
+            v = (v << 1) | self.bit()?;
+        }
+        Ok(v)
+    }
+
+    /// Header length, including the zero byte that follows a final 0xFF.
+    fn end(&self) -> usize {
+        if self.pos > 0 && self.data[self.pos - 1] == 0xFF {
+            self.pos + 1
+        } else {
+            self.pos
+        }
+    }
+}
+
+/// Tag tree decoder (B.10.2).
+struct TagTree {
+    value: Vec<u32>,
+    low: Vec<u32>,
+    parent: Vec<Option<usize>>,
+}
+
+impl TagTree {
+    fn new(width: usize, height: usize) -> Self {
+        let mut parent = Vec::new();
+        let (mut w, mut h, mut start) = (width, height, 0);
+        while w * h > 1 {
+            let (pw, ph) = ((w + 1) / 2, (h + 1) / 2);
+            for y in 0..h {
+                for x in 0..w {
+                    parent.push(Some(start + w * h + (y / 2) * pw + x / 2));
+                }
+            }
+            start += w * h;
+            (w, h) = (pw, ph);
+        }
+        parent.push(None);
+        let n = parent.len();
+        Self { value: vec![u32::MAX; n], low: vec![0; n], parent }
+    }
+
+    /// Returns whether the leaf's value is below `threshold`.
+    fn decode(&mut self, br: &mut BitReader, leaf: usize, threshold: u32) -> IoResult<bool> {
+        let mut path = vec![leaf];
+        while let Some(p) = self.parent[*path.last().unwrap()] {
+            path.push(p);
+        }
+        let mut low = 0;
+        for &node in path.iter().rev() {
+            low = low.max(self.low[node]);
+            while low < threshold && low < self.value[node] {
+                if br.bit()? == 1 {
+                    self.value[node] = low;
+                } else {
+                    low += 1;
+                }
+            }
+            self.low[node] = low;
+        }
+        Ok(self.value[leaf] < threshold)
+    }
+}
+
+/// Number of coding passes (Table B.4).
+fn read_pass_count(br: &mut BitReader) -> IoResult<usize> {
+    if br.bit()? == 0 {
+        return Ok(1);
+    }
+    if br.bit()? == 0 {
+        return Ok(2);
+    }
+    let v = br.bits(2)?;
+    if v < 3 {
+        return Ok(3 + v as usize);
+    }
+    let v = br.bits(5)?;
+    if v < 31 {
+        return Ok(6 + v as usize);
+    }
+    Ok(37 + br.bits(7)? as usize)
+}
+
+/// Reads the packet of `precinct` at `pos`, returning the next position.
+fn read_packet(
+    data: &[u8],
+    pos: usize,
+    res: &ResolutionGeom,
+    precinct: usize,
+    bands: &mut [Vec<BlockData>],
+) -> IoResult<usize> {
+    let mut br = BitReader { data: &data[pos..], pos: 0, current: 0, left: 0 };
+    let mut lengths = Vec::new();
+
+    if br.bit()? == 1 {
+        for (b, band) in res.bands.iter().enumerate() {
+            let range = res.precincts[precinct].blocks[b];
+            if range.is_empty() {
+                continue;
+            }
+            let (w, h) = (range.width() as usize, range.height() as usize);
+            let grid = band.block_grid();
+            let mut inclusion = TagTree::new(w, h);
+            let mut msbs = TagTree::new(w, h);
+            for i in 0..w * h {
+                if !inclusion.decode(&mut br, i, 1)? {
+                    continue;
+                }
+                let mut threshold = 1;
+                while !msbs.decode(&mut br, i, threshold)? {
+                    threshold += 1;
+                }
+                let passes = read_pass_count(&mut br)?;
+                let mut lblock = 3;
+                while br.bit()? == 1 {
+                    lblock += 1;
+                }
+                let len = br.bits(lblock + passes.ilog2())? as usize;
+
+                let (cbx, cby) = (range.x0 + (i % w) as u32, range.y0 + (i / w) as u32);
+                let index = ((cby - grid.y0) * grid.width() + cbx - grid.x0) as usize;
+                bands[b][index] = BlockData { data: Vec::new(), passes, missing_msbs: msbs.value[i] };
+                lengths.push((b, index, len));
+            }
+        }
+    }
+
+    let mut next = pos + br.end();
+    for (b, index, len) in lengths {
+        let body = data.get(next..next + len).ok_or_else(|| invalid("truncated packet body"))?;
+        bands[b][index].data = body.to_vec();
+        next += len;
+    }
+    Ok(next)
+}
+
+fn decode_tile(header: &Header, rect: Rect, data: &[u8]) -> IoResult<Vec<Vec<i32>>> {
+    let nc = header.components;
+    let levels = header.levels as usize;
+    let precincts = header.precincts.clone().unwrap_or_else(|| vec![(15, 15); levels + 1]);
+    let geoms: Vec<Vec<ResolutionGeom>> = (0..nc)
+        .map(|_| t2::tile_component(rect, header.levels, header.code_block, &precincts))
+        .collect();
+    let mut blocks: Vec<Vec<Vec<Vec<BlockData>>>> = geoms
+        .iter()
+        .map(|comp| {
+            comp.iter()
+                .map(|res| {
+                    res.bands
+                        .iter()
+                        .map(|b| {
+                            let grid = b.block_grid();
+                            vec![BlockData::default(); (grid.width() * grid.height()) as usize]
+                        })
+                        .collect()
+                })
+                .collect()
+        })
+        .collect();
+
+    let progressions = if header.pocs.is_empty() {
+        vec![(0..levels + 1, 0..nc, header.order)]
+    } else {
+        header.pocs.clone()
+    };
+    let mut pos = 0;
+    for (resolutions, components, order) in progressions {
+        for packet in t2::packet_order(&geoms, order_from_code(order)?, resolutions, components) {
+            let res = &geoms[packet.comp][packet.res];
+            pos = read_packet(data, pos, res, packet.precinct, &mut blocks[packet.comp][packet.res])?;
+        }
+    }
+
+    let (w, h) = (rect.width() as usize, rect.height() as usize);
+    let mut planes: Vec<Vec<f64>> = Vec::with_capacity(nc);
+    for (geom, comp_blocks) in geoms.iter().zip(&blocks) {
+        let mut coeffs = vec![0.0; w * h];
+        for (r, res) in geom.iter().enumerate() {
+            for (b, band) in res.bands.iter().enumerate() {
+                let index = if r == 0 { 0 } else { 1 + 3 * (r - 1) + band.orient as usize - 1 };
+                let (step, mb) = header.quantizer(index, band.orient);
+                let grid = band.block_grid();
+                for (i, block) in comp_blocks[r][b].iter().enumerate() {
+                    if block.passes == 0 {
+                        continue;
+                    }
+                    let (cbx, cby) = (grid.x0 + i as u32 % grid.width(), grid.y0 + i as u32 / grid.width());
+                    let area = band.block_rect(cbx, cby);
+                    let (bw, bh) = (area.width() as usize, area.height() as usize);
+                    let top = mb as i32 - block.missing_msbs as i32 - 1;
+                    let values = decode_block(block, bw, bh, band.orient, top, header.reversible);
+                    for y in 0..bh {
+                        for x in 0..bw {
+                            let tx = (band.layout_x + area.x0 - band.rect.x0) as usize + x;
+                            let ty = (band.layout_y + area.y0 - band.rect.y0) as usize + y;
+                            coeffs[ty * w + tx] = values[y * bw + x] * step;
+                        }
+                    }
+                }
+            }
+        }
+
+        let rects: Vec<Rect> = (0..=levels).map(|r| rect.scaled_down((levels - r) as u32)).collect();
+        if header.reversible {
+            let mut ints: Vec<i32> = coeffs.iter().map(|v| v.round() as i32).collect();
+            inverse_dwt(&mut ints, w, &rects, inverse_53);
+            planes.push(ints.into_iter().map(f64::from).collect());
+        } else {
+            inverse_dwt(&mut coeffs, w, &rects, inverse_97);
+            planes.push(coeffs);
+        }
+    }
+
+    if header.mct && nc >= 3 {
+        for i in 0..w * h {
+            let (y0, y1, y2) = (planes[0][i], planes[1][i], planes[2][i]);
+            let (r, g, b) = if header.reversible {
+                let g = y0 - ((y1 + y2) / 4.0).floor();
+                (y2 + g, g, y1 + g)
+            } else {
+                (y0 + 1.402 * y2, y0 - 0.34413 * y1 - 0.71414 * y2, y0 + 1.772 * y1)
+            };
+            planes[0][i] = r;
+            planes[1][i] = g;
+            planes[2][i] = b;
+        }
+    }
+
+    let shift = 1i32 << (header.precision - 1);
+    let max = (1i32 << header.precision) - 1;
+    Ok(planes
+        .into_iter()
+        .map(|p| p.into_iter().map(|v| (v.round() as i32 + shift).clamp(0, max)).collect())
+        .collect())
+}
+
+// ============================================================================
+// Tier 1
+// ============================================================================
+
+const QE: [u32; 47] = [
+    0x5601, 0x3401, 0x1801, 0x0AC1, 0x0521, 0x0221, 0x5601, 0x5401, 0x4801, 0x3801, 0x3001, 0x2401,
+    0x1C01, 0x1601, 0x5601, 0x5401, 0x5101, 0x4801, 0x3801, 0x3401, 0x3001, 0x2801, 0x2401, 0x2201,
+    0x1C01, 0x1801, 0x1601, 0x1401, 0x1201, 0x1101, 0x0AC1, 0x09C1, 0x08A1, 0x0521, 0x0441, 0x02A1,
+    0x0221, 0x0141, 0x0111, 0x0085, 0x0049, 0x0025, 0x0015, 0x0009, 0x0005, 0x0001, 0x5601,
+];
+const NMPS: [u8; 47] = [
+    1, 2, 3, 4, 5, 38, 7, 8, 9, 10, 11, 12, 13, 29, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26,
+    27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 45, 46,
+];
+const NLPS: [u8; 47] = [
+    1, 6, 9, 12, 29, 33, 6, 14, 14, 14, 17, 18, 20, 21, 14, 14, 15, 16, 17, 18, 19, 19, 20, 21, 22,
+    23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 46,
+];
+
+const RUN_LENGTH: usize = 17;
+const UNIFORM: usize = 18;
+
+/// MQ arithmetic decoder (C.3); reads 0xFF past the end of the data.
+struct MqDecoder<'a> {
+    data: &'a [u8],
+    pos: usize,
+    a: u32,
+    c: u32,
+    ct: u32,
+    state: [usize; 19],
+    mps: [u32; 19],
+}
+
+impl<'a> MqDecoder<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        let mut state = [0; 19];
+        state[0] = 4;
+        state[RUN_LENGTH] = 3;
+        state[UNIFORM] = 46;
+        let mut dec = Self { data, pos: 0, a: 0, c: 0, ct: 0, state, mps: [0; 19] };
+        dec.c = (dec.byte(0) as u32) << 16;
+        dec.byte_in();
+        dec.c <<= 7;
+        dec.ct -= 7;
+        dec.a = 0x8000;
+        dec
+    }
+
+    fn byte(&self, i: usize) -> u8 {
+        self.data.get(i).copied().unwrap_or(0xFF)
+    }
+
+    fn byte_in(&mut self) {
+        if self.byte(self.pos) == 0xFF {
+            let next = self.byte(self.pos + 1);
+            if next > 0x8F {
+                self.c = self.c.wrapping_add(0xFF00);
+                self.ct = 8;
+            } else {
+                self.pos += 1;
+                self.c = self.c.wrapping_add((next as u32) << 9);
+                self.ct = 7;
+            }
+        } else {
+            self.pos += 1;
+            self.c = self.c.wrapping_add((self.byte(self.pos) as u32) << 8);
+            self.ct = 8;
+        }
+    }
+
+    fn decode(&mut self, cx: usize) -> u32 {
+        let s = self.state[cx];
+        let qe = QE[s];
+        let mps = self.mps[cx];
+        self.a -= qe;
+        let d;
+        if (self.c >> 16) < qe {
+            // LPS exchange
+            if self.a < qe {
+                d = mps;
+                self.state[cx] = NMPS[s] as usize;
+            } else {
+                d = 1 - mps;
+                if matches!(s, 0 | 6 | 14) {
+                    self.mps[cx] = 1 - mps;
+                }
+                self.state[cx] = NLPS[s] as usize;
+            }
+            self.a = qe;
+        } else {
+            self.c -= qe << 16;
+            if self.a & 0x8000 != 0 {
+                return mps;
+            }
+            // MPS exchange
+            if self.a < qe {
+                d = 1 - mps;
+                if matches!(s, 0 | 6 | 14) {
+                    self.mps[cx] = 1 - mps;
+                }
+                self.state[cx] = NLPS[s] as usize;
+            } else {
+                d = mps;
+                self.state[cx] = NMPS[s] as usize;
+            }
+        }
+        while self.a & 0x8000 == 0 {
+            if self.ct == 0 {
+                self.byte_in();
+            }
+            self.a <<= 1;
+            self.c <<= 1;
+            self.ct -= 1;
+        }
+        d
+    }
+}
+
+struct BlockDecoder<'a> {
+    mq: MqDecoder<'a>,
+    width: usize,
+    height: usize,
+    orient: u8,
+    sig: Vec<bool>,
+    negative: Vec<bool>,
+    visited: Vec<bool>,
+    refined: Vec<bool>,
+    magnitude: Vec<u64>,
+    /// Lowest bit-plane decoded for each significant sample.
+    last_plane: Vec<i32>,
+}
+
+impl BlockDecoder<'_> {
+    fn is_sig(&self, x: usize, y: usize, dx: isize, dy: isize) -> bool {
+        let (nx, ny) = (x as isize + dx, y as isize + dy);
+        nx >= 0
+            && ny >= 0
+            && (nx as usize) < self.width
+            && (ny as usize) < self.height
+            && self.sig[ny as usize * self.width + nx as usize]
+    }
+
+    /// Zero coding context (Table D.1).
+    fn zero_context(&self, x: usize, y: usize) -> usize {
+        let s = |dx, dy| self.is_sig(x, y, dx, dy) as u32;
+        let mut h = s(-1, 0) + s(1, 0);
+        let mut v = s(0, -1) + s(0, 1);
+        let d = s(-1, -1) + s(1, -1) + s(-1, 1) + s(1, 1);
+        if self.orient == 1 {
+            std::mem::swap(&mut h, &mut v);
+        }
+        if self.orient == 3 {
+            let hv = (h + v).min(2) as usize;
+            return match d {
+                0 => hv,
+                1 => 3 + hv,
+                2 => 6 + (hv > 0) as usize,
+                _ => 8,
+            };
+        }
+        match (h, v) {
+            (2, _) => 8,
+            (1, v) if v > 0 => 7,
+            (1, _) => 5 + (d > 0) as usize,
+            (0, 2) => 4,
+            (0, 1) => 3,
+            _ => d.min(2) as usize,
+        }
+    }
+
+    /// Sign coding context and XOR bit (Table D.3).
+    fn sign_context(&self, x: usize, y: usize) -> (usize, u32) {
+        let c = |dx, dy| -> i32 {
+            if !self.is_sig(x, y, dx, dy) {
+                0
+            } else if self.negative[(y as isize + dy) as usize * self.width + (x as isize + dx) as usize] {
+                -1
+            } else {
+                1
+            }
+        };
+        let h = (c(-1, 0) + c(1, 0)).clamp(-1, 1);
+        let v = (c(0, -1) + c(0, 1)).clamp(-1, 1);
+        let (h, v, xor) = if h < 0 || (h == 0 && v < 0) { (-h, -v, 1) } else { (h, v, 0) };
+        let ctx = match (h, v) {
+            (0, 0) => 9,
+            (0, _) => 10,
+            (_, -1) => 11,
+            (_, 0) => 12,
+            _ => 13,
+        };
+        (ctx, xor)
+    }
+
+    fn has_sig_neighbour(&self, x: usize, y: usize) -> bool {
+        (-1..=1).any(|dy| (-1..=1).any(|dx| (dx, dy) != (0, 0) && self.is_sig(x, y, dx, dy)))
+    }
+
+    fn stripe_order(&self) -> Vec<(usize, usize)> {
+        let mut order = Vec::with_capacity(self.width * self.height);
+        for y0 in (0..self.height).step_by(4) {
+            for x in 0..self.width {
+                for y in y0..(y0 + 4).min(self.height) {
+                    order.push((x, y));
+                }
+            }
+        }
+        order
+    }
+
+    fn become_significant(&mut self, x: usize, y: usize, plane: i32) {
+        let (ctx, xor) = self.sign_context(x, y);
+        let i = y * self.width + x;
+        self.negative[i] = self.mq.decode(ctx) ^ xor == 1;
+        self.sig[i] = true;
+        self.magnitude[i] = 1 << plane;
+        self.last_plane[i] = plane;
+    }
+
+    fn significance_pass(&mut self, plane: i32) {
+        for (x, y) in self.stripe_order() {
+            let i = y * self.width + x;
+            if !self.sig[i] && self.has_sig_neighbour(x, y) {
+                let ctx = self.zero_context(x, y);
+                if self.mq.decode(ctx) == 1 {
+                    self.become_significant(x, y, plane);
+                }
+                self.visited[i] = true;
+            }
+        }
+    }
+
+    fn refinement_pass(&mut self, plane: i32) {
+        for (x, y) in self.stripe_order() {
+            let i = y * self.width + x;
+            if self.sig[i] && !self.visited[i] {
+                let ctx = if self.refined[i] {
+                    16
+                } else if self.has_sig_neighbour(x, y) {
+                    15
+                } else {
+                    14
+                };
+                self.magnitude[i] |= (self.mq.decode(ctx) as u64) << plane;
+                self.refined[i] = true;
+                self.last_plane[i] = plane;
+            }
+        }
+    }
+
+    fn cleanup_pass(&mut self, plane: i32) {
+        for y0 in (0..self.height).step_by(4) {
+            for x in 0..self.width {
+                let rows = (self.height - y0).min(4);
+                let mut start = 0;
+                let quiet = |d: &Self, y: usize| {
+                    let i = y * d.width + x;
+                    !d.sig[i] && !d.visited[i] && !d.has_sig_neighbour(x, y)
+                };
+                if rows == 4 && (y0..y0 + 4).all(|y| quiet(self, y)) {
+                    if self.mq.decode(RUN_LENGTH) == 0 {
+                        continue;
+                    }
+                    let k = ((self.mq.decode(UNIFORM) << 1) | self.mq.decode(UNIFORM)) as usize;
+                    self.become_significant(x, y0 + k, plane);
+                    start = k + 1;
+                }
+                for y in y0 + start..y0 + rows {
+                    let i = y * self.width + x;
+                    if !self.sig[i] && !self.visited[i] {
+                        let ctx = self.zero_context(x, y);
+                        if self.mq.decode(ctx) == 1 {
+                            self.become_significant(x, y, plane);
+                        }
+                    }
+                }
+            }
+        }
+        self.visited.fill(false);
+    }
+}
+
+/// Decodes a code-block into coefficients in quantizer step units, with
+/// samples that were cut short reconstructed mid-interval.
+fn decode_block(block: &BlockData, width: usize, height: usize, orient: u8, top: i32, reversible: bool) -> Vec<f64> {
+    let n = width * height;
+    let mut dec = BlockDecoder {
+        mq: MqDecoder::new(&block.data),
+        width,
+        height,
+        orient,
+        sig: vec![false; n],
+        negative: vec![false; n],
+        visited: vec![false; n],
+        refined: vec![false; n],
+        magnitude: vec![0; n],
+        last_plane: vec![0; n],
+    };
+
+    let mut plane = top;
+    let mut kind = 2;
+    for _ in 0..block.passes {
+        match kind {
+            0 => dec.significance_pass(plane),
+            1 => dec.refinement_pass(plane),
+            _ => dec.cleanup_pass(plane),
+        }
+        if kind == 2 {
+            plane -= 1;
+            kind = 0;
+        } else {
+            kind += 1;
+        }
+    }
+
+    (0..n)
+        .map(|i| {
+            if !dec.sig[i] {
+                return 0.0;
+            }
+            let last = dec.last_plane[i];
+            let offset = if reversible && last == 0 { 0.0 } else { 2f64.powi(last) * 0.5 };
+            let v = dec.magnitude[i] as f64 + offset;
+            if dec.negative[i] { -v } else { v }
+        })
+        .collect()
+}
+
+// ============================================================================
+// Inverse Wavelet Transforms
+// ============================================================================
+
+/// Whole-sample symmetric reflection of index `i` into `0..n`.
+fn reflect(i: isize, n: usize) -> usize {
+    if n == 1 {
+        return 0;
+    }
+    let period = 2 * (n as isize - 1);
+    let m = i.rem_euclid(period);
+    (if m >= n as isize { period - m } else { m }) as usize
+}
+
+/// Undoes each level in place on the Mallat layout: rows, then columns.
+fn inverse_dwt<T: Copy + Default>(data: &mut [T], stride: usize, resolutions: &[Rect], line: fn(&mut [T], bool)) {
+    for r in 1..resolutions.len() {
+        let rect = resolutions[r];
+        let low = resolutions[r - 1];
+        let (w, h) = (rect.width() as usize, rect.height() as usize);
+        if w == 0 || h == 0 {
+            continue;
+        }
+        let mut buf = vec![T::default(); w.max(h)];
+        for y in 0..h {
+            interleave(&data[y * stride..y * stride + w], &mut buf[..w], low.width() as usize, rect.x0);
+            line(&mut buf[..w], rect.x0 % 2 == 1);
+            data[y * stride..y * stride + w].copy_from_slice(&buf[..w]);
+        }
+        let mut column = vec![T::default(); h];
+        for x in 0..w {
+            for y in 0..h {
+                column[y] = data[y * stride + x];
+            }
+            interleave(&column, &mut buf[..h], low.height() as usize, rect.y0);
+            line(&mut buf[..h], rect.y0 % 2 == 1);
+            for y in 0..h {
+                data[y * stride + x] = buf[y];
+            }
+        }
+    }
+}
+
+/// Places `lows` low-pass then high-pass samples at even then odd
+/// absolute positions starting at `origin`.
+fn interleave<T: Copy>(src: &[T], dst: &mut [T], lows: usize, origin: u32) {
+    let (mut l, mut h) = (0, lows);
+    for (i, d) in dst.iter_mut().enumerate() {
+        if (origin as usize + i) % 2 == 0 {
+            *d = src[l];
+            l += 1;
+        } else {
+            *d = src[h];
+            h += 1;
+        }
+    }
+}
+
+/// Reversible 5/3 synthesis (F.3.8.1).
+fn inverse_53(x: &mut [i32], odd: bool) {
+    let n = x.len();
+    if n == 1 {
+        if odd {
+            x[0] /= 2;
+        }
+        return;
+    }
+    let at = |x: &[i32], i: isize| x[reflect(i, n)];
+    let even = if odd { 1 } else { 0 };
+    for i in (even..n).step_by(2) {
+        x[i] -= (at(x, i as isize - 1) + at(x, i as isize + 1) + 2) >> 2;
+    }
+    for i in (1 - even..n).step_by(2) {
+        x[i] += (at(x, i as isize - 1) + at(x, i as isize + 1)) >> 1;
+    }
+}
+
+/// Irreversible 9/7 synthesis (F.3.8.2).
+fn inverse_97(x: &mut [f64], odd: bool) {
+    const ALPHA: f64 = -1.586_134_342_059_924;
+    const BETA: f64 = -0.052_980_118_572_961;
+    const GAMMA: f64 = 0.882_911_075_530_934;
+    const DELTA: f64 = 0.443_506_852_043_971;
+    const K: f64 = 1.230_174_104_914_001;
+
+    let n = x.len();
+    if n == 1 {
+        if odd {
+            x[0] /= 2.0;
+        }
+        return;
+    }
+    let at = |x: &[f64], i: isize| x[reflect(i, n)];
+    let even = if odd { 1 } else { 0 };
+    for (i, v) in x.iter_mut().enumerate() {
+        *v *= if i % 2 == even { K } else { 1.0 / K };
+    }
+    for (step, coeff) in [DELTA, GAMMA, BETA, ALPHA].into_iter().enumerate() {
+        let start = if step % 2 == 0 { even } else { 1 - even };
+        for i in (start..n).step_by(2) {
+            x[i] -= coeff * (at(x, i as isize - 1) + at(x, i as isize + 1));
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Forward discrete wavelet transforms (ISO/IEC 15444-1 Annex F).
+//!
+//! Both transforms work in place on a tile-component and leave the
+//! coefficients in the usual Mallat layout: after each level the LL band
+//! occupies the top-left corner and the next level only touches that
+//! corner. Columns are filtered before rows, so a decoder that undoes rows
+//! first and then columns reproduces the reversible transform exactly.
+
+use super::t2::Rect;
+
+/// 9/7 lifting coefficients (Table F.4).
+const ALPHA: f32 = -1.586_134_3;
+const BETA: f32 = -0.052_980_117;
+const GAMMA: f32 = 0.882_911_1;
+const DELTA: f32 = 0.443_506_87;
+const K: f32 = 1.230_174_1;
+
+/// Samples of symmetric extension on each side of a line. Even, so that
+/// the parity of an extended index matches the parity of its sample.
+const PAD: usize = 4;
+
+/// Reversible 5/3 transform of `levels` levels over `rect`.
+pub(crate) fn forward_53(data: &mut [i32], stride: usize, rect: Rect, levels: u8) {
+    forward_2d(data, stride, rect, levels, lift_53);
+}
+
+/// Irreversible 9/7 transform of `levels` levels over `rect`.
+pub(crate) fn forward_97(data: &mut [f32], stride: usize, rect: Rect, levels: u8) {
+    forward_2d(data, stride, rect, levels, lift_97);
+}
+
+/// Runs a 1D transform over columns then rows, once per level.
+fn forward_2d<T: Copy + Default>(
+    data: &mut [T],
+    stride: usize,
+    rect: Rect,
+    levels: u8,
+    lift: fn(&mut [T], bool, &mut Vec<T>),
+) {
+    let mut r = rect;
+    let mut ext = Vec::new();
+    let mut column = Vec::new();
+
+    for _ in 0..levels {
+        let (w, h) = (r.width() as usize, r.height() as usize);
+        if w == 0 || h == 0 {
+            break;
+        }
+
+        for x in 0..w {
+            column.clear();
+            column.extend((0..h).map(|y| data[y * stride + x]));
+            lift(&mut column, r.y0 & 1 == 1, &mut ext);
+            for (y, &v) in column.iter().enumerate() {
+                data[y * stride + x] = v;
+            }
+        }
+        for y in 0..h {
+            lift(&mut data[y * stride..y * stride + w], r.x0 & 1 == 1, &mut ext);
+        }
+
+        r = r.scaled_down(1);
+    }
+}
+
+/// Fills `ext` with `line` plus [`PAD`] samples of whole-sample symmetric
+/// extension on each side.
+fn extend<T: Copy>(line: &[T], ext: &mut Vec<T>) {
+    let n = line.len() as isize;
+    let period = 2 * (n - 1);
+    ext.clear();
+    ext.extend((-(PAD as isize)..n + PAD as isize).map(|i| {
+        let m = i.rem_euclid(period);
+        line[if m >= n { period - m } else { m } as usize]
+    }));
+}
+
+/// Splits the extended signal back into low-pass then high-pass samples.
+fn deinterleave<T: Copy>(line: &mut [T], odd: bool, ext: &[T]) {
+    let n = line.len();
+    let start = odd as usize;
+    let lows = (0..n).filter(|i| (i + start) % 2 == 0).map(|i| ext[PAD + i]);
+    let highs = (0..n).filter(|i| (i + start) % 2 == 1).map(|i| ext[PAD + i]);
+    let out: Vec<T> = lows.chain(highs).collect();
+    line.copy_from_slice(&out);
+}
+
+/// One level of the 5/3 filter (F.4.8.1). `odd` is the parity of the
+/// first sample's absolute coordinate.
+fn lift_53(line: &mut [i32], odd: bool, ext: &mut Vec<i32>) {
+    match line.len() {
+        0 => return,
+        1 => {
+            if odd {
+                line[0] *= 2;
+            }
+            return;
+        }
+        _ => {}
+    }
+    extend(line, ext);
+    let len = ext.len();
+    let high = |j: usize| (j + odd as usize) % 2 == 1;
+
+    for j in (1..len - 1).filter(|&j| high(j)) {
+        ext[j] -= (ext[j - 1] + ext[j + 1]) >> 1;
+    }
+    for j in (2..len - 2).filter(|&j| !high(j)) {
+        ext[j] += (ext[j - 1] + ext[j + 1] + 2) >> 2;
+    }
+    deinterleave(line, odd, ext);
+}
+
+/// One level of the 9/7 filter (F.4.8.2).
+fn lift_97(line: &mut [f32], odd: bool, ext: &mut Vec<f32>) {
+    match line.len() {
+        0 => return,
+        1 => {
+            if odd {
+                line[0] *= 2.0;
+            }
+            return;
+        }
+        _ => {}
+    }
+    extend(line, ext);
+    let len = ext.len();
+    let high = |j: usize| (j + odd as usize) % 2 == 1;
+
+    for (step, coeff) in [ALPHA, BETA, GAMMA, DELTA].into_iter().enumerate() {
+        let lifts_high = step % 2 == 0;
+        for j in (step + 1..len - step - 1).filter(|&j| high(j) == lifts_high) {
+            ext[j] += coeff * (ext[j - 1] + ext[j + 1]);
+        }
+    }
+    for (j, v) in ext.iter_mut().enumerate() {
+        *v *= if high(j) { K } else { 1.0 / K };
+    }
+    deinterleave(line, odd, ext);
+}
+
+/// Synthesis basis norms used to weight quantization steps and distortion.
+///
+/// Returns `(low, high)` where `low[n]` and `high[n]` are the L2 norms of
+/// the 1D synthesis basis functions of the low and high band at level
+/// `n` (index 0 unused).
+pub(crate) fn basis_norms(levels: u8, reversible: bool) -> (Vec<f64>, Vec<f64>) {
+    let mut low = vec![1.0];
+    let mut high = vec![1.0];
+    for n in 1..=levels as usize {
+        low.push(synthesis_norm(n, false, reversible));
+        high.push(synthesis_norm(n, true, reversible));
+    }
+    (low, high)
+}
+
+/// Norm of an impulse in the low or high band of level `level`, synthesized
+/// back to full resolution.
+fn synthesis_norm(level: usize, high: bool, reversible: bool) -> f64 {
+    const M: usize = 32;
+    let mut signal = vec![0.0f64; M];
+    let mut highs = vec![0.0f64; M];
+    if high {
+        highs[M / 2] = 1.0;
+    } else {
+        signal[M / 2] = 1.0;
+    }
+    for _ in 0..level {
+        let mut line = signal.clone();
+        line.extend_from_slice(&highs);
+        synthesize(&mut line, reversible);
+        highs = vec![0.0; line.len()];
+        signal = line;
+    }
+    signal.iter().map(|v| v * v).sum::<f64>().sqrt()
+}
+
+/// Inverse of one even-aligned level on a deinterleaved line, linearized
+/// for the 5/3 filter.
+fn synthesize(line: &mut [f64], reversible: bool) {
+    let n = line.len();
+    let half = n / 2;
+    let mut x: Vec<f64> = (0..n)
+        .map(|i| if i % 2 == 0 { line[i / 2] } else { line[half + i / 2] })
+        .collect();
+    let at = |x: &[f64], i: isize| x[i.clamp(0, n as isize - 1) as usize];
+
+    if reversible {
+        for i in (0..n).step_by(2) {
+            x[i] -= (at(&x, i as isize - 1) + at(&x, i as isize + 1)) / 4.0;
+        }
+        for i in (1..n).step_by(2) {
+            x[i] += (at(&x, i as isize - 1) + at(&x, i as isize + 1)) / 2.0;
+        }
+    } else {
+        for (i, v) in x.iter_mut().enumerate() {
+            *v *= if i % 2 == 0 { K as f64 } else { 1.0 / K as f64 };
+        }
+        for (step, coeff) in [DELTA, GAMMA, BETA, ALPHA].into_iter().enumerate() {
+            let start = if step % 2 == 0 { 0 } else { 1 };
+            for i in (start..n).step_by(2) {
+                x[i] -= coeff as f64 * (at(&x, i as isize - 1) + at(&x, i as isize + 1));
+            }
+        }
+    }
+    line.copy_from_slice(&x);
+}
@@ -0,0 +1,1264 @@
+//! JPEG 2000 codestream writer with the DCI digital cinema profiles.
+//!
+//! Pure Rust Part 1 encoder: 5/3 reversible or 9/7 irreversible wavelet,
+//! EBCOT code-blocks, one quality layer and post-compression rate control
+//! that truncates every code-block at the same rate-distortion slope.
+//! Reading JPEG 2000 still goes through [`crate::jp2`] and OpenJPEG.
+//!
+//! # Profiles
+//!
+//! | Profile | Rsiz | Levels | Code-block | Precincts | Progression | Tile-parts |
+//! |---------|------|--------|------------|-----------|-------------|------------|
+//! | Generic | 0 | 5 (configurable) | 64x64 | none | LRCP (configurable) | 1 per tile |
+//! | Cinema 2K | 3 | 5 | 32x32 | 128 at LL, 256 above | CPRL | 1 per component |
+//! | Cinema 4K | 4 | 6 | 32x32 | 128 at LL, 256 above | CPRL, split at 2K by POC | 2 per component |
+//!
+//! The cinema profiles (SMPTE 429-4) write a single tile of three 12-bit
+//! components with the 9/7 filter, no color transform, one guard bit and a
+//! TLM marker. Every frame is rate controlled to the DCI limits of
+//! 250 Mbit/s per frame and 200 Mbit/s per component at the given frame
+//! rate. Feed them X'Y'Z' images, e.g. from
+//! [`dci_xyz`](crate::imagebufalgo::dci_xyz).
+//!
+//! # Example
+//!
+//! ```ignore
+//! use vfx_io::j2k::{self, J2kWriterOptions};
+//!
+//! // Lossless archival master
+//! j2k::write("plate.jp2", &image)?;
+//!
+//! // DCI 2K frame for a DCP
+//! let options = J2kWriterOptions::cinema_2k(24);
+//! let codestream = j2k::encode(&xyz, &options)?;
+//! std::fs::write("reel1.000001.j2c", codestream)?;
+//! ```
+
+mod dwt;
+mod t1;
+mod t2;
+#[cfg(test)]
+mod decode;
+
+use std::ops::Range;
+use std::path::Path;
+
+use crate::{FormatWriter, ImageData, IoError, IoResult, PixelData, PixelFormat};
+use t1::CodedBlock;
+use t2::{BlockContribution, PacketRef, PrecinctBand, Rect, ResolutionGeom};
+
+const SOC: u16 = 0xFF4F;
+const SIZ: u16 = 0xFF51;
+const COD: u16 = 0xFF52;
+const QCD: u16 = 0xFF5C;
+const POC: u16 = 0xFF5F;
+const TLM: u16 = 0xFF55;
+const SOT: u16 = 0xFF90;
+const SOD: u16 = 0xFF93;
+const EOC: u16 = 0xFFD9;
+
+/// SOT marker segment plus SOD marker, in bytes.
+const TILE_PART_OVERHEAD: usize = 14;
+
+/// DCI maximum bit rate of a frame (SMPTE 429-4), in bits per second.
+pub const DCI_MAX_BIT_RATE: u64 = 250_000_000;
+
+/// DCI maximum bit rate of a single component, in bits per second.
+pub const DCI_MAX_COMPONENT_BIT_RATE: u64 = 200_000_000;
+
+/// Largest codestream allowed for one frame at `frame_rate` fps.
+pub fn dci_max_frame_bytes(frame_rate: u32) -> usize {
+    (DCI_MAX_BIT_RATE / 8 / frame_rate.max(1) as u64) as usize
+}
+
+/// Largest compressed size of one component of a frame at `frame_rate` fps.
+pub fn dci_max_component_bytes(frame_rate: u32) -> usize {
+    (DCI_MAX_COMPONENT_BIT_RATE / 8 / frame_rate.max(1) as u64) as usize
+}
+
+// ============================================================================
+// Writer Options
+// ============================================================================
+
+/// Codestream profile (the Rsiz capabilities).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum J2kProfile {
+    /// No restrictions; every other option applies.
+    #[default]
+    Generic,
+    /// DCI 2K: up to 2048x1080.
+    Cinema2k,
+    /// DCI 4K: up to 4096x2160.
+    Cinema4k,
+}
+
+impl J2kProfile {
+    /// Rsiz value written to the SIZ marker.
+    pub fn rsiz(&self) -> u16 {
+        match self {
+            Self::Generic => 0,
+            Self::Cinema2k => 3,
+            Self::Cinema4k => 4,
+        }
+    }
+
+    /// Largest image size allowed by a cinema profile.
+    pub fn max_size(&self) -> Option<(u32, u32)> {
+        match self {
+            Self::Generic => None,
+            Self::Cinema2k => Some((2048, 1080)),
+            Self::Cinema4k => Some((4096, 2160)),
+        }
+    }
+}
+
+/// Packet progression order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProgressionOrder {
+    /// Layer-resolution-component-position.
+    #[default]
+    Lrcp,
+    /// Resolution-layer-component-position.
+    Rlcp,
+    /// Resolution-position-component-layer.
+    Rpcl,
+    /// Position-component-resolution-layer.
+    Pcrl,
+    /// Component-position-resolution-layer (required by the cinema profiles).
+    Cprl,
+}
+
+impl ProgressionOrder {
+    /// Value written to the COD and POC markers.
+    pub fn code(&self) -> u8 {
+        match self {
+            Self::Lrcp => 0,
+            Self::Rlcp => 1,
+            Self::Rpcl => 2,
+            Self::Pcrl => 3,
+            Self::Cprl => 4,
+        }
+    }
+}
+
+/// Options for writing JPEG 2000 codestreams.
+///
+/// With a cinema profile only `max_bytes` and `frame_rate` are used; the
+/// profile fixes the rest.
+#[derive(Debug, Clone)]
+pub struct J2kWriterOptions {
+    /// Codestream profile. Default: generic.
+    pub profile: J2kProfile,
+    /// Use the reversible 5/3 filter, lossless unless `max_bytes` cuts it.
+    /// Otherwise the 9/7 filter. Default: true.
+    pub reversible: bool,
+    /// Wavelet decomposition levels, at most 10. Default: 5.
+    pub levels: u8,
+    /// Tile size, or a single tile covering the image. Default: None.
+    pub tile_size: Option<(u32, u32)>,
+    /// Code-block size: powers of two from 4 to 1024, at most 4096 samples.
+    /// Default: 64x64.
+    pub code_block: (u32, u32),
+    /// Packet progression order. Default: LRCP.
+    pub progression: ProgressionOrder,
+    /// Decorrelate the first three components (RCT or ICT). Default: true.
+    pub mct: bool,
+    /// Component precision in bits (1-16). Default: 8 for 8-bit images,
+    /// 16 otherwise.
+    pub bit_depth: Option<u8>,
+    /// Largest codestream size in bytes; passes are dropped by rate
+    /// control to fit. Default: None.
+    pub max_bytes: Option<usize>,
+    /// Frame rate that sets the DCI byte budget of the cinema profiles.
+    /// Default: 24.
+    pub frame_rate: u32,
+}
+
+impl Default for J2kWriterOptions {
+    fn default() -> Self {
+        Self {
+            profile: J2kProfile::Generic,
+            reversible: true,
+            levels: 5,
+            tile_size: None,
+            code_block: (64, 64),
+            progression: ProgressionOrder::Lrcp,
+            mct: true,
+            bit_depth: None,
+            max_bytes: None,
+            frame_rate: 24,
+        }
+    }
+}
+
+impl J2kWriterOptions {
+    /// DCI 2K frames at `frame_rate` fps.
+    pub fn cinema_2k(frame_rate: u32) -> Self {
+        Self { profile: J2kProfile::Cinema2k, frame_rate, ..Default::default() }
+    }
+
+    /// DCI 4K frames at `frame_rate` fps.
+    pub fn cinema_4k(frame_rate: u32) -> Self {
+        Self { profile: J2kProfile::Cinema4k, frame_rate, ..Default::default() }
+    }
+}
+
+// ============================================================================
+// Coding Parameters
+// ============================================================================
+
+/// A progression over a resolution and component range (one POC entry).
+#[derive(Debug, Clone)]
+struct Progression {
+    resolutions: Range<usize>,
+    components: Range<usize>,
+    order: ProgressionOrder,
+}
+
+/// Options resolved against the image and profile.
+#[derive(Debug, Clone)]
+struct Params {
+    width: u32,
+    height: u32,
+    components: usize,
+    precision: u8,
+    tile: (u32, u32),
+    levels: u8,
+    /// Log2 code-block size.
+    code_block: (u8, u8),
+    /// Log2 precinct size per resolution, when signalled.
+    precincts: Option<Vec<(u8, u8)>>,
+    reversible: bool,
+    mct: bool,
+    progression: ProgressionOrder,
+    /// POC progressions; empty to use `progression` throughout.
+    pocs: Vec<Progression>,
+    guard_bits: u8,
+    rsiz: u16,
+    /// Start a tile-part whenever the component or POC progression changes.
+    split_components: bool,
+    tlm: bool,
+    max_bytes: Option<usize>,
+    max_component_bytes: Option<usize>,
+}
+
+impl Params {
+    fn new(image: &ImageData, options: &J2kWriterOptions) -> IoResult<Self> {
+        let (width, height) = (image.width, image.height);
+        let components = image.channels as usize;
+        if width == 0 || height == 0 || components == 0 {
+            return Err(IoError::EncodeError("JPEG 2000: empty image".into()));
+        }
+
+        if let Some((max_w, max_h)) = options.profile.max_size() {
+            return Self::cinema(image, options, max_w, max_h);
+        }
+
+        let precision = options
+            .bit_depth
+            .unwrap_or(if image.format == PixelFormat::U8 { 8 } else { 16 });
+        if !(1..=16).contains(&precision) {
+            return Err(IoError::UnsupportedBitDepth(format!(
+                "JPEG 2000 writer supports 1-16 bits, got {}",
+                precision
+            )));
+        }
+        if options.levels > 10 {
+            return Err(IoError::EncodeError(format!(
+                "JPEG 2000 writer supports at most 10 decomposition levels, got {}",
+                options.levels
+            )));
+        }
+        let (cw, ch) = options.code_block;
+        let valid = |s: u32| s.is_power_of_two() && (4..=1024).contains(&s);
+        if !valid(cw) || !valid(ch) || cw * ch > 4096 {
+            return Err(IoError::EncodeError(format!(
+                "invalid JPEG 2000 code-block size {}x{}",
+                cw, ch
+            )));
+        }
+        let tile = options.tile_size.unwrap_or((width, height));
+        if tile.0 == 0 || tile.1 == 0 {
+            return Err(IoError::EncodeError("JPEG 2000 tile size must be non-zero".into()));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            components,
+            precision,
+            tile: (tile.0.min(width), tile.1.min(height)),
+            levels: options.levels,
+            code_block: (cw.ilog2() as u8, ch.ilog2() as u8),
+            precincts: None,
+            reversible: options.reversible,
+            mct: options.mct && components >= 3,
+            progression: options.progression,
+            pocs: Vec::new(),
+            guard_bits: 2,
+            rsiz: 0,
+            split_components: false,
+            tlm: false,
+            max_bytes: options.max_bytes,
+            max_component_bytes: None,
+        })
+    }
+
+    fn cinema(image: &ImageData, options: &J2kWriterOptions, max_w: u32, max_h: u32) -> IoResult<Self> {
+        let profile = options.profile;
+        if image.channels != 3 {
+            return Err(IoError::EncodeError(format!(
+                "DCI profiles need 3 components (X'Y'Z'), got {}",
+                image.channels
+            )));
+        }
+        if image.width > max_w || image.height > max_h {
+            return Err(IoError::EncodeError(format!(
+                "{:?} allows at most {}x{}, got {}x{}",
+                profile, max_w, max_h, image.width, image.height
+            )));
+        }
+        if options.frame_rate == 0 {
+            return Err(IoError::EncodeError("DCI frame rate must be non-zero".into()));
+        }
+
+        let levels = if profile == J2kProfile::Cinema4k { 6 } else { 5 };
+        let mut precincts = vec![(8, 8); levels as usize + 1];
+        precincts[0] = (7, 7);
+        // 4K streams carry their 2K image first: one progression for the
+        // resolutions shared with 2K, one for the top level.
+        let pocs = if profile == J2kProfile::Cinema4k {
+            let top = levels as usize;
+            vec![
+                Progression { resolutions: 0..top, components: 0..3, order: ProgressionOrder::Cprl },
+                Progression { resolutions: top..top + 1, components: 0..3, order: ProgressionOrder::Cprl },
+            ]
+        } else {
+            Vec::new()
+        };
+        let frame = dci_max_frame_bytes(options.frame_rate);
+
+        Ok(Self {
+            width: image.width,
+            height: image.height,
+            components: 3,
+            precision: 12,
+            tile: (image.width, image.height),
+            levels,
+            code_block: (5, 5),
+            precincts: Some(precincts),
+            reversible: false,
+            mct: false,
+            progression: ProgressionOrder::Cprl,
+            pocs,
+            guard_bits: 1,
+            rsiz: profile.rsiz(),
+            split_components: true,
+            tlm: true,
+            max_bytes: Some(options.max_bytes.map_or(frame, |m| m.min(frame))),
+            max_component_bytes: Some(dci_max_component_bytes(options.frame_rate)),
+        })
+    }
+
+    fn progressions(&self) -> Vec<Progression> {
+        if self.pocs.is_empty() {
+            vec![Progression {
+                resolutions: 0..self.levels as usize + 1,
+                components: 0..self.components,
+                order: self.progression,
+            }]
+        } else {
+            self.pocs.clone()
+        }
+    }
+
+    fn precinct_sizes(&self) -> Vec<(u8, u8)> {
+        self.precincts.clone().unwrap_or_else(|| vec![(15, 15); self.levels as usize + 1])
+    }
+
+    fn tile_rects(&self) -> Vec<Rect> {
+        let (tw, th) = self.tile;
+        let mut rects = Vec::new();
+        for y in (0..self.height).step_by(th as usize) {
+            for x in (0..self.width).step_by(tw as usize) {
+                rects.push(Rect::new(x, y, (x + tw).min(self.width), (y + th).min(self.height)));
+            }
+        }
+        rects
+    }
+}
+
+// ============================================================================
+// Quantization
+// ============================================================================
+
+/// Quantization of one subband.
+#[derive(Debug, Clone, Copy)]
+struct BandQuant {
+    exponent: u8,
+    mantissa: u16,
+    /// Step size in sample units.
+    step: f32,
+    /// Magnitude bit-planes (Mb).
+    planes: u32,
+    /// Squared-error weight of one quantizer step in the image.
+    weight: f64,
+}
+
+/// Index of a band in QCD order: LL, then HL, LH, HH per resolution.
+fn band_index(res: usize, orient: u8) -> usize {
+    if res == 0 { 0 } else { 1 + 3 * (res - 1) + orient as usize - 1 }
+}
+
+/// Quantizers for every band, in QCD order.
+///
+/// Irreversible steps are one sample unit divided by the band's synthesis
+/// norm, so every band contributes comparable error per step and rate
+/// control can work on a common slope.
+fn band_quantizers(params: &Params) -> Vec<BandQuant> {
+    let (low, high) = dwt::basis_norms(params.levels, params.reversible);
+    let nl = params.levels as usize;
+    let mut bands = vec![(0i32, low[nl] * low[nl])];
+    for res in 1..=nl {
+        let n = nl - res + 1;
+        bands.push((1, high[n] * low[n]));
+        bands.push((1, low[n] * high[n]));
+        bands.push((2, high[n] * high[n]));
+    }
+
+    bands
+        .into_iter()
+        .map(|(gain, norm)| {
+            let range = params.precision as i32 + gain;
+            if params.reversible {
+                return BandQuant {
+                    exponent: range as u8,
+                    mantissa: 0,
+                    step: 1.0,
+                    planes: (params.guard_bits as i32 + range - 1) as u32,
+                    weight: norm * norm,
+                };
+            }
+            let delta = 1.0 / norm;
+            let log = delta.log2().floor() as i32;
+            let mut exponent = (range - log).clamp(0, 31);
+            let scale = 2f64.powi(range - exponent);
+            let mut mantissa = ((delta / scale - 1.0) * 2048.0).round().clamp(0.0, 2048.0) as u16;
+            if mantissa == 2048 {
+                exponent = (exponent - 1).max(0);
+                mantissa = 0;
+            }
+            let step = 2f64.powi(range - exponent) * (1.0 + mantissa as f64 / 2048.0);
+            BandQuant {
+                exponent: exponent as u8,
+                mantissa,
+                step: step as f32,
+                planes: (params.guard_bits as i32 + exponent - 1).max(0) as u32,
+                weight: (norm * step) * (norm * step),
+            }
+        })
+        .collect()
+}
+
+// ============================================================================
+// Tile Coding
+// ============================================================================
+
+/// A coded block with its rate-distortion convex hull.
+struct Block {
+    coded: CodedBlock,
+    /// (passes, slope) truncation points with strictly decreasing slope.
+    hull: Vec<(usize, f64)>,
+}
+
+impl Block {
+    fn new(coded: CodedBlock, weight: f64) -> Self {
+        let mut hull: Vec<(usize, f64, f64, f64)> = Vec::new();
+        for (i, pass) in coded.passes.iter().enumerate() {
+            let (rate, dist) = (pass.rate as f64, pass.distortion * weight);
+            loop {
+                let (r0, d0, s0) = hull.last().map_or((0.0, 0.0, f64::INFINITY), |h| (h.1, h.2, h.3));
+                if dist <= d0 {
+                    break;
+                }
+                if rate <= r0 {
+                    if hull.pop().is_none() {
+                        hull.push((i + 1, rate, dist, f64::INFINITY));
+                        break;
+                    }
+                    continue;
+                }
+                let slope = (dist - d0) / (rate - r0);
+                if slope >= s0 {
+                    hull.pop();
+                    continue;
+                }
+                hull.push((i + 1, rate, dist, slope));
+                break;
+            }
+        }
+        Self { coded, hull: hull.into_iter().map(|h| (h.0, h.3)).collect() }
+    }
+
+    /// Passes kept at rate-distortion slope `lambda`; all of them if None.
+    fn passes_at(&self, lambda: Option<f64>) -> usize {
+        match lambda {
+            None => self.coded.passes.len(),
+            Some(l) => self.hull.iter().take_while(|h| h.1 >= l).last().map_or(0, |h| h.0),
+        }
+    }
+
+    fn contribution(&self, lambda: Option<f64>) -> BlockContribution<'_> {
+        let passes = self.passes_at(lambda);
+        let len = if passes == 0 { 0 } else { self.coded.passes[passes - 1].rate };
+        BlockContribution {
+            data: &self.coded.data[..len],
+            passes,
+            missing_msbs: self.coded.missing_msbs,
+        }
+    }
+}
+
+/// Coded blocks of one band, over its code-block grid.
+struct BandBlocks {
+    grid: Rect,
+    blocks: Vec<Block>,
+}
+
+struct TileComponent {
+    resolutions: Vec<ResolutionGeom>,
+    /// Indexed by resolution, then band.
+    bands: Vec<Vec<BandBlocks>>,
+}
+
+struct Tile {
+    index: u16,
+    comps: Vec<TileComponent>,
+}
+
+/// Normalized samples, with U32 scaled like the other integer formats.
+fn normalized_samples(image: &ImageData) -> Vec<f32> {
+    match &image.data {
+        PixelData::U32(data) => data.iter().map(|&v| (v as f64 / u32::MAX as f64) as f32).collect(),
+        _ => image.to_f32(),
+    }
+}
+
+/// Level-shifted integer samples of each component.
+fn component_planes(image: &ImageData, precision: u8) -> Vec<Vec<i32>> {
+    let nc = image.channels as usize;
+    let max = ((1u32 << precision) - 1) as f32;
+    let shift = 1i32 << (precision - 1);
+    let samples = normalized_samples(image);
+    (0..nc)
+        .map(|c| {
+            samples
+                .iter()
+                .skip(c)
+                .step_by(nc)
+                .map(|v| (v.clamp(0.0, 1.0) * max).round() as i32 - shift)
+                .collect()
+        })
+        .collect()
+}
+
+fn encode_tile(
+    params: &Params,
+    planes: &[Vec<i32>],
+    quants: &[BandQuant],
+    rect: Rect,
+    index: u16,
+) -> Tile {
+    let (w, h) = (rect.width() as usize, rect.height() as usize);
+    let stride = params.width as usize;
+    let extract = |c: usize| -> Vec<i32> {
+        let mut out = Vec::with_capacity(w * h);
+        for y in rect.y0 as usize..rect.y1 as usize {
+            out.extend_from_slice(&planes[c][y * stride + rect.x0 as usize..][..w]);
+        }
+        out
+    };
+
+    let mut coeffs: Vec<Vec<f32>> = Vec::with_capacity(params.components);
+    if params.reversible {
+        let mut ints: Vec<Vec<i32>> = (0..params.components).map(extract).collect();
+        if let (true, [c0, c1, c2, ..]) = (params.mct, ints.as_mut_slice()) {
+            for ((y0, y1), y2) in c0.iter_mut().zip(c1.iter_mut()).zip(c2.iter_mut()) {
+                let (r, g, b) = (*y0, *y1, *y2);
+                *y0 = (r + 2 * g + b) >> 2;
+                *y1 = b - g;
+                *y2 = r - g;
+            }
+        }
+        for mut plane in ints {
+            dwt::forward_53(&mut plane, w, rect, params.levels);
+            coeffs.push(plane.into_iter().map(|v| v as f32).collect());
+        }
+    } else {
+        coeffs = (0..params.components)
+            .map(|c| extract(c).into_iter().map(|v| v as f32).collect())
+            .collect();
+        if let (true, [c0, c1, c2, ..]) = (params.mct, coeffs.as_mut_slice()) {
+            for ((y0, y1), y2) in c0.iter_mut().zip(c1.iter_mut()).zip(c2.iter_mut()) {
+                let (r, g, b) = (*y0, *y1, *y2);
+                *y0 = 0.299 * r + 0.587 * g + 0.114 * b;
+                *y1 = -0.16875 * r - 0.33126 * g + 0.5 * b;
+                *y2 = 0.5 * r - 0.41869 * g - 0.08131 * b;
+            }
+        }
+        for plane in coeffs.iter_mut() {
+            dwt::forward_97(plane, w, rect, params.levels);
+        }
+    }
+
+    let precincts = params.precinct_sizes();
+    let comps = coeffs
+        .iter()
+        .map(|data| {
+            let resolutions = t2::tile_component(rect, params.levels, params.code_block, &precincts);
+            let bands = resolutions
+                .iter()
+                .enumerate()
+                .map(|(r, res)| {
+                    res.bands
+                        .iter()
+                        .map(|band| {
+                            let quant = quants[band_index(r, band.orient)];
+                            let grid = band.block_grid();
+                            let rects: Vec<Rect> = (grid.y0..grid.y1)
+                                .flat_map(|cby| (grid.x0..grid.x1).map(move |cbx| (cbx, cby)))
+                                .map(|(cbx, cby)| band.block_rect(cbx, cby))
+                                .collect();
+                            let encode = |b: &Rect| {
+                                let mut values = Vec::with_capacity((b.width() * b.height()) as usize);
+                                for y in b.y0..b.y1 {
+                                    let row = (band.layout_y + y - band.rect.y0) as usize * w;
+                                    let x0 = row + (band.layout_x + b.x0 - band.rect.x0) as usize;
+                                    values.extend(data[x0..x0 + b.width() as usize].iter().map(|v| v / quant.step));
+                                }
+                                let coded = t1::encode_block(
+                                    &values,
+                                    b.width() as usize,
+                                    b.height() as usize,
+                                    band.orient,
+                                    quant.planes,
+                                );
+                                Block::new(coded, quant.weight)
+                            };
+
+                            #[cfg(feature = "rayon")]
+                            let blocks = {
+                                use rayon::prelude::*;
+                                rects.par_iter().map(encode).collect()
+                            };
+                            #[cfg(not(feature = "rayon"))]
+                            let blocks = rects.iter().map(encode).collect();
+
+                            BandBlocks { grid, blocks }
+                        })
+                        .collect()
+                })
+                .collect();
+            TileComponent { resolutions, bands }
+        })
+        .collect();
+
+    Tile { index, comps }
+}
+
+/// Encodes one packet of a tile at slope `lambda`.
+fn packet_bytes(tile: &Tile, packet: PacketRef, lambda: Option<f64>) -> Vec<u8> {
+    let comp = &tile.comps[packet.comp];
+    let precinct = &comp.resolutions[packet.res].precincts[packet.precinct];
+    let bands: Vec<PrecinctBand> = comp.bands[packet.res]
+        .iter()
+        .zip(&precinct.blocks)
+        .map(|(band, range)| {
+            let mut blocks = Vec::new();
+            for cby in range.y0..range.y1 {
+                for cbx in range.x0..range.x1 {
+                    let i = (cby - band.grid.y0) * band.grid.width() + (cbx - band.grid.x0);
+                    blocks.push(band.blocks[i as usize].contribution(lambda));
+                }
+            }
+            PrecinctBand {
+                width: range.width() as usize,
+                height: range.height() as usize,
+                blocks,
+            }
+        })
+        .collect();
+    t2::encode_packet(&bands)
+}
+
+/// Tile-parts of a tile and the bytes spent on each component.
+fn tile_parts(params: &Params, tile: &Tile, lambda: Option<f64>) -> (Vec<Vec<u8>>, Vec<usize>) {
+    let geoms: Vec<Vec<ResolutionGeom>> = tile.comps.iter().map(|c| c.resolutions.clone()).collect();
+    let mut parts: Vec<Vec<u8>> = Vec::new();
+    let mut comp_bytes = vec![0; params.components];
+    let mut current = None;
+
+    for (pi, progression) in params.progressions().iter().enumerate() {
+        let packets = t2::packet_order(
+            &geoms,
+            progression.order,
+            progression.resolutions.clone(),
+            progression.components.clone(),
+        );
+        for packet in packets {
+            let key = params.split_components.then_some((pi, packet.comp));
+            if parts.is_empty() || key != current {
+                parts.push(Vec::new());
+                current = key;
+                if params.split_components {
+                    comp_bytes[packet.comp] += TILE_PART_OVERHEAD;
+                }
+            }
+            let bytes = packet_bytes(tile, packet, lambda);
+            comp_bytes[packet.comp] += bytes.len();
+            parts.last_mut().unwrap().extend_from_slice(&bytes);
+        }
+    }
+    if parts.is_empty() {
+        parts.push(Vec::new());
+    }
+    (parts, comp_bytes)
+}
+
+// ============================================================================
+// Rate Control
+// ============================================================================
+
+/// Finds the smallest rate-distortion slope whose codestream fits the
+/// frame and component budgets. None keeps every pass.
+fn select_slope(params: &Params, tiles: &[Tile]) -> IoResult<Option<f64>> {
+    if params.max_bytes.is_none() && params.max_component_bytes.is_none() {
+        return Ok(None);
+    }
+    let fits = |lambda: Option<f64>| {
+        let (len, comps) = codestream_size(params, tiles, lambda);
+        params.max_bytes.is_none_or(|m| len <= m)
+            && params.max_component_bytes.is_none_or(|m| comps.iter().all(|&c| c <= m))
+    };
+    if fits(None) {
+        return Ok(None);
+    }
+
+    let slopes = tiles
+        .iter()
+        .flat_map(|t| &t.comps)
+        .flat_map(|c| c.bands.iter().flatten())
+        .flat_map(|b| &b.blocks)
+        .flat_map(|b| b.hull.iter().map(|h| h.1))
+        .filter(|s| s.is_finite() && *s > 0.0);
+    let (mut lo, mut hi) = slopes.fold((f64::MAX, 0.0f64), |(lo, hi), s| (lo.min(s), hi.max(s)));
+    hi = if hi > 0.0 { hi * 2.0 } else { 1.0 };
+    lo = lo.min(hi);
+
+    if !fits(Some(f64::INFINITY)) {
+        return Err(IoError::EncodeError(format!(
+            "JPEG 2000 rate limit of {} bytes is below the codestream overhead",
+            params.max_bytes.unwrap_or(0)
+        )));
+    }
+    if !fits(Some(hi)) {
+        return Ok(Some(f64::INFINITY));
+    }
+    for _ in 0..40 {
+        let mid = (lo * hi).sqrt();
+        if fits(Some(mid)) {
+            hi = mid;
+        } else {
+            lo = mid;
+        }
+    }
+    Ok(Some(hi))
+}
+
+/// Codestream length and per-component bytes at slope `lambda`.
+fn codestream_size(params: &Params, tiles: &[Tile], lambda: Option<f64>) -> (usize, Vec<usize>) {
+    let mut comps = vec![0; params.components];
+    let mut parts = 0;
+    let mut body = 0;
+    for tile in tiles {
+        let (tile_parts, comp_bytes) = tile_parts(params, tile, lambda);
+        parts += tile_parts.len();
+        body += tile_parts.iter().map(|p| p.len() + TILE_PART_OVERHEAD).sum::<usize>();
+        for (total, bytes) in comps.iter_mut().zip(comp_bytes) {
+            *total += bytes;
+        }
+    }
+    (main_header(params, &vec![0; parts]).len() + body + 2, comps)
+}
+
+// ============================================================================
+// Codestream
+// ============================================================================
+
+fn segment(out: &mut Vec<u8>, marker: u16, body: &[u8]) {
+    out.extend_from_slice(&marker.to_be_bytes());
+    out.extend_from_slice(&(body.len() as u16 + 2).to_be_bytes());
+    out.extend_from_slice(body);
+}
+
+/// SOC through the last main header marker segment. `tile_parts` holds
+/// the Psot of every tile-part, for TLM.
+fn main_header(params: &Params, tile_parts: &[u32]) -> Vec<u8> {
+    let mut out = SOC.to_be_bytes().to_vec();
+
+    let mut siz = Vec::new();
+    siz.extend_from_slice(&params.rsiz.to_be_bytes());
+    for v in [params.width, params.height, 0, 0, params.tile.0, params.tile.1, 0, 0] {
+        siz.extend_from_slice(&v.to_be_bytes());
+    }
+    siz.extend_from_slice(&(params.components as u16).to_be_bytes());
+    for _ in 0..params.components {
+        siz.extend_from_slice(&[params.precision - 1, 1, 1]);
+    }
+    segment(&mut out, SIZ, &siz);
+
+    let mut cod = vec![params.precincts.is_some() as u8, params.progression.code(), 0, 1];
+    cod.push(params.mct as u8);
+    cod.push(params.levels);
+    cod.push(params.code_block.0 - 2);
+    cod.push(params.code_block.1 - 2);
+    cod.push(0);
+    cod.push(params.reversible as u8);
+    if let Some(precincts) = &params.precincts {
+        cod.extend(precincts.iter().map(|&(x, y)| (y << 4) | x));
+    }
+    segment(&mut out, COD, &cod);
+
+    let quants = band_quantizers(params);
+    let mut qcd = vec![(params.guard_bits << 5) | if params.reversible { 0 } else { 2 }];
+    for q in &quants {
+        if params.reversible {
+            qcd.push(q.exponent << 3);
+        } else {
+            qcd.extend_from_slice(&(((q.exponent as u16) << 11) | q.mantissa).to_be_bytes());
+        }
+    }
+    segment(&mut out, QCD, &qcd);
+
+    if !params.pocs.is_empty() {
+        let mut poc = Vec::new();
+        for p in &params.pocs {
+            poc.push(p.resolutions.start as u8);
+            poc.push(p.components.start as u8);
+            poc.extend_from_slice(&1u16.to_be_bytes());
+            poc.push(p.resolutions.end as u8);
+            poc.push(p.components.end as u8);
+            poc.push(p.order.code());
+        }
+        segment(&mut out, POC, &poc);
+    }
+
+    if params.tlm {
+        // Ztlm 0; Stlm: 8-bit tile index, 32-bit tile-part length.
+        let mut tlm = vec![0, 0x50];
+        for &len in tile_parts {
+            tlm.push(0);
+            tlm.extend_from_slice(&len.to_be_bytes());
+        }
+        segment(&mut out, TLM, &tlm);
+    }
+
+    out
+}
+
+fn assemble(params: &Params, tiles: &[Tile], lambda: Option<f64>) -> IoResult<Vec<u8>> {
+    let mut body = Vec::new();
+    let mut lengths = Vec::new();
+    for tile in tiles {
+        let (parts, _) = tile_parts(params, tile, lambda);
+        let count = u8::try_from(parts.len())
+            .map_err(|_| IoError::EncodeError("JPEG 2000: too many tile-parts".into()))?;
+        for (i, part) in parts.iter().enumerate() {
+            let psot = u32::try_from(part.len() + TILE_PART_OVERHEAD)
+                .map_err(|_| IoError::EncodeError("JPEG 2000 tile-part exceeds 4 GB".into()))?;
+            let mut sot = Vec::new();
+            sot.extend_from_slice(&tile.index.to_be_bytes());
+            sot.extend_from_slice(&psot.to_be_bytes());
+            sot.extend_from_slice(&[i as u8, count]);
+            segment(&mut body, SOT, &sot);
+            body.extend_from_slice(&SOD.to_be_bytes());
+            body.extend_from_slice(part);
+            lengths.push(psot);
+        }
+    }
+
+    let mut out = main_header(params, &lengths);
+    out.extend_from_slice(&body);
+    out.extend_from_slice(&EOC.to_be_bytes());
+    Ok(out)
+}
+
+/// Encodes an image as a raw JPEG 2000 codestream (.j2c).
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::j2k::{encode, J2kWriterOptions};
+///
+/// let options = J2kWriterOptions { reversible: false, max_bytes: Some(500_000), ..Default::default() };
+/// let codestream = encode(&image, &options)?;
+/// assert!(codestream.len() <= 500_000);
+/// ```
+pub fn encode(image: &ImageData, options: &J2kWriterOptions) -> IoResult<Vec<u8>> {
+    let params = Params::new(image, options)?;
+    let planes = component_planes(image, params.precision);
+    let quants = band_quantizers(&params);
+
+    let rects = params.tile_rects();
+    if rects.len() > u16::MAX as usize || (params.tlm && rects.len() > 256) {
+        return Err(IoError::EncodeError("JPEG 2000: too many tiles".into()));
+    }
+    let tiles: Vec<Tile> = rects
+        .iter()
+        .enumerate()
+        .map(|(i, &rect)| encode_tile(&params, &planes, &quants, rect, i as u16))
+        .collect();
+
+    let lambda = select_slope(&params, &tiles)?;
+    assemble(&params, &tiles, lambda)
+}
+
+// ============================================================================
+// JP2 Container
+// ============================================================================
+
+/// JP2 signature box.
+const JP2_SIGNATURE: [u8; 12] = [0, 0, 0, 12, b'j', b'P', b' ', b' ', 0x0D, 0x0A, 0x87, 0x0A];
+
+fn put_box(out: &mut Vec<u8>, kind: &[u8; 4], body: &[u8]) {
+    out.extend_from_slice(&(body.len() as u32 + 8).to_be_bytes());
+    out.extend_from_slice(kind);
+    out.extend_from_slice(body);
+}
+
+/// Wraps a codestream in a minimal JP2 file: signature, file type,
+/// header (size, sRGB or greyscale colour, alpha definition) and the
+/// contiguous codestream box.
+fn wrap_jp2(image: &ImageData, precision: u8, codestream: &[u8]) -> IoResult<Vec<u8>> {
+    let nc = image.channels as u16;
+    let gray = nc < 3;
+
+    let mut ihdr = Vec::new();
+    ihdr.extend_from_slice(&image.height.to_be_bytes());
+    ihdr.extend_from_slice(&image.width.to_be_bytes());
+    ihdr.extend_from_slice(&nc.to_be_bytes());
+    // BPC, compression type 7, colourspace known, no IPR
+    ihdr.extend_from_slice(&[precision - 1, 7, 0, 0]);
+
+    let mut colr = vec![1, 0, 0];
+    colr.extend_from_slice(&(if gray { 17u32 } else { 16 }).to_be_bytes());
+
+    let mut jp2h = Vec::new();
+    put_box(&mut jp2h, b"ihdr", &ihdr);
+    put_box(&mut jp2h, b"colr", &colr);
+    let colors = if gray { 1 } else { 3 };
+    if nc == colors + 1 {
+        // Last channel is (unassociated) alpha, the rest are colours
+        let mut cdef = nc.to_be_bytes().to_vec();
+        for c in 0..nc {
+            let (typ, assoc) = if c == colors { (1u16, 0u16) } else { (0, c + 1) };
+            for v in [c, typ, assoc] {
+                cdef.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+        put_box(&mut jp2h, b"cdef", &cdef);
+    }
+
+    let mut out = JP2_SIGNATURE.to_vec();
+    put_box(&mut out, b"ftyp", b"jp2 \0\0\0\0jp2 ");
+    put_box(&mut out, b"jp2h", &jp2h);
+    let len = u32::try_from(codestream.len() + 8)
+        .map_err(|_| IoError::EncodeError("JPEG 2000 codestream exceeds 4 GB".into()))?;
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(b"jp2c");
+    out.extend_from_slice(codestream);
+    Ok(out)
+}
+
+/// Checks for the SOC and SIZ markers of a raw codestream.
+pub fn is_j2k_codestream(data: &[u8]) -> bool {
+    data.starts_with(&[0xFF, 0x4F, 0xFF, 0x51])
+}
+
+// ============================================================================
+// Writer
+// ============================================================================
+
+/// JPEG 2000 writer.
+///
+/// Files with a `.jp2` or `.jpx` extension get the JP2 container; every
+/// other extension, and [`write_to_memory`](FormatWriter::write_to_memory),
+/// produces a raw codestream as used in DCP track files.
+#[derive(Debug, Clone)]
+pub struct J2kWriter {
+    options: J2kWriterOptions,
+}
+
+impl J2kWriter {
+    /// Creates a new writer with default options (lossless).
+    pub fn new() -> Self {
+        Self::with_options(J2kWriterOptions::default())
+    }
+
+    /// Encodes `image` and wraps it in a JP2 container.
+    pub fn write_jp2(&self, image: &ImageData) -> IoResult<Vec<u8>> {
+        let params = Params::new(image, &self.options)?;
+        let codestream = encode(image, &self.options)?;
+        wrap_jp2(image, params.precision, &codestream)
+    }
+}
+
+impl Default for J2kWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatWriter<J2kWriterOptions> for J2kWriter {
+    /// Returns "JPEG 2000".
+    fn format_name(&self) -> &'static str {
+        "JPEG 2000"
+    }
+
+    /// Returns `["j2c", "j2k", "jp2"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["j2c", "j2k", "jp2"]
+    }
+
+    /// Writes a codestream, or a JP2 file for `.jp2`/`.jpx` paths.
+    fn write<P: AsRef<Path>>(&self, path: P, image: &ImageData) -> IoResult<()> {
+        let path = path.as_ref();
+        let ext = path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase());
+        let bytes = match ext.as_deref() {
+            Some("jp2") | Some("jpx") => self.write_jp2(image)?,
+            _ => encode(image, &self.options)?,
+        };
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Encodes a raw codestream to a byte vector.
+    fn write_to_memory(&self, image: &ImageData) -> IoResult<Vec<u8>> {
+        encode(image, &self.options)
+    }
+
+    /// Creates writer with custom options.
+    fn with_options(options: J2kWriterOptions) -> Self {
+        Self { options }
+    }
+}
+
+// ============================================================================
+// Convenience Functions
+// ============================================================================
+
+/// Writes a lossless JPEG 2000 file with default options.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::j2k;
+///
+/// j2k::write("master.jp2", &image)?;
+/// ```
+pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
+    J2kWriter::new().write(path, image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(width: u32, height: u32, channels: u32) -> ImageData {
+        let mut data = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                for c in 0..channels {
+                    data.push(((x * 7 + y * 13 + c * 50 + (x * y) % 11) % 256) as u8);
+                }
+            }
+        }
+        ImageData::from_u8(width, height, channels, data)
+    }
+
+    fn smooth(width: u32, height: u32, channels: u32) -> ImageData {
+        let mut data = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                for c in 0..channels {
+                    let v = 0.5 + 0.4 * ((x as f32 * 0.11 + c as f32).sin() * (y as f32 * 0.07).cos());
+                    data.push(v);
+                }
+            }
+        }
+        ImageData::from_f32(width, height, channels, data)
+    }
+
+    fn psnr(a: &[f32], b: &[f32]) -> f64 {
+        let mse = a.iter().zip(b).map(|(x, y)| ((x - y) as f64).powi(2)).sum::<f64>() / a.len() as f64;
+        10.0 * (1.0 / mse.max(1e-20)).log10()
+    }
+
+    #[test]
+    fn test_lossless_roundtrip_all_progressions() {
+        let image = gradient(37, 29, 3);
+        for progression in [
+            ProgressionOrder::Lrcp,
+            ProgressionOrder::Rlcp,
+            ProgressionOrder::Rpcl,
+            ProgressionOrder::Pcrl,
+            ProgressionOrder::Cprl,
+        ] {
+            let options = J2kWriterOptions {
+                progression,
+                levels: 3,
+                tile_size: Some((16, 20)),
+                code_block: (8, 16),
+                ..Default::default()
+            };
+            let stream = encode(&image, &options).unwrap();
+            let decoded = decode::decode(&stream).unwrap();
+            assert_eq!(decoded.precision, 8);
+            assert_eq!(decoded.to_u8(), image.to_u8(), "{:?}", progression);
+        }
+    }
+
+    #[test]
+    fn test_lossless_16bit_gray() {
+        let data: Vec<u16> = (0..23 * 17).map(|i| ((i * 2731) % 65536) as u16).collect();
+        let mut image = ImageData::new(23, 17, 1, PixelFormat::U16);
+        image.data = PixelData::U16(data.clone());
+        let options = J2kWriterOptions { code_block: (4, 8), ..Default::default() };
+        let stream = encode(&image, &options).unwrap();
+        let decoded = decode::decode(&stream).unwrap();
+        assert_eq!(decoded.precision, 16);
+        assert_eq!(decoded.samples, data.iter().map(|&v| v as i32).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_irreversible_quality() {
+        let image = smooth(40, 24, 3);
+        let options = J2kWriterOptions { reversible: false, bit_depth: Some(12), ..Default::default() };
+        let stream = encode(&image, &options).unwrap();
+        let decoded = decode::decode(&stream).unwrap();
+        assert!(psnr(&image.to_f32(), &decoded.to_f32()) > 50.0);
+    }
+
+    #[test]
+    fn test_rate_control() {
+        let image = smooth(48, 40, 3);
+        let mut last = 0.0;
+        for max_bytes in [600, 1500, 4000] {
+            let options = J2kWriterOptions {
+                reversible: false,
+                max_bytes: Some(max_bytes),
+                ..Default::default()
+            };
+            let stream = encode(&image, &options).unwrap();
+            assert!(stream.len() <= max_bytes, "{} > {}", stream.len(), max_bytes);
+            let quality = psnr(&image.to_f32(), &decode::decode(&stream).unwrap().to_f32());
+            assert!(quality > last, "{} <= {}", quality, last);
+            last = quality;
+        }
+
+        let options = J2kWriterOptions { max_bytes: Some(50), ..Default::default() };
+        assert!(encode(&image, &options).is_err());
+    }
+
+    fn markers(stream: &[u8], marker: u16) -> Vec<usize> {
+        let m = marker.to_be_bytes();
+        stream.windows(2).enumerate().filter(|(_, w)| *w == m).map(|(i, _)| i).collect()
+    }
+
+    fn tile_part_lengths(stream: &[u8]) -> Vec<u32> {
+        let mut pos = markers(stream, SOT)[0];
+        let mut lengths = Vec::new();
+        while stream[pos..pos + 2] == SOT.to_be_bytes() {
+            let psot = u32::from_be_bytes(stream[pos + 6..pos + 10].try_into().unwrap());
+            lengths.push(psot);
+            pos += psot as usize;
+        }
+        assert_eq!(stream[pos..], EOC.to_be_bytes());
+        lengths
+    }
+
+    fn tlm_lengths(stream: &[u8]) -> Vec<u32> {
+        let pos = markers(stream, TLM)[0];
+        let len = u16::from_be_bytes([stream[pos + 2], stream[pos + 3]]) as usize;
+        assert_eq!(stream[pos + 5], 0x50);
+        stream[pos + 6..pos + 2 + len].chunks(5).map(|e| u32::from_be_bytes(e[1..5].try_into().unwrap())).collect()
+    }
+
+    #[test]
+    fn test_cinema_2k_codestream() {
+        let image = smooth(64, 36, 3);
+        let options = J2kWriterOptions { max_bytes: Some(2000), ..J2kWriterOptions::cinema_2k(24) };
+        let stream = encode(&image, &options).unwrap();
+        assert!(is_j2k_codestream(&stream));
+        assert!(stream.len() <= 2000);
+
+        // Rsiz, then COD (precincts, CPRL, 5 levels, 32x32, 9/7), QCD
+        assert_eq!(stream[6..8], [0, 3]);
+        let cod = markers(&stream, COD)[0];
+        assert_eq!(stream[cod + 4..cod + 14], [1, 4, 0, 1, 0, 5, 3, 3, 0, 0]);
+        assert_eq!(stream[cod + 14..cod + 20], [0x77, 0x88, 0x88, 0x88, 0x88, 0x88]);
+        let qcd = markers(&stream, QCD)[0];
+        assert_eq!(stream[qcd + 4], 0x22);
+
+        let parts = tile_part_lengths(&stream);
+        assert_eq!(parts.len(), 3);
+        assert_eq!(tlm_lengths(&stream), parts);
+
+        let decoded = decode::decode(&stream).unwrap();
+        assert_eq!(decoded.precision, 12);
+        assert!(psnr(&image.to_f32(), &decoded.to_f32()) > 30.0);
+    }
+
+    #[test]
+    fn test_cinema_4k_codestream() {
+        let image = smooth(80, 40, 3);
+        let stream = encode(&image, &J2kWriterOptions::cinema_4k(24)).unwrap();
+        assert_eq!(stream[6..8], [0, 4]);
+        let poc = markers(&stream, POC)[0];
+        assert_eq!(stream[poc + 4..poc + 18], [0, 0, 0, 1, 6, 3, 4, 6, 0, 0, 1, 7, 3, 4]);
+
+        let parts = tile_part_lengths(&stream);
+        assert_eq!(parts.len(), 6);
+        assert_eq!(tlm_lengths(&stream), parts);
+
+        let decoded = decode::decode(&stream).unwrap();
+        assert!(psnr(&image.to_f32(), &decoded.to_f32()) > 50.0);
+    }
+
+    #[test]
+    fn test_cinema_restrictions() {
+        let rgba = smooth(16, 16, 4);
+        assert!(encode(&rgba, &J2kWriterOptions::cinema_2k(24)).is_err());
+        let wide = smooth(2050, 1, 3);
+        assert!(encode(&wide, &J2kWriterOptions::cinema_2k(24)).is_err());
+    }
+
+    #[test]
+    fn test_dci_budgets() {
+        assert_eq!(dci_max_frame_bytes(24), 1_302_083);
+        assert_eq!(dci_max_component_bytes(24), 1_041_666);
+        assert_eq!(dci_max_frame_bytes(48), 651_041);
+    }
+
+    #[test]
+    fn test_jp2_container() {
+        let image = gradient(9, 7, 4);
+        let bytes = J2kWriter::new().write_jp2(&image).unwrap();
+        assert_eq!(bytes[..12], JP2_SIGNATURE);
+        assert_eq!(&bytes[16..20], b"ftyp");
+        assert!(bytes.windows(4).any(|w| w == b"cdef"));
+        let start = bytes.windows(4).position(|w| w == b"jp2c").unwrap() + 4;
+        assert!(is_j2k_codestream(&bytes[start..]));
+        assert_eq!(decode::decode(&bytes[start..]).unwrap().to_u8(), image.to_u8());
+    }
+}
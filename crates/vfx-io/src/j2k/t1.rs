@@ -0,0 +1,543 @@
+//! Tier-1 coding: the MQ arithmetic coder (Annex C) and the EBCOT
+//! bit-plane passes (Annex D).
+//!
+//! Code-blocks use the default coding style: one arithmetic codeword
+//! segment per block, no bypass, no context reset and no termination
+//! between passes. Each pass records how many bytes a decoder needs to
+//! reach its end and how much it lowers the block's squared error, which
+//! is what rate control truncates on.
+
+/// MQ coder probability states: (Qe, NMPS, NLPS, SWITCH), Table C.2.
+const QE: [(u32, u8, u8, bool); 47] = [
+    (0x5601, 1, 1, true),
+    (0x3401, 2, 6, false),
+    (0x1801, 3, 9, false),
+    (0x0AC1, 4, 12, false),
+    (0x0521, 5, 29, false),
+    (0x0221, 38, 33, false),
+    (0x5601, 7, 6, true),
+    (0x5401, 8, 14, false),
+    (0x4801, 9, 14, false),
+    (0x3801, 10, 14, false),
+    (0x3001, 11, 17, false),
+    (0x2401, 12, 18, false),
+    (0x1C01, 13, 20, false),
+    (0x1601, 29, 21, false),
+    (0x5601, 15, 14, true),
+    (0x5401, 16, 14, false),
+    (0x5101, 17, 15, false),
+    (0x4801, 18, 16, false),
+    (0x3801, 19, 17, false),
+    (0x3401, 20, 18, false),
+    (0x3001, 21, 19, false),
+    (0x2801, 22, 19, false),
+    (0x2401, 23, 20, false),
+    (0x2201, 24, 21, false),
+    (0x1C01, 25, 22, false),
+    (0x1801, 26, 23, false),
+    (0x1601, 27, 24, false),
+    (0x1401, 28, 25, false),
+    (0x1201, 29, 26, false),
+    (0x1101, 30, 27, false),
+    (0x0AC1, 31, 28, false),
+    (0x09C1, 32, 29, false),
+    (0x08A1, 33, 30, false),
+    (0x0521, 34, 31, false),
+    (0x0441, 35, 32, false),
+    (0x02A1, 36, 33, false),
+    (0x0221, 37, 34, false),
+    (0x0141, 38, 35, false),
+    (0x0111, 39, 36, false),
+    (0x0085, 40, 37, false),
+    (0x0049, 41, 38, false),
+    (0x0025, 42, 39, false),
+    (0x0015, 43, 40, false),
+    (0x0009, 44, 41, false),
+    (0x0005, 45, 42, false),
+    (0x0001, 45, 43, false),
+    (0x5601, 46, 46, false),
+];
+
+/// Contexts 0-8 are zero coding, 9-13 sign coding, 14-16 magnitude
+/// refinement.
+const CTX_SC: usize = 9;
+const CTX_MR: usize = 14;
+const CTX_RL: usize = 17;
+const CTX_UNIFORM: usize = 18;
+const NUM_CONTEXTS: usize = 19;
+
+// ============================================================================
+// MQ Encoder
+// ============================================================================
+
+/// MQ arithmetic encoder (C.2), writing into a byte vector whose first
+/// entry stands in for the byte before the codeword.
+struct MqEncoder {
+    a: u32,
+    c: u32,
+    ct: u32,
+    bytes: Vec<u8>,
+    /// Per-context (state index, MPS).
+    contexts: [(u8, u8); NUM_CONTEXTS],
+}
+
+impl MqEncoder {
+    fn new() -> Self {
+        let mut contexts = [(0, 0); NUM_CONTEXTS];
+        contexts[0] = (4, 0);
+        contexts[CTX_RL] = (3, 0);
+        contexts[CTX_UNIFORM] = (46, 0);
+        Self { a: 0x8000, c: 0, ct: 12, bytes: vec![0], contexts }
+    }
+
+    fn encode(&mut self, ctx: usize, bit: u32) {
+        let (state, mps) = self.contexts[ctx];
+        let (qe, nmps, nlps, switch) = QE[state as usize];
+        self.a -= qe;
+        if bit == mps as u32 {
+            if self.a & 0x8000 != 0 {
+                self.c += qe;
+                return;
+            }
+            if self.a < qe {
+                self.a = qe;
+            } else {
+                self.c += qe;
+            }
+            self.contexts[ctx].0 = nmps;
+        } else {
+            if self.a < qe {
+                self.c += qe;
+            } else {
+                self.a = qe;
+            }
+            if switch {
+                self.contexts[ctx].1 = 1 - mps;
+            }
+            self.contexts[ctx].0 = nlps;
+        }
+        while self.a & 0x8000 == 0 {
+            self.a <<= 1;
+            self.c <<= 1;
+            self.ct -= 1;
+            if self.ct == 0 {
+                self.byte_out();
+            }
+        }
+    }
+
+    fn byte_out(&mut self) {
+        let last = self.bytes.len() - 1;
+        if self.bytes[last] == 0xFF {
+            self.bytes.push((self.c >> 20) as u8);
+            self.c &= 0xFFFFF;
+            self.ct = 7;
+        } else if self.c & 0x800_0000 == 0 {
+            self.bytes.push((self.c >> 19) as u8);
+            self.c &= 0x7FFFF;
+            self.ct = 8;
+        } else {
+            self.bytes[last] += 1;
+            if self.bytes[last] == 0xFF {
+                self.c &= 0x7FF_FFFF;
+                self.bytes.push((self.c >> 20) as u8);
+                self.c &= 0xFFFFF;
+                self.ct = 7;
+            } else {
+                self.bytes.push((self.c >> 19) as u8);
+                self.c &= 0x7FFFF;
+                self.ct = 8;
+            }
+        }
+    }
+
+    /// Bytes a decoder needs to decode every symbol coded so far.
+    ///
+    /// Covers the last output byte, which a carry may still change, plus
+    /// every bit of the C register down to the interval's precision.
+    /// Later output only narrows the interval, so the final codeword cut
+    /// at this length (and padded with 1 bits) still decodes correctly.
+    fn truncation_length(&self) -> usize {
+        let written = self.bytes.len() - 1;
+        let stuffed = self.bytes[written] == 0xFF && written > 0;
+        written + (27 - self.ct as usize).div_ceil(8) + stuffed as usize
+    }
+
+    /// Terminates the codeword (C.2.9) and returns it.
+    fn flush(mut self) -> Vec<u8> {
+        let temp = self.c + self.a;
+        self.c |= 0xFFFF;
+        if self.c >= temp {
+            self.c -= 0x8000;
+        }
+        self.c <<= self.ct;
+        self.byte_out();
+        self.c <<= self.ct;
+        self.byte_out();
+        if self.bytes.last() == Some(&0xFF) {
+            self.bytes.pop();
+        }
+        self.bytes.remove(0);
+        self.bytes
+    }
+}
+
+// ============================================================================
+// Context Formation
+// ============================================================================
+
+// Per-sample state. The low byte holds the significance of the eight
+// neighbours, the next nibble the signs of the four direct ones.
+const NW: u16 = 1;
+const N: u16 = 1 << 1;
+const NE: u16 = 1 << 2;
+const W: u16 = 1 << 3;
+const E: u16 = 1 << 4;
+const SW: u16 = 1 << 5;
+const S: u16 = 1 << 6;
+const SE: u16 = 1 << 7;
+const N_NEG: u16 = 1 << 8;
+const W_NEG: u16 = 1 << 9;
+const E_NEG: u16 = 1 << 10;
+const S_NEG: u16 = 1 << 11;
+const SIG: u16 = 1 << 12;
+const VISITED: u16 = 1 << 13;
+const REFINED: u16 = 1 << 14;
+const NEIGHBOURS: u16 = 0xFF;
+
+/// Zero coding contexts by subband orientation and neighbour byte
+/// (Table D.1).
+static ZC_CONTEXTS: [[u8; 256]; 4] =
+    [zc_table(0), zc_table(1), zc_table(2), zc_table(3)];
+
+const fn zc_table(orient: u8) -> [u8; 256] {
+    let mut table = [0u8; 256];
+    let mut f = 0;
+    while f < 256 {
+        let mut h = bit(f, 3) + bit(f, 4);
+        let mut v = bit(f, 1) + bit(f, 6);
+        let d = bit(f, 0) + bit(f, 2) + bit(f, 5) + bit(f, 7);
+        // HL bands see vertical structure, so H and V swap roles.
+        if orient == 1 {
+            let t = h;
+            h = v;
+            v = t;
+        }
+        table[f] = if orient == 3 {
+            let hv = h + v;
+            match d {
+                0 => match hv {
+                    0 => 0,
+                    1 => 1,
+                    _ => 2,
+                },
+                1 => match hv {
+                    0 => 3,
+                    1 => 4,
+                    _ => 5,
+                },
+                2 => {
+                    if hv == 0 {
+                        6
+                    } else {
+                        7
+                    }
+                }
+                _ => 8,
+            }
+        } else {
+            match h {
+                0 => match v {
+                    0 => match d {
+                        0 => 0,
+                        1 => 1,
+                        _ => 2,
+                    },
+                    1 => 3,
+                    _ => 4,
+                },
+                1 => {
+                    if v > 0 {
+                        7
+                    } else if d > 0 {
+                        6
+                    } else {
+                        5
+                    }
+                }
+                _ => 8,
+            }
+        };
+        f += 1;
+    }
+    table
+}
+
+const fn bit(flags: usize, n: usize) -> u8 {
+    ((flags >> n) & 1) as u8
+}
+
+/// Sign coding context and XOR bit (Table D.3).
+fn sign_context(flags: u16) -> (usize, u32) {
+    let contribution = |sig: u16, neg: u16| -> i32 {
+        if flags & sig == 0 {
+            0
+        } else if flags & neg != 0 {
+            -1
+        } else {
+            1
+        }
+    };
+    let h = (contribution(W, W_NEG) + contribution(E, E_NEG)).clamp(-1, 1);
+    let v = (contribution(N, N_NEG) + contribution(S, S_NEG)).clamp(-1, 1);
+    let (ctx, xor) = match (h, v) {
+        (1, 1) => (4, 0),
+        (1, 0) => (3, 0),
+        (1, _) => (2, 0),
+        (0, 1) => (1, 0),
+        (0, 0) => (0, 0),
+        (0, _) => (1, 1),
+        (_, 1) => (2, 1),
+        (_, 0) => (3, 1),
+        _ => (4, 1),
+    };
+    (CTX_SC + ctx, xor)
+}
+
+/// Magnitude refinement context (Table D.4).
+fn refinement_context(flags: u16) -> usize {
+    if flags & REFINED != 0 {
+        CTX_MR + 2
+    } else if flags & NEIGHBOURS != 0 {
+        CTX_MR + 1
+    } else {
+        CTX_MR
+    }
+}
+
+// ============================================================================
+// Code-Block Encoder
+// ============================================================================
+
+/// One coding pass of a code-block.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Pass {
+    /// Bytes of the codeword needed to decode up to the end of this pass.
+    pub rate: usize,
+    /// Cumulative reduction in squared error, in quantizer step units.
+    pub distortion: f64,
+}
+
+/// An entropy-coded code-block.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CodedBlock {
+    pub data: Vec<u8>,
+    pub passes: Vec<Pass>,
+    /// Most significant magnitude bit-planes that are all zero.
+    pub missing_msbs: u32,
+}
+
+/// Encodes one code-block.
+///
+/// `values` are the `width * height` coefficients of the block in raster
+/// order, divided by the quantizer step; the integer part of the
+/// magnitude is what gets coded. `orient` is 0 (LL), 1 (HL), 2 (LH) or 3
+/// (HH) and `planes` is the band's magnitude bit-plane count Mb.
+pub(crate) fn encode_block(
+    values: &[f32],
+    width: usize,
+    height: usize,
+    orient: u8,
+    planes: u32,
+) -> CodedBlock {
+    let cap = if planes >= 31 { i32::MAX as u32 } else { (1u32 << planes) - 1 };
+    let mags: Vec<u32> = values.iter().map(|v| (v.abs() as u32).min(cap)).collect();
+    let max = mags.iter().copied().max().unwrap_or(0);
+    if max == 0 {
+        return CodedBlock { missing_msbs: planes, ..Default::default() };
+    }
+    let bitplanes = 32 - max.leading_zeros();
+
+    let mut coder = BlockCoder {
+        mq: MqEncoder::new(),
+        zc: &ZC_CONTEXTS[orient as usize],
+        width,
+        height,
+        stride: width + 2,
+        flags: vec![0; (width + 2) * (height + 2)],
+        mags,
+        values,
+        distortion: 0.0,
+    };
+
+    let mut passes = Vec::with_capacity(bitplanes as usize * 3);
+    for p in (0..bitplanes).rev() {
+        if p + 1 < bitplanes {
+            coder.significance_pass(p);
+            passes.push(coder.pass_end());
+            coder.refinement_pass(p);
+            passes.push(coder.pass_end());
+        }
+        coder.cleanup_pass(p);
+        passes.push(coder.pass_end());
+    }
+
+    let data = coder.mq.flush();
+    let mut previous = 0;
+    for pass in passes.iter_mut() {
+        let mut rate = pass.rate.min(data.len()).max(previous);
+        // A truncated segment must not end on 0xFF; the decoder pads
+        // with 1 bits anyway.
+        if rate > previous && data[rate - 1] == 0xFF {
+            rate -= 1;
+        }
+        pass.rate = rate;
+        previous = rate;
+    }
+    if let Some(last) = passes.last_mut() {
+        last.rate = data.len();
+    }
+
+    CodedBlock { data, passes, missing_msbs: planes - bitplanes }
+}
+
+struct BlockCoder<'a> {
+    mq: MqEncoder,
+    zc: &'a [u8; 256],
+    width: usize,
+    height: usize,
+    /// Row stride of `flags`, which has a one-sample border.
+    stride: usize,
+    flags: Vec<u16>,
+    mags: Vec<u32>,
+    values: &'a [f32],
+    distortion: f64,
+}
+
+impl BlockCoder<'_> {
+    fn pass_end(&self) -> Pass {
+        Pass { rate: self.mq.truncation_length(), distortion: self.distortion }
+    }
+
+    /// Calls `f(index, flag_index)` in stripe order: stripes of four rows,
+    /// each scanned column by column.
+    fn for_each_stripe_sample(&mut self, mut f: impl FnMut(&mut Self, usize, usize)) {
+        for y0 in (0..self.height).step_by(4) {
+            for x in 0..self.width {
+                for y in y0..(y0 + 4).min(self.height) {
+                    f(self, y * self.width + x, (y + 1) * self.stride + x + 1);
+                }
+            }
+        }
+    }
+
+    fn significance_pass(&mut self, p: u32) {
+        self.for_each_stripe_sample(|c, i, fi| {
+            let flags = c.flags[fi];
+            if flags & SIG == 0 && flags & NEIGHBOURS != 0 {
+                c.code_significance(i, fi, p);
+                c.flags[fi] |= VISITED;
+            }
+        });
+    }
+
+    fn refinement_pass(&mut self, p: u32) {
+        self.for_each_stripe_sample(|c, i, fi| {
+            let flags = c.flags[fi];
+            if flags & (SIG | VISITED) == SIG {
+                let bit = (c.mags[i] >> p) & 1;
+                c.mq.encode(refinement_context(flags), bit);
+                c.flags[fi] |= REFINED;
+                c.distortion += refinement_gain(c.values[i].abs(), c.mags[i], p);
+            }
+        });
+    }
+
+    fn cleanup_pass(&mut self, p: u32) {
+        for y0 in (0..self.height).step_by(4) {
+            let rows = (self.height - y0).min(4);
+            for x in 0..self.width {
+                let (width, stride) = (self.width, self.stride);
+                let index = |y: usize| (y * width + x, (y + 1) * stride + x + 1);
+                let mut start = 0;
+
+                let run_mode = rows == 4
+                    && (0..4).all(|k| {
+                        self.flags[index(y0 + k).1] & (SIG | VISITED | NEIGHBOURS) == 0
+                    });
+                if run_mode {
+                    let first = (0..4).find(|&k| (self.mags[index(y0 + k).0] >> p) & 1 == 1);
+                    let Some(k) = first else {
+                        self.mq.encode(CTX_RL, 0);
+                        continue;
+                    };
+                    self.mq.encode(CTX_RL, 1);
+                    self.mq.encode(CTX_UNIFORM, (k as u32 >> 1) & 1);
+                    self.mq.encode(CTX_UNIFORM, k as u32 & 1);
+                    let (i, fi) = index(y0 + k);
+                    self.code_sign(i, fi, p);
+                    start = k + 1;
+                }
+
+                for k in start..rows {
+                    let (i, fi) = index(y0 + k);
+                    if self.flags[fi] & (SIG | VISITED) == 0 {
+                        self.code_significance(i, fi, p);
+                    }
+                }
+            }
+        }
+        for f in self.flags.iter_mut() {
+            *f &= !VISITED;
+        }
+    }
+
+    /// Zero-codes one sample and, if it becomes significant, its sign.
+    fn code_significance(&mut self, i: usize, fi: usize, p: u32) {
+        let bit = (self.mags[i] >> p) & 1;
+        let ctx = self.zc[(self.flags[fi] & NEIGHBOURS) as usize] as usize;
+        self.mq.encode(ctx, bit);
+        if bit == 1 {
+            self.code_sign(i, fi, p);
+        }
+    }
+
+    /// Codes the sign of a newly significant sample and updates the
+    /// neighbourhood state.
+    fn code_sign(&mut self, i: usize, fi: usize, p: u32) {
+        let negative = self.values[i] < 0.0;
+        let (ctx, xor) = sign_context(self.flags[fi]);
+        self.mq.encode(ctx, negative as u32 ^ xor);
+        self.distortion += significance_gain(self.values[i].abs(), p);
+
+        let s = self.stride;
+        let neg = |flag: u16| if negative { flag } else { 0 };
+        self.flags[fi] |= SIG;
+        self.flags[fi - s - 1] |= SE;
+        self.flags[fi - s] |= S | neg(S_NEG);
+        self.flags[fi - s + 1] |= SW;
+        self.flags[fi - 1] |= E | neg(E_NEG);
+        self.flags[fi + 1] |= W | neg(W_NEG);
+        self.flags[fi + s - 1] |= NE;
+        self.flags[fi + s] |= N | neg(N_NEG);
+        self.flags[fi + s + 1] |= NW;
+    }
+}
+
+/// Error reduction when a sample of magnitude `v` becomes significant at
+/// bit-plane `p` and is reconstructed at the middle of its interval.
+fn significance_gain(v: f32, p: u32) -> f64 {
+    let v = v as f64;
+    let step = (1u64 << p) as f64;
+    let recon = 1.5 * step;
+    v * v - (v - recon) * (v - recon)
+}
+
+/// Error reduction from refining a significant sample at bit-plane `p`.
+fn refinement_gain(v: f32, mag: u32, p: u32) -> f64 {
+    let v = v as f64;
+    let step = (1u64 << p) as f64;
+    let before = ((mag >> (p + 1)) as f64 + 0.5) * 2.0 * step;
+    let after = ((mag >> p) as f64 + 0.5) * step;
+    (v - before) * (v - before) - (v - after) * (v - after)
+}
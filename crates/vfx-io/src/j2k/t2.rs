@@ -0,0 +1,407 @@
+//! Tier-2 coding: tile geometry, progression orders and packet headers
+//! (ISO/IEC 15444-1 Annex B).
+
+use super::ProgressionOrder;
+
+// ============================================================================
+// Geometry
+// ============================================================================
+
+/// Half-open rectangle on the reference grid or in resolution/band
+/// coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Rect {
+    pub x0: u32,
+    pub y0: u32,
+    pub x1: u32,
+    pub y1: u32,
+}
+
+impl Rect {
+    pub fn new(x0: u32, y0: u32, x1: u32, y1: u32) -> Self {
+        Self { x0, y0, x1, y1 }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.x1.saturating_sub(self.x0)
+    }
+
+    pub fn height(&self) -> u32 {
+        self.y1.saturating_sub(self.y0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width() == 0 || self.height() == 0
+    }
+
+    /// Coordinates divided by `2^n`, rounding up (B-14).
+    pub fn scaled_down(&self, n: u32) -> Self {
+        let f = |v: u32| ceil_shift(v as i64, n) as u32;
+        Self::new(f(self.x0), f(self.y0), f(self.x1), f(self.y1))
+    }
+
+    pub fn intersect(&self, other: &Rect) -> Self {
+        Self::new(
+            self.x0.max(other.x0),
+            self.y0.max(other.y0),
+            self.x1.min(other.x1),
+            self.y1.min(other.y1),
+        )
+    }
+}
+
+/// `ceil(v / 2^n)` for possibly negative `v`.
+fn ceil_shift(v: i64, n: u32) -> i64 {
+    -((-v) >> n)
+}
+
+/// A subband of one resolution level.
+#[derive(Debug, Clone)]
+pub(crate) struct BandGeom {
+    /// 0 = LL, 1 = HL, 2 = LH, 3 = HH.
+    pub orient: u8,
+    pub rect: Rect,
+    /// Position of the band in the Mallat layout of the transformed tile.
+    pub layout_x: u32,
+    pub layout_y: u32,
+    /// Log2 code-block size, clipped to the precinct.
+    pub cbw: u32,
+    pub cbh: u32,
+}
+
+impl BandGeom {
+    /// Code-block index range covering the band.
+    pub fn block_grid(&self) -> Rect {
+        grid(&self.rect, self.cbw, self.cbh)
+    }
+
+    /// Rectangle of code-block `(cbx, cby)`, clipped to the band.
+    pub fn block_rect(&self, cbx: u32, cby: u32) -> Rect {
+        Rect::new(
+            cbx << self.cbw,
+            cby << self.cbh,
+            (cbx + 1) << self.cbw,
+            (cby + 1) << self.cbh,
+        )
+        .intersect(&self.rect)
+    }
+}
+
+/// A precinct: one packet's worth of code-blocks in every band.
+#[derive(Debug, Clone)]
+pub(crate) struct PrecinctGeom {
+    /// Reference grid position used by the position-driven progressions.
+    pub anchor: (u32, u32),
+    /// Code-block index range in each band of the resolution.
+    pub blocks: Vec<Rect>,
+}
+
+/// One resolution level of a tile-component.
+#[derive(Debug, Clone)]
+pub(crate) struct ResolutionGeom {
+    pub bands: Vec<BandGeom>,
+    pub precincts: Vec<PrecinctGeom>,
+}
+
+/// Partition of `r` into cells of `2^w x 2^h`, as a range of cell indices.
+fn grid(r: &Rect, w: u32, h: u32) -> Rect {
+    if r.is_empty() {
+        return Rect::new(0, 0, 0, 0);
+    }
+    Rect::new(r.x0 >> w, r.y0 >> h, r.scaled_down(w).x1, r.scaled_down(h).y1)
+}
+
+/// Lays out the resolutions, bands, precincts and code-blocks of a
+/// tile-component with the given decomposition levels, log2 code-block
+/// size and per-resolution log2 precinct sizes.
+pub(crate) fn tile_component(
+    tc: Rect,
+    levels: u8,
+    code_block: (u8, u8),
+    precincts: &[(u8, u8)],
+) -> Vec<ResolutionGeom> {
+    let nl = levels as u32;
+    let mut resolutions = Vec::with_capacity(nl as usize + 1);
+
+    for r in 0..=nl {
+        let rect = tc.scaled_down(nl - r);
+        let (ppx, ppy) = precincts[r as usize];
+        let (ppx, ppy) = (ppx as u32, ppy as u32);
+        // Band-domain precinct size is halved above the lowest resolution.
+        let (bpx, bpy) = if r == 0 { (ppx, ppy) } else { (ppx - 1, ppy - 1) };
+        let cbw = (code_block.0 as u32).min(bpx);
+        let cbh = (code_block.1 as u32).min(bpy);
+
+        let bands: Vec<BandGeom> = if r == 0 {
+            vec![BandGeom { orient: 0, rect, layout_x: 0, layout_y: 0, cbw, cbh }]
+        } else {
+            let n = nl - r + 1;
+            let low = tc.scaled_down(n);
+            (1u8..=3)
+                .map(|orient| {
+                    let (xo, yo) = ((orient & 1) as i64, (orient >> 1) as i64);
+                    let f = |v: u32, o: i64| ceil_shift(v as i64 - (o << (n - 1)), n) as u32;
+                    BandGeom {
+                        orient,
+                        rect: Rect::new(f(tc.x0, xo), f(tc.y0, yo), f(tc.x1, xo), f(tc.y1, yo)),
+                        layout_x: if xo == 1 { low.width() } else { 0 },
+                        layout_y: if yo == 1 { low.height() } else { 0 },
+                        cbw,
+                        cbh,
+                    }
+                })
+                .collect()
+        };
+
+        let cells = grid(&rect, ppx, ppy);
+        let shift = nl - r;
+        let mut precinct_list = Vec::new();
+        for py in cells.y0..cells.y1 {
+            for px in cells.x0..cells.x1 {
+                let blocks = bands
+                    .iter()
+                    .map(|band| {
+                        let area = Rect::new(
+                            px << bpx,
+                            py << bpy,
+                            (px + 1) << bpx,
+                            (py + 1) << bpy,
+                        )
+                        .intersect(&band.rect);
+                        grid(&area, band.cbw, band.cbh)
+                    })
+                    .collect();
+                let anchor = (
+                    tc.x0.max((px << ppx) << shift),
+                    tc.y0.max((py << ppy) << shift),
+                );
+                precinct_list.push(PrecinctGeom { anchor, blocks });
+            }
+        }
+
+        resolutions.push(ResolutionGeom { bands, precincts: precinct_list });
+    }
+
+    resolutions
+}
+
+// ============================================================================
+// Progression
+// ============================================================================
+
+/// One packet of a tile (the single quality layer is implied).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PacketRef {
+    pub comp: usize,
+    pub res: usize,
+    pub precinct: usize,
+}
+
+/// Packets of a tile in progression order, restricted to the resolution
+/// and component ranges of one progression (B.12.1).
+pub(crate) fn packet_order(
+    comps: &[Vec<ResolutionGeom>],
+    order: ProgressionOrder,
+    resolutions: std::ops::Range<usize>,
+    components: std::ops::Range<usize>,
+) -> Vec<PacketRef> {
+    let mut packets = Vec::new();
+    for comp in components.clone().filter(|&c| c < comps.len()) {
+        for res in resolutions.clone().filter(|&r| r < comps[comp].len()) {
+            for precinct in 0..comps[comp][res].precincts.len() {
+                packets.push(PacketRef { comp, res, precinct });
+            }
+        }
+    }
+
+    let anchor = |p: &PacketRef| {
+        let (x, y) = comps[p.comp][p.res].precincts[p.precinct].anchor;
+        (y, x)
+    };
+    match order {
+        ProgressionOrder::Lrcp | ProgressionOrder::Rlcp => {
+            packets.sort_by_key(|p| (p.res, p.comp, p.precinct))
+        }
+        ProgressionOrder::Rpcl => packets.sort_by_key(|p| (p.res, anchor(p), p.comp)),
+        ProgressionOrder::Pcrl => packets.sort_by_key(|p| (anchor(p), p.comp, p.res)),
+        ProgressionOrder::Cprl => packets.sort_by_key(|p| (p.comp, anchor(p), p.res)),
+    }
+    packets
+}
+
+// ============================================================================
+// Packet Headers
+// ============================================================================
+
+/// Bit writer with the packet header bit-stuffing rule: a byte following
+/// 0xFF only carries 7 bits.
+struct BitWriter {
+    out: Vec<u8>,
+    current: u8,
+    bits: u8,
+    capacity: u8,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self { out: Vec::new(), current: 0, bits: 0, capacity: 8 }
+    }
+
+    fn put(&mut self, bit: bool) {
+        self.current = (self.current << 1) | bit as u8;
+        self.bits += 1;
+        if self.bits == self.capacity {
+            self.emit();
+        }
+    }
+
+    fn put_bits(&mut self, value: u32, count: u32) {
+        for i in (0..count).rev() {
+            self.put((value >> i) & 1 == 1);
+        }
+    }
+
+    fn emit(&mut self) {
+        self.out.push(self.current);
+        self.capacity = if self.current == 0xFF { 7 } else { 8 };
+        self.current = 0;
+        self.bits = 0;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.current <<= self.capacity - self.bits;
+            self.emit();
+        }
+        if self.out.last() == Some(&0xFF) {
+            self.out.push(0);
+        }
+        self.out
+    }
+}
+
+/// Tag tree encoder (B.10.2).
+struct TagTree {
+    value: Vec<u32>,
+    low: Vec<u32>,
+    known: Vec<bool>,
+    parent: Vec<usize>,
+}
+
+impl TagTree {
+    /// Builds a tree over a `width x height` grid of leaf values.
+    fn new(width: usize, height: usize, leaves: &[u32]) -> Self {
+        let mut value = leaves.to_vec();
+        let mut parent = Vec::new();
+        let (mut w, mut h, mut start) = (width, height, 0);
+        while w * h > 1 {
+            let (pw, ph) = (w.div_ceil(2), h.div_ceil(2));
+            let next = start + w * h;
+            value.resize(next + pw * ph, u32::MAX);
+            for y in 0..h {
+                for x in 0..w {
+                    let p = next + (y / 2) * pw + x / 2;
+                    parent.push(p);
+                    value[p] = value[p].min(value[start + y * w + x]);
+                }
+            }
+            (w, h, start) = (pw, ph, next);
+        }
+        parent.push(usize::MAX);
+        let n = value.len();
+        Self { value, low: vec![0; n], known: vec![false; n], parent }
+    }
+
+    /// Codes whether leaf's value is below `threshold`, and the value
+    /// itself if so.
+    fn encode(&mut self, bw: &mut BitWriter, leaf: usize, threshold: u32) {
+        let mut path = vec![leaf];
+        while self.parent[*path.last().unwrap()] != usize::MAX {
+            path.push(self.parent[*path.last().unwrap()]);
+        }
+        let mut low = 0;
+        for &node in path.iter().rev() {
+            low = low.max(self.low[node]);
+            while low < threshold {
+                if low >= self.value[node] {
+                    if !self.known[node] {
+                        bw.put(true);
+                        self.known[node] = true;
+                    }
+                    break;
+                }
+                bw.put(false);
+                low += 1;
+            }
+            self.low[node] = low;
+        }
+    }
+}
+
+/// A code-block's contribution to a packet.
+pub(crate) struct BlockContribution<'a> {
+    /// Codeword bytes included in this packet.
+    pub data: &'a [u8],
+    /// Coding passes included (0 = not included).
+    pub passes: usize,
+    pub missing_msbs: u32,
+}
+
+/// Code-blocks of one band within a precinct, in raster order.
+pub(crate) struct PrecinctBand<'a> {
+    pub width: usize,
+    pub height: usize,
+    pub blocks: Vec<BlockContribution<'a>>,
+}
+
+/// Encodes the only quality layer of a precinct: header then body.
+pub(crate) fn encode_packet(bands: &[PrecinctBand]) -> Vec<u8> {
+    let mut bw = BitWriter::new();
+    let included = bands.iter().flat_map(|b| &b.blocks).any(|b| b.passes > 0);
+    bw.put(included);
+
+    if included {
+        for band in bands.iter().filter(|b| !b.blocks.is_empty()) {
+            let inclusion: Vec<u32> = band.blocks.iter().map(|b| (b.passes == 0) as u32).collect();
+            let msbs: Vec<u32> = band.blocks.iter().map(|b| b.missing_msbs).collect();
+            let mut inclusion = TagTree::new(band.width, band.height, &inclusion);
+            let mut msb_tree = TagTree::new(band.width, band.height, &msbs);
+
+            for (i, block) in band.blocks.iter().enumerate() {
+                inclusion.encode(&mut bw, i, 1);
+                if block.passes == 0 {
+                    continue;
+                }
+                msb_tree.encode(&mut bw, i, block.missing_msbs + 1);
+                put_pass_count(&mut bw, block.passes);
+
+                // Lblock starts at 3 and grows with a comma code.
+                let mut bits = 3 + block.passes.ilog2();
+                while block.data.len() >> bits != 0 {
+                    bw.put(true);
+                    bits += 1;
+                }
+                bw.put(false);
+                bw.put_bits(block.data.len() as u32, bits);
+            }
+        }
+    }
+
+    let mut packet = bw.finish();
+    for block in bands.iter().flat_map(|b| &b.blocks) {
+        packet.extend_from_slice(block.data);
+    }
+    packet
+}
+
+/// Number of coding passes codeword (Table B.4).
+fn put_pass_count(bw: &mut BitWriter, passes: usize) {
+    let n = passes as u32;
+    match n {
+        1 => bw.put(false),
+        2 => bw.put_bits(0b10, 2),
+        3..=5 => bw.put_bits((0b11 << 2) | (n - 3), 4),
+        6..=36 => bw.put_bits((0b1111 << 5) | (n - 6), 9),
+        _ => bw.put_bits((0x1FF << 7) | (n - 37), 16),
+    }
+}
@@ -11,7 +11,8 @@
 //! # Note
 //! 
 //! Writing JP2 from scratch is not supported by the underlying jpeg2k crate.
-//! The crate only supports transcoding existing JP2 files.
+//! Use [`crate::j2k`] to write codestreams and JP2 files, including the
+//! DCI digital cinema profiles.
 //!
 //! # Example
 //!
@@ -10,7 +10,8 @@
 //! - 8-bit per channel only
 //! - RGB, Grayscale, and CMYK color modes
 //! - Variable quality/compression ratio
-//! - EXIF, XMP, and ICC profile metadata
+//! - EXIF and XMP metadata as typed attributes (read and written)
+//! - Embedded ICC profiles (read and written, see [`crate::icc`])
 //!
 //! # Architecture
 //!
@@ -59,10 +60,21 @@
 //! - Color grading (use DPX/EXR)
 //! - Anything requiring lossless quality
 
+use crate::attrs::{exif, xmp};
+use crate::icc;
 use crate::{AttrValue, FormatReader, FormatWriter, ImageData, IoError, IoResult, Metadata, PixelData, PixelFormat};
 use std::io::{BufReader, Cursor};
 use std::path::Path;
 
+/// APP1 prefix of an EXIF segment.
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+
+/// APP1 prefix of an XMP segment.
+const XMP_HEADER: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+/// Largest APP segment payload.
+const MAX_SEGMENT_DATA: usize = 65533;
+
 // ============================================================================
 // Color Type
 // ============================================================================
@@ -97,9 +109,12 @@ pub enum ColorType {
 /// let image = reader.read("photo.jpg")?;
 /// ```
 #[derive(Debug, Clone, Default)]
+#[non_exhaustive]
 pub struct JpegReaderOptions {
-    /// Reserved for future use.
-    _reserved: (),
+    /// Converts pixels from the embedded ICC profile into a working space.
+    /// Default: None (pixels are returned as stored).
+    #[cfg(feature = "icc")]
+    pub icc_convert: Option<icc::IccConvert>,
 }
 
 // ============================================================================
@@ -255,14 +270,18 @@ impl JpegReader {
             self.parse_metadata(raw, &mut metadata);
         }
 
-        Ok(ImageData {
+        #[allow(unused_mut)]
+        let mut image = ImageData {
             width,
             height,
             channels,
             format: PixelFormat::U8,
             data: PixelData::U8(data),
             metadata,
-        })
+        };
+        #[cfg(feature = "icc")]
+        icc::convert_on_read(&mut image, self.options.icc_convert.as_ref())?;
+        Ok(image)
     }
 
     /// Parses JPEG segments for metadata.
@@ -312,18 +331,19 @@ impl JpegReader {
             match marker {
                 0xE0 => self.parse_jfif(segment, metadata),
                 0xE1 => {
-                    if segment.starts_with(b"Exif\0\0") && segment.len() > 6 {
-                        metadata.attrs.set(
-                            "ExifSize",
-                            AttrValue::UInt((segment.len() - 6) as u32),
-                        );
-                    } else if segment.starts_with(b"http://ns.adobe.com/xap/1.0/\0") {
-                        let len = segment.len().saturating_sub(29);
-                        metadata.attrs.set("XMPSize", AttrValue::UInt(len as u32));
+                    if let Some(exif) = segment.strip_prefix(EXIF_HEADER) {
+                        metadata.attrs.set("ExifSize", AttrValue::UInt(exif.len() as u32));
+                        // Malformed EXIF is not fatal for the pixels
+                        let _ = exif::read_exif(exif, &mut metadata.attrs);
+                    } else if let Some(packet) = segment.strip_prefix(XMP_HEADER) {
+                        metadata.attrs.set("XMPSize", AttrValue::UInt(packet.len() as u32));
+                        if let Ok(packet) = std::str::from_utf8(packet) {
+                            let _ = xmp::read_xmp(packet, &mut metadata.attrs);
+                        }
                     }
                 }
                 0xE2 => {
-                    if segment.starts_with(b"ICC_PROFILE\0") && segment.len() > 14 {
+                    if segment.starts_with(icc::JPEG_SIGNATURE) && segment.len() > 14 {
                         let chunk_num = segment[12];
                         let total_chunks = segment[13];
                         icc_chunks.push((chunk_num, total_chunks, segment[14..].to_vec()));
@@ -402,36 +422,10 @@ impl JpegReader {
         metadata.attrs.set("Compression", AttrValue::Str(compression.into()));
     }
 
-    /// Parses ICC profile from chunks.
+    /// Reassembles the ICC profile from its APP2 chunks.
     fn parse_icc_profile(&self, chunks: &mut [(u8, u8, Vec<u8>)], metadata: &mut Metadata) {
-        chunks.sort_by_key(|(num, _, _)| *num);
-        let mut profile_data = Vec::new();
-        for (_, _, data) in chunks.iter() {
-            profile_data.extend_from_slice(data);
-        }
-
-        if profile_data.len() < 20 {
-            metadata.attrs.set(
-                "ICCProfileSize",
-                AttrValue::UInt(profile_data.len() as u32),
-            );
-            return;
-        }
-
-        let profile_size = u32::from_be_bytes([
-            profile_data[0],
-            profile_data[1],
-            profile_data[2],
-            profile_data[3],
-        ]);
-        metadata.attrs.set("ICCProfileSize", AttrValue::UInt(profile_size));
-
-        if let Ok(space) = std::str::from_utf8(&profile_data[16..20]) {
-            metadata.attrs.set(
-                "ICCColorSpace",
-                AttrValue::Str(space.trim().to_string()),
-            );
-        }
+        let profile = icc::join_jpeg_segments(chunks);
+        icc::set_profile(&mut metadata.attrs, profile);
     }
 }
 
@@ -570,7 +564,30 @@ impl JpegWriter {
 
         // Encode to memory buffer
         let mut buffer = Vec::new();
-        let encoder = Encoder::new(&mut buffer, self.options.quality);
+        let mut encoder = Encoder::new(&mut buffer, self.options.quality);
+
+        // EXIF and XMP go in APP1; oversized blocks don't fit one segment
+        let exif = exif::write_exif(&image.metadata.attrs)
+            .map(|tiff| [EXIF_HEADER, &tiff].concat());
+        let xmp = xmp::write_xmp(&image.metadata.attrs)
+            .map(|packet| [XMP_HEADER, packet.as_bytes()].concat());
+        for segment in exif.iter().chain(&xmp) {
+            if segment.len() <= MAX_SEGMENT_DATA {
+                encoder
+                    .add_app_segment(1, segment)
+                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
+            }
+        }
+
+        // ICC profile in APP2, split into numbered chunks
+        if let Some(profile) = icc::profile(&image.metadata.attrs) {
+            for segment in icc::jpeg_segments(profile).unwrap_or_default() {
+                encoder
+                    .add_app_segment(2, &segment)
+                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
+            }
+        }
+
         encoder
             .encode(&pixel_data, image.width as u16, image.height as u16, color_type)
             .map_err(|e: jpeg_encoder::EncodingError| IoError::EncodeError(e.to_string()))?;
@@ -729,6 +746,68 @@ mod tests {
         assert_eq!(loaded.height, 16);
     }
 
+    /// Tests that EXIF and XMP survive a write/read cycle.
+    #[test]
+    fn test_exif_xmp_roundtrip() {
+        let mut image = ImageData::from_u8(16, 16, 3, vec![100; 16 * 16 * 3]);
+        let attrs = &mut image.metadata.attrs;
+        attrs.set("Make", AttrValue::Str("Sony".into()));
+        attrs.set("Orientation", AttrValue::UInt(8));
+        attrs.set("Exif:ExposureTime", AttrValue::URational(1, 60));
+        attrs.set("Exif:ISOSpeedRatings", AttrValue::UInt(800));
+        attrs.set("GPS:Latitude", AttrValue::Double(48.8584));
+        attrs.set("XMP:xmp:Rating", AttrValue::Str("3".into()));
+
+        let bytes = JpegWriter::new().write_to_memory(&image).expect("Write failed");
+        let loaded = JpegReader::new().read_from_memory(&bytes).expect("Read failed");
+        let attrs = &loaded.metadata.attrs;
+
+        assert_eq!(attrs.get_str("Make"), Some("Sony"));
+        assert_eq!(attrs.get_u32("Orientation"), Some(8));
+        assert_eq!(attrs.get_urational("Exif:ExposureTime"), Some((1, 60)));
+        assert_eq!(attrs.get_u32("Exif:ISOSpeedRatings"), Some(800));
+        assert!((attrs.get_f64("GPS:Latitude").unwrap() - 48.8584).abs() < 1e-6);
+        assert_eq!(attrs.get_str("XMP:xmp:Rating"), Some("3"));
+        assert!(attrs.get_u32("ExifSize").is_some());
+    }
+
+    /// Tests that a profile spanning several APP2 segments survives.
+    #[test]
+    fn test_icc_roundtrip() {
+        let mut profile: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        profile[16..20].copy_from_slice(b"RGB ");
+        let mut image = ImageData::from_u8(8, 8, 3, vec![50; 8 * 8 * 3]);
+        icc::set_profile(&mut image.metadata.attrs, profile.clone());
+
+        let bytes = JpegWriter::new().write_to_memory(&image).expect("Write failed");
+        let loaded = JpegReader::new().read_from_memory(&bytes).expect("Read failed");
+        let attrs = &loaded.metadata.attrs;
+
+        assert_eq!(icc::profile(attrs), Some(&profile[..]));
+        assert_eq!(attrs.get_u32("ICCProfileSize"), Some(100_000));
+        assert_eq!(attrs.get_str("ICCColorSpace"), Some("RGB"));
+    }
+
+    /// Tests conversion to a working space on read.
+    #[cfg(feature = "icc")]
+    #[test]
+    fn test_icc_convert_on_read() {
+        let mut image = ImageData::from_u8(8, 8, 3, vec![128; 8 * 8 * 3]);
+        icc::embed_profile(&mut image.metadata.attrs, &icc::Profile::srgb()).unwrap();
+        let bytes = JpegWriter::new().write_to_memory(&image).expect("Write failed");
+
+        let mut options = JpegReaderOptions::default();
+        options.icc_convert = Some(icc::IccConvert::new(icc::StandardProfile::LinearSrgb));
+        let loaded = JpegReader::with_options(options)
+            .read_from_memory(&bytes)
+            .expect("Read failed");
+
+        assert_eq!(loaded.format, PixelFormat::F32);
+        assert_eq!(loaded.metadata.colorspace.as_deref(), Some("linear"));
+        let px = loaded.to_f32();
+        assert!((px[0] - 0.216).abs() < 0.02, "got {}", px[0]);
+    }
+
     /// Tests magic byte detection.
     #[test]
     fn test_can_read() {
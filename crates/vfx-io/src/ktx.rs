@@ -8,13 +8,16 @@
 //! - Read KTX2 file headers and metadata (key-value pairs)
 //! - Support for uncompressed textures (R8, RG8, RGBA8, RGBA16F, RGBA32F)
 //! - Mipmap chain access
+//! - zstd supercompression (read and write)
+//! - Write BC1/BC3/BC4/BC5/BC6H/BC7 and uncompressed textures with mip
+//!   chains, cubemaps and arrays (see [`KtxWriter`])
 //!
 //! # Limitations
 //!
-//! - BC-compressed textures (BC1-BC7) not supported - use DDS format instead
+//! - Reading BC-compressed textures (BC1-BC7) not supported - use DDS format instead
 //! - Basis Universal transcoding requires external tooling (ktx2-rw, basisu)
 //! - ASTC/ETC decompression not yet implemented
-//! - Supercompressed textures (zstd, BasisLZ) not supported
+//! - BasisLZ and zlib supercompression not supported
 //!
 //! # Example
 //!
@@ -30,9 +33,15 @@
 //! # Ok::<(), vfx_io::IoError>(())
 //! ```
 
+use crate::bcn::{
+    default_mip_options, BcFormat, BcQuality, GpuTexture, SurfaceFormat, TextureLayout,
+};
+use crate::imagebufalgo::MipmapFilter;
+use crate::traits::FormatWriter;
 use crate::{ImageData, IoError, IoResult};
-use std::fs::File;
-use std::io::{BufReader, Read};
+use std::borrow::Cow;
+use std::fs;
+use std::io::Read;
 use std::path::Path;
 
 /// KTX2 texture format information.
@@ -81,22 +90,22 @@ impl KtxFormat {
     fn from_vk_format(vk_format: u32) -> Self {
         // VkFormat enum values from Vulkan spec
         match vk_format {
-            9 => KtxFormat::R8,           // VK_FORMAT_R8_UNORM
-            16 => KtxFormat::Rg8,         // VK_FORMAT_R8G8_UNORM
+            9 | 15 => KtxFormat::R8,      // VK_FORMAT_R8_UNORM / SRGB
+            16 | 22 => KtxFormat::Rg8,    // VK_FORMAT_R8G8_UNORM / SRGB
             37 => KtxFormat::Rgba8,       // VK_FORMAT_R8G8B8A8_UNORM
             43 => KtxFormat::Rgba8,       // VK_FORMAT_R8G8B8A8_SRGB
             97 => KtxFormat::Rgba16Float, // VK_FORMAT_R16G16B16A16_SFLOAT
             109 => KtxFormat::Rgba32Float, // VK_FORMAT_R32G32B32A32_SFLOAT
             // BC formats
-            131 | 132 => KtxFormat::Bc1, // VK_FORMAT_BC1_RGB[A]_UNORM_BLOCK
-            135 | 136 => KtxFormat::Bc2, // VK_FORMAT_BC2_UNORM_BLOCK
-            139 | 140 => KtxFormat::Bc3, // VK_FORMAT_BC3_UNORM_BLOCK
-            143 | 144 => KtxFormat::Bc4, // VK_FORMAT_BC4_UNORM_BLOCK
-            147 | 148 => KtxFormat::Bc5, // VK_FORMAT_BC5_UNORM_BLOCK
-            151 | 152 => KtxFormat::Bc6h, // VK_FORMAT_BC6H_UFLOAT_BLOCK
-            155 | 156 => KtxFormat::Bc7, // VK_FORMAT_BC7_UNORM_BLOCK
-            // ETC2
-            147..=160 => KtxFormat::Etc2,
+            131..=134 => KtxFormat::Bc1, // VK_FORMAT_BC1_RGB[A]_UNORM/SRGB_BLOCK
+            135 | 136 => KtxFormat::Bc2, // VK_FORMAT_BC2_UNORM/SRGB_BLOCK
+            137 | 138 => KtxFormat::Bc3, // VK_FORMAT_BC3_UNORM/SRGB_BLOCK
+            139 | 140 => KtxFormat::Bc4, // VK_FORMAT_BC4_UNORM/SNORM_BLOCK
+            141 | 142 => KtxFormat::Bc5, // VK_FORMAT_BC5_UNORM/SNORM_BLOCK
+            143 | 144 => KtxFormat::Bc6h, // VK_FORMAT_BC6H_UFLOAT/SFLOAT_BLOCK
+            145 | 146 => KtxFormat::Bc7, // VK_FORMAT_BC7_UNORM/SRGB_BLOCK
+            // ETC2 and EAC
+            147..=156 => KtxFormat::Etc2,
             // ASTC (various block sizes)
             157..=184 => KtxFormat::Astc,
             // Unknown
@@ -182,6 +191,9 @@ const KTX2_IDENTIFIER: [u8; 12] = [
     0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
 ];
 
+/// `supercompressionScheme` value for Zstandard.
+const SUPERCOMPRESSION_ZSTD: u32 = 2;
+
 /// Reads KTX2 texture info without fully loading pixel data.
 pub fn read_info<P: AsRef<Path>>(path: P) -> IoResult<KtxInfo> {
     // Read file to memory to enable metadata parsing (needs full buffer access)
@@ -201,7 +213,7 @@ fn read_info_impl_with_metadata(data: &[u8]) -> IoResult<KtxInfo> {
     }
 
     // Check magic bytes
-    if &data[0..12] != &KTX2_IDENTIFIER {
+    if data[0..12] != KTX2_IDENTIFIER {
         return Err(IoError::Format("Not a valid KTX2 file".into()));
     }
 
@@ -297,7 +309,7 @@ fn parse_ktx2_metadata(data: &[u8], offset: usize, length: usize) -> Vec<(String
 ///
 /// Returns error for:
 /// - BC-compressed formats (BC1-BC7) - use DDS format instead
-/// - Supercompressed textures (zstd, BasisLZ, etc.)
+/// - BasisLZ or zlib supercompressed textures
 /// - Basis Universal formats (ETC1S, UASTC) - requires external tooling
 /// - ASTC/ETC formats (not yet implemented)
 /// - Invalid or corrupted files
@@ -310,9 +322,9 @@ pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
 pub fn read_from_memory(data: &[u8]) -> IoResult<ImageData> {
     let info = read_info_from_memory(data)?;
 
-    if info.is_supercompressed {
+    if info.is_supercompressed && supercompression_scheme(data) != SUPERCOMPRESSION_ZSTD {
         return Err(IoError::UnsupportedFeature(
-            "KTX2 supercompression requires additional tooling (zstd, BasisLZ)".into(),
+            "KTX2 BasisLZ and zlib supercompression require additional tooling".into(),
         ));
     }
 
@@ -394,14 +406,34 @@ fn get_level_offset(data: &[u8], level: u32, info: &KtxInfo) -> IoResult<(usize,
     Ok((byte_offset, byte_length))
 }
 
-fn decode_rgba8(data: &[u8], info: &KtxInfo) -> IoResult<ImageData> {
-    let (offset, length) = get_level_offset(data, 0, info)?;
+/// Supercompression scheme from the header.
+fn supercompression_scheme(data: &[u8]) -> u32 {
+    u32::from_le_bytes([data[44], data[45], data[46], data[47]])
+}
+
+/// Bytes of a mip level, inflating zstd-supercompressed data.
+fn level_data<'a>(data: &'a [u8], level: u32, info: &KtxInfo) -> IoResult<Cow<'a, [u8]>> {
+    let (offset, length) = get_level_offset(data, level, info)?;
 
     if offset + length > data.len() {
         return Err(IoError::InvalidFile("Truncated pixel data".into()));
     }
 
-    let pixel_data = &data[offset..offset + length];
+    let raw = &data[offset..offset + length];
+    if supercompression_scheme(data) != SUPERCOMPRESSION_ZSTD {
+        return Ok(Cow::Borrowed(raw));
+    }
+    let mut decoder = ruzstd::decoding::StreamingDecoder::new(raw)
+        .map_err(|e| IoError::DecodeError(format!("KTX2 zstd error: {e}")))?;
+    let mut out = Vec::new();
+    decoder
+        .read_to_end(&mut out)
+        .map_err(|e| IoError::DecodeError(format!("KTX2 zstd error: {e}")))?;
+    Ok(Cow::Owned(out))
+}
+
+fn decode_rgba8(data: &[u8], info: &KtxInfo) -> IoResult<ImageData> {
+    let pixel_data = level_data(data, 0, info)?;
     let expected = (info.width * info.height * 4) as usize;
 
     if pixel_data.len() < expected {
@@ -419,13 +451,7 @@ fn decode_rgba8(data: &[u8], info: &KtxInfo) -> IoResult<ImageData> {
 }
 
 fn decode_rgba16f(data: &[u8], info: &KtxInfo) -> IoResult<ImageData> {
-    let (offset, length) = get_level_offset(data, 0, info)?;
-
-    if offset + length > data.len() {
-        return Err(IoError::InvalidFile("Truncated pixel data".into()));
-    }
-
-    let pixel_data = &data[offset..offset + length];
+    let pixel_data = level_data(data, 0, info)?;
     let pixel_count = (info.width * info.height * 4) as usize;
     let expected_bytes = pixel_count * 2;
 
@@ -445,13 +471,7 @@ fn decode_rgba16f(data: &[u8], info: &KtxInfo) -> IoResult<ImageData> {
 }
 
 fn decode_rgba32f(data: &[u8], info: &KtxInfo) -> IoResult<ImageData> {
-    let (offset, length) = get_level_offset(data, 0, info)?;
-
-    if offset + length > data.len() {
-        return Err(IoError::InvalidFile("Truncated pixel data".into()));
-    }
-
-    let pixel_data = &data[offset..offset + length];
+    let pixel_data = level_data(data, 0, info)?;
     let pixel_count = (info.width * info.height * 4) as usize;
     let expected_bytes = pixel_count * 4;
 
@@ -475,13 +495,7 @@ fn decode_rgba32f(data: &[u8], info: &KtxInfo) -> IoResult<ImageData> {
 }
 
 fn decode_r8(data: &[u8], info: &KtxInfo) -> IoResult<ImageData> {
-    let (offset, length) = get_level_offset(data, 0, info)?;
-
-    if offset + length > data.len() {
-        return Err(IoError::InvalidFile("Truncated pixel data".into()));
-    }
-
-    let pixel_data = &data[offset..offset + length];
+    let pixel_data = level_data(data, 0, info)?;
     let expected = (info.width * info.height) as usize;
 
     if pixel_data.len() < expected {
@@ -499,13 +513,7 @@ fn decode_r8(data: &[u8], info: &KtxInfo) -> IoResult<ImageData> {
 }
 
 fn decode_rg8(data: &[u8], info: &KtxInfo) -> IoResult<ImageData> {
-    let (offset, length) = get_level_offset(data, 0, info)?;
-
-    if offset + length > data.len() {
-        return Err(IoError::InvalidFile("Truncated pixel data".into()));
-    }
-
-    let pixel_data = &data[offset..offset + length];
+    let pixel_data = level_data(data, 0, info)?;
     let pixel_count = (info.width * info.height) as usize;
     let expected = pixel_count * 2;
 
@@ -524,6 +532,401 @@ fn decode_rg8(data: &[u8], info: &KtxInfo) -> IoResult<ImageData> {
     Ok(ImageData::from_f32(info.width, info.height, 4, pixels))
 }
 
+// ============================================================================
+// Writer
+// ============================================================================
+
+/// Supercompression applied to each mip level when writing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KtxSupercompression {
+    /// Level data stored as is.
+    #[default]
+    None,
+    /// Zstandard, one frame per mip level.
+    Zstd,
+}
+
+/// Data Format Descriptor color models and channel ids (Khronos Data
+/// Format spec).
+const KHR_DF_MODEL_RGBSDA: u8 = 1;
+const KHR_DF_MODEL_BC1A: u8 = 128;
+const KHR_DF_MODEL_BC3: u8 = 130;
+const KHR_DF_MODEL_BC4: u8 = 131;
+const KHR_DF_MODEL_BC5: u8 = 132;
+const KHR_DF_MODEL_BC6H: u8 = 133;
+const KHR_DF_MODEL_BC7: u8 = 134;
+const KHR_DF_CHANNEL_ALPHA: u8 = 15;
+const KHR_DF_CHANNEL_BC1A_ALPHAPRESENT: u8 = 1;
+const KHR_DF_SAMPLE_LINEAR: u8 = 0x10;
+const KHR_DF_SAMPLE_SIGNED: u8 = 0x40;
+const KHR_DF_SAMPLE_FLOAT: u8 = 0x80;
+const F32_ONE: u32 = 0x3F80_0000;
+const F32_MINUS_ONE: u32 = 0xBF80_0000;
+
+/// One DFD sample: bit offset, bit count, channel id with qualifiers,
+/// lower and upper values.
+type DfdSample = (u16, u8, u8, u32, u32);
+
+impl KtxFormat {
+    /// Surface encoding and VkFormat for writing.
+    fn encoding(&self, srgb: bool) -> IoResult<(SurfaceFormat, u32)> {
+        let pick = |linear: u32, encoded: u32| if srgb { encoded } else { linear };
+        Ok(match self {
+            KtxFormat::R8 => (SurfaceFormat::R8, pick(9, 15)),
+            KtxFormat::Rg8 => (SurfaceFormat::Rg8, pick(16, 22)),
+            KtxFormat::Rgba8 => (SurfaceFormat::Rgba8, pick(37, 43)),
+            KtxFormat::Rgba16Float => (SurfaceFormat::Rgba16Float, 97),
+            KtxFormat::Rgba32Float => (SurfaceFormat::Rgba32Float, 109),
+            KtxFormat::Bc1 => (SurfaceFormat::Bc(BcFormat::Bc1), pick(133, 134)),
+            KtxFormat::Bc3 => (SurfaceFormat::Bc(BcFormat::Bc3), pick(137, 138)),
+            KtxFormat::Bc4 => (SurfaceFormat::Bc(BcFormat::Bc4), 139),
+            KtxFormat::Bc5 => (SurfaceFormat::Bc(BcFormat::Bc5), 141),
+            KtxFormat::Bc6h => (SurfaceFormat::Bc(BcFormat::Bc6h), 143),
+            KtxFormat::Bc7 => (SurfaceFormat::Bc(BcFormat::Bc7), pick(145, 146)),
+            other => {
+                return Err(IoError::UnsupportedFeature(format!(
+                    "writing {:?} KTX2",
+                    other
+                )));
+            }
+        })
+    }
+
+    /// Basic Data Format Descriptor block for a writable format.
+    fn data_format_descriptor(&self, srgb: bool, supercompressed: bool) -> Vec<u8> {
+        // SRGB only applies to color; alpha stays linear
+        let srgb = srgb && !self.is_hdr() && !matches!(self, KtxFormat::Bc4 | KtxFormat::Bc5);
+        let alpha = KHR_DF_CHANNEL_ALPHA | if srgb { KHR_DF_SAMPLE_LINEAR } else { 0 };
+        let unorm8 = |offset: u16, channel: u8| (offset, 7, channel, 0, 255);
+        let float = |offset: u16, bits: u8, channel: u8| {
+            (
+                offset,
+                bits - 1,
+                channel | KHR_DF_SAMPLE_FLOAT | KHR_DF_SAMPLE_SIGNED,
+                F32_MINUS_ONE,
+                F32_ONE,
+            )
+        };
+        let block = |offset: u16, channel: u8| (offset, 63, channel, 0, u32::MAX);
+
+        let (model, bytes, samples): (u8, u8, Vec<DfdSample>) = match self {
+            KtxFormat::R8 => (KHR_DF_MODEL_RGBSDA, 1, vec![unorm8(0, 0)]),
+            KtxFormat::Rg8 => (KHR_DF_MODEL_RGBSDA, 2, vec![unorm8(0, 0), unorm8(8, 1)]),
+            KtxFormat::Rgba8 => (
+                KHR_DF_MODEL_RGBSDA,
+                4,
+                vec![unorm8(0, 0), unorm8(8, 1), unorm8(16, 2), unorm8(24, alpha)],
+            ),
+            KtxFormat::Rgba16Float => (
+                KHR_DF_MODEL_RGBSDA,
+                8,
+                vec![
+                    float(0, 16, 0),
+                    float(16, 16, 1),
+                    float(32, 16, 2),
+                    float(48, 16, KHR_DF_CHANNEL_ALPHA),
+                ],
+            ),
+            KtxFormat::Rgba32Float => (
+                KHR_DF_MODEL_RGBSDA,
+                16,
+                vec![
+                    float(0, 32, 0),
+                    float(32, 32, 1),
+                    float(64, 32, 2),
+                    float(96, 32, KHR_DF_CHANNEL_ALPHA),
+                ],
+            ),
+            KtxFormat::Bc1 => (
+                KHR_DF_MODEL_BC1A,
+                8,
+                vec![block(0, KHR_DF_CHANNEL_BC1A_ALPHAPRESENT)],
+            ),
+            KtxFormat::Bc3 => (KHR_DF_MODEL_BC3, 16, vec![block(0, alpha), block(64, 0)]),
+            KtxFormat::Bc4 => (KHR_DF_MODEL_BC4, 8, vec![block(0, 0)]),
+            KtxFormat::Bc5 => (KHR_DF_MODEL_BC5, 16, vec![block(0, 0), block(64, 1)]),
+            KtxFormat::Bc6h => (
+                KHR_DF_MODEL_BC6H,
+                16,
+                vec![(0, 127, KHR_DF_SAMPLE_FLOAT, 0, F32_ONE)],
+            ),
+            _ => (KHR_DF_MODEL_BC7, 16, vec![(0, 127, 0, 0, u32::MAX)]),
+        };
+        let block_dim = if self.is_compressed() { 3 } else { 0 };
+
+        let block_size = 24 + 16 * samples.len() as u32;
+        let mut out = Vec::with_capacity(4 + block_size as usize);
+        out.extend_from_slice(&(4 + block_size).to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes()); // Khronos vendor, basic descriptor
+        out.extend_from_slice(&(2 | (block_size << 16)).to_le_bytes()); // version 1.3
+        out.extend_from_slice(&[model, 1, if srgb { 2 } else { 1 }, 0]); // BT.709, straight alpha
+        out.extend_from_slice(&[block_dim, block_dim, 0, 0]);
+        out.extend_from_slice(&[if supercompressed { 0 } else { bytes }, 0, 0, 0, 0, 0, 0, 0]);
+        for (offset, bits, channel, lower, upper) in samples {
+            out.extend_from_slice(&offset.to_le_bytes());
+            out.extend_from_slice(&[bits, channel, 0, 0, 0, 0]);
+            out.extend_from_slice(&lower.to_le_bytes());
+            out.extend_from_slice(&upper.to_le_bytes());
+        }
+        out
+    }
+}
+
+impl From<BcFormat> for KtxFormat {
+    fn from(format: BcFormat) -> Self {
+        match format {
+            BcFormat::Bc1 => KtxFormat::Bc1,
+            BcFormat::Bc3 => KtxFormat::Bc3,
+            BcFormat::Bc4 => KtxFormat::Bc4,
+            BcFormat::Bc5 => KtxFormat::Bc5,
+            BcFormat::Bc6h => KtxFormat::Bc6h,
+            BcFormat::Bc7 => KtxFormat::Bc7,
+        }
+    }
+}
+
+/// Options for writing KTX2 files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::ktx::{KtxFormat, KtxSupercompression, KtxWriter, KtxWriterOptions};
+///
+/// let writer = KtxWriter::with_options(KtxWriterOptions {
+///     format: KtxFormat::Bc6h,
+///     supercompression: KtxSupercompression::Zstd,
+///     ..Default::default()
+/// });
+/// writer.write("env.ktx2", &image)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct KtxWriterOptions {
+    /// Pixel format. BC2, ETC, ASTC and Basis formats cannot be written.
+    /// Default: BC7.
+    pub format: KtxFormat,
+    /// BCn encoder effort. Default: [`BcQuality::Normal`].
+    pub quality: BcQuality,
+    /// Generate a full mip chain when writing a single image. Default: true.
+    pub mipmaps: bool,
+    /// Filter for generated mip levels. Default: bilinear.
+    pub mip_filter: MipmapFilter,
+    /// Data is sRGB-encoded: selects the `_SRGB` VkFormat where one exists
+    /// and filters mips in linear light. Default: false.
+    pub srgb: bool,
+    /// Level supercompression. Default: none.
+    pub supercompression: KtxSupercompression,
+}
+
+impl Default for KtxWriterOptions {
+    fn default() -> Self {
+        Self {
+            format: KtxFormat::Bc7,
+            quality: BcQuality::Normal,
+            mipmaps: true,
+            mip_filter: MipmapFilter::default(),
+            srgb: false,
+            supercompression: KtxSupercompression::None,
+        }
+    }
+}
+
+/// KTX2 texture writer.
+#[derive(Debug, Clone, Default)]
+pub struct KtxWriter {
+    options: KtxWriterOptions,
+}
+
+impl KtxWriter {
+    /// Creates a new writer with default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a writer with custom options.
+    pub fn with_options(options: KtxWriterOptions) -> Self {
+        Self { options }
+    }
+
+    /// Writes a texture with its existing mip chains and layout.
+    pub fn write_texture<P: AsRef<Path>>(&self, path: P, texture: &GpuTexture) -> IoResult<()> {
+        let data = self.write_texture_to_memory(texture)?;
+        fs::write(path.as_ref(), data)?;
+        Ok(())
+    }
+
+    /// Encodes a texture with its existing mip chains and layout.
+    pub fn write_texture_to_memory(&self, texture: &GpuTexture) -> IoResult<Vec<u8>> {
+        texture.validate()?;
+        let format = self.options.format;
+        let (surface, vk_format) = format.encoding(self.options.srgb)?;
+        let zstd = self.options.supercompression == KtxSupercompression::Zstd;
+        let levels = texture.mip_count() as usize;
+
+        // Level data: each level holds every layer and face in order
+        let mut level_data = vec![Vec::new(); levels];
+        for chain in &texture.surfaces {
+            for (data, image) in level_data.iter_mut().zip(chain) {
+                data.extend_from_slice(&surface.encode(image, self.options.quality)?);
+            }
+        }
+
+        let (layer_count, face_count) = match texture.layout {
+            TextureLayout::Texture2D => (0, 1),
+            TextureLayout::Cubemap if texture.array_size() == 1 => (0, 6),
+            TextureLayout::Cubemap => (texture.array_size(), 6),
+            TextureLayout::Array => (texture.array_size(), 1),
+        };
+        let type_size = match surface {
+            SurfaceFormat::Rgba16Float => 2,
+            SurfaceFormat::Rgba32Float => 4,
+            _ => 1,
+        };
+
+        let dfd = format.data_format_descriptor(self.options.srgb, zstd);
+        let kvd = key_value_data(&[("KTXwriter", concat!("vfx-io ", env!("CARGO_PKG_VERSION")))]);
+        let dfd_offset = 80 + 24 * levels;
+        let kvd_offset = dfd_offset + dfd.len();
+
+        // Levels are stored smallest first, aligned to the texel block
+        // size (and 4 bytes) unless supercompressed
+        let align = if zstd {
+            1
+        } else {
+            surface.block_bytes().max(4)
+        };
+        let mut payload = Vec::new();
+        let mut index = vec![(0u64, 0u64, 0u64); levels];
+        let mut offset = kvd_offset + kvd.len();
+        for level in (0..levels).rev() {
+            let raw = &level_data[level];
+            let stored = if zstd {
+                ruzstd::encoding::compress_to_vec(
+                    raw.as_slice(),
+                    ruzstd::encoding::CompressionLevel::Fastest,
+                )
+            } else {
+                raw.clone()
+            };
+            let padding = offset.next_multiple_of(align) - offset;
+            payload.resize(payload.len() + padding, 0);
+            offset += padding;
+            index[level] = (offset as u64, stored.len() as u64, raw.len() as u64);
+            offset += stored.len();
+            payload.extend_from_slice(&stored);
+        }
+
+        let mut out = Vec::with_capacity(offset);
+        out.extend_from_slice(&KTX2_IDENTIFIER);
+        for v in [
+            vk_format,
+            type_size,
+            texture.width(),
+            texture.height(),
+            0, // depth
+            layer_count,
+            face_count,
+            levels as u32,
+            if zstd { SUPERCOMPRESSION_ZSTD } else { 0 },
+            dfd_offset as u32,
+            dfd.len() as u32,
+            kvd_offset as u32,
+            kvd.len() as u32,
+        ] {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        out.extend_from_slice(&[0u8; 16]); // no supercompression global data
+        for (byte_offset, byte_length, uncompressed) in index {
+            out.extend_from_slice(&byte_offset.to_le_bytes());
+            out.extend_from_slice(&byte_length.to_le_bytes());
+            out.extend_from_slice(&uncompressed.to_le_bytes());
+        }
+        out.extend_from_slice(&dfd);
+        out.extend_from_slice(&kvd);
+        out.extend_from_slice(&payload);
+
+        tracing::debug!(
+            "KTX2: {}x{} {:?}, {} levels, {} surfaces, {} bytes",
+            texture.width(),
+            texture.height(),
+            format,
+            levels,
+            texture.surfaces.len(),
+            out.len()
+        );
+        Ok(out)
+    }
+
+    /// Wraps a single image, adding mipmaps if enabled.
+    fn texture(&self, image: &ImageData) -> IoResult<GpuTexture> {
+        let texture = GpuTexture::from_image(image.clone());
+        if !self.options.mipmaps {
+            return Ok(texture);
+        }
+        texture.with_mipmaps(&default_mip_options(
+            self.options.mip_filter,
+            self.options.srgb,
+        ))
+    }
+}
+
+/// Encodes key/value pairs (already in key order) with NUL-terminated
+/// string values, each entry padded to 4 bytes.
+fn key_value_data(pairs: &[(&str, &str)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (key, value) in pairs {
+        let len = key.len() + value.len() + 2;
+        out.extend_from_slice(&(len as u32).to_le_bytes());
+        out.extend_from_slice(key.as_bytes());
+        out.push(0);
+        out.extend_from_slice(value.as_bytes());
+        out.push(0);
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+    out
+}
+
+impl FormatWriter<KtxWriterOptions> for KtxWriter {
+    /// Returns "KTX2".
+    fn format_name(&self) -> &'static str {
+        "KTX2"
+    }
+
+    /// Returns `["ktx2"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ktx2"]
+    }
+
+    /// Writes a 2D texture, with a mip chain if enabled.
+    fn write<P: AsRef<Path>>(&self, path: P, image: &ImageData) -> IoResult<()> {
+        self.write_texture(path, &self.texture(image)?)
+    }
+
+    /// Encodes a 2D texture, with a mip chain if enabled.
+    fn write_to_memory(&self, image: &ImageData) -> IoResult<Vec<u8>> {
+        self.write_texture_to_memory(&self.texture(image)?)
+    }
+
+    /// Creates writer with custom options.
+    fn with_options(options: KtxWriterOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Writes an image as a mipmapped BC7 KTX2 file.
+pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
+    KtxWriter::new().write(path, image)
+}
+
+/// Writes a texture (cubemap, array or prebuilt mip chain) with the
+/// given options.
+pub fn write_texture<P: AsRef<Path>>(
+    path: P,
+    texture: &GpuTexture,
+    options: KtxWriterOptions,
+) -> IoResult<()> {
+    KtxWriter::with_options(options).write_texture(path, texture)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -553,6 +956,95 @@ mod tests {
         assert_eq!(KtxFormat::from_vk_format(37), KtxFormat::Rgba8);
         assert_eq!(KtxFormat::from_vk_format(109), KtxFormat::Rgba32Float);
         assert_eq!(KtxFormat::from_vk_format(131), KtxFormat::Bc1);
-        assert_eq!(KtxFormat::from_vk_format(155), KtxFormat::Bc7);
+        assert_eq!(KtxFormat::from_vk_format(137), KtxFormat::Bc3);
+        assert_eq!(KtxFormat::from_vk_format(143), KtxFormat::Bc6h);
+        assert_eq!(KtxFormat::from_vk_format(145), KtxFormat::Bc7);
+        assert_eq!(KtxFormat::from_vk_format(155), KtxFormat::Etc2);
+    }
+
+    fn gradient(w: u32, h: u32) -> ImageData {
+        let mut data = Vec::with_capacity((w * h * 4) as usize);
+        for y in 0..h {
+            for x in 0..w {
+                let t = (x + y) as f32 / (w + h) as f32;
+                data.extend_from_slice(&[t, 1.0 - t, 0.25, 1.0]);
+            }
+        }
+        ImageData::from_f32(w, h, 4, data)
+    }
+
+    fn max_error(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| (x - y).abs()).fold(0.0, f32::max)
+    }
+
+    fn writer(format: KtxFormat, supercompression: KtxSupercompression) -> KtxWriter {
+        KtxWriter::with_options(KtxWriterOptions {
+            format,
+            supercompression,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_write_read_uncompressed() {
+        let image = gradient(16, 8);
+        for supercompression in [KtxSupercompression::None, KtxSupercompression::Zstd] {
+            let data = writer(KtxFormat::Rgba16Float, supercompression)
+                .write_to_memory(&image)
+                .unwrap();
+            let info = read_info_from_memory(&data).unwrap();
+            assert_eq!(info.format, KtxFormat::Rgba16Float);
+            assert_eq!((info.width, info.height, info.mip_count), (16, 8, 5));
+            assert_eq!(info.is_supercompressed, supercompression == KtxSupercompression::Zstd);
+            assert!(info.metadata.iter().any(|(k, _)| k == "KTXwriter"));
+
+            let back = read_from_memory(&data).unwrap().to_f32();
+            let err = max_error(&image.to_f32(), &back);
+            assert!(err < 1e-3, "max error {}", err);
+        }
+    }
+
+    #[test]
+    fn test_write_bc_levels() {
+        let image = gradient(32, 32);
+        let data = writer(KtxFormat::Bc7, KtxSupercompression::Zstd)
+            .write_to_memory(&image)
+            .unwrap();
+        let info = read_info_from_memory(&data).unwrap();
+        assert_eq!(info.format, KtxFormat::Bc7);
+        assert_eq!(info.mip_count, 6);
+
+        // Level 0 inflates to the BC7 encoding of the image
+        let level0 = level_data(&data, 0, &info).unwrap();
+        assert_eq!(level0.len(), BcFormat::Bc7.compressed_size(32, 32));
+        let decoded = crate::bcn::decode::decode(&level0, 32, 32, BcFormat::Bc7);
+        let err = max_error(&image.to_f32(), &decoded);
+        assert!(err < 0.02, "max error {}", err);
+
+        // Smallest level is stored first
+        let (last, _) = get_level_offset(&data, 5, &info).unwrap();
+        let (first, _) = get_level_offset(&data, 0, &info).unwrap();
+        assert!(last < first);
+    }
+
+    #[test]
+    fn test_write_cubemap_array() {
+        let faces = (0..12).map(|_| gradient(8, 8)).collect();
+        let texture = GpuTexture::cubemap(faces).unwrap();
+        let data = writer(KtxFormat::Bc1, KtxSupercompression::None)
+            .write_texture_to_memory(&texture)
+            .unwrap();
+        let info = read_info_from_memory(&data).unwrap();
+        assert_eq!((info.face_count, info.array_size), (6, 2));
+        let (offset, length) = get_level_offset(&data, 0, &info).unwrap();
+        assert_eq!(offset % 8, 0);
+        assert_eq!(length, 12 * BcFormat::Bc1.compressed_size(8, 8));
+    }
+
+    #[test]
+    fn test_write_unsupported_format() {
+        let result =
+            writer(KtxFormat::Astc, KtxSupercompression::None).write_to_memory(&gradient(4, 4));
+        assert!(result.is_err());
     }
 }
@@ -14,7 +14,7 @@
 //! - **HEIF/HEIC** - Modern HDR format with PQ/HLG (requires `heif` feature)
 //! - **WebP** - Modern lossy/lossless format (requires `webp` feature)
 //! - **AVIF** - AV1-based format with HDR support (requires `avif` feature)
-//! - **JPEG2000** - JP2/J2K for cinema/archival (requires `jp2` feature)
+//! - **JPEG2000** - JP2/J2K for cinema/archival (read via `jp2`, write via `j2k` with DCI profiles)
 //!
 //! # Architecture
 //!
@@ -87,6 +87,17 @@
 //! | TIFF | Yes | Yes | 8, 16, 32f | LZW, Deflate compression |
 //! | DPX | Yes | Yes | 8, 10, 12, 16 | Film metadata, log encoding |
 //! | HEIF | Yes | Yes | 8, 10 | HDR PQ/HLG, NCLX profiles |
+//! | BMP | Yes | Yes | 1-32 | Palette, RLE4/RLE8, bitfields, alpha |
+//! | Cineon | Yes | Yes | 8-16 | 10-bit log, all packings, film header |
+//! | TGA | Yes | Yes | 8-32 | Color-mapped, RLE, alpha type, TGA 2.0 metadata |
+//! | Netpbm | Yes | Yes | 1-16 | PBM/PGM/PPM plain and raw, PAM |
+//! | PFM | Yes | Yes | 32f | Both byte orders, bottom-up rows |
+//! | SGI | Yes | Yes | 8, 16 | Verbatim and RLE, 1-4 channels |
+//! | Softimage PIC | Yes | Yes | 8 | Mixed/pure RLE, alpha packet |
+//! | Maya IFF | Yes | Yes | 8, 16 | RLE tiles, Z buffer as a layer channel |
+//! | RLA/RPF | Yes | Yes | 8-32, 32f | Matte and aux channels as layer channels |
+//! | Y4M | Yes | Yes | 8-16 | 4:2:0/4:2:2/4:4:4 sequences, BT.601/709/2020 |
+//! | DNG | Yes | No | 8-16 | LJPEG, CFA demosaic, ACES output |
 //!
 //! # Feature Flags
 //!
@@ -95,11 +106,24 @@
 //! - `jpeg` - JPEG support (default)
 //! - `tiff` - TIFF support (default)
 //! - `dpx` - DPX support (default)
+//! - `cineon` - Kodak Cineon support (default)
 //! - `hdr` - Radiance HDR support (default)
+//! - `bmp` - BMP support (default)
+//! - `tga` - TGA support (default)
+//! - `pnm` - Netpbm PBM/PGM/PPM/PAM support (default)
+//! - `pfm` - Portable Float Map support (default)
+//! - `sgi` - SGI image support (default)
+//! - `softimage` - Softimage PIC support (default)
+//! - `iff` - Maya IFF support (default)
+//! - `rla` - Wavefront RLA / 3ds Max RPF support (default)
+//! - `y4m` - YUV4MPEG2 sequence support (default)
 //! - `heif` - HEIF/HEIC support (requires system libheif, see Cargo.toml)
 //! - `webp` - WebP support (via image crate)
 //! - `avif` - AVIF support (via image crate)
-//! - `jp2` - JPEG2000 support (requires OpenJPEG)
+//! - `jp2` - JPEG2000 reading (requires OpenJPEG)
+//! - `j2k` - JPEG2000 writing with DCI cinema profiles, pure Rust (default)
+//! - `icc` - ICC profile conversion and embedding via `vfx-icc` (lcms2)
+//! - `ptex` - Ptex per-face texture reading, pure Rust (default)
 
 #![warn(missing_docs)]
 #![warn(rustdoc::missing_crate_level_docs)]
@@ -109,6 +133,7 @@ mod traits;
 mod detect;
 
 pub mod attrs;
+pub mod icc;
 pub mod registry;
 
 #[cfg(feature = "exr")]
@@ -126,9 +151,41 @@ pub mod tiff;
 #[cfg(feature = "dpx")]
 pub mod dpx;
 
+#[cfg(feature = "cineon")]
+pub mod cineon;
+
 #[cfg(feature = "hdr")]
 pub mod hdr;
 
+#[cfg(feature = "bmp")]
+pub mod bmp;
+
+#[cfg(feature = "tga")]
+pub mod tga;
+
+#[cfg(feature = "pnm")]
+pub mod pnm;
+
+#[cfg(feature = "pfm")]
+pub mod pfm;
+
+#[cfg(feature = "sgi")]
+pub mod sgi;
+
+#[cfg(feature = "softimage")]
+pub mod softimage;
+
+#[cfg(feature = "iff")]
+pub mod iff;
+
+#[cfg(feature = "rla")]
+pub mod rla;
+
+#[cfg(feature = "y4m")]
+pub mod y4m;
+
+pub mod yuv;
+
 pub mod heif;
 
 #[cfg(feature = "webp")]
@@ -140,6 +197,9 @@ pub mod avif;
 #[cfg(feature = "jp2")]
 pub mod jp2;
 
+#[cfg(feature = "j2k")]
+pub mod j2k;
+
 /// Adobe Photoshop PSD/PSB format.
 #[cfg(feature = "psd")]
 pub mod psd;
@@ -156,6 +216,10 @@ pub mod ktx;
 pub mod arriraw;
 /// RED REDCODE format (.r3d) - requires RED SDK for decode.
 pub mod redcode;
+/// Adobe DNG camera raw (.dng) - CFA and LinearRaw, lossless JPEG.
+pub mod dng;
+/// BCn block compression (BC1, BC3-BC7) for DDS and KTX2 output.
+pub mod bcn;
 /// Deep EXR types and utilities (stub until exrs crate publishes deep support).
 #[cfg(feature = "exr")]
 pub mod exr_deep;
@@ -164,6 +228,8 @@ pub mod sequence;
 pub mod cinema_dng;
 pub mod cache;
 pub mod texture;
+#[cfg(feature = "ptex")]
+pub mod ptex;
 pub mod udim;
 pub mod streaming;
 pub mod imagebuf;
@@ -228,6 +294,66 @@ pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
         #[cfg(feature = "hdr")]
         Format::Hdr => hdr::read(path),
 
+        #[cfg(feature = "bmp")]
+        Format::Bmp => bmp::read(path),
+
+        #[cfg(not(feature = "bmp"))]
+        Format::Bmp => Err(IoError::UnsupportedFormat("BMP support requires 'bmp' feature".into())),
+
+        #[cfg(feature = "tga")]
+        Format::Tga => tga::read(path),
+
+        #[cfg(not(feature = "tga"))]
+        Format::Tga => Err(IoError::UnsupportedFormat("TGA support requires 'tga' feature".into())),
+
+        #[cfg(feature = "cineon")]
+        Format::Cineon => cineon::read(path),
+
+        #[cfg(not(feature = "cineon"))]
+        Format::Cineon => Err(IoError::UnsupportedFormat("Cineon support requires 'cineon' feature".into())),
+
+        #[cfg(feature = "pnm")]
+        Format::Pnm => pnm::read(path),
+
+        #[cfg(not(feature = "pnm"))]
+        Format::Pnm => Err(IoError::UnsupportedFormat("Netpbm support requires 'pnm' feature".into())),
+
+        #[cfg(feature = "pfm")]
+        Format::Pfm => pfm::read(path),
+
+        #[cfg(not(feature = "pfm"))]
+        Format::Pfm => Err(IoError::UnsupportedFormat("PFM support requires 'pfm' feature".into())),
+
+        #[cfg(feature = "sgi")]
+        Format::Sgi => sgi::read(path),
+
+        #[cfg(not(feature = "sgi"))]
+        Format::Sgi => Err(IoError::UnsupportedFormat("SGI support requires 'sgi' feature".into())),
+
+        #[cfg(feature = "softimage")]
+        Format::Softimage => softimage::read(path),
+
+        #[cfg(not(feature = "softimage"))]
+        Format::Softimage => Err(IoError::UnsupportedFormat("Softimage PIC support requires 'softimage' feature".into())),
+
+        #[cfg(feature = "iff")]
+        Format::Iff => iff::read(path),
+
+        #[cfg(not(feature = "iff"))]
+        Format::Iff => Err(IoError::UnsupportedFormat("IFF support requires 'iff' feature".into())),
+
+        #[cfg(feature = "rla")]
+        Format::Rla => rla::read(path),
+
+        #[cfg(not(feature = "rla"))]
+        Format::Rla => Err(IoError::UnsupportedFormat("RLA support requires 'rla' feature".into())),
+
+        #[cfg(feature = "y4m")]
+        Format::Y4m => y4m::read(path),
+
+        #[cfg(not(feature = "y4m"))]
+        Format::Y4m => Err(IoError::UnsupportedFormat("Y4M support requires 'y4m' feature".into())),
+
         #[cfg(feature = "heif")]
         Format::Heif => heif::read_heif(path).map(|(img, _hdr)| img),
 
@@ -251,6 +377,7 @@ pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
 
         Format::ArriRaw => arriraw::decode(path),
         Format::RedCode => redcode::decode(path, 0),
+        Format::Dng => dng::read(path),
 
         Format::Unknown => Err(IoError::UnsupportedFormat(
             path.extension()
@@ -364,6 +491,66 @@ pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
         #[cfg(feature = "hdr")]
         Format::Hdr => hdr::write(path, image),
 
+        #[cfg(feature = "bmp")]
+        Format::Bmp => bmp::write(path, image),
+
+        #[cfg(not(feature = "bmp"))]
+        Format::Bmp => Err(IoError::UnsupportedFormat("BMP support requires 'bmp' feature".into())),
+
+        #[cfg(feature = "tga")]
+        Format::Tga => tga::write(path, image),
+
+        #[cfg(not(feature = "tga"))]
+        Format::Tga => Err(IoError::UnsupportedFormat("TGA support requires 'tga' feature".into())),
+
+        #[cfg(feature = "cineon")]
+        Format::Cineon => cineon::write(path, image),
+
+        #[cfg(not(feature = "cineon"))]
+        Format::Cineon => Err(IoError::UnsupportedFormat("Cineon support requires 'cineon' feature".into())),
+
+        #[cfg(feature = "pnm")]
+        Format::Pnm => pnm::write(path, image),
+
+        #[cfg(not(feature = "pnm"))]
+        Format::Pnm => Err(IoError::UnsupportedFormat("Netpbm support requires 'pnm' feature".into())),
+
+        #[cfg(feature = "pfm")]
+        Format::Pfm => pfm::write(path, image),
+
+        #[cfg(not(feature = "pfm"))]
+        Format::Pfm => Err(IoError::UnsupportedFormat("PFM support requires 'pfm' feature".into())),
+
+        #[cfg(feature = "sgi")]
+        Format::Sgi => sgi::write(path, image),
+
+        #[cfg(not(feature = "sgi"))]
+        Format::Sgi => Err(IoError::UnsupportedFormat("SGI support requires 'sgi' feature".into())),
+
+        #[cfg(feature = "softimage")]
+        Format::Softimage => softimage::write(path, image),
+
+        #[cfg(not(feature = "softimage"))]
+        Format::Softimage => Err(IoError::UnsupportedFormat("Softimage PIC support requires 'softimage' feature".into())),
+
+        #[cfg(feature = "iff")]
+        Format::Iff => iff::write(path, image),
+
+        #[cfg(not(feature = "iff"))]
+        Format::Iff => Err(IoError::UnsupportedFormat("IFF support requires 'iff' feature".into())),
+
+        #[cfg(feature = "rla")]
+        Format::Rla => rla::write(path, image),
+
+        #[cfg(not(feature = "rla"))]
+        Format::Rla => Err(IoError::UnsupportedFormat("RLA support requires 'rla' feature".into())),
+
+        #[cfg(feature = "y4m")]
+        Format::Y4m => y4m::write(path, image),
+
+        #[cfg(not(feature = "y4m"))]
+        Format::Y4m => Err(IoError::UnsupportedFormat("Y4M support requires 'y4m' feature".into())),
+
         #[cfg(feature = "heif")]
         Format::Heif => heif::write_heif(path, image, None),
 
@@ -382,12 +569,16 @@ pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
         #[cfg(not(feature = "avif"))]
         Format::Avif => Err(IoError::UnsupportedFormat("AVIF support requires 'avif' feature".into())),
 
-        // JP2 is read-only - the jpeg2k crate doesn't support creating new images
-        Format::Jp2 => Err(IoError::UnsupportedFormat("JPEG2000 write not supported (read-only format)".into())),
+        #[cfg(feature = "j2k")]
+        Format::Jp2 => j2k::write(path, image),
+
+        #[cfg(not(feature = "j2k"))]
+        Format::Jp2 => Err(IoError::UnsupportedFormat("JPEG2000 write requires 'j2k' feature".into())),
 
         // Camera raw formats are read-only
         Format::ArriRaw => Err(IoError::UnsupportedFormat("ARRIRAW write not supported (camera raw format)".into())),
         Format::RedCode => Err(IoError::UnsupportedFormat("REDCODE write not supported (camera raw format)".into())),
+        Format::Dng => Err(IoError::UnsupportedFormat("DNG write not supported (camera raw format)".into())),
 
         Format::Unknown => Err(IoError::UnsupportedFormat(
             path.extension()
@@ -450,6 +641,66 @@ pub fn write_with_format<P: AsRef<Path>>(
         #[cfg(feature = "hdr")]
         Format::Hdr => hdr::write(path, image),
 
+        #[cfg(feature = "bmp")]
+        Format::Bmp => bmp::write(path, image),
+
+        #[cfg(not(feature = "bmp"))]
+        Format::Bmp => Err(IoError::UnsupportedFormat("BMP support requires 'bmp' feature".into())),
+
+        #[cfg(feature = "tga")]
+        Format::Tga => tga::write(path, image),
+
+        #[cfg(not(feature = "tga"))]
+        Format::Tga => Err(IoError::UnsupportedFormat("TGA support requires 'tga' feature".into())),
+
+        #[cfg(feature = "cineon")]
+        Format::Cineon => cineon::write(path, image),
+
+        #[cfg(not(feature = "cineon"))]
+        Format::Cineon => Err(IoError::UnsupportedFormat("Cineon support requires 'cineon' feature".into())),
+
+        #[cfg(feature = "pnm")]
+        Format::Pnm => pnm::write(path, image),
+
+        #[cfg(not(feature = "pnm"))]
+        Format::Pnm => Err(IoError::UnsupportedFormat("Netpbm support requires 'pnm' feature".into())),
+
+        #[cfg(feature = "pfm")]
+        Format::Pfm => pfm::write(path, image),
+
+        #[cfg(not(feature = "pfm"))]
+        Format::Pfm => Err(IoError::UnsupportedFormat("PFM support requires 'pfm' feature".into())),
+
+        #[cfg(feature = "sgi")]
+        Format::Sgi => sgi::write(path, image),
+
+        #[cfg(not(feature = "sgi"))]
+        Format::Sgi => Err(IoError::UnsupportedFormat("SGI support requires 'sgi' feature".into())),
+
+        #[cfg(feature = "softimage")]
+        Format::Softimage => softimage::write(path, image),
+
+        #[cfg(not(feature = "softimage"))]
+        Format::Softimage => Err(IoError::UnsupportedFormat("Softimage PIC support requires 'softimage' feature".into())),
+
+        #[cfg(feature = "iff")]
+        Format::Iff => iff::write(path, image),
+
+        #[cfg(not(feature = "iff"))]
+        Format::Iff => Err(IoError::UnsupportedFormat("IFF support requires 'iff' feature".into())),
+
+        #[cfg(feature = "rla")]
+        Format::Rla => rla::write(path, image),
+
+        #[cfg(not(feature = "rla"))]
+        Format::Rla => Err(IoError::UnsupportedFormat("RLA support requires 'rla' feature".into())),
+
+        #[cfg(feature = "y4m")]
+        Format::Y4m => y4m::write(path, image),
+
+        #[cfg(not(feature = "y4m"))]
+        Format::Y4m => Err(IoError::UnsupportedFormat("Y4M support requires 'y4m' feature".into())),
+
         #[cfg(feature = "heif")]
         Format::Heif => heif::write_heif(path, image, None),
 
@@ -468,9 +719,15 @@ pub fn write_with_format<P: AsRef<Path>>(
         #[cfg(not(feature = "avif"))]
         Format::Avif => Err(IoError::UnsupportedFormat("AVIF support requires 'avif' feature".into())),
 
-        Format::Jp2 => Err(IoError::UnsupportedFormat("JPEG2000 write not supported (read-only format)".into())),
+        #[cfg(feature = "j2k")]
+        Format::Jp2 => j2k::write(path, image),
+
+        #[cfg(not(feature = "j2k"))]
+        Format::Jp2 => Err(IoError::UnsupportedFormat("JPEG2000 write requires 'j2k' feature".into())),
+
         Format::ArriRaw => Err(IoError::UnsupportedFormat("ARRIRAW write not supported (camera raw format)".into())),
         Format::RedCode => Err(IoError::UnsupportedFormat("REDCODE write not supported (camera raw format)".into())),
+        Format::Dng => Err(IoError::UnsupportedFormat("DNG write not supported (camera raw format)".into())),
 
         Format::Unknown => Err(IoError::UnsupportedFormat(
             format_hint.unwrap_or("unknown").to_string()
@@ -0,0 +1,412 @@
+//! Portable Float Map (PFM) support.
+//!
+//! PFM is the floating-point member of the Netpbm family, common for
+//! exchanging data with research code and denoisers.
+//!
+//! # Format
+//!
+//! ```text
+//! PF          <- "PF" for RGB, "Pf" for grayscale
+//! 640 480     <- width height
+//! -1.0        <- scale: negative = little-endian, positive = big-endian
+//! <raw f32 samples, rows stored bottom to top>
+//! ```
+//!
+//! # Features
+//!
+//! - 1 and 3 channel 32-bit float images
+//! - Both byte orders, chosen by the sign of the scale
+//! - Bottom-up row order converted to the usual top-down layout
+//! - The absolute scale is stored as `PFM:Scale`, samples are not multiplied
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use vfx_io::pfm;
+//!
+//! let image = pfm::read("albedo.pfm")?;
+//! pfm::write("denoised.pfm", &image)?;
+//! ```
+
+use crate::{AttrValue, FormatReader, FormatWriter, ImageData, IoError, IoResult, Metadata};
+use std::io::Write;
+use std::path::Path;
+use tracing::debug;
+
+// ============================================================================
+// Reader Options
+// ============================================================================
+
+/// Options for reading PFM files.
+///
+/// Currently minimal - PFM reading is mostly automatic.
+#[derive(Debug, Clone, Default)]
+pub struct PfmReaderOptions {
+    /// Reserved for future use.
+    _reserved: (),
+}
+
+// ============================================================================
+// Writer Options
+// ============================================================================
+
+/// Options for writing PFM files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::pfm::{PfmWriter, PfmWriterOptions};
+/// use vfx_io::FormatWriter;
+///
+/// let writer = PfmWriter::with_options(PfmWriterOptions { big_endian: true });
+/// writer.write("output.pfm", &image)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PfmWriterOptions {
+    /// Write big-endian samples (positive scale). Default: little-endian.
+    pub big_endian: bool,
+}
+
+// ============================================================================
+// PfmReader
+// ============================================================================
+
+/// PFM file reader.
+///
+/// Implements [`FormatReader`] for reading Portable Float Map files.
+#[derive(Debug, Clone)]
+pub struct PfmReader {
+    #[allow(dead_code)]
+    options: PfmReaderOptions,
+}
+
+impl PfmReader {
+    /// Creates a new reader with default options.
+    pub fn new() -> Self {
+        Self::with_options(PfmReaderOptions::default())
+    }
+
+    /// Internal read implementation.
+    fn read_impl(&self, data: &[u8]) -> IoResult<ImageData> {
+        if !is_pfm_header(data) {
+            return Err(IoError::InvalidFile("PFM magic not found".into()));
+        }
+        let channels = if data[1] == b'F' { 3 } else { 1 };
+
+        // Width, height and scale follow as whitespace-separated tokens
+        let mut pos = 2;
+        let mut tokens = Vec::with_capacity(3);
+        while tokens.len() < 3 {
+            while pos < data.len() && data[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            let start = pos;
+            while pos < data.len() && !data[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            if start == pos {
+                return Err(IoError::InvalidFile("PFM header truncated".into()));
+            }
+            tokens.push(String::from_utf8_lossy(&data[start..pos]).into_owned());
+        }
+        // A single whitespace character separates header and raster
+        pos += 1;
+
+        let invalid = |t: &str| IoError::InvalidFile(format!("invalid PFM header value: {}", t));
+        let width: usize = tokens[0].parse().map_err(|_| invalid(&tokens[0]))?;
+        let height: usize = tokens[1].parse().map_err(|_| invalid(&tokens[1]))?;
+        let scale: f32 = tokens[2].parse().map_err(|_| invalid(&tokens[2]))?;
+        if width == 0 || height == 0 {
+            return Err(IoError::InvalidFile(format!("invalid PFM dimensions: {}x{}", width, height)));
+        }
+        if scale == 0.0 || !scale.is_finite() {
+            return Err(IoError::InvalidFile(format!("invalid PFM scale: {}", scale)));
+        }
+        let little_endian = scale < 0.0;
+
+        let too_large = || IoError::InvalidFile(format!("PFM dimensions too large: {}x{}", width, height));
+        let row_len = width.checked_mul(channels).ok_or_else(too_large)?;
+        let samples = row_len.checked_mul(height).ok_or_else(too_large)?;
+        let bytes = samples.checked_mul(4).ok_or_else(too_large)?;
+        let raster = pos
+            .checked_add(bytes)
+            .and_then(|end| data.get(pos..end))
+            .ok_or_else(|| IoError::InvalidFile("PFM raster truncated".into()))?;
+
+        // Rows are stored bottom to top
+        let mut pixels = Vec::with_capacity(samples);
+        for row in raster.chunks_exact(row_len * 4).rev() {
+            pixels.extend(row.chunks_exact(4).map(|b| {
+                let b = [b[0], b[1], b[2], b[3]];
+                if little_endian {
+                    f32::from_le_bytes(b)
+                } else {
+                    f32::from_be_bytes(b)
+                }
+            }));
+        }
+
+        let mut image = ImageData::from_f32(width as u32, height as u32, channels as u32, pixels);
+        image.metadata = Metadata {
+            colorspace: Some("linear".to_string()),
+            ..Metadata::default()
+        };
+        image.metadata.attrs.set("PFM:Scale", AttrValue::Float(scale.abs()));
+        image.metadata.attrs.set(
+            "PFM:ByteOrder",
+            AttrValue::Str(if little_endian { "little" } else { "big" }.to_string()),
+        );
+        Ok(image)
+    }
+}
+
+impl Default for PfmReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatReader<PfmReaderOptions> for PfmReader {
+    /// Returns "PFM".
+    fn format_name(&self) -> &'static str {
+        "PFM"
+    }
+
+    /// Returns `["pfm"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pfm"]
+    }
+
+    /// Checks for PFM magic bytes ("PF" or "Pf").
+    fn can_read(&self, header: &[u8]) -> bool {
+        is_pfm_header(header)
+    }
+
+    /// Reads a PFM file from disk.
+    fn read<P: AsRef<Path>>(&self, path: P) -> IoResult<ImageData> {
+        let data = std::fs::read(path.as_ref())?;
+        self.read_impl(&data)
+    }
+
+    /// Reads a PFM image from a byte slice.
+    fn read_from_memory(&self, data: &[u8]) -> IoResult<ImageData> {
+        self.read_impl(data)
+    }
+
+    /// Creates reader with custom options.
+    fn with_options(options: PfmReaderOptions) -> Self {
+        Self { options }
+    }
+}
+
+// ============================================================================
+// PfmWriter
+// ============================================================================
+
+/// PFM file writer.
+///
+/// Implements [`FormatWriter`] for writing Portable Float Map files.
+/// PFM only stores 1 or 3 channels: gray-alpha is written as gray and
+/// images with 4 or more channels as RGB, dropping the rest.
+#[derive(Debug, Clone)]
+pub struct PfmWriter {
+    options: PfmWriterOptions,
+}
+
+impl PfmWriter {
+    /// Creates a new writer with default options.
+    pub fn new() -> Self {
+        Self::with_options(PfmWriterOptions::default())
+    }
+
+    /// Internal write implementation.
+    fn write_impl<W: Write>(&self, mut writer: W, image: &ImageData) -> IoResult<()> {
+        let width = image.width as usize;
+        let height = image.height as usize;
+        let channels = image.channels as usize;
+        if width == 0 || height == 0 || channels == 0 {
+            return Err(IoError::EncodeError(format!(
+                "invalid PFM dimensions: {}x{}x{}",
+                width, height, channels
+            )));
+        }
+
+        let out_channels = if channels >= 3 { 3 } else { 1 };
+        if out_channels != channels {
+            debug!("PFM: writing {} of {} channels", out_channels, channels);
+        }
+
+        let scale = if self.options.big_endian { 1.0 } else { -1.0 };
+        let mut out = Vec::with_capacity(width * height * out_channels * 4 + 32);
+        write!(out, "{}\n{} {}\n{:.1}\n", if out_channels == 3 { "PF" } else { "Pf" }, width, height, scale)?;
+
+        // Rows are stored bottom to top
+        let src = image.to_f32();
+        for row in src.chunks_exact(width * channels).rev() {
+            for px in row.chunks_exact(channels) {
+                for &v in &px[..out_channels] {
+                    let bytes = if self.options.big_endian { v.to_be_bytes() } else { v.to_le_bytes() };
+                    out.extend_from_slice(&bytes);
+                }
+            }
+        }
+
+        writer.write_all(&out)?;
+        Ok(())
+    }
+}
+
+impl Default for PfmWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatWriter<PfmWriterOptions> for PfmWriter {
+    /// Returns "PFM".
+    fn format_name(&self) -> &'static str {
+        "PFM"
+    }
+
+    /// Returns `["pfm"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pfm"]
+    }
+
+    /// Writes a PFM file to disk.
+    fn write<P: AsRef<Path>>(&self, path: P, image: &ImageData) -> IoResult<()> {
+        let file = std::fs::File::create(path.as_ref())?;
+        self.write_impl(std::io::BufWriter::new(file), image)
+    }
+
+    /// Writes a PFM image to a byte vector.
+    fn write_to_memory(&self, image: &ImageData) -> IoResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.write_impl(&mut buffer, image)?;
+        Ok(buffer)
+    }
+
+    /// Creates writer with custom options.
+    fn with_options(options: PfmWriterOptions) -> Self {
+        Self { options }
+    }
+}
+
+// ============================================================================
+// Convenience Functions
+// ============================================================================
+
+/// Reads a PFM file with default options.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::pfm;
+///
+/// let image = pfm::read("albedo.pfm")?;
+/// ```
+pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
+    PfmReader::new().read(path)
+}
+
+/// Writes a little-endian PFM file.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::pfm;
+///
+/// pfm::write("output.pfm", &image)?;
+/// ```
+pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
+    PfmWriter::new().write(path, image)
+}
+
+/// Checks for PFM magic bytes: `PF` or `Pf` followed by whitespace.
+pub fn is_pfm_header(header: &[u8]) -> bool {
+    header.len() >= 3
+        && header[0] == b'P'
+        && (header[1] == b'F' || header[1] == b'f')
+        && header[2].is_ascii_whitespace()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_image(channels: u32) -> ImageData {
+        let data = (0..4 * 3 * channels).map(|i| i as f32 * 0.25 - 1.0).collect();
+        ImageData::from_f32(4, 3, channels, data)
+    }
+
+    /// Tests roundtrips in both byte orders.
+    #[test]
+    fn test_roundtrip() {
+        for big_endian in [false, true] {
+            for channels in [1, 3] {
+                let image = sample_image(channels);
+                let writer = PfmWriter::with_options(PfmWriterOptions { big_endian });
+                let bytes = writer.write_to_memory(&image).expect("Write failed");
+
+                let loaded = PfmReader::new().read_from_memory(&bytes).expect("Read failed");
+                assert_eq!((loaded.width, loaded.height, loaded.channels), (4, 3, channels));
+                assert_eq!(loaded.to_f32(), image.to_f32());
+            }
+        }
+    }
+
+    /// Tests the sign of the scale and the bottom-up row order on disk.
+    #[test]
+    fn test_byte_order_and_rows() {
+        // 1x2 gray, big-endian: bottom row (1.0) first, then top row (2.0)
+        let mut data = b"Pf\n1 2\n4.0\n".to_vec();
+        data.extend_from_slice(&1.0f32.to_be_bytes());
+        data.extend_from_slice(&2.0f32.to_be_bytes());
+
+        let image = PfmReader::new().read_from_memory(&data).expect("Read failed");
+        assert_eq!(image.to_f32(), [2.0, 1.0]);
+        assert_eq!(image.metadata.attrs.get("PFM:Scale"), Some(&AttrValue::Float(4.0)));
+
+        let bytes = PfmWriter::new().write_to_memory(&image).expect("Write failed");
+        assert!(bytes.starts_with(b"Pf\n1 2\n-1.0\n"));
+        assert_eq!(&bytes[bytes.len() - 8..bytes.len() - 4], 1.0f32.to_le_bytes());
+    }
+
+    /// Tests that alpha is dropped on write.
+    #[test]
+    fn test_rgba_to_rgb() {
+        let image = sample_image(4);
+        let bytes = PfmWriter::new().write_to_memory(&image).expect("Write failed");
+        let loaded = PfmReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(loaded.channels, 3);
+        let expected: Vec<f32> = image.to_f32().chunks_exact(4).flat_map(|p| p[..3].to_vec()).collect();
+        assert_eq!(loaded.to_f32(), expected);
+    }
+
+    /// Tests that oversized header dimensions are rejected instead of overflowing.
+    #[test]
+    fn test_huge_dimensions() {
+        for header in [
+            &b"PF\n99999999999 99999999999\n-1\n"[..],
+            b"Pf\n18446744073709551615 2\n-1\n",
+            b"PF\n4611686018427387904 1\n-1\n",
+        ] {
+            let result = PfmReader::new().read_from_memory(header);
+            assert!(matches!(result, Err(IoError::InvalidFile(_))));
+        }
+    }
+
+    /// Tests magic byte detection.
+    #[test]
+    fn test_can_read() {
+        let reader = PfmReader::new();
+        assert!(reader.can_read(b"PF\n"));
+        assert!(reader.can_read(b"Pf\n"));
+        assert!(!reader.can_read(b"P6\n"));
+        assert!(!reader.can_read(b"PFX"));
+    }
+}
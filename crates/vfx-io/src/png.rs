@@ -11,6 +11,10 @@
 //! - Alpha channel transparency
 //! - Gamma and color profile metadata
 //! - Text chunks for metadata
+//! - EXIF (`eXIf`) and XMP (`iTXt`) as typed attributes
+//! - Embedded ICC profiles (`iCCP`), see [`crate::icc`]
+//! - Animated PNG (APNG) via [`ApngAnimation`]
+//! - HDR signaling (`cICP`, `mDCV`, `cLLI`) for Rec.2100 PQ/HLG
 //!
 //! # Architecture
 //!
@@ -24,6 +28,7 @@
 //! 2. **Convenience functions** (simple cases):
 //!    - [`read()`] - read with defaults
 //!    - [`write()`] - write with defaults
+//!    - [`read_animation()`] / [`write_animation()`] - APNG frames
 //!
 //! # Examples
 //!
@@ -48,10 +53,15 @@
 //! writer.write("output.png", &image)?;
 //! ```
 
+use crate::attrs::{exif, xmp};
+use crate::icc;
 use crate::{AttrValue, FormatReader, FormatWriter, ImageData, IoError, IoResult, Metadata, PixelData, PixelFormat};
 use std::io::{BufReader, BufWriter, Cursor};
 use std::path::Path;
 
+/// `iTXt` keyword of the XMP packet.
+const XMP_KEYWORD: &str = "XML:com.adobe.xmp";
+
 // ============================================================================
 // Bit Depth
 // ============================================================================
@@ -136,9 +146,12 @@ impl CompressionLevel {
 /// let image = reader.read("input.png")?;
 /// ```
 #[derive(Debug, Clone, Default)]
+#[non_exhaustive]
 pub struct PngReaderOptions {
-    /// Reserved for future use.
-    _reserved: (),
+    /// Converts pixels from the embedded ICC profile into a working space.
+    /// Default: None (pixels are returned as stored).
+    #[cfg(feature = "icc")]
+    pub icc_convert: Option<icc::IccConvert>,
 }
 
 // ============================================================================
@@ -243,167 +256,21 @@ impl PngReader {
         let width = output_info.width;
         let height = output_info.height;
 
-        // Convert to channels/format/data based on color type and bit depth
-        let (channels, format, data) = match (output_info.color_type, output_info.bit_depth) {
-            (png::ColorType::Rgb, png::BitDepth::Eight) => {
-                (3, PixelFormat::U8, PixelData::U8(buf[..output_info.buffer_size()].to_vec()))
-            }
-            (png::ColorType::Rgba, png::BitDepth::Eight) => {
-                (4, PixelFormat::U8, PixelData::U8(buf[..output_info.buffer_size()].to_vec()))
-            }
-            (png::ColorType::Rgb, png::BitDepth::Sixteen) => {
-                let u16_data = bytes_to_u16_be(&buf[..output_info.buffer_size()]);
-                (3, PixelFormat::U16, PixelData::U16(u16_data))
-            }
-            (png::ColorType::Rgba, png::BitDepth::Sixteen) => {
-                let u16_data = bytes_to_u16_be(&buf[..output_info.buffer_size()]);
-                (4, PixelFormat::U16, PixelData::U16(u16_data))
-            }
-            (png::ColorType::Grayscale, png::BitDepth::Eight) => {
-                // Convert grayscale to RGB
-                let rgb: Vec<u8> = buf[..output_info.buffer_size()]
-                    .iter()
-                    .flat_map(|&g| [g, g, g])
-                    .collect();
-                (3, PixelFormat::U8, PixelData::U8(rgb))
-            }
-            (png::ColorType::GrayscaleAlpha, png::BitDepth::Eight) => {
-                // Convert grayscale+alpha to RGBA
-                let rgba: Vec<u8> = buf[..output_info.buffer_size()]
-                    .chunks(2)
-                    .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
-                    .collect();
-                (4, PixelFormat::U8, PixelData::U8(rgba))
-            }
-            (png::ColorType::Grayscale, png::BitDepth::Sixteen) => {
-                // Convert 16-bit grayscale to RGB
-                let u16_data = bytes_to_u16_be(&buf[..output_info.buffer_size()]);
-                let rgb: Vec<u16> = u16_data.iter().flat_map(|&g| [g, g, g]).collect();
-                (3, PixelFormat::U16, PixelData::U16(rgb))
-            }
-            (png::ColorType::GrayscaleAlpha, png::BitDepth::Sixteen) => {
-                // Convert 16-bit grayscale+alpha to RGBA
-                let u16_data = bytes_to_u16_be(&buf[..output_info.buffer_size()]);
-                let rgba: Vec<u16> = u16_data
-                    .chunks(2)
-                    .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
-                    .collect();
-                (4, PixelFormat::U16, PixelData::U16(rgba))
-            }
-            (color_type, bit_depth) => {
-                return Err(IoError::UnsupportedBitDepth(format!(
-                    "{:?} {:?}",
-                    color_type, bit_depth
-                )));
-            }
-        };
+        let (channels, format, data) = decode_frame(&buf, &output_info)?;
+        let metadata = read_metadata(info);
 
-        // Build metadata
-        let mut metadata = Metadata::default();
-        metadata.colorspace = Some("sRGB".to_string());
-
-        // Basic image info
-        metadata.attrs.set("ImageWidth", AttrValue::UInt(width));
-        metadata.attrs.set("ImageHeight", AttrValue::UInt(height));
-        metadata.attrs.set(
-            "ColorType",
-            AttrValue::Str(format!("{:?}", info.color_type)),
-        );
-        metadata.attrs.set(
-            "BitDepth",
-            AttrValue::UInt(bit_depth_to_u32(info.bit_depth)),
-        );
-
-        // Gamma
-        if let Some(gamma) = info.gamma() {
-            let gamma = gamma.into_value();
-            metadata.gamma = Some(gamma);
-            metadata.attrs.set("Gamma", AttrValue::Float(gamma));
-        }
-
-        // Resolution/DPI
-        if let Some(dim) = info.pixel_dims {
-            if dim.xppu > 0 && dim.yppu > 0 {
-                match dim.unit {
-                    png::Unit::Meter => {
-                        let x_dpi = (dim.xppu as f64 * 0.0254) as f32;
-                        let y_dpi = (dim.yppu as f64 * 0.0254) as f32;
-                        metadata.attrs.set("XResolution", AttrValue::Float(x_dpi));
-                        metadata.attrs.set("YResolution", AttrValue::Float(y_dpi));
-                        metadata.attrs.set("ResolutionUnit", AttrValue::Str("dpi".into()));
-                        if (x_dpi - y_dpi).abs() < f32::EPSILON {
-                            metadata.dpi = Some(x_dpi);
-                        }
-                    }
-                    png::Unit::Unspecified => {
-                        metadata.attrs.set(
-                            "PixelAspectRatio",
-                            AttrValue::Str(format!("{}:{}", dim.xppu, dim.yppu)),
-                        );
-                    }
-                }
-            }
-        }
-
-        // sRGB rendering intent
-        if let Some(intent) = info.srgb {
-            metadata.attrs.set(
-                "sRGBRendering",
-                AttrValue::Str(format!("{:?}", intent)),
-            );
-        }
-
-        // ICC profile
-        if let Some(icc) = info.icc_profile.as_deref() {
-            metadata.attrs.set("ICCProfileSize", AttrValue::UInt(icc.len() as u32));
-            if icc.len() >= 20 {
-                if let Ok(space) = std::str::from_utf8(&icc[16..20]) {
-                    metadata.attrs.set(
-                        "ICCColorSpace",
-                        AttrValue::Str(space.trim().to_string()),
-                    );
-                }
-            }
-        }
-
-        // EXIF data
-        if let Some(exif) = info.exif_metadata.as_deref() {
-            metadata.attrs.set("ExifSize", AttrValue::UInt(exif.len() as u32));
-            if exif.len() <= 65536 {
-                metadata.attrs.set("ExifData", AttrValue::Bytes(exif.to_vec()));
-            }
-        }
-
-        // Text chunks (uncompressed)
-        for text in &info.uncompressed_latin1_text {
-            let key = format!("Text:{}", text.keyword);
-            metadata.attrs.set(key, AttrValue::Str(text.text.clone()));
-        }
-
-        // Text chunks (compressed)
-        for text in info.compressed_latin1_text.clone() {
-            if let Ok(value) = text.get_text() {
-                let key = format!("Text:{}", text.keyword);
-                metadata.attrs.set(key, AttrValue::Str(value));
-            }
-        }
-
-        // UTF-8 text chunks
-        for text in info.utf8_text.clone() {
-            if let Ok(value) = text.get_text() {
-                let key = format!("Text:{}", text.keyword);
-                metadata.attrs.set(key, AttrValue::Str(value));
-            }
-        }
-
-        Ok(ImageData {
+        #[allow(unused_mut)]
+        let mut image = ImageData {
             width,
             height,
             channels,
             format,
             data,
             metadata,
-        })
+        };
+        #[cfg(feature = "icc")]
+        icc::convert_on_read(&mut image, self.options.icc_convert.as_ref())?;
+        Ok(image)
     }
 }
 
@@ -455,6 +322,175 @@ impl FormatReader<PngReaderOptions> for PngReader {
     }
 }
 
+/// Converts a decoded frame buffer to channels, format and samples.
+///
+/// Grayscale is expanded to RGB, matching the rest of the reader.
+fn decode_frame(
+    buf: &[u8],
+    output_info: &png::OutputInfo,
+) -> IoResult<(u32, PixelFormat, PixelData)> {
+    let frame = match (output_info.color_type, output_info.bit_depth) {
+        (png::ColorType::Rgb, png::BitDepth::Eight) => {
+            (3, PixelFormat::U8, PixelData::U8(buf[..output_info.buffer_size()].to_vec()))
+        }
+        (png::ColorType::Rgba, png::BitDepth::Eight) => {
+            (4, PixelFormat::U8, PixelData::U8(buf[..output_info.buffer_size()].to_vec()))
+        }
+        (png::ColorType::Rgb, png::BitDepth::Sixteen) => {
+            let u16_data = bytes_to_u16_be(&buf[..output_info.buffer_size()]);
+            (3, PixelFormat::U16, PixelData::U16(u16_data))
+        }
+        (png::ColorType::Rgba, png::BitDepth::Sixteen) => {
+            let u16_data = bytes_to_u16_be(&buf[..output_info.buffer_size()]);
+            (4, PixelFormat::U16, PixelData::U16(u16_data))
+        }
+        (png::ColorType::Grayscale, png::BitDepth::Eight) => {
+            // Convert grayscale to RGB
+            let rgb: Vec<u8> = buf[..output_info.buffer_size()]
+                .iter()
+                .flat_map(|&g| [g, g, g])
+                .collect();
+            (3, PixelFormat::U8, PixelData::U8(rgb))
+        }
+        (png::ColorType::GrayscaleAlpha, png::BitDepth::Eight) => {
+            // Convert grayscale+alpha to RGBA
+            let rgba: Vec<u8> = buf[..output_info.buffer_size()]
+                .chunks(2)
+                .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+                .collect();
+            (4, PixelFormat::U8, PixelData::U8(rgba))
+        }
+        (png::ColorType::Grayscale, png::BitDepth::Sixteen) => {
+            // Convert 16-bit grayscale to RGB
+            let u16_data = bytes_to_u16_be(&buf[..output_info.buffer_size()]);
+            let rgb: Vec<u16> = u16_data.iter().flat_map(|&g| [g, g, g]).collect();
+            (3, PixelFormat::U16, PixelData::U16(rgb))
+        }
+        (png::ColorType::GrayscaleAlpha, png::BitDepth::Sixteen) => {
+            // Convert 16-bit grayscale+alpha to RGBA
+            let u16_data = bytes_to_u16_be(&buf[..output_info.buffer_size()]);
+            let rgba: Vec<u16> = u16_data
+                .chunks(2)
+                .flat_map(|ga| [ga[0], ga[0], ga[0], ga[1]])
+                .collect();
+            (4, PixelFormat::U16, PixelData::U16(rgba))
+        }
+        (color_type, bit_depth) => {
+            return Err(IoError::UnsupportedBitDepth(format!(
+                "{:?} {:?}",
+                color_type, bit_depth
+            )));
+        }
+    };
+    Ok(frame)
+}
+
+/// Collects image-level metadata from the PNG header chunks.
+fn read_metadata(info: &png::Info) -> Metadata {
+    let mut metadata = Metadata::default();
+    metadata.colorspace = Some("sRGB".to_string());
+
+    // Basic image info
+    metadata.attrs.set("ImageWidth", AttrValue::UInt(info.width));
+    metadata.attrs.set("ImageHeight", AttrValue::UInt(info.height));
+    metadata.attrs.set(
+        "ColorType",
+        AttrValue::Str(format!("{:?}", info.color_type)),
+    );
+    metadata.attrs.set(
+        "BitDepth",
+        AttrValue::UInt(bit_depth_to_u32(info.bit_depth)),
+    );
+
+    // Gamma
+    if let Some(gamma) = info.gamma() {
+        let gamma = gamma.into_value();
+        metadata.gamma = Some(gamma);
+        metadata.attrs.set("Gamma", AttrValue::Float(gamma));
+    }
+
+    // Resolution/DPI
+    if let Some(dim) = info.pixel_dims {
+        if dim.xppu > 0 && dim.yppu > 0 {
+            match dim.unit {
+                png::Unit::Meter => {
+                    let x_dpi = (dim.xppu as f64 * 0.0254) as f32;
+                    let y_dpi = (dim.yppu as f64 * 0.0254) as f32;
+                    metadata.attrs.set("XResolution", AttrValue::Float(x_dpi));
+                    metadata.attrs.set("YResolution", AttrValue::Float(y_dpi));
+                    metadata.attrs.set("ResolutionUnit", AttrValue::Str("dpi".into()));
+                    if (x_dpi - y_dpi).abs() < f32::EPSILON {
+                        metadata.dpi = Some(x_dpi);
+                    }
+                }
+                png::Unit::Unspecified => {
+                    metadata.attrs.set(
+                        "PixelAspectRatio",
+                        AttrValue::Str(format!("{}:{}", dim.xppu, dim.yppu)),
+                    );
+                }
+            }
+        }
+    }
+
+    // sRGB rendering intent
+    if let Some(intent) = info.srgb {
+        metadata.attrs.set(
+            "sRGBRendering",
+            AttrValue::Str(format!("{:?}", intent)),
+        );
+    }
+
+    // ICC profile
+    if let Some(profile) = info.icc_profile.as_deref() {
+        icc::set_profile(&mut metadata.attrs, profile.to_vec());
+    }
+
+    // HDR signaling (cICP overrides the sRGB default)
+    read_hdr(info, &mut metadata);
+
+    // Animation control
+    if let Some(actl) = info.animation_control {
+        metadata.attrs.set("AnimationFrames", AttrValue::UInt(actl.num_frames));
+        metadata.attrs.set("AnimationPlays", AttrValue::UInt(actl.num_plays));
+    }
+
+    // EXIF data (malformed EXIF is not fatal for the pixels)
+    if let Some(data) = info.exif_metadata.as_deref() {
+        metadata.attrs.set("ExifSize", AttrValue::UInt(data.len() as u32));
+        let _ = exif::read_exif(data, &mut metadata.attrs);
+    }
+
+    // Text chunks (uncompressed)
+    for text in &info.uncompressed_latin1_text {
+        let key = format!("Text:{}", text.keyword);
+        metadata.attrs.set(key, AttrValue::Str(text.text.clone()));
+    }
+
+    // Text chunks (compressed)
+    for text in info.compressed_latin1_text.clone() {
+        if let Ok(value) = text.get_text() {
+            let key = format!("Text:{}", text.keyword);
+            metadata.attrs.set(key, AttrValue::Str(value));
+        }
+    }
+
+    // UTF-8 text chunks, including the XMP packet
+    for text in info.utf8_text.clone() {
+        if let Ok(value) = text.get_text() {
+            if text.keyword == XMP_KEYWORD {
+                metadata.attrs.set("XMPSize", AttrValue::UInt(value.len() as u32));
+                let _ = xmp::read_xmp(&value, &mut metadata.attrs);
+            } else {
+                let key = format!("Text:{}", text.keyword);
+                metadata.attrs.set(key, AttrValue::Str(value));
+            }
+        }
+    }
+
+    metadata
+}
+
 // ============================================================================
 // PngWriter
 // ============================================================================
@@ -496,24 +532,39 @@ impl PngWriter {
 
     /// Internal write implementation.
     fn write_impl<W: std::io::Write>(&self, writer: W, image: &ImageData) -> IoResult<()> {
-        let buf_writer = BufWriter::new(writer);
+        let color_type = color_type(image.channels)?;
+        let encoder =
+            self.encoder(writer, image.width, image.height, color_type, &image.metadata)?;
+        let mut png_writer = write_header(encoder, &image.metadata)?;
+        png_writer
+            .write_image_data(&self.samples(image))
+            .map_err(|e| IoError::EncodeError(e.to_string()))?;
+        Ok(())
+    }
 
-        let color_type = match image.channels {
-            1 => png::ColorType::Grayscale,
-            2 => png::ColorType::GrayscaleAlpha,
-            3 => png::ColorType::Rgb,
-            4 => png::ColorType::Rgba,
-            n => return Err(IoError::EncodeError(format!("unsupported channels: {}", n))),
-        };
+    /// Creates an encoder configured from the writer options and `metadata`.
+    fn encoder<'a, W: std::io::Write>(
+        &self,
+        writer: W,
+        width: u32,
+        height: u32,
+        color_type: png::ColorType,
+        metadata: &'a Metadata,
+    ) -> IoResult<png::Encoder<'a, BufWriter<W>>> {
+        let buf_writer = BufWriter::new(writer);
 
-        let mut encoder = png::Encoder::new(buf_writer, image.width, image.height);
+        // An embedded profile goes in iCCP, which excludes the sRGB chunk
+        let profile = icc::profile(&metadata.attrs);
+        let mut info = png::Info::with_size(width, height);
+        info.icc_profile = profile.map(std::borrow::Cow::Borrowed);
+        let mut encoder = png::Encoder::with_info(buf_writer, info)
+            .map_err(|e| IoError::EncodeError(e.to_string()))?;
         encoder.set_color(color_type);
         encoder.set_depth(self.options.bit_depth.to_png());
         encoder.set_compression(self.options.compression.to_png());
 
         // Add sRGB chunk if requested or present in metadata
-        let srgb_intent = image
-            .metadata
+        let srgb_intent = metadata
             .attrs
             .get("sRGBRendering")
             .and_then(|v| v.as_str())
@@ -526,25 +577,25 @@ impl PngWriter {
                 }
             })
             .or_else(|| {
-                image
-                    .metadata
+                metadata
                     .colorspace
                     .as_ref()
                     .and_then(|cs| cs.eq_ignore_ascii_case("srgb").then_some(png::SrgbRenderingIntent::Perceptual))
             });
-        if let Some(intent) = srgb_intent {
+        // cICP overrides sRGB for decoders that know it, so only one is written
+        let tagged = profile.is_some() || cicp_from_metadata(metadata).is_some();
+        if let Some(intent) = srgb_intent.filter(|_| !tagged) {
             encoder.set_source_srgb(intent);
         }
 
-        if let Some(gamma) = image
-            .metadata
+        if let Some(gamma) = metadata
             .gamma
-            .or_else(|| image.metadata.attrs.get("Gamma").and_then(|v| v.as_f32()))
+            .or_else(|| metadata.attrs.get("Gamma").and_then(|v| v.as_f32()))
         {
             encoder.set_source_gamma(png::ScaledFloat::new(gamma));
         }
 
-        if let Some((x_dpi, y_dpi)) = dpi_from_metadata(&image.metadata) {
+        if let Some((x_dpi, y_dpi)) = dpi_from_metadata(metadata) {
             let xppu = (x_dpi / 0.0254) as u32;
             let yppu = (y_dpi / 0.0254) as u32;
             if xppu > 0 && yppu > 0 {
@@ -556,37 +607,64 @@ impl PngWriter {
             }
         }
 
-        for (key, value) in image.metadata.attrs.iter() {
+        let packet = xmp::write_xmp(&metadata.attrs);
+        for (key, value) in metadata.attrs.iter() {
             if let Some(text_key) = key.strip_prefix("Text:") {
+                if packet.is_some() && text_key == XMP_KEYWORD {
+                    continue;
+                }
                 if let AttrValue::Str(text) = value {
                     let _ = encoder.add_text_chunk(text_key.to_string(), text.clone());
                 }
             }
         }
+        if let Some(packet) = packet {
+            encoder
+                .add_itxt_chunk(XMP_KEYWORD.to_string(), packet)
+                .map_err(|e| IoError::EncodeError(e.to_string()))?;
+        }
 
-        let mut png_writer = encoder
-            .write_header()
-            .map_err(|e| IoError::EncodeError(e.to_string()))?;
+        Ok(encoder)
+    }
 
-        // Write pixel data based on bit depth
+    /// Converts pixels to big-endian samples at the configured bit depth.
+    fn samples(&self, image: &ImageData) -> Vec<u8> {
         match self.options.bit_depth {
-            BitDepth::Eight => {
-                let u8_data = image.to_u8();
-                png_writer
-                    .write_image_data(&u8_data)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-            }
-            BitDepth::Sixteen => {
-                let u16_data = image.to_u16();
-                let bytes = u16_to_bytes_be(&u16_data);
-                png_writer
-                    .write_image_data(&bytes)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-            }
+            BitDepth::Eight => image.to_u8(),
+            BitDepth::Sixteen => u16_to_bytes_be(&image.to_u16()),
         }
+    }
+}
 
-        Ok(())
+/// Maps a channel count to the PNG color type.
+fn color_type(channels: u32) -> IoResult<png::ColorType> {
+    match channels {
+        1 => Ok(png::ColorType::Grayscale),
+        2 => Ok(png::ColorType::GrayscaleAlpha),
+        3 => Ok(png::ColorType::Rgb),
+        4 => Ok(png::ColorType::Rgba),
+        n => Err(IoError::EncodeError(format!("unsupported channels: {}", n))),
+    }
+}
+
+/// Writes the header plus the chunks the encoder has no setters for.
+///
+/// eXIf, cICP, mDCV and cLLI must all precede the image data.
+fn write_header<W: std::io::Write>(
+    encoder: png::Encoder<'_, W>,
+    metadata: &Metadata,
+) -> IoResult<png::Writer<W>> {
+    let mut png_writer = encoder
+        .write_header()
+        .map_err(|e| IoError::EncodeError(e.to_string()))?;
+
+    let exif = exif::write_exif(&metadata.attrs).map(|data| (png::chunk::eXIf, data));
+    for (chunk, data) in exif.into_iter().chain(hdr_chunks(metadata)) {
+        png_writer
+            .write_chunk(chunk, &data)
+            .map_err(|e| IoError::EncodeError(e.to_string()))?;
     }
+    Ok(png_writer)
 }
 
 fn dpi_from_metadata(metadata: &Metadata) -> Option<(f32, f32)> {
@@ -656,6 +734,580 @@ impl FormatWriter<PngWriterOptions> for PngWriter {
     }
 }
 
+// ============================================================================
+// HDR Signaling
+// ============================================================================
+
+/// CICP code points of Rec.2100 PQ: BT.2020 primaries, ST 2084, RGB, full range.
+const CICP_PQ: [u8; 4] = [9, 16, 0, 1];
+
+/// CICP code points of Rec.2100 HLG: BT.2020 primaries, ARIB STD-B67, RGB, full range.
+const CICP_HLG: [u8; 4] = [9, 18, 0, 1];
+
+/// mDCV chromaticities are stored in units of 0.00002.
+const CHROMATICITY_SCALE: f32 = 50_000.0;
+
+/// mDCV and cLLI luminances are stored in units of 0.0001 cd/m².
+const LUMINANCE_SCALE: f32 = 10_000.0;
+
+/// Color space name for CICP primaries and transfer, as the HEIF reader names them.
+fn cicp_colorspace(primaries: u8, transfer: u8) -> Option<&'static str> {
+    let name = match (primaries, transfer) {
+        (9, 16) => "Rec.2100-PQ",
+        (9, 18) => "Rec.2100-HLG",
+        (9, _) => "Rec.2020",
+        (12, _) => "Display P3",
+        (11, _) => "DCI-P3",
+        (_, 13) => "sRGB",
+        (_, 8) => "Linear",
+        (1, _) => "Rec.709",
+        _ => return None,
+    };
+    Some(name)
+}
+
+/// Reads cICP, mDCV and cLLI into attributes.
+///
+/// cICP also sets the color space, taking precedence over sRGB/iCCP.
+fn read_hdr(info: &png::Info, metadata: &mut Metadata) {
+    if let Some(cicp) = info.coding_independent_code_points {
+        let points = [
+            cicp.color_primaries,
+            cicp.transfer_function,
+            cicp.matrix_coefficients,
+            cicp.is_video_full_range_image as u8,
+        ];
+        let list = points.iter().map(|&v| AttrValue::UInt(v.into())).collect();
+        metadata.attrs.set("CICP", AttrValue::List(list));
+        if let Some(name) = cicp_colorspace(points[0], points[1]) {
+            metadata.colorspace = Some(name.to_string());
+        }
+    }
+
+    if let Some(mdcv) = info.mastering_display_color_volume {
+        let c = mdcv.chromaticities;
+        let xy = [c.red, c.green, c.blue, c.white]
+            .into_iter()
+            .flat_map(|(x, y)| [x, y])
+            .map(|v| AttrValue::Float(v.into_value()))
+            .collect();
+        metadata.attrs.set("MasteringDisplayChromaticities", AttrValue::List(xy));
+        metadata.attrs.set(
+            "MasteringDisplayMaxLuminance",
+            AttrValue::Float(mdcv.max_luminance as f32 / LUMINANCE_SCALE),
+        );
+        metadata.attrs.set(
+            "MasteringDisplayMinLuminance",
+            AttrValue::Float(mdcv.min_luminance as f32 / LUMINANCE_SCALE),
+        );
+    }
+
+    if let Some(cll) = info.content_light_level {
+        metadata.attrs.set(
+            "MaxCLL",
+            AttrValue::Float(cll.max_content_light_level as f32 / LUMINANCE_SCALE),
+        );
+        metadata.attrs.set(
+            "MaxFALL",
+            AttrValue::Float(cll.max_frame_average_light_level as f32 / LUMINANCE_SCALE),
+        );
+    }
+}
+
+/// CICP code points to write: the `CICP` attribute, else Rec.2100 from the color space.
+///
+/// PNG only carries RGB, so the matrix coefficients are always written as 0.
+fn cicp_from_metadata(metadata: &Metadata) -> Option<[u8; 4]> {
+    if let Some(list) = metadata.attrs.get("CICP").and_then(|v| v.as_list()) {
+        let points: Vec<u8> = list
+            .iter()
+            .filter_map(|v| v.as_u32())
+            .filter_map(|v| u8::try_from(v).ok())
+            .collect();
+        let mut points: [u8; 4] = points.try_into().ok()?;
+        points[2] = 0;
+        return Some(points);
+    }
+    match metadata.colorspace.as_deref()?.to_ascii_lowercase().as_str() {
+        "rec.2100-pq" => Some(CICP_PQ),
+        "rec.2100-hlg" => Some(CICP_HLG),
+        _ => None,
+    }
+}
+
+/// Builds the cICP, mDCV and cLLI chunks from metadata.
+fn hdr_chunks(metadata: &Metadata) -> Vec<(png::chunk::ChunkType, Vec<u8>)> {
+    let mut chunks = Vec::new();
+    if let Some(points) = cicp_from_metadata(metadata) {
+        chunks.push((png::chunk::cICP, points.to_vec()));
+    }
+
+    let attrs = &metadata.attrs;
+    let xy: Option<Vec<f32>> = attrs
+        .get("MasteringDisplayChromaticities")
+        .and_then(|v| v.as_list())
+        .map(|list| list.iter().filter_map(|v| v.as_f32()).collect());
+    if let (Some(xy), Some(max), Some(min)) = (
+        xy.filter(|xy| xy.len() == 8),
+        attrs.get_f32("MasteringDisplayMaxLuminance"),
+        attrs.get_f32("MasteringDisplayMinLuminance"),
+    ) {
+        let mut data = Vec::with_capacity(24);
+        for v in xy {
+            let v = (v * CHROMATICITY_SCALE).round().clamp(0.0, u16::MAX as f32) as u16;
+            data.extend_from_slice(&v.to_be_bytes());
+        }
+        for nits in [max, min] {
+            data.extend_from_slice(&((nits * LUMINANCE_SCALE).round() as u32).to_be_bytes());
+        }
+        chunks.push((png::chunk::mDCV, data));
+    }
+
+    if let (Some(cll), Some(fall)) = (attrs.get_f32("MaxCLL"), attrs.get_f32("MaxFALL")) {
+        let mut data = Vec::with_capacity(8);
+        for nits in [cll, fall] {
+            data.extend_from_slice(&((nits * LUMINANCE_SCALE).round() as u32).to_be_bytes());
+        }
+        chunks.push((png::chunk::cLLI, data));
+    }
+
+    chunks
+}
+
+// ============================================================================
+// APNG
+// ============================================================================
+
+/// How a frame's region is treated before the next frame is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApngDispose {
+    /// Leave the canvas as it is.
+    #[default]
+    None,
+    /// Clear the region to transparent black.
+    Background,
+    /// Restore the region to its contents before the frame.
+    Previous,
+}
+
+impl ApngDispose {
+    fn to_png(self) -> png::DisposeOp {
+        match self {
+            Self::None => png::DisposeOp::None,
+            Self::Background => png::DisposeOp::Background,
+            Self::Previous => png::DisposeOp::Previous,
+        }
+    }
+
+    fn from_png(op: png::DisposeOp) -> Self {
+        match op {
+            png::DisposeOp::None => Self::None,
+            png::DisposeOp::Background => Self::Background,
+            png::DisposeOp::Previous => Self::Previous,
+        }
+    }
+}
+
+/// How a frame is drawn onto the canvas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApngBlend {
+    /// Replace the region, alpha included.
+    #[default]
+    Source,
+    /// Alpha-composite the frame over the region.
+    Over,
+}
+
+impl ApngBlend {
+    fn to_png(self) -> png::BlendOp {
+        match self {
+            Self::Source => png::BlendOp::Source,
+            Self::Over => png::BlendOp::Over,
+        }
+    }
+
+    fn from_png(op: png::BlendOp) -> Self {
+        match op {
+            png::BlendOp::Source => Self::Source,
+            png::BlendOp::Over => Self::Over,
+        }
+    }
+}
+
+/// A single APNG frame: a region of the canvas with timing and compositing ops.
+#[derive(Debug, Clone)]
+pub struct ApngFrame {
+    /// Frame pixels, covering `width` x `height` of the canvas.
+    pub image: ImageData,
+    /// Left edge of the region on the canvas.
+    pub x_offset: u32,
+    /// Top edge of the region on the canvas.
+    pub y_offset: u32,
+    /// Delay numerator, in seconds.
+    pub delay_num: u16,
+    /// Delay denominator; 0 is read as 100.
+    pub delay_den: u16,
+    /// Disposal after the frame is shown.
+    pub dispose: ApngDispose,
+    /// Blending onto the canvas.
+    pub blend: ApngBlend,
+}
+
+impl ApngFrame {
+    /// Creates a full-canvas frame shown for `delay_num / delay_den` seconds.
+    pub fn new(image: ImageData, delay_num: u16, delay_den: u16) -> Self {
+        Self {
+            image,
+            x_offset: 0,
+            y_offset: 0,
+            delay_num,
+            delay_den,
+            dispose: ApngDispose::None,
+            blend: ApngBlend::Source,
+        }
+    }
+
+    /// Returns the display time in seconds.
+    pub fn delay_seconds(&self) -> f64 {
+        let den = if self.delay_den == 0 { 100 } else { self.delay_den };
+        self.delay_num as f64 / den as f64
+    }
+}
+
+/// An animated PNG: canvas size, loop count and frames in display order.
+///
+/// Frames are stored as encoded (sub-regions with dispose/blend ops);
+/// use [`ApngAnimation::composite`] for the displayed images.
+#[derive(Debug, Clone, Default)]
+pub struct ApngAnimation {
+    /// Canvas width.
+    pub width: u32,
+    /// Canvas height.
+    pub height: u32,
+    /// Number of loops, 0 for infinite.
+    pub num_plays: u32,
+    /// Frames; the first one covers the whole canvas.
+    pub frames: Vec<ApngFrame>,
+    /// File-level metadata (text, EXIF, ICC, HDR chunks).
+    pub metadata: Metadata,
+}
+
+impl ApngAnimation {
+    /// Builds a looping animation from same-sized images shown at `fps`.
+    ///
+    /// Metadata is taken from the first image.
+    pub fn from_sequence(images: Vec<ImageData>, fps: f64) -> IoResult<Self> {
+        let first = images
+            .first()
+            .ok_or_else(|| IoError::MissingData("APNG needs at least one frame".into()))?;
+        let (width, height) = (first.width, first.height);
+        if let Some(image) = images.iter().find(|i| (i.width, i.height) != (width, height)) {
+            return Err(IoError::EncodeError(format!(
+                "frame size {}x{} differs from {}x{}",
+                image.width, image.height, width, height
+            )));
+        }
+        if !(fps.is_finite() && fps > 0.0) {
+            return Err(IoError::EncodeError(format!("invalid frame rate: {}", fps)));
+        }
+
+        let (delay_num, delay_den) = fps_to_delay(fps);
+        let metadata = first.metadata.clone();
+        let frames = images
+            .into_iter()
+            .map(|image| ApngFrame::new(image, delay_num, delay_den))
+            .collect();
+        Ok(Self {
+            width,
+            height,
+            num_plays: 0,
+            frames,
+            metadata,
+        })
+    }
+
+    /// Renders every frame onto the canvas, applying blend and dispose ops.
+    ///
+    /// Returns one full-canvas RGBA float image per frame. Alpha is straight,
+    /// as in the file; a `Previous` disposal on the first frame acts as
+    /// `Background`.
+    pub fn composite(&self) -> Vec<ImageData> {
+        let (width, height) = (self.width as usize, self.height as usize);
+        let mut canvas = vec![0.0f32; width * height * 4];
+        let mut composited = Vec::with_capacity(self.frames.len());
+
+        for (index, frame) in self.frames.iter().enumerate() {
+            let dispose = match frame.dispose {
+                ApngDispose::Previous if index == 0 => ApngDispose::Background,
+                op => op,
+            };
+            let saved = (dispose == ApngDispose::Previous).then(|| canvas.clone());
+
+            let rgba = to_rgba(&frame.image);
+            let frame_width = frame.image.width as usize;
+            let (x0, y0) = (frame.x_offset as usize, frame.y_offset as usize);
+            let region_width = frame_width.min(width.saturating_sub(x0));
+            let region_height = (frame.image.height as usize).min(height.saturating_sub(y0));
+
+            for y in 0..region_height {
+                for x in 0..region_width {
+                    let src = &rgba[(y * frame_width + x) * 4..][..4];
+                    let dst = &mut canvas[((y0 + y) * width + x0 + x) * 4..][..4];
+                    match frame.blend {
+                        ApngBlend::Source => dst.copy_from_slice(src),
+                        ApngBlend::Over => blend_over(src, dst),
+                    }
+                }
+            }
+
+            let mut image = ImageData::from_f32(self.width, self.height, 4, canvas.clone());
+            image.metadata = self.metadata.clone();
+            composited.push(image);
+
+            match dispose {
+                ApngDispose::None => {}
+                ApngDispose::Background => {
+                    for y in 0..region_height {
+                        let row = ((y0 + y) * width + x0) * 4;
+                        canvas[row..row + region_width * 4].fill(0.0);
+                    }
+                }
+                ApngDispose::Previous => {
+                    if let Some(saved) = saved {
+                        canvas = saved;
+                    }
+                }
+            }
+        }
+
+        composited
+    }
+}
+
+/// Picks an exact frame delay for common rates (integer and NTSC), else milliseconds.
+fn fps_to_delay(fps: f64) -> (u16, u16) {
+    let ntsc = fps * 1.001;
+    if (fps - fps.round()).abs() < 1e-6 && fps.round() <= u16::MAX as f64 {
+        (1, fps.round() as u16)
+    } else if (ntsc - ntsc.round()).abs() < 1e-3 && ntsc.round() * 1000.0 <= u16::MAX as f64 {
+        (1001, (ntsc.round() * 1000.0) as u16)
+    } else if fps * 1000.0 <= u16::MAX as f64 {
+        (1000, (fps * 1000.0).round().max(1.0) as u16)
+    } else {
+        (1, fps.round().min(u16::MAX as f64) as u16)
+    }
+}
+
+/// Expands any channel layout to straight-alpha RGBA floats.
+fn to_rgba(image: &ImageData) -> Vec<f32> {
+    let channels = image.channels.max(1) as usize;
+    image
+        .to_f32()
+        .chunks(channels)
+        .flat_map(|p| match p.len() {
+            1 => [p[0], p[0], p[0], 1.0],
+            2 => [p[0], p[0], p[0], p[1]],
+            3 => [p[0], p[1], p[2], 1.0],
+            _ => [p[0], p[1], p[2], p[3]],
+        })
+        .collect()
+}
+
+/// Straight-alpha "over" of `src` onto `dst`.
+fn blend_over(src: &[f32], dst: &mut [f32]) {
+    let (src_a, dst_a) = (src[3], dst[3]);
+    let out_a = src_a + dst_a * (1.0 - src_a);
+    if out_a <= 0.0 {
+        dst.fill(0.0);
+        return;
+    }
+    for c in 0..3 {
+        dst[c] = (src[c] * src_a + dst[c] * dst_a * (1.0 - src_a)) / out_a;
+    }
+    dst[3] = out_a;
+}
+
+/// Returns `image` with exactly `channels` channels, converting through RGBA if needed.
+fn with_channels(image: &ImageData, channels: u32) -> std::borrow::Cow<'_, ImageData> {
+    if image.channels == channels {
+        return std::borrow::Cow::Borrowed(image);
+    }
+    let data: Vec<f32> = to_rgba(image)
+        .chunks(4)
+        .flat_map(|p| match channels {
+            1 => vec![p[0]],
+            2 => vec![p[0], p[3]],
+            3 => vec![p[0], p[1], p[2]],
+            _ => p.to_vec(),
+        })
+        .collect();
+    std::borrow::Cow::Owned(ImageData::from_f32(image.width, image.height, channels, data))
+}
+
+impl PngReader {
+    /// Reads every frame of an APNG file.
+    ///
+    /// A still PNG yields a single full-canvas frame. A default image that
+    /// is not part of the animation is skipped.
+    pub fn read_animation<P: AsRef<Path>>(&self, path: P) -> IoResult<ApngAnimation> {
+        let file = std::fs::File::open(path.as_ref())?;
+        self.read_animation_impl(file)
+    }
+
+    /// Reads every frame of an APNG from a byte slice.
+    pub fn read_animation_from_memory(&self, data: &[u8]) -> IoResult<ApngAnimation> {
+        self.read_animation_impl(Cursor::new(data))
+    }
+
+    fn read_animation_impl<R: std::io::Read + std::io::Seek>(
+        &self,
+        reader: R,
+    ) -> IoResult<ApngAnimation> {
+        let decoder = png::Decoder::new(BufReader::new(reader));
+        let mut reader = decoder
+            .read_info()
+            .map_err(|e| IoError::DecodeError(e.to_string()))?;
+
+        let info = reader.info();
+        let metadata = read_metadata(info);
+        let (width, height) = (info.width, info.height);
+        let (num_frames, num_plays) = info
+            .animation_control
+            .map_or((1, 0), |actl| (actl.num_frames, actl.num_plays));
+        let mut skip_default = info.animation_control.is_some() && info.frame_control.is_none();
+
+        let buf_size = reader
+            .output_buffer_size()
+            .ok_or_else(|| IoError::DecodeError("cannot determine buffer size".into()))?;
+        let mut buf = vec![0u8; buf_size];
+        let mut frames = Vec::with_capacity(num_frames as usize);
+        while frames.len() < num_frames as usize {
+            let output_info = reader
+                .next_frame(&mut buf)
+                .map_err(|e| IoError::DecodeError(e.to_string()))?;
+            if std::mem::take(&mut skip_default) {
+                continue;
+            }
+
+            let (channels, format, data) = decode_frame(&buf, &output_info)?;
+            let image = ImageData {
+                width: output_info.width,
+                height: output_info.height,
+                channels,
+                format,
+                data,
+                metadata: Metadata::default(),
+            };
+            let frame = match reader.info().frame_control {
+                Some(fctl) => ApngFrame {
+                    image,
+                    x_offset: fctl.x_offset,
+                    y_offset: fctl.y_offset,
+                    delay_num: fctl.delay_num,
+                    delay_den: fctl.delay_den,
+                    dispose: ApngDispose::from_png(fctl.dispose_op),
+                    blend: ApngBlend::from_png(fctl.blend_op),
+                },
+                None => ApngFrame::new(image, 0, 0),
+            };
+            frames.push(frame);
+        }
+
+        Ok(ApngAnimation {
+            width,
+            height,
+            num_plays,
+            frames,
+            metadata,
+        })
+    }
+}
+
+impl PngWriter {
+    /// Writes an APNG file.
+    ///
+    /// The first frame must cover the whole canvas. All frames share one
+    /// color type: RGB if any frame has color, with alpha if any has alpha.
+    pub fn write_animation<P: AsRef<Path>>(
+        &self,
+        path: P,
+        animation: &ApngAnimation,
+    ) -> IoResult<()> {
+        let file = std::fs::File::create(path.as_ref())?;
+        self.write_animation_impl(file, animation)
+    }
+
+    /// Writes an APNG to a byte vector.
+    pub fn write_animation_to_memory(&self, animation: &ApngAnimation) -> IoResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.write_animation_impl(Cursor::new(&mut buffer), animation)?;
+        Ok(buffer)
+    }
+
+    fn write_animation_impl<W: std::io::Write>(
+        &self,
+        writer: W,
+        animation: &ApngAnimation,
+    ) -> IoResult<()> {
+        let encode_err = |e: png::EncodingError| IoError::EncodeError(e.to_string());
+
+        let first = animation
+            .frames
+            .first()
+            .ok_or_else(|| IoError::MissingData("APNG needs at least one frame".into()))?;
+        let full_canvas = (0, 0, animation.width, animation.height);
+        if (first.x_offset, first.y_offset, first.image.width, first.image.height) != full_canvas {
+            return Err(IoError::EncodeError(
+                "first APNG frame must cover the whole canvas".into(),
+            ));
+        }
+        let num_frames = u32::try_from(animation.frames.len())
+            .map_err(|_| IoError::EncodeError("too many APNG frames".into()))?;
+
+        let color = animation.frames.iter().any(|f| f.image.channels >= 3);
+        let alpha = animation.frames.iter().any(|f| matches!(f.image.channels, 2 | 4));
+        let channels = if color { 3 } else { 1 } + alpha as u32;
+
+        let mut encoder = self.encoder(
+            writer,
+            animation.width,
+            animation.height,
+            color_type(channels)?,
+            &animation.metadata,
+        )?;
+        encoder
+            .set_animated(num_frames, animation.num_plays)
+            .map_err(encode_err)?;
+        let mut png_writer = write_header(encoder, &animation.metadata)?;
+
+        for frame in &animation.frames {
+            // Reset the position first so the new size is validated against the origin
+            png_writer.set_frame_position(0, 0).map_err(encode_err)?;
+            png_writer
+                .set_frame_dimension(frame.image.width, frame.image.height)
+                .map_err(encode_err)?;
+            png_writer
+                .set_frame_position(frame.x_offset, frame.y_offset)
+                .map_err(encode_err)?;
+            png_writer
+                .set_frame_delay(frame.delay_num, frame.delay_den)
+                .map_err(encode_err)?;
+            png_writer
+                .set_dispose_op(frame.dispose.to_png())
+                .map_err(encode_err)?;
+            png_writer
+                .set_blend_op(frame.blend.to_png())
+                .map_err(encode_err)?;
+            let image = with_channels(&frame.image, channels);
+            png_writer
+                .write_image_data(&self.samples(&image))
+                .map_err(encode_err)?;
+        }
+
+        png_writer.finish().map_err(encode_err)
+    }
+}
+
 // ============================================================================
 // Convenience Functions
 // ============================================================================
@@ -692,6 +1344,34 @@ pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
     PngWriter::new().write(path, image)
 }
 
+/// Reads all frames of an APNG file with default options.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::png;
+///
+/// let animation = png::read_animation("turntable.png")?;
+/// let frames = animation.composite();
+/// ```
+pub fn read_animation<P: AsRef<Path>>(path: P) -> IoResult<ApngAnimation> {
+    PngReader::new().read_animation(path)
+}
+
+/// Writes an APNG file with default options.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::png::{self, ApngAnimation};
+///
+/// let animation = ApngAnimation::from_sequence(frames, 24.0)?;
+/// png::write_animation("review.png", &animation)?;
+/// ```
+pub fn write_animation<P: AsRef<Path>>(path: P, animation: &ApngAnimation) -> IoResult<()> {
+    PngWriter::new().write_animation(path, animation)
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -818,6 +1498,178 @@ mod tests {
         assert_eq!(loaded.height, 16);
     }
 
+    #[test]
+    fn test_exif_xmp_roundtrip() {
+        let mut image = ImageData::from_u8(8, 8, 3, vec![50; 8 * 8 * 3]);
+        let attrs = &mut image.metadata.attrs;
+        attrs.set("Model", AttrValue::Str("Mavic 3".into()));
+        attrs.set("Exif:FocalLength", AttrValue::URational(12, 1));
+        attrs.set("GPS:Altitude", AttrValue::URational(12050, 100));
+        attrs.set("XMP:dc:subject", AttrValue::List(vec!["hdri".into(), "sky".into()]));
+        attrs.set("Text:Comment", AttrValue::Str("kept".into()));
+
+        let bytes = PngWriter::new().write_to_memory(&image).expect("Write failed");
+        let loaded = PngReader::new().read_from_memory(&bytes).expect("Read failed");
+        let attrs = &loaded.metadata.attrs;
+
+        assert_eq!(attrs.get_str("Model"), Some("Mavic 3"));
+        assert_eq!(attrs.get_urational("Exif:FocalLength"), Some((12, 1)));
+        assert_eq!(attrs.get_urational("GPS:Altitude"), Some((12050, 100)));
+        let subject = attrs.get("XMP:dc:subject").and_then(|v| v.as_list()).unwrap();
+        assert_eq!(subject[1].as_str(), Some("sky"));
+        assert_eq!(attrs.get_str("Text:Comment"), Some("kept"));
+        assert!(attrs.get("Text:XML:com.adobe.xmp").is_none());
+    }
+
+    /// Tests that an iCCP profile replaces the default sRGB chunk.
+    #[test]
+    fn test_icc_roundtrip() {
+        let mut profile = vec![7u8; 3000];
+        profile[16..20].copy_from_slice(b"RGB ");
+        let mut image = ImageData::from_u8(4, 4, 3, vec![10; 4 * 4 * 3]);
+        icc::set_profile(&mut image.metadata.attrs, profile.clone());
+
+        let bytes = PngWriter::new().write_to_memory(&image).expect("Write failed");
+        let loaded = PngReader::new().read_from_memory(&bytes).expect("Read failed");
+        let attrs = &loaded.metadata.attrs;
+
+        assert_eq!(icc::profile(attrs), Some(&profile[..]));
+        assert_eq!(attrs.get_str("ICCColorSpace"), Some("RGB"));
+        assert!(attrs.get("sRGBRendering").is_none());
+    }
+
+    /// Tests that PQ tagging and mastering metadata survive a 16-bit roundtrip.
+    #[test]
+    fn test_hdr_roundtrip() {
+        let data: Vec<u16> = (0..8 * 8 * 3).map(|i| (i * 170) as u16).collect();
+        let mut image = ImageData::new(8, 8, 3, PixelFormat::U16);
+        image.data = PixelData::U16(data.clone());
+        image.metadata.colorspace = Some("Rec.2100-PQ".into());
+        let xy = [0.708, 0.292, 0.170, 0.797, 0.131, 0.046, 0.3127, 0.329];
+        let list = xy.iter().map(|&v| AttrValue::Float(v)).collect();
+        let attrs = &mut image.metadata.attrs;
+        attrs.set("MasteringDisplayChromaticities", AttrValue::List(list));
+        attrs.set("MasteringDisplayMaxLuminance", AttrValue::Float(1000.0));
+        attrs.set("MasteringDisplayMinLuminance", AttrValue::Float(0.0001));
+        attrs.set("MaxCLL", AttrValue::Float(800.0));
+        attrs.set("MaxFALL", AttrValue::Float(250.0));
+
+        let writer = PngWriter::with_options(PngWriterOptions {
+            bit_depth: BitDepth::Sixteen,
+            ..Default::default()
+        });
+        let bytes = writer.write_to_memory(&image).expect("Write failed");
+        let loaded = PngReader::new().read_from_memory(&bytes).expect("Read failed");
+        let attrs = &loaded.metadata.attrs;
+
+        assert_eq!(loaded.to_u16(), data);
+        assert_eq!(loaded.metadata.colorspace.as_deref(), Some("Rec.2100-PQ"));
+        let cicp: Vec<u32> = attrs
+            .get("CICP")
+            .and_then(|v| v.as_list())
+            .unwrap()
+            .iter()
+            .filter_map(|v| v.as_u32())
+            .collect();
+        assert_eq!(cicp, [9, 16, 0, 1]);
+        assert!(attrs.get("sRGBRendering").is_none());
+
+        let loaded_xy = attrs.get("MasteringDisplayChromaticities").and_then(|v| v.as_list());
+        for (a, b) in loaded_xy.unwrap().iter().zip(xy) {
+            assert!((a.as_f32().unwrap() - b).abs() < 1e-4);
+        }
+        assert_eq!(attrs.get_f32("MasteringDisplayMaxLuminance"), Some(1000.0));
+        assert!((attrs.get_f32("MasteringDisplayMinLuminance").unwrap() - 0.0001).abs() < 1e-6);
+        assert_eq!(attrs.get_f32("MaxCLL"), Some(800.0));
+        assert_eq!(attrs.get_f32("MaxFALL"), Some(250.0));
+
+        // HLG is tagged from the color space name as well
+        image.metadata.colorspace = Some("Rec.2100-HLG".into());
+        let bytes = writer.write_to_memory(&image).expect("Write failed");
+        let loaded = PngReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(loaded.metadata.colorspace.as_deref(), Some("Rec.2100-HLG"));
+    }
+
+    fn solid(width: u32, height: u32, rgba: [u8; 4]) -> ImageData {
+        let data = rgba.repeat((width * height) as usize);
+        ImageData::from_u8(width, height, 4, data)
+    }
+
+    /// Tests APNG frame regions, timing and ops through a roundtrip and composite.
+    #[test]
+    fn test_apng_roundtrip() {
+        let mut overlay = ApngFrame::new(solid(2, 2, [0, 255, 0, 128]), 1, 10);
+        overlay.x_offset = 1;
+        overlay.y_offset = 1;
+        overlay.blend = ApngBlend::Over;
+        overlay.dispose = ApngDispose::Previous;
+        let mut patch = ApngFrame::new(solid(2, 2, [0, 0, 255, 255]), 1, 10);
+        patch.x_offset = 2;
+        patch.y_offset = 2;
+        patch.dispose = ApngDispose::Background;
+
+        let animation = ApngAnimation {
+            width: 4,
+            height: 4,
+            num_plays: 3,
+            frames: vec![ApngFrame::new(solid(4, 4, [255, 0, 0, 255]), 1, 10), overlay, patch],
+            metadata: Metadata::default(),
+        };
+        let bytes = PngWriter::new()
+            .write_animation_to_memory(&animation)
+            .expect("Write failed");
+
+        // The default image is the first frame
+        let still = PngReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!((still.width, still.height), (4, 4));
+        assert_eq!(still.metadata.attrs.get_u32("AnimationFrames"), Some(3));
+
+        let loaded = PngReader::new()
+            .read_animation_from_memory(&bytes)
+            .expect("Read failed");
+        assert_eq!((loaded.width, loaded.height, loaded.num_plays), (4, 4, 3));
+        assert_eq!(loaded.frames.len(), 3);
+        let frame = &loaded.frames[1];
+        assert_eq!((frame.x_offset, frame.y_offset), (1, 1));
+        assert_eq!((frame.image.width, frame.image.height), (2, 2));
+        assert_eq!((frame.blend, frame.dispose), (ApngBlend::Over, ApngDispose::Previous));
+        assert!((frame.delay_seconds() - 0.1).abs() < 1e-9);
+        assert_eq!(loaded.frames[2].dispose, ApngDispose::Background);
+
+        let composited = loaded.composite();
+        assert_eq!(composited.len(), 3);
+        let pixel = |image: &ImageData, x: usize, y: usize| -> Vec<f32> {
+            image.to_f32()[(y * 4 + x) * 4..][..4].to_vec()
+        };
+        // Half green over red
+        let blended = pixel(&composited[1], 1, 1);
+        assert!((blended[0] - 0.498).abs() < 0.01 && (blended[1] - 0.502).abs() < 0.01);
+        assert_eq!(pixel(&composited[1], 0, 0), [1.0, 0.0, 0.0, 1.0]);
+        // The overlay was disposed to the previous canvas before the blue patch
+        assert_eq!(pixel(&composited[2], 1, 1), [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(pixel(&composited[2], 3, 3), [0.0, 0.0, 1.0, 1.0]);
+    }
+
+    /// Tests building an animation from an image sequence.
+    #[test]
+    fn test_apng_from_sequence() {
+        let frames = vec![solid(4, 4, [255, 0, 0, 255]), solid(4, 4, [0, 0, 255, 255])];
+        let animation = ApngAnimation::from_sequence(frames.clone(), 24.0).unwrap();
+        assert_eq!(animation.frames[0].delay_num, 1);
+        assert_eq!(animation.frames[0].delay_den, 24);
+
+        let ntsc = ApngAnimation::from_sequence(frames, 24000.0 / 1001.0).unwrap();
+        assert_eq!((ntsc.frames[1].delay_num, ntsc.frames[1].delay_den), (1001, 24000));
+
+        let bytes = PngWriter::new().write_animation_to_memory(&ntsc).unwrap();
+        let loaded = PngReader::new().read_animation_from_memory(&bytes).unwrap();
+        assert_eq!(loaded.num_plays, 0);
+        assert_eq!(loaded.composite()[1].to_f32()[..4], [0.0, 0.0, 1.0, 1.0]);
+
+        let mismatched = vec![solid(4, 4, [0; 4]), solid(2, 2, [0; 4])];
+        assert!(ApngAnimation::from_sequence(mismatched, 24.0).is_err());
+    }
+
     /// Tests magic byte detection.
     #[test]
     fn test_can_read() {
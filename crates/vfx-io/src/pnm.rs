@@ -0,0 +1,843 @@
+//! Netpbm format support (PBM, PGM, PPM and PAM).
+//!
+//! Provides reading and writing of the Netpbm family - simple uncompressed
+//! formats used for debug dumps and for exchanging images with research code.
+//!
+//! # Overview
+//!
+//! | Magic | Format | Channels |
+//! |-------|--------|----------|
+//! | `P1` / `P4` | PBM bitmap, plain / raw | 1 (1 = black) |
+//! | `P2` / `P5` | PGM graymap, plain / raw | 1 |
+//! | `P3` / `P6` | PPM pixmap, plain / raw | 3 |
+//! | `P7` | PAM, arbitrary tuples | 1-n |
+//!
+//! Samples with a maximum value up to 255 are read as 8-bit, larger ones
+//! (stored big-endian) as 16-bit. Other maximum values are rescaled to the
+//! full 8 or 16-bit range. Header comments are stored as `ImageDescription`.
+//!
+//! Only the first image of a multi-image stream is read.
+//!
+//! # Examples
+//!
+//! Simple usage:
+//! ```ignore
+//! use vfx_io::pnm;
+//!
+//! let image = pnm::read("debug.ppm")?;
+//! pnm::write("mask.pgm", &image)?;
+//! ```
+//!
+//! Plain (ASCII) output:
+//! ```ignore
+//! use vfx_io::pnm::{PnmWriter, PnmWriterOptions};
+//! use vfx_io::FormatWriter;
+//!
+//! let writer = PnmWriter::with_options(PnmWriterOptions {
+//!     plain: true,
+//!     ..Default::default()
+//! });
+//! writer.write("debug.ppm", &image)?;
+//! ```
+
+use crate::{AttrValue, FormatReader, FormatWriter, ImageData, IoError, IoResult, Metadata, PixelData, PixelFormat};
+use std::io::Write;
+use std::path::Path;
+
+/// Maximum line length of plain formats.
+const PLAIN_LINE_LENGTH: usize = 70;
+
+// ============================================================================
+// Reader Options
+// ============================================================================
+
+/// Options for reading Netpbm files.
+///
+/// Currently minimal - Netpbm reading is mostly automatic.
+#[derive(Debug, Clone, Default)]
+pub struct PnmReaderOptions {
+    /// Reserved for future use.
+    _reserved: (),
+}
+
+// ============================================================================
+// Writer Options
+// ============================================================================
+
+/// Netpbm output variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PnmKind {
+    /// Picked from the file extension, or from the channel count:
+    /// PGM for 1 channel, PPM for 3, PAM otherwise.
+    #[default]
+    Auto,
+    /// PBM bitmap (P1/P4), thresholded at 0.5.
+    Bitmap,
+    /// PGM graymap (P2/P5).
+    Graymap,
+    /// PPM pixmap (P3/P6).
+    Pixmap,
+    /// PAM (P7), keeps all channels including alpha.
+    Pam,
+}
+
+/// Options for writing Netpbm files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::pnm::{PnmKind, PnmWriter, PnmWriterOptions};
+/// use vfx_io::FormatWriter;
+///
+/// let writer = PnmWriter::with_options(PnmWriterOptions {
+///     kind: PnmKind::Graymap,
+///     max_value: Some(65535),
+///     ..Default::default()
+/// });
+/// writer.write("depth.pgm", &image)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PnmWriterOptions {
+    /// Output variant. Default: [`PnmKind::Auto`].
+    pub kind: PnmKind,
+    /// Write plain (ASCII) P1-P3 instead of raw P4-P6. Ignored for PAM.
+    pub plain: bool,
+    /// Maximum sample value. Default: 255 for 8-bit images, 65535 otherwise.
+    pub max_value: Option<u16>,
+}
+
+// ============================================================================
+// Header
+// ============================================================================
+
+/// Parsed header of any Netpbm variant.
+#[derive(Debug, Clone)]
+struct PnmHeader {
+    /// Magic digit, 1-7.
+    kind: u8,
+    width: usize,
+    height: usize,
+    depth: usize,
+    max_value: u32,
+    tuple_type: String,
+    comments: Vec<String>,
+    /// Byte offset of the raster.
+    data_offset: usize,
+}
+
+/// Whitespace and comment aware tokenizer for Netpbm headers.
+struct Tokens<'a> {
+    data: &'a [u8],
+    pos: usize,
+    comments: Vec<String>,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(data: &'a [u8], pos: usize) -> Self {
+        Self { data, pos, comments: Vec::new() }
+    }
+
+    /// Skips whitespace and collects comments.
+    fn skip_space(&mut self) {
+        while let Some(&b) = self.data.get(self.pos) {
+            if b == b'#' {
+                let start = self.pos + 1;
+                while self.pos < self.data.len() && self.data[self.pos] != b'\n' && self.data[self.pos] != b'\r' {
+                    self.pos += 1;
+                }
+                let comment = String::from_utf8_lossy(&self.data[start..self.pos]).trim().to_string();
+                self.comments.push(comment);
+            } else if b.is_ascii_whitespace() {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn token(&mut self) -> IoResult<&'a str> {
+        self.skip_space();
+        let start = self.pos;
+        while self.pos < self.data.len() && !self.data[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(IoError::InvalidFile("Netpbm header truncated".into()));
+        }
+        std::str::from_utf8(&self.data[start..self.pos])
+            .map_err(|_| IoError::InvalidFile("Netpbm header is not ASCII".into()))
+    }
+
+    fn number(&mut self) -> IoResult<u32> {
+        let token = self.token()?;
+        token
+            .parse()
+            .map_err(|_| IoError::InvalidFile(format!("invalid Netpbm header value: {}", token)))
+    }
+
+    /// Returns the rest of the current line.
+    fn rest_of_line(&mut self) -> &'a str {
+        let start = self.pos;
+        while self.pos < self.data.len() && self.data[self.pos] != b'\n' {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.data[start..self.pos]).unwrap_or("").trim()
+    }
+}
+
+impl PnmHeader {
+    fn parse(data: &[u8]) -> IoResult<Self> {
+        if !is_pnm_header(data) {
+            return Err(IoError::InvalidFile("Netpbm magic not found".into()));
+        }
+        let kind = data[1] - b'0';
+        let mut tokens = Tokens::new(data, 2);
+
+        let mut header = Self {
+            kind,
+            width: 0,
+            height: 0,
+            depth: match kind {
+                3 | 6 => 3,
+                _ => 1,
+            },
+            max_value: 1,
+            tuple_type: String::new(),
+            comments: Vec::new(),
+            data_offset: 0,
+        };
+
+        if kind == 7 {
+            loop {
+                match tokens.token()? {
+                    "WIDTH" => header.width = tokens.number()? as usize,
+                    "HEIGHT" => header.height = tokens.number()? as usize,
+                    "DEPTH" => header.depth = tokens.number()? as usize,
+                    "MAXVAL" => header.max_value = tokens.number()?,
+                    "TUPLTYPE" => {
+                        // Multiple TUPLTYPE lines are concatenated
+                        let value = tokens.rest_of_line();
+                        if !header.tuple_type.is_empty() {
+                            header.tuple_type.push(' ');
+                        }
+                        header.tuple_type.push_str(value);
+                    }
+                    "ENDHDR" => {
+                        tokens.rest_of_line();
+                        tokens.pos += 1;
+                        break;
+                    }
+                    other => {
+                        return Err(IoError::InvalidFile(format!("unknown PAM header field: {}", other)));
+                    }
+                }
+            }
+        } else {
+            header.width = tokens.number()? as usize;
+            header.height = tokens.number()? as usize;
+            if kind != 1 && kind != 4 {
+                header.max_value = tokens.number()?;
+            }
+            // A single whitespace character separates header and raster
+            tokens.pos += 1;
+        }
+
+        if header.width == 0 || header.height == 0 || header.depth == 0 {
+            return Err(IoError::InvalidFile(format!(
+                "invalid Netpbm dimensions: {}x{}x{}",
+                header.width, header.height, header.depth
+            )));
+        }
+        if header.max_value == 0 || header.max_value > 65535 {
+            return Err(IoError::InvalidFile(format!("invalid Netpbm maximum value: {}", header.max_value)));
+        }
+
+        header.comments = tokens.comments;
+        header.data_offset = tokens.pos.min(data.len());
+        Ok(header)
+    }
+
+    fn is_plain(&self) -> bool {
+        self.kind <= 3
+    }
+
+    fn is_bitmap(&self) -> bool {
+        self.kind == 1 || self.kind == 4
+    }
+}
+
+// ============================================================================
+// PnmReader
+// ============================================================================
+
+/// Netpbm file reader.
+///
+/// Implements [`FormatReader`] for reading PBM, PGM, PPM and PAM files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::pnm::PnmReader;
+/// use vfx_io::FormatReader;
+///
+/// let reader = PnmReader::new();
+/// let image = reader.read("debug.ppm")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct PnmReader {
+    #[allow(dead_code)]
+    options: PnmReaderOptions,
+}
+
+impl PnmReader {
+    /// Creates a new reader with default options.
+    pub fn new() -> Self {
+        Self::with_options(PnmReaderOptions::default())
+    }
+
+    /// Internal read implementation.
+    fn read_impl(&self, data: &[u8]) -> IoResult<ImageData> {
+        let header = PnmHeader::parse(data)?;
+        let raster = &data[header.data_offset..];
+        let count = header
+            .width
+            .checked_mul(header.height)
+            .and_then(|n| n.checked_mul(header.depth))
+            .ok_or_else(|| {
+                IoError::InvalidFile(format!("Netpbm dimensions too large: {}x{}", header.width, header.height))
+            })?;
+
+        let samples = if header.kind == 4 {
+            read_packed_bits(raster, header.width, header.height)?
+        } else if header.is_plain() {
+            read_plain(raster, count, header.is_bitmap())?
+        } else {
+            read_binary(raster, count, header.max_value)?
+        };
+
+        // PBM stores 1 for black
+        let (samples, max_value) = if header.is_bitmap() {
+            (samples.into_iter().map(|v| 1 - v.min(1)).collect(), 1)
+        } else {
+            (samples, header.max_value)
+        };
+
+        let mut image = scale_samples(&samples, &header, max_value);
+        image.metadata = self.read_metadata(&header);
+        Ok(image)
+    }
+
+    /// Builds metadata from the header.
+    fn read_metadata(&self, header: &PnmHeader) -> Metadata {
+        let mut metadata = Metadata {
+            colorspace: Some("sRGB".to_string()),
+            ..Metadata::default()
+        };
+        metadata.attrs.set("PNM:Type", AttrValue::Str(format!("P{}", header.kind)));
+        if !header.is_bitmap() {
+            metadata.attrs.set("PNM:MaxVal", AttrValue::UInt(header.max_value));
+        }
+        if !header.tuple_type.is_empty() {
+            metadata.attrs.set("PNM:TupleType", AttrValue::Str(header.tuple_type.clone()));
+        }
+        let comments: Vec<&str> = header.comments.iter().map(String::as_str).filter(|c| !c.is_empty()).collect();
+        if !comments.is_empty() {
+            metadata.attrs.set("ImageDescription", AttrValue::Str(comments.join("\n")));
+        }
+        metadata
+    }
+}
+
+impl Default for PnmReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatReader<PnmReaderOptions> for PnmReader {
+    /// Returns "Netpbm".
+    fn format_name(&self) -> &'static str {
+        "Netpbm"
+    }
+
+    /// Returns `["pnm", "pbm", "pgm", "ppm", "pam"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pnm", "pbm", "pgm", "ppm", "pam"]
+    }
+
+    /// Checks for Netpbm magic bytes (P1-P7).
+    fn can_read(&self, header: &[u8]) -> bool {
+        is_pnm_header(header)
+    }
+
+    /// Reads a Netpbm file from disk.
+    fn read<P: AsRef<Path>>(&self, path: P) -> IoResult<ImageData> {
+        let data = std::fs::read(path.as_ref())?;
+        self.read_impl(&data)
+    }
+
+    /// Reads a Netpbm image from a byte slice.
+    fn read_from_memory(&self, data: &[u8]) -> IoResult<ImageData> {
+        self.read_impl(data)
+    }
+
+    /// Creates reader with custom options.
+    fn with_options(options: PnmReaderOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Reads P4 rows, each padded to a whole byte.
+fn read_packed_bits(raster: &[u8], width: usize, height: usize) -> IoResult<Vec<u32>> {
+    let stride = width.div_ceil(8);
+    if stride.checked_mul(height).is_none_or(|bytes| raster.len() < bytes) {
+        return Err(IoError::InvalidFile("PBM raster truncated".into()));
+    }
+    let mut out = Vec::with_capacity(width * height);
+    for row in raster.chunks_exact(stride).take(height) {
+        out.extend((0..width).map(|x| ((row[x / 8] >> (7 - x % 8)) & 1) as u32));
+    }
+    Ok(out)
+}
+
+/// Reads ASCII samples. Plain PBM digits need no separators.
+fn read_plain(raster: &[u8], count: usize, bitmap: bool) -> IoResult<Vec<u32>> {
+    // Every sample takes at least one byte
+    if raster.len() < count {
+        return Err(IoError::InvalidFile("Netpbm raster truncated".into()));
+    }
+    let mut out = Vec::with_capacity(count);
+    let mut tokens = Tokens::new(raster, 0);
+
+    while out.len() < count {
+        if bitmap {
+            tokens.skip_space();
+            match tokens.data.get(tokens.pos) {
+                Some(b'0') => out.push(0),
+                Some(b'1') => out.push(1),
+                _ => return Err(IoError::InvalidFile("PBM raster truncated".into())),
+            }
+            tokens.pos += 1;
+        } else {
+            out.push(tokens.number()?);
+        }
+    }
+    Ok(out)
+}
+
+/// Reads raw samples, 16-bit big-endian if the maximum value exceeds 255.
+fn read_binary(raster: &[u8], count: usize, max_value: u32) -> IoResult<Vec<u32>> {
+    let wide = max_value > 255;
+    let bytes = count.checked_mul(if wide { 2 } else { 1 });
+    let Some(bytes) = bytes.filter(|&bytes| raster.len() >= bytes) else {
+        return Err(IoError::InvalidFile("Netpbm raster truncated".into()));
+    };
+    Ok(if wide {
+        raster[..bytes].chunks_exact(2).map(|b| u16::from_be_bytes([b[0], b[1]]) as u32).collect()
+    } else {
+        raster[..bytes].iter().map(|&b| b as u32).collect()
+    })
+}
+
+/// Rescales samples to 8 or 16 bits.
+fn scale_samples(samples: &[u32], header: &PnmHeader, max_value: u32) -> ImageData {
+    let (width, height, channels) = (header.width as u32, header.height as u32, header.depth as u32);
+    let scale = |v: u32, out_max: u32| (v.min(max_value) * out_max + max_value / 2) / max_value;
+
+    if max_value > 255 {
+        let data = samples.iter().map(|&v| scale(v, 65535) as u16).collect();
+        ImageData {
+            width,
+            height,
+            channels,
+            format: PixelFormat::U16,
+            data: PixelData::U16(data),
+            metadata: Metadata::default(),
+        }
+    } else {
+        let data = samples.iter().map(|&v| scale(v, 255) as u8).collect();
+        ImageData::from_u8(width, height, channels, data)
+    }
+}
+
+// ============================================================================
+// PnmWriter
+// ============================================================================
+
+/// Netpbm file writer.
+///
+/// Implements [`FormatWriter`] for writing PBM, PGM, PPM and PAM files.
+/// Color images written as PGM or PBM use the mean of their color channels;
+/// alpha is only kept in PAM.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::pnm::PnmWriter;
+/// use vfx_io::FormatWriter;
+///
+/// let writer = PnmWriter::new();
+/// writer.write("debug.ppm", &image)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct PnmWriter {
+    options: PnmWriterOptions,
+}
+
+impl PnmWriter {
+    /// Creates a new writer with default options.
+    pub fn new() -> Self {
+        Self::with_options(PnmWriterOptions::default())
+    }
+
+    /// Internal write implementation.
+    fn write_impl<W: Write>(&self, mut writer: W, image: &ImageData, kind: PnmKind) -> IoResult<()> {
+        let width = image.width as usize;
+        let height = image.height as usize;
+        let channels = image.channels as usize;
+        if width == 0 || height == 0 || channels == 0 {
+            return Err(IoError::EncodeError(format!(
+                "invalid Netpbm dimensions: {}x{}x{}",
+                width, height, channels
+            )));
+        }
+
+        let kind = match kind {
+            PnmKind::Auto => match channels {
+                1 => PnmKind::Graymap,
+                3 => PnmKind::Pixmap,
+                _ => PnmKind::Pam,
+            },
+            kind => kind,
+        };
+        let plain = self.options.plain && kind != PnmKind::Pam;
+        let max_value = match kind {
+            PnmKind::Bitmap => 1,
+            _ => self
+                .options
+                .max_value
+                .unwrap_or(if image.format == PixelFormat::U8 { 255 } else { 65535 })
+                .max(1) as u32,
+        };
+
+        // Convert to the output channel layout, as normalized floats
+        let src = image.to_f32();
+        let color = if channels == 2 || channels >= 4 { channels - 1 } else { channels };
+        let gray = |px: &[f32]| px[..color.min(3)].iter().sum::<f32>() / color.min(3) as f32;
+        let out_channels = match kind {
+            PnmKind::Bitmap | PnmKind::Graymap => 1,
+            PnmKind::Pixmap => 3,
+            _ => channels,
+        };
+        let mut values = Vec::with_capacity(width * height * out_channels);
+        for px in src.chunks_exact(channels) {
+            match kind {
+                PnmKind::Bitmap => values.push(if gray(px) < 0.5 { 1 } else { 0 }),
+                PnmKind::Graymap => values.push(quantize(gray(px), max_value)),
+                PnmKind::Pixmap if color >= 3 => values.extend(px[..3].iter().map(|&v| quantize(v, max_value))),
+                PnmKind::Pixmap => values.extend([quantize(px[0], max_value); 3]),
+                _ => values.extend(px.iter().map(|&v| quantize(v, max_value))),
+            }
+        }
+
+        let magic = match (kind, plain) {
+            (PnmKind::Bitmap, true) => 1,
+            (PnmKind::Graymap, true) => 2,
+            (PnmKind::Pixmap, true) => 3,
+            (PnmKind::Bitmap, false) => 4,
+            (PnmKind::Graymap, false) => 5,
+            (PnmKind::Pixmap, false) => 6,
+            _ => 7,
+        };
+
+        let mut out = Vec::with_capacity(values.len() * 2 + 64);
+        writeln!(out, "P{}", magic)?;
+        if let Some(description) = image.metadata.attrs.get("ImageDescription").and_then(|v| v.as_str()) {
+            for line in description.lines() {
+                writeln!(out, "# {}", line)?;
+            }
+        }
+
+        if magic == 7 {
+            writeln!(out, "WIDTH {}", width)?;
+            writeln!(out, "HEIGHT {}", height)?;
+            writeln!(out, "DEPTH {}", out_channels)?;
+            writeln!(out, "MAXVAL {}", max_value)?;
+            let tuple_type = match out_channels {
+                1 => Some("GRAYSCALE"),
+                2 => Some("GRAYSCALE_ALPHA"),
+                3 => Some("RGB"),
+                4 => Some("RGB_ALPHA"),
+                _ => None,
+            };
+            if let Some(tuple_type) = tuple_type {
+                writeln!(out, "TUPLTYPE {}", tuple_type)?;
+            }
+            writeln!(out, "ENDHDR")?;
+        } else {
+            writeln!(out, "{} {}", width, height)?;
+            if kind != PnmKind::Bitmap {
+                writeln!(out, "{}", max_value)?;
+            }
+        }
+
+        if magic == 4 {
+            for row in values.chunks_exact(width) {
+                let mut packed = vec![0u8; width.div_ceil(8)];
+                for (x, &bit) in row.iter().enumerate() {
+                    packed[x / 8] |= (bit as u8) << (7 - x % 8);
+                }
+                out.extend_from_slice(&packed);
+            }
+        } else if plain {
+            write_plain(&mut out, &values, width * out_channels, magic == 1)?;
+        } else if max_value > 255 {
+            for v in values {
+                out.extend_from_slice(&(v as u16).to_be_bytes());
+            }
+        } else {
+            out.extend(values.into_iter().map(|v| v as u8));
+        }
+
+        writer.write_all(&out)?;
+        Ok(())
+    }
+}
+
+impl Default for PnmWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatWriter<PnmWriterOptions> for PnmWriter {
+    /// Returns "Netpbm".
+    fn format_name(&self) -> &'static str {
+        "Netpbm"
+    }
+
+    /// Returns `["pnm", "pbm", "pgm", "ppm", "pam"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pnm", "pbm", "pgm", "ppm", "pam"]
+    }
+
+    /// Writes a Netpbm file to disk. With [`PnmKind::Auto`], the variant
+    /// follows the file extension.
+    fn write<P: AsRef<Path>>(&self, path: P, image: &ImageData) -> IoResult<()> {
+        let path = path.as_ref();
+        let kind = match self.options.kind {
+            PnmKind::Auto => kind_from_extension(path),
+            kind => kind,
+        };
+        let file = std::fs::File::create(path)?;
+        self.write_impl(std::io::BufWriter::new(file), image, kind)
+    }
+
+    /// Writes a Netpbm image to a byte vector.
+    fn write_to_memory(&self, image: &ImageData) -> IoResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.write_impl(&mut buffer, image, self.options.kind)?;
+        Ok(buffer)
+    }
+
+    /// Creates writer with custom options.
+    fn with_options(options: PnmWriterOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Picks the output variant from a file extension.
+fn kind_from_extension(path: &Path) -> PnmKind {
+    let ext = path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+    match ext.as_deref() {
+        Some("pbm") => PnmKind::Bitmap,
+        Some("pgm") => PnmKind::Graymap,
+        Some("ppm") => PnmKind::Pixmap,
+        Some("pam") => PnmKind::Pam,
+        _ => PnmKind::Auto,
+    }
+}
+
+/// Converts a normalized value to an integer sample.
+fn quantize(value: f32, max_value: u32) -> u32 {
+    (value.clamp(0.0, 1.0) * max_value as f32).round() as u32
+}
+
+/// Writes ASCII samples, one raster row per line group, lines up to 70 characters.
+fn write_plain(out: &mut Vec<u8>, values: &[u32], row_len: usize, bitmap: bool) -> IoResult<()> {
+    for row in values.chunks_exact(row_len) {
+        let mut line_len = 0;
+        for &v in row {
+            let token = v.to_string();
+            let separator = usize::from(!bitmap && line_len > 0);
+            if line_len + separator + token.len() > PLAIN_LINE_LENGTH {
+                out.push(b'\n');
+                line_len = 0;
+            } else if separator == 1 {
+                out.push(b' ');
+                line_len += 1;
+            }
+            out.extend_from_slice(token.as_bytes());
+            line_len += token.len();
+        }
+        out.push(b'\n');
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Convenience Functions
+// ============================================================================
+
+/// Reads a Netpbm file with default options.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::pnm;
+///
+/// let image = pnm::read("debug.ppm")?;
+/// ```
+pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
+    PnmReader::new().read(path)
+}
+
+/// Writes a Netpbm file with default options.
+///
+/// The variant follows the extension: `.pbm`, `.pgm`, `.ppm` or `.pam`.
+/// For `.pnm`, it is picked from the channel count.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::pnm;
+///
+/// pnm::write("debug.ppm", &image)?;
+/// ```
+pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
+    PnmWriter::new().write(path, image)
+}
+
+/// Checks for Netpbm magic bytes: `P1` to `P7` followed by whitespace.
+pub fn is_pnm_header(header: &[u8]) -> bool {
+    header.len() >= 3
+        && header[0] == b'P'
+        && (b'1'..=b'7').contains(&header[1])
+        && header[2].is_ascii_whitespace()
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_kind(image: &ImageData, kind: PnmKind, plain: bool) -> Vec<u8> {
+        let writer = PnmWriter::with_options(PnmWriterOptions { kind, plain, max_value: None });
+        writer.write_to_memory(image).expect("Write failed")
+    }
+
+    /// Tests raw and plain roundtrips of gray and color images.
+    #[test]
+    fn test_roundtrip() {
+        let gray = ImageData::from_u8(5, 3, 1, (0..15).map(|i| i * 17).collect());
+        let rgb = ImageData::from_u8(5, 3, 3, (0..45).map(|i| i * 5).collect());
+
+        for plain in [false, true] {
+            for (image, kind, magic) in [(&gray, PnmKind::Graymap, 2), (&rgb, PnmKind::Pixmap, 3)] {
+                let bytes = write_kind(image, kind, plain);
+                let expected = if plain { magic } else { magic + 3 };
+                assert_eq!(&bytes[..2], format!("P{}", expected).as_bytes());
+
+                let loaded = PnmReader::new().read_from_memory(&bytes).expect("Read failed");
+                assert_eq!((loaded.width, loaded.height, loaded.channels), (5, 3, image.channels));
+                assert_eq!(loaded.to_u8(), image.to_u8());
+            }
+        }
+    }
+
+    /// Tests 16-bit PAM with alpha.
+    #[test]
+    fn test_pam_16bit() {
+        let data: Vec<u16> = (0..3 * 2 * 4).map(|i| (i * 2731) as u16).collect();
+        let image = ImageData {
+            width: 3,
+            height: 2,
+            channels: 4,
+            format: PixelFormat::U16,
+            data: PixelData::U16(data.clone()),
+            metadata: Metadata::default(),
+        };
+
+        let bytes = write_kind(&image, PnmKind::Auto, false);
+        assert!(bytes.starts_with(b"P7\nWIDTH 3\nHEIGHT 2\nDEPTH 4\nMAXVAL 65535\nTUPLTYPE RGB_ALPHA\nENDHDR\n"));
+
+        let loaded = PnmReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(loaded.format, PixelFormat::U16);
+        assert_eq!(loaded.to_u16(), data);
+        assert_eq!(
+            loaded.metadata.attrs.get("PNM:TupleType").and_then(|v| v.as_str()),
+            Some("RGB_ALPHA")
+        );
+    }
+
+    /// Tests PBM bitmaps, where 1 is black.
+    #[test]
+    fn test_bitmap() {
+        let plain = b"P1\n# mask\n10 2\n1111100000\n0 1 0 1 0 1 0 1 0 1\n";
+        let image = PnmReader::new().read_from_memory(plain).expect("Read failed");
+        assert_eq!(image.channels, 1);
+        let data = image.to_u8();
+        assert_eq!(&data[..10], [0, 0, 0, 0, 0, 255, 255, 255, 255, 255]);
+        assert_eq!(&data[10..12], [255, 0]);
+        assert_eq!(image.metadata.attrs.get("ImageDescription").and_then(|v| v.as_str()), Some("mask"));
+
+        for plain in [false, true] {
+            let bytes = write_kind(&image, PnmKind::Bitmap, plain);
+            let loaded = PnmReader::new().read_from_memory(&bytes).expect("Read failed");
+            assert_eq!(loaded.to_u8(), data);
+        }
+    }
+
+    /// Tests rescaling of unusual maximum values.
+    #[test]
+    fn test_max_value_scaling() {
+        let image = PnmReader::new()
+            .read_from_memory(b"P2 3 1 15 0 7 15")
+            .expect("Read failed");
+        assert_eq!(image.to_u8(), [0, 119, 255]);
+
+        let mut raw = b"P5\n2 1\n1023\n".to_vec();
+        raw.extend_from_slice(&[0x03, 0xFF, 0x02, 0x00]);
+        let image = PnmReader::new().read_from_memory(&raw).expect("Read failed");
+        assert_eq!(image.format, PixelFormat::U16);
+        assert_eq!(image.to_u16(), [65535, 32800]);
+    }
+
+    /// Tests that oversized headers are rejected before allocating.
+    #[test]
+    fn test_huge_dimensions() {
+        for header in [
+            &b"P7\nWIDTH 4294967295\nHEIGHT 4294967295\nDEPTH 4294967295\nMAXVAL 255\nENDHDR\n"[..],
+            b"P5\n4294967295 4294967295\n65535\n\0\0",
+            b"P2\n65535 65535\n255\n1 2 3\n",
+            b"P1\n65535 65535\n0101\n",
+            b"P4\n4294967295 4294967295\n\xFF",
+        ] {
+            let result = PnmReader::new().read_from_memory(header);
+            assert!(matches!(result, Err(IoError::InvalidFile(_))), "{:?}", result.err());
+        }
+    }
+
+    /// Tests magic byte detection.
+    #[test]
+    fn test_can_read() {
+        let reader = PnmReader::new();
+        assert!(reader.can_read(b"P6\n"));
+        assert!(reader.can_read(b"P7\nWIDTH"));
+        assert!(!reader.can_read(b"P8\n"));
+        assert!(!reader.can_read(b"PF\n"));
+    }
+}
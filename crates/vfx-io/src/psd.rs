@@ -1,6 +1,7 @@
 //! Adobe Photoshop PSD/PSB format support.
 //!
-//! Provides read support for PSD files with layer access.
+//! Provides read support for PSD files with layer access, and a layered
+//! PSD/PSB writer.
 //!
 //! # Features
 //!
@@ -8,6 +9,10 @@
 //! - Access individual layers by name or index
 //! - Layer blend modes and opacity
 //! - 8-bit RGB/RGBA support
+//! - Write layered RGB documents at 8, 16 or 32 bits per channel
+//! - Layer names, opacity, visibility, blend modes and a composite image
+//! - PSB (large document) output for canvases over 30000 pixels
+//! - Embedded ICC profile (image resource 1039), read and written
 //!
 //! # Example
 //!
@@ -24,11 +29,23 @@
 //! }
 //! # Ok::<(), vfx_io::IoError>(())
 //! ```
+//!
+//! Writing AOVs as layers:
+//!
+//! ```ignore
+//! use vfx_io::psd::{self, PsdBlendMode, PsdWriteLayer};
+//!
+//! let mut spec = PsdWriteLayer::new("specular", specular);
+//! spec.blend_mode = PsdBlendMode::LinearDodge;
+//! psd::write_layers("beauty.psd", &[PsdWriteLayer::new("diffuse", diffuse), spec])?;
+//! ```
 
-use crate::{ImageData, IoError, IoResult};
+use crate::icc;
+use crate::{FormatWriter, ImageData, IoError, IoResult, LayeredImage};
 use psd::{ColorMode, Psd};
 use std::fs;
 use std::path::Path;
+use tracing::debug;
 
 /// PSD layer information.
 #[derive(Debug, Clone)]
@@ -118,7 +135,23 @@ pub fn read_from_memory(data: &[u8]) -> IoResult<ImageData> {
         pixels.push(chunk[3] as f32 / 255.0);
     }
 
-    Ok(ImageData::from_f32(width, height, 4, pixels))
+    let mut image = ImageData::from_f32(width, height, 4, pixels);
+    if let Some(profile) = image_resources(data).and_then(icc::psd_profile) {
+        icc::set_profile(&mut image.metadata.attrs, profile.to_vec());
+    }
+    Ok(image)
+}
+
+/// Returns the image resource section, which follows the 26-byte header and
+/// the colour mode data.
+fn image_resources(data: &[u8]) -> Option<&[u8]> {
+    let be_u32 = |pos: usize| -> Option<usize> {
+        let bytes = data.get(pos..pos + 4)?;
+        Some(u32::from_be_bytes(bytes.try_into().ok()?) as usize)
+    };
+    let start = 30 + be_u32(26)?;
+    let len = be_u32(start)?;
+    data.get(start + 4..start + 4 + len)
 }
 
 /// Reads PSD document info without loading pixel data.
@@ -297,6 +330,819 @@ where
     Ok(ImageData::from_f32(width, height, 4, pixels))
 }
 
+// ============================================================================
+// Writer
+// ============================================================================
+
+/// Largest canvas side a PSD can hold; bigger documents are written as PSB.
+const PSD_MAX_SIDE: u32 = 30_000;
+
+/// Photoshop layer blend mode.
+///
+/// The composite image is computed for the separable modes. Dissolve,
+/// Darker/Lighter Color and the Hue/Saturation/Color/Luminosity modes are
+/// stored in the layer but composited as Normal; Photoshop recomputes the
+/// composite on open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PsdBlendMode {
+    /// Normal (`norm`).
+    #[default]
+    Normal,
+    /// Dissolve (`diss`).
+    Dissolve,
+    /// Darken (`dark`).
+    Darken,
+    /// Multiply (`mul `).
+    Multiply,
+    /// Color Burn (`idiv`).
+    ColorBurn,
+    /// Linear Burn (`lbrn`).
+    LinearBurn,
+    /// Darker Color (`dkCl`).
+    DarkerColor,
+    /// Lighten (`lite`).
+    Lighten,
+    /// Screen (`scrn`).
+    Screen,
+    /// Color Dodge (`div `).
+    ColorDodge,
+    /// Linear Dodge / Add (`lddg`).
+    LinearDodge,
+    /// Lighter Color (`lgCl`).
+    LighterColor,
+    /// Overlay (`over`).
+    Overlay,
+    /// Soft Light (`sLit`).
+    SoftLight,
+    /// Hard Light (`hLit`).
+    HardLight,
+    /// Vivid Light (`vLit`).
+    VividLight,
+    /// Linear Light (`lLit`).
+    LinearLight,
+    /// Pin Light (`pLit`).
+    PinLight,
+    /// Hard Mix (`hMix`).
+    HardMix,
+    /// Difference (`diff`).
+    Difference,
+    /// Exclusion (`smud`).
+    Exclusion,
+    /// Subtract (`fsub`).
+    Subtract,
+    /// Divide (`fdiv`).
+    Divide,
+    /// Hue (`hue `).
+    Hue,
+    /// Saturation (`sat `).
+    Saturation,
+    /// Color (`colr`).
+    Color,
+    /// Luminosity (`lum `).
+    Luminosity,
+}
+
+impl PsdBlendMode {
+    const ALL: [(PsdBlendMode, &'static [u8; 4]); 27] = [
+        (Self::Normal, b"norm"),
+        (Self::Dissolve, b"diss"),
+        (Self::Darken, b"dark"),
+        (Self::Multiply, b"mul "),
+        (Self::ColorBurn, b"idiv"),
+        (Self::LinearBurn, b"lbrn"),
+        (Self::DarkerColor, b"dkCl"),
+        (Self::Lighten, b"lite"),
+        (Self::Screen, b"scrn"),
+        (Self::ColorDodge, b"div "),
+        (Self::LinearDodge, b"lddg"),
+        (Self::LighterColor, b"lgCl"),
+        (Self::Overlay, b"over"),
+        (Self::SoftLight, b"sLit"),
+        (Self::HardLight, b"hLit"),
+        (Self::VividLight, b"vLit"),
+        (Self::LinearLight, b"lLit"),
+        (Self::PinLight, b"pLit"),
+        (Self::HardMix, b"hMix"),
+        (Self::Difference, b"diff"),
+        (Self::Exclusion, b"smud"),
+        (Self::Subtract, b"fsub"),
+        (Self::Divide, b"fdiv"),
+        (Self::Hue, b"hue "),
+        (Self::Saturation, b"sat "),
+        (Self::Color, b"colr"),
+        (Self::Luminosity, b"lum "),
+    ];
+
+    /// Returns the four-character blend mode key stored in the file.
+    pub fn key(&self) -> &'static [u8; 4] {
+        Self::ALL
+            .iter()
+            .find(|(mode, _)| mode == self)
+            .map(|(_, key)| *key)
+            .unwrap_or(b"norm")
+    }
+
+    /// Parses a blend mode key such as `b"mul "`.
+    pub fn from_key(key: &[u8]) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|(_, k)| k.as_slice() == key)
+            .map(|(mode, _)| *mode)
+    }
+
+    /// Parses a blend mode name such as "multiply", "Linear Dodge" or
+    /// "add", ignoring case, spaces and underscores.
+    ///
+    /// Accepts the names reported in [`PsdLayer::blend_mode`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name: String = name
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        let mode = match name.as_str() {
+            "normal" => Self::Normal,
+            "dissolve" => Self::Dissolve,
+            "darken" => Self::Darken,
+            "multiply" => Self::Multiply,
+            "colorburn" => Self::ColorBurn,
+            "linearburn" => Self::LinearBurn,
+            "darkercolor" => Self::DarkerColor,
+            "lighten" => Self::Lighten,
+            "screen" => Self::Screen,
+            "colordodge" => Self::ColorDodge,
+            "lineardodge" | "add" | "plus" => Self::LinearDodge,
+            "lightercolor" => Self::LighterColor,
+            "overlay" => Self::Overlay,
+            "softlight" => Self::SoftLight,
+            "hardlight" => Self::HardLight,
+            "vividlight" => Self::VividLight,
+            "linearlight" => Self::LinearLight,
+            "pinlight" => Self::PinLight,
+            "hardmix" => Self::HardMix,
+            "difference" => Self::Difference,
+            "exclusion" => Self::Exclusion,
+            "subtract" => Self::Subtract,
+            "divide" => Self::Divide,
+            "hue" => Self::Hue,
+            "saturation" => Self::Saturation,
+            "color" => Self::Color,
+            "luminosity" => Self::Luminosity,
+            _ => return None,
+        };
+        Some(mode)
+    }
+
+    /// Blends one channel of source `s` onto backdrop `b`.
+    fn blend(&self, b: f32, s: f32) -> f32 {
+        let multiply = |b: f32, s: f32| b * s;
+        let screen = |b: f32, s: f32| b + s - b * s;
+        let dodge = |b: f32, s: f32| {
+            if b <= 0.0 {
+                0.0
+            } else if s >= 1.0 {
+                1.0
+            } else {
+                (b / (1.0 - s)).min(1.0)
+            }
+        };
+        let burn = |b: f32, s: f32| {
+            if b >= 1.0 {
+                1.0
+            } else if s <= 0.0 {
+                0.0
+            } else {
+                1.0 - ((1.0 - b) / s).min(1.0)
+            }
+        };
+        let hard_light = |b: f32, s: f32| {
+            if s <= 0.5 {
+                multiply(b, 2.0 * s)
+            } else {
+                screen(b, 2.0 * s - 1.0)
+            }
+        };
+
+        match self {
+            Self::Darken => b.min(s),
+            Self::Multiply => multiply(b, s),
+            Self::ColorBurn => burn(b, s),
+            Self::LinearBurn => (b + s - 1.0).max(0.0),
+            Self::Lighten => b.max(s),
+            Self::Screen => screen(b, s),
+            Self::ColorDodge => dodge(b, s),
+            Self::LinearDodge => b + s,
+            Self::Overlay => hard_light(s, b),
+            Self::HardLight => hard_light(b, s),
+            Self::SoftLight => {
+                if s <= 0.5 {
+                    b - (1.0 - 2.0 * s) * b * (1.0 - b)
+                } else {
+                    let d = if b <= 0.25 {
+                        ((16.0 * b - 12.0) * b + 4.0) * b
+                    } else {
+                        b.sqrt()
+                    };
+                    b + (2.0 * s - 1.0) * (d - b)
+                }
+            }
+            Self::VividLight => {
+                if s <= 0.5 {
+                    burn(b, 2.0 * s)
+                } else {
+                    dodge(b, 2.0 * s - 1.0)
+                }
+            }
+            Self::LinearLight => b + 2.0 * s - 1.0,
+            Self::PinLight => {
+                if s <= 0.5 {
+                    b.min(2.0 * s)
+                } else {
+                    b.max(2.0 * s - 1.0)
+                }
+            }
+            Self::HardMix => {
+                if b + s >= 1.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Self::Difference => (b - s).abs(),
+            Self::Exclusion => b + s - 2.0 * b * s,
+            Self::Subtract => (b - s).max(0.0),
+            Self::Divide => {
+                if s <= 0.0 {
+                    if b > 0.0 { 1.0 } else { 0.0 }
+                } else {
+                    b / s
+                }
+            }
+            _ => s,
+        }
+    }
+}
+
+/// A layer to write into a PSD/PSB document.
+///
+/// Layers are given bottom to top: the first one is the background.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::psd::{PsdBlendMode, PsdWriteLayer};
+///
+/// let mut spec = PsdWriteLayer::new("specular", spec_image);
+/// spec.blend_mode = PsdBlendMode::LinearDodge;
+/// spec.opacity = 0.5;
+/// ```
+#[derive(Debug, Clone)]
+pub struct PsdWriteLayer {
+    /// Layer name.
+    pub name: String,
+    /// Layer pixels: gray, gray-alpha, RGB or RGBA (extra channels are dropped).
+    pub image: ImageData,
+    /// Left offset in the document.
+    pub left: i32,
+    /// Top offset in the document.
+    pub top: i32,
+    /// Layer opacity (0.0 - 1.0).
+    pub opacity: f32,
+    /// Layer visibility.
+    pub visible: bool,
+    /// Blend mode.
+    pub blend_mode: PsdBlendMode,
+}
+
+impl PsdWriteLayer {
+    /// Creates a visible, fully opaque Normal layer at the origin.
+    pub fn new(name: impl Into<String>, image: ImageData) -> Self {
+        Self {
+            name: name.into(),
+            image,
+            left: 0,
+            top: 0,
+            opacity: 1.0,
+            visible: true,
+            blend_mode: PsdBlendMode::Normal,
+        }
+    }
+}
+
+/// Options for writing PSD/PSB files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::psd::{PsdWriter, PsdWriterOptions};
+///
+/// let writer = PsdWriter::with_options(PsdWriterOptions {
+///     bit_depth: 16,
+///     ..Default::default()
+/// });
+/// writer.write_layers("precomp.psd", &layers)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct PsdWriterOptions {
+    /// Bits per channel: 8, 16 or 32 (float). Default: 8.
+    pub bit_depth: u8,
+    /// Always write PSB. Documents wider or taller than 30000 pixels are
+    /// written as PSB regardless.
+    pub large_document: bool,
+    /// PackBits (RLE) compression for 8 and 16-bit data. 32-bit data is
+    /// always stored raw. Default: true.
+    pub compress: bool,
+    /// Canvas size. Default: the extent of all layers from the origin.
+    pub canvas: Option<(u32, u32)>,
+}
+
+impl Default for PsdWriterOptions {
+    fn default() -> Self {
+        Self {
+            bit_depth: 8,
+            large_document: false,
+            compress: true,
+            canvas: None,
+        }
+    }
+}
+
+/// Layered PSD/PSB writer.
+///
+/// Writes RGB documents with a transparency channel per layer and a
+/// composite of the visible layers. 16 and 32-bit layers are stored in the
+/// `Lr16` / `Lr32` blocks, as Photoshop does.
+#[derive(Debug, Clone, Default)]
+pub struct PsdWriter {
+    options: PsdWriterOptions,
+}
+
+/// A layer converted to RGBA float planes.
+struct Planes {
+    width: usize,
+    height: usize,
+    /// R, G, B, A planes, `width * height` each.
+    channels: [Vec<f32>; 4],
+}
+
+impl Planes {
+    fn from_image(image: &ImageData) -> Self {
+        let (width, height) = (image.width as usize, image.height as usize);
+        let nc = (image.channels as usize).max(1);
+        let data = image.to_f32();
+        let mut channels: [Vec<f32>; 4] = Default::default();
+        for plane in &mut channels {
+            plane.reserve(width * height);
+        }
+        for px in data.chunks_exact(nc) {
+            let (rgb, alpha) = match nc {
+                1 => ([px[0]; 3], 1.0),
+                2 => ([px[0]; 3], px[1]),
+                3 => ([px[0], px[1], px[2]], 1.0),
+                _ => ([px[0], px[1], px[2]], px[3]),
+            };
+            for (plane, v) in channels.iter_mut().zip(rgb.into_iter().chain([alpha])) {
+                plane.push(v);
+            }
+        }
+        Self {
+            width,
+            height,
+            channels,
+        }
+    }
+}
+
+impl PsdWriter {
+    /// Creates a new writer with default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a writer with custom options.
+    pub fn with_options(options: PsdWriterOptions) -> Self {
+        Self { options }
+    }
+
+    /// Writes layers (bottom to top) to a PSD or PSB file.
+    pub fn write_layers<P: AsRef<Path>>(&self, path: P, layers: &[PsdWriteLayer]) -> IoResult<()> {
+        let data = self.write_layers_to_memory(layers)?;
+        fs::write(path.as_ref(), data)?;
+        Ok(())
+    }
+
+    /// Writes a [`LayeredImage`], one Photoshop layer per image layer.
+    ///
+    /// Each layer is packed with its preferred channel order (RGBA first).
+    pub fn write_layered<P: AsRef<Path>>(&self, path: P, image: &LayeredImage) -> IoResult<()> {
+        let layers = image
+            .layers
+            .iter()
+            .map(|layer| {
+                Ok(PsdWriteLayer::new(
+                    layer.name.clone(),
+                    layer.to_image_data()?,
+                ))
+            })
+            .collect::<IoResult<Vec<_>>>()?;
+        self.write_layers(path, &layers)
+    }
+
+    /// Encodes layers (bottom to top) as a PSD or PSB document in memory.
+    pub fn write_layers_to_memory(&self, layers: &[PsdWriteLayer]) -> IoResult<Vec<u8>> {
+        let depth = self.options.bit_depth;
+        if !matches!(depth, 8 | 16 | 32) {
+            return Err(IoError::UnsupportedBitDepth(format!(
+                "PSD bit depth {} (use 8, 16 or 32)",
+                depth
+            )));
+        }
+        if layers.is_empty() {
+            return Err(IoError::MissingData("PSD needs at least one layer".into()));
+        }
+
+        let (width, height) = match self.options.canvas {
+            Some(size) => size,
+            None => layers.iter().fold((0, 0), |(w, h), l| {
+                (
+                    w.max((l.left + l.image.width as i32).max(0) as u32),
+                    h.max((l.top + l.image.height as i32).max(0) as u32),
+                )
+            }),
+        };
+        if width == 0 || height == 0 {
+            return Err(IoError::EncodeError("PSD canvas is empty".into()));
+        }
+        let psb = self.options.large_document || width > PSD_MAX_SIDE || height > PSD_MAX_SIDE;
+        if width > 300_000 || height > 300_000 {
+            return Err(IoError::EncodeError(format!(
+                "{}x{} exceeds the PSB limit of 300000 pixels",
+                width, height
+            )));
+        }
+        debug!(
+            "Writing {} {}x{} {}-bit, {} layers",
+            if psb { "PSB" } else { "PSD" },
+            width,
+            height,
+            depth,
+            layers.len()
+        );
+
+        let planes: Vec<Planes> = layers
+            .iter()
+            .map(|l| Planes::from_image(&l.image))
+            .collect();
+        let enc = Encoding {
+            depth,
+            psb,
+            compress: self.options.compress && depth != 32,
+        };
+
+        let mut out = Vec::new();
+
+        // File header
+        out.extend_from_slice(b"8BPS");
+        out.extend_from_slice(&(if psb { 2u16 } else { 1u16 }).to_be_bytes());
+        out.extend_from_slice(&[0; 6]);
+        out.extend_from_slice(&4u16.to_be_bytes());
+        out.extend_from_slice(&height.to_be_bytes());
+        out.extend_from_slice(&width.to_be_bytes());
+        out.extend_from_slice(&(depth as u16).to_be_bytes());
+        out.extend_from_slice(&3u16.to_be_bytes()); // RGB
+
+        // Color mode data: empty
+        out.extend_from_slice(&0u32.to_be_bytes());
+
+        // Image resources: the bottom layer's ICC profile, if any
+        let resources = icc::profile(&layers[0].image.metadata.attrs)
+            .map(icc::psd_resource)
+            .unwrap_or_default();
+        out.extend_from_slice(&(resources.len() as u32).to_be_bytes());
+        out.extend_from_slice(&resources);
+
+        // Layer and mask information
+        let layer_info = encode_layer_info(layers, &planes, &enc);
+        let mut section = Vec::new();
+        if depth == 8 {
+            let mut padded = layer_info;
+            padded.resize(padded.len().next_multiple_of(2), 0);
+            enc.put_length(&mut section, padded.len());
+            section.extend_from_slice(&padded);
+            section.extend_from_slice(&0u32.to_be_bytes()); // Global layer mask
+        } else {
+            // Photoshop keeps 16/32-bit layers in a tagged block and leaves
+            // the regular layer info empty
+            enc.put_length(&mut section, 0);
+            section.extend_from_slice(&0u32.to_be_bytes());
+            let mut padded = layer_info;
+            padded.resize(padded.len().next_multiple_of(4), 0);
+            section.extend_from_slice(b"8BIM");
+            section.extend_from_slice(if depth == 16 { b"Lr16" } else { b"Lr32" });
+            enc.put_length(&mut section, padded.len());
+            section.extend_from_slice(&padded);
+        }
+        enc.put_length(&mut out, section.len());
+        out.extend_from_slice(&section);
+
+        // Composite image data
+        let composite = composite(
+            layers,
+            &planes,
+            width as usize,
+            height as usize,
+            depth == 32,
+        );
+        out.extend_from_slice(&(if enc.compress { 1u16 } else { 0u16 }).to_be_bytes());
+        if enc.compress {
+            let mut counts = Vec::new();
+            let mut rows = Vec::new();
+            for plane in &composite {
+                for row in plane.chunks_exact(width as usize) {
+                    let start = rows.len();
+                    packbits(&enc.sample_bytes(row), &mut rows);
+                    enc.put_row_count(&mut counts, rows.len() - start);
+                }
+            }
+            out.extend_from_slice(&counts);
+            out.extend_from_slice(&rows);
+        } else {
+            for plane in &composite {
+                out.extend_from_slice(&enc.sample_bytes(plane));
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl FormatWriter<PsdWriterOptions> for PsdWriter {
+    /// Returns "PSD".
+    fn format_name(&self) -> &'static str {
+        "PSD"
+    }
+
+    /// Returns `["psd", "psb"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["psd", "psb"]
+    }
+
+    /// Writes a single-layer document named "Background".
+    fn write<P: AsRef<Path>>(&self, path: P, image: &ImageData) -> IoResult<()> {
+        self.write_layers(path, &[PsdWriteLayer::new("Background", image.clone())])
+    }
+
+    /// Encodes a single-layer document named "Background".
+    fn write_to_memory(&self, image: &ImageData) -> IoResult<Vec<u8>> {
+        self.write_layers_to_memory(&[PsdWriteLayer::new("Background", image.clone())])
+    }
+
+    /// Creates writer with custom options.
+    fn with_options(options: PsdWriterOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Sample encoding shared by layers and the composite.
+struct Encoding {
+    depth: u8,
+    psb: bool,
+    compress: bool,
+}
+
+impl Encoding {
+    /// Writes a section length: 4 bytes in PSD, 8 in PSB.
+    fn put_length(&self, out: &mut Vec<u8>, len: usize) {
+        if self.psb {
+            out.extend_from_slice(&(len as u64).to_be_bytes());
+        } else {
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+    }
+
+    /// Writes an RLE row byte count: 2 bytes in PSD, 4 in PSB.
+    fn put_row_count(&self, out: &mut Vec<u8>, len: usize) {
+        if self.psb {
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        } else {
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+    }
+
+    /// Converts samples to big-endian bytes of the document depth.
+    fn sample_bytes(&self, samples: &[f32]) -> Vec<u8> {
+        match self.depth {
+            8 => samples
+                .iter()
+                .map(|&v| (v.clamp(0.0, 1.0) * 255.0).round() as u8)
+                .collect(),
+            16 => samples
+                .iter()
+                .flat_map(|&v| ((v.clamp(0.0, 1.0) * 65535.0).round() as u16).to_be_bytes())
+                .collect(),
+            _ => samples.iter().flat_map(|&v| v.to_be_bytes()).collect(),
+        }
+    }
+
+    /// Encodes one layer channel, including its compression field.
+    fn channel(&self, plane: &[f32], width: usize) -> Vec<u8> {
+        let mut out = Vec::new();
+        if !self.compress || plane.is_empty() {
+            out.extend_from_slice(&0u16.to_be_bytes());
+            out.extend_from_slice(&self.sample_bytes(plane));
+            return out;
+        }
+        out.extend_from_slice(&1u16.to_be_bytes());
+        let mut counts = Vec::new();
+        let mut rows = Vec::new();
+        for row in plane.chunks_exact(width) {
+            let start = rows.len();
+            packbits(&self.sample_bytes(row), &mut rows);
+            self.put_row_count(&mut counts, rows.len() - start);
+        }
+        out.extend_from_slice(&counts);
+        out.extend_from_slice(&rows);
+        out
+    }
+}
+
+/// Encodes the layer info structure: count, records and channel data.
+fn encode_layer_info(layers: &[PsdWriteLayer], planes: &[Planes], enc: &Encoding) -> Vec<u8> {
+    // Negative count: the composite's alpha channel is its transparency
+    let mut out = (-(layers.len() as i16)).to_be_bytes().to_vec();
+    let mut channel_data = Vec::new();
+
+    for (layer, planes) in layers.iter().zip(planes) {
+        let channels: Vec<Vec<u8>> = planes
+            .channels
+            .iter()
+            .map(|p| enc.channel(p, planes.width))
+            .collect();
+
+        // Bounds: top, left, bottom, right
+        for v in [
+            layer.top,
+            layer.left,
+            layer.top + planes.height as i32,
+            layer.left + planes.width as i32,
+        ] {
+            out.extend_from_slice(&v.to_be_bytes());
+        }
+        out.extend_from_slice(&4u16.to_be_bytes());
+        for (id, data) in [0i16, 1, 2, -1].iter().zip(&channels) {
+            out.extend_from_slice(&id.to_be_bytes());
+            enc.put_length(&mut out, data.len());
+        }
+
+        out.extend_from_slice(b"8BIM");
+        out.extend_from_slice(layer.blend_mode.key());
+        out.push((layer.opacity.clamp(0.0, 1.0) * 255.0).round() as u8);
+        out.push(0); // Clipping: base
+        out.push(if layer.visible { 0 } else { 0x02 }); // Bit 1 hides the layer
+        out.push(0);
+
+        let mut extra = Vec::new();
+        extra.extend_from_slice(&0u32.to_be_bytes()); // Layer mask
+        extra.extend_from_slice(&0u32.to_be_bytes()); // Blending ranges
+
+        // Pascal name (Mac Roman, ASCII subset), padded to 4 bytes
+        let ascii: Vec<u8> = layer
+            .name
+            .chars()
+            .map(|c| if c.is_ascii() { c as u8 } else { b'?' })
+            .take(255)
+            .collect();
+        let start = extra.len();
+        extra.push(ascii.len() as u8);
+        extra.extend_from_slice(&ascii);
+        extra.resize(start + (ascii.len() + 1).next_multiple_of(4), 0);
+
+        // Unicode name
+        let utf16: Vec<u16> = layer.name.encode_utf16().collect();
+        let mut luni = (utf16.len() as u32).to_be_bytes().to_vec();
+        for c in &utf16 {
+            luni.extend_from_slice(&c.to_be_bytes());
+        }
+        luni.resize(luni.len().next_multiple_of(4), 0);
+        extra.extend_from_slice(b"8BIMluni");
+        extra.extend_from_slice(&(luni.len() as u32).to_be_bytes());
+        extra.extend_from_slice(&luni);
+
+        out.extend_from_slice(&(extra.len() as u32).to_be_bytes());
+        out.extend_from_slice(&extra);
+
+        for data in channels {
+            channel_data.extend_from_slice(&data);
+        }
+    }
+
+    out.extend_from_slice(&channel_data);
+    out
+}
+
+/// Composites the visible layers into RGBA planes of the canvas.
+fn composite(
+    layers: &[PsdWriteLayer],
+    planes: &[Planes],
+    width: usize,
+    height: usize,
+    float: bool,
+) -> [Vec<f32>; 4] {
+    let mut out: [Vec<f32>; 4] = std::array::from_fn(|_| vec![0.0; width * height]);
+
+    for (layer, src) in layers.iter().zip(planes) {
+        if !layer.visible || layer.opacity <= 0.0 {
+            continue;
+        }
+        for sy in 0..src.height {
+            let y = layer.top + sy as i32;
+            if y < 0 || y >= height as i32 {
+                continue;
+            }
+            for sx in 0..src.width {
+                let x = layer.left + sx as i32;
+                if x < 0 || x >= width as i32 {
+                    continue;
+                }
+                let si = sy * src.width + sx;
+                let di = y as usize * width + x as usize;
+
+                let alpha_s = src.channels[3][si].clamp(0.0, 1.0) * layer.opacity.clamp(0.0, 1.0);
+                let alpha_b = out[3][di];
+                let alpha_o = alpha_s + alpha_b * (1.0 - alpha_s);
+                for (dst, plane) in out.iter_mut().zip(&src.channels).take(3) {
+                    let (b, s) = (dst[di], plane[si]);
+                    let mixed = (1.0 - alpha_b) * s + alpha_b * layer.blend_mode.blend(b, s);
+                    let mut v = if alpha_o > 0.0 {
+                        (alpha_s * mixed + (1.0 - alpha_s) * alpha_b * b) / alpha_o
+                    } else {
+                        0.0
+                    };
+                    if !float {
+                        v = v.clamp(0.0, 1.0);
+                    }
+                    dst[di] = v;
+                }
+                out[3][di] = alpha_o;
+            }
+        }
+    }
+    out
+}
+
+/// PackBits-compresses one row.
+fn packbits(data: &[u8], out: &mut Vec<u8>) {
+    let mut i = 0;
+    while i < data.len() {
+        // Length of the run starting at i
+        let mut run = 1;
+        while i + run < data.len() && run < 128 && data[i + run] == data[i] {
+            run += 1;
+        }
+        if run >= 3 {
+            out.push((1 - run as i32) as u8);
+            out.push(data[i]);
+            i += run;
+            continue;
+        }
+
+        // Literal bytes up to the next run of three
+        let start = i;
+        while i < data.len() && i - start < 128 {
+            if i + 2 < data.len() && data[i] == data[i + 1] && data[i] == data[i + 2] {
+                break;
+            }
+            i += 1;
+        }
+        out.push((i - start - 1) as u8);
+        out.extend_from_slice(&data[start..i]);
+    }
+}
+
+/// Writes layers (bottom to top) to a PSD file with default options.
+///
+/// Convenience wrapper around [`PsdWriter`]. Use
+/// [`PsdWriter::with_options`] for 16/32-bit or PSB output.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::psd::{self, PsdWriteLayer};
+///
+/// let layers = vec![
+///     PsdWriteLayer::new("diffuse", diffuse),
+///     PsdWriteLayer::new("specular", specular),
+/// ];
+/// psd::write_layers("aovs.psd", &layers)?;
+/// ```
+pub fn write_layers<P: AsRef<Path>>(path: P, layers: &[PsdWriteLayer]) -> IoResult<()> {
+    PsdWriter::new().write_layers(path, layers)
+}
+
+/// Writes a [`LayeredImage`] (e.g. a multi-part EXR) as a layered PSD file.
+pub fn write_layered<P: AsRef<Path>>(path: P, image: &LayeredImage) -> IoResult<()> {
+    PsdWriter::new().write_layered(path, image)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -330,4 +1176,422 @@ mod tests {
         assert_eq!(layer.name, "Background");
         assert!(layer.visible);
     }
+
+    /// Layer record decoded by [`parse`].
+    struct ParsedLayer {
+        name: String,
+        rect: [i32; 4],
+        key: [u8; 4],
+        opacity: u8,
+        flags: u8,
+        /// R, G, B, A planes as normalized floats.
+        planes: Vec<Vec<f32>>,
+    }
+
+    /// Document decoded by [`parse`].
+    struct Parsed {
+        version: u16,
+        width: usize,
+        height: usize,
+        depth: u16,
+        layers: Vec<ParsedLayer>,
+        composite: Vec<Vec<f32>>,
+    }
+
+    struct Cursor<'a> {
+        data: &'a [u8],
+        pos: usize,
+    }
+
+    impl<'a> Cursor<'a> {
+        fn take(&mut self, n: usize) -> &'a [u8] {
+            let s = &self.data[self.pos..self.pos + n];
+            self.pos += n;
+            s
+        }
+        fn u8(&mut self) -> u8 {
+            self.take(1)[0]
+        }
+        fn u16(&mut self) -> u16 {
+            u16::from_be_bytes(self.take(2).try_into().unwrap())
+        }
+        fn u32(&mut self) -> u32 {
+            u32::from_be_bytes(self.take(4).try_into().unwrap())
+        }
+        fn len(&mut self, psb: bool) -> usize {
+            if psb {
+                u64::from_be_bytes(self.take(8).try_into().unwrap()) as usize
+            } else {
+                self.u32() as usize
+            }
+        }
+    }
+
+    fn unpackbits(mut data: &[u8], expected: usize) -> Vec<u8> {
+        let mut out = Vec::new();
+        while out.len() < expected {
+            let n = data[0] as i8;
+            data = &data[1..];
+            if n >= 0 {
+                let len = n as usize + 1;
+                out.extend_from_slice(&data[..len]);
+                data = &data[len..];
+            } else if n != -128 {
+                out.extend(std::iter::repeat_n(data[0], (1 - n as isize) as usize));
+                data = &data[1..];
+            }
+        }
+        out
+    }
+
+    fn samples(bytes: &[u8], depth: u16) -> Vec<f32> {
+        match depth {
+            8 => bytes.iter().map(|&v| v as f32 / 255.0).collect(),
+            16 => bytes
+                .chunks_exact(2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]) as f32 / 65535.0)
+                .collect(),
+            _ => bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_be_bytes(b.try_into().unwrap()))
+                .collect(),
+        }
+    }
+
+    /// Reads `planes` planes of `w`x`h` samples; RLE counts precede all data.
+    fn read_planes(
+        c: &mut Cursor,
+        planes: usize,
+        w: usize,
+        h: usize,
+        depth: u16,
+        psb: bool,
+    ) -> Vec<Vec<f32>> {
+        let row_bytes = w * depth as usize / 8;
+        match c.u16() {
+            0 => (0..planes)
+                .map(|_| samples(c.take(row_bytes * h), depth))
+                .collect(),
+            1 => {
+                let counts: Vec<usize> = (0..planes * h)
+                    .map(|_| {
+                        if psb {
+                            c.u32() as usize
+                        } else {
+                            c.u16() as usize
+                        }
+                    })
+                    .collect();
+                let mut rows = counts.iter().map(|&n| unpackbits(c.take(n), row_bytes));
+                (0..planes)
+                    .map(|_| {
+                        samples(
+                            &(0..h)
+                                .flat_map(|_| rows.next().unwrap())
+                                .collect::<Vec<_>>(),
+                            depth,
+                        )
+                    })
+                    .collect()
+            }
+            other => panic!("unexpected compression {}", other),
+        }
+    }
+
+    fn parse_layer_info(c: &mut Cursor, depth: u16, psb: bool) -> Vec<ParsedLayer> {
+        let count = (c.u16() as i16).unsigned_abs() as usize;
+        let mut layers = Vec::new();
+        for _ in 0..count {
+            let rect = [
+                c.u32() as i32,
+                c.u32() as i32,
+                c.u32() as i32,
+                c.u32() as i32,
+            ];
+            assert_eq!(c.u16(), 4);
+            for id in [0i16, 1, 2, -1] {
+                assert_eq!(c.u16() as i16, id);
+                c.len(psb);
+            }
+            assert_eq!(c.take(4), b"8BIM");
+            let key: [u8; 4] = c.take(4).try_into().unwrap();
+            let opacity = c.u8();
+            c.u8();
+            let flags = c.u8();
+            c.u8();
+            let extra_len = c.u32() as usize;
+            let mut extra = Cursor {
+                data: c.take(extra_len),
+                pos: 0,
+            };
+            assert_eq!(extra.u32(), 0);
+            assert_eq!(extra.u32(), 0);
+            let pascal = extra.u8() as usize;
+            extra.take(pascal);
+            extra.take((pascal + 1).next_multiple_of(4) - pascal - 1);
+            assert_eq!(extra.take(8), b"8BIMluni");
+            extra.u32();
+            let chars = extra.u32() as usize;
+            let utf16: Vec<u16> = (0..chars).map(|_| extra.u16()).collect();
+            layers.push(ParsedLayer {
+                name: String::from_utf16(&utf16).unwrap(),
+                rect,
+                key,
+                opacity,
+                flags,
+                planes: Vec::new(),
+            });
+        }
+        for layer in &mut layers {
+            let w = (layer.rect[3] - layer.rect[1]) as usize;
+            let h = (layer.rect[2] - layer.rect[0]) as usize;
+            layer.planes = (0..4)
+                .flat_map(|_| read_planes(c, 1, w, h, depth, psb))
+                .collect();
+        }
+        layers
+    }
+
+    fn parse(data: &[u8]) -> Parsed {
+        let mut c = Cursor { data, pos: 0 };
+        assert_eq!(c.take(4), b"8BPS");
+        let version = c.u16();
+        let psb = version == 2;
+        c.take(6);
+        assert_eq!(c.u16(), 4);
+        let height = c.u32() as usize;
+        let width = c.u32() as usize;
+        let depth = c.u16();
+        assert_eq!(c.u16(), 3);
+        assert_eq!(c.u32(), 0);
+        assert_eq!(c.u32(), 0);
+
+        let section_len = c.len(psb);
+        let section_end = c.pos + section_len;
+        let info_len = c.len(psb);
+        let layers = if info_len > 0 {
+            let start = c.pos;
+            let layers = parse_layer_info(&mut c, depth, psb);
+            c.pos = start + info_len;
+            assert_eq!(c.u32(), 0);
+            layers
+        } else {
+            assert_eq!(c.u32(), 0);
+            assert_eq!(c.take(4), b"8BIM");
+            let key = c.take(4);
+            assert_eq!(key, if depth == 16 { b"Lr16" } else { b"Lr32" });
+            c.len(psb);
+            parse_layer_info(&mut c, depth, psb)
+        };
+        c.pos = section_end;
+
+        let composite = read_planes(&mut c, 4, width, height, depth, psb);
+        assert_eq!(c.pos, data.len());
+        Parsed {
+            version,
+            width,
+            height,
+            depth,
+            layers,
+            composite,
+        }
+    }
+
+    fn solid(w: u32, h: u32, rgba: [f32; 4]) -> ImageData {
+        let data = (0..w * h).flat_map(|_| rgba).collect();
+        ImageData::from_f32(w, h, 4, data)
+    }
+
+    fn two_layers() -> Vec<PsdWriteLayer> {
+        let bottom = PsdWriteLayer::new("Background", solid(4, 3, [0.5, 0.25, 1.0, 1.0]));
+        let mut top = PsdWriteLayer::new("Spec \u{00e9}", solid(2, 2, [0.5, 0.5, 0.5, 1.0]));
+        top.left = 1;
+        top.top = 1;
+        top.opacity = 0.5;
+        top.blend_mode = PsdBlendMode::Multiply;
+        vec![bottom, top]
+    }
+
+    #[test]
+    fn test_blend_mode_keys() {
+        for (mode, key) in PsdBlendMode::ALL {
+            assert_eq!(mode.key(), key);
+            assert_eq!(PsdBlendMode::from_key(key), Some(mode));
+        }
+        assert_eq!(
+            PsdBlendMode::from_name("LinearDodge"),
+            Some(PsdBlendMode::LinearDodge)
+        );
+        assert_eq!(
+            PsdBlendMode::from_name("soft_light"),
+            Some(PsdBlendMode::SoftLight)
+        );
+        assert_eq!(
+            PsdBlendMode::from_name("Color Burn"),
+            Some(PsdBlendMode::ColorBurn)
+        );
+        assert_eq!(PsdBlendMode::from_name("bogus"), None);
+    }
+
+    #[test]
+    fn test_packbits_roundtrip() {
+        let mut row = vec![7u8; 300];
+        row.extend(0..=255u8);
+        row.extend([1, 1, 2, 2, 2, 3]);
+        let mut packed = Vec::new();
+        packbits(&row, &mut packed);
+        assert!(packed.len() < row.len());
+        assert_eq!(unpackbits(&packed, row.len()), row);
+    }
+
+    #[test]
+    fn test_write_8bit_layers() {
+        let data = PsdWriter::new()
+            .write_layers_to_memory(&two_layers())
+            .unwrap();
+        let psd = parse(&data);
+        assert_eq!(
+            (psd.version, psd.width, psd.height, psd.depth),
+            (1, 4, 3, 8)
+        );
+        assert_eq!(psd.layers.len(), 2);
+
+        let top = &psd.layers[1];
+        assert_eq!(top.name, "Spec \u{00e9}");
+        assert_eq!(top.rect, [1, 1, 3, 3]);
+        assert_eq!(&top.key, b"mul ");
+        assert_eq!(top.opacity, 128);
+        assert_eq!(top.flags & 0x02, 0);
+        assert_eq!(psd.layers[0].planes[2][0], 1.0);
+
+        // Outside the top layer: background as-is
+        assert!((psd.composite[0][0] - 0.5).abs() < 0.01);
+        // Multiply at 50%: 0.5 * (1 - 0.5) + 0.5 * 0.5 * 0.5 = 0.375
+        let i = 4 + 1;
+        assert!((psd.composite[0][i] - 0.375).abs() < 0.01);
+        assert!((psd.composite[2][i] - 0.75).abs() < 0.01);
+        assert_eq!(psd.composite[3][i], 1.0);
+    }
+
+    #[test]
+    fn test_write_hidden_layer_skips_composite() {
+        let mut layers = two_layers();
+        layers[1].visible = false;
+        layers[1].blend_mode = PsdBlendMode::Normal;
+        let psd = parse(&PsdWriter::new().write_layers_to_memory(&layers).unwrap());
+        assert_eq!(psd.layers[1].flags & 0x02, 0x02);
+        assert!((psd.composite[0][5] - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_write_16_and_32bit() {
+        for depth in [16u8, 32] {
+            let writer = PsdWriter::with_options(PsdWriterOptions {
+                bit_depth: depth,
+                ..Default::default()
+            });
+            let psd = parse(&writer.write_layers_to_memory(&two_layers()).unwrap());
+            assert_eq!(psd.depth, depth as u16);
+            assert_eq!(psd.layers.len(), 2);
+            assert_eq!(psd.layers[1].name, "Spec \u{00e9}");
+            assert!((psd.layers[0].planes[1][0] - 0.25).abs() < 1e-4);
+            assert!((psd.composite[0][5] - 0.375).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_write_32bit_keeps_hdr_values() {
+        let writer = PsdWriter::with_options(PsdWriterOptions {
+            bit_depth: 32,
+            ..Default::default()
+        });
+        let layers = [PsdWriteLayer::new("hdr", solid(2, 2, [4.0, 0.5, 0.0, 1.0]))];
+        let psd = parse(&writer.write_layers_to_memory(&layers).unwrap());
+        assert_eq!(psd.layers[0].planes[0][0], 4.0);
+        assert_eq!(psd.composite[0][3], 4.0);
+    }
+
+    #[test]
+    fn test_write_psb() {
+        let writer = PsdWriter::with_options(PsdWriterOptions {
+            large_document: true,
+            bit_depth: 16,
+            ..Default::default()
+        });
+        let psd = parse(&writer.write_layers_to_memory(&two_layers()).unwrap());
+        assert_eq!(psd.version, 2);
+        assert_eq!(psd.layers[1].rect, [1, 1, 3, 3]);
+        assert!((psd.composite[2][5] - 0.75).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_write_gray_and_canvas() {
+        let gray = ImageData::from_u8(2, 2, 1, vec![0, 64, 128, 255]);
+        let writer = PsdWriter::with_options(PsdWriterOptions {
+            canvas: Some((3, 3)),
+            compress: false,
+            ..Default::default()
+        });
+        let psd = parse(&writer.write_to_memory(&gray).unwrap());
+        assert_eq!((psd.width, psd.height), (3, 3));
+        assert_eq!(psd.layers[0].name, "Background");
+        assert_eq!(psd.layers[0].planes[1], psd.layers[0].planes[0]);
+        assert_eq!(psd.layers[0].planes[3], vec![1.0; 4]);
+        // Uncovered canvas stays transparent
+        assert_eq!(psd.composite[3][2], 0.0);
+    }
+
+    #[test]
+    fn test_write_layered_image() {
+        let layered = crate::LayeredImage {
+            layers: vec![
+                ImageData::from_f32(2, 1, 3, vec![1.0, 0.0, 0.0, 0.0, 1.0, 0.0])
+                    .to_layer("diffuse"),
+                ImageData::from_f32(2, 1, 3, vec![0.0; 6]).to_layer("specular"),
+            ],
+            metadata: Default::default(),
+        };
+        let dir = std::env::temp_dir().join("vfx_io_psd_layered.psd");
+        write_layered(&dir, &layered).unwrap();
+        let psd = parse(&fs::read(&dir).unwrap());
+        let _ = fs::remove_file(&dir);
+        let names: Vec<_> = psd.layers.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["diffuse", "specular"]);
+    }
+
+    #[test]
+    fn test_write_rejects_bad_input() {
+        let writer = PsdWriter::with_options(PsdWriterOptions {
+            bit_depth: 12,
+            ..Default::default()
+        });
+        assert!(writer.write_layers_to_memory(&two_layers()).is_err());
+        assert!(PsdWriter::new().write_layers_to_memory(&[]).is_err());
+    }
+
+    #[test]
+    fn test_write_read_back_with_psd_crate() {
+        let data = PsdWriter::new()
+            .write_layers_to_memory(&two_layers())
+            .unwrap();
+        let doc = Psd::from_bytes(&data).unwrap();
+        assert_eq!((doc.width(), doc.height()), (4, 3));
+        let names: Vec<_> = doc.layers().iter().map(|l| l.name().to_string()).collect();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[0], "Background");
+
+        let image = read_from_memory(&data).unwrap();
+        assert_eq!((image.width, image.height), (4, 3));
+    }
+
+    #[test]
+    fn test_icc_roundtrip() {
+        let mut layers = two_layers();
+        let profile = vec![9u8; 301];
+        icc::set_profile(&mut layers[0].image.metadata.attrs, profile.clone());
+
+        let data = PsdWriter::new().write_layers_to_memory(&layers).unwrap();
+        let image = read_from_memory(&data).unwrap();
+        assert_eq!(icc::profile(&image.metadata.attrs), Some(&profile[..]));
+    }
 }
@@ -0,0 +1,1325 @@
+//! Ptex per-face texture reader.
+//!
+//! Ptex stores a separate texture for every face of a mesh, so assets can be
+//! painted without UV layouts. Each face has its own power-of-two resolution,
+//! a chain of reductions (per-face MIP levels) and adjacency data that lets
+//! filters cross face boundaries seamlessly.
+//!
+//! # Features
+//!
+//! - Quad and triangle meshes
+//! - 8-bit, 16-bit, half and float data, any channel count
+//! - Constant, zipped, difference-zipped and tiled face data
+//! - Reductions, decoded lazily per face and cached
+//! - Face adjacency and edge crossing
+//! - Metadata, including large metadata blocks, as [`Attrs`]
+//!
+//! Edit blocks (`flag_hasedits`) are not applied.
+//!
+//! Filtered lookups by face id and (u, v) live in
+//! [`TextureSystem::sample_ptex`](crate::texture::TextureSystem::sample_ptex).
+//!
+//! # Example
+//!
+//! ```ignore
+//! use vfx_io::ptex::PtexTexture;
+//!
+//! let ptx = PtexTexture::open("asset.ptx")?;
+//! println!("{} faces, {} channels", ptx.num_faces(), ptx.channels());
+//!
+//! let face = ptx.face_data(12, 0)?;
+//! let texel = face.pixel(3, 5);
+//! ```
+
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+
+use crate::{AttrValue, Attrs, IoError, IoResult};
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Ptex file magic ("Ptex").
+pub const PTEX_MAGIC: &[u8; 4] = b"Ptex";
+
+/// Supported major version.
+const PTEX_VERSION: u32 = 1;
+
+/// Size of the fixed main header.
+const HEADER_SIZE: usize = 64;
+
+/// Size of a face info record.
+const FACE_INFO_SIZE: usize = 20;
+
+/// Size of a level info record.
+const LEVEL_INFO_SIZE: usize = 16;
+
+/// Face info flags.
+const FLAG_CONSTANT: u8 = 1;
+const FLAG_HAS_EDITS: u8 = 2;
+const FLAG_NB_CONSTANT: u8 = 4;
+const FLAG_SUBFACE: u8 = 8;
+
+/// Face data encodings (top two bits of a face data header).
+const ENC_CONSTANT: u32 = 0;
+const ENC_TILED: u32 = 1;
+const ENC_ZIPPED: u32 = 2;
+const ENC_DIFF_ZIPPED: u32 = 3;
+
+// ============================================================================
+// Types
+// ============================================================================
+
+/// Mesh type the texture was painted on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeshType {
+    /// Triangle mesh; faces are addressed with barycentric (u, v).
+    Triangle,
+    /// Quad mesh.
+    Quad,
+}
+
+/// Channel data type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtexDataType {
+    /// 8-bit unsigned, normalized to [0, 1].
+    U8,
+    /// 16-bit unsigned, normalized to [0, 1].
+    U16,
+    /// 16-bit float.
+    Half,
+    /// 32-bit float.
+    F32,
+}
+
+impl PtexDataType {
+    fn from_code(code: u32) -> IoResult<Self> {
+        match code {
+            0 => Ok(Self::U8),
+            1 => Ok(Self::U16),
+            2 => Ok(Self::Half),
+            3 => Ok(Self::F32),
+            _ => Err(IoError::InvalidFile(format!("Ptex: unknown data type {}", code))),
+        }
+    }
+
+    /// Bytes per channel value.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::U8 => 1,
+            Self::U16 | Self::Half => 2,
+            Self::F32 => 4,
+        }
+    }
+}
+
+/// Behavior at face edges without a neighbor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BorderMode {
+    /// Clamp to the edge texel.
+    #[default]
+    Clamp,
+    /// Black outside the face.
+    Black,
+    /// Wrap around to the opposite edge of the same face.
+    Periodic,
+}
+
+impl BorderMode {
+    fn from_code(code: u32) -> Self {
+        match code {
+            1 => Self::Black,
+            2 => Self::Periodic,
+            _ => Self::Clamp,
+        }
+    }
+}
+
+/// Face edge, counter-clockwise from the bottom (v = 0) edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Edge {
+    /// v = 0.
+    Bottom = 0,
+    /// u = 1.
+    Right = 1,
+    /// v = 1.
+    Top = 2,
+    /// u = 0.
+    Left = 3,
+}
+
+impl Edge {
+    /// All edges in id order.
+    pub const ALL: [Edge; 4] = [Edge::Bottom, Edge::Right, Edge::Top, Edge::Left];
+
+    /// Edge from its id (0-3, higher bits ignored).
+    pub fn from_index(index: usize) -> Self {
+        Self::ALL[index & 3]
+    }
+
+    /// Position along the edge (counter-clockwise) and distance outside it.
+    fn edge_coords(self, u: f32, v: f32) -> (f32, f32) {
+        match self {
+            Edge::Bottom => (u, -v),
+            Edge::Right => (v, u - 1.0),
+            Edge::Top => (1.0 - u, v - 1.0),
+            Edge::Left => (1.0 - v, -u),
+        }
+    }
+
+    /// Inverse of [`edge_coords`](Self::edge_coords) for a point `depth` inside the face.
+    fn face_coords(self, t: f32, depth: f32) -> (f32, f32) {
+        match self {
+            Edge::Bottom => (t, depth),
+            Edge::Right => (1.0 - depth, t),
+            Edge::Top => (1.0 - t, 1.0 - depth),
+            Edge::Left => (depth, 1.0 - t),
+        }
+    }
+}
+
+/// Face resolution as log2 of the texel counts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FaceRes {
+    /// log2 of the width.
+    pub ulog2: u8,
+    /// log2 of the height.
+    pub vlog2: u8,
+}
+
+impl FaceRes {
+    /// Creates a resolution from log2 sizes.
+    pub fn new(ulog2: u8, vlog2: u8) -> Self {
+        Self { ulog2, vlog2 }
+    }
+
+    /// Width in texels.
+    pub fn u(&self) -> usize {
+        1 << self.ulog2
+    }
+
+    /// Height in texels.
+    pub fn v(&self) -> usize {
+        1 << self.vlog2
+    }
+
+    /// Number of texels.
+    pub fn size(&self) -> usize {
+        self.u() * self.v()
+    }
+
+    /// Resolution after `level` halvings in both directions.
+    pub fn reduced(&self, level: u32) -> Self {
+        let level = level.min(u8::MAX as u32) as u8;
+        Self {
+            ulog2: self.ulog2.saturating_sub(level),
+            vlog2: self.vlog2.saturating_sub(level),
+        }
+    }
+}
+
+/// Per-face resolution, adjacency and flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaceInfo {
+    /// Full resolution of the face.
+    pub res: FaceRes,
+    /// Adjacent face ids per edge (-1 for none).
+    pub adj_faces: [i32; 4],
+    /// Packed adjacent edge ids, two bits per edge.
+    pub adj_edges: u8,
+    /// Face flags.
+    pub flags: u8,
+}
+
+impl FaceInfo {
+    /// Neighbor across `edge`, if any.
+    pub fn adj_face(&self, edge: Edge) -> Option<u32> {
+        let id = self.adj_faces[edge as usize];
+        (id >= 0).then_some(id as u32)
+    }
+
+    /// Edge of the neighbor that is shared with `edge`.
+    pub fn adj_edge(&self, edge: Edge) -> Edge {
+        Edge::from_index((self.adj_edges >> (2 * edge as u8)) as usize)
+    }
+
+    /// True if the whole face is a single value.
+    pub fn is_constant(&self) -> bool {
+        self.flags & FLAG_CONSTANT != 0
+    }
+
+    /// True if the face and all its neighbors are constant.
+    pub fn is_neighborhood_constant(&self) -> bool {
+        self.flags & FLAG_NB_CONSTANT != 0
+    }
+
+    /// True if the face carries edits.
+    pub fn has_edits(&self) -> bool {
+        self.flags & FLAG_HAS_EDITS != 0
+    }
+
+    /// True if the face is a subface of a split non-quad face.
+    pub fn is_subface(&self) -> bool {
+        self.flags & FLAG_SUBFACE != 0
+    }
+}
+
+/// Decoded texels of one face at one reduction level.
+#[derive(Debug, Clone)]
+pub struct PtexFace {
+    /// Resolution of this level.
+    pub res: FaceRes,
+    /// Number of channels.
+    pub channels: u32,
+    /// Interleaved texels, rows of increasing v. A single texel for constant faces.
+    pub data: Vec<f32>,
+}
+
+impl PtexFace {
+    /// True if the face holds a single value.
+    pub fn is_constant(&self) -> bool {
+        self.data.len() == self.channels as usize
+    }
+
+    /// Texel at (ui, vi), clamped to the face.
+    pub fn pixel(&self, ui: usize, vi: usize) -> &[f32] {
+        let ch = self.channels as usize;
+        if self.is_constant() {
+            return &self.data[..ch];
+        }
+        let ui = ui.min(self.res.u() - 1);
+        let vi = vi.min(self.res.v() - 1);
+        let idx = (vi * self.res.u() + ui) * ch;
+        &self.data[idx..idx + ch]
+    }
+}
+
+/// One reduction level in the file.
+#[derive(Debug)]
+struct Level {
+    /// Face data headers (size and encoding) in level order.
+    headers: Vec<u32>,
+    /// Absolute offset of each face's data block.
+    offsets: Vec<usize>,
+}
+
+// ============================================================================
+// Reader
+// ============================================================================
+
+/// An open Ptex file.
+///
+/// Face data is decoded on first access and cached, so the texture can be
+/// shared between threads behind an `Arc`.
+#[derive(Debug)]
+pub struct PtexTexture {
+    data: Vec<u8>,
+    mesh_type: MeshType,
+    data_type: PtexDataType,
+    channels: u32,
+    alpha_channel: Option<u32>,
+    border_modes: [BorderMode; 2],
+    faces: Vec<FaceInfo>,
+    const_data: Vec<f32>,
+    levels: Vec<Level>,
+    /// Position of each face in the reduction levels.
+    rface_ids: Vec<u32>,
+    metadata: Attrs,
+    cache: Vec<Vec<OnceLock<Arc<PtexFace>>>>,
+}
+
+impl PtexTexture {
+    /// Opens a Ptex file.
+    pub fn open<P: AsRef<Path>>(path: P) -> IoResult<Self> {
+        Self::from_bytes(std::fs::read(path.as_ref())?)
+    }
+
+    /// Parses a Ptex file held in memory.
+    pub fn from_bytes(data: Vec<u8>) -> IoResult<Self> {
+        if !is_ptex(&data) || data.len() < HEADER_SIZE {
+            return Err(IoError::InvalidFile("Ptex: bad magic".into()));
+        }
+        let version = le_u32(&data, 4);
+        if version != PTEX_VERSION {
+            return Err(IoError::UnsupportedFormat(format!(
+                "Ptex version {}", version
+            )));
+        }
+        let mesh_type = if le_u32(&data, 8) == 0 { MeshType::Triangle } else { MeshType::Quad };
+        let data_type = PtexDataType::from_code(le_u32(&data, 12))?;
+        let alpha = le_u32(&data, 16) as i32;
+        let channels = le_u16(&data, 20) as u32;
+        let nlevels = le_u16(&data, 22) as usize;
+        let nfaces = le_u32(&data, 24) as usize;
+        let ext_header_size = le_u32(&data, 28) as usize;
+        let face_info_size = le_u32(&data, 32) as usize;
+        let const_data_size = le_u32(&data, 36) as usize;
+        let level_info_size = le_u32(&data, 40) as usize;
+        let level_data_size = le_u64(&data, 48) as usize;
+        let meta_zip_size = le_u32(&data, 56) as usize;
+        let meta_mem_size = le_u32(&data, 60) as usize;
+
+        if channels == 0 {
+            return Err(IoError::InvalidFile("Ptex: no channels".into()));
+        }
+        let alpha_channel = (alpha >= 0 && (alpha as u32) < channels).then_some(alpha as u32);
+
+        // Extended header; older files may carry only part of it
+        let ext = checked_slice(&data, HEADER_SIZE, ext_header_size)?;
+        let ext_u32 = |off: usize| if off + 4 <= ext.len() { le_u32(ext, off) } else { 0 };
+        let ext_u64 = |off: usize| if off + 8 <= ext.len() { le_u64(ext, off) } else { 0 };
+        let border_modes = [BorderMode::from_code(ext_u32(0)), BorderMode::from_code(ext_u32(4))];
+        let lmd_header_zip_size = ext_u32(8) as usize;
+        let lmd_header_mem_size = ext_u32(12) as usize;
+        let lmd_data_size = ext_u64(16) as usize;
+
+        let mut pos = HEADER_SIZE + ext_header_size;
+        let face_info_pos = pos;
+        pos += face_info_size;
+        let const_data_pos = pos;
+        pos += const_data_size;
+        let level_info_pos = pos;
+        pos += level_info_size;
+        let level_data_pos = pos;
+        pos += level_data_size;
+        let meta_pos = pos;
+        pos += meta_zip_size;
+        // Compatibility barrier between the small and large metadata
+        pos += 8;
+        let lmd_header_pos = pos;
+        pos += lmd_header_zip_size;
+        let lmd_data_pos = pos;
+
+        // Face info
+        let raw = inflate(
+            checked_slice(&data, face_info_pos, face_info_size)?,
+            nfaces * FACE_INFO_SIZE,
+        )?;
+        let faces: Vec<FaceInfo> = raw
+            .chunks_exact(FACE_INFO_SIZE)
+            .map(|r| FaceInfo {
+                res: FaceRes::new(r[0], r[1]),
+                adj_edges: r[2],
+                flags: r[3],
+                adj_faces: [le_i32(r, 4), le_i32(r, 8), le_i32(r, 12), le_i32(r, 16)],
+            })
+            .collect();
+        if faces.iter().any(|f| f.res.ulog2 > 30 || f.res.vlog2 > 30) {
+            return Err(IoError::InvalidFile("Ptex: face resolution out of range".into()));
+        }
+
+        // Constant values, one texel per face
+        let pixel_size = data_type.size() * channels as usize;
+        let raw = inflate(
+            checked_slice(&data, const_data_pos, const_data_size)?,
+            nfaces * pixel_size,
+        )?;
+        let const_data = to_f32(data_type, &raw);
+
+        // Level info and level headers
+        let level_info = checked_slice(&data, level_info_pos, level_info_size)?;
+        if level_info.len() < nlevels * LEVEL_INFO_SIZE {
+            return Err(IoError::InvalidFile("Ptex: truncated level info".into()));
+        }
+        let mut levels = Vec::with_capacity(nlevels);
+        let mut level_pos = level_data_pos;
+        for i in 0..nlevels {
+            let rec = &level_info[i * LEVEL_INFO_SIZE..];
+            let size = le_u64(rec, 0) as usize;
+            let header_size = le_u32(rec, 8) as usize;
+            let count = le_u32(rec, 12) as usize;
+            if count > nfaces {
+                return Err(IoError::InvalidFile("Ptex: level face count exceeds faces".into()));
+            }
+            let raw = inflate(checked_slice(&data, level_pos, header_size)?, count * 4)?;
+            let headers: Vec<u32> = raw.chunks_exact(4).map(|b| le_u32(b, 0)).collect();
+            let mut offsets = Vec::with_capacity(count);
+            let mut offset = level_pos + header_size;
+            for &fdh in &headers {
+                offsets.push(offset);
+                offset += block_size(fdh);
+            }
+            levels.push(Level { headers, offsets });
+            level_pos += size;
+        }
+
+        // Metadata
+        let mut metadata = Attrs::new();
+        if meta_zip_size > 0 {
+            let raw = inflate(checked_slice(&data, meta_pos, meta_zip_size)?, meta_mem_size)?;
+            parse_metadata(&raw, &mut metadata)?;
+        }
+        if lmd_header_zip_size > 0 {
+            let header = inflate(
+                checked_slice(&data, lmd_header_pos, lmd_header_zip_size)?,
+                lmd_header_mem_size,
+            )?;
+            let lmd = checked_slice(&data, lmd_data_pos, lmd_data_size)?;
+            parse_large_metadata(&header, lmd, &mut metadata)?;
+        }
+
+        let rface_ids = reduction_order(&faces);
+        let cache = levels
+            .iter()
+            .map(|l| (0..l.headers.len()).map(|_| OnceLock::new()).collect())
+            .collect();
+
+        Ok(Self {
+            data,
+            mesh_type,
+            data_type,
+            channels,
+            alpha_channel,
+            border_modes,
+            faces,
+            const_data,
+            levels,
+            rface_ids,
+            metadata,
+            cache,
+        })
+    }
+
+    /// Mesh type.
+    pub fn mesh_type(&self) -> MeshType {
+        self.mesh_type
+    }
+
+    /// Stored channel data type.
+    pub fn data_type(&self) -> PtexDataType {
+        self.data_type
+    }
+
+    /// Number of channels.
+    pub fn channels(&self) -> u32 {
+        self.channels
+    }
+
+    /// Index of the alpha channel, if any.
+    pub fn alpha_channel(&self) -> Option<u32> {
+        self.alpha_channel
+    }
+
+    /// Border modes for faces without neighbors, as (u, v).
+    pub fn border_modes(&self) -> (BorderMode, BorderMode) {
+        (self.border_modes[0], self.border_modes[1])
+    }
+
+    /// Number of faces.
+    pub fn num_faces(&self) -> u32 {
+        self.faces.len() as u32
+    }
+
+    /// Number of reduction levels in the file (including full resolution).
+    pub fn num_levels(&self) -> u32 {
+        self.levels.len() as u32
+    }
+
+    /// File metadata.
+    pub fn metadata(&self) -> &Attrs {
+        &self.metadata
+    }
+
+    /// Resolution, adjacency and flags of a face.
+    pub fn face_info(&self, face_id: u32) -> IoResult<&FaceInfo> {
+        self.faces.get(face_id as usize).ok_or_else(|| {
+            IoError::MissingData(format!(
+                "Ptex face {} out of range ({} faces)",
+                face_id,
+                self.faces.len()
+            ))
+        })
+    }
+
+    /// Number of levels available for a face (at least 1).
+    pub fn face_levels(&self, face_id: u32) -> u32 {
+        let Some(info) = self.faces.get(face_id as usize) else {
+            return 0;
+        };
+        if info.is_constant() {
+            return 1;
+        }
+        let rface = self.rface_ids[face_id as usize] as usize;
+        let stored = self
+            .levels
+            .iter()
+            .skip(1)
+            .take_while(|l| rface < l.headers.len())
+            .count() as u32;
+        1 + stored.min(info.res.ulog2.min(info.res.vlog2) as u32)
+    }
+
+    /// Constant (average) value of a face.
+    pub fn face_constant(&self, face_id: u32) -> IoResult<&[f32]> {
+        self.face_info(face_id)?;
+        let ch = self.channels as usize;
+        let start = face_id as usize * ch;
+        Ok(&self.const_data[start..start + ch])
+    }
+
+    /// Decoded texels of a face at a reduction level (0 = full resolution).
+    pub fn face_data(&self, face_id: u32, level: u32) -> IoResult<Arc<PtexFace>> {
+        let info = *self.face_info(face_id)?;
+        let res = info.res.reduced(level);
+        if info.is_constant() {
+            return Ok(Arc::new(PtexFace {
+                res,
+                channels: self.channels,
+                data: self.face_constant(face_id)?.to_vec(),
+            }));
+        }
+        if level >= self.face_levels(face_id) {
+            return Err(IoError::MissingData(format!(
+                "Ptex face {} has no reduction level {}",
+                face_id, level
+            )));
+        }
+
+        let index = if level == 0 { face_id } else { self.rface_ids[face_id as usize] } as usize;
+        let level = level as usize;
+        if let Some(face) = self.cache[level][index].get() {
+            return Ok(Arc::clone(face));
+        }
+        let lvl = &self.levels[level];
+        let data = self.read_face(lvl.offsets[index], lvl.headers[index], res)?;
+        let face = Arc::new(PtexFace { res, channels: self.channels, data });
+        Ok(Arc::clone(self.cache[level][index].get_or_init(|| face)))
+    }
+
+    /// Maps a point outside a quad face into the neighbor across the crossed edge.
+    ///
+    /// `u` or `v` outside [0, 1] selects the edge (u takes precedence at
+    /// corners). Returns the neighbor id and the point in its coordinates, or
+    /// `None` when the point is inside the face or the edge has no neighbor.
+    pub fn cross_edge(&self, face_id: u32, u: f32, v: f32) -> Option<(u32, f32, f32)> {
+        let info = self.faces.get(face_id as usize)?;
+        let edge = if u < 0.0 {
+            Edge::Left
+        } else if u > 1.0 {
+            Edge::Right
+        } else if v < 0.0 {
+            Edge::Bottom
+        } else if v > 1.0 {
+            Edge::Top
+        } else {
+            return None;
+        };
+        let adj = info.adj_face(edge)?;
+        if adj as usize >= self.faces.len() {
+            return None;
+        }
+        let (t, outside) = edge.edge_coords(u, v);
+        let (nu, nv) = info.adj_edge(edge).face_coords(1.0 - t, outside);
+        Some((adj, nu, nv))
+    }
+
+    /// Decodes a top-level face block, expanding constant and tiled data.
+    fn read_face(&self, pos: usize, fdh: u32, res: FaceRes) -> IoResult<Vec<f32>> {
+        let ch = self.channels as usize;
+        if encoding(fdh) != ENC_TILED {
+            return self.read_block(pos, fdh, res.u(), res.v());
+        }
+
+        // Tile resolution, zipped tile headers, then the tile blocks
+        let head = checked_slice(&self.data, pos, 6)?;
+        let tile_res = FaceRes::new(head[0], head[1]);
+        let tile_header_size = le_u32(head, 2) as usize;
+        if tile_res.ulog2 > res.ulog2 || tile_res.vlog2 > res.vlog2 {
+            return Err(IoError::InvalidFile("Ptex: tile larger than face".into()));
+        }
+        let tiles_u = 1 << (res.ulog2 - tile_res.ulog2);
+        let tiles_v = 1 << (res.vlog2 - tile_res.vlog2);
+        let raw = inflate(
+            checked_slice(&self.data, pos + 6, tile_header_size)?,
+            tiles_u * tiles_v * 4,
+        )?;
+
+        let (tw, th) = (tile_res.u(), tile_res.v());
+        let width = res.u();
+        let mut out = vec![0.0f32; res.size() * ch];
+        let mut offset = pos + 6 + tile_header_size;
+        for (i, b) in raw.chunks_exact(4).enumerate() {
+            let tile_fdh = le_u32(b, 0);
+            if encoding(tile_fdh) == ENC_TILED {
+                return Err(IoError::InvalidFile("Ptex: nested tiles".into()));
+            }
+            let tile = self.read_block(offset, tile_fdh, tw, th)?;
+            offset += block_size(tile_fdh);
+            let (x0, y0) = ((i % tiles_u) * tw, (i / tiles_u) * th);
+            for y in 0..th {
+                let dst = ((y0 + y) * width + x0) * ch;
+                out[dst..dst + tw * ch].copy_from_slice(&tile[y * tw * ch..(y + 1) * tw * ch]);
+            }
+        }
+        Ok(out)
+    }
+
+    /// Decodes a constant or zipped block into `w * h` interleaved texels.
+    fn read_block(&self, pos: usize, fdh: u32, w: usize, h: usize) -> IoResult<Vec<f32>> {
+        let ch = self.channels as usize;
+        let dt = self.data_type;
+        let block = checked_slice(&self.data, pos, block_size(fdh))?;
+        match encoding(fdh) {
+            ENC_CONSTANT => {
+                if block.len() < dt.size() * ch {
+                    return Err(IoError::InvalidFile("Ptex: short constant block".into()));
+                }
+                let value = to_f32(dt, &block[..dt.size() * ch]);
+                Ok(value.repeat(w * h))
+            }
+            enc @ (ENC_ZIPPED | ENC_DIFF_ZIPPED) => {
+                let mut raw = inflate(block, w * h * ch * dt.size())?;
+                if enc == ENC_DIFF_ZIPPED {
+                    decode_difference(dt, &mut raw);
+                }
+                // Stored channel-planar
+                let planar = to_f32(dt, &raw);
+                let plane = w * h;
+                let mut out = vec![0.0f32; plane * ch];
+                for (c, src) in planar.chunks_exact(plane).enumerate() {
+                    for (i, &v) in src.iter().enumerate() {
+                        out[i * ch + c] = v;
+                    }
+                }
+                Ok(out)
+            }
+            _ => Err(IoError::InvalidFile("Ptex: unexpected tiled block".into())),
+        }
+    }
+}
+
+/// Checks for the Ptex magic.
+pub fn is_ptex(data: &[u8]) -> bool {
+    data.len() >= 4 && &data[..4] == PTEX_MAGIC
+}
+
+/// Opens a Ptex file.
+///
+/// # Example
+///
+/// ```ignore
+/// let ptx = vfx_io::ptex::open("asset.ptx")?;
+/// let face = ptx.face_data(0, 0)?;
+/// ```
+pub fn open<P: AsRef<Path>>(path: P) -> IoResult<PtexTexture> {
+    PtexTexture::open(path)
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn le_u16(b: &[u8], off: usize) -> u16 {
+    u16::from_le_bytes([b[off], b[off + 1]])
+}
+
+fn le_u32(b: &[u8], off: usize) -> u32 {
+    u32::from_le_bytes([b[off], b[off + 1], b[off + 2], b[off + 3]])
+}
+
+fn le_i32(b: &[u8], off: usize) -> i32 {
+    le_u32(b, off) as i32
+}
+
+fn le_u64(b: &[u8], off: usize) -> u64 {
+    le_u32(b, off) as u64 | ((le_u32(b, off + 4) as u64) << 32)
+}
+
+fn block_size(fdh: u32) -> usize {
+    (fdh & 0x3fff_ffff) as usize
+}
+
+fn encoding(fdh: u32) -> u32 {
+    fdh >> 30
+}
+
+fn checked_slice(data: &[u8], pos: usize, len: usize) -> IoResult<&[u8]> {
+    pos.checked_add(len)
+        .and_then(|end| data.get(pos..end))
+        .ok_or_else(|| IoError::InvalidFile("Ptex: truncated file".into()))
+}
+
+/// Inflates a zlib block of known size.
+fn inflate(data: &[u8], expected: usize) -> IoResult<Vec<u8>> {
+    if expected == 0 {
+        return Ok(Vec::new());
+    }
+    let options = zune_inflate::DeflateOptions::default()
+        .set_limit(expected)
+        .set_size_hint(expected);
+    let out = zune_inflate::DeflateDecoder::new_with_options(data, options)
+        .decode_zlib()
+        .map_err(|e| IoError::DecodeError(format!("Ptex: zlib: {:?}", e)))?;
+    if out.len() != expected {
+        return Err(IoError::DecodeError(format!(
+            "Ptex: block inflated to {} bytes, expected {}",
+            out.len(),
+            expected
+        )));
+    }
+    Ok(out)
+}
+
+/// Undoes the running difference applied to integer data before zipping.
+fn decode_difference(dt: PtexDataType, raw: &mut [u8]) {
+    match dt {
+        PtexDataType::U8 => {
+            let mut prev = 0u8;
+            for b in raw.iter_mut() {
+                prev = prev.wrapping_add(*b);
+                *b = prev;
+            }
+        }
+        PtexDataType::U16 => {
+            let mut prev = 0u16;
+            for b in raw.chunks_exact_mut(2) {
+                prev = prev.wrapping_add(u16::from_le_bytes([b[0], b[1]]));
+                b.copy_from_slice(&prev.to_le_bytes());
+            }
+        }
+        PtexDataType::Half | PtexDataType::F32 => {}
+    }
+}
+
+/// Converts raw channel values to normalized floats.
+fn to_f32(dt: PtexDataType, raw: &[u8]) -> Vec<f32> {
+    match dt {
+        PtexDataType::U8 => raw.iter().map(|&v| v as f32 / 255.0).collect(),
+        PtexDataType::U16 => raw
+            .chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]) as f32 / 65535.0)
+            .collect(),
+        PtexDataType::Half => raw
+            .chunks_exact(2)
+            .map(|b| half::f16::from_le_bytes([b[0], b[1]]).to_f32())
+            .collect(),
+        PtexDataType::F32 => raw
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+    }
+}
+
+/// Position of each face in the reduction levels.
+///
+/// Reductions store faces sorted by their smaller dimension, largest first
+/// (stable, constant faces count as 1).
+fn reduction_order(faces: &[FaceInfo]) -> Vec<u32> {
+    let min_log = |f: &FaceInfo| {
+        if f.is_constant() { 1 } else { f.res.ulog2.min(f.res.vlog2) }
+    };
+    let mut order: Vec<u32> = (0..faces.len() as u32).collect();
+    order.sort_by(|&a, &b| min_log(&faces[b as usize]).cmp(&min_log(&faces[a as usize])));
+    let mut rface_ids = vec![0u32; faces.len()];
+    for (i, &face) in order.iter().enumerate() {
+        rface_ids[face as usize] = i as u32;
+    }
+    rface_ids
+}
+
+/// Metadata value from its type code and raw bytes.
+fn meta_value(kind: u8, raw: &[u8]) -> IoResult<AttrValue> {
+    let values: Vec<AttrValue> = match kind {
+        0 => {
+            let end = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+            return Ok(AttrValue::Str(String::from_utf8_lossy(&raw[..end]).into_owned()));
+        }
+        1 => raw.iter().map(|&b| AttrValue::Int(b as i8 as i32)).collect(),
+        2 => raw
+            .chunks_exact(2)
+            .map(|b| AttrValue::Int(i16::from_le_bytes([b[0], b[1]]) as i32))
+            .collect(),
+        3 => raw.chunks_exact(4).map(|b| AttrValue::Int(le_i32(b, 0))).collect(),
+        4 => raw
+            .chunks_exact(4)
+            .map(|b| AttrValue::Float(f32::from_le_bytes([b[0], b[1], b[2], b[3]])))
+            .collect(),
+        5 => raw
+            .chunks_exact(8)
+            .map(|b| AttrValue::Double(f64::from_bits(le_u64(b, 0))))
+            .collect(),
+        _ => return Err(IoError::InvalidFile(format!("Ptex: unknown metadata type {}", kind))),
+    };
+    Ok(match values.len() {
+        1 => values.into_iter().next().unwrap_or(AttrValue::Int(0)),
+        _ => AttrValue::List(values),
+    })
+}
+
+/// Reads a metadata key (length includes the terminator) and type.
+fn meta_key(raw: &[u8], pos: &mut usize) -> IoResult<(String, u8, usize)> {
+    let key_size = *raw.get(*pos).ok_or_else(|| IoError::InvalidFile("Ptex: truncated metadata".into()))? as usize;
+    let key = checked_slice(raw, *pos + 1, key_size)?;
+    let end = key.iter().position(|&b| b == 0).unwrap_or(key.len());
+    let key = String::from_utf8_lossy(&key[..end]).into_owned();
+    *pos += 1 + key_size;
+    let head = checked_slice(raw, *pos, 5)?;
+    let kind = head[0];
+    let size = le_u32(head, 1) as usize;
+    *pos += 5;
+    Ok((key, kind, size))
+}
+
+fn parse_metadata(raw: &[u8], attrs: &mut Attrs) -> IoResult<()> {
+    let mut pos = 0;
+    while pos < raw.len() {
+        let (key, kind, size) = meta_key(raw, &mut pos)?;
+        let value = checked_slice(raw, pos, size)?;
+        pos += size;
+        attrs.set(key, meta_value(kind, value)?);
+    }
+    Ok(())
+}
+
+/// Large entries keep their values zipped in a separate data section.
+fn parse_large_metadata(header: &[u8], lmd: &[u8], attrs: &mut Attrs) -> IoResult<()> {
+    let mut pos = 0;
+    let mut offset = 0;
+    while pos < header.len() {
+        let (key, kind, size) = meta_key(header, &mut pos)?;
+        let zip_size = le_u32(checked_slice(header, pos, 4)?, 0) as usize;
+        pos += 4;
+        let value = inflate(checked_slice(lmd, offset, zip_size)?, size)?;
+        offset += zip_size;
+        attrs.set(key, meta_value(kind, &value)?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Test face: resolution, adjacency and interleaved texels (one for constant).
+    pub(crate) struct TestFace {
+        res: FaceRes,
+        adj: [(i32, u8); 4],
+        data: Vec<f32>,
+    }
+
+    fn encode_values(dt: PtexDataType, values: &[f32]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for &v in values {
+            match dt {
+                PtexDataType::U8 => out.push((v * 255.0).round() as u8),
+                PtexDataType::U16 => out.extend_from_slice(&((v * 65535.0).round() as u16).to_le_bytes()),
+                PtexDataType::Half => out.extend_from_slice(&half::f16::from_f32(v).to_le_bytes()),
+                PtexDataType::F32 => out.extend_from_slice(&v.to_le_bytes()),
+            }
+        }
+        out
+    }
+
+    fn zip(data: &[u8]) -> Vec<u8> {
+        miniz_oxide::deflate::compress_to_vec_zlib(data, 6)
+    }
+
+    /// Zipped (planar, differenced for integers) face block.
+    fn face_block(dt: PtexDataType, ch: usize, texels: &[f32]) -> (u32, Vec<u8>) {
+        let n = texels.len() / ch;
+        let planar: Vec<f32> = (0..ch).flat_map(|c| (0..n).map(move |i| texels[i * ch + c])).collect();
+        let mut raw = encode_values(dt, &planar);
+        let diff = matches!(dt, PtexDataType::U8 | PtexDataType::U16);
+        if diff {
+            match dt {
+                PtexDataType::U8 => {
+                    for i in (1..raw.len()).rev() {
+                        raw[i] = raw[i].wrapping_sub(raw[i - 1]);
+                    }
+                }
+                _ => {
+                    let vals: Vec<u16> = raw.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])).collect();
+                    raw.clear();
+                    let mut prev = 0u16;
+                    for v in vals {
+                        raw.extend_from_slice(&v.wrapping_sub(prev).to_le_bytes());
+                        prev = v;
+                    }
+                }
+            }
+        }
+        let zipped = zip(&raw);
+        let enc = if diff { ENC_DIFF_ZIPPED } else { ENC_ZIPPED };
+        ((enc << 30) | zipped.len() as u32, zipped)
+    }
+
+    /// Box-filters a face down by one level.
+    fn reduce(res: FaceRes, ch: usize, texels: &[f32]) -> (FaceRes, Vec<f32>) {
+        let (w, h) = (res.u(), res.v());
+        let out_res = res.reduced(1);
+        let (ow, oh) = (out_res.u(), out_res.v());
+        let mut out = vec![0.0; ow * oh * ch];
+        for y in 0..oh {
+            for x in 0..ow {
+                for c in 0..ch {
+                    let mut sum = 0.0;
+                    for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                        let sx = (x * 2 + dx).min(w - 1);
+                        let sy = (y * 2 + dy).min(h - 1);
+                        sum += texels[(sy * w + sx) * ch + c];
+                    }
+                    out[(y * ow + x) * ch + c] = sum / 4.0;
+                }
+            }
+        }
+        (out_res, out)
+    }
+
+    /// Writes a Ptex file the way the reference writer lays it out.
+    pub(crate) fn build_ptex(
+        dt: PtexDataType,
+        ch: usize,
+        faces: &[TestFace],
+        meta: &[(&str, u8, Vec<u8>)],
+        tile_res: Option<FaceRes>,
+    ) -> Vec<u8> {
+        let nfaces = faces.len();
+        let infos: Vec<FaceInfo> = faces
+            .iter()
+            .map(|f| FaceInfo {
+                res: f.res,
+                adj_faces: [f.adj[0].0, f.adj[1].0, f.adj[2].0, f.adj[3].0],
+                adj_edges: f.adj.iter().enumerate().fold(0, |acc, (i, a)| acc | ((a.1 & 3) << (2 * i))),
+                flags: if f.data.len() == ch { FLAG_CONSTANT } else { 0 },
+            })
+            .collect();
+
+        let mut face_info = Vec::new();
+        for info in &infos {
+            face_info.extend_from_slice(&[info.res.ulog2, info.res.vlog2, info.adj_edges, info.flags]);
+            for a in info.adj_faces {
+                face_info.extend_from_slice(&a.to_le_bytes());
+            }
+        }
+        let mut consts = Vec::new();
+        for f in faces {
+            let n = f.data.len() / ch;
+            let avg: Vec<f32> = (0..ch).map(|c| (0..n).map(|i| f.data[i * ch + c]).sum::<f32>() / n as f32).collect();
+            consts.extend(encode_values(dt, &avg));
+        }
+
+        // Reductions per face
+        let rface_ids = reduction_order(&infos);
+        let mut order = vec![0usize; nfaces];
+        for (f, &r) in rface_ids.iter().enumerate() {
+            order[r as usize] = f;
+        }
+        let max_level = infos
+            .iter()
+            .filter(|i| !i.is_constant())
+            .map(|i| i.res.ulog2.min(i.res.vlog2) as usize)
+            .max()
+            .unwrap_or(0);
+        let mut chains: Vec<Vec<Vec<f32>>> = faces.iter().map(|f| vec![f.data.clone()]).collect();
+        for (f, chain) in chains.iter_mut().enumerate() {
+            if infos[f].is_constant() {
+                continue;
+            }
+            let mut res = infos[f].res;
+            for _ in 0..max_level {
+                let (r, d) = reduce(res, ch, chain.last().unwrap());
+                res = r;
+                chain.push(d);
+            }
+        }
+
+        let mut level_info = Vec::new();
+        let mut level_data = Vec::new();
+        for level in 0..=max_level {
+            let ids: Vec<usize> = if level == 0 {
+                (0..nfaces).collect()
+            } else {
+                order
+                    .iter()
+                    .copied()
+                    .take_while(|&f| {
+                        infos[f].is_constant() && level == 1
+                            || infos[f].res.ulog2.min(infos[f].res.vlog2) as usize >= level
+                    })
+                    .collect()
+            };
+            let mut headers = Vec::new();
+            let mut blocks = Vec::new();
+            for &f in &ids {
+                if infos[f].is_constant() {
+                    let v = encode_values(dt, &faces[f].data);
+                    headers.extend_from_slice(&(v.len() as u32).to_le_bytes());
+                    blocks.extend(v);
+                    continue;
+                }
+                let res = infos[f].res.reduced(level as u32);
+                let texels = &chains[f][level];
+                match tile_res {
+                    Some(tr) if level == 0 && tr.ulog2 < res.ulog2 => {
+                        let (tw, th) = (tr.u(), tr.v());
+                        let tiles_u = res.u() / tw;
+                        let tiles_v = res.v() / th;
+                        let mut tile_headers = Vec::new();
+                        let mut tile_data = Vec::new();
+                        for t in 0..tiles_u * tiles_v {
+                            let (x0, y0) = ((t % tiles_u) * tw, (t / tiles_u) * th);
+                            let mut tile = Vec::new();
+                            for y in 0..th {
+                                let s = ((y0 + y) * res.u() + x0) * ch;
+                                tile.extend_from_slice(&texels[s..s + tw * ch]);
+                            }
+                            let (fdh, block) = face_block(dt, ch, &tile);
+                            tile_headers.extend_from_slice(&fdh.to_le_bytes());
+                            tile_data.extend(block);
+                        }
+                        let zipped = zip(&tile_headers);
+                        let mut block = vec![tr.ulog2, tr.vlog2];
+                        block.extend_from_slice(&(zipped.len() as u32).to_le_bytes());
+                        block.extend(zipped);
+                        block.extend(tile_data);
+                        headers.extend_from_slice(&((ENC_TILED << 30) | block.len() as u32).to_le_bytes());
+                        blocks.extend(block);
+                    }
+                    _ => {
+                        let (fdh, block) = face_block(dt, ch, texels);
+                        headers.extend_from_slice(&fdh.to_le_bytes());
+                        blocks.extend(block);
+                    }
+                }
+            }
+            let zipped = zip(&headers);
+            level_info.extend_from_slice(&((zipped.len() + blocks.len()) as u64).to_le_bytes());
+            level_info.extend_from_slice(&(zipped.len() as u32).to_le_bytes());
+            level_info.extend_from_slice(&(ids.len() as u32).to_le_bytes());
+            level_data.extend(zipped);
+            level_data.extend(blocks);
+        }
+
+        let mut meta_raw = Vec::new();
+        for (key, kind, value) in meta {
+            meta_raw.push(key.len() as u8 + 1);
+            meta_raw.extend_from_slice(key.as_bytes());
+            meta_raw.push(0);
+            meta_raw.push(*kind);
+            meta_raw.extend_from_slice(&(value.len() as u32).to_le_bytes());
+            meta_raw.extend_from_slice(value);
+        }
+
+        let face_info = zip(&face_info);
+        let consts = zip(&consts);
+        let meta_zip = if meta_raw.is_empty() { Vec::new() } else { zip(&meta_raw) };
+
+        let mut out = Vec::new();
+        out.extend_from_slice(PTEX_MAGIC);
+        for v in [PTEX_VERSION, 1, dt as u32, u32::MAX] {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        out.extend_from_slice(&(ch as u16).to_le_bytes());
+        out.extend_from_slice(&((max_level + 1) as u16).to_le_bytes());
+        for v in [nfaces, 40, face_info.len(), consts.len(), level_info.len(), 4] {
+            out.extend_from_slice(&(v as u32).to_le_bytes());
+        }
+        out.extend_from_slice(&(level_data.len() as u64).to_le_bytes());
+        out.extend_from_slice(&(meta_zip.len() as u32).to_le_bytes());
+        out.extend_from_slice(&(meta_raw.len() as u32).to_le_bytes());
+        assert_eq!(out.len(), HEADER_SIZE);
+        // Extended header: clamp borders, no large metadata or edits
+        out.extend_from_slice(&[0u8; 40]);
+        out.extend(face_info);
+        out.extend(consts);
+        out.extend(level_info);
+        out.extend(level_data);
+        out.extend(meta_zip);
+        out.extend_from_slice(&[0u8; 8]);
+        out
+    }
+
+    fn gradient(res: FaceRes, ch: usize, seed: f32) -> Vec<f32> {
+        let (w, h) = (res.u(), res.v());
+        (0..w * h)
+            .flat_map(|i| {
+                let (x, y) = ((i % w) as f32, (i / w) as f32);
+                (0..ch).map(move |c| (x * 0.11 + y * 0.07 + c as f32 * 0.2 + seed) % 1.0)
+            })
+            .collect()
+    }
+
+    /// Two quads side by side: face 0 right edge meets face 1 left edge.
+    pub(crate) fn two_quads(dt: PtexDataType, tile_res: Option<FaceRes>) -> (Vec<u8>, Vec<Vec<f32>>) {
+        let r0 = FaceRes::new(3, 2);
+        let r1 = FaceRes::new(2, 2);
+        let d0 = gradient(r0, 3, 0.0);
+        let d1 = gradient(r1, 3, 0.5);
+        let faces = [
+            TestFace { res: r0, adj: [(-1, 0), (1, 3), (-1, 0), (-1, 0)], data: d0.clone() },
+            TestFace { res: r1, adj: [(-1, 0), (-1, 0), (-1, 0), (0, 1)], data: d1.clone() },
+        ];
+        (build_ptex(dt, 3, &faces, &[], tile_res), vec![d0, d1])
+    }
+
+    #[test]
+    fn test_header_and_faces() {
+        let (file, _) = two_quads(PtexDataType::F32, None);
+        let ptx = PtexTexture::from_bytes(file).unwrap();
+        assert_eq!(ptx.mesh_type(), MeshType::Quad);
+        assert_eq!(ptx.data_type(), PtexDataType::F32);
+        assert_eq!(ptx.channels(), 3);
+        assert_eq!(ptx.alpha_channel(), None);
+        assert_eq!(ptx.num_faces(), 2);
+        assert_eq!(ptx.num_levels(), 3);
+        assert_eq!(ptx.border_modes(), (BorderMode::Clamp, BorderMode::Clamp));
+
+        let info = ptx.face_info(0).unwrap();
+        assert_eq!(info.res, FaceRes::new(3, 2));
+        assert_eq!(info.adj_face(Edge::Right), Some(1));
+        assert_eq!(info.adj_edge(Edge::Right), Edge::Left);
+        assert_eq!(info.adj_face(Edge::Top), None);
+        assert!(ptx.face_info(2).is_err());
+    }
+
+    #[test]
+    fn test_face_data_types() {
+        for (dt, tol) in [
+            (PtexDataType::U8, 0.51 / 255.0),
+            (PtexDataType::U16, 0.51 / 65535.0),
+            (PtexDataType::Half, 1e-3),
+            (PtexDataType::F32, 0.0),
+        ] {
+            let (file, data) = two_quads(dt, None);
+            let ptx = PtexTexture::from_bytes(file).unwrap();
+            for (f, expected) in data.iter().enumerate() {
+                let face = ptx.face_data(f as u32, 0).unwrap();
+                assert_eq!(face.data.len(), expected.len());
+                for (a, b) in face.data.iter().zip(expected) {
+                    assert!((a - b).abs() <= tol, "{:?}: {} vs {}", dt, a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reductions() {
+        let (file, data) = two_quads(PtexDataType::F32, None);
+        let ptx = PtexTexture::from_bytes(file).unwrap();
+        // Face 0 is 8x4 (two reductions), face 1 is 4x4
+        assert_eq!(ptx.face_levels(0), 3);
+        assert_eq!(ptx.face_levels(1), 3);
+
+        let (res, expected) = reduce(FaceRes::new(3, 2), 3, &data[0]);
+        let face = ptx.face_data(0, 1).unwrap();
+        assert_eq!(face.res, res);
+        assert_eq!(face.res, FaceRes::new(2, 1));
+        assert_eq!(face.data, expected);
+
+        let face = ptx.face_data(1, 2).unwrap();
+        assert_eq!(face.res, FaceRes::new(0, 0));
+        let mean: f32 = (0..16).map(|i| data[1][i * 3]).sum::<f32>() / 16.0;
+        assert!((face.pixel(0, 0)[0] - mean).abs() < 1e-6);
+        assert!(ptx.face_data(1, 3).is_err());
+
+        // Cached decode returns the same allocation
+        let a = ptx.face_data(0, 1).unwrap();
+        let b = ptx.face_data(0, 1).unwrap();
+        assert!(Arc::ptr_eq(&a, &b));
+    }
+
+    #[test]
+    fn test_tiled_faces() {
+        let (file, data) = two_quads(PtexDataType::U16, Some(FaceRes::new(1, 1)));
+        let ptx = PtexTexture::from_bytes(file).unwrap();
+        let face = ptx.face_data(0, 0).unwrap();
+        for (a, b) in face.data.iter().zip(&data[0]) {
+            assert!((a - b).abs() <= 0.51 / 65535.0);
+        }
+        assert!((face.pixel(7, 3)[2] - data[0][(3 * 8 + 7) * 3 + 2]).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_constant_faces_and_metadata() {
+        let res = FaceRes::new(2, 2);
+        let faces = [
+            TestFace { res, adj: [(-1, 0); 4], data: vec![0.25, 0.5] },
+            TestFace { res, adj: [(-1, 0); 4], data: gradient(res, 2, 0.1) },
+        ];
+        let meta = [
+            ("PtexFaceVertCounts", 3, [4i32, 4].iter().flat_map(|v| v.to_le_bytes()).collect()),
+            ("author", 0, b"paint\0".to_vec()),
+            ("gamma", 4, 2.2f32.to_le_bytes().to_vec()),
+        ];
+        let file = build_ptex(PtexDataType::Half, 2, &faces, &meta, None);
+        let ptx = PtexTexture::from_bytes(file).unwrap();
+
+        assert!(ptx.face_info(0).unwrap().is_constant());
+        assert_eq!(ptx.face_levels(0), 1);
+        let face = ptx.face_data(0, 0).unwrap();
+        assert!(face.is_constant());
+        assert_eq!(face.res, res);
+        assert_eq!(face.pixel(3, 1), &[0.25, 0.5]);
+        assert_eq!(ptx.face_data(0, 2).unwrap().pixel(0, 0), &[0.25, 0.5]);
+        assert_eq!(ptx.face_levels(1), 3);
+
+        let attrs = ptx.metadata();
+        assert_eq!(attrs.get("author"), Some(&AttrValue::Str("paint".into())));
+        assert_eq!(attrs.get("gamma"), Some(&AttrValue::Float(2.2)));
+        assert_eq!(
+            attrs.get("PtexFaceVertCounts"),
+            Some(&AttrValue::List(vec![AttrValue::Int(4), AttrValue::Int(4)]))
+        );
+    }
+
+    #[test]
+    fn test_large_metadata() {
+        let mut header = Vec::new();
+        let mut lmd = Vec::new();
+        let value: Vec<u8> = (0..600i32).flat_map(|v| v.to_le_bytes()).collect();
+        let zipped = zip(&value);
+        header.push(4);
+        header.extend_from_slice(b"ids\0");
+        header.push(3);
+        header.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        header.extend_from_slice(&(zipped.len() as u32).to_le_bytes());
+        lmd.extend(zipped);
+
+        let mut attrs = Attrs::new();
+        parse_large_metadata(&header, &lmd, &mut attrs).unwrap();
+        let list = attrs.get("ids").and_then(|v| v.as_list()).unwrap();
+        assert_eq!(list.len(), 600);
+        assert_eq!(list[599], AttrValue::Int(599));
+    }
+
+    #[test]
+    fn test_cross_edge() {
+        let (file, _) = two_quads(PtexDataType::U8, None);
+        let ptx = PtexTexture::from_bytes(file).unwrap();
+
+        // Right of face 0 lands just inside the left of face 1
+        let (f, u, v) = ptx.cross_edge(0, 1.1, 0.3).unwrap();
+        assert_eq!(f, 1);
+        assert!((u - 0.1).abs() < 1e-6 && (v - 0.3).abs() < 1e-6);
+        let (f, u, v) = ptx.cross_edge(1, -0.25, 0.6).unwrap();
+        assert_eq!(f, 0);
+        assert!((u - 0.75).abs() < 1e-6 && (v - 0.6).abs() < 1e-6);
+
+        assert!(ptx.cross_edge(0, 0.5, 0.5).is_none());
+        assert!(ptx.cross_edge(0, 0.5, 1.2).is_none());
+    }
+
+    #[test]
+    fn test_edge_rotation() {
+        // Entering through an edge and leaving through it again is the identity
+        for edge in Edge::ALL {
+            let (t, d) = (0.3, 0.2);
+            let (u, v) = edge.face_coords(1.0 - t, d);
+            assert!((0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v));
+            let (t2, d2) = edge.edge_coords(u, v);
+            assert!((t2 - (1.0 - t)).abs() < 1e-6 && (d2 + d).abs() < 1e-6, "{:?}", edge);
+        }
+        let (u, v) = Edge::Top.face_coords(1.0 - 0.3, 0.2);
+        assert!((u - 0.3).abs() < 1e-6 && (v - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        assert!(PtexTexture::from_bytes(b"nope".to_vec()).is_err());
+        let (mut file, _) = two_quads(PtexDataType::U8, None);
+        file.truncate(file.len() / 2);
+        assert!(PtexTexture::from_bytes(file).is_err());
+        assert!(is_ptex(b"Ptex\x01\0\0\0"));
+    }
+}
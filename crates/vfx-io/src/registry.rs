@@ -33,10 +33,32 @@
 //!     println!("Detected: {}", name);
 //! }
 //! ```
+//!
+//! # Streams and Memory
+//!
+//! Formats with [`FormatCapability::IoProxy`] can also be read from any
+//! `Read + Seek` source and written to any `Write` sink. The format is
+//! detected from the stream's magic bytes; the hint (format name,
+//! extension or file name) is only used when detection fails.
+//!
+//! ```ignore
+//! use std::io::Cursor;
+//! use vfx_io::exr::ExrWriterOptions;
+//! use vfx_io::registry::FormatRegistry;
+//!
+//! let registry = FormatRegistry::global();
+//! let image = registry.read_from(&mut Cursor::new(bytes), Some("plate.exr"))?;
+//!
+//! let mut out = Vec::new();
+//! let options = ExrWriterOptions::default();
+//! registry.write_to(&mut out, &image, "exr", Some(&options))?;
+//! ```
 
-use crate::{ImageData, IoResult, FormatReader, FormatWriter, FormatCapability};
+use crate::{ImageData, IoError, IoResult, FormatReader, FormatWriter, FormatCapability, ReadSeek};
 use crate::deepdata::DeepData;
+use std::any::Any;
 use std::collections::HashMap;
+use std::io::{SeekFrom, Write};
 use std::path::Path;
 use std::sync::{Arc, OnceLock};
 
@@ -55,6 +77,8 @@ pub struct FormatInfo {
     pub read_memory: fn(&[u8]) -> IoResult<ImageData>,
     /// Function to read specific subimage/miplevel from path.
     pub read_subimage_path: Option<fn(&Path, usize, usize) -> IoResult<ImageData>>,
+    /// Function to read specific subimage/miplevel from memory.
+    pub read_subimage_memory: Option<fn(&[u8], usize, usize) -> IoResult<ImageData>>,
     /// Function to get number of subimages.
     pub num_subimages: Option<fn(&Path) -> IoResult<usize>>,
     /// Function to get number of miplevels for subimage.
@@ -63,10 +87,14 @@ pub struct FormatInfo {
     pub write_path: Option<fn(&Path, &ImageData) -> IoResult<()>>,
     /// Function to write to memory (None if write not supported).
     pub write_memory: Option<fn(&ImageData) -> IoResult<Vec<u8>>>,
+    /// Function to write to memory with writer options (see [`write_with_options`]).
+    pub write_memory_options: Option<fn(&ImageData, &dyn Any) -> IoResult<Vec<u8>>>,
     /// Capabilities supported by this format.
     pub capabilities: &'static [FormatCapability],
     /// Function to read deep data from path (None if deep not supported).
     pub read_deep_path: Option<fn(&Path) -> IoResult<DeepData>>,
+    /// Function to read deep data from memory (None if deep not supported).
+    pub read_deep_memory: Option<fn(&[u8]) -> IoResult<DeepData>>,
 }
 
 /// Dynamic format reader trait (object-safe).
@@ -95,6 +123,34 @@ pub trait FormatWriterDyn: Send + Sync {
     fn write_memory(&self, image: &ImageData) -> IoResult<Vec<u8>>;
 }
 
+/// Writes an image to memory with a writer built from type-erased options.
+///
+/// Used for [`FormatInfo::write_memory_options`] entries. `options` must be
+/// the writer's own options type (e.g. `PngWriterOptions` for `PngWriter`).
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::png::{PngWriter, PngWriterOptions};
+/// use vfx_io::registry::write_with_options;
+///
+/// let options = PngWriterOptions::default();
+/// let bytes = write_with_options::<PngWriter, PngWriterOptions>(&image, &options)?;
+/// ```
+pub fn write_with_options<W, O>(image: &ImageData, options: &dyn Any) -> IoResult<Vec<u8>>
+where
+    W: FormatWriter<O>,
+    O: Default + Clone + 'static,
+{
+    let options = options.downcast_ref::<O>().ok_or_else(|| {
+        IoError::UnsupportedOperation(format!(
+            "writer options must be {}",
+            std::any::type_name::<O>()
+        ))
+    })?;
+    W::with_options(options.clone()).write_to_memory(image)
+}
+
 /// Central registry for image format handlers.
 ///
 /// Provides format detection, reader/writer creation, and format enumeration.
@@ -158,12 +214,15 @@ impl FormatRegistry {
             read_path: |p| crate::dpx::read(p),
             read_memory: |d| crate::dpx::DpxReader::new().read_from_memory(d),
             read_subimage_path: None,
+            read_subimage_memory: None,
             num_subimages: None,
             num_miplevels: None,
             write_path: Some(|p, i| crate::dpx::write(p, i)),
             write_memory: Some(|i| crate::dpx::DpxWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::dpx::DpxWriter, crate::dpx::DpxWriterOptions>),
             capabilities: &[FormatCapability::IoProxy],
             read_deep_path: None, // DPX doesn't support deep data
+            read_deep_memory: None,
         });
 
         #[cfg(feature = "exr")]
@@ -175,10 +234,12 @@ impl FormatRegistry {
             read_memory: |d| crate::exr::ExrReader::new().read_from_memory(d),
             // EXR multipart support - read specific layer/subimage
             read_subimage_path: Some(|p, subimage, miplevel| crate::exr::read_layer(p, subimage, miplevel)),
+            read_subimage_memory: Some(crate::exr::read_layer_from_memory),
             num_subimages: Some(|p| crate::exr::num_layers(p)),
             num_miplevels: None, // TODO: implement miplevel counting for EXR
             write_path: Some(|p, i| crate::exr::write(p, i)),
             write_memory: Some(|i| crate::exr::ExrWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::exr::ExrWriter, crate::exr::ExrWriterOptions>),
             capabilities: &[
                 FormatCapability::MultiImage,
                 FormatCapability::MipMap,
@@ -191,6 +252,10 @@ impl FormatRegistry {
                 let (samples, channels) = crate::exr_deep::read_deep_exr(p)?;
                 crate::exr_deep::deep_samples_to_deepdata(&samples, &channels)
             }),
+            read_deep_memory: Some(|d| {
+                let (samples, channels) = crate::exr_deep::read_deep_exr_from_memory(d)?;
+                crate::exr_deep::deep_samples_to_deepdata(&samples, &channels)
+            }),
         });
 
         #[cfg(feature = "png")]
@@ -211,12 +276,15 @@ impl FormatRegistry {
             read_path: |p| crate::png::read(p),
             read_memory: |d| crate::png::PngReader::new().read_from_memory(d),
             read_subimage_path: None,
+            read_subimage_memory: None,
             num_subimages: None,
             num_miplevels: None,
             write_path: Some(|p, i| crate::png::write(p, i)),
             write_memory: Some(|i| crate::png::PngWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::png::PngWriter, crate::png::PngWriterOptions>),
             capabilities: &[FormatCapability::IoProxy],
             read_deep_path: None, // PNG doesn't support deep data
+            read_deep_memory: None,
         });
 
         #[cfg(feature = "jpeg")]
@@ -227,12 +295,15 @@ impl FormatRegistry {
             read_path: |p| crate::jpeg::read(p),
             read_memory: |d| crate::jpeg::JpegReader::new().read_from_memory(d),
             read_subimage_path: None,
+            read_subimage_memory: None,
             num_subimages: None,
             num_miplevels: None,
             write_path: Some(|p, i| crate::jpeg::write(p, i)),
             write_memory: Some(|i| crate::jpeg::JpegWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::jpeg::JpegWriter, crate::jpeg::JpegWriterOptions>),
             capabilities: &[FormatCapability::IoProxy, FormatCapability::Exif],
             read_deep_path: None, // JPEG doesn't support deep data
+            read_deep_memory: None,
         });
 
         #[cfg(feature = "tiff")]
@@ -243,18 +314,25 @@ impl FormatRegistry {
                 if h.len() < 4 {
                     return false;
                 }
-                let le = h[0] == b'I' && h[1] == b'I' && h[2] == 0x2A && h[3] == 0x00;
-                let be = h[0] == b'M' && h[1] == b'M' && h[2] == 0x00 && h[3] == 0x2A;
+                // Classic (42) or BigTIFF (43)
+                let le = h[0] == b'I' && h[1] == b'I' && matches!(h[2], 0x2A | 0x2B) && h[3] == 0x00;
+                let be = h[0] == b'M' && h[1] == b'M' && h[2] == 0x00 && matches!(h[3], 0x2A | 0x2B);
                 le || be
             },
             read_path: |p| crate::tiff::read(p),
             read_memory: |d| crate::tiff::TiffReader::new().read_from_memory(d),
-            // TIFF supports pages/directories - TODO: implement real subimage support
-            read_subimage_path: None,
-            num_subimages: None,
+            // TIFF pages (IFDs) are subimages
+            read_subimage_path: Some(|p, subimage, miplevel| {
+                crate::tiff::TiffReader::new().read_subimage(p, subimage, miplevel)
+            }),
+            read_subimage_memory: Some(|d, subimage, miplevel| {
+                crate::tiff::TiffReader::new().read_subimage_from_memory(d, subimage, miplevel)
+            }),
+            num_subimages: Some(|p| crate::tiff::num_pages(p)),
             num_miplevels: None,
             write_path: Some(|p, i| crate::tiff::write(p, i)),
             write_memory: Some(|i| crate::tiff::TiffWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::tiff::TiffWriter, crate::tiff::TiffWriterOptions>),
             capabilities: &[
                 FormatCapability::MultiImage,
                 FormatCapability::Tiles,
@@ -262,6 +340,27 @@ impl FormatRegistry {
                 FormatCapability::Exif,
             ],
             read_deep_path: None, // TIFF doesn't support deep data
+            read_deep_memory: None,
+        });
+
+        #[cfg(feature = "softimage")]
+        self.register(FormatInfo {
+            name: "Softimage PIC",
+            extensions: &["pic"],
+            // Registered before Radiance HDR, which keeps the `pic` extension
+            can_read: crate::softimage::is_softimage_header,
+            read_path: |p| crate::softimage::read(p),
+            read_memory: |d| crate::softimage::SoftimageReader::new().read_from_memory(d),
+            read_subimage_path: None,
+            read_subimage_memory: None,
+            num_subimages: None,
+            num_miplevels: None,
+            write_path: Some(|p, i| crate::softimage::write(p, i)),
+            write_memory: Some(|i| crate::softimage::SoftimageWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::softimage::SoftimageWriter, crate::softimage::SoftimageWriterOptions>),
+            capabilities: &[FormatCapability::IoProxy],
+            read_deep_path: None, // Softimage PIC doesn't support deep data
+            read_deep_memory: None,
         });
 
         #[cfg(feature = "hdr")]
@@ -272,12 +371,206 @@ impl FormatRegistry {
             read_path: |p| crate::hdr::read(p),
             read_memory: |d| crate::hdr::HdrReader::new().read_from_memory(d),
             read_subimage_path: None,
+            read_subimage_memory: None,
             num_subimages: None,
             num_miplevels: None,
             write_path: Some(|p, i| crate::hdr::write(p, i)),
             write_memory: Some(|i| crate::hdr::HdrWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::hdr::HdrWriter, crate::hdr::HdrWriterOptions>),
             capabilities: &[FormatCapability::IoProxy],
             read_deep_path: None, // HDR doesn't support deep data
+            read_deep_memory: None,
+        });
+
+        #[cfg(feature = "bmp")]
+        self.register(FormatInfo {
+            name: "BMP",
+            extensions: &["bmp", "dib"],
+            can_read: crate::bmp::is_bmp_header,
+            read_path: |p| crate::bmp::read(p),
+            read_memory: |d| crate::bmp::BmpReader::new().read_from_memory(d),
+            read_subimage_path: None,
+            read_subimage_memory: None,
+            num_subimages: None,
+            num_miplevels: None,
+            write_path: Some(|p, i| crate::bmp::write(p, i)),
+            write_memory: Some(|i| crate::bmp::BmpWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::bmp::BmpWriter, crate::bmp::BmpWriterOptions>),
+            capabilities: &[FormatCapability::IoProxy],
+            read_deep_path: None, // BMP doesn't support deep data
+            read_deep_memory: None,
+        });
+
+        #[cfg(feature = "tga")]
+        self.register(FormatInfo {
+            name: "TGA",
+            extensions: &["tga", "tpic", "icb", "vda", "vst"],
+            // No magic bytes, the header fields are checked for consistency
+            can_read: crate::tga::is_tga_header,
+            read_path: |p| crate::tga::read(p),
+            read_memory: |d| crate::tga::TgaReader::new().read_from_memory(d),
+            read_subimage_path: None,
+            read_subimage_memory: None,
+            num_subimages: None,
+            num_miplevels: None,
+            write_path: Some(|p, i| crate::tga::write(p, i)),
+            write_memory: Some(|i| crate::tga::TgaWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::tga::TgaWriter, crate::tga::TgaWriterOptions>),
+            capabilities: &[FormatCapability::IoProxy],
+            read_deep_path: None, // TGA doesn't support deep data
+            read_deep_memory: None,
+        });
+
+        #[cfg(feature = "cineon")]
+        self.register(FormatInfo {
+            name: "Cineon",
+            extensions: &["cin"],
+            can_read: crate::cineon::is_cineon_header,
+            read_path: |p| crate::cineon::read(p),
+            read_memory: |d| crate::cineon::CineonReader::new().read_from_memory(d),
+            read_subimage_path: None,
+            read_subimage_memory: None,
+            num_subimages: None,
+            num_miplevels: None,
+            write_path: Some(|p, i| crate::cineon::write(p, i)),
+            write_memory: Some(|i| crate::cineon::CineonWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::cineon::CineonWriter, crate::cineon::CineonWriterOptions>),
+            capabilities: &[FormatCapability::IoProxy],
+            read_deep_path: None, // Cineon doesn't support deep data
+            read_deep_memory: None,
+        });
+
+        #[cfg(feature = "pnm")]
+        self.register(FormatInfo {
+            name: "Netpbm",
+            extensions: &["pnm", "pbm", "pgm", "ppm", "pam"],
+            can_read: crate::pnm::is_pnm_header,
+            read_path: |p| crate::pnm::read(p),
+            read_memory: |d| crate::pnm::PnmReader::new().read_from_memory(d),
+            read_subimage_path: None,
+            read_subimage_memory: None,
+            num_subimages: None,
+            num_miplevels: None,
+            write_path: Some(|p, i| crate::pnm::write(p, i)),
+            write_memory: Some(|i| crate::pnm::PnmWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::pnm::PnmWriter, crate::pnm::PnmWriterOptions>),
+            capabilities: &[FormatCapability::IoProxy],
+            read_deep_path: None, // Netpbm doesn't support deep data
+            read_deep_memory: None,
+        });
+
+        #[cfg(feature = "pfm")]
+        self.register(FormatInfo {
+            name: "PFM",
+            extensions: &["pfm"],
+            can_read: crate::pfm::is_pfm_header,
+            read_path: |p| crate::pfm::read(p),
+            read_memory: |d| crate::pfm::PfmReader::new().read_from_memory(d),
+            read_subimage_path: None,
+            read_subimage_memory: None,
+            num_subimages: None,
+            num_miplevels: None,
+            write_path: Some(|p, i| crate::pfm::write(p, i)),
+            write_memory: Some(|i| crate::pfm::PfmWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::pfm::PfmWriter, crate::pfm::PfmWriterOptions>),
+            capabilities: &[FormatCapability::IoProxy],
+            read_deep_path: None, // PFM doesn't support deep data
+            read_deep_memory: None,
+        });
+
+        #[cfg(feature = "sgi")]
+        self.register(FormatInfo {
+            name: "SGI",
+            extensions: &["sgi", "rgb", "rgba", "bw", "int", "inta"],
+            can_read: crate::sgi::is_sgi_header,
+            read_path: |p| crate::sgi::read(p),
+            read_memory: |d| crate::sgi::SgiReader::new().read_from_memory(d),
+            read_subimage_path: None,
+            read_subimage_memory: None,
+            num_subimages: None,
+            num_miplevels: None,
+            write_path: Some(|p, i| crate::sgi::write(p, i)),
+            write_memory: Some(|i| crate::sgi::SgiWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::sgi::SgiWriter, crate::sgi::SgiWriterOptions>),
+            capabilities: &[FormatCapability::IoProxy],
+            read_deep_path: None, // SGI doesn't support deep data
+            read_deep_memory: None,
+        });
+
+        #[cfg(feature = "iff")]
+        self.register(FormatInfo {
+            name: "Maya IFF",
+            extensions: &["iff", "tdi"],
+            can_read: crate::iff::is_iff_header,
+            read_path: |p| crate::iff::read(p),
+            read_memory: |d| crate::iff::IffReader::new().read_from_memory(d),
+            read_subimage_path: None,
+            read_subimage_memory: None,
+            num_subimages: None,
+            num_miplevels: None,
+            write_path: Some(|p, i| crate::iff::write(p, i)),
+            write_memory: Some(|i| crate::iff::IffWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::iff::IffWriter, crate::iff::IffWriterOptions>),
+            capabilities: &[FormatCapability::IoProxy],
+            read_deep_path: None, // IFF doesn't support deep data
+            read_deep_memory: None,
+        });
+
+        #[cfg(feature = "rla")]
+        self.register(FormatInfo {
+            name: "RLA",
+            extensions: &["rla", "rpf"],
+            can_read: crate::rla::is_rla_header,
+            read_path: |p| crate::rla::read(p),
+            read_memory: |d| crate::rla::RlaReader::new().read_from_memory(d),
+            read_subimage_path: None,
+            read_subimage_memory: None,
+            num_subimages: None,
+            num_miplevels: None,
+            write_path: Some(|p, i| crate::rla::write(p, i)),
+            write_memory: Some(|i| crate::rla::RlaWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::rla::RlaWriter, crate::rla::RlaWriterOptions>),
+            capabilities: &[FormatCapability::IoProxy],
+            read_deep_path: None, // RLA doesn't support deep data
+            read_deep_memory: None,
+        });
+
+        #[cfg(feature = "y4m")]
+        self.register(FormatInfo {
+            name: "Y4M",
+            extensions: &["y4m"],
+            can_read: crate::y4m::is_y4m_header,
+            // First frame only; use y4m::read_frames for sequences
+            read_path: |p| crate::y4m::read(p),
+            read_memory: |d| crate::y4m::Y4mReader::new().read_from_memory(d),
+            read_subimage_path: None,
+            read_subimage_memory: None,
+            num_subimages: None,
+            num_miplevels: None,
+            write_path: Some(|p, i| crate::y4m::write(p, i)),
+            write_memory: Some(|i| crate::y4m::Y4mWriter::new().write_to_memory(i)),
+            write_memory_options: Some(write_with_options::<crate::y4m::Y4mWriter, crate::y4m::Y4mWriterOptions>),
+            capabilities: &[FormatCapability::IoProxy],
+            read_deep_path: None, // Y4M doesn't support deep data
+            read_deep_memory: None,
+        });
+
+        self.register(FormatInfo {
+            name: "DNG",
+            extensions: &["dng"],
+            can_read: crate::dng::is_dng,
+            read_path: |p| crate::dng::read(p),
+            read_memory: |d| crate::dng::DngReader::new().read_from_memory(d),
+            read_subimage_path: None,
+            read_subimage_memory: None,
+            num_subimages: None,
+            num_miplevels: None,
+            write_path: None, // Camera raw, read-only
+            write_memory: None,
+            write_memory_options: None,
+            capabilities: &[FormatCapability::IoProxy],
+            read_deep_path: None, // DNG doesn't support deep data
+            read_deep_memory: None,
         });
     }
 
@@ -326,13 +619,25 @@ impl FormatRegistry {
         None
     }
 
+    /// Detects the format of a whole file held in memory.
+    ///
+    /// The magic bytes only identify the container, so TIFF data carrying a
+    /// DNGVersion tag is routed to the DNG reader before header detection
+    /// can hand it to the TIFF reader.
+    fn detect_data_format(&self, data: &[u8]) -> Option<&'static str> {
+        if self.formats.contains_key("DNG") && crate::dng::is_dng(data) {
+            return Some("DNG");
+        }
+        self.detect_format(&data[..data.len().min(28)])
+    }
+
     /// Reads an image from a file using auto-detection.
     ///
     /// First tries to detect format by magic bytes, falls back to extension.
     pub fn read(&self, path: &Path) -> IoResult<ImageData> {
         // Try magic bytes detection first
         let header = std::fs::read(path)?;
-        if let Some(name) = self.detect_format(&header[..header.len().min(16)]) {
+        if let Some(name) = self.detect_data_format(&header) {
             if let Some(info) = self.formats.get(name) {
                 return (info.read_memory)(&header);
             }
@@ -360,7 +665,7 @@ impl FormatRegistry {
     pub fn read_subimage(&self, path: &Path, subimage: usize, miplevel: usize) -> IoResult<ImageData> {
         // Detect format
         let header = std::fs::read(path)?;
-        let format_name = self.detect_format(&header[..header.len().min(16)])
+        let format_name = self.detect_data_format(&header)
             .or_else(|| path.extension().and_then(|e| e.to_str()).and_then(|ext| self.by_extension.get(ext.to_lowercase().as_str()).copied()));
         
         if let Some(name) = format_name {
@@ -390,7 +695,7 @@ impl FormatRegistry {
     /// Gets number of subimages in a file.
     pub fn num_subimages(&self, path: &Path) -> IoResult<usize> {
         let header = std::fs::read(path)?;
-        let format_name = self.detect_format(&header[..header.len().min(16)])
+        let format_name = self.detect_data_format(&header)
             .or_else(|| path.extension().and_then(|e| e.to_str()).and_then(|ext| self.by_extension.get(ext.to_lowercase().as_str()).copied()));
         
         if let Some(name) = format_name {
@@ -407,7 +712,7 @@ impl FormatRegistry {
     /// Gets number of miplevels for a subimage.
     pub fn num_miplevels(&self, path: &Path, subimage: usize) -> IoResult<usize> {
         let header = std::fs::read(path)?;
-        let format_name = self.detect_format(&header[..header.len().min(16)])
+        let format_name = self.detect_data_format(&header)
             .or_else(|| path.extension().and_then(|e| e.to_str()).and_then(|ext| self.by_extension.get(ext.to_lowercase().as_str()).copied()));
         
         if let Some(name) = format_name {
@@ -466,7 +771,7 @@ impl FormatRegistry {
     pub fn read_deep(&self, path: &Path) -> IoResult<DeepData> {
         // Detect format
         let header = std::fs::read(path)?;
-        let format_name = self.detect_format(&header[..header.len().min(16)])
+        let format_name = self.detect_data_format(&header)
             .or_else(|| path.extension().and_then(|e| e.to_str()).and_then(|ext| self.by_extension.get(ext.to_lowercase().as_str()).copied()));
         
         if let Some(name) = format_name {
@@ -487,6 +792,206 @@ impl FormatRegistry {
                 .to_string(),
         ))
     }
+
+    // ========================================================================
+    // Streams and Memory
+    // ========================================================================
+
+    /// Resolves a format hint to a registered format name.
+    ///
+    /// Accepts a format name ("OpenEXR"), an extension ("exr", ".exr")
+    /// or a file name ("shot.0001.exr").
+    fn format_for_hint(&self, hint: &str) -> Option<&'static str> {
+        if let Some(name) = self.formats.keys().find(|name| name.eq_ignore_ascii_case(hint)) {
+            return Some(name);
+        }
+        let ext = Path::new(hint)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_else(|| hint.trim_start_matches('.'));
+        self.by_extension.get(ext.to_lowercase().as_str()).copied()
+    }
+
+    /// Picks the format for in-memory data: magic bytes first, then the hint.
+    ///
+    /// Fails if the format can't be determined or doesn't declare
+    /// [`FormatCapability::IoProxy`].
+    fn resolve_memory_format(&self, data: &[u8], hint: Option<&str>) -> IoResult<&FormatInfo> {
+        let name = self
+            .detect_data_format(data)
+            .or_else(|| hint.and_then(|h| self.format_for_hint(h)))
+            .ok_or_else(|| IoError::UnsupportedFormat(hint.unwrap_or("unknown").to_string()))?;
+        self.proxy_format(name)
+    }
+
+    /// Returns format info by name if the format supports I/O proxies.
+    fn proxy_format(&self, name: &str) -> IoResult<&FormatInfo> {
+        let info = self
+            .get(name)
+            .ok_or_else(|| IoError::UnsupportedFormat(name.to_string()))?;
+        if !info.capabilities.contains(&FormatCapability::IoProxy) {
+            return Err(IoError::UnsupportedFeature(format!(
+                "format '{}' doesn't support stream I/O",
+                name
+            )));
+        }
+        Ok(info)
+    }
+
+    /// Reads the rest of a stream and resolves its format.
+    ///
+    /// Detection sees the whole data, as telling DNG from TIFF needs the
+    /// first IFD. A stream whose format can't be determined is rewound to
+    /// where it was.
+    fn read_stream<R: ReadSeek + ?Sized>(
+        &self,
+        reader: &mut R,
+        hint: Option<&str>,
+    ) -> IoResult<(&FormatInfo, Vec<u8>)> {
+        let start = reader.stream_position()?;
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+
+        match self.resolve_memory_format(&data, hint) {
+            Ok(info) => Ok((info, data)),
+            Err(e) => {
+                reader.seek(SeekFrom::Start(start))?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Reads an image from memory using auto-detection.
+    ///
+    /// First tries to detect format by magic bytes, falls back to `hint`.
+    pub fn read_from_memory(&self, data: &[u8], hint: Option<&str>) -> IoResult<ImageData> {
+        let info = self.resolve_memory_format(data, hint)?;
+        (info.read_memory)(data)
+    }
+
+    /// Reads an image from a stream using auto-detection.
+    ///
+    /// Reads from the current position to the end of the stream.
+    /// First tries to detect format by magic bytes, falls back to `hint`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use std::fs::File;
+    /// use vfx_io::registry::FormatRegistry;
+    ///
+    /// let mut file = File::open("archive_entry.bin")?;
+    /// let image = FormatRegistry::global().read_from(&mut file, Some("exr"))?;
+    /// ```
+    pub fn read_from<R: ReadSeek + ?Sized>(&self, reader: &mut R, hint: Option<&str>) -> IoResult<ImageData> {
+        let (info, data) = self.read_stream(reader, hint)?;
+        (info.read_memory)(&data)
+    }
+
+    /// Reads a specific subimage/miplevel from memory.
+    ///
+    /// Falls back to regular read for subimage=0, miplevel=0 if format
+    /// doesn't have subimage support.
+    pub fn read_subimage_from_memory(
+        &self,
+        data: &[u8],
+        hint: Option<&str>,
+        subimage: usize,
+        miplevel: usize,
+    ) -> IoResult<ImageData> {
+        let info = self.resolve_memory_format(data, hint)?;
+        if let Some(read_sub) = info.read_subimage_memory {
+            return read_sub(data, subimage, miplevel);
+        }
+        if subimage == 0 && miplevel == 0 {
+            return (info.read_memory)(data);
+        }
+        Err(IoError::UnsupportedFeature(format!(
+            "format '{}' doesn't support subimage {} miplevel {}",
+            info.name, subimage, miplevel
+        )))
+    }
+
+    /// Reads a specific subimage/miplevel from a stream.
+    pub fn read_subimage_from<R: ReadSeek + ?Sized>(
+        &self,
+        reader: &mut R,
+        hint: Option<&str>,
+        subimage: usize,
+        miplevel: usize,
+    ) -> IoResult<ImageData> {
+        let (info, data) = self.read_stream(reader, hint)?;
+        self.read_subimage_from_memory(&data, Some(info.name), subimage, miplevel)
+    }
+
+    /// Reads deep data from memory.
+    ///
+    /// Returns error if format doesn't support deep data.
+    pub fn read_deep_from_memory(&self, data: &[u8], hint: Option<&str>) -> IoResult<DeepData> {
+        let info = self.resolve_memory_format(data, hint)?;
+        match info.read_deep_memory {
+            Some(read_deep) => read_deep(data),
+            None => Err(IoError::UnsupportedFeature(format!(
+                "format '{}' doesn't support deep data",
+                info.name
+            ))),
+        }
+    }
+
+    /// Reads deep data from a stream.
+    pub fn read_deep_from<R: ReadSeek + ?Sized>(&self, reader: &mut R, hint: Option<&str>) -> IoResult<DeepData> {
+        let (info, data) = self.read_stream(reader, hint)?;
+        self.read_deep_from_memory(&data, Some(info.name))
+    }
+
+    /// Writes an image to memory.
+    ///
+    /// `format` is a format name, extension or file name. `options` must be
+    /// the format's writer options type (e.g. `ExrWriterOptions`); `None`
+    /// uses the writer defaults.
+    pub fn write_to_memory(
+        &self,
+        image: &ImageData,
+        format: &str,
+        options: Option<&dyn Any>,
+    ) -> IoResult<Vec<u8>> {
+        let name = self
+            .format_for_hint(format)
+            .ok_or_else(|| IoError::UnsupportedFormat(format.to_string()))?;
+        let info = self.proxy_format(name)?;
+        let write_fn = match options {
+            None => info.write_memory.map(|write| write(image)),
+            Some(options) => info.write_memory_options.map(|write| write(image, options)),
+        };
+        write_fn.unwrap_or_else(|| {
+            Err(IoError::UnsupportedOperation(format!("format '{}' is read-only", name)))
+        })
+    }
+
+    /// Writes an image to a stream.
+    ///
+    /// See [`write_to_memory`](Self::write_to_memory) for `format` and `options`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use vfx_io::png::PngWriterOptions;
+    /// use vfx_io::registry::FormatRegistry;
+    ///
+    /// let mut out = Vec::new();
+    /// FormatRegistry::global().write_to(&mut out, &image, "png", None)?;
+    /// ```
+    pub fn write_to<W: Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        image: &ImageData,
+        format: &str,
+        options: Option<&dyn Any>,
+    ) -> IoResult<()> {
+        let data = self.write_to_memory(image, format, options)?;
+        writer.write_all(&data)?;
+        Ok(())
+    }
 }
 
 impl Default for FormatRegistry {
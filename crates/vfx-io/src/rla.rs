@@ -0,0 +1,1087 @@
+//! Wavefront RLA and 3ds Max RPF format support.
+//!
+//! Provides reading and writing of RLA images, the scanline format used by
+//! Wavefront and 3ds Max, including its color, matte and auxiliary channels.
+//!
+//! # Overview
+//!
+//! - Color (gray or RGB), matte and auxiliary channel groups
+//! - 8, 16 and 32-bit integer channels (byte-plane RLE) and float channels
+//! - Scanlines are stored bottom-up and returned top-down
+//! - An auxiliary channel tagged `depth` is exposed as `Z`
+//!
+//! [`RlaReader::read`] returns color plus the first matte channel as alpha.
+//! [`read_layers`] returns every channel as one layer named `default`: color
+//! as `R`, `G`, `B` (or `Y`), mattes as `A`, `A1`, ..., and auxiliary
+//! channels as `Z` or `Aux0`, `Aux1`, ....
+//!
+//! RPF files (`.rpf`) share the RLA layout and add the 3ds Max G-buffer:
+//! a channel mask in the first reserved header word selects typed records
+//! that follow the aux channels of each scanline. [`read_layers`] decodes
+//! them into named channels: `Z`, `MaterialID`, `ObjectID`, `U`/`V`,
+//! `Normal` (packed), `RealPix.*`, `Coverage`, `Background.*`, `RenderID`,
+//! `Color.*`, `Transparency.*`, `Velocity.X`/`Velocity.Y`, `Weight.*` and
+//! `SubpixelMask`. IDs, masks and the packed normal are integer channels.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use vfx_io::rla;
+//!
+//! let image = rla::read("render.rla")?;
+//!
+//! // Color, mattes and depth
+//! let layered = rla::read_layers("render.rla")?;
+//! ```
+
+use crate::{
+    AttrValue, ChannelSampleType, ChannelSamples, FormatReader, FormatWriter, ImageChannel,
+    ImageData, ImageLayer, IoError, IoResult, LayeredImage, Metadata, PixelData, PixelFormat,
+};
+use std::io::Write;
+use std::path::Path;
+
+/// Header size in bytes.
+const HEADER_SIZE: usize = 740;
+
+/// Header revision written by current producers.
+const REVISION: u16 = 0xFFFE;
+
+/// Channel type for 32-bit float data.
+const FLOAT_TYPE: i16 = 4;
+
+/// Largest image read, in samples.
+const MAX_SAMPLES: usize = 16384 * 16384 * 4;
+
+/// Header offset of the RPF G-buffer channel mask.
+const GBUFFER_MASK_OFFSET: usize = 700;
+
+/// How a G-buffer record's bytes map to channel samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GbufferEncoding {
+    /// Big-endian 32-bit floats, one per channel.
+    Float,
+    /// One unsigned big-endian integer.
+    Int,
+    /// Normalized bytes, one per channel.
+    Byte,
+    /// Shared-exponent RGB (RGBE).
+    RealPixel,
+}
+
+/// A 3ds Max G-buffer channel: mask bit, bytes per pixel, names, encoding.
+type GbufferChannel = (u32, usize, &'static [&'static str], GbufferEncoding);
+
+/// G-buffer channels in file order.
+const GBUFFER_CHANNELS: [GbufferChannel; 14] = [
+    (1 << 0, 4, &["Z"], GbufferEncoding::Float),
+    (1 << 1, 1, &["MaterialID"], GbufferEncoding::Int),
+    (1 << 2, 2, &["ObjectID"], GbufferEncoding::Int),
+    (1 << 3, 8, &["U", "V"], GbufferEncoding::Float),
+    (1 << 4, 4, &["Normal"], GbufferEncoding::Int),
+    (1 << 5, 4, &["RealPix.R", "RealPix.G", "RealPix.B"], GbufferEncoding::RealPixel),
+    (1 << 6, 1, &["Coverage"], GbufferEncoding::Byte),
+    (1 << 7, 3, &["Background.R", "Background.G", "Background.B"], GbufferEncoding::Byte),
+    (1 << 8, 2, &["RenderID"], GbufferEncoding::Int),
+    (1 << 9, 3, &["Color.R", "Color.G", "Color.B"], GbufferEncoding::Byte),
+    (1 << 10, 3, &["Transparency.R", "Transparency.G", "Transparency.B"], GbufferEncoding::Byte),
+    (1 << 11, 8, &["Velocity.X", "Velocity.Y"], GbufferEncoding::Float),
+    (1 << 12, 3, &["Weight.R", "Weight.G", "Weight.B"], GbufferEncoding::Byte),
+    (1 << 13, 2, &["SubpixelMask"], GbufferEncoding::Int),
+];
+
+/// Text fields mapped to standard attribute names: (offset, length, key).
+const TEXT_FIELDS: [(usize, usize, &str); 15] = [
+    (44, 24, "RLA:RedChroma"),
+    (68, 24, "RLA:GreenChroma"),
+    (92, 24, "RLA:BlueChroma"),
+    (116, 24, "RLA:WhitePoint"),
+    (144, 128, "DocumentName"),
+    (272, 128, "ImageDescription"),
+    (400, 64, "Software"),
+    (464, 32, "HostComputer"),
+    (496, 32, "Artist"),
+    (528, 20, "DateTime"),
+    (548, 24, "RLA:Aspect"),
+    (572, 8, "RLA:AspectRatio"),
+    (580, 32, "RLA:ColorChannel"),
+    (614, 12, "RLA:Time"),
+    (626, 32, "RLA:Filter"),
+];
+
+// ============================================================================
+// Reader Options
+// ============================================================================
+
+/// Options for reading RLA files.
+///
+/// Currently minimal - RLA reading is mostly automatic.
+#[derive(Debug, Clone, Default)]
+pub struct RlaReaderOptions {
+    /// Reserved for future use.
+    _reserved: (),
+}
+
+// ============================================================================
+// Writer Options
+// ============================================================================
+
+/// Options for writing RLA files.
+///
+/// The channel type follows the image: 8-bit and 16-bit images are written
+/// as RLE integers, float images as raw floats.
+#[derive(Debug, Clone, Default)]
+pub struct RlaWriterOptions {
+    /// Reserved for future use.
+    _reserved: (),
+}
+
+// ============================================================================
+// Header
+// ============================================================================
+
+/// Sample type of a channel group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SampleType {
+    float: bool,
+    bits: u32,
+}
+
+impl SampleType {
+    fn parse(kind: i16, bits: i16) -> IoResult<Self> {
+        if kind == FLOAT_TYPE {
+            return Ok(Self { float: true, bits: 32 });
+        }
+        let bits = if bits <= 0 { 8 } else { bits as u32 };
+        if bits > 32 {
+            return Err(IoError::UnsupportedBitDepth(format!("RLA {}-bit channels", bits)));
+        }
+        Ok(Self { float: false, bits })
+    }
+
+    /// Byte planes per integer sample.
+    fn planes(&self) -> usize {
+        self.bits.div_ceil(8) as usize
+    }
+
+    /// Largest integer value, used for normalization.
+    fn max(&self) -> f32 {
+        ((1u64 << self.bits) - 1) as f32
+    }
+
+    fn kind(&self) -> i16 {
+        if self.float {
+            FLOAT_TYPE
+        } else {
+            0
+        }
+    }
+}
+
+/// Parsed header fields needed for decoding.
+#[derive(Debug, Clone)]
+struct RlaHeader {
+    width: usize,
+    height: usize,
+    num_color: usize,
+    num_matte: usize,
+    num_aux: usize,
+    color: SampleType,
+    matte: SampleType,
+    aux: SampleType,
+    aux_data: String,
+    gbuffer: u32,
+}
+
+impl RlaHeader {
+    fn parse(data: &[u8]) -> IoResult<Self> {
+        if data.len() < HEADER_SIZE || !is_rla_header(data) {
+            return Err(IoError::InvalidFile("not an RLA file".into()));
+        }
+        let (left, right) = (read_i16(data, 8), read_i16(data, 10));
+        let (bottom, top) = (read_i16(data, 12), read_i16(data, 14));
+        let count = |offset| read_i16(data, offset).max(0) as usize;
+
+        Ok(Self {
+            width: (right as i32 - left as i32 + 1) as usize,
+            height: (top as i32 - bottom as i32 + 1) as usize,
+            num_color: count(20),
+            num_matte: count(22),
+            num_aux: count(24),
+            color: SampleType::parse(read_i16(data, 18), read_i16(data, 658))?,
+            matte: SampleType::parse(read_i16(data, 660), read_i16(data, 662))?,
+            aux: SampleType::parse(read_i16(data, 664), read_i16(data, 666))?,
+            aux_data: read_cstr(&data[668..700]),
+            gbuffer: read_i32(data, GBUFFER_MASK_OFFSET) as u32,
+        })
+    }
+
+    /// Channel names and types in file order.
+    fn channels(&self) -> Vec<(String, SampleType)> {
+        let mut channels = Vec::new();
+        for i in 0..self.num_color {
+            let name = match (self.num_color, i) {
+                (1, _) => "Y".to_string(),
+                (_, 0..=2) => ["R", "G", "B"][i].to_string(),
+                _ => format!("C{}", i),
+            };
+            channels.push((name, self.color));
+        }
+        for i in 0..self.num_matte {
+            let name = if i == 0 { "A".to_string() } else { format!("A{}", i) };
+            channels.push((name, self.matte));
+        }
+        // A G-buffer Z takes the name
+        let depth = self.aux_data.eq_ignore_ascii_case("depth") && self.gbuffer & 1 == 0;
+        for i in 0..self.num_aux {
+            let name = if depth && i == 0 { "Z".to_string() } else { format!("Aux{}", i) };
+            channels.push((name, self.aux));
+        }
+        channels
+    }
+
+    /// G-buffer channels present, in file order.
+    fn gbuffer_channels(&self) -> Vec<GbufferChannel> {
+        GBUFFER_CHANNELS.into_iter().filter(|c| self.gbuffer & c.0 != 0).collect()
+    }
+
+    fn metadata(&self, data: &[u8]) -> Metadata {
+        let mut metadata = Metadata {
+            colorspace: Some(if self.color.float { "linear" } else { "sRGB" }.to_string()),
+            ..Metadata::default()
+        };
+        metadata.gamma = read_cstr(&data[28..44]).parse::<f32>().ok().filter(|g| *g > 0.0);
+        for (offset, len, key) in TEXT_FIELDS {
+            let value = read_cstr(&data[offset..offset + len]);
+            if !value.is_empty() {
+                metadata.attrs.set(key, AttrValue::Str(value));
+            }
+        }
+        let attrs = &mut metadata.attrs;
+        attrs.set("RLA:FrameNumber", AttrValue::Int(read_i16(data, 16) as i32));
+        attrs.set("RLA:JobNumber", AttrValue::Int(read_i32(data, 140)));
+        attrs.set("RLA:FieldRendered", AttrValue::Int(read_i16(data, 612) as i32));
+        attrs.set("RLA:BitsPerSample", AttrValue::UInt(self.color.bits));
+        if !self.aux_data.is_empty() {
+            attrs.set("RLA:AuxData", AttrValue::Str(self.aux_data.clone()));
+        }
+        metadata
+    }
+}
+
+/// Decoded channel planes, top-down.
+struct RlaPlanes {
+    header: RlaHeader,
+    channels: Vec<(String, SampleType)>,
+    planes: Vec<Vec<f32>>,
+    gbuffer: Vec<ImageChannel>,
+    metadata: Metadata,
+}
+
+// ============================================================================
+// RlaReader
+// ============================================================================
+
+/// RLA/RPF file reader.
+///
+/// Implements [`FormatReader`] for reading color and alpha; use
+/// [`RlaReader::read_layers`] to include every matte and aux channel.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::rla::RlaReader;
+/// use vfx_io::FormatReader;
+///
+/// let reader = RlaReader::new();
+/// let image = reader.read("render.rla")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct RlaReader {
+    #[allow(dead_code)]
+    options: RlaReaderOptions,
+}
+
+impl RlaReader {
+    /// Creates a new reader with default options.
+    pub fn new() -> Self {
+        Self::with_options(RlaReaderOptions::default())
+    }
+
+    /// Reads every channel as a single layer named `default`.
+    pub fn read_layers<P: AsRef<Path>>(&self, path: P) -> IoResult<LayeredImage> {
+        let data = std::fs::read(path.as_ref())?;
+        self.read_layers_from_memory(&data)
+    }
+
+    /// Reads every channel from a byte slice as a single layer.
+    pub fn read_layers_from_memory(&self, data: &[u8]) -> IoResult<LayeredImage> {
+        let RlaPlanes { header, channels, planes, gbuffer, metadata } = decode(data)?;
+        let channels = channels
+            .into_iter()
+            .zip(planes)
+            .map(|((name, _), samples)| ImageChannel {
+                kind: crate::channel_kind_from_name(&name, ChannelSampleType::F32),
+                name,
+                sample_type: ChannelSampleType::F32,
+                samples: ChannelSamples::F32(samples),
+                sampling: (1, 1),
+                quantize_linearly: false,
+            })
+            .chain(gbuffer)
+            .collect();
+        let layer = ImageLayer {
+            name: "default".to_string(),
+            width: header.width as u32,
+            height: header.height as u32,
+            channels,
+        };
+        Ok(LayeredImage { layers: vec![layer], metadata })
+    }
+}
+
+impl Default for RlaReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatReader<RlaReaderOptions> for RlaReader {
+    /// Returns "RLA".
+    fn format_name(&self) -> &'static str {
+        "RLA"
+    }
+
+    /// Returns `["rla", "rpf"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["rla", "rpf"]
+    }
+
+    /// Checks for the 0xFFFE revision and sane windows.
+    fn can_read(&self, header: &[u8]) -> bool {
+        is_rla_header(header)
+    }
+
+    /// Reads an RLA file from disk.
+    fn read<P: AsRef<Path>>(&self, path: P) -> IoResult<ImageData> {
+        let data = std::fs::read(path.as_ref())?;
+        self.read_from_memory(&data)
+    }
+
+    /// Reads an RLA from a byte slice.
+    fn read_from_memory(&self, data: &[u8]) -> IoResult<ImageData> {
+        let RlaPlanes { header, channels, planes, metadata, .. } = decode(data)?;
+        let count = header.num_color + header.num_matte.min(1);
+        if header.num_color == 0 {
+            return Err(IoError::MissingData("RLA file has no color channels".into()));
+        }
+
+        let types: Vec<SampleType> = channels[..count].iter().map(|c| c.1).collect();
+        let format = if types.iter().all(|t| !t.float && t.bits <= 8) {
+            PixelFormat::U8
+        } else if types.iter().all(|t| !t.float && t.bits <= 16) {
+            PixelFormat::U16
+        } else {
+            PixelFormat::F32
+        };
+
+        let pixels = header.width * header.height;
+        let mut samples = vec![0f32; pixels * count];
+        for (c, plane) in planes[..count].iter().enumerate() {
+            for (i, value) in plane.iter().enumerate() {
+                samples[i * count + c] = *value;
+            }
+        }
+
+        let (width, height) = (header.width as u32, header.height as u32);
+        let mut image = ImageData::new(width, height, count as u32, format);
+        image.data = match format {
+            PixelFormat::U8 => {
+                PixelData::U8(samples.iter().map(|v| (v * 255.0).round() as u8).collect())
+            }
+            PixelFormat::U16 => {
+                PixelData::U16(samples.iter().map(|v| (v * 65535.0).round() as u16).collect())
+            }
+            _ => PixelData::F32(samples),
+        };
+        image.metadata = metadata;
+        Ok(image)
+    }
+
+    /// Creates reader with custom options.
+    fn with_options(options: RlaReaderOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Decodes all channels into normalized, top-down planes.
+fn decode(data: &[u8]) -> IoResult<RlaPlanes> {
+    let header = RlaHeader::parse(data)?;
+    let (width, height) = (header.width, header.height);
+    let channels = header.channels();
+    let gbuffer_channels = header.gbuffer_channels();
+    let components: usize = gbuffer_channels.iter().map(|c| c.2.len()).sum();
+
+    let table_end = HEADER_SIZE + height * 4;
+    if data.len() < table_end {
+        return Err(IoError::InvalidFile("RLA scanline table truncated".into()));
+    }
+    // Scanlines may share records, so the file size doesn't bound the image
+    let pixels = width * height;
+    let count = channels.len() + components;
+    if pixels.checked_mul(count).is_none_or(|samples| samples > MAX_SAMPLES) {
+        return Err(IoError::InvalidFile(format!(
+            "RLA image too large: {}x{} with {} channels",
+            width, height, count
+        )));
+    }
+
+    let truncated = || IoError::InvalidFile("RLA scanline truncated".into());
+    let mut planes = vec![vec![0f32; pixels]; channels.len()];
+    let mut gbuffer: Vec<Vec<ChannelSamples>> = gbuffer_channels
+        .iter()
+        .map(|&(_, _, names, encoding)| {
+            let samples = match encoding {
+                GbufferEncoding::Int => ChannelSamples::U32(vec![0; pixels]),
+                _ => ChannelSamples::F32(vec![0.0; pixels]),
+            };
+            vec![samples; names.len()]
+        })
+        .collect();
+    for y in 0..height {
+        let mut pos = read_i32(data, HEADER_SIZE + y * 4).max(0) as usize;
+        let row = (height - 1 - y) * width;
+        for (plane, (_, sample_type)) in planes.iter_mut().zip(&channels) {
+            let len = data.get(pos..pos + 2).ok_or_else(truncated)?;
+            let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+            let record = data.get(pos + 2..pos + 2 + len).ok_or_else(truncated)?;
+            decode_channel(record, *sample_type, &mut plane[row..row + width])?;
+            pos += 2 + len;
+        }
+        for (planes, channel) in gbuffer.iter_mut().zip(&gbuffer_channels) {
+            let len = data.get(pos..pos + 2).ok_or_else(truncated)?;
+            let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+            let record = data.get(pos + 2..pos + 2 + len).ok_or_else(truncated)?;
+            decode_gbuffer(record, channel, row, width, planes)?;
+            pos += 2 + len;
+        }
+    }
+
+    let gbuffer = gbuffer
+        .into_iter()
+        .zip(&gbuffer_channels)
+        .flat_map(|(planes, &(_, _, names, _))| {
+            names.iter().zip(planes).map(|(name, samples)| {
+                let sample_type = match samples {
+                    ChannelSamples::U32(_) => ChannelSampleType::U32,
+                    ChannelSamples::F32(_) => ChannelSampleType::F32,
+                };
+                ImageChannel {
+                    kind: crate::channel_kind_from_name(name, sample_type),
+                    name: name.to_string(),
+                    sample_type,
+                    samples,
+                    sampling: (1, 1),
+                    quantize_linearly: false,
+                }
+            })
+        })
+        .collect();
+
+    let metadata = header.metadata(data);
+    Ok(RlaPlanes { header, channels, planes, gbuffer, metadata })
+}
+
+/// Decodes one G-buffer record of a scanline starting at pixel `row`.
+///
+/// Records hold one RLE byte plane per byte of the pixel, most significant
+/// first, like integer channels.
+fn decode_gbuffer(
+    record: &[u8],
+    channel: &GbufferChannel,
+    row: usize,
+    width: usize,
+    out: &mut [ChannelSamples],
+) -> IoResult<()> {
+    let &(_, size, _, encoding) = channel;
+    let mut bytes = vec![0u8; width * size];
+    let mut pos = 0usize;
+    for plane in 0..size {
+        for (x, byte) in decode_rle(record, &mut pos, width)?.into_iter().enumerate() {
+            bytes[x * size + plane] = byte;
+        }
+    }
+
+    for (x, px) in bytes.chunks_exact(size).enumerate() {
+        let i = row + x;
+        for (c, samples) in out.iter_mut().enumerate() {
+            match samples {
+                ChannelSamples::U32(values) => {
+                    values[i] = px.iter().fold(0u32, |acc, &b| (acc << 8) | b as u32);
+                }
+                ChannelSamples::F32(values) => {
+                    values[i] = match encoding {
+                        GbufferEncoding::Float => {
+                            f32::from_be_bytes([px[c * 4], px[c * 4 + 1], px[c * 4 + 2], px[c * 4 + 3]])
+                        }
+                        GbufferEncoding::RealPixel if px[3] == 0 => 0.0,
+                        GbufferEncoding::RealPixel => {
+                            (px[c] as f32 + 0.5) * 2f32.powi(px[3] as i32 - 136)
+                        }
+                        _ => px[c] as f32 / 255.0,
+                    };
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Decodes one channel of a scanline.
+fn decode_channel(record: &[u8], sample_type: SampleType, out: &mut [f32]) -> IoResult<()> {
+    let width = out.len();
+    if sample_type.float {
+        if record.len() < width * 4 {
+            return Err(IoError::InvalidFile("RLA float scanline truncated".into()));
+        }
+        for (value, bytes) in out.iter_mut().zip(record.chunks_exact(4)) {
+            *value = f32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        return Ok(());
+    }
+
+    // Byte planes, most significant first
+    let mut values = vec![0u32; width];
+    let mut pos = 0usize;
+    for _ in 0..sample_type.planes() {
+        let plane = decode_rle(record, &mut pos, width)?;
+        for (value, byte) in values.iter_mut().zip(plane) {
+            *value = (*value << 8) | byte as u32;
+        }
+    }
+    let max = sample_type.max();
+    for (value, raw) in out.iter_mut().zip(values) {
+        *value = raw as f32 / max;
+    }
+    Ok(())
+}
+
+/// Decodes `count` bytes of RLE.
+///
+/// A signed count byte `n >= 0` repeats the next byte `n + 1` times;
+/// `n < 0` copies `-n` literal bytes.
+fn decode_rle(data: &[u8], pos: &mut usize, count: usize) -> IoResult<Vec<u8>> {
+    let truncated = || IoError::InvalidFile("RLA RLE data truncated".into());
+    let mut out = Vec::with_capacity(count);
+    while out.len() < count {
+        let control = *data.get(*pos).ok_or_else(truncated)? as i8;
+        *pos += 1;
+        if control >= 0 {
+            let value = *data.get(*pos).ok_or_else(truncated)?;
+            *pos += 1;
+            out.extend(std::iter::repeat_n(value, control as usize + 1));
+        } else {
+            let n = control.unsigned_abs() as usize;
+            out.extend_from_slice(data.get(*pos..*pos + n).ok_or_else(truncated)?);
+            *pos += n;
+        }
+    }
+    out.truncate(count);
+    Ok(out)
+}
+
+// ============================================================================
+// RlaWriter
+// ============================================================================
+
+/// RLA file writer.
+///
+/// Implements [`FormatWriter`] for writing gray, RGB and RGBA RLA files.
+/// Use [`RlaWriter::write_layers`] to add a depth channel.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::rla::RlaWriter;
+/// use vfx_io::FormatWriter;
+///
+/// let writer = RlaWriter::new();
+/// writer.write("output.rla", &image)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct RlaWriter {
+    #[allow(dead_code)]
+    options: RlaWriterOptions,
+}
+
+impl RlaWriter {
+    /// Creates a new writer with default options.
+    pub fn new() -> Self {
+        Self::with_options(RlaWriterOptions::default())
+    }
+
+    /// Writes a single layer with color, optional `A` and aux channels.
+    ///
+    /// Channels other than `R`, `G`, `B` and `A` become float aux channels,
+    /// with `Z` first; layered input is float, so color is written as float.
+    pub fn write_layers<P: AsRef<Path>>(&self, path: P, image: &LayeredImage) -> IoResult<()> {
+        let bytes = self.write_layers_to_memory(image)?;
+        std::fs::write(path.as_ref(), bytes)?;
+        Ok(())
+    }
+
+    /// Writes a layered image to a byte vector.
+    pub fn write_layers_to_memory(&self, image: &LayeredImage) -> IoResult<Vec<u8>> {
+        let layer = match image.layers.as_slice() {
+            [layer] => layer,
+            [] => return Err(IoError::MissingData("no layers to write".into())),
+            _ => return Err(IoError::EncodeError("RLA holds a single layer".into())),
+        };
+        let has = |name: &str| layer.channels.iter().any(|c| c.name == name);
+        let order: &[&str] = if has("A") { &["R", "G", "B", "A"] } else { &["R", "G", "B"] };
+        let mut color = layer.to_image_data_with_order(order)?;
+        color.metadata = image.metadata.clone();
+
+        let mut aux: Vec<&ImageChannel> = layer
+            .channels
+            .iter()
+            .filter(|c| !["R", "G", "B", "A"].contains(&c.name.as_str()))
+            .collect();
+        aux.sort_by_key(|c| c.name != "Z");
+        let depth = aux.first().is_some_and(|c| c.name == "Z");
+        let aux: Vec<Vec<f32>> = aux.iter().map(|c| c.samples.to_f32()).collect();
+
+        let mut buffer = Vec::new();
+        self.write_impl(&mut buffer, &color, &aux, depth)?;
+        Ok(buffer)
+    }
+
+    /// Internal write implementation.
+    fn write_impl<W: Write>(
+        &self,
+        mut writer: W,
+        image: &ImageData,
+        aux: &[Vec<f32>],
+        depth: bool,
+    ) -> IoResult<()> {
+        let width = image.width as usize;
+        let height = image.height as usize;
+        let channels = image.channels as usize;
+        if width == 0 || height == 0 || width > i16::MAX as usize || height > i16::MAX as usize {
+            return Err(IoError::EncodeError(format!("invalid RLA dimensions: {}x{}", width, height)));
+        }
+        if !(1..=4).contains(&channels) {
+            return Err(IoError::EncodeError(format!("unsupported channels: {}", channels)));
+        }
+        if aux.iter().any(|plane| plane.len() != width * height) {
+            return Err(IoError::EncodeError("RLA aux channel size does not match the image".into()));
+        }
+
+        let sample_type = match image.format {
+            PixelFormat::U8 => SampleType { float: false, bits: 8 },
+            PixelFormat::U16 => SampleType { float: false, bits: 16 },
+            _ => SampleType { float: true, bits: 32 },
+        };
+        let aux_type = SampleType { float: true, bits: 32 };
+        let num_color = if channels <= 2 { 1 } else { 3 };
+        let num_matte = channels - num_color;
+
+        // Planar samples in file order: color, matte, aux
+        let samples: Vec<u32> = match image.format {
+            PixelFormat::U8 => image.to_u8().into_iter().map(u32::from).collect(),
+            PixelFormat::U16 => image.to_u16().into_iter().map(u32::from).collect(),
+            _ => image.to_f32().into_iter().map(f32::to_bits).collect(),
+        };
+        let mut planes: Vec<(SampleType, Vec<u32>)> = (0..channels)
+            .map(|c| (sample_type, samples.iter().skip(c).step_by(channels).copied().collect()))
+            .collect();
+        for plane in aux {
+            planes.push((aux_type, plane.iter().map(|v| v.to_bits()).collect()));
+        }
+
+        let mut header = vec![0u8; HEADER_SIZE];
+        let window = [0, width as i16 - 1, 0, height as i16 - 1];
+        for (i, value) in window.iter().chain(&window).enumerate() {
+            header[i * 2..i * 2 + 2].copy_from_slice(&value.to_be_bytes());
+        }
+        let mut put = |offset: usize, value: i16| {
+            header[offset..offset + 2].copy_from_slice(&value.to_be_bytes());
+        };
+        put(18, sample_type.kind());
+        put(20, num_color as i16);
+        put(22, num_matte as i16);
+        put(24, aux.len() as i16);
+        put(26, REVISION as i16);
+        put(658, sample_type.bits as i16);
+        put(660, sample_type.kind());
+        put(662, sample_type.bits as i16);
+        put(664, aux_type.kind());
+        put(666, aux_type.bits as i16);
+        if let Some(gamma) = image.metadata.gamma {
+            write_cstr(&mut header[28..44], &format!("{:.4}", gamma));
+        }
+        for (offset, len, key) in TEXT_FIELDS {
+            if let Some(value) = image.metadata.attrs.get(key).and_then(|v| v.as_str()) {
+                write_cstr(&mut header[offset..offset + len], value);
+            }
+        }
+        if depth {
+            write_cstr(&mut header[668..700], "depth");
+        }
+
+        // Bottom-up scanlines after the offset table
+        let mut out = header;
+        out.resize(HEADER_SIZE + height * 4, 0);
+        let mut record = Vec::new();
+        for y in 0..height {
+            let offset = out.len() as i32;
+            out[HEADER_SIZE + y * 4..][..4].copy_from_slice(&offset.to_be_bytes());
+            let row = (height - 1 - y) * width;
+            for (plane_type, plane) in &planes {
+                record.clear();
+                encode_channel(&plane[row..row + width], *plane_type, &mut record);
+                let len = u16::try_from(record.len())
+                    .map_err(|_| IoError::EncodeError("RLA scanline too wide".into()))?;
+                out.extend_from_slice(&len.to_be_bytes());
+                out.extend_from_slice(&record);
+            }
+        }
+
+        writer.write_all(&out)?;
+        Ok(())
+    }
+}
+
+impl Default for RlaWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatWriter<RlaWriterOptions> for RlaWriter {
+    /// Returns "RLA".
+    fn format_name(&self) -> &'static str {
+        "RLA"
+    }
+
+    /// Returns `["rla", "rpf"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["rla", "rpf"]
+    }
+
+    /// Writes an RLA file to disk.
+    fn write<P: AsRef<Path>>(&self, path: P, image: &ImageData) -> IoResult<()> {
+        let file = std::fs::File::create(path.as_ref())?;
+        self.write_impl(std::io::BufWriter::new(file), image, &[], false)
+    }
+
+    /// Writes an RLA to a byte vector.
+    fn write_to_memory(&self, image: &ImageData) -> IoResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.write_impl(&mut buffer, image, &[], false)?;
+        Ok(buffer)
+    }
+
+    /// Creates writer with custom options.
+    fn with_options(options: RlaWriterOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Encodes one channel of a scanline: raw floats or RLE byte planes.
+fn encode_channel(values: &[u32], sample_type: SampleType, out: &mut Vec<u8>) {
+    if sample_type.float {
+        for value in values {
+            out.extend_from_slice(&value.to_be_bytes());
+        }
+        return;
+    }
+    let planes = sample_type.planes();
+    for plane in 0..planes {
+        let shift = 8 * (planes - 1 - plane);
+        let bytes: Vec<u8> = values.iter().map(|v| (v >> shift) as u8).collect();
+        encode_rle(&bytes, out);
+    }
+}
+
+/// Encodes bytes as signed-count RLE packets of at most 128 bytes.
+fn encode_rle(data: &[u8], out: &mut Vec<u8>) {
+    let len = data.len();
+    let mut x = 0usize;
+
+    while x < len {
+        let mut run = 1usize;
+        while x + run < len && run < 128 && data[x + run] == data[x] {
+            run += 1;
+        }
+        if run >= 3 {
+            out.push((run - 1) as u8);
+            out.push(data[x]);
+            x += run;
+            continue;
+        }
+
+        // Literal bytes until the next run of 3
+        let start = x;
+        while x < len && x - start < 128 {
+            if x + 2 < len && data[x] == data[x + 1] && data[x] == data[x + 2] {
+                break;
+            }
+            x += 1;
+        }
+        out.push((-((x - start) as i32)) as i8 as u8);
+        out.extend_from_slice(&data[start..x]);
+    }
+}
+
+// ============================================================================
+// Convenience Functions
+// ============================================================================
+
+/// Reads color and alpha of an RLA file.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::rla;
+///
+/// let image = rla::read("render.rla")?;
+/// ```
+pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
+    RlaReader::new().read(path)
+}
+
+/// Reads every channel of an RLA file as a single layer.
+pub fn read_layers<P: AsRef<Path>>(path: P) -> IoResult<LayeredImage> {
+    RlaReader::new().read_layers(path)
+}
+
+/// Writes an RLA file with default options.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::rla;
+///
+/// rla::write("output.rla", &image)?;
+/// ```
+pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
+    RlaWriter::new().write(path, image)
+}
+
+/// Writes a single layer with color, optional alpha and aux channels.
+pub fn write_layers<P: AsRef<Path>>(path: P, image: &LayeredImage) -> IoResult<()> {
+    RlaWriter::new().write_layers(path, image)
+}
+
+/// Checks for the 0xFFFE revision and non-empty windows.
+///
+/// Needs the first 28 header bytes.
+pub fn is_rla_header(h: &[u8]) -> bool {
+    if h.len() < 28 || read_u16(h, 26) != REVISION {
+        return false;
+    }
+    let ordered = |lo: usize, hi: usize| read_i16(h, lo) <= read_i16(h, hi);
+    ordered(0, 2) && ordered(4, 6) && ordered(8, 10) && ordered(12, 14)
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_i16(data: &[u8], offset: usize) -> i16 {
+    read_u16(data, offset) as i16
+}
+
+fn read_i32(data: &[u8], offset: usize) -> i32 {
+    i32::from_be_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn read_cstr(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
+
+fn write_cstr(dst: &mut [u8], value: &str) {
+    let len = value.len().min(dst.len() - 1);
+    dst[..len].copy_from_slice(&value.as_bytes()[..len]);
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests 8-bit roundtrips for gray through RGBA.
+    #[test]
+    fn test_roundtrip() {
+        for channels in 1..=4 {
+            let data: Vec<u8> = (0..40 * 5 * channels)
+                .map(|i| if i < 60 { 7 } else { (i * 13) as u8 })
+                .collect();
+            let mut image = ImageData::from_u8(40, 5, channels, data);
+            image.metadata.attrs.set("Software", AttrValue::Str("vfx-rs".into()));
+            image.metadata.gamma = Some(2.2);
+
+            let bytes = RlaWriter::new().write_to_memory(&image).expect("Write failed");
+            let loaded = RlaReader::new().read_from_memory(&bytes).expect("Read failed");
+            assert_eq!((loaded.width, loaded.height, loaded.channels), (40, 5, channels));
+            assert_eq!(loaded.format, PixelFormat::U8);
+            assert_eq!(loaded.to_u8(), image.to_u8(), "channels={}", channels);
+            assert_eq!(loaded.metadata.attrs.get("Software").and_then(|v| v.as_str()), Some("vfx-rs"));
+            assert_eq!(loaded.metadata.gamma, Some(2.2));
+        }
+    }
+
+    /// Tests 16-bit and float roundtrips.
+    #[test]
+    fn test_deep_roundtrip() {
+        let mut image = ImageData::new(9, 4, 3, PixelFormat::U16);
+        image.data = PixelData::U16((0..9 * 4 * 3).map(|i| (i as u16).wrapping_mul(1500)).collect());
+        let bytes = RlaWriter::new().write_to_memory(&image).expect("Write failed");
+        let loaded = RlaReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(loaded.format, PixelFormat::U16);
+        assert_eq!(loaded.to_u16(), image.to_u16());
+
+        let values: Vec<f32> = (0..9 * 4 * 4).map(|i| i as f32 * 0.25 - 3.0).collect();
+        let image = ImageData::from_f32(9, 4, 4, values.clone());
+        let bytes = RlaWriter::new().write_to_memory(&image).expect("Write failed");
+        let loaded = RlaReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(loaded.format, PixelFormat::F32);
+        assert_eq!(loaded.to_f32(), values);
+    }
+
+    /// Tests mattes and depth through layers.
+    #[test]
+    fn test_layers_with_z() {
+        let image = ImageData::from_f32(6, 3, 4, (0..72).map(|i| i as f32 / 72.0).collect());
+        let mut layered = image.to_layered("default");
+        let z: Vec<f32> = (0..18).map(|i| 10.0 + i as f32).collect();
+        layered.layers[0].channels.push(ImageChannel {
+            name: "Z".into(),
+            kind: crate::ChannelKind::Depth,
+            sample_type: ChannelSampleType::F32,
+            samples: ChannelSamples::F32(z.clone()),
+            sampling: (1, 1),
+            quantize_linearly: false,
+        });
+
+        let bytes = RlaWriter::new().write_layers_to_memory(&layered).expect("Write failed");
+        let loaded = RlaReader::new().read_layers_from_memory(&bytes).expect("Read failed");
+        let layer = &loaded.layers[0];
+        let names: Vec<&str> = layer.channels.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["R", "G", "B", "A", "Z"]);
+        assert_eq!(layer.channels[4].kind, crate::ChannelKind::Depth);
+        assert_eq!(layer.channels[4].samples.to_f32(), z);
+        assert_eq!(layer.to_image_data_with_order(&["R", "G", "B", "A"]).unwrap().to_f32(), image.to_f32());
+    }
+
+    /// Tests decoding of RPF G-buffer channels after the color channels.
+    #[test]
+    fn test_rpf_gbuffer() {
+        let color = ImageData::from_u8(3, 2, 3, (0..18).map(|i| i * 10).collect());
+        let rla = RlaWriter::new().write_to_memory(&color).expect("Write failed");
+        // Z, MaterialID, ObjectID, UV, RealPix and Coverage
+        let mask: u32 = 0b111_1111 & !(1 << 4);
+        let mut out = rla[..HEADER_SIZE].to_vec();
+        out[GBUFFER_MASK_OFFSET..][..4].copy_from_slice(&mask.to_be_bytes());
+        out.resize(HEADER_SIZE + 2 * 4, 0);
+
+        let pixel = |p: usize| -> Vec<Vec<u8>> {
+            let uv = [p as f32 * 0.1, 1.0 - p as f32 * 0.1];
+            vec![
+                (-(p as f32) - 1.5).to_be_bytes().to_vec(),
+                vec![p as u8 + 1],
+                (1000 + p as u16).to_be_bytes().to_vec(),
+                [uv[0].to_be_bytes(), uv[1].to_be_bytes()].concat(),
+                vec![128, 64, 32, 129],
+                vec![p as u8 * 40],
+            ]
+        };
+        let samples = color.to_u8();
+        for y in 0..2 {
+            let offset = out.len() as i32;
+            out[HEADER_SIZE + y * 4..][..4].copy_from_slice(&offset.to_be_bytes());
+            let row: Vec<usize> = (0..3).map(|x| (1 - y) * 3 + x).collect();
+            let mut records = Vec::new();
+            for c in 0..3 {
+                let mut record = Vec::new();
+                let values: Vec<u32> = row.iter().map(|&p| samples[p * 3 + c] as u32).collect();
+                encode_channel(&values, SampleType { float: false, bits: 8 }, &mut record);
+                records.push(record);
+            }
+            for channel in 0..6 {
+                let bytes: Vec<Vec<u8>> = row.iter().map(|&p| pixel(p)[channel].clone()).collect();
+                let mut record = Vec::new();
+                for plane in 0..bytes[0].len() {
+                    let plane: Vec<u8> = bytes.iter().map(|px| px[plane]).collect();
+                    encode_rle(&plane, &mut record);
+                }
+                records.push(record);
+            }
+            for record in records {
+                out.extend_from_slice(&(record.len() as u16).to_be_bytes());
+                out.extend_from_slice(&record);
+            }
+        }
+
+        let loaded = RlaReader::new().read_from_memory(&out).expect("Read failed");
+        assert_eq!(loaded.to_u8(), samples);
+
+        let layered = RlaReader::new().read_layers_from_memory(&out).expect("Read failed");
+        let layer = &layered.layers[0];
+        let names: Vec<&str> = layer.channels.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["R", "G", "B", "Z", "MaterialID", "ObjectID", "U", "V", "RealPix.R", "RealPix.G", "RealPix.B", "Coverage"]
+        );
+        let channel = |name: &str| layer.channels.iter().find(|c| c.name == name).unwrap();
+        let ids = |name: &str| match &channel(name).samples {
+            ChannelSamples::U32(values) => values.clone(),
+            ChannelSamples::F32(_) => panic!("{} is not an integer channel", name),
+        };
+        assert_eq!(channel("Z").kind, crate::ChannelKind::Depth);
+        assert_eq!(channel("Z").samples.to_f32(), [-1.5, -2.5, -3.5, -4.5, -5.5, -6.5]);
+        assert_eq!(channel("MaterialID").sample_type, ChannelSampleType::U32);
+        assert_eq!(ids("MaterialID"), [1, 2, 3, 4, 5, 6]);
+        assert_eq!(channel("ObjectID").kind, crate::ChannelKind::Id);
+        assert_eq!(ids("ObjectID"), (1000..1006).collect::<Vec<u32>>());
+        assert_eq!(channel("V").samples.to_f32()[4], 1.0 - 4.0 * 0.1);
+        assert_eq!(channel("RealPix.R").samples.to_f32(), [128.5 / 128.0; 6]);
+        assert_eq!(channel("RealPix.B").samples.to_f32(), [32.5 / 128.0; 6]);
+        assert_eq!(channel("Coverage").samples.to_f32()[5], 200.0 / 255.0);
+    }
+
+    /// Tests that oversized headers are rejected before allocating.
+    #[test]
+    fn test_huge_dimensions() {
+        let image = ImageData::from_u8(2, 2, 3, vec![7; 12]);
+        let mut bytes = RlaWriter::new().write_to_memory(&image).expect("Write failed");
+        // Active window of 65536x65536, with a complete scanline table
+        bytes[8..16].copy_from_slice(&[0x80, 0, 0x7F, 0xFF, 0x80, 0, 0x7F, 0xFF]);
+        bytes.resize(HEADER_SIZE + 65536 * 4, 0);
+        let result = RlaReader::new().read_from_memory(&bytes);
+        assert!(matches!(result, Err(IoError::InvalidFile(_))));
+    }
+
+    /// Tests header detection.
+    #[test]
+    fn test_can_read() {
+        let mut header = [0u8; 28];
+        header[2..4].copy_from_slice(&9i16.to_be_bytes());
+        header[10..12].copy_from_slice(&9i16.to_be_bytes());
+        header[26..28].copy_from_slice(&REVISION.to_be_bytes());
+        assert!(RlaReader::new().can_read(&header));
+
+        header[10..12].copy_from_slice(&(-5i16).to_be_bytes());
+        assert!(!RlaReader::new().can_read(&header));
+        assert!(!RlaReader::new().can_read(&header[..20]));
+    }
+}
@@ -0,0 +1,693 @@
+//! SGI image format support.
+//!
+//! Provides reading and writing of SGI (Silicon Graphics) images, the native
+//! format of IRIX-era tools that still turns up in render archives as
+//! `.rgb`, `.rgba`, `.bw` and `.sgi` files.
+//!
+//! # Overview
+//!
+//! - Verbatim and RLE storage
+//! - 8 and 16 bits per channel
+//! - Any number of channels; 1 to 4 map to gray, gray + alpha, RGB and RGBA
+//! - Rows are stored bottom-up and returned top-down
+//! - The 80-byte image name maps to `ImageDescription`
+//!
+//! Only the "normal" colormap mode is supported; the obsolete dithered,
+//! screen and colormap modes are rejected.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use vfx_io::sgi;
+//!
+//! let image = sgi::read("plate.rgb")?;
+//! sgi::write("output.sgi", &image)?;
+//! ```
+
+use crate::{AttrValue, FormatReader, FormatWriter, ImageData, IoError, IoResult, Metadata, PixelFormat};
+use std::io::Write;
+use std::path::Path;
+
+/// SGI magic number.
+const MAGIC: u16 = 474;
+
+/// Size of the fixed file header.
+const HEADER_SIZE: usize = 512;
+
+/// Largest RLE image read, in samples.
+const MAX_RLE_SAMPLES: usize = 16384 * 16384 * 4;
+
+// ============================================================================
+// Reader Options
+// ============================================================================
+
+/// Options for reading SGI files.
+///
+/// Currently minimal - SGI reading is mostly automatic.
+#[derive(Debug, Clone, Default)]
+pub struct SgiReaderOptions {
+    /// Reserved for future use.
+    _reserved: (),
+}
+
+// ============================================================================
+// Writer Options
+// ============================================================================
+
+/// Options for writing SGI files.
+///
+/// The sample size follows the image: 8-bit images are written with one
+/// byte per sample, everything else with two.
+#[derive(Debug, Clone)]
+pub struct SgiWriterOptions {
+    /// Use RLE compression. Default: true.
+    pub rle: bool,
+}
+
+impl Default for SgiWriterOptions {
+    fn default() -> Self {
+        Self { rle: true }
+    }
+}
+
+// ============================================================================
+// Header
+// ============================================================================
+
+/// Parsed file header.
+#[derive(Debug, Clone)]
+struct SgiHeader {
+    rle: bool,
+    bpc: usize,
+    width: usize,
+    height: usize,
+    channels: usize,
+    colormap: u32,
+    name: String,
+}
+
+impl SgiHeader {
+    fn parse(data: &[u8]) -> IoResult<Self> {
+        if !is_sgi_header(data) || data.len() < HEADER_SIZE {
+            return Err(IoError::InvalidFile("not an SGI file".into()));
+        }
+
+        // Dimension 1 is a single row, 2 a single channel
+        let dimension = read_u16(data, 4);
+        let width = read_u16(data, 6) as usize;
+        let height = if dimension == 1 { 1 } else { read_u16(data, 8) as usize };
+        let channels = if dimension < 3 { 1 } else { read_u16(data, 10) as usize };
+        if width == 0 || height == 0 || channels == 0 {
+            return Err(IoError::InvalidFile(format!(
+                "invalid SGI dimensions: {}x{}x{}",
+                width, height, channels
+            )));
+        }
+
+        Ok(Self {
+            rle: data[2] == 1,
+            bpc: data[3] as usize,
+            width,
+            height,
+            channels,
+            colormap: read_u32(data, 104),
+            name: read_cstr(&data[24..104]),
+        })
+    }
+}
+
+// ============================================================================
+// SgiReader
+// ============================================================================
+
+/// SGI file reader.
+///
+/// Implements [`FormatReader`] for reading SGI files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::sgi::SgiReader;
+/// use vfx_io::FormatReader;
+///
+/// let reader = SgiReader::new();
+/// let image = reader.read("plate.rgb")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct SgiReader {
+    #[allow(dead_code)]
+    options: SgiReaderOptions,
+}
+
+impl SgiReader {
+    /// Creates a new reader with default options.
+    pub fn new() -> Self {
+        Self::with_options(SgiReaderOptions::default())
+    }
+
+    /// Internal read implementation.
+    fn read_impl(&self, data: &[u8]) -> IoResult<ImageData> {
+        let header = SgiHeader::parse(data)?;
+        if header.colormap != 0 {
+            return Err(IoError::UnsupportedFeature(format!(
+                "SGI colormap mode {}",
+                header.colormap
+            )));
+        }
+
+        let SgiHeader { width, height, channels, bpc, .. } = header;
+        let too_large = || {
+            IoError::InvalidFile(format!("SGI dimensions too large: {}x{}x{}", width, height, channels))
+        };
+        let rows = height.checked_mul(channels).ok_or_else(too_large)?;
+        let sample_count = rows.checked_mul(width).ok_or_else(too_large)?;
+        let (starts, lengths) = if header.rle {
+            let table = data
+                .get(HEADER_SIZE..HEADER_SIZE.saturating_add(rows.saturating_mul(8)))
+                .ok_or_else(|| IoError::InvalidFile("SGI RLE tables truncated".into()))?;
+            let starts: Vec<usize> = (0..rows).map(|i| read_u32(table, i * 4) as usize).collect();
+            let lengths: Vec<usize> =
+                (0..rows).map(|i| read_u32(table, (rows + i) * 4) as usize).collect();
+            (starts, lengths)
+        } else {
+            let size = sample_count.checked_mul(bpc).and_then(|size| size.checked_add(HEADER_SIZE));
+            if size.is_none_or(|size| data.len() < size) {
+                return Err(IoError::InvalidFile("SGI pixel data truncated".into()));
+            }
+            (Vec::new(), Vec::new())
+        };
+        if header.rle && sample_count > MAX_RLE_SAMPLES {
+            // Rows may share RLE data, so the file size doesn't bound the image
+            return Err(too_large());
+        }
+
+        let mut samples = vec![0u16; sample_count];
+        for z in 0..channels {
+            for row in 0..height {
+                let index = z * height + row;
+                let scanline = if header.rle {
+                    let src = data
+                        .get(starts[index]..starts[index] + lengths[index])
+                        .ok_or_else(|| IoError::InvalidFile("SGI RLE row out of bounds".into()))?;
+                    decode_rle_row(src, bpc, width)?
+                } else {
+                    let start = HEADER_SIZE + index * width * bpc;
+                    let src = data
+                        .get(start..start + width * bpc)
+                        .ok_or_else(|| IoError::InvalidFile("SGI pixel data truncated".into()))?;
+                    (0..width).map(|x| read_sample(src, x, bpc)).collect()
+                };
+
+                // Bottom-up rows
+                let y = height - 1 - row;
+                for (x, value) in scanline.into_iter().enumerate() {
+                    samples[(y * width + x) * channels + z] = value;
+                }
+            }
+        }
+
+        let mut metadata = Metadata {
+            colorspace: Some("sRGB".to_string()),
+            ..Metadata::default()
+        };
+        if !header.name.is_empty() {
+            metadata.attrs.set("ImageDescription", AttrValue::Str(header.name));
+        }
+        metadata.attrs.set("SGI:BitsPerSample", AttrValue::UInt(bpc as u32 * 8));
+        metadata.attrs.set(
+            "SGI:Compression",
+            AttrValue::Str(if header.rle { "rle" } else { "none" }.into()),
+        );
+
+        let mut image = if bpc == 1 {
+            let data = samples.into_iter().map(|v| v as u8).collect();
+            ImageData::from_u8(width as u32, height as u32, channels as u32, data)
+        } else {
+            let mut image = ImageData::new(width as u32, height as u32, channels as u32, PixelFormat::U16);
+            image.data = crate::PixelData::U16(samples);
+            image
+        };
+        image.metadata = metadata;
+        Ok(image)
+    }
+}
+
+impl Default for SgiReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatReader<SgiReaderOptions> for SgiReader {
+    /// Returns "SGI".
+    fn format_name(&self) -> &'static str {
+        "SGI"
+    }
+
+    /// Returns `["sgi", "rgb", "rgba", "bw", "int", "inta"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["sgi", "rgb", "rgba", "bw", "int", "inta"]
+    }
+
+    /// Checks for the SGI magic number and a valid storage/depth pair.
+    fn can_read(&self, header: &[u8]) -> bool {
+        is_sgi_header(header)
+    }
+
+    /// Reads an SGI file from disk.
+    fn read<P: AsRef<Path>>(&self, path: P) -> IoResult<ImageData> {
+        let data = std::fs::read(path.as_ref())?;
+        self.read_impl(&data)
+    }
+
+    /// Reads an SGI image from a byte slice.
+    fn read_from_memory(&self, data: &[u8]) -> IoResult<ImageData> {
+        self.read_impl(data)
+    }
+
+    /// Creates reader with custom options.
+    fn with_options(options: SgiReaderOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Reads one big-endian sample of `bpc` bytes.
+fn read_sample(src: &[u8], index: usize, bpc: usize) -> u16 {
+    if bpc == 1 {
+        src[index] as u16
+    } else {
+        read_u16(src, index * 2)
+    }
+}
+
+/// Decodes one RLE row of `bpc`-byte units.
+///
+/// A unit with the high bit set copies `count` literal units, otherwise the
+/// next unit is repeated `count` times; a zero count ends the row.
+fn decode_rle_row(src: &[u8], bpc: usize, width: usize) -> IoResult<Vec<u16>> {
+    let units = src.len() / bpc;
+    let mut out = Vec::with_capacity(width);
+    let mut i = 0usize;
+    let truncated = || IoError::InvalidFile("SGI RLE row truncated".into());
+
+    while i < units {
+        let unit = read_sample(src, i, bpc);
+        i += 1;
+        let count = (unit & 0x7F) as usize;
+        if count == 0 {
+            break;
+        }
+        if unit & 0x80 != 0 {
+            if i + count > units {
+                return Err(truncated());
+            }
+            out.extend((i..i + count).map(|j| read_sample(src, j, bpc)));
+            i += count;
+        } else {
+            if i >= units {
+                return Err(truncated());
+            }
+            let value = read_sample(src, i, bpc);
+            i += 1;
+            out.extend(std::iter::repeat_n(value, count));
+        }
+    }
+
+    if out.len() < width {
+        return Err(truncated());
+    }
+    out.truncate(width);
+    Ok(out)
+}
+
+// ============================================================================
+// SgiWriter
+// ============================================================================
+
+/// SGI file writer.
+///
+/// Implements [`FormatWriter`] for writing SGI files. 8-bit images are
+/// written with one byte per sample and everything else with two.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::sgi::{SgiWriter, SgiWriterOptions};
+/// use vfx_io::FormatWriter;
+///
+/// let writer = SgiWriter::with_options(SgiWriterOptions { rle: false });
+/// writer.write("plate.rgb", &image)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct SgiWriter {
+    options: SgiWriterOptions,
+}
+
+impl SgiWriter {
+    /// Creates a new writer with default options.
+    pub fn new() -> Self {
+        Self::with_options(SgiWriterOptions::default())
+    }
+
+    /// Internal write implementation.
+    fn write_impl<W: Write>(&self, mut writer: W, image: &ImageData) -> IoResult<()> {
+        let width = image.width as usize;
+        let height = image.height as usize;
+        let channels = image.channels as usize;
+        if width == 0 || height == 0 || width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(IoError::EncodeError(format!("invalid SGI dimensions: {}x{}", width, height)));
+        }
+        if channels == 0 || channels > u16::MAX as usize {
+            return Err(IoError::EncodeError(format!("unsupported channels: {}", channels)));
+        }
+
+        let bpc = if image.format == PixelFormat::U8 { 1 } else { 2 };
+        let samples: Vec<u16> = if bpc == 1 {
+            image.to_u8().into_iter().map(u16::from).collect()
+        } else {
+            image.to_u16()
+        };
+
+        let mut out = Vec::with_capacity(HEADER_SIZE + image.sample_count() * bpc);
+        out.extend_from_slice(&MAGIC.to_be_bytes());
+        out.push(self.options.rle as u8);
+        out.push(bpc as u8);
+        out.extend_from_slice(&(if channels == 1 { 2u16 } else { 3 }).to_be_bytes());
+        out.extend_from_slice(&(width as u16).to_be_bytes());
+        out.extend_from_slice(&(height as u16).to_be_bytes());
+        out.extend_from_slice(&(channels as u16).to_be_bytes());
+        out.extend_from_slice(&0u32.to_be_bytes());
+        out.extend_from_slice(&(if bpc == 1 { 255u32 } else { 65535 }).to_be_bytes());
+        out.resize(24, 0);
+        let mut name = [0u8; 80];
+        if let Some(description) = image.metadata.attrs.get("ImageDescription").and_then(|v| v.as_str()) {
+            write_cstr(&mut name, description);
+        }
+        out.extend_from_slice(&name);
+        out.resize(HEADER_SIZE, 0);
+
+        // Rows of one channel, bottom-up
+        let samples = &samples;
+        let row = |z: usize, index: usize| {
+            let y = height - 1 - index;
+            (0..width).map(move |x| samples[(y * width + x) * channels + z])
+        };
+        let push = |out: &mut Vec<u8>, value: u16| {
+            if bpc == 1 {
+                out.push(value as u8);
+            } else {
+                out.extend_from_slice(&value.to_be_bytes());
+            }
+        };
+
+        if self.options.rle {
+            let rows = height * channels;
+            let table_start = out.len();
+            out.resize(table_start + rows * 8, 0);
+            for z in 0..channels {
+                for index in 0..height {
+                    let start = out.len();
+                    let values: Vec<u16> = row(z, index).collect();
+                    encode_rle_row(&values, &mut out, push);
+                    let entry = z * height + index;
+                    let offset = u32::try_from(start)
+                        .map_err(|_| IoError::EncodeError("SGI file exceeds 4 GB".into()))?;
+                    out[table_start + entry * 4..][..4].copy_from_slice(&offset.to_be_bytes());
+                    let length = (out.len() - start) as u32;
+                    out[table_start + (rows + entry) * 4..][..4].copy_from_slice(&length.to_be_bytes());
+                }
+            }
+        } else {
+            for z in 0..channels {
+                for index in 0..height {
+                    for value in row(z, index) {
+                        push(&mut out, value);
+                    }
+                }
+            }
+        }
+
+        writer.write_all(&out)?;
+        Ok(())
+    }
+}
+
+impl Default for SgiWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatWriter<SgiWriterOptions> for SgiWriter {
+    /// Returns "SGI".
+    fn format_name(&self) -> &'static str {
+        "SGI"
+    }
+
+    /// Returns `["sgi", "rgb", "rgba", "bw", "int", "inta"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["sgi", "rgb", "rgba", "bw", "int", "inta"]
+    }
+
+    /// Writes an SGI file to disk.
+    fn write<P: AsRef<Path>>(&self, path: P, image: &ImageData) -> IoResult<()> {
+        let file = std::fs::File::create(path.as_ref())?;
+        self.write_impl(std::io::BufWriter::new(file), image)
+    }
+
+    /// Writes an SGI image to a byte vector.
+    fn write_to_memory(&self, image: &ImageData) -> IoResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.write_impl(&mut buffer, image)?;
+        Ok(buffer)
+    }
+
+    /// Creates writer with custom options.
+    fn with_options(options: SgiWriterOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Encodes one row as RLE units, ending with a zero unit.
+fn encode_rle_row(row: &[u16], out: &mut Vec<u8>, push: impl Fn(&mut Vec<u8>, u16)) {
+    let width = row.len();
+    let mut x = 0usize;
+
+    while x < width {
+        let mut run = 1usize;
+        while x + run < width && run < 127 && row[x + run] == row[x] {
+            run += 1;
+        }
+        if run >= 3 {
+            push(out, run as u16);
+            push(out, row[x]);
+            x += run;
+            continue;
+        }
+
+        // Literal units until the next run of 3
+        let start = x;
+        while x < width
+            && x - start < 127
+            && !(x + 2 < width && row[x] == row[x + 1] && row[x] == row[x + 2])
+        {
+            x += 1;
+        }
+        push(out, 0x80 | (x - start) as u16);
+        for &value in &row[start..x] {
+            push(out, value);
+        }
+    }
+
+    push(out, 0);
+}
+
+// ============================================================================
+// Convenience Functions
+// ============================================================================
+
+/// Reads an SGI file with default options.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::sgi;
+///
+/// let image = sgi::read("plate.rgb")?;
+/// ```
+pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
+    SgiReader::new().read(path)
+}
+
+/// Writes an SGI file with default options (RLE compression).
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::sgi;
+///
+/// sgi::write("output.sgi", &image)?;
+/// ```
+pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
+    SgiWriter::new().write(path, image)
+}
+
+/// Checks for the SGI magic number with a valid storage and sample size.
+pub fn is_sgi_header(h: &[u8]) -> bool {
+    h.len() >= 4 && read_u16(h, 0) == MAGIC && h[2] <= 1 && matches!(h[3], 1 | 2)
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+/// Reads a NUL-terminated string field.
+fn read_cstr(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
+
+/// Writes a string into a fixed field, leaving room for the terminator.
+fn write_cstr(dst: &mut [u8], value: &str) {
+    let len = value.len().min(dst.len() - 1);
+    dst[..len].copy_from_slice(&value.as_bytes()[..len]);
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the verbatim 16-bit layout: planar, bottom-up, big-endian.
+    #[test]
+    fn test_verbatim_16bit_layout() {
+        // 2x2, two channels: 0x1000 * (c + 1) + 0x10 * y + x
+        let data: Vec<u16> = (0..8u16).map(|i| 0x1000 * (i % 2 + 1) + 0x10 * (i / 4) + (i / 2) % 2).collect();
+        let mut image = ImageData::new(2, 2, 2, PixelFormat::U16);
+        image.data = crate::PixelData::U16(data.clone());
+        image.metadata.attrs.set("ImageDescription", AttrValue::Str("plate".into()));
+
+        let writer = SgiWriter::with_options(SgiWriterOptions { rle: false });
+        let bytes = writer.write_to_memory(&image).expect("Write failed");
+        assert_eq!((bytes[2], bytes[3]), (0, 2));
+        assert_eq!(&bytes[24..30], b"plate\0");
+        let body: Vec<u8> = [0x1010u16, 0x1011, 0x1000, 0x1001, 0x2010, 0x2011, 0x2000, 0x2001]
+            .iter()
+            .flat_map(|v| v.to_be_bytes())
+            .collect();
+        assert_eq!(&bytes[HEADER_SIZE..], &body[..]);
+
+        let loaded = SgiReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(loaded.format, PixelFormat::U16);
+        assert_eq!(loaded.to_u16(), data);
+        assert_eq!(
+            loaded.metadata.attrs.get("ImageDescription").and_then(|v| v.as_str()),
+            Some("plate")
+        );
+    }
+
+    /// Tests the per-scanline offset and length tables of 16-bit RLE files.
+    #[test]
+    fn test_rle_16bit_tables() {
+        // 3x2 RGB: the top row is one color, the bottom row has no runs
+        let mut data = [0x1234u16, 0x5678, 0x9ABC].repeat(3);
+        data.extend(1..=9u16);
+        let mut image = ImageData::new(3, 2, 3, PixelFormat::U16);
+        image.data = crate::PixelData::U16(data.clone());
+
+        let bytes = SgiWriter::new().write_to_memory(&image).expect("Write failed");
+        assert_eq!((bytes[2], bytes[3]), (1, 2));
+        let entry = |i: usize| read_u32(&bytes, HEADER_SIZE + i * 4) as usize;
+        let offsets: Vec<usize> = (0..6).map(entry).collect();
+        let lengths: Vec<usize> = (6..12).map(entry).collect();
+
+        // Literal rows: count, 3 units, terminator; run rows: count, unit, terminator
+        assert_eq!(lengths, [10, 6, 10, 6, 10, 6]);
+        let mut expected_offset = HEADER_SIZE + 48;
+        for (offset, length) in offsets.iter().zip(&lengths) {
+            assert_eq!(*offset, expected_offset);
+            expected_offset += length;
+        }
+        assert_eq!(expected_offset, bytes.len());
+
+        // Channel 0 bottom row and channel 1 top row
+        assert_eq!(&bytes[offsets[0]..][..10], [0x00, 0x83, 0, 1, 0, 4, 0, 7, 0, 0]);
+        assert_eq!(&bytes[offsets[3]..][..6], [0x00, 0x03, 0x56, 0x78, 0, 0]);
+
+        let loaded = SgiReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(loaded.to_u16(), data);
+    }
+
+    /// Tests 16-bit RLE roundtrip.
+    #[test]
+    fn test_16bit_roundtrip() {
+        let data: Vec<u16> = (0..300 * 2 * 3).map(|i| if i < 600 { 4000 } else { (i as u16).wrapping_mul(97) }).collect();
+        let mut image = ImageData::new(300, 2, 3, PixelFormat::U16);
+        image.data = crate::PixelData::U16(data.clone());
+
+        let bytes = SgiWriter::new().write_to_memory(&image).expect("Write failed");
+        assert_eq!(bytes[3], 2);
+        let loaded = SgiReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(loaded.format, PixelFormat::U16);
+        assert_eq!(loaded.to_u16(), data);
+    }
+
+    /// Tests a hand-built RLE file with bottom-up rows.
+    #[test]
+    fn test_rle_bottom_up() {
+        let mut data = vec![0u8; HEADER_SIZE];
+        data[..12].copy_from_slice(&[0x01, 0xDA, 1, 1, 0, 2, 0, 3, 0, 2, 0, 1]);
+        let rows = [vec![0x03u8, 10, 0], vec![0x83, 1, 2, 3, 0]];
+        let mut offset = HEADER_SIZE + 16;
+        for row in &rows {
+            data.extend_from_slice(&(offset as u32).to_be_bytes());
+            offset += row.len();
+        }
+        for row in &rows {
+            data.extend_from_slice(&(row.len() as u32).to_be_bytes());
+        }
+        data.extend(rows.concat());
+
+        let image = SgiReader::new().read_from_memory(&data).expect("Read failed");
+        assert_eq!((image.width, image.height, image.channels), (3, 2, 1));
+        assert_eq!(image.to_u8(), [1, 2, 3, 10, 10, 10]);
+    }
+
+    /// Tests that oversized headers are rejected before allocating.
+    #[test]
+    fn test_huge_dimensions() {
+        let image = ImageData::from_u8(2, 2, 1, vec![1, 2, 3, 4]);
+        for rle in [false, true] {
+            let writer = SgiWriter::with_options(SgiWriterOptions { rle });
+            let mut bytes = writer.write_to_memory(&image).expect("Write failed");
+            bytes[6..10].copy_from_slice(&[0xFF; 4]);
+            if rle {
+                // Complete RLE tables, so only the image size is left to reject
+                bytes.resize(HEADER_SIZE + 65535 * 8, 0);
+            }
+            let result = SgiReader::new().read_from_memory(&bytes);
+            assert!(matches!(result, Err(IoError::InvalidFile(_))), "rle={}", rle);
+        }
+    }
+
+    /// Tests header detection.
+    #[test]
+    fn test_can_read() {
+        let reader = SgiReader::new();
+        assert!(reader.can_read(&[0x01, 0xDA, 1, 1, 0, 3]));
+        assert!(reader.can_read(&[0x01, 0xDA, 0, 2, 0, 3]));
+        assert!(!reader.can_read(&[0x01, 0xDA, 2, 1, 0, 3]));
+        assert!(!reader.can_read(&[0x89, 0x50, 0x4E, 0x47]));
+    }
+}
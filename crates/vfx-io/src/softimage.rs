@@ -0,0 +1,652 @@
+//! Softimage PIC format support.
+//!
+//! Provides reading and writing of Softimage 3D `.pic` images, the render
+//! output of Softimage|3D and Mental Ray setups of the 1990s.
+//!
+//! # Overview
+//!
+//! - Channel packets (R, G, B, A in any grouping) of 8 bits per channel
+//! - Uncompressed, pure RLE and mixed RLE packets
+//! - Scanlines are stored top-down
+//! - The comment maps to `ImageDescription`, the ratio to `PixelAspectRatio`
+//!   and the field flag to `Softimage:Fields`
+//!
+//! The `.pic` extension is shared with Radiance HDR; files are told apart by
+//! their magic number, and writing through [`crate::write`] with a `.pic`
+//! path still produces Radiance. Use [`write`] or the `"softimage"` format
+//! hint for Softimage output.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use vfx_io::softimage;
+//!
+//! let image = softimage::read("render.pic")?;
+//! softimage::write("output.pic", &image)?;
+//! ```
+
+use crate::{AttrValue, FormatReader, FormatWriter, ImageData, IoError, IoResult, Metadata};
+use std::io::Write;
+use std::path::Path;
+
+/// Softimage PIC magic number.
+const MAGIC: u32 = 0x5380_F634;
+
+/// Size of the fixed file header.
+const HEADER_SIZE: usize = 104;
+
+/// Version written to new files.
+const VERSION: f32 = 3.71;
+
+/// Channel bits of a packet, in sample order.
+const CHANNEL_BITS: [u8; 4] = [0x80, 0x40, 0x20, 0x10];
+
+/// Packet encodings.
+const UNCOMPRESSED: u8 = 0;
+const PURE_RLE: u8 = 1;
+const MIXED_RLE: u8 = 2;
+
+/// Largest RLE image read, in pixels.
+const MAX_RLE_PIXELS: usize = 16384 * 16384;
+
+/// Field flag names, indexed by value.
+const FIELDS: [&str; 4] = ["none", "odd", "even", "full"];
+
+// ============================================================================
+// Reader Options
+// ============================================================================
+
+/// Options for reading Softimage PIC files.
+///
+/// Currently minimal - PIC reading is mostly automatic.
+#[derive(Debug, Clone, Default)]
+pub struct SoftimageReaderOptions {
+    /// Reserved for future use.
+    _reserved: (),
+}
+
+// ============================================================================
+// Writer Options
+// ============================================================================
+
+/// Options for writing Softimage PIC files.
+#[derive(Debug, Clone)]
+pub struct SoftimageWriterOptions {
+    /// Use mixed RLE packets. Default: true.
+    pub rle: bool,
+}
+
+impl Default for SoftimageWriterOptions {
+    fn default() -> Self {
+        Self { rle: true }
+    }
+}
+
+// ============================================================================
+// Header
+// ============================================================================
+
+/// One channel packet: encoding and the channels it carries.
+#[derive(Debug, Clone, Copy)]
+struct Packet {
+    encoding: u8,
+    channels: u8,
+}
+
+impl Packet {
+    /// Offsets into an RGBA pixel, in sample order.
+    fn offsets(&self) -> Vec<usize> {
+        (0..4).filter(|&c| self.channels & CHANNEL_BITS[c] != 0).collect()
+    }
+}
+
+// ============================================================================
+// SoftimageReader
+// ============================================================================
+
+/// Softimage PIC file reader.
+///
+/// Implements [`FormatReader`] for reading Softimage PIC files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::softimage::SoftimageReader;
+/// use vfx_io::FormatReader;
+///
+/// let reader = SoftimageReader::new();
+/// let image = reader.read("render.pic")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct SoftimageReader {
+    #[allow(dead_code)]
+    options: SoftimageReaderOptions,
+}
+
+impl SoftimageReader {
+    /// Creates a new reader with default options.
+    pub fn new() -> Self {
+        Self::with_options(SoftimageReaderOptions::default())
+    }
+
+    /// Internal read implementation.
+    fn read_impl(&self, data: &[u8]) -> IoResult<ImageData> {
+        if !is_softimage_header(data) || data.len() < HEADER_SIZE {
+            return Err(IoError::InvalidFile("not a Softimage PIC file".into()));
+        }
+        let width = read_u16(data, 92) as usize;
+        let height = read_u16(data, 94) as usize;
+        if width == 0 || height == 0 {
+            return Err(IoError::InvalidFile(format!("invalid PIC dimensions: {}x{}", width, height)));
+        }
+
+        // Channel packets, chained until a packet clears the flag
+        let mut packets = Vec::new();
+        let mut pos = HEADER_SIZE;
+        loop {
+            let entry = data
+                .get(pos..pos + 4)
+                .ok_or_else(|| IoError::InvalidFile("PIC channel packets truncated".into()))?;
+            pos += 4;
+            if entry[1] != 8 {
+                return Err(IoError::UnsupportedBitDepth(format!("PIC {}-bit channels", entry[1])));
+            }
+            if entry[2] > MIXED_RLE {
+                return Err(IoError::UnsupportedFeature(format!("PIC packet encoding {}", entry[2])));
+            }
+            packets.push(Packet { encoding: entry[2], channels: entry[3] });
+            if entry[0] == 0 || packets.len() > 8 {
+                break;
+            }
+        }
+
+        let has_alpha = packets.iter().any(|p| p.channels & CHANNEL_BITS[3] != 0);
+        let channels = if has_alpha { 4 } else { 3 };
+
+        // Check the pixel data size before allocating, the dimensions come from the header
+        let pixels = width * height;
+        if packets.iter().all(|p| p.encoding == UNCOMPRESSED) {
+            let pixel_bytes: usize = packets.iter().map(|p| p.offsets().len()).sum();
+            if data.len() - pos < pixels * pixel_bytes {
+                return Err(IoError::InvalidFile("PIC pixel data truncated".into()));
+            }
+        } else if pixels > MAX_RLE_PIXELS {
+            // A few RLE bytes can describe any size, so there is no data to check against
+            return Err(IoError::InvalidFile(format!("PIC RLE image too large: {}x{}", width, height)));
+        }
+
+        let mut rgba = vec![0u8; width * 4];
+        let mut out = Vec::with_capacity(pixels * channels);
+        for _ in 0..height {
+            for packet in &packets {
+                let offsets = packet.offsets();
+                let size = offsets.len();
+                let mut row = vec![0u8; width * size];
+                pos = match packet.encoding {
+                    UNCOMPRESSED => {
+                        let src = data
+                            .get(pos..pos + row.len())
+                            .ok_or_else(|| IoError::InvalidFile("PIC pixel data truncated".into()))?;
+                        row.copy_from_slice(src);
+                        pos + row.len()
+                    }
+                    PURE_RLE => decode_pure_rle(data, pos, size, &mut row)?,
+                    _ => decode_mixed_rle(data, pos, size, &mut row)?,
+                };
+                for (x, px) in row.chunks_exact(size.max(1)).enumerate() {
+                    for (&offset, &value) in offsets.iter().zip(px) {
+                        rgba[x * 4 + offset] = value;
+                    }
+                }
+            }
+            for px in rgba.chunks_exact(4) {
+                out.extend_from_slice(&px[..channels]);
+            }
+        }
+
+        let mut metadata = Metadata {
+            colorspace: Some("sRGB".to_string()),
+            ..Metadata::default()
+        };
+        let comment = read_cstr(&data[8..88]);
+        if !comment.is_empty() {
+            metadata.attrs.set("ImageDescription", AttrValue::Str(comment));
+        }
+        let ratio = read_f32(data, 96);
+        if ratio.is_finite() && ratio > 0.0 {
+            metadata.attrs.set("PixelAspectRatio", AttrValue::Float(ratio));
+        }
+        if let Some(fields) = FIELDS.get(read_u16(data, 100) as usize) {
+            metadata.attrs.set("Softimage:Fields", AttrValue::Str(fields.to_string()));
+        }
+        metadata.attrs.set("Softimage:Version", AttrValue::Float(read_f32(data, 4)));
+
+        let mut image = ImageData::from_u8(width as u32, height as u32, channels as u32, out);
+        image.metadata = metadata;
+        Ok(image)
+    }
+}
+
+impl Default for SoftimageReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatReader<SoftimageReaderOptions> for SoftimageReader {
+    /// Returns "Softimage PIC".
+    fn format_name(&self) -> &'static str {
+        "Softimage PIC"
+    }
+
+    /// Returns `["pic"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pic"]
+    }
+
+    /// Checks for the Softimage PIC magic number.
+    fn can_read(&self, header: &[u8]) -> bool {
+        is_softimage_header(header)
+    }
+
+    /// Reads a Softimage PIC file from disk.
+    fn read<P: AsRef<Path>>(&self, path: P) -> IoResult<ImageData> {
+        let data = std::fs::read(path.as_ref())?;
+        self.read_impl(&data)
+    }
+
+    /// Reads a Softimage PIC from a byte slice.
+    fn read_from_memory(&self, data: &[u8]) -> IoResult<ImageData> {
+        self.read_impl(data)
+    }
+
+    /// Creates reader with custom options.
+    fn with_options(options: SoftimageReaderOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Decodes pure RLE: a count byte followed by one pixel, repeated.
+fn decode_pure_rle(data: &[u8], mut pos: usize, size: usize, row: &mut [u8]) -> IoResult<usize> {
+    let truncated = || IoError::InvalidFile("PIC RLE data truncated".into());
+    let mut x = 0usize;
+    while x < row.len() {
+        let count = *data.get(pos).ok_or_else(truncated)? as usize;
+        let px = data.get(pos + 1..pos + 1 + size).ok_or_else(truncated)?;
+        pos += 1 + size;
+        if x + count * size > row.len() {
+            return Err(IoError::InvalidFile("PIC RLE run overflows the scanline".into()));
+        }
+        for _ in 0..count {
+            row[x..x + size].copy_from_slice(px);
+            x += size;
+        }
+    }
+    Ok(pos)
+}
+
+/// Decodes mixed RLE.
+///
+/// A count below 128 copies `count + 1` literal pixels, above 128 repeats
+/// the next pixel `count - 127` times, and 128 takes a 16-bit repeat count.
+fn decode_mixed_rle(data: &[u8], mut pos: usize, size: usize, row: &mut [u8]) -> IoResult<usize> {
+    let truncated = || IoError::InvalidFile("PIC RLE data truncated".into());
+    let mut x = 0usize;
+    while x < row.len() {
+        let count = *data.get(pos).ok_or_else(truncated)? as usize;
+        pos += 1;
+        if count < 128 {
+            let len = (count + 1) * size;
+            if x + len > row.len() {
+                return Err(IoError::InvalidFile("PIC RLE run overflows the scanline".into()));
+            }
+            row[x..x + len].copy_from_slice(data.get(pos..pos + len).ok_or_else(truncated)?);
+            pos += len;
+            x += len;
+        } else {
+            let repeat = if count == 128 {
+                let bytes = data.get(pos..pos + 2).ok_or_else(truncated)?;
+                pos += 2;
+                read_u16(bytes, 0) as usize
+            } else {
+                count - 127
+            };
+            let px = data.get(pos..pos + size).ok_or_else(truncated)?;
+            pos += size;
+            if x + repeat * size > row.len() {
+                return Err(IoError::InvalidFile("PIC RLE run overflows the scanline".into()));
+            }
+            for _ in 0..repeat {
+                row[x..x + size].copy_from_slice(px);
+                x += size;
+            }
+        }
+    }
+    Ok(pos)
+}
+
+// ============================================================================
+// SoftimageWriter
+// ============================================================================
+
+/// Softimage PIC file writer.
+///
+/// Implements [`FormatWriter`] for writing 8-bit PIC files with an RGB
+/// packet and, for images with alpha, a separate alpha packet. Gray images
+/// are expanded to RGB.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::softimage::SoftimageWriter;
+/// use vfx_io::FormatWriter;
+///
+/// let writer = SoftimageWriter::new();
+/// writer.write("render.pic", &image)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct SoftimageWriter {
+    options: SoftimageWriterOptions,
+}
+
+impl SoftimageWriter {
+    /// Creates a new writer with default options.
+    pub fn new() -> Self {
+        Self::with_options(SoftimageWriterOptions::default())
+    }
+
+    /// Internal write implementation.
+    fn write_impl<W: Write>(&self, mut writer: W, image: &ImageData) -> IoResult<()> {
+        let width = image.width as usize;
+        let height = image.height as usize;
+        let channels = image.channels as usize;
+        if width == 0 || height == 0 || width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(IoError::EncodeError(format!("invalid PIC dimensions: {}x{}", width, height)));
+        }
+        if !(1..=4).contains(&channels) {
+            return Err(IoError::EncodeError(format!("unsupported channels: {}", channels)));
+        }
+        let has_alpha = channels == 2 || channels == 4;
+
+        let attrs = &image.metadata.attrs;
+        let mut out = Vec::with_capacity(HEADER_SIZE + 8 + image.sample_count());
+        out.extend_from_slice(&MAGIC.to_be_bytes());
+        out.extend_from_slice(&VERSION.to_be_bytes());
+        let mut comment = [0u8; 80];
+        if let Some(description) = attrs.get("ImageDescription").and_then(|v| v.as_str()) {
+            write_cstr(&mut comment, description);
+        }
+        out.extend_from_slice(&comment);
+        out.extend_from_slice(b"PICT");
+        out.extend_from_slice(&(width as u16).to_be_bytes());
+        out.extend_from_slice(&(height as u16).to_be_bytes());
+        let ratio = attrs.get("PixelAspectRatio").and_then(|v| v.as_f32()).unwrap_or(1.0);
+        out.extend_from_slice(&ratio.to_be_bytes());
+        let fields = attrs
+            .get("Softimage:Fields")
+            .and_then(|v| v.as_str())
+            .and_then(|name| FIELDS.iter().position(|&f| f == name))
+            .unwrap_or(3) as u16;
+        out.extend_from_slice(&fields.to_be_bytes());
+        out.extend_from_slice(&0u16.to_be_bytes());
+
+        let encoding = if self.options.rle { MIXED_RLE } else { UNCOMPRESSED };
+        let color = Packet { encoding, channels: 0xE0 };
+        let alpha = Packet { encoding, channels: 0x10 };
+        out.extend_from_slice(&[has_alpha as u8, 8, encoding, color.channels]);
+        if has_alpha {
+            out.extend_from_slice(&[0, 8, encoding, alpha.channels]);
+        }
+
+        let samples = image.to_u8();
+        let mut rgb = vec![0u8; width * 3];
+        let mut a = vec![0u8; width];
+        for row in samples.chunks_exact(width * channels) {
+            for (x, px) in row.chunks_exact(channels).enumerate() {
+                let (value, alpha) = match channels {
+                    1 => ([px[0]; 3], 255),
+                    2 => ([px[0]; 3], px[1]),
+                    3 => ([px[0], px[1], px[2]], 255),
+                    _ => ([px[0], px[1], px[2]], px[3]),
+                };
+                rgb[x * 3..x * 3 + 3].copy_from_slice(&value);
+                a[x] = alpha;
+            }
+
+            let mut write_packet = |data: &[u8], size: usize| {
+                if self.options.rle {
+                    encode_mixed_rle(data, size, &mut out);
+                } else {
+                    out.extend_from_slice(data);
+                }
+            };
+            write_packet(&rgb, 3);
+            if has_alpha {
+                write_packet(&a, 1);
+            }
+        }
+
+        writer.write_all(&out)?;
+        Ok(())
+    }
+}
+
+impl Default for SoftimageWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatWriter<SoftimageWriterOptions> for SoftimageWriter {
+    /// Returns "Softimage PIC".
+    fn format_name(&self) -> &'static str {
+        "Softimage PIC"
+    }
+
+    /// Returns `["pic"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pic"]
+    }
+
+    /// Writes a Softimage PIC file to disk.
+    fn write<P: AsRef<Path>>(&self, path: P, image: &ImageData) -> IoResult<()> {
+        let file = std::fs::File::create(path.as_ref())?;
+        self.write_impl(std::io::BufWriter::new(file), image)
+    }
+
+    /// Writes a Softimage PIC to a byte vector.
+    fn write_to_memory(&self, image: &ImageData) -> IoResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.write_impl(&mut buffer, image)?;
+        Ok(buffer)
+    }
+
+    /// Creates writer with custom options.
+    fn with_options(options: SoftimageWriterOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Encodes one scanline of `size`-byte pixels as mixed RLE.
+fn encode_mixed_rle(row: &[u8], size: usize, out: &mut Vec<u8>) {
+    let width = row.len() / size;
+    let px = |x: usize| &row[x * size..(x + 1) * size];
+    let mut x = 0usize;
+
+    while x < width {
+        let mut run = 1usize;
+        while x + run < width && run < u16::MAX as usize && px(x + run) == px(x) {
+            run += 1;
+        }
+        if run >= 2 {
+            if run <= 128 {
+                out.push((run + 127) as u8);
+            } else {
+                out.push(128);
+                out.extend_from_slice(&(run as u16).to_be_bytes());
+            }
+            out.extend_from_slice(px(x));
+            x += run;
+            continue;
+        }
+
+        // Literal pixels until the next run of 2
+        let start = x;
+        while x < width && x - start < 128 && !(x + 1 < width && px(x) == px(x + 1)) {
+            x += 1;
+        }
+        out.push((x - start - 1) as u8);
+        out.extend_from_slice(&row[start * size..x * size]);
+    }
+}
+
+// ============================================================================
+// Convenience Functions
+// ============================================================================
+
+/// Reads a Softimage PIC file with default options.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::softimage;
+///
+/// let image = softimage::read("render.pic")?;
+/// ```
+pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
+    SoftimageReader::new().read(path)
+}
+
+/// Writes a Softimage PIC file with default options (mixed RLE).
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::softimage;
+///
+/// softimage::write("output.pic", &image)?;
+/// ```
+pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
+    SoftimageWriter::new().write(path, image)
+}
+
+/// Checks for the Softimage PIC magic number.
+pub fn is_softimage_header(h: &[u8]) -> bool {
+    h.len() >= 4 && read_u32(h, 0) == MAGIC
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+fn read_f32(data: &[u8], offset: usize) -> f32 {
+    f32::from_bits(read_u32(data, offset))
+}
+
+/// Reads a NUL-terminated string field.
+fn read_cstr(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
+
+/// Writes a string into a fixed field, leaving room for the terminator.
+fn write_cstr(dst: &mut [u8], value: &str) {
+    let len = value.len().min(dst.len() - 1);
+    dst[..len].copy_from_slice(&value.as_bytes()[..len]);
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the mixed RLE packets written for an RGBA image.
+    #[test]
+    fn test_mixed_rle_layout() {
+        // A run longer than 128 pixels, a short run and literals
+        let mut rgb = [10u8, 20, 30].repeat(150);
+        rgb.extend([1, 2, 3].repeat(2));
+        rgb.extend(4..=12);
+        let data: Vec<u8> = rgb.chunks_exact(3).flat_map(|px| [px[0], px[1], px[2], 255]).collect();
+        let mut image = ImageData::from_u8(155, 1, 4, data);
+        image.metadata.attrs.set("ImageDescription", AttrValue::Str("beauty".into()));
+
+        let writer = SoftimageWriter::with_options(SoftimageWriterOptions { rle: true });
+        let bytes = writer.write_to_memory(&image).expect("Write failed");
+        assert_eq!(
+            &bytes[HEADER_SIZE..HEADER_SIZE + 8],
+            [1, 8, MIXED_RLE, 0xE0, 0, 8, MIXED_RLE, 0x10]
+        );
+        let mut packets = vec![128, 0, 150, 10, 20, 30, 0x81, 1, 2, 3, 2];
+        packets.extend(4..=12);
+        packets.extend([128, 0, 155, 255]);
+        assert_eq!(&bytes[HEADER_SIZE + 8..], &packets[..]);
+
+        let loaded = SoftimageReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(loaded.to_u8(), image.to_u8());
+        let attrs = &loaded.metadata.attrs;
+        assert_eq!(attrs.get("ImageDescription").and_then(|v| v.as_str()), Some("beauty"));
+        assert_eq!(attrs.get("Softimage:Fields").and_then(|v| v.as_str()), Some("full"));
+    }
+
+    /// Tests a hand-built file mixing a pure RLE and a mixed RLE packet.
+    #[test]
+    fn test_pure_and_mixed_packets() {
+        let mut data = vec![0u8; HEADER_SIZE];
+        data[..4].copy_from_slice(&MAGIC.to_be_bytes());
+        data[92..96].copy_from_slice(&[0, 3, 0, 1]);
+        data.extend_from_slice(&[1, 8, PURE_RLE, 0xE0, 0, 8, MIXED_RLE, 0x10]);
+        data.extend_from_slice(&[2, 1, 2, 3, 1, 7, 8, 9]);
+        data.extend_from_slice(&[0, 50, 0x81, 60]);
+
+        let image = SoftimageReader::new().read_from_memory(&data).expect("Read failed");
+        assert_eq!((image.width, image.height, image.channels), (3, 1, 4));
+        assert_eq!(image.to_u8(), [1, 2, 3, 50, 1, 2, 3, 60, 7, 8, 9, 60]);
+    }
+
+    /// Tests a hand-built file with a pure RLE RGBA packet.
+    #[test]
+    fn test_pure_rle() {
+        let mut data = vec![0u8; HEADER_SIZE];
+        data[..4].copy_from_slice(&MAGIC.to_be_bytes());
+        data[92..96].copy_from_slice(&[0, 3, 0, 1]);
+        data.extend_from_slice(&[0, 8, PURE_RLE, 0xF0]);
+        data.extend_from_slice(&[2, 1, 2, 3, 4, 1, 9, 9, 9, 9]);
+
+        let image = SoftimageReader::new().read_from_memory(&data).expect("Read failed");
+        assert_eq!(image.channels, 4);
+        assert_eq!(image.to_u8(), [1, 2, 3, 4, 1, 2, 3, 4, 9, 9, 9, 9]);
+    }
+
+    /// Tests that oversized headers are rejected before allocating.
+    #[test]
+    fn test_huge_dimensions() {
+        let image = ImageData::from_u8(2, 2, 3, vec![7; 12]);
+        for rle in [false, true] {
+            let writer = SoftimageWriter::with_options(SoftimageWriterOptions { rle });
+            let mut bytes = writer.write_to_memory(&image).expect("Write failed");
+            bytes[92..96].copy_from_slice(&[0xFF; 4]);
+            let result = SoftimageReader::new().read_from_memory(&bytes);
+            assert!(matches!(result, Err(IoError::InvalidFile(_))), "rle={}", rle);
+        }
+    }
+
+    /// Tests header detection.
+    #[test]
+    fn test_can_read() {
+        let reader = SoftimageReader::new();
+        assert!(reader.can_read(&[0x53, 0x80, 0xF6, 0x34]));
+        assert!(!reader.can_read(b"#?RADIANCE"));
+    }
+}
@@ -16,11 +16,22 @@
 //! };
 //! let color = texsys.sample("texture.exr", 0.5, 0.5, &opts)?;
 //! ```
+//!
+//! Ptex files are sampled by face id and face-local (u, v) with
+//! [`TextureSystem::sample_ptex`], filtering across face edges.
 
+#[cfg(feature = "ptex")]
+use std::collections::HashMap;
 use std::path::Path;
+#[cfg(feature = "ptex")]
+use std::path::PathBuf;
 use std::sync::Arc;
+#[cfg(feature = "ptex")]
+use std::sync::RwLock;
 
 use crate::cache::{ImageCache, CachedImageInfo};
+#[cfg(feature = "ptex")]
+use crate::ptex::{BorderMode, Edge, FaceRes, MeshType, PtexTexture};
 use crate::IoResult;
 
 /// Texture wrap modes.
@@ -101,19 +112,24 @@ impl Default for TextureOptions {
 /// Uses ImageCache for efficient tile-based access.
 pub struct TextureSystem {
     cache: Arc<ImageCache>,
+    /// Open Ptex files (decoded faces are cached inside each texture).
+    #[cfg(feature = "ptex")]
+    ptex: RwLock<HashMap<PathBuf, Arc<PtexTexture>>>,
 }
 
 impl TextureSystem {
     /// Creates a new texture system with default cache.
     pub fn new() -> Self {
-        Self {
-            cache: Arc::new(ImageCache::default()),
-        }
+        Self::with_cache(Arc::new(ImageCache::default()))
     }
 
     /// Creates a texture system with custom cache.
     pub fn with_cache(cache: Arc<ImageCache>) -> Self {
-        Self { cache }
+        Self {
+            cache,
+            #[cfg(feature = "ptex")]
+            ptex: RwLock::new(HashMap::new()),
+        }
     }
 
     /// Returns a reference to the image cache.
@@ -376,11 +392,19 @@ impl TextureSystem {
 
     /// Invalidates cached data for a texture.
     pub fn invalidate(&self, path: impl AsRef<Path>) {
+        #[cfg(feature = "ptex")]
+        if let Ok(mut ptex) = self.ptex.write() {
+            ptex.remove(path.as_ref());
+        }
         self.cache.invalidate(path);
     }
 
     /// Clears all cached texture data.
     pub fn clear(&self) {
+        #[cfg(feature = "ptex")]
+        if let Ok(mut ptex) = self.ptex.write() {
+            ptex.clear();
+        }
         self.cache.clear();
     }
 }
@@ -687,6 +711,215 @@ impl TextureSystem {
     }
 }
 
+// ============================================================================
+// Ptex Sampling
+// ============================================================================
+
+#[cfg(feature = "ptex")]
+impl TextureSystem {
+    /// Opens a Ptex file, or returns it from the texture system's cache.
+    pub fn ptex_texture(&self, path: impl AsRef<Path>) -> IoResult<Arc<PtexTexture>> {
+        let path = path.as_ref();
+        if let Some(ptx) = self.ptex.read().ok().and_then(|m| m.get(path).cloned()) {
+            return Ok(ptx);
+        }
+        let ptx = Arc::new(PtexTexture::open(path)?);
+        if let Ok(mut map) = self.ptex.write() {
+            return Ok(Arc::clone(map.entry(path.to_path_buf()).or_insert(ptx)));
+        }
+        Ok(ptx)
+    }
+
+    /// Samples a Ptex texture at face-local (u, v).
+    ///
+    /// Bilinear filtering reads texels across face edges from the adjacent
+    /// faces, so lookups near edges are seamless. Edges without a neighbor
+    /// follow the file's border mode. Triangle meshes use nearest-texel
+    /// lookups within the face.
+    ///
+    /// As with [`sample`](Self::sample), `FilterMode::Trilinear` and
+    /// `FilterMode::Anisotropic` fall back to bilinear at full resolution;
+    /// use [`sample_ptex_d`](Self::sample_ptex_d) for filtered reductions.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use vfx_io::texture::{TextureSystem, TextureOptions};
+    ///
+    /// let texsys = TextureSystem::new();
+    /// let color = texsys.sample_ptex("asset.ptx", 42, 0.25, 0.75, &TextureOptions::default())?;
+    /// ```
+    pub fn sample_ptex(&self, path: impl AsRef<Path>, face_id: u32, u: f32, v: f32,
+                       opts: &TextureOptions) -> IoResult<[f32; 4]> {
+        let ptx = self.ptex_texture(path)?;
+        let filter = match opts.filter {
+            FilterMode::Nearest => FilterMode::Nearest,
+            _ => FilterMode::Bilinear,
+        };
+        ptex_lookup(&ptx, face_id, u, v, 0, filter, opts)
+    }
+
+    /// Samples a Ptex texture with face-space derivatives for reduction selection.
+    ///
+    /// Derivatives are in face (u, v) units per pixel. `FilterMode::Trilinear`
+    /// blends the two nearest reductions; `FilterMode::Anisotropic` is
+    /// treated as trilinear.
+    #[allow(clippy::too_many_arguments)]
+    pub fn sample_ptex_d(&self, path: impl AsRef<Path>, face_id: u32, u: f32, v: f32,
+                         dudx: f32, dvdx: f32, dudy: f32, dvdy: f32,
+                         opts: &TextureOptions) -> IoResult<[f32; 4]> {
+        let ptx = self.ptex_texture(path)?;
+        let info = *ptx.face_info(face_id)?;
+
+        // Footprint in texels at full resolution picks the reduction
+        let w = info.res.u() as f32;
+        let h = info.res.v() as f32;
+        let len_x = ((dudx * w).powi(2) + (dvdx * h).powi(2)).sqrt();
+        let len_y = ((dudy * w).powi(2) + (dvdy * h).powi(2)).sqrt();
+        let max_level = ptx.face_levels(face_id).saturating_sub(1) as f32;
+        let level = len_x.max(len_y).max(1.0).log2().clamp(0.0, max_level);
+
+        match opts.filter {
+            FilterMode::Nearest | FilterMode::Bilinear => {
+                ptex_lookup(&ptx, face_id, u, v, level as u32, opts.filter, opts)
+            }
+            FilterMode::Trilinear | FilterMode::Anisotropic => {
+                let level0 = level.floor() as u32;
+                let c0 = ptex_lookup(&ptx, face_id, u, v, level0, FilterMode::Bilinear, opts)?;
+                let blend = level.fract();
+                if blend == 0.0 {
+                    return Ok(c0);
+                }
+                let c1 = ptex_lookup(&ptx, face_id, u, v, level0 + 1, FilterMode::Bilinear, opts)?;
+                let mut result = [0.0f32; 4];
+                for (i, r) in result.iter_mut().enumerate() {
+                    *r = c0[i] * (1.0 - blend) + c1[i] * blend;
+                }
+                Ok(result)
+            }
+        }
+    }
+}
+
+/// Nearest or bilinear lookup in one face at a reduction level.
+#[cfg(feature = "ptex")]
+fn ptex_lookup(ptx: &PtexTexture, face_id: u32, u: f32, v: f32, level: u32,
+               filter: FilterMode, opts: &TextureOptions) -> IoResult<[f32; 4]> {
+    let info = *ptx.face_info(face_id)?;
+    let level = level.min(ptx.face_levels(face_id).saturating_sub(1));
+    let res = info.res.reduced(level);
+    let face = ptx.face_data(face_id, level)?;
+    let u = u.clamp(0.0, 1.0);
+    let v = v.clamp(0.0, 1.0);
+    let w = res.u() as f32;
+    let h = res.v() as f32;
+
+    if ptx.mesh_type() == MeshType::Triangle {
+        // Square texel grid: upright texels below the diagonal of each cell,
+        // inverted ones stored mirrored across the face diagonal
+        let n = res.u();
+        let (ut, vt) = (u * w, v * w);
+        let ui = (ut as usize).min(n - 1);
+        let vi = (vt as usize).min(n - 1);
+        let texel = if (ut - ui as f32) + (vt - vi as f32) <= 1.0 {
+            face.pixel(ui, vi)
+        } else {
+            face.pixel(n - 1 - vi, n - 1 - ui)
+        };
+        return Ok(ptex_rgba(texel, opts));
+    }
+
+    if filter == FilterMode::Nearest {
+        let x = ((u * w) as usize).min(res.u() - 1);
+        let y = ((v * h) as usize).min(res.v() - 1);
+        return Ok(ptex_rgba(face.pixel(x, y), opts));
+    }
+
+    let px = u * w - 0.5;
+    let py = v * h - 0.5;
+    let x0 = px.floor() as i32;
+    let y0 = py.floor() as i32;
+    let fx = px - x0 as f32;
+    let fy = py - y0 as f32;
+
+    let c00 = ptex_texel(ptx, face_id, level, x0, y0, opts)?;
+    let c10 = ptex_texel(ptx, face_id, level, x0 + 1, y0, opts)?;
+    let c01 = ptex_texel(ptx, face_id, level, x0, y0 + 1, opts)?;
+    let c11 = ptex_texel(ptx, face_id, level, x0 + 1, y0 + 1, opts)?;
+
+    let mut result = [0.0f32; 4];
+    for (i, r) in result.iter_mut().enumerate() {
+        let top = c00[i] * (1.0 - fx) + c10[i] * fx;
+        let bot = c01[i] * (1.0 - fx) + c11[i] * fx;
+        *r = top * (1.0 - fy) + bot * fy;
+    }
+    Ok(result)
+}
+
+/// Fetches a texel of a quad face, crossing into the neighbor when outside.
+#[cfg(feature = "ptex")]
+fn ptex_texel(ptx: &PtexTexture, face_id: u32, level: u32, x: i32, y: i32,
+              opts: &TextureOptions) -> IoResult<[f32; 4]> {
+    let info = *ptx.face_info(face_id)?;
+    let res = info.res.reduced(level);
+    let (w, h) = (res.u() as i32, res.v() as i32);
+    if (0..w).contains(&x) && (0..h).contains(&y) {
+        let face = ptx.face_data(face_id, level)?;
+        return Ok(ptex_rgba(face.pixel(x as usize, y as usize), opts));
+    }
+
+    // Texel center in face coordinates, mapped across the edge
+    let u = (x as f32 + 0.5) / w as f32;
+    let v = (y as f32 + 0.5) / h as f32;
+    let Some((adj, nu, nv)) = ptx.cross_edge(face_id, u, v) else {
+        let (border_u, border_v) = ptx.border_modes();
+        let border = if (0..w).contains(&x) { border_v } else { border_u };
+        let (x, y) = match border {
+            BorderMode::Black => return Ok(opts.fill),
+            BorderMode::Clamp => (x.clamp(0, w - 1), y.clamp(0, h - 1)),
+            BorderMode::Periodic => (x.rem_euclid(w), y.rem_euclid(h)),
+        };
+        let face = ptx.face_data(face_id, level)?;
+        return Ok(ptex_rgba(face.pixel(x as usize, y as usize), opts));
+    };
+
+    // Match the texel density along the shared edge
+    let edge = if x < 0 {
+        Edge::Left
+    } else if x >= w {
+        Edge::Right
+    } else if y < 0 {
+        Edge::Bottom
+    } else {
+        Edge::Top
+    };
+    let along = |res: FaceRes, edge: Edge| match edge {
+        Edge::Left | Edge::Right => res.vlog2 as i32,
+        Edge::Bottom | Edge::Top => res.ulog2 as i32,
+    };
+    let adj_info = *ptx.face_info(adj)?;
+    let adj_edge = info.adj_edge(edge);
+    let adj_levels = ptx.face_levels(adj) as i32;
+    let adj_level = (level as i32 + along(adj_info.res, adj_edge) - along(info.res, edge))
+        .clamp(0, adj_levels - 1) as u32;
+
+    let adj_res = adj_info.res.reduced(adj_level);
+    let ax = ((nu.clamp(0.0, 1.0) * adj_res.u() as f32) as usize).min(adj_res.u() - 1);
+    let ay = ((nv.clamp(0.0, 1.0) * adj_res.v() as f32) as usize).min(adj_res.v() - 1);
+    let face = ptx.face_data(adj, adj_level)?;
+    Ok(ptex_rgba(face.pixel(ax, ay), opts))
+}
+
+/// Expands a texel to RGBA, using `fill` for missing channels.
+#[cfg(feature = "ptex")]
+fn ptex_rgba(texel: &[f32], opts: &TextureOptions) -> [f32; 4] {
+    let mut result = opts.fill;
+    for (dst, &src) in result.iter_mut().zip(texel) {
+        *dst = src;
+    }
+    result
+}
+
 /// Applies wrap mode to a coordinate.
 fn apply_wrap(coord: f32, mode: WrapMode) -> f32 {
     match mode {
@@ -791,4 +1024,70 @@ mod tests {
         let ts = TextureSystem::new();
         assert_eq!(ts.cache().size(), 0);
     }
+
+    #[cfg(feature = "ptex")]
+    fn ptex_fixture() -> (tempfile::TempDir, std::path::PathBuf, Vec<Vec<f32>>) {
+        use crate::ptex::{tests::two_quads, PtexDataType};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("quads.ptx");
+        let (file, data) = two_quads(PtexDataType::F32, None);
+        std::fs::write(&path, file).unwrap();
+        (dir, path, data)
+    }
+
+    #[cfg(feature = "ptex")]
+    #[test]
+    fn ptex_bilinear_crosses_edges() {
+        let (_dir, path, data) = ptex_fixture();
+        let ts = TextureSystem::new();
+        let opts = TextureOptions::default();
+        // Face 0 is 8x4, face 1 is 4x4, face 0's right edge meets face 1's left edge
+        let f0 = |x: usize, y: usize, c: usize| data[0][(y * 8 + x) * 3 + c];
+        let f1 = |x: usize, y: usize, c: usize| data[1][(y * 4 + x) * 3 + c];
+
+        // Texel center inside the face
+        let c = ts.sample_ptex(&path, 0, 2.5 / 8.0, 1.5 / 4.0, &opts).unwrap();
+        assert!((c[0] - f0(2, 1, 0)).abs() < 1e-6);
+        assert_eq!(c[3], opts.fill[3]);
+
+        // The shared edge blends both faces, and agrees from either side
+        let expected = 0.5 * (f0(7, 1, 1) + f1(0, 1, 1));
+        let a = ts.sample_ptex(&path, 0, 1.0, 0.375, &opts).unwrap();
+        let b = ts.sample_ptex(&path, 1, 0.0, 0.375, &opts).unwrap();
+        assert!((a[1] - expected).abs() < 1e-6);
+        assert!((b[1] - expected).abs() < 1e-6);
+
+        // No neighbor on the left of face 0: clamp border
+        let c = ts.sample_ptex(&path, 0, 0.0, 1.5 / 4.0, &opts).unwrap();
+        assert!((c[2] - f0(0, 1, 2)).abs() < 1e-6);
+
+        assert!(ts.sample_ptex(&path, 5, 0.5, 0.5, &opts).is_err());
+    }
+
+    #[cfg(feature = "ptex")]
+    #[test]
+    fn ptex_derivatives_select_reductions() {
+        let (_dir, path, _) = ptex_fixture();
+        let ts = TextureSystem::new();
+        let ptx = ts.ptex_texture(&path).unwrap();
+        assert!(Arc::ptr_eq(&ptx, &ts.ptex_texture(&path).unwrap()));
+
+        // Two texels per pixel at full resolution selects the first reduction
+        let nearest = TextureOptions { filter: FilterMode::Nearest, ..Default::default() };
+        let c = ts.sample_ptex_d(&path, 0, 0.3, 0.6, 2.0 / 8.0, 0.0, 0.0, 0.0, &nearest).unwrap();
+        let level1 = ptx.face_data(0, 1).unwrap();
+        assert_eq!(c[0], level1.pixel(1, 1)[0]);
+
+        // Halfway between levels blends them
+        let trilinear = TextureOptions { filter: FilterMode::Trilinear, ..Default::default() };
+        let d = 2f32.sqrt() / 4.0;
+        let c = ts.sample_ptex_d(&path, 1, 0.5, 0.5, d, 0.0, 0.0, 0.0, &trilinear).unwrap();
+        let c0 = ts.sample_ptex(&path, 1, 0.5, 0.5, &TextureOptions::default()).unwrap();
+        let c1 = ts.sample_ptex_d(&path, 1, 0.5, 0.5, 0.5, 0.0, 0.0, 0.0, &TextureOptions::default()).unwrap();
+        assert!((c[0] - 0.5 * (c0[0] + c1[0])).abs() < 1e-5);
+
+        ts.invalidate(&path);
+        assert!(!Arc::ptr_eq(&ptx, &ts.ptex_texture(&path).unwrap()));
+    }
 }
@@ -0,0 +1,886 @@
+//! Truevision TGA (Targa) format support.
+//!
+//! Provides reading and writing of TGA files, still the common exchange
+//! format for game textures and older texture libraries.
+//!
+//! # Overview
+//!
+//! The reader handles all image types of the TGA 1.0 and 2.0 specifications:
+//! - Color-mapped, true-color and grayscale images
+//! - Uncompressed and RLE compressed pixel data
+//! - 8, 15/16 (ARGB1555), 24 and 32-bit pixels
+//! - All four origins (descriptor bits 4 and 5), returned top-down
+//! - The TGA 2.0 extension area: author, comments, date, software,
+//!   pixel aspect ratio, gamma and the alpha attribute type
+//!
+//! # Alpha
+//!
+//! The extension area's attributes type decides whether alpha is kept:
+//! "no alpha" (0) and "undefined, ignore" (1) drop the channel, while
+//! "undefined, retain" (2), "straight" (3) and "premultiplied" (4) keep it
+//! and record the type in `TGA:AlphaType`. Files without an extension area
+//! keep alpha unless it is zero everywhere, which older writers produce for
+//! opaque images.
+//!
+//! # Examples
+//!
+//! Simple usage:
+//! ```ignore
+//! use vfx_io::tga;
+//!
+//! let image = tga::read("diffuse.tga")?;
+//! tga::write("output.tga", &image)?;
+//! ```
+//!
+//! With options:
+//! ```ignore
+//! use vfx_io::tga::{TgaWriter, TgaWriterOptions};
+//! use vfx_io::FormatWriter;
+//!
+//! let writer = TgaWriter::with_options(TgaWriterOptions { rle: false });
+//! writer.write("output.tga", &image)?;
+//! ```
+
+use crate::{AttrValue, FormatReader, FormatWriter, ImageData, IoError, IoResult, Metadata};
+use std::io::Write;
+use std::path::Path;
+
+/// Size of the fixed file header.
+const HEADER_SIZE: usize = 18;
+
+/// Size of the TGA 2.0 extension area.
+const EXTENSION_SIZE: usize = 495;
+
+/// Signature at the end of TGA 2.0 files.
+const FOOTER_SIGNATURE: &[u8; 18] = b"TRUEVISION-XFILE.\0";
+
+/// Size of the TGA 2.0 footer.
+const FOOTER_SIZE: usize = 26;
+
+/// Descriptor bit for right-to-left pixel order.
+const RIGHT_TO_LEFT: u8 = 0x10;
+
+/// Descriptor bit for top-to-bottom row order.
+const TOP_TO_BOTTOM: u8 = 0x20;
+
+// ============================================================================
+// Reader Options
+// ============================================================================
+
+/// Options for reading TGA files.
+///
+/// Currently minimal - TGA reading is mostly automatic.
+#[derive(Debug, Clone, Default)]
+pub struct TgaReaderOptions {
+    /// Reserved for future use.
+    _reserved: (),
+}
+
+// ============================================================================
+// Writer Options
+// ============================================================================
+
+/// Options for writing TGA files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::tga::{TgaWriter, TgaWriterOptions};
+/// use vfx_io::FormatWriter;
+///
+/// let writer = TgaWriter::with_options(TgaWriterOptions { rle: false });
+/// writer.write("output.tga", &image)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct TgaWriterOptions {
+    /// Use RLE compression. Default: true.
+    pub rle: bool,
+}
+
+impl Default for TgaWriterOptions {
+    fn default() -> Self {
+        Self { rle: true }
+    }
+}
+
+// ============================================================================
+// Header
+// ============================================================================
+
+/// Parsed file header.
+#[derive(Debug, Clone)]
+struct TgaHeader {
+    id_length: usize,
+    color_map_type: u8,
+    image_type: u8,
+    color_map_first: usize,
+    color_map_length: usize,
+    color_map_depth: u8,
+    width: usize,
+    height: usize,
+    depth: u8,
+    descriptor: u8,
+}
+
+impl TgaHeader {
+    fn parse(data: &[u8]) -> IoResult<Self> {
+        if !is_tga_header(data) {
+            return Err(IoError::InvalidFile("not a TGA file".into()));
+        }
+
+        Ok(Self {
+            id_length: data[0] as usize,
+            color_map_type: data[1],
+            image_type: data[2],
+            color_map_first: read_u16(data, 3) as usize,
+            color_map_length: read_u16(data, 5) as usize,
+            color_map_depth: data[7],
+            width: read_u16(data, 12) as usize,
+            height: read_u16(data, 14) as usize,
+            depth: data[16],
+            descriptor: data[17],
+        })
+    }
+
+    fn is_rle(&self) -> bool {
+        self.image_type >= 9
+    }
+
+    fn is_color_mapped(&self) -> bool {
+        self.image_type == 1 || self.image_type == 9
+    }
+
+    fn is_gray(&self) -> bool {
+        self.image_type == 3 || self.image_type == 11
+    }
+
+    fn alpha_bits(&self) -> u8 {
+        self.descriptor & 0x0F
+    }
+}
+
+// ============================================================================
+// TgaReader
+// ============================================================================
+
+/// TGA file reader.
+///
+/// Implements [`FormatReader`] for reading TGA files.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::tga::TgaReader;
+/// use vfx_io::FormatReader;
+///
+/// let reader = TgaReader::new();
+/// let image = reader.read("diffuse.tga")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct TgaReader {
+    #[allow(dead_code)]
+    options: TgaReaderOptions,
+}
+
+impl TgaReader {
+    /// Creates a new reader with default options.
+    pub fn new() -> Self {
+        Self::with_options(TgaReaderOptions::default())
+    }
+
+    /// Internal read implementation.
+    fn read_impl(&self, data: &[u8]) -> IoResult<ImageData> {
+        let header = TgaHeader::parse(data)?;
+        let mut metadata = Metadata {
+            colorspace: Some("sRGB".to_string()),
+            ..Metadata::default()
+        };
+
+        let mut pos = HEADER_SIZE;
+        let id = data
+            .get(pos..pos + header.id_length)
+            .ok_or_else(|| IoError::InvalidFile("TGA image ID truncated".into()))?;
+        let id = String::from_utf8_lossy(id).trim_end_matches('\0').trim().to_string();
+        if !id.is_empty() {
+            metadata.attrs.set("TGA:ImageID", AttrValue::Str(id));
+        }
+        pos += header.id_length;
+
+        let mut palette = Vec::new();
+        if header.color_map_type == 1 {
+            let entry_size = (header.color_map_depth as usize).div_ceil(8);
+            let bytes = data
+                .get(pos..pos + header.color_map_length * entry_size)
+                .ok_or_else(|| IoError::InvalidFile("TGA color map truncated".into()))?;
+            palette = bytes
+                .chunks_exact(entry_size)
+                .map(|entry| decode_color(entry, header.color_map_depth))
+                .collect();
+            pos += bytes.len();
+        }
+
+        let pixel_size = (header.depth as usize).div_ceil(8);
+        let count = header.width * header.height;
+        let pixels = if header.is_rle() {
+            decode_rle(&data[pos.min(data.len())..], pixel_size, count)?
+        } else {
+            data.get(pos..pos + count * pixel_size)
+                .ok_or_else(|| IoError::InvalidFile("TGA pixel data truncated".into()))?
+                .to_vec()
+        };
+
+        metadata.attrs.set("TGA:BitsPerPixel", AttrValue::UInt(header.depth as u32));
+        metadata.attrs.set(
+            "TGA:Compression",
+            AttrValue::Str(if header.is_rle() { "rle" } else { "none" }.into()),
+        );
+
+        let alpha_type = extension_area(data).map(|extension| read_extension(extension, &mut metadata));
+
+        // Decode to gray+alpha or RGBA, in file order
+        let gray = header.is_gray();
+        let mut alpha_candidate = if gray {
+            header.depth == 16
+        } else if header.is_color_mapped() {
+            header.color_map_depth == 32 || (header.color_map_depth == 16 && header.alpha_bits() > 0)
+        } else {
+            header.depth == 32 || (header.depth == 16 && header.alpha_bits() > 0)
+        };
+
+        let mut samples: Vec<[u8; 4]> = Vec::with_capacity(count);
+        for px in pixels.chunks_exact(pixel_size) {
+            let color = if gray {
+                let alpha = if pixel_size > 1 { px[1] } else { 255 };
+                [px[0], px[0], px[0], alpha]
+            } else if header.is_color_mapped() {
+                let index = if pixel_size > 1 { read_u16(px, 0) as usize } else { px[0] as usize };
+                index
+                    .checked_sub(header.color_map_first)
+                    .and_then(|i| palette.get(i))
+                    .copied()
+                    .unwrap_or([0, 0, 0, 255])
+            } else {
+                decode_color(px, header.depth)
+            };
+            samples.push(color);
+        }
+
+        match alpha_type {
+            Some(0) | Some(1) => alpha_candidate = false,
+            Some(kind) if alpha_candidate => {
+                let name = match kind {
+                    2 => "undefined",
+                    4 => "premultiplied",
+                    _ => "straight",
+                };
+                metadata.attrs.set("TGA:AlphaType", AttrValue::Str(name.into()));
+            }
+            None if alpha_candidate => {
+                alpha_candidate = samples.iter().any(|c| c[3] != 0);
+            }
+            _ => {}
+        }
+
+        let channels = match (gray, alpha_candidate) {
+            (true, false) => 1,
+            (true, true) => 2,
+            (false, false) => 3,
+            (false, true) => 4,
+        };
+
+        // Reorient to top-down, left-to-right
+        let (width, height) = (header.width, header.height);
+        let mut out = vec![0u8; count * channels];
+        for (i, color) in samples.iter().enumerate() {
+            let (mut x, mut y) = (i % width, i / width);
+            if header.descriptor & RIGHT_TO_LEFT != 0 {
+                x = width - 1 - x;
+            }
+            if header.descriptor & TOP_TO_BOTTOM == 0 {
+                y = height - 1 - y;
+            }
+            let dst = &mut out[(y * width + x) * channels..][..channels];
+            match channels {
+                1 => dst[0] = color[0],
+                2 => dst.copy_from_slice(&[color[0], color[3]]),
+                _ => dst.copy_from_slice(&color[..channels]),
+            }
+        }
+
+        let mut image = ImageData::from_u8(width as u32, height as u32, channels as u32, out);
+        image.metadata = metadata;
+        Ok(image)
+    }
+}
+
+impl Default for TgaReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatReader<TgaReaderOptions> for TgaReader {
+    /// Returns "TGA".
+    fn format_name(&self) -> &'static str {
+        "TGA"
+    }
+
+    /// Returns `["tga", "tpic", "icb", "vda", "vst"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["tga", "tpic", "icb", "vda", "vst"]
+    }
+
+    /// Checks for a plausible TGA header (TGA has no magic bytes).
+    fn can_read(&self, header: &[u8]) -> bool {
+        is_tga_header(header)
+    }
+
+    /// Reads a TGA file from disk.
+    fn read<P: AsRef<Path>>(&self, path: P) -> IoResult<ImageData> {
+        let data = std::fs::read(path.as_ref())?;
+        self.read_impl(&data)
+    }
+
+    /// Reads a TGA from a byte slice.
+    fn read_from_memory(&self, data: &[u8]) -> IoResult<ImageData> {
+        self.read_impl(data)
+    }
+
+    /// Creates reader with custom options.
+    fn with_options(options: TgaReaderOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Decodes a color map entry or true-color pixel to RGBA.
+fn decode_color(px: &[u8], depth: u8) -> [u8; 4] {
+    match depth {
+        15 | 16 => {
+            let v = read_u16(px, 0);
+            let expand = |c: u16| ((c << 3) | (c >> 2)) as u8;
+            let alpha = if depth == 15 || v & 0x8000 != 0 { 255 } else { 0 };
+            [expand((v >> 10) & 31), expand((v >> 5) & 31), expand(v & 31), alpha]
+        }
+        24 => [px[2], px[1], px[0], 255],
+        32 => [px[2], px[1], px[0], px[3]],
+        _ => [px[0], px[0], px[0], 255],
+    }
+}
+
+/// Decodes RLE packets into `count` pixels of `pixel_size` bytes.
+fn decode_rle(src: &[u8], pixel_size: usize, count: usize) -> IoResult<Vec<u8>> {
+    let truncated = || IoError::InvalidFile("TGA RLE data truncated".into());
+    let total = count * pixel_size;
+    let mut out = Vec::with_capacity(total);
+    let mut i = 0usize;
+
+    while out.len() < total {
+        let packet = *src.get(i).ok_or_else(truncated)?;
+        i += 1;
+        let n = (packet & 0x7F) as usize + 1;
+
+        if packet & 0x80 != 0 {
+            let px = src.get(i..i + pixel_size).ok_or_else(truncated)?;
+            for _ in 0..n {
+                out.extend_from_slice(px);
+            }
+            i += pixel_size;
+        } else {
+            let bytes = src.get(i..i + n * pixel_size).ok_or_else(truncated)?;
+            out.extend_from_slice(bytes);
+            i += bytes.len();
+        }
+    }
+
+    // Packets may run past the last pixel
+    out.truncate(total);
+    Ok(out)
+}
+
+/// Returns the extension area of a TGA 2.0 file.
+fn extension_area(data: &[u8]) -> Option<&[u8]> {
+    if data.len() < HEADER_SIZE + FOOTER_SIZE || &data[data.len() - FOOTER_SIGNATURE.len()..] != FOOTER_SIGNATURE {
+        return None;
+    }
+    let offset = read_u32(data, data.len() - FOOTER_SIZE) as usize;
+    if offset == 0 {
+        return None;
+    }
+    data.get(offset..offset + EXTENSION_SIZE)
+}
+
+/// Reads the extension area into metadata, returning the attributes type.
+fn read_extension(ext: &[u8], metadata: &mut Metadata) -> u8 {
+    let mut set_str = |key: &str, bytes: &[u8]| {
+        let value = read_cstr(bytes);
+        if !value.is_empty() {
+            metadata.attrs.set(key, AttrValue::Str(value));
+        }
+    };
+
+    set_str("Artist", &ext[2..43]);
+    set_str("TGA:JobName", &ext[379..420]);
+    set_str("Software", &ext[426..467]);
+
+    let comments: Vec<String> = ext[43..367]
+        .chunks_exact(81)
+        .map(read_cstr)
+        .filter(|line| !line.is_empty())
+        .collect();
+    if !comments.is_empty() {
+        metadata.attrs.set("ImageDescription", AttrValue::Str(comments.join("\n")));
+    }
+
+    let date: Vec<u16> = (0..6).map(|i| read_u16(ext, 367 + i * 2)).collect();
+    if date[2] != 0 {
+        metadata.attrs.set(
+            "DateTime",
+            AttrValue::Str(format!(
+                "{:04}:{:02}:{:02} {:02}:{:02}:{:02}",
+                date[2], date[0], date[1], date[3], date[4], date[5]
+            )),
+        );
+    }
+
+    let job_time: Vec<u16> = (0..3).map(|i| read_u16(ext, 420 + i * 2)).collect();
+    if job_time.iter().any(|&t| t != 0) {
+        metadata.attrs.set(
+            "TGA:JobTime",
+            AttrValue::Str(format!("{}:{:02}:{:02}", job_time[0], job_time[1], job_time[2])),
+        );
+    }
+
+    let version = read_u16(ext, 467);
+    if version != 0 {
+        let letter = if ext[469].is_ascii_alphanumeric() { (ext[469] as char).to_string() } else { String::new() };
+        metadata.attrs.set(
+            "TGA:SoftwareVersion",
+            AttrValue::Str(format!("{}.{:02}{}", version / 100, version % 100, letter)),
+        );
+    }
+
+    let (num, den) = (read_u16(ext, 474), read_u16(ext, 476));
+    if num != 0 && den != 0 {
+        metadata.attrs.set("PixelAspectRatio", AttrValue::Float(num as f32 / den as f32));
+    }
+
+    let (num, den) = (read_u16(ext, 478), read_u16(ext, 480));
+    if num != 0 && den != 0 {
+        let gamma = num as f32 / den as f32;
+        metadata.gamma = Some(gamma);
+        metadata.attrs.set("Gamma", AttrValue::Float(gamma));
+    }
+
+    ext[494]
+}
+
+// ============================================================================
+// TgaWriter
+// ============================================================================
+
+/// TGA file writer.
+///
+/// Implements [`FormatWriter`] for writing TGA 2.0 files with a top-left
+/// origin and an extension area carrying metadata and the alpha type.
+///
+/// Gray images are written as 8-bit grayscale, gray + alpha as 16-bit
+/// grayscale, RGB as 24-bit and RGBA as 32-bit true-color.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::tga::TgaWriter;
+/// use vfx_io::FormatWriter;
+///
+/// let writer = TgaWriter::new();
+/// writer.write("diffuse.tga", &image)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct TgaWriter {
+    options: TgaWriterOptions,
+}
+
+impl TgaWriter {
+    /// Creates a new writer with default options.
+    pub fn new() -> Self {
+        Self::with_options(TgaWriterOptions::default())
+    }
+
+    /// Internal write implementation.
+    fn write_impl<W: Write>(&self, mut writer: W, image: &ImageData) -> IoResult<()> {
+        let width = image.width as usize;
+        let height = image.height as usize;
+        let channels = image.channels as usize;
+        if width == 0 || height == 0 || width > u16::MAX as usize || height > u16::MAX as usize {
+            return Err(IoError::EncodeError(format!("invalid TGA dimensions: {}x{}", width, height)));
+        }
+        if !(1..=4).contains(&channels) {
+            return Err(IoError::EncodeError(format!("unsupported channels: {}", channels)));
+        }
+
+        let gray = channels <= 2;
+        let has_alpha = channels == 2 || channels == 4;
+        let image_type = match (gray, self.options.rle) {
+            (true, false) => 3u8,
+            (true, true) => 11,
+            (false, false) => 2,
+            (false, true) => 10,
+        };
+
+        let mut out = Vec::with_capacity(HEADER_SIZE + image.sample_count() + EXTENSION_SIZE + FOOTER_SIZE);
+        out.extend_from_slice(&[0, 0, image_type, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        out.extend_from_slice(&(width as u16).to_le_bytes());
+        out.extend_from_slice(&(height as u16).to_le_bytes());
+        out.push((channels * 8) as u8);
+        out.push(TOP_TO_BOTTOM | if has_alpha { 8 } else { 0 });
+
+        // Gray stays as is, color is stored as BGR(A)
+        let mut samples = image.to_u8();
+        if !gray {
+            for px in samples.chunks_exact_mut(channels) {
+                px.swap(0, 2);
+            }
+        }
+
+        if self.options.rle {
+            for row in samples.chunks_exact(width * channels) {
+                encode_rle_row(row, channels, &mut out);
+            }
+        } else {
+            out.extend_from_slice(&samples);
+        }
+
+        let extension_offset = out.len() as u32;
+        out.extend_from_slice(&build_extension(&image.metadata, has_alpha));
+        out.extend_from_slice(&extension_offset.to_le_bytes());
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(FOOTER_SIGNATURE);
+
+        writer.write_all(&out)?;
+        Ok(())
+    }
+}
+
+impl Default for TgaWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FormatWriter<TgaWriterOptions> for TgaWriter {
+    /// Returns "TGA".
+    fn format_name(&self) -> &'static str {
+        "TGA"
+    }
+
+    /// Returns `["tga", "tpic", "icb", "vda", "vst"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["tga", "tpic", "icb", "vda", "vst"]
+    }
+
+    /// Writes a TGA file to disk.
+    fn write<P: AsRef<Path>>(&self, path: P, image: &ImageData) -> IoResult<()> {
+        let file = std::fs::File::create(path.as_ref())?;
+        self.write_impl(std::io::BufWriter::new(file), image)
+    }
+
+    /// Writes a TGA to a byte vector.
+    fn write_to_memory(&self, image: &ImageData) -> IoResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.write_impl(&mut buffer, image)?;
+        Ok(buffer)
+    }
+
+    /// Creates writer with custom options.
+    fn with_options(options: TgaWriterOptions) -> Self {
+        Self { options }
+    }
+}
+
+/// Encodes one scanline as RLE packets, which never cross rows.
+fn encode_rle_row(row: &[u8], pixel_size: usize, out: &mut Vec<u8>) {
+    let width = row.len() / pixel_size;
+    let px = |x: usize| &row[x * pixel_size..(x + 1) * pixel_size];
+    let mut x = 0usize;
+
+    while x < width {
+        let mut run = 1usize;
+        while x + run < width && run < 128 && px(x + run) == px(x) {
+            run += 1;
+        }
+        if run >= 2 {
+            out.push(0x80 | (run - 1) as u8);
+            out.extend_from_slice(px(x));
+            x += run;
+            continue;
+        }
+
+        // Raw packet until the next run of 2
+        let start = x;
+        while x < width && x - start < 128 && !(x + 1 < width && px(x) == px(x + 1)) {
+            x += 1;
+        }
+        out.push((x - start - 1) as u8);
+        out.extend_from_slice(&row[start * pixel_size..x * pixel_size]);
+    }
+}
+
+/// Builds the extension area from metadata.
+fn build_extension(metadata: &Metadata, has_alpha: bool) -> Vec<u8> {
+    let mut ext = vec![0u8; EXTENSION_SIZE];
+    ext[0..2].copy_from_slice(&(EXTENSION_SIZE as u16).to_le_bytes());
+
+    let get_str = |key: &str| metadata.attrs.get(key).and_then(|v| v.as_str());
+    if let Some(artist) = get_str("Artist") {
+        write_cstr(&mut ext[2..43], artist);
+    }
+    if let Some(description) = get_str("ImageDescription") {
+        for (line, dst) in description.lines().zip(ext[43..367].chunks_exact_mut(81)) {
+            write_cstr(dst, line);
+        }
+    }
+    if let Some(date) = get_str("DateTime").and_then(parse_date_time) {
+        // Stored as month, day, year, hour, minute, second
+        let fields = [date[1], date[2], date[0], date[3], date[4], date[5]];
+        for (i, field) in fields.iter().enumerate() {
+            ext[367 + i * 2..369 + i * 2].copy_from_slice(&field.to_le_bytes());
+        }
+    }
+    if let Some(job) = get_str("TGA:JobName") {
+        write_cstr(&mut ext[379..420], job);
+    }
+    if let Some(software) = get_str("Software") {
+        write_cstr(&mut ext[426..467], software);
+    }
+
+    let aspect = metadata.attrs.get("PixelAspectRatio").and_then(|v| v.as_f32());
+    if let Some((num, den)) = aspect.and_then(to_ratio) {
+        ext[474..476].copy_from_slice(&num.to_le_bytes());
+        ext[476..478].copy_from_slice(&den.to_le_bytes());
+    }
+    let gamma = metadata.gamma.or_else(|| metadata.attrs.get("Gamma").and_then(|v| v.as_f32()));
+    if let Some((num, den)) = gamma.and_then(to_ratio) {
+        ext[478..480].copy_from_slice(&num.to_le_bytes());
+        ext[480..482].copy_from_slice(&den.to_le_bytes());
+    }
+
+    ext[494] = if !has_alpha {
+        0
+    } else {
+        match get_str("TGA:AlphaType") {
+            Some("premultiplied") => 4,
+            Some("undefined") => 2,
+            _ => 3,
+        }
+    };
+
+    ext
+}
+
+/// Parses an EXIF style "YYYY:MM:DD HH:MM:SS" date.
+fn parse_date_time(value: &str) -> Option<[u16; 6]> {
+    let mut fields = [0u16; 6];
+    let parts = value.split([':', ' ', '-', 'T']);
+    let mut count = 0;
+    for (field, part) in fields.iter_mut().zip(parts) {
+        *field = part.trim().parse().ok()?;
+        count += 1;
+    }
+    (count == 6).then_some(fields)
+}
+
+/// Converts a value to a 16-bit ratio with a fixed denominator.
+fn to_ratio(value: f32) -> Option<(u16, u16)> {
+    let num = (value * 1000.0).round();
+    (num > 0.0 && num <= u16::MAX as f32).then_some((num as u16, 1000))
+}
+
+// ============================================================================
+// Convenience Functions
+// ============================================================================
+
+/// Reads a TGA file with default options.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::tga;
+///
+/// let image = tga::read("diffuse.tga")?;
+/// ```
+pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
+    TgaReader::new().read(path)
+}
+
+/// Writes a TGA file with default options (RLE compression).
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::tga;
+///
+/// tga::write("output.tga", &image)?;
+/// ```
+pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
+    TgaWriter::new().write(path, image)
+}
+
+/// Checks whether the first 18 bytes form a plausible TGA header.
+///
+/// TGA has no magic bytes, so the image type, color map and pixel depth
+/// fields are checked for consistency instead.
+pub fn is_tga_header(h: &[u8]) -> bool {
+    if h.len() < HEADER_SIZE {
+        return false;
+    }
+    let (color_map_type, image_type, color_map_depth, depth) = (h[1], h[2], h[7], h[16]);
+    if read_u16(h, 12) == 0 || read_u16(h, 14) == 0 || h[17] & 0xC0 != 0 {
+        return false;
+    }
+    let no_color_map = color_map_type == 0 && h[3..8] == [0, 0, 0, 0, 0];
+    let valid_color_map = color_map_type == 1 && matches!(color_map_depth, 15 | 16 | 24 | 32);
+
+    match image_type {
+        1 | 9 => valid_color_map && matches!(depth, 8 | 16),
+        2 | 10 => (no_color_map || valid_color_map) && matches!(depth, 15 | 16 | 24 | 32),
+        3 | 11 => no_color_map && matches!(depth, 8 | 16),
+        _ => false,
+    }
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+fn read_u16(data: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([data[offset], data[offset + 1]])
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]])
+}
+
+/// Reads a NUL terminated string field.
+fn read_cstr(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+}
+
+/// Writes a string into a NUL terminated field, truncating if needed.
+fn write_cstr(dst: &mut [u8], value: &str) {
+    let bytes = value.as_bytes();
+    let len = bytes.len().min(dst.len() - 1);
+    dst[..len].copy_from_slice(&bytes[..len]);
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_image(channels: u32) -> ImageData {
+        // Rows with runs and noise to exercise both packet types
+        let data: Vec<u8> = (0..9 * 4 * channels)
+            .map(|i| if (i / channels) % 9 < 4 { 200 + (i % channels) as u8 } else { (i * 37) as u8 })
+            .collect();
+        ImageData::from_u8(9, 4, channels, data)
+    }
+
+    /// Tests roundtrips for all channel counts, with and without RLE.
+    #[test]
+    fn test_roundtrip() {
+        for rle in [false, true] {
+            for channels in 1..=4 {
+                let mut image = test_image(channels);
+                image.metadata.attrs.set("Artist", AttrValue::Str("lookdev".into()));
+                let writer = TgaWriter::with_options(TgaWriterOptions { rle });
+                let bytes = writer.write_to_memory(&image).expect("Write failed");
+                let loaded = TgaReader::new().read_from_memory(&bytes).expect("Read failed");
+
+                assert_eq!((loaded.width, loaded.height, loaded.channels), (9, 4, channels));
+                assert_eq!(loaded.to_u8(), image.to_u8(), "rle={} channels={}", rle, channels);
+                assert_eq!(loaded.metadata.attrs.get("Artist").and_then(|v| v.as_str()), Some("lookdev"));
+            }
+        }
+    }
+
+    /// Tests a bottom-up color-mapped RLE image with 16-bit palette entries.
+    #[test]
+    fn test_color_mapped_rle() {
+        let mut data = vec![0u8, 1, 9, 0, 0, 2, 0, 16, 0, 0, 0, 0, 3, 0, 2, 0, 8, 0];
+        // Palette: opaque red, transparent blue (alpha bit clear)
+        data.extend_from_slice(&0xFC00u16.to_le_bytes());
+        data.extend_from_slice(&0x001Fu16.to_le_bytes());
+        // Bottom row: 3 x index 1, top row: raw 0, 1, 0
+        data.extend_from_slice(&[0x82, 1, 0x02, 0, 1, 0]);
+
+        let image = TgaReader::new().read_from_memory(&data).expect("Read failed");
+        assert_eq!(image.channels, 3);
+        let px = image.to_u8();
+        assert_eq!(&px[0..3], [255, 0, 0]);
+        assert_eq!(&px[3..6], [0, 0, 255]);
+        assert_eq!(&px[9..12], [0, 0, 255]);
+    }
+
+    /// Tests that the attributes type controls alpha.
+    #[test]
+    fn test_alpha_attribute_type() {
+        let mut image = test_image(4);
+        image.metadata.attrs.set("TGA:AlphaType", AttrValue::Str("premultiplied".into()));
+        let mut bytes = TgaWriter::new().write_to_memory(&image).expect("Write failed");
+
+        let loaded = TgaReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(loaded.channels, 4);
+        assert_eq!(
+            loaded.metadata.attrs.get("TGA:AlphaType").and_then(|v| v.as_str()),
+            Some("premultiplied")
+        );
+
+        // Attributes type 0: alpha is not meaningful
+        let attribute_type = bytes.len() - FOOTER_SIZE - 1;
+        bytes[attribute_type] = 0;
+        let loaded = TgaReader::new().read_from_memory(&bytes).expect("Read failed");
+        assert_eq!(loaded.channels, 3);
+
+        // TGA 1.0 files with zero alpha everywhere are opaque
+        let mut v1 = vec![0u8, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 0, 32, 0x28];
+        v1.extend_from_slice(&[10, 20, 30, 0]);
+        let loaded = TgaReader::new().read_from_memory(&v1).expect("Read failed");
+        assert_eq!(loaded.channels, 3);
+        assert_eq!(loaded.to_u8(), [30, 20, 10]);
+    }
+
+    /// Tests extension area metadata.
+    #[test]
+    fn test_extension_metadata() {
+        let mut image = test_image(3);
+        image.metadata.attrs.set("ImageDescription", AttrValue::Str("first\nsecond".into()));
+        image.metadata.attrs.set("DateTime", AttrValue::Str("2024:03:05 10:20:30".into()));
+        image.metadata.attrs.set("PixelAspectRatio", AttrValue::Float(2.0));
+        image.metadata.gamma = Some(2.2);
+
+        let bytes = TgaWriter::new().write_to_memory(&image).expect("Write failed");
+        let loaded = TgaReader::new().read_from_memory(&bytes).expect("Read failed");
+        let attrs = &loaded.metadata.attrs;
+
+        assert_eq!(attrs.get("ImageDescription").and_then(|v| v.as_str()), Some("first\nsecond"));
+        assert_eq!(attrs.get("DateTime").and_then(|v| v.as_str()), Some("2024:03:05 10:20:30"));
+        assert_eq!(attrs.get("PixelAspectRatio").and_then(|v| v.as_f32()), Some(2.0));
+        assert_eq!(loaded.metadata.gamma, Some(2.2));
+    }
+
+    /// Tests header detection.
+    #[test]
+    fn test_can_read() {
+        let reader = TgaReader::new();
+        assert!(reader.can_read(&[0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 4, 0, 24, 0]));
+        assert!(reader.can_read(&[0, 1, 9, 0, 0, 2, 0, 24, 0, 0, 0, 0, 4, 0, 4, 0, 8, 0]));
+        // Wrong depth for gray, missing color map for indexed
+        assert!(!reader.can_read(&[0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 4, 0, 24, 0]));
+        assert!(!reader.can_read(&[0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 0, 4, 0, 8, 0]));
+        assert!(!reader.can_read(&[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A]));
+    }
+}
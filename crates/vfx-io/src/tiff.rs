@@ -6,11 +6,14 @@
 //! # Overview
 //!
 //! TIFF (Tagged Image File Format) is a flexible format supporting:
-//! - 8-bit, 16-bit, and 32-bit float per channel
+//! - 8-bit, 16-bit, 16-bit half and 32-bit float per channel
 //! - Grayscale, RGB, RGBA, and CMYK
-//! - Multiple compression methods (LZW, ZIP, PackBits)
-//! - Rich metadata through IFD tags
-//! - Multi-page documents
+//! - Multiple compression methods (LZW, ZIP, PackBits) with horizontal
+//!   and floating-point predictors
+//! - Rich metadata through IFD tags, including EXIF/GPS sub-IFDs and XMP
+//! - Embedded ICC profiles (tag 34675), see [`crate::icc`]
+//! - Multi-page documents, exposed as subimages
+//! - Strips or tiles, in classic TIFF or BigTIFF (64-bit offsets)
 //!
 //! # Architecture
 //!
@@ -24,6 +27,7 @@
 //! 2. **Convenience functions** (simple cases):
 //!    - [`read()`] - read with defaults
 //!    - [`write()`] - write with defaults
+//!    - [`num_pages()`], [`read_page()`], [`write_pages()`] - multi-page files
 //!
 //! # Examples
 //!
@@ -47,10 +51,32 @@
 //! });
 //! writer.write("output.tiff", &image)?;
 //! ```
+//!
+//! Tiled BigTIFF with float prediction, and page access:
+//! ```ignore
+//! use vfx_io::tiff::{self, BitDepth, Compression, Predictor, TiffWriter, TiffWriterOptions};
+//!
+//! let writer = TiffWriter::with_options(TiffWriterOptions {
+//!     bit_depth: BitDepth::SixteenFloat,
+//!     compression: Compression::Deflate,
+//!     predictor: Predictor::FloatingPoint,
+//!     tile_size: Some((256, 256)),
+//!     bigtiff: true,
+//! });
+//! writer.write_pages("scan.tif", &[page0, page1])?;
+//!
+//! for page in 0..tiff::num_pages("scan.tif")? {
+//!     let image = tiff::read_page("scan.tif", page)?;
+//! }
+//! ```
 
-use crate::{AttrValue, FormatReader, FormatWriter, ImageData, IoError, IoResult, Metadata, PixelData, PixelFormat};
-use std::io::{BufReader, Cursor, Read, Seek};
+use crate::attrs::exif::{self, Field};
+use crate::attrs::xmp;
+use crate::icc;
+use crate::{AttrValue, Attrs, FormatReader, FormatWriter, ImageData, IoError, IoResult, Metadata, PixelData, PixelFormat};
+use std::io::{BufReader, BufWriter, Cursor, Read, Seek, Write};
 use std::path::Path;
+use tiff::encoder::{DirectoryEncoder, TiffEncoder, TiffKind};
 use tiff::tags::Tag;
 
 // ============================================================================
@@ -80,10 +106,48 @@ pub enum BitDepth {
     /// 16 bits per channel (0-65535). Default for quality.
     #[default]
     Sixteen,
+    /// 16-bit half float per channel. HDR at half the size of 32-bit.
+    SixteenFloat,
     /// 32-bit float per channel. For HDR/linear workflow.
     ThirtyTwoFloat,
 }
 
+impl BitDepth {
+    /// Bits per sample.
+    fn bits(self) -> u16 {
+        match self {
+            BitDepth::Eight => 8,
+            BitDepth::Sixteen | BitDepth::SixteenFloat => 16,
+            BitDepth::ThirtyTwoFloat => 32,
+        }
+    }
+
+    /// Returns true for IEEE floating-point samples.
+    fn is_float(self) -> bool {
+        matches!(self, BitDepth::SixteenFloat | BitDepth::ThirtyTwoFloat)
+    }
+
+    /// Converts image samples to native-endian bytes at this depth.
+    fn encode(self, image: &ImageData) -> Vec<u8> {
+        match self {
+            BitDepth::Eight => image.to_u8(),
+            BitDepth::Sixteen => image
+                .to_f32()
+                .iter()
+                .flat_map(|&v| ((v.clamp(0.0, 1.0) * 65535.0) as u16).to_ne_bytes())
+                .collect(),
+            BitDepth::SixteenFloat => image
+                .to_f32()
+                .iter()
+                .flat_map(|&v| half::f16::from_f32(v).to_ne_bytes())
+                .collect(),
+            BitDepth::ThirtyTwoFloat => {
+                image.to_f32().iter().flat_map(|v| v.to_ne_bytes()).collect()
+            }
+        }
+    }
+}
+
 // ============================================================================
 // Compression
 // ============================================================================
@@ -112,13 +176,116 @@ pub enum Compression {
 }
 
 impl Compression {
-    /// Convert to tiff crate's compression type.
-    fn to_tiff(&self) -> tiff::encoder::Compression {
+    /// TIFF `Compression` tag value.
+    fn tag(self) -> u16 {
+        use tiff::tags::CompressionMethod;
+
         match self {
-            Compression::None => tiff::encoder::Compression::Uncompressed,
-            Compression::Lzw => tiff::encoder::Compression::Lzw,
-            Compression::Deflate => tiff::encoder::Compression::Deflate(tiff::encoder::DeflateLevel::Balanced),
-            Compression::PackBits => tiff::encoder::Compression::Packbits,
+            Compression::None => CompressionMethod::None,
+            Compression::Lzw => CompressionMethod::LZW,
+            Compression::Deflate => CompressionMethod::Deflate,
+            Compression::PackBits => CompressionMethod::PackBits,
+        }
+        .to_u16()
+    }
+
+    /// Compresses one strip or tile of `row_bytes`-wide rows.
+    ///
+    /// PackBits runs never cross a row boundary, as the spec requires.
+    fn compress(self, chunk: Vec<u8>, row_bytes: usize) -> IoResult<Vec<u8>> {
+        use tiff::encoder::compression::{CompressionAlgorithm, Deflate, Lzw, Packbits};
+
+        let mut out = Vec::with_capacity(chunk.len() / 2);
+        match self {
+            Compression::None => return Ok(chunk),
+            Compression::Lzw => Lzw.write_to(&mut out, &chunk).map(drop),
+            Compression::Deflate => Deflate::with_level(tiff::encoder::DeflateLevel::Balanced)
+                .write_to(&mut out, &chunk)
+                .map(drop),
+            Compression::PackBits => chunk
+                .chunks(row_bytes)
+                .try_for_each(|row| Packbits.write_to(&mut out, row).map(drop)),
+        }?;
+        Ok(out)
+    }
+}
+
+// ============================================================================
+// Predictor
+// ============================================================================
+
+/// TIFF predictor applied before compression.
+///
+/// Predictors store differences between neighbouring samples, which LZW
+/// and Deflate compress far better than raw values on smooth images.
+///
+/// # Recommendations
+///
+/// - **Horizontal**: 8/16-bit integer scans and textures.
+/// - **FloatingPoint**: half and float plates (Adobe Photoshop
+///   Technical Note 3). Rearranges bytes so exponents compress together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Predictor {
+    /// No prediction.
+    #[default]
+    None,
+    /// Horizontal differencing (predictor 2). Integer bit depths only.
+    Horizontal,
+    /// Floating-point byte-plane differencing (predictor 3). Float bit depths only.
+    FloatingPoint,
+}
+
+impl Predictor {
+    /// TIFF `Predictor` tag value.
+    fn tag(self) -> u16 {
+        match self {
+            Predictor::None => 1,
+            Predictor::Horizontal => 2,
+            Predictor::FloatingPoint => 3,
+        }
+    }
+
+    /// Applies the predictor in place to one row of native-endian samples.
+    fn apply(self, row: &mut [u8], samples: usize, sample_bytes: usize) {
+        match (self, sample_bytes) {
+            (Predictor::None, _) => {}
+            (Predictor::Horizontal, 1) => {
+                for i in (samples..row.len()).rev() {
+                    row[i] = row[i].wrapping_sub(row[i - samples]);
+                }
+            }
+            (Predictor::Horizontal, _) => {
+                let mut values: Vec<u16> = row
+                    .chunks_exact(2)
+                    .map(|b| u16::from_ne_bytes([b[0], b[1]]))
+                    .collect();
+                for i in (samples..values.len()).rev() {
+                    values[i] = values[i].wrapping_sub(values[i - samples]);
+                }
+                for (dst, v) in row.chunks_exact_mut(2).zip(values) {
+                    dst.copy_from_slice(&v.to_ne_bytes());
+                }
+            }
+            (Predictor::FloatingPoint, _) => {
+                // Split into byte planes, most significant first, then
+                // difference the planes as one byte stream
+                let count = row.len() / sample_bytes;
+                let mut planes = vec![0u8; row.len()];
+                for (i, value) in row.chunks_exact(sample_bytes).enumerate() {
+                    for b in 0..sample_bytes {
+                        let byte = if cfg!(target_endian = "little") {
+                            value[sample_bytes - 1 - b]
+                        } else {
+                            value[b]
+                        };
+                        planes[b * count + i] = byte;
+                    }
+                }
+                for i in (samples..planes.len()).rev() {
+                    planes[i] = planes[i].wrapping_sub(planes[i - samples]);
+                }
+                row.copy_from_slice(&planes);
+            }
         }
     }
 }
@@ -129,7 +296,7 @@ impl Compression {
 
 /// Options for reading TIFF files.
 ///
-/// Currently minimal - TIFF reading is mostly automatic.
+/// Selects the page (IFD) to decode; everything else is automatic.
 ///
 /// # Example
 ///
@@ -137,13 +304,21 @@ impl Compression {
 /// use vfx_io::tiff::{TiffReader, TiffReaderOptions};
 /// use vfx_io::FormatReader;
 ///
-/// let reader = TiffReader::with_options(TiffReaderOptions::default());
+/// // Third page of a multi-page scan
+/// let reader = TiffReader::with_options(TiffReaderOptions {
+///     page: 2,
+///     ..Default::default()
+/// });
 /// let image = reader.read("scan.tiff")?;
 /// ```
 #[derive(Debug, Clone, Default)]
 pub struct TiffReaderOptions {
-    /// Reserved for future use (page selection, etc.)
-    _reserved: (),
+    /// Page (IFD) index to read, 0-based. Default: 0.
+    pub page: usize,
+    /// Converts pixels from the embedded ICC profile into a working space.
+    /// Default: None (pixels are returned as stored).
+    #[cfg(feature = "icc")]
+    pub icc_convert: Option<icc::IccConvert>,
 }
 
 // ============================================================================
@@ -152,18 +327,20 @@ pub struct TiffReaderOptions {
 
 /// Options for writing TIFF files.
 ///
-/// Controls bit depth and compression method.
+/// Controls bit depth, compression, prediction and file layout.
 ///
 /// # Example
 ///
 /// ```ignore
-/// use vfx_io::tiff::{TiffWriter, TiffWriterOptions, BitDepth, Compression};
+/// use vfx_io::tiff::{TiffWriter, TiffWriterOptions, BitDepth, Compression, Predictor};
 /// use vfx_io::FormatWriter;
 ///
 /// // High quality archival
 /// let options = TiffWriterOptions {
 ///     bit_depth: BitDepth::Sixteen,
 ///     compression: Compression::Deflate,
+///     predictor: Predictor::Horizontal,
+///     ..Default::default()
 /// };
 /// let writer = TiffWriter::with_options(options);
 /// writer.write("archive.tiff", &image)?;
@@ -174,6 +351,17 @@ pub struct TiffWriterOptions {
     pub bit_depth: BitDepth,
     /// Compression method. Default: LZW.
     pub compression: Compression,
+    /// Predictor applied before compression. Must match the bit depth
+    /// (horizontal for integers, floating-point for floats). Default: None.
+    pub predictor: Predictor,
+    /// Tile width and height, or `None` for strips. Both must be
+    /// multiples of 16. Default: None.
+    pub tile_size: Option<(u32, u32)>,
+    /// Writes BigTIFF with 64-bit offsets. Default: false.
+    ///
+    /// Files whose uncompressed pixel data reaches 4 GiB are written as
+    /// BigTIFF regardless.
+    pub bigtiff: bool,
 }
 
 impl Default for TiffWriterOptions {
@@ -181,6 +369,9 @@ impl Default for TiffWriterOptions {
         Self {
             bit_depth: BitDepth::Sixteen,
             compression: Compression::Lzw,
+            predictor: Predictor::None,
+            tile_size: None,
+            bigtiff: false,
         }
     }
 }
@@ -195,8 +386,10 @@ impl Default for TiffWriterOptions {
 ///
 /// # Features
 ///
-/// - 8-bit, 16-bit, and 32-bit float support
+/// - 8-bit, 16-bit, 16-bit half and 32-bit float support
 /// - Grayscale, RGB, RGBA input
+/// - Strips or tiles, classic TIFF or BigTIFF, any predictor
+/// - Page selection; pages are exposed as subimages
 /// - Comprehensive metadata extraction (resolution, dates, software)
 /// - Memory and file reading
 ///
@@ -216,7 +409,6 @@ impl Default for TiffWriterOptions {
 /// ```
 #[derive(Debug, Clone)]
 pub struct TiffReader {
-    #[allow(dead_code)]
     options: TiffReaderOptions,
 }
 
@@ -226,14 +418,37 @@ impl TiffReader {
         Self::with_options(TiffReaderOptions::default())
     }
 
+    /// Counts the pages in a TIFF held in memory.
+    pub fn num_pages_from_memory(&self, data: &[u8]) -> IoResult<usize> {
+        count_pages(Cursor::new(data))
+    }
+
+    /// Returns a reader for `page`; TIFF pages have no mip levels.
+    fn for_page(&self, page: usize, miplevel: usize) -> IoResult<Self> {
+        if miplevel != 0 {
+            return Err(IoError::UnsupportedFeature(format!(
+                "TIFF has no mip level {}",
+                miplevel
+            )));
+        }
+        let mut options = self.options.clone();
+        options.page = page;
+        Ok(Self::with_options(options))
+    }
+
     /// Internal read implementation.
     fn read_impl<R: Read + Seek>(&self, reader: R) -> IoResult<ImageData> {
-        use tiff::decoder::{Decoder, DecodingResult};
+        use tiff::decoder::DecodingResult;
         use tiff::ColorType;
 
-        let buf_reader = BufReader::new(reader);
-        let mut decoder = Decoder::new(buf_reader)
-            .map_err(|e| IoError::DecodeError(e.to_string()))?;
+        let mut decoder = open_decoder(reader)?;
+
+        let page = self.options.page;
+        if page > 0 {
+            decoder
+                .seek_to_image(page)
+                .map_err(|e| IoError::DecodeError(format!("TIFF page {}: {}", page, e)))?;
+        }
 
         let (width, height) = decoder
             .dimensions()
@@ -278,6 +493,23 @@ impl TiffReader {
                 let f32_data: Vec<f32> = buf.iter().map(|&v| v as f32 / 65535.0).collect();
                 (PixelData::F32(f32_data), PixelFormat::F32, 1)
             }
+            // 16-bit half float -> F32
+            (ColorType::Gray(16), DecodingResult::F16(buf)) => {
+                let f32_data: Vec<f32> = buf.iter().map(|v| v.to_f32()).collect();
+                (PixelData::F32(f32_data), PixelFormat::F32, 1)
+            }
+            (ColorType::RGB(16), DecodingResult::F16(buf)) => {
+                let f32_data: Vec<f32> = buf.iter().map(|v| v.to_f32()).collect();
+                (PixelData::F32(f32_data), PixelFormat::F32, 3)
+            }
+            (ColorType::RGBA(16), DecodingResult::F16(buf)) => {
+                let f32_data: Vec<f32> = buf.iter().map(|v| v.to_f32()).collect();
+                (PixelData::F32(f32_data), PixelFormat::F32, 4)
+            }
+            // 32-bit float Grayscale
+            (ColorType::Gray(32), DecodingResult::F32(buf)) => {
+                (PixelData::F32(buf), PixelFormat::F32, 1)
+            }
             // 32-bit float RGB
             (ColorType::RGB(32), DecodingResult::F32(buf)) => {
                 (PixelData::F32(buf), PixelFormat::F32, 3)
@@ -346,21 +578,31 @@ impl TiffReader {
 
         // Extract TIFF tags
         extract_tag_u16(&mut decoder, Tag::Compression, "Compression", &mut metadata);
+        extract_tag_u16(&mut decoder, Tag::Predictor, "Predictor", &mut metadata);
+        extract_tag_u32(&mut decoder, Tag::TileWidth, "TileWidth", &mut metadata);
+        extract_tag_u32(&mut decoder, Tag::TileLength, "TileLength", &mut metadata);
         extract_tag_f64(&mut decoder, Tag::XResolution, "XResolution", &mut metadata);
         extract_tag_f64(&mut decoder, Tag::YResolution, "YResolution", &mut metadata);
         extract_tag_u16(&mut decoder, Tag::ResolutionUnit, "ResolutionUnit", &mut metadata);
-        extract_tag_string(&mut decoder, Tag::Software, "Software", &mut metadata);
-        extract_tag_string(&mut decoder, Tag::Artist, "Artist", &mut metadata);
-        extract_tag_string(&mut decoder, Tag::DateTime, "DateTime", &mut metadata);
+        read_exif_tags(&mut decoder, &mut metadata.attrs);
+        if let Ok(Some(value)) = decoder.find_tag(Tag::IccProfile) {
+            if let Ok(profile) = value.into_u8_vec() {
+                icc::set_profile(&mut metadata.attrs, profile);
+            }
+        }
 
-        Ok(ImageData {
+        #[allow(unused_mut)]
+        let mut image = ImageData {
             width,
             height,
             channels,
             format,
             data,
             metadata,
-        })
+        };
+        #[cfg(feature = "icc")]
+        icc::convert_on_read(&mut image, self.options.icc_convert.as_ref())?;
+        Ok(image)
     }
 }
 
@@ -381,15 +623,21 @@ impl FormatReader<TiffReaderOptions> for TiffReader {
         &["tiff", "tif"]
     }
 
-    /// Checks for TIFF magic bytes (II/MM + 42).
+    /// Checks for TIFF magic bytes (II/MM + 42, or 43 for BigTIFF).
     fn can_read(&self, header: &[u8]) -> bool {
         if header.len() < 4 {
             return false;
         }
         // Little-endian: II + 42 (0x2A00)
-        let le = header[0] == b'I' && header[1] == b'I' && header[2] == 0x2A && header[3] == 0x00;
+        let le = header[0] == b'I'
+            && header[1] == b'I'
+            && matches!(header[2], 0x2A | 0x2B)
+            && header[3] == 0x00;
         // Big-endian: MM + 42 (0x002A)
-        let be = header[0] == b'M' && header[1] == b'M' && header[2] == 0x00 && header[3] == 0x2A;
+        let be = header[0] == b'M'
+            && header[1] == b'M'
+            && header[2] == 0x00
+            && matches!(header[3], 0x2A | 0x2B);
         le || be
     }
 
@@ -408,6 +656,37 @@ impl FormatReader<TiffReaderOptions> for TiffReader {
     fn with_options(options: TiffReaderOptions) -> Self {
         Self { options }
     }
+
+    /// Pages (IFDs) are exposed as subimages.
+    fn supports_subimages(&self) -> bool {
+        true
+    }
+
+    /// Counts the pages in the file.
+    fn num_subimages<P: AsRef<Path>>(&self, path: P) -> IoResult<usize> {
+        count_pages(std::fs::File::open(path.as_ref())?)
+    }
+
+    /// Reads page `subimage`; `miplevel` must be 0.
+    fn read_subimage<P: AsRef<Path>>(
+        &self,
+        path: P,
+        subimage: usize,
+        miplevel: usize,
+    ) -> IoResult<ImageData> {
+        let reader = self.for_page(subimage, miplevel)?;
+        reader.read_impl(std::fs::File::open(path.as_ref())?)
+    }
+
+    /// Reads page `subimage` from memory; `miplevel` must be 0.
+    fn read_subimage_from_memory(
+        &self,
+        data: &[u8],
+        subimage: usize,
+        miplevel: usize,
+    ) -> IoResult<ImageData> {
+        self.for_page(subimage, miplevel)?.read_impl(Cursor::new(data))
+    }
 }
 
 // ============================================================================
@@ -420,8 +699,10 @@ impl FormatReader<TiffReaderOptions> for TiffReader {
 ///
 /// # Features
 ///
-/// - 8-bit, 16-bit, and 32-bit float output
-/// - Multiple compression methods
+/// - 8-bit, 16-bit, 16-bit half and 32-bit float output
+/// - Multiple compression methods with horizontal/floating-point predictors
+/// - Strips or tiles, classic TIFF or BigTIFF
+/// - Multi-page files via [`TiffWriter::write_pages`]
 /// - Grayscale, RGB, RGBA output
 /// - Memory and file writing
 ///
@@ -449,233 +730,307 @@ impl TiffWriter {
         Self::with_options(TiffWriterOptions::default())
     }
 
+    /// Writes several images as the pages of one TIFF file.
+    ///
+    /// Pages may differ in size and channel count. They read back as
+    /// subimages through [`read_page`] and the format registry.
+    pub fn write_pages<P: AsRef<Path>>(&self, path: P, pages: &[ImageData]) -> IoResult<()> {
+        let mut file = BufWriter::new(std::fs::File::create(path.as_ref())?);
+        self.write_impl(&mut file, pages)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Writes several images as the pages of one TIFF in memory.
+    pub fn write_pages_to_memory(&self, pages: &[ImageData]) -> IoResult<Vec<u8>> {
+        let mut buffer = Cursor::new(Vec::new());
+        self.write_impl(&mut buffer, pages)?;
+        Ok(buffer.into_inner())
+    }
+
     /// Internal write implementation.
-    fn write_impl<W: std::io::Write + Seek>(&self, writer: W, image: &ImageData) -> IoResult<()> {
-        use tiff::encoder::TiffEncoder;
-
-        let encoder = TiffEncoder::new(writer)
-            .map_err(|e| IoError::EncodeError(e.to_string()))?;
-
-        let width = image.width;
-        let height = image.height;
-
-        let channels = image.channels as u8;
-        match self.options.bit_depth {
-            BitDepth::Eight => {
-                let u8_data = image.to_u8();
-                self.write_u8(encoder, width, height, channels, &u8_data, image)?;
-            }
-            BitDepth::Sixteen => {
-                let f32_data = image.to_f32();
-                let u16_data: Vec<u16> = f32_data
-                    .iter()
-                    .map(|&v| (v.clamp(0.0, 1.0) * 65535.0) as u16)
-                    .collect();
-                self.write_u16(encoder, width, height, channels, &u16_data, image)?;
-            }
-            BitDepth::ThirtyTwoFloat => {
-                let f32_data = image.to_f32();
-                self.write_f32(encoder, width, height, channels, &f32_data, image)?;
-            }
+    fn write_impl<W: Write + Seek>(&self, writer: W, pages: &[ImageData]) -> IoResult<()> {
+        if pages.is_empty() {
+            return Err(IoError::EncodeError("no TIFF pages to write".into()));
         }
+        if self.needs_bigtiff(pages) {
+            let encoder = TiffEncoder::new_big(writer).map_err(encode_err)?;
+            self.write_all(encoder, pages)
+        } else {
+            let encoder = TiffEncoder::new(writer).map_err(encode_err)?;
+            self.write_all(encoder, pages)
+        }
+    }
 
-        Ok(())
+    /// BigTIFF when requested, or when raw pixel data would overflow
+    /// classic TIFF's 32-bit offsets.
+    fn needs_bigtiff(&self, pages: &[ImageData]) -> bool {
+        let sample_bytes = u64::from(self.options.bit_depth.bits() / 8);
+        let samples: u64 = pages
+            .iter()
+            .map(|p| u64::from(p.width) * u64::from(p.height) * u64::from(p.channels))
+            .sum();
+        let total = samples * sample_bytes;
+        self.options.bigtiff || total >= u64::from(u32::MAX)
     }
 
-    /// Writes 8-bit data.
-    fn write_u8<W: std::io::Write + Seek>(
+    fn write_all<W: Write + Seek, K: TiffKind>(
         &self,
-        encoder: tiff::encoder::TiffEncoder<W>,
-        width: u32,
-        height: u32,
-        channels: u8,
-        data: &[u8],
-        image: &ImageData,
+        mut encoder: TiffEncoder<W, K>,
+        pages: &[ImageData],
     ) -> IoResult<()> {
-        use tiff::encoder::colortype;
-
-        let mut encoder = encoder.with_compression(self.options.compression.to_tiff());
-        match channels {
-            1 => {
-                let mut image_encoder = encoder
-                    .new_image::<colortype::Gray8>(width, height)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-                apply_tiff_metadata(&mut image_encoder, image)?;
-                image_encoder
-                    .write_data(data)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-            }
-            3 => {
-                let mut image_encoder = encoder
-                    .new_image::<colortype::RGB8>(width, height)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-                apply_tiff_metadata(&mut image_encoder, image)?;
-                image_encoder
-                    .write_data(data)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-            }
-            4 => {
-                let mut image_encoder = encoder
-                    .new_image::<colortype::RGBA8>(width, height)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-                apply_tiff_metadata(&mut image_encoder, image)?;
-                image_encoder
-                    .write_data(data)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-            }
-            _ => {
-                return Err(IoError::EncodeError(format!(
-                    "unsupported channel count: {}",
-                    channels
-                )));
-            }
+        for (index, page) in pages.iter().enumerate() {
+            self.write_page(&mut encoder, page, index, pages.len())?;
         }
         Ok(())
     }
 
-    /// Writes 16-bit data.
-    fn write_u16<W: std::io::Write + Seek>(
+    /// Writes one image directory with its strips or tiles.
+    fn write_page<W: Write + Seek, K: TiffKind>(
         &self,
-        encoder: tiff::encoder::TiffEncoder<W>,
-        width: u32,
-        height: u32,
-        channels: u8,
-        data: &[u16],
+        encoder: &mut TiffEncoder<W, K>,
         image: &ImageData,
+        index: usize,
+        count: usize,
     ) -> IoResult<()> {
-        use tiff::encoder::colortype;
-
-        let mut encoder = encoder.with_compression(self.options.compression.to_tiff());
-        match channels {
-            1 => {
-                let mut image_encoder = encoder
-                    .new_image::<colortype::Gray16>(width, height)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-                apply_tiff_metadata(&mut image_encoder, image)?;
-                image_encoder
-                    .write_data(data)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-            }
-            3 => {
-                let mut image_encoder = encoder
-                    .new_image::<colortype::RGB16>(width, height)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-                apply_tiff_metadata(&mut image_encoder, image)?;
-                image_encoder
-                    .write_data(data)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-            }
-            4 => {
-                let mut image_encoder = encoder
-                    .new_image::<colortype::RGBA16>(width, height)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-                apply_tiff_metadata(&mut image_encoder, image)?;
-                image_encoder
-                    .write_data(data)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-            }
-            _ => {
-                return Err(IoError::EncodeError(format!(
-                    "unsupported channel count: {}",
-                    channels
+        use tiff::tags::{PhotometricInterpretation, PlanarConfiguration, SampleFormat};
+
+        let options = &self.options;
+        let (width, height) = (image.width, image.height);
+        let samples = image.channels as usize;
+        if !matches!(samples, 1 | 3 | 4) {
+            return Err(IoError::EncodeError(format!(
+                "unsupported channel count: {}",
+                samples
+            )));
+        }
+        if width == 0 || height == 0 {
+            return Err(IoError::EncodeError(format!(
+                "invalid TIFF dimensions: {}x{}",
+                width, height
+            )));
+        }
+        let float = options.bit_depth.is_float();
+        match (options.predictor, float) {
+            (Predictor::Horizontal, true) | (Predictor::FloatingPoint, false) => {
+                return Err(IoError::UnsupportedFeature(format!(
+                    "{:?} predictor with {:?} samples",
+                    options.predictor, options.bit_depth
                 )));
             }
+            _ => {}
         }
-        Ok(())
-    }
 
-    /// Writes 32-bit float data.
-    fn write_f32<W: std::io::Write + Seek>(
-        &self,
-        encoder: tiff::encoder::TiffEncoder<W>,
-        width: u32,
-        height: u32,
-        channels: u8,
-        data: &[f32],
-        image: &ImageData,
-    ) -> IoResult<()> {
-        use tiff::encoder::colortype;
-
-        // Note: f32 tiffs don't support compression (horizontal_predict is unreachable)
-        let mut encoder = encoder.with_compression(tiff::encoder::Compression::Uncompressed);
-        match channels {
-            1 => {
-                let mut image_encoder = encoder
-                    .new_image::<colortype::Gray32Float>(width, height)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-                apply_tiff_metadata(&mut image_encoder, image)?;
-                image_encoder
-                    .write_data(data)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-            }
-            3 => {
-                let mut image_encoder = encoder
-                    .new_image::<colortype::RGB32Float>(width, height)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-                apply_tiff_metadata(&mut image_encoder, image)?;
-                image_encoder
-                    .write_data(data)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-            }
-            4 => {
-                let mut image_encoder = encoder
-                    .new_image::<colortype::RGBA32Float>(width, height)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-                apply_tiff_metadata(&mut image_encoder, image)?;
-                image_encoder
-                    .write_data(data)
-                    .map_err(|e| IoError::EncodeError(e.to_string()))?;
-            }
-            _ => {
-                return Err(IoError::EncodeError(format!(
-                    "unsupported channel count for f32: {}",
-                    channels
+        let sample_bytes = usize::from(options.bit_depth.bits() / 8);
+        let pixel_bytes = samples * sample_bytes;
+        let (chunk_w, chunk_h) = match options.tile_size {
+            Some((tw, th)) if tw == 0 || th == 0 || tw % 16 != 0 || th % 16 != 0 => {
+                return Err(IoError::UnsupportedFeature(format!(
+                    "TIFF tile size must be a multiple of 16, got {}x{}",
+                    tw, th
                 )));
             }
+            Some(size) => size,
+            None => (width, strip_rows(width as usize * pixel_bytes, height)),
+        };
+        let tiled = options.tile_size.is_some();
+        let row_bytes = chunk_w as usize * pixel_bytes;
+        let data = options.bit_depth.encode(image);
+
+        // EXIF and GPS sub-IFDs precede the image directory that points at them
+        let mut sub_ifds = Vec::new();
+        for (group, tag) in [
+            (exif::Group::Exif, Tag::ExifDirectory),
+            (exif::Group::Gps, Tag::GpsDirectory),
+        ] {
+            let fields = exif::fields(&image.metadata.attrs, group);
+            if fields.is_empty() {
+                continue;
+            }
+            let mut sub = encoder.extra_directory().map_err(encode_err)?;
+            for (field_tag, field) in &fields {
+                write_exif_field(&mut sub, *field_tag, field)?;
+            }
+            sub_ifds.push((tag, sub.finish_with_offsets().map_err(encode_err)?.offset));
         }
-        Ok(())
+
+        let mut dir = encoder.image_directory().map_err(encode_err)?;
+        let mut offsets = Vec::new();
+        let mut byte_counts = Vec::new();
+        for y in (0..height).step_by(chunk_h as usize) {
+            for x in (0..width).step_by(chunk_w as usize) {
+                // Strips stop at the last row; tiles are zero-padded to full size
+                let rows = chunk_h.min(height - y) as usize;
+                let copy_bytes = chunk_w.min(width - x) as usize * pixel_bytes;
+                let mut chunk = vec![0u8; if tiled { chunk_h as usize } else { rows } * row_bytes];
+                for r in 0..rows {
+                    let src = ((y as usize + r) * width as usize + x as usize) * pixel_bytes;
+                    chunk[r * row_bytes..][..copy_bytes]
+                        .copy_from_slice(&data[src..src + copy_bytes]);
+                }
+                for row in chunk.chunks_exact_mut(row_bytes) {
+                    options.predictor.apply(row, samples, sample_bytes);
+                }
+
+                let packed = options.compression.compress(chunk, row_bytes)?;
+                let offset = dir.write_data(&packed[..]).map_err(encode_err)?;
+                offsets.push(K::convert_offset(offset).map_err(encode_err)?);
+                byte_counts.push(K::convert_offset(packed.len() as u64).map_err(encode_err)?);
+            }
+        }
+
+        let photometric = if samples == 1 {
+            PhotometricInterpretation::BlackIsZero
+        } else {
+            PhotometricInterpretation::RGB
+        };
+        let sample_format = if float { SampleFormat::IEEEFP } else { SampleFormat::Uint };
+        let bits = vec![options.bit_depth.bits(); samples];
+        let formats = vec![sample_format.to_u16(); samples];
+
+        dir.write_tag(Tag::ImageWidth, width).map_err(encode_err)?;
+        dir.write_tag(Tag::ImageLength, height).map_err(encode_err)?;
+        dir.write_tag(Tag::BitsPerSample, &bits[..]).map_err(encode_err)?;
+        dir.write_tag(Tag::Compression, options.compression.tag())
+            .map_err(encode_err)?;
+        dir.write_tag(Tag::PhotometricInterpretation, photometric.to_u16())
+            .map_err(encode_err)?;
+        dir.write_tag(Tag::SamplesPerPixel, samples as u16)
+            .map_err(encode_err)?;
+        dir.write_tag(Tag::PlanarConfiguration, PlanarConfiguration::Chunky.to_u16())
+            .map_err(encode_err)?;
+        dir.write_tag(Tag::SampleFormat, &formats[..]).map_err(encode_err)?;
+        if samples == 4 {
+            // Unassociated alpha
+            dir.write_tag(Tag::ExtraSamples, 2u16).map_err(encode_err)?;
+        }
+        if options.predictor != Predictor::None {
+            dir.write_tag(Tag::Predictor, options.predictor.tag())
+                .map_err(encode_err)?;
+        }
+
+        let offsets = K::convert_slice(&offsets);
+        let byte_counts = K::convert_slice(&byte_counts);
+        if tiled {
+            dir.write_tag(Tag::TileWidth, chunk_w).map_err(encode_err)?;
+            dir.write_tag(Tag::TileLength, chunk_h).map_err(encode_err)?;
+            dir.write_tag(Tag::TileOffsets, offsets).map_err(encode_err)?;
+            dir.write_tag(Tag::TileByteCounts, byte_counts)
+                .map_err(encode_err)?;
+        } else {
+            dir.write_tag(Tag::RowsPerStrip, chunk_h).map_err(encode_err)?;
+            dir.write_tag(Tag::StripOffsets, offsets).map_err(encode_err)?;
+            dir.write_tag(Tag::StripByteCounts, byte_counts)
+                .map_err(encode_err)?;
+        }
+
+        if count > 1 {
+            // NewSubfileType "page of a multi-page image", PageNumber
+            dir.write_tag(Tag::NewSubfileType, 2u32).map_err(encode_err)?;
+            if let (Ok(page), Ok(total)) = (u16::try_from(index), u16::try_from(count)) {
+                dir.write_tag(Tag::Unknown(PAGE_NUMBER_TAG), &[page, total][..])
+                    .map_err(encode_err)?;
+            }
+        }
+
+        for (tag, offset) in sub_ifds {
+            dir.write_tag(tag, offset).map_err(encode_err)?;
+        }
+        apply_tiff_metadata(&mut dir, image)?;
+        dir.finish().map_err(encode_err)
     }
 }
 
-fn apply_tiff_metadata<
-    W: std::io::Write + Seek,
-    C: tiff::encoder::colortype::ColorType,
-    K: tiff::encoder::TiffKind,
->(
-    encoder: &mut tiff::encoder::ImageEncoder<'_, W, C, K>,
+/// TIFF `PageNumber` tag, which the tiff crate doesn't name.
+const PAGE_NUMBER_TAG: u16 = 297;
+
+/// TIFF `XMP` tag (XML packet as BYTE).
+const XMP_TAG: u16 = 700;
+
+/// Target uncompressed strip size.
+const STRIP_BYTES: usize = 1 << 20;
+
+/// Rows per strip for roughly [`STRIP_BYTES`] strips.
+fn strip_rows(row_bytes: usize, height: u32) -> u32 {
+    let rows = (STRIP_BYTES / row_bytes.max(1)).max(1);
+    u32::try_from(rows).unwrap_or(u32::MAX).min(height)
+}
+
+/// Maps a tiff crate error to an encode error.
+fn encode_err(e: impl std::fmt::Display) -> IoError {
+    IoError::EncodeError(e.to_string())
+}
+
+fn apply_tiff_metadata<W: Write + Seek, K: TiffKind>(
+    dir: &mut DirectoryEncoder<'_, W, K>,
     image: &ImageData,
 ) -> IoResult<()> {
-    use tiff::tags::{ResolutionUnit, Tag};
+    use tiff::encoder::Rational;
+    use tiff::tags::ResolutionUnit;
 
-    let dir = encoder.encoder();
-
-    if let Some(value) = image.metadata.attrs.get("Software").and_then(|v| v.as_str()) {
-        dir.write_tag(Tag::Software, value)
-            .map_err(|e| IoError::EncodeError(e.to_string()))?;
+    // Make, Model, Orientation, Software, DateTime, ...
+    for (tag, field) in exif::fields(&image.metadata.attrs, exif::Group::Image) {
+        write_exif_field(dir, tag, &field)?;
     }
-    if let Some(value) = image.metadata.attrs.get("Artist").and_then(|v| v.as_str()) {
-        dir.write_tag(Tag::Artist, value)
-            .map_err(|e| IoError::EncodeError(e.to_string()))?;
+    if let Some(packet) = xmp::write_xmp(&image.metadata.attrs) {
+        dir.write_tag(Tag::Unknown(XMP_TAG), packet.as_bytes())
+            .map_err(encode_err)?;
     }
-    if let Some(value) = image.metadata.attrs.get("DateTime").and_then(|v| v.as_str()) {
-        dir.write_tag(Tag::DateTime, value)
-            .map_err(|e| IoError::EncodeError(e.to_string()))?;
+    if let Some(profile) = icc::profile(&image.metadata.attrs) {
+        dir.write_tag(Tag::IccProfile, profile).map_err(encode_err)?;
     }
 
     let x_res = attr_to_f32(image.metadata.attrs.get("XResolution")).or(image.metadata.dpi);
     let y_res = attr_to_f32(image.metadata.attrs.get("YResolution")).or(image.metadata.dpi);
 
-    if let (Some(x_res), Some(y_res)) = (x_res, y_res) {
-        let x = rational_from_f32(x_res);
-        let y = rational_from_f32(y_res);
-        encoder.resolution_unit(ResolutionUnit::Inch);
-        encoder.x_resolution(x);
-        encoder.y_resolution(y);
-    }
+    // Unitless 1:1 unless the image carries a resolution
+    let (unit, x, y) = match (x_res, y_res) {
+        (Some(x_res), Some(y_res)) => (
+            ResolutionUnit::Inch,
+            rational_from_f32(x_res),
+            rational_from_f32(y_res),
+        ),
+        _ => (
+            ResolutionUnit::None,
+            Rational { n: 1, d: 1 },
+            Rational { n: 1, d: 1 },
+        ),
+    };
+    dir.write_tag(Tag::ResolutionUnit, unit.to_u16())
+        .map_err(encode_err)?;
+    dir.write_tag(Tag::XResolution, x).map_err(encode_err)?;
+    dir.write_tag(Tag::YResolution, y).map_err(encode_err)?;
 
     Ok(())
 }
 
+/// Writes one EXIF field. The tiff crate has no UNDEFINED type, so those
+/// fields are written as BYTE.
+fn write_exif_field<W: Write + Seek, K: TiffKind>(
+    dir: &mut DirectoryEncoder<'_, W, K>,
+    tag: u16,
+    field: &Field,
+) -> IoResult<()> {
+    use tiff::encoder::{Rational, SRational};
+
+    let tag = Tag::from_u16_exhaustive(tag);
+    match field {
+        Field::Byte(v) | Field::Undefined(v) => dir.write_tag(tag, &v[..]),
+        Field::Ascii(v) => dir.write_tag(tag, v.as_str()),
+        Field::Short(v) => dir.write_tag(tag, &v[..]),
+        Field::Long(v) => dir.write_tag(tag, &v[..]),
+        Field::Rational(v) => {
+            let v: Vec<Rational> = v.iter().map(|&(n, d)| Rational { n, d }).collect();
+            dir.write_tag(tag, &v[..])
+        }
+        Field::SRational(v) => {
+            let v: Vec<SRational> = v.iter().map(|&(n, d)| SRational { n, d }).collect();
+            dir.write_tag(tag, &v[..])
+        }
+    }
+    .map_err(encode_err)
+}
+
 fn attr_to_f32(value: Option<&AttrValue>) -> Option<f32> {
     match value {
         Some(AttrValue::Float(v)) => Some(*v),
@@ -711,15 +1066,12 @@ impl FormatWriter<TiffWriterOptions> for TiffWriter {
 
     /// Writes a TIFF file to disk.
     fn write<P: AsRef<Path>>(&self, path: P, image: &ImageData) -> IoResult<()> {
-        let file = std::fs::File::create(path.as_ref())?;
-        self.write_impl(file, image)
+        self.write_pages(path, std::slice::from_ref(image))
     }
 
     /// Writes a TIFF to a byte vector.
     fn write_to_memory(&self, image: &ImageData) -> IoResult<Vec<u8>> {
-        let mut buffer = Cursor::new(Vec::new());
-        self.write_impl(&mut buffer, image)?;
-        Ok(buffer.into_inner())
+        self.write_pages_to_memory(std::slice::from_ref(image))
     }
 
     /// Creates writer with custom options.
@@ -748,6 +1100,40 @@ pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
     TiffReader::new().read(path)
 }
 
+/// Returns the number of pages (IFDs) in a TIFF file.
+///
+/// Walks the IFD chain without decoding pixel data.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::tiff;
+///
+/// let pages = tiff::num_pages("scan.tiff")?;
+/// ```
+pub fn num_pages<P: AsRef<Path>>(path: P) -> IoResult<usize> {
+    TiffReader::new().num_subimages(path)
+}
+
+/// Reads one page of a TIFF file, 0-based.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::tiff;
+///
+/// let second = tiff::read_page("scan.tiff", 1)?;
+/// ```
+pub fn read_page<P: AsRef<Path>>(path: P, page: usize) -> IoResult<ImageData> {
+    // The remaining options depend on enabled features
+    #[allow(clippy::needless_update)]
+    let options = TiffReaderOptions {
+        page,
+        ..Default::default()
+    };
+    TiffReader::with_options(options).read(path)
+}
+
 /// Probe TIFF dimensions without decoding pixel data.
 ///
 /// Reads only the IFD header to extract width/height, much faster
@@ -791,6 +1177,19 @@ pub fn write<P: AsRef<Path>>(path: P, image: &ImageData) -> IoResult<()> {
     TiffWriter::new().write(path, image)
 }
 
+/// Writes images as the pages of one TIFF file with default options.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::tiff;
+///
+/// tiff::write_pages("contact.tiff", &[front, back])?;
+/// ```
+pub fn write_pages<P: AsRef<Path>>(path: P, pages: &[ImageData]) -> IoResult<()> {
+    TiffWriter::new().write_pages(path, pages)
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -809,34 +1208,135 @@ fn extract_tag_u16<R: Read + Seek>(
     }
 }
 
-/// Extracts f64 tag value.
-fn extract_tag_f64<R: Read + Seek>(
+/// Extracts u32 tag value (SHORT or LONG).
+fn extract_tag_u32<R: Read + Seek>(
     decoder: &mut tiff::decoder::Decoder<R>,
     tag: Tag,
     key: &str,
     metadata: &mut Metadata,
 ) {
     if let Ok(Some(value)) = decoder.find_tag(tag) {
-        if let Ok(v) = value.into_f64() {
-            metadata.attrs.set(key, AttrValue::Float(v as f32));
+        if let Ok(v) = value.into_u32() {
+            metadata.attrs.set(key, AttrValue::UInt(v));
         }
     }
 }
 
-/// Extracts string tag value.
-fn extract_tag_string<R: Read + Seek>(
+/// Extracts f64 tag value.
+fn extract_tag_f64<R: Read + Seek>(
     decoder: &mut tiff::decoder::Decoder<R>,
     tag: Tag,
     key: &str,
     metadata: &mut Metadata,
 ) {
     if let Ok(Some(value)) = decoder.find_tag(tag) {
-        if let Ok(v) = value.into_string() {
-            metadata.attrs.set(key, AttrValue::Str(v));
+        if let Ok(v) = value.into_f64() {
+            metadata.attrs.set(key, AttrValue::Float(v as f32));
         }
     }
 }
 
+/// Converts a decoded tag value to an EXIF field.
+fn exif_field(value: tiff::decoder::ifd::Value) -> Option<Field> {
+    use tiff::decoder::ifd::Value;
+
+    let values = match value {
+        Value::List(values) => values,
+        value => vec![value],
+    };
+    match values.first()? {
+        Value::Ascii(s) => Some(Field::Ascii(s.clone())),
+        // UNDEFINED decodes as BYTE
+        Value::Byte(_) => values
+            .iter()
+            .map(|v| if let Value::Byte(b) = v { Some(*b) } else { None })
+            .collect::<Option<_>>()
+            .map(Field::Byte),
+        Value::Short(_) => values
+            .iter()
+            .map(|v| if let Value::Short(x) = v { Some(*x) } else { None })
+            .collect::<Option<_>>()
+            .map(Field::Short),
+        Value::Unsigned(_) => values
+            .iter()
+            .map(|v| if let Value::Unsigned(x) = v { Some(*x) } else { None })
+            .collect::<Option<_>>()
+            .map(Field::Long),
+        Value::Rational(..) => values
+            .iter()
+            .map(|v| if let Value::Rational(n, d) = v { Some((*n, *d)) } else { None })
+            .collect::<Option<_>>()
+            .map(Field::Rational),
+        Value::SRational(..) => values
+            .iter()
+            .map(|v| if let Value::SRational(n, d) = v { Some((*n, *d)) } else { None })
+            .collect::<Option<_>>()
+            .map(Field::SRational),
+        _ => None,
+    }
+}
+
+/// Reads EXIF tags from the current IFD and its Exif/GPS sub-IFDs, and the
+/// XMP packet. Unreadable tags are skipped.
+fn read_exif_tags<R: Read + Seek>(decoder: &mut tiff::decoder::Decoder<R>, attrs: &mut Attrs) {
+    for tag in exif::tags(exif::Group::Image) {
+        if let Ok(Some(value)) = decoder.find_tag(Tag::from_u16_exhaustive(tag)) {
+            if let Some(field) = exif_field(value) {
+                exif::set_field(attrs, exif::Group::Image, tag, &field);
+            }
+        }
+    }
+
+    for (group, pointer) in [
+        (exif::Group::Exif, Tag::ExifDirectory),
+        (exif::Group::Gps, Tag::GpsDirectory),
+    ] {
+        let Ok(Some(value)) = decoder.find_tag(pointer) else { continue };
+        // BigTIFF writers store the pointer as LONG8
+        let ptr = match value {
+            tiff::decoder::ifd::Value::UnsignedBig(offset) => Ok(tiff::tags::IfdPointer(offset)),
+            value => value.into_ifd_pointer(),
+        };
+        let Ok(dir) = ptr.and_then(|ptr| decoder.read_directory(ptr)) else { continue };
+        for (tag, value) in decoder.read_directory_tags(&dir).tag_iter().flatten() {
+            if let Some(field) = exif_field(value) {
+                exif::set_field(attrs, group, tag.to_u16(), &field);
+            }
+        }
+    }
+
+    if let Ok(Some(value)) = decoder.find_tag(Tag::Unknown(XMP_TAG)) {
+        if let Ok(packet) = value.into_u8_vec() {
+            attrs.set("XMPSize", AttrValue::UInt(packet.len() as u32));
+            if let Ok(packet) = std::str::from_utf8(&packet) {
+                let _ = xmp::read_xmp(packet, attrs);
+            }
+        }
+    }
+}
+
+/// Opens a decoder without the tiff crate's default memory limits.
+///
+/// Film scans and BigTIFF plates exceed the default 256 MiB decode limit.
+fn open_decoder<R: Read + Seek>(reader: R) -> IoResult<tiff::decoder::Decoder<BufReader<R>>> {
+    let decoder = tiff::decoder::Decoder::new(BufReader::new(reader))
+        .map_err(|e| IoError::DecodeError(e.to_string()))?;
+    Ok(decoder.with_limits(tiff::decoder::Limits::unlimited()))
+}
+
+/// Counts pages by walking the IFD chain.
+fn count_pages<R: Read + Seek>(reader: R) -> IoResult<usize> {
+    let mut decoder = open_decoder(reader)?;
+    let mut count = 1;
+    while decoder.more_images() {
+        decoder
+            .next_image()
+            .map_err(|e| IoError::DecodeError(e.to_string()))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
 /// Extracts bit depth from color type.
 fn bit_depth_from_color(color_type: tiff::ColorType) -> u32 {
     match color_type {
@@ -952,9 +1452,236 @@ mod tests {
         assert!(reader.can_read(&[b'I', b'I', 0x2A, 0x00]));
         // Big-endian TIFF
         assert!(reader.can_read(&[b'M', b'M', 0x00, 0x2A]));
+        // BigTIFF, both byte orders
+        assert!(reader.can_read(&[b'I', b'I', 0x2B, 0x00]));
+        assert!(reader.can_read(&[b'M', b'M', 0x00, 0x2B]));
 
         // Invalid
         assert!(!reader.can_read(&[0x89, 0x50, 0x4E, 0x47])); // PNG
         assert!(!reader.can_read(&[0xFF, 0xD8, 0xFF])); // JPEG
     }
+
+    /// Smooth test image with an odd size so strips and tiles are ragged.
+    fn gradient(width: u32, height: u32, channels: u32) -> ImageData {
+        let mut data = Vec::with_capacity((width * height * channels) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                for c in 0..channels {
+                    data.push((x + y * 2 + c * 7) as f32 / (width + height * 2 + 32) as f32);
+                }
+            }
+        }
+        ImageData::from_f32(width, height, channels, data)
+    }
+
+    fn max_error(a: &ImageData, b: &ImageData) -> f32 {
+        a.to_f32()
+            .iter()
+            .zip(b.to_f32())
+            .map(|(x, y)| (x - y).abs())
+            .fold(0.0, f32::max)
+    }
+
+    fn roundtrip(image: &ImageData, options: TiffWriterOptions) -> (Vec<u8>, ImageData) {
+        let bytes = TiffWriter::with_options(options)
+            .write_to_memory(image)
+            .expect("Write failed");
+        let loaded = TiffReader::new()
+            .read_from_memory(&bytes)
+            .expect("Read failed");
+        assert_eq!((loaded.width, loaded.height), (image.width, image.height));
+        assert_eq!(loaded.channels, image.channels);
+        (bytes, loaded)
+    }
+
+    /// Tests tiled output with every compression and integer predictors.
+    #[test]
+    fn test_tiled_roundtrip() {
+        let image = gradient(40, 23, 3);
+        for compression in [
+            Compression::None,
+            Compression::Lzw,
+            Compression::Deflate,
+            Compression::PackBits,
+        ] {
+            for bit_depth in [BitDepth::Eight, BitDepth::Sixteen] {
+                let (_, loaded) = roundtrip(
+                    &image,
+                    TiffWriterOptions {
+                        bit_depth,
+                        compression,
+                        predictor: Predictor::Horizontal,
+                        tile_size: Some((16, 32)),
+                        ..Default::default()
+                    },
+                );
+                let limit = if bit_depth == BitDepth::Eight { 1.0 / 255.0 } else { 1.0 / 65535.0 };
+                let err = max_error(&image, &loaded);
+                assert!(err <= limit, "{:?} {:?}: {}", compression, bit_depth, err);
+                assert_eq!(loaded.metadata.attrs.get("TileWidth"), Some(&AttrValue::UInt(16)));
+                assert_eq!(loaded.metadata.attrs.get("TileLength"), Some(&AttrValue::UInt(32)));
+            }
+        }
+    }
+
+    /// Tests the floating-point predictor for half and float samples.
+    #[test]
+    fn test_float_predictor_roundtrip() {
+        // Smooth but non-repeating, like a plate
+        let (width, height) = (70, 45);
+        let data = (0..width * height * 4)
+            .map(|i| {
+                let (x, y, c) = ((i / 4) % width, (i / 4) / width, i % 4);
+                let (x, y) = (x as f32, y as f32);
+                0.5 + 0.4 * (x * 0.11 + c as f32).sin() * (y * 0.07).cos()
+            })
+            .collect();
+        let image = ImageData::from_f32(width, height, 4, data);
+        for bit_depth in [BitDepth::SixteenFloat, BitDepth::ThirtyTwoFloat] {
+            for tile_size in [None, Some((16, 16))] {
+                let options = TiffWriterOptions {
+                    bit_depth,
+                    compression: Compression::Deflate,
+                    tile_size,
+                    ..Default::default()
+                };
+                let (plain, _) = roundtrip(&image, options.clone());
+                let (predicted, loaded) = roundtrip(
+                    &image,
+                    TiffWriterOptions {
+                        predictor: Predictor::FloatingPoint,
+                        ..options
+                    },
+                );
+                let limit = if bit_depth == BitDepth::SixteenFloat { 1e-3 } else { 0.0 };
+                assert!(max_error(&image, &loaded) <= limit, "{:?}", bit_depth);
+                assert_eq!(loaded.metadata.attrs.get("Predictor"), Some(&AttrValue::UInt(3)));
+                assert!(predicted.len() < plain.len(), "{:?} {:?}", bit_depth, tile_size);
+            }
+        }
+    }
+
+    /// Tests BigTIFF output.
+    #[test]
+    fn test_bigtiff_roundtrip() {
+        let image = gradient(20, 20, 1);
+        let (bytes, loaded) = roundtrip(
+            &image,
+            TiffWriterOptions {
+                bit_depth: BitDepth::ThirtyTwoFloat,
+                tile_size: Some((16, 16)),
+                bigtiff: true,
+                ..Default::default()
+            },
+        );
+        // Version 43, 8-byte offsets
+        assert!(bytes[2..4] == [0x2B, 0x00] || bytes[2..4] == [0x00, 0x2B]);
+        assert!(TiffReader::new().can_read(&bytes));
+        assert_eq!(max_error(&image, &loaded), 0.0);
+    }
+
+    /// Tests writing pages and reading them back as subimages.
+    #[test]
+    fn test_multipage() {
+        let pages = [gradient(16, 8, 3), gradient(9, 5, 1), gradient(12, 12, 4)];
+        let temp_path = std::env::temp_dir().join("vfx_io_tiff_pages_test.tiff");
+        let writer = TiffWriter::with_options(TiffWriterOptions {
+            bigtiff: true,
+            ..Default::default()
+        });
+        writer.write_pages(&temp_path, &pages).expect("Write failed");
+
+        assert_eq!(num_pages(&temp_path).unwrap(), 3);
+        for (index, page) in pages.iter().enumerate() {
+            let loaded = read_page(&temp_path, index).expect("Read failed");
+            assert_eq!((loaded.width, loaded.channels), (page.width, page.channels));
+        }
+        assert!(read_page(&temp_path, 3).is_err());
+
+        let registry = crate::registry::FormatRegistry::global();
+        assert_eq!(registry.num_subimages(&temp_path).unwrap(), 3);
+        let second = registry.read_subimage(&temp_path, 1, 0).expect("Read failed");
+        assert_eq!((second.width, second.height), (9, 5));
+        assert!(registry.read_subimage(&temp_path, 1, 1).is_err());
+
+        let bytes = writer.write_pages_to_memory(&pages).unwrap();
+        let reader = TiffReader::new();
+        assert_eq!(reader.num_pages_from_memory(&bytes).unwrap(), 3);
+        let last = reader.read_subimage_from_memory(&bytes, 2, 0).unwrap();
+        assert_eq!(last.channels, 4);
+
+        let _ = std::fs::remove_file(&temp_path);
+    }
+
+    /// Tests EXIF, GPS and XMP attributes in classic TIFF and BigTIFF.
+    #[test]
+    fn test_exif_xmp_roundtrip() {
+        let mut image = gradient(16, 8, 3);
+        let attrs = &mut image.metadata.attrs;
+        attrs.set("Make", AttrValue::Str("Phase One".into()));
+        attrs.set("Software", AttrValue::Str("vfx-rs".into()));
+        attrs.set("Orientation", AttrValue::UInt(3));
+        attrs.set("Exif:FNumber", AttrValue::URational(8, 1));
+        attrs.set("Exif:ExifVersion", AttrValue::Str("0231".into()));
+        attrs.set("GPS:LongitudeRef", AttrValue::Str("E".into()));
+        attrs.set("GPS:Longitude", AttrValue::Double(13.4050));
+        attrs.set("XMP:photoshop:City", AttrValue::Str("Berlin".into()));
+
+        for bigtiff in [false, true] {
+            let options = TiffWriterOptions {
+                bigtiff,
+                ..Default::default()
+            };
+            let (_, loaded) = roundtrip(&image, options);
+            let attrs = &loaded.metadata.attrs;
+            assert_eq!(attrs.get_str("Make"), Some("Phase One"));
+            assert_eq!(attrs.get_str("Software"), Some("vfx-rs"));
+            assert_eq!(attrs.get_u32("Orientation"), Some(3));
+            assert_eq!(attrs.get_urational("Exif:FNumber"), Some((8, 1)));
+            assert_eq!(attrs.get_str("Exif:ExifVersion"), Some("0231"));
+            assert_eq!(attrs.get_str("GPS:LongitudeRef"), Some("E"));
+            assert!((attrs.get_f64("GPS:Longitude").unwrap() - 13.405).abs() < 1e-6);
+            assert_eq!(attrs.get_str("XMP:photoshop:City"), Some("Berlin"));
+        }
+    }
+
+    /// Tests embedding and reading back an ICC profile.
+    #[test]
+    fn test_icc_roundtrip() {
+        let mut profile = vec![3u8; 2000];
+        profile[16..20].copy_from_slice(b"RGB ");
+        let mut image = gradient(8, 8, 3);
+        icc::set_profile(&mut image.metadata.attrs, profile.clone());
+
+        let bytes = TiffWriter::new().write_to_memory(&image).unwrap();
+        let loaded = TiffReader::new().read_from_memory(&bytes).unwrap();
+        assert_eq!(icc::profile(&loaded.metadata.attrs), Some(&profile[..]));
+        assert_eq!(loaded.metadata.attrs.get_u32("ICCProfileSize"), Some(2000));
+    }
+
+    /// Tests rejection of mismatched predictors and bad tile sizes.
+    #[test]
+    fn test_invalid_layout() {
+        let image = gradient(8, 8, 3);
+        let write =
+            |options: TiffWriterOptions| TiffWriter::with_options(options).write_to_memory(&image);
+
+        assert!(write(TiffWriterOptions {
+            bit_depth: BitDepth::ThirtyTwoFloat,
+            predictor: Predictor::Horizontal,
+            ..Default::default()
+        })
+        .is_err());
+        assert!(write(TiffWriterOptions {
+            predictor: Predictor::FloatingPoint,
+            ..Default::default()
+        })
+        .is_err());
+        assert!(write(TiffWriterOptions {
+            tile_size: Some((24, 16)),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(TiffWriter::new().write_pages_to_memory(&[]).is_err());
+    }
 }
@@ -3,6 +3,9 @@
 //! Read/write WebP images via the `image` crate.
 //! Supports lossless compression and alpha channel.
 //!
+//! An embedded ICC profile is kept as `ICCProfile` bytes and written back
+//! (see [`crate::icc`]).
+//!
 //! **Note**: The pure-Rust WebP encoder only supports lossless mode.
 //! Lossy encoding with quality control requires the native libwebp library.
 //!
@@ -19,8 +22,9 @@ use std::fs::File;
 use std::io::{BufReader, BufWriter};
 use std::path::Path;
 
-use image::{DynamicImage, ImageFormat, ImageReader};
+use image::{DynamicImage, ImageDecoder, ImageEncoder, ImageFormat, ImageReader};
 
+use crate::icc;
 use crate::{ImageData, IoError, IoResult, PixelData, PixelFormat, Metadata};
 
 /// WebP writer options.
@@ -52,11 +56,20 @@ pub fn read<P: AsRef<Path>>(path: P) -> IoResult<ImageData> {
     let file = File::open(path.as_ref())?;
     let reader = BufReader::new(file);
     
-    let img = ImageReader::with_format(reader, ImageFormat::WebP)
-        .decode()
+    let mut decoder = ImageReader::with_format(reader, ImageFormat::WebP)
+        .into_decoder()
         .map_err(|e| IoError::DecodeError(e.to_string()))?;
-    
-    dynamic_to_image_data(img)
+    let profile = decoder
+        .icc_profile()
+        .map_err(|e| IoError::DecodeError(e.to_string()))?;
+    let img = DynamicImage::from_decoder(decoder)
+        .map_err(|e| IoError::DecodeError(e.to_string()))?;
+
+    let mut image = dynamic_to_image_data(img)?;
+    if let Some(profile) = profile {
+        icc::set_profile(&mut image.metadata.attrs, profile);
+    }
+    Ok(image)
 }
 
 /// Writes an image to WebP format with default options.
@@ -84,7 +97,12 @@ pub fn write_with_options<P: AsRef<Path>>(
         tracing::warn!("WebP lossy encoding requested but only lossless is available (pure-Rust encoder)");
     }
 
-    let encoder = image::codecs::webp::WebPEncoder::new_lossless(writer);
+    let mut encoder = image::codecs::webp::WebPEncoder::new_lossless(writer);
+    if let Some(profile) = icc::profile(&image.metadata.attrs) {
+        encoder
+            .set_icc_profile(profile.to_vec())
+            .map_err(|e| IoError::EncodeError(e.to_string()))?;
+    }
     dyn_img.write_with_encoder(encoder)
         .map_err(|e| IoError::EncodeError(e.to_string()))?;
 
@@ -212,4 +230,18 @@ mod tests {
         
         std::fs::remove_file(temp).ok();
     }
+
+    #[test]
+    fn test_webp_icc_roundtrip() {
+        let mut image = ImageData::from_u8(8, 8, 3, vec![64u8; 8 * 8 * 3]);
+        let profile = vec![5u8; 600];
+        icc::set_profile(&mut image.metadata.attrs, profile.clone());
+
+        let temp = std::env::temp_dir().join("test_webp_icc.webp");
+        write(&temp, &image).unwrap();
+        let loaded = read(&temp).unwrap();
+        assert_eq!(icc::profile(&loaded.metadata.attrs), Some(&profile[..]));
+
+        std::fs::remove_file(temp).ok();
+    }
 }
@@ -0,0 +1,861 @@
+//! Y'CbCr (YUV) conversion and raw YUV frame reading.
+//!
+//! Converts between RGB images and planar Y'CbCr frames, and reads headerless
+//! raw YUV files (`.yuv`) whose geometry is supplied by the caller.
+//!
+//! # Overview
+//!
+//! - BT.601, BT.709 and BT.2020 (non-constant luminance) matrices
+//! - Narrow (limited) and full range, 8 to 16 bits per sample
+//! - 4:2:0, 4:2:2, 4:4:4 and luma-only frames
+//! - Chroma siting aware up- and downsampling (center, left, top-left)
+//! - Raw files: planar (I420), planar YVU (YV12), semi-planar (NV12) and
+//!   packed 4:2:2 (YUYV, UYVY) layouts
+//!
+//! The BT.709 and BT.2020 luma weights are derived from the primaries in
+//! `vfx-primaries`, rounded to the four decimals the standards specify.
+//! BT.601 keeps its historical 0.299/0.587/0.114 weights.
+//!
+//! Chroma is downsampled with a triangle filter centered on each chroma
+//! sample and upsampled bilinearly, both honoring the siting. RGB values
+//! are gamma-encoded; no transfer function is applied.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use vfx_io::yuv::{RawYuvOptions, RawYuvReader, YuvFormat, YuvLayout, YuvMatrix};
+//!
+//! // 1080p 10-bit 4:2:0 planar frames
+//! let reader = RawYuvReader::with_options(RawYuvOptions {
+//!     width: 1920,
+//!     height: 1080,
+//!     format: YuvFormat { bit_depth: 10, ..Default::default() },
+//!     layout: YuvLayout::Planar,
+//!     matrix: YuvMatrix::Bt709,
+//! });
+//! let frames = reader.read_frames("clip.yuv")?;
+//!
+//! // RGB to 4:2:2 and back
+//! let format = YuvFormat { subsampling: ChromaSubsampling::Yuv422, ..Default::default() };
+//! let frame = YuvFrame::from_image(&image, format, YuvMatrix::Bt709)?;
+//! let rgb = frame.to_image(YuvMatrix::Bt709)?;
+//! ```
+
+use crate::{AttrValue, FormatReader, ImageData, IoError, IoResult, Metadata, PixelData, PixelFormat};
+use std::path::Path;
+use vfx_math::{Mat3, Vec3};
+use vfx_primaries::{rgb_to_xyz_matrix, Primaries, REC2020, REC709};
+
+// ============================================================================
+// Color Model
+// ============================================================================
+
+/// Y'CbCr matrix coefficients.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum YuvMatrix {
+    /// ITU-R BT.601 (SD video, JPEG).
+    Bt601,
+    /// ITU-R BT.709 (HD video).
+    #[default]
+    Bt709,
+    /// ITU-R BT.2020 non-constant luminance (UHD video).
+    Bt2020,
+}
+
+impl YuvMatrix {
+    /// Luma weights `(Kr, Kg, Kb)`.
+    pub fn luma_coefficients(&self) -> (f32, f32, f32) {
+        let (kr, kb) = match self {
+            // Historical NTSC 1953 weights, kept as specified
+            YuvMatrix::Bt601 => (0.299, 0.114),
+            YuvMatrix::Bt709 => luma_from_primaries(&REC709),
+            YuvMatrix::Bt2020 => luma_from_primaries(&REC2020),
+        };
+        (kr, 1.0 - kr - kb, kb)
+    }
+
+    /// RGB to Y'CbCr matrix; Cb and Cr are centered on zero.
+    pub fn rgb_to_ycbcr(&self) -> Mat3 {
+        let (kr, kg, kb) = self.luma_coefficients();
+        let cb = 2.0 * (1.0 - kb);
+        let cr = 2.0 * (1.0 - kr);
+        Mat3::from_rows([
+            [kr, kg, kb],
+            [-kr / cb, -kg / cb, 0.5],
+            [0.5, -kg / cr, -kb / cr],
+        ])
+    }
+
+    /// Y'CbCr to RGB matrix.
+    pub fn ycbcr_to_rgb(&self) -> Mat3 {
+        self.rgb_to_ycbcr()
+            .inverse()
+            .expect("Y'CbCr matrices are invertible")
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            YuvMatrix::Bt601 => "BT.601",
+            YuvMatrix::Bt709 => "BT.709",
+            YuvMatrix::Bt2020 => "BT.2020",
+        }
+    }
+
+    fn colorspace(&self) -> &'static str {
+        match self {
+            YuvMatrix::Bt601 => "Rec.601",
+            YuvMatrix::Bt709 => "Rec.709",
+            YuvMatrix::Bt2020 => "Rec.2020",
+        }
+    }
+}
+
+/// Luma weights `(Kr, Kb)` from the Y row of the RGB to XYZ matrix.
+fn luma_from_primaries(primaries: &Primaries) -> (f32, f32) {
+    let y = rgb_to_xyz_matrix(primaries).row(1);
+    let round = |v: f32| (v * 1e4).round() / 1e4;
+    (round(y.x), round(y.z))
+}
+
+/// Code value range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum YuvRange {
+    /// Narrow range: Y' in 16-235, Cb/Cr in 16-240 (scaled for deeper samples).
+    #[default]
+    Limited,
+    /// Full range: every code value is used.
+    Full,
+}
+
+/// Chroma subsampling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChromaSubsampling {
+    /// Half horizontal and vertical chroma resolution.
+    #[default]
+    Yuv420,
+    /// Half horizontal chroma resolution.
+    Yuv422,
+    /// Full chroma resolution.
+    Yuv444,
+    /// Luma only.
+    Mono,
+}
+
+impl ChromaSubsampling {
+    /// Horizontal and vertical subsampling factors.
+    pub fn factors(&self) -> (usize, usize) {
+        match self {
+            ChromaSubsampling::Yuv420 => (2, 2),
+            ChromaSubsampling::Yuv422 => (2, 1),
+            ChromaSubsampling::Yuv444 | ChromaSubsampling::Mono => (1, 1),
+        }
+    }
+
+    /// Chroma plane size for a frame, or zero for mono.
+    pub fn chroma_size(&self, width: usize, height: usize) -> (usize, usize) {
+        if *self == ChromaSubsampling::Mono {
+            return (0, 0);
+        }
+        let (fx, fy) = self.factors();
+        (width.div_ceil(fx), height.div_ceil(fy))
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ChromaSubsampling::Yuv420 => "4:2:0",
+            ChromaSubsampling::Yuv422 => "4:2:2",
+            ChromaSubsampling::Yuv444 => "4:4:4",
+            ChromaSubsampling::Mono => "4:0:0",
+        }
+    }
+}
+
+/// Position of subsampled chroma samples relative to luma.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChromaSiting {
+    /// Centered between luma samples (JPEG, MPEG-1).
+    #[default]
+    Center,
+    /// Co-sited horizontally, centered vertically (MPEG-2, BT.601/709 video).
+    Left,
+    /// Co-sited horizontally and vertically (BT.2020, PAL DV).
+    TopLeft,
+}
+
+impl ChromaSiting {
+    /// Chroma sample offset in luma pixels from the start of its block.
+    fn offsets(&self, subsampling: ChromaSubsampling) -> (f32, f32) {
+        let (fx, fy) = subsampling.factors();
+        let center = |f: usize| (f as f32 - 1.0) / 2.0;
+        match self {
+            ChromaSiting::Center => (center(fx), center(fy)),
+            ChromaSiting::Left => (0.0, center(fy)),
+            ChromaSiting::TopLeft => (0.0, 0.0),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ChromaSiting::Center => "center",
+            ChromaSiting::Left => "left",
+            ChromaSiting::TopLeft => "topleft",
+        }
+    }
+}
+
+/// Sample layout of a Y'CbCr frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct YuvFormat {
+    /// Chroma subsampling. Default: 4:2:0.
+    pub subsampling: ChromaSubsampling,
+    /// Bits per sample, 8 to 16. Default: 8.
+    pub bit_depth: u8,
+    /// Code value range. Default: limited.
+    pub range: YuvRange,
+    /// Chroma siting. Default: center.
+    pub siting: ChromaSiting,
+}
+
+impl Default for YuvFormat {
+    fn default() -> Self {
+        Self {
+            subsampling: ChromaSubsampling::Yuv420,
+            bit_depth: 8,
+            range: YuvRange::Limited,
+            siting: ChromaSiting::Center,
+        }
+    }
+}
+
+impl YuvFormat {
+    fn validate(&self) -> IoResult<()> {
+        if !(8..=16).contains(&self.bit_depth) {
+            return Err(IoError::UnsupportedBitDepth(format!("{}-bit YUV", self.bit_depth)));
+        }
+        Ok(())
+    }
+
+    /// Bytes per sample: 1 for 8-bit, 2 (little-endian) for deeper samples.
+    pub fn sample_bytes(&self) -> usize {
+        if self.bit_depth > 8 {
+            2
+        } else {
+            1
+        }
+    }
+
+    /// Code value scale and offsets: `(luma_scale, luma_offset, chroma_scale, chroma_offset)`.
+    fn quantization(&self) -> (f32, f32, f32, f32) {
+        let max = ((1u32 << self.bit_depth) - 1) as f32;
+        let mid = (1u32 << (self.bit_depth - 1)) as f32;
+        match self.range {
+            YuvRange::Limited => {
+                let scale = (1u32 << (self.bit_depth - 8)) as f32;
+                (219.0 * scale, 16.0 * scale, 224.0 * scale, mid)
+            }
+            YuvRange::Full => (max, 0.0, max, mid),
+        }
+    }
+}
+
+// ============================================================================
+// YuvFrame
+// ============================================================================
+
+/// A planar Y'CbCr frame.
+///
+/// Samples are stored unnormalized as code values in the frame's bit depth.
+/// `u` and `v` hold Cb and Cr at the chroma plane size; both are empty for
+/// mono frames.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YuvFrame {
+    /// Width in luma samples.
+    pub width: usize,
+    /// Height in luma samples.
+    pub height: usize,
+    /// Sample layout.
+    pub format: YuvFormat,
+    /// Luma plane.
+    pub y: Vec<u16>,
+    /// Cb plane.
+    pub u: Vec<u16>,
+    /// Cr plane.
+    pub v: Vec<u16>,
+}
+
+impl YuvFrame {
+    /// Creates a black frame.
+    pub fn new(width: usize, height: usize, format: YuvFormat) -> Self {
+        let (cw, ch) = format.subsampling.chroma_size(width, height);
+        let (_, luma_offset, _, chroma_offset) = format.quantization();
+        Self {
+            width,
+            height,
+            format,
+            y: vec![luma_offset as u16; width * height],
+            u: vec![chroma_offset as u16; cw * ch],
+            v: vec![chroma_offset as u16; cw * ch],
+        }
+    }
+
+    /// Converts an RGB or gray image to Y'CbCr.
+    ///
+    /// Alpha is dropped. Gray images have neutral chroma.
+    pub fn from_image(image: &ImageData, format: YuvFormat, matrix: YuvMatrix) -> IoResult<Self> {
+        format.validate()?;
+        let (width, height) = (image.width as usize, image.height as usize);
+        let channels = image.channels as usize;
+        if width == 0 || height == 0 || channels == 0 {
+            return Err(IoError::EncodeError("empty image".into()));
+        }
+
+        let pixels = image.to_f32();
+        let m = matrix.rgb_to_ycbcr();
+        let count = width * height;
+        let mut luma = Vec::with_capacity(count);
+        let mut cb = Vec::with_capacity(count);
+        let mut cr = Vec::with_capacity(count);
+        for px in pixels.chunks_exact(channels) {
+            let rgb = if channels >= 3 {
+                Vec3::new(px[0], px[1], px[2])
+            } else {
+                Vec3::new(px[0], px[0], px[0])
+            };
+            let ycc = m * rgb;
+            luma.push(ycc.x);
+            cb.push(ycc.y);
+            cr.push(ycc.z);
+        }
+
+        let (luma_scale, luma_offset, chroma_scale, chroma_offset) = format.quantization();
+        let max = ((1u32 << format.bit_depth) - 1) as f32;
+        let quantize = |plane: &[f32], scale: f32, offset: f32| -> Vec<u16> {
+            plane.iter().map(|v| (v * scale + offset).round().clamp(0.0, max) as u16).collect()
+        };
+
+        let mut frame = Self::new(width, height, format);
+        frame.y = quantize(&luma, luma_scale, luma_offset);
+        if format.subsampling != ChromaSubsampling::Mono {
+            let (fx, fy) = format.subsampling.factors();
+            let offsets = format.siting.offsets(format.subsampling);
+            let cb = downsample(&cb, width, height, fx, fy, offsets);
+            let cr = downsample(&cr, width, height, fx, fy, offsets);
+            frame.u = quantize(&cb, chroma_scale, chroma_offset);
+            frame.v = quantize(&cr, chroma_scale, chroma_offset);
+        }
+        Ok(frame)
+    }
+
+    /// Converts to an RGB image (gray for mono frames).
+    ///
+    /// 8-bit frames produce U8 images, deeper frames U16.
+    pub fn to_image(&self, matrix: YuvMatrix) -> IoResult<ImageData> {
+        let format = self.format;
+        format.validate()?;
+        let count = self.width * self.height;
+        let (cw, ch) = format.subsampling.chroma_size(self.width, self.height);
+        if self.y.len() != count || self.u.len() != cw * ch || self.v.len() != cw * ch {
+            return Err(IoError::DecodeError("YUV plane sizes do not match the frame".into()));
+        }
+
+        let (luma_scale, luma_offset, chroma_scale, chroma_offset) = format.quantization();
+        let normalize = |plane: &[u16], scale: f32, offset: f32| -> Vec<f32> {
+            plane.iter().map(|&v| (v as f32 - offset) / scale).collect()
+        };
+        let luma = normalize(&self.y, luma_scale, luma_offset);
+
+        let mono = format.subsampling == ChromaSubsampling::Mono;
+        let rgb: Vec<f32> = if mono {
+            luma.iter().map(|v| v.clamp(0.0, 1.0)).collect()
+        } else {
+            let (fx, fy) = format.subsampling.factors();
+            let offsets = format.siting.offsets(format.subsampling);
+            let cb = normalize(&self.u, chroma_scale, chroma_offset);
+            let cr = normalize(&self.v, chroma_scale, chroma_offset);
+            let cb = upsample(&cb, cw, ch, self.width, self.height, fx, fy, offsets);
+            let cr = upsample(&cr, cw, ch, self.width, self.height, fx, fy, offsets);
+
+            let m = matrix.ycbcr_to_rgb();
+            let mut rgb = Vec::with_capacity(count * 3);
+            for i in 0..count {
+                let c = m * Vec3::new(luma[i], cb[i], cr[i]);
+                rgb.extend([c.x, c.y, c.z].map(|v| v.clamp(0.0, 1.0)));
+            }
+            rgb
+        };
+
+        let channels = if mono { 1 } else { 3 };
+        let (width, height) = (self.width as u32, self.height as u32);
+        let mut image = if format.bit_depth == 8 {
+            ImageData::new(width, height, channels, PixelFormat::U8)
+        } else {
+            ImageData::new(width, height, channels, PixelFormat::U16)
+        };
+        image.data = if format.bit_depth == 8 {
+            PixelData::U8(rgb.iter().map(|v| (v * 255.0).round() as u8).collect())
+        } else {
+            PixelData::U16(rgb.iter().map(|v| (v * 65535.0).round() as u16).collect())
+        };
+        image.metadata = self.metadata(matrix);
+        Ok(image)
+    }
+
+    fn metadata(&self, matrix: YuvMatrix) -> Metadata {
+        let mut metadata = Metadata {
+            colorspace: Some(matrix.colorspace().to_string()),
+            ..Metadata::default()
+        };
+        let format = self.format;
+        let range = if format.range == YuvRange::Full { "full" } else { "limited" };
+        let attrs = &mut metadata.attrs;
+        attrs.set("YUV:Matrix", AttrValue::Str(matrix.name().into()));
+        attrs.set("YUV:Range", AttrValue::Str(range.into()));
+        attrs.set("YUV:Subsampling", AttrValue::Str(format.subsampling.name().into()));
+        attrs.set("YUV:ChromaSiting", AttrValue::Str(format.siting.name().into()));
+        attrs.set("YUV:BitsPerSample", AttrValue::UInt(format.bit_depth as u32));
+        metadata
+    }
+
+    /// Size in bytes of the planar (Y, Cb, Cr) encoding.
+    pub(crate) fn planar_size(width: usize, height: usize, format: &YuvFormat) -> usize {
+        let (cw, ch) = format.subsampling.chroma_size(width, height);
+        (width * height + 2 * cw * ch) * format.sample_bytes()
+    }
+
+    /// Decodes planar Y, Cb, Cr samples; deeper samples are little-endian.
+    pub(crate) fn from_planar(data: &[u8], width: usize, height: usize, format: YuvFormat) -> IoResult<Self> {
+        if data.len() < Self::planar_size(width, height, &format) {
+            return Err(IoError::InvalidFile("YUV frame truncated".into()));
+        }
+        let mut samples = read_samples(data, format.sample_bytes());
+        let (cw, ch) = format.subsampling.chroma_size(width, height);
+        let v = samples.split_off(width * height + cw * ch);
+        let u = samples.split_off(width * height);
+        Ok(Self {
+            width,
+            height,
+            format,
+            y: samples,
+            u,
+            v: v[..cw * ch].to_vec(),
+        })
+    }
+
+    /// Appends planar Y, Cb, Cr samples.
+    pub(crate) fn write_planar(&self, out: &mut Vec<u8>) {
+        for plane in [&self.y, &self.u, &self.v] {
+            write_samples(plane, self.format.sample_bytes(), out);
+        }
+    }
+}
+
+// ============================================================================
+// Chroma Resampling
+// ============================================================================
+
+/// Downsamples a plane with a triangle filter centered on each chroma sample.
+fn downsample(plane: &[f32], width: usize, height: usize, fx: usize, fy: usize, offsets: (f32, f32)) -> Vec<f32> {
+    let filter = |src: &[f32], factor: usize, offset: f32| -> Vec<f32> {
+        let n = src.len();
+        (0..n.div_ceil(factor))
+            .map(|i| {
+                let center = (i * factor) as f32 + offset;
+                let first = (center - factor as f32).floor() as isize + 1;
+                let (mut sum, mut total) = (0.0, 0.0);
+                for x in first..first + 2 * factor as isize {
+                    let weight = 1.0 - (x as f32 - center).abs() / factor as f32;
+                    if weight > 0.0 {
+                        sum += weight * src[x.clamp(0, n as isize - 1) as usize];
+                        total += weight;
+                    }
+                }
+                sum / total
+            })
+            .collect()
+    };
+    resample(plane, width, height, fx, fy, offsets, filter)
+}
+
+/// Upsamples a plane bilinearly from sited chroma samples.
+#[allow(clippy::too_many_arguments)]
+fn upsample(
+    plane: &[f32],
+    cw: usize,
+    ch: usize,
+    width: usize,
+    height: usize,
+    fx: usize,
+    fy: usize,
+    offsets: (f32, f32),
+) -> Vec<f32> {
+    let interpolate = |src: &[f32], factor: usize, offset: f32, n: usize| -> Vec<f32> {
+        let last = src.len() - 1;
+        (0..n)
+            .map(|x| {
+                let pos = ((x as f32 - offset) / factor as f32).max(0.0);
+                let i = (pos.floor() as usize).min(last);
+                let t = pos - i as f32;
+                src[i] * (1.0 - t) + src[(i + 1).min(last)] * t
+            })
+            .collect()
+    };
+    let rows = map_rows(plane, cw, ch, |row| interpolate(row, fx, offsets.0, width));
+    let cols = map_rows(&transpose(&rows, width, ch), ch, width, |col| {
+        interpolate(col, fy, offsets.1, height)
+    });
+    transpose(&cols, height, width)
+}
+
+/// Applies a 1D downsampling filter to rows, then columns.
+fn resample(
+    plane: &[f32],
+    width: usize,
+    height: usize,
+    fx: usize,
+    fy: usize,
+    offsets: (f32, f32),
+    filter: impl Fn(&[f32], usize, f32) -> Vec<f32>,
+) -> Vec<f32> {
+    let (cw, ch) = (width.div_ceil(fx), height.div_ceil(fy));
+    let rows = map_rows(plane, width, height, |row| filter(row, fx, offsets.0));
+    let cols = map_rows(&transpose(&rows, cw, height), height, cw, |col| filter(col, fy, offsets.1));
+    transpose(&cols, ch, cw)
+}
+
+/// Maps each row of a `width` x `height` plane.
+fn map_rows(plane: &[f32], width: usize, height: usize, f: impl Fn(&[f32]) -> Vec<f32>) -> Vec<f32> {
+    plane.chunks_exact(width).take(height).flat_map(f).collect()
+}
+
+/// Transposes a `width` x `height` plane.
+fn transpose(plane: &[f32], width: usize, height: usize) -> Vec<f32> {
+    let mut out = vec![0.0; plane.len()];
+    for y in 0..height {
+        for x in 0..width {
+            out[x * height + y] = plane[y * width + x];
+        }
+    }
+    out
+}
+
+// ============================================================================
+// Raw YUV Reader
+// ============================================================================
+
+/// Memory layout of a raw YUV frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum YuvLayout {
+    /// Y, Cb and Cr planes (I420, I422, I444).
+    #[default]
+    Planar,
+    /// Y, Cr and Cb planes (YV12, YV16).
+    PlanarYvu,
+    /// Y plane followed by interleaved Cb/Cr (NV12, NV16).
+    SemiPlanar,
+    /// Packed 4:2:2 as Y0 Cb Y1 Cr.
+    Yuyv,
+    /// Packed 4:2:2 as Cb Y0 Cr Y1.
+    Uyvy,
+}
+
+/// Options for reading raw YUV files.
+///
+/// Raw files carry no header, so the frame geometry must be given.
+#[derive(Debug, Clone, Default)]
+pub struct RawYuvOptions {
+    /// Frame width in luma samples.
+    pub width: u32,
+    /// Frame height in luma samples.
+    pub height: u32,
+    /// Subsampling, bit depth, range and siting.
+    pub format: YuvFormat,
+    /// Memory layout. Default: planar.
+    pub layout: YuvLayout,
+    /// Matrix used for RGB conversion. Default: BT.709.
+    pub matrix: YuvMatrix,
+}
+
+/// Raw YUV frame reader.
+///
+/// Implements [`FormatReader`] for headerless YUV files. Samples deeper than
+/// 8 bits are 16-bit little-endian, LSB-aligned. Frames follow each other
+/// without padding.
+///
+/// # Example
+///
+/// ```ignore
+/// use vfx_io::yuv::{RawYuvOptions, RawYuvReader};
+/// use vfx_io::FormatReader;
+///
+/// let reader = RawYuvReader::with_options(RawYuvOptions {
+///     width: 1920,
+///     height: 1080,
+///     ..Default::default()
+/// });
+/// let first = reader.read("clip.yuv")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct RawYuvReader {
+    options: RawYuvOptions,
+}
+
+impl RawYuvReader {
+    /// Bytes per frame for the configured geometry.
+    pub fn frame_size(&self) -> usize {
+        let o = &self.options;
+        YuvFrame::planar_size(o.width as usize, o.height as usize, &o.format)
+    }
+
+    /// Reads every frame as RGB images.
+    pub fn read_frames<P: AsRef<Path>>(&self, path: P) -> IoResult<Vec<ImageData>> {
+        let data = std::fs::read(path.as_ref())?;
+        self.read_frames_from_memory(&data)
+    }
+
+    /// Reads every frame from a byte slice as RGB images.
+    pub fn read_frames_from_memory(&self, data: &[u8]) -> IoResult<Vec<ImageData>> {
+        self.read_yuv_from_memory(data)?
+            .iter()
+            .map(|frame| frame.to_image(self.options.matrix))
+            .collect()
+    }
+
+    /// Reads every frame from a byte slice as planar Y'CbCr.
+    pub fn read_yuv_from_memory(&self, data: &[u8]) -> IoResult<Vec<YuvFrame>> {
+        let o = &self.options;
+        o.format.validate()?;
+        let (width, height) = (o.width as usize, o.height as usize);
+        if width == 0 || height == 0 {
+            return Err(IoError::MissingData("raw YUV needs the frame width and height".into()));
+        }
+        let packed = matches!(o.layout, YuvLayout::Yuyv | YuvLayout::Uyvy);
+        if packed && (o.format.subsampling != ChromaSubsampling::Yuv422 || width % 2 != 0) {
+            return Err(IoError::UnsupportedFeature(
+                "packed YUV layouts are 4:2:2 with an even width".into(),
+            ));
+        }
+        if o.layout != YuvLayout::Planar && o.format.subsampling == ChromaSubsampling::Mono {
+            return Err(IoError::UnsupportedFeature("mono YUV is planar only".into()));
+        }
+
+        let frame_size = self.frame_size();
+        if data.len() < frame_size {
+            return Err(IoError::InvalidFile(format!(
+                "raw YUV holds {} bytes, one {}x{} frame needs {}",
+                data.len(),
+                width,
+                height,
+                frame_size
+            )));
+        }
+        data.chunks_exact(frame_size)
+            .map(|chunk| self.decode_frame(chunk, width, height))
+            .collect()
+    }
+
+    fn decode_frame(&self, chunk: &[u8], width: usize, height: usize) -> IoResult<YuvFrame> {
+        let format = self.options.format;
+        let mut frame = YuvFrame::from_planar(chunk, width, height, format)?;
+        let luma = width * height;
+        let chroma = frame.u.len();
+        match self.options.layout {
+            YuvLayout::Planar => {}
+            YuvLayout::PlanarYvu => std::mem::swap(&mut frame.u, &mut frame.v),
+            YuvLayout::SemiPlanar => {
+                let samples = read_samples(chunk, format.sample_bytes());
+                let uv = &samples[luma..luma + 2 * chroma];
+                frame.u = uv.iter().step_by(2).copied().collect();
+                frame.v = uv.iter().skip(1).step_by(2).copied().collect();
+            }
+            YuvLayout::Yuyv | YuvLayout::Uyvy => {
+                // Pairs of luma share one chroma sample
+                let samples = read_samples(chunk, format.sample_bytes());
+                let (y0, cb, y1, cr) = if self.options.layout == YuvLayout::Yuyv {
+                    (0, 1, 2, 3)
+                } else {
+                    (1, 0, 3, 2)
+                };
+                for (i, quad) in samples[..luma * 2].chunks_exact(4).enumerate() {
+                    frame.y[2 * i] = quad[y0];
+                    frame.y[2 * i + 1] = quad[y1];
+                    frame.u[i] = quad[cb];
+                    frame.v[i] = quad[cr];
+                }
+            }
+        }
+        Ok(frame)
+    }
+}
+
+impl FormatReader<RawYuvOptions> for RawYuvReader {
+    /// Returns "Raw YUV".
+    fn format_name(&self) -> &'static str {
+        "Raw YUV"
+    }
+
+    /// Returns `["yuv"]`.
+    fn extensions(&self) -> &'static [&'static str] {
+        &["yuv"]
+    }
+
+    /// Raw YUV has no header; always false.
+    fn can_read(&self, _header: &[u8]) -> bool {
+        false
+    }
+
+    /// Reads the first frame as an RGB image.
+    fn read<P: AsRef<Path>>(&self, path: P) -> IoResult<ImageData> {
+        let data = std::fs::read(path.as_ref())?;
+        self.read_from_memory(&data)
+    }
+
+    /// Reads the first frame from a byte slice.
+    fn read_from_memory(&self, data: &[u8]) -> IoResult<ImageData> {
+        let frame_size = self.frame_size().min(data.len());
+        self.read_frames_from_memory(&data[..frame_size])?
+            .into_iter()
+            .next()
+            .ok_or_else(|| IoError::MissingData("raw YUV has no frames".into()))
+    }
+
+    /// Creates reader with the frame geometry.
+    fn with_options(options: RawYuvOptions) -> Self {
+        Self { options }
+    }
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Reads 8-bit or 16-bit little-endian samples.
+fn read_samples(data: &[u8], sample_bytes: usize) -> Vec<u16> {
+    if sample_bytes == 1 {
+        data.iter().map(|&b| b as u16).collect()
+    } else {
+        data.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]])).collect()
+    }
+}
+
+/// Writes 8-bit or 16-bit little-endian samples.
+fn write_samples(samples: &[u16], sample_bytes: usize, out: &mut Vec<u8>) {
+    if sample_bytes == 1 {
+        out.extend(samples.iter().map(|&v| v as u8));
+    } else {
+        for value in samples {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gradient(width: u32, height: u32) -> ImageData {
+        let data = (0..width * height)
+            .flat_map(|i| {
+                let (x, y) = (i % width, i / width);
+                [(x * 255 / width) as u8, (y * 255 / height) as u8, 128]
+            })
+            .collect();
+        ImageData::from_u8(width, height, 3, data)
+    }
+
+    /// Tests that derived luma weights match the standards.
+    #[test]
+    fn test_luma_coefficients() {
+        let (kr, kg, kb) = YuvMatrix::Bt709.luma_coefficients();
+        assert!((kr - 0.2126).abs() < 1e-6 && (kg - 0.7152).abs() < 1e-5 && (kb - 0.0722).abs() < 1e-6);
+        let (kr, _, kb) = YuvMatrix::Bt2020.luma_coefficients();
+        assert!((kr - 0.2627).abs() < 1e-6 && (kb - 0.0593).abs() < 1e-6);
+
+        // 75% yellow in BT.601 limited range
+        let frame = YuvFrame::from_image(
+            &ImageData::from_u8(1, 1, 3, vec![191, 191, 0]),
+            YuvFormat { subsampling: ChromaSubsampling::Yuv444, ..Default::default() },
+            YuvMatrix::Bt601,
+        )
+        .unwrap();
+        assert_eq!((frame.y[0], frame.u[0], frame.v[0]), (161, 44, 142));
+    }
+
+    /// Tests RGB roundtrips across subsampling, depth, range and siting.
+    #[test]
+    fn test_roundtrip() {
+        let image = gradient(129, 127);
+        for subsampling in [ChromaSubsampling::Yuv444, ChromaSubsampling::Yuv422, ChromaSubsampling::Yuv420] {
+            for siting in [ChromaSiting::Center, ChromaSiting::Left, ChromaSiting::TopLeft] {
+                for (bit_depth, range) in [(8, YuvRange::Limited), (10, YuvRange::Full), (16, YuvRange::Limited)] {
+                    let format = YuvFormat { subsampling, bit_depth, range, siting };
+                    let frame = YuvFrame::from_image(&image, format, YuvMatrix::Bt2020).unwrap();
+                    let rgb = frame.to_image(YuvMatrix::Bt2020).unwrap();
+                    let max_error = image
+                        .to_u8()
+                        .iter()
+                        .zip(rgb.to_u8())
+                        .map(|(a, b)| (*a as i32 - b as i32).abs())
+                        .max()
+                        .unwrap();
+                    // Smooth gradient: subsampling loses almost nothing
+                    assert!(max_error <= 3, "{:?} {:?} {} -> {}", format.subsampling, siting, bit_depth, max_error);
+                }
+            }
+        }
+    }
+
+    /// Tests that the filters follow the chroma siting.
+    #[test]
+    fn test_siting() {
+        let plane = [0.0, 1.0, 0.0, 1.0];
+        assert_eq!(downsample(&plane, 4, 1, 2, 1, (0.5, 0.0)), [0.375, 0.625]);
+        assert_eq!(downsample(&plane, 4, 1, 2, 1, (0.0, 0.0)), [0.25, 0.5]);
+        assert_eq!(upsample(&[0.0, 1.0], 2, 1, 4, 1, 2, 1, (0.0, 0.0)), [0.0, 0.5, 1.0, 1.0]);
+        assert_eq!(upsample(&[0.0, 1.0], 2, 1, 4, 1, 2, 1, (0.5, 0.0)), [0.0, 0.25, 0.75, 1.0]);
+    }
+
+    /// Tests raw layouts decode to the same frame.
+    #[test]
+    fn test_raw_layouts() {
+        let format = YuvFormat { subsampling: ChromaSubsampling::Yuv422, bit_depth: 10, ..Default::default() };
+        let frame = YuvFrame::from_image(&gradient(6, 2), format, YuvMatrix::Bt709).unwrap();
+        let mut planar = Vec::new();
+        frame.write_planar(&mut planar);
+
+        let mut yvu = Vec::new();
+        let mut nv = Vec::new();
+        let mut yuyv = Vec::new();
+        write_samples(&frame.y, 2, &mut yvu);
+        write_samples(&frame.v, 2, &mut yvu);
+        write_samples(&frame.u, 2, &mut yvu);
+        write_samples(&frame.y, 2, &mut nv);
+        for i in 0..frame.u.len() {
+            write_samples(&[frame.u[i], frame.v[i]], 2, &mut nv);
+            write_samples(&[frame.y[2 * i], frame.u[i], frame.y[2 * i + 1], frame.v[i]], 2, &mut yuyv);
+        }
+
+        for (layout, data) in [
+            (YuvLayout::Planar, &planar),
+            (YuvLayout::PlanarYvu, &yvu),
+            (YuvLayout::SemiPlanar, &nv),
+            (YuvLayout::Yuyv, &yuyv),
+        ] {
+            let reader = RawYuvReader::with_options(RawYuvOptions {
+                width: 6,
+                height: 2,
+                format,
+                layout,
+                matrix: YuvMatrix::Bt709,
+            });
+            let mut two = data.clone();
+            two.extend_from_slice(data);
+            let frames = reader.read_yuv_from_memory(&two).unwrap();
+            assert_eq!(frames.len(), 2);
+            assert_eq!(frames[1], frame, "{:?}", layout);
+        }
+    }
+}
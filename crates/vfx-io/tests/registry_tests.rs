@@ -50,6 +50,23 @@ fn registry_global_has_all_formats() {
     
     #[cfg(feature = "dpx")]
     assert!(names.contains(&"DPX"), "DPX not found in registry");
+
+    #[cfg(feature = "bmp")]
+    assert!(names.contains(&"BMP"), "BMP not found in registry");
+
+    #[cfg(feature = "tga")]
+    assert!(names.contains(&"TGA"), "TGA not found in registry");
+
+    #[cfg(feature = "cineon")]
+    assert!(names.contains(&"Cineon"), "Cineon not found in registry");
+
+    #[cfg(feature = "pnm")]
+    assert!(names.contains(&"Netpbm"), "Netpbm not found in registry");
+
+    #[cfg(feature = "pfm")]
+    assert!(names.contains(&"PFM"), "PFM not found in registry");
+
+    assert!(names.contains(&"DNG"), "DNG not found in registry");
 }
 
 #[test]
@@ -144,6 +161,65 @@ fn registry_detect_hdr_magic() {
     }
 }
 
+#[test]
+fn registry_detect_bmp_and_tga() {
+    let registry = FormatRegistry::global();
+
+    #[cfg(feature = "bmp")]
+    {
+        let bmp_magic = [b'B', b'M', 0x46, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x36, 0x00];
+        assert_eq!(registry.detect_format(&bmp_magic), Some("BMP"));
+    }
+
+    #[cfg(feature = "tga")]
+    {
+        // RLE true-color, 16x8, 32-bit with 8 alpha bits
+        let tga_header = [0, 0, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16, 0, 8, 0, 32, 8];
+        assert_eq!(registry.detect_format(&tga_header), Some("TGA"));
+    }
+}
+
+#[test]
+fn registry_detect_netpbm_and_pfm() {
+    let registry = FormatRegistry::global();
+
+    #[cfg(feature = "pnm")]
+    {
+        assert_eq!(registry.detect_format(b"P6\n4 4\n255\n"), Some("Netpbm"));
+        assert_eq!(registry.detect_format(b"P7\nWIDTH 4\n"), Some("Netpbm"));
+        assert_eq!(registry.get_by_extension("pgm").map(|f| f.name), Some("Netpbm"));
+    }
+
+    #[cfg(feature = "pfm")]
+    {
+        assert_eq!(registry.detect_format(b"PF\n4 4\n-1.0\n"), Some("PFM"));
+        assert_eq!(registry.detect_format(b"Pf\n4 4\n1.0\n"), Some("PFM"));
+    }
+}
+
+#[test]
+fn registry_dng_is_read_only() {
+    let registry = FormatRegistry::global();
+
+    let dng = registry.get_by_extension("DNG").expect("DNG not registered");
+    assert_eq!(dng.name, "DNG");
+    assert!(dng.write_path.is_none());
+    // A bare TIFF header is not enough to claim a DNG
+    assert!(!(dng.can_read)(b"II*\0\x08\0\0\0"));
+}
+
+#[test]
+fn registry_detect_cineon() {
+    let registry = FormatRegistry::global();
+
+    #[cfg(feature = "cineon")]
+    {
+        assert_eq!(registry.detect_format(&[0x80, 0x2A, 0x5F, 0xD7, 0x00, 0x00, 0x08, 0x00]), Some("Cineon"));
+        assert_eq!(registry.detect_format(&[0xD7, 0x5F, 0x2A, 0x80, 0x00, 0x08, 0x00, 0x00]), Some("Cineon"));
+        assert_eq!(registry.get_by_extension("cin").map(|f| f.name), Some("Cineon"));
+    }
+}
+
 #[test]
 fn registry_detect_dpx_be_magic() {
     let registry = FormatRegistry::global();
@@ -371,3 +447,170 @@ fn registry_thread_safe() {
         handle.join().expect("thread panicked");
     }
 }
+
+#[test]
+fn registry_read_from_stream_detects_magic() {
+    use std::io::{Cursor, Seek, SeekFrom};
+
+    let registry = FormatRegistry::global();
+
+    #[cfg(feature = "png")]
+    {
+        let original = test_image(64, 48);
+        let mut bytes = b"archive header".to_vec();
+        registry
+            .write_to(&mut bytes, &original, "png", None)
+            .expect("write PNG to stream");
+
+        // Read from the middle of the stream, with a misleading hint
+        let mut cursor = Cursor::new(bytes);
+        cursor.seek(SeekFrom::Start(14)).unwrap();
+        let decoded = registry
+            .read_from(&mut cursor, Some("exr"))
+            .expect("read PNG from stream");
+        assert_eq!(decoded.width, original.width);
+        assert_eq!(decoded.height, original.height);
+    }
+
+    #[cfg(feature = "exr")]
+    {
+        let mut file = std::fs::File::open(fixture_path("test.exr")).expect("open EXR");
+        let expected = registry.read(&fixture_path("test.exr")).expect("read EXR");
+        let image = registry.read_from(&mut file, None).expect("read EXR from stream");
+        assert_eq!(image.width, expected.width);
+        assert_eq!(image.height, expected.height);
+    }
+}
+
+#[test]
+fn registry_read_from_unknown_keeps_position() {
+    use std::io::{Cursor, Seek, SeekFrom};
+
+    let registry = FormatRegistry::global();
+    let mut cursor = Cursor::new(vec![0u8; 64]);
+    cursor.seek(SeekFrom::Start(8)).unwrap();
+
+    let result = registry.read_from(&mut cursor, Some("xyz"));
+    assert!(matches!(result, Err(vfx_io::IoError::UnsupportedFormat(_))));
+    assert_eq!(cursor.position(), 8);
+}
+
+#[test]
+fn registry_write_to_resolves_format_hint() {
+    let registry = FormatRegistry::global();
+
+    #[cfg(feature = "exr")]
+    {
+        let image = test_image(8, 4);
+        for hint in ["OpenEXR", "exr", ".EXR", "shot.0001.exr"] {
+            let bytes = registry
+                .write_to_memory(&image, hint, None)
+                .unwrap_or_else(|e| panic!("write with hint {}: {}", hint, e));
+            assert!(bytes.starts_with(&[0x76, 0x2F, 0x31, 0x01]), "hint {}", hint);
+        }
+    }
+
+    assert!(registry.write_to_memory(&test_image(8, 4), "xyz", None).is_err());
+    assert!(registry.write_to_memory(&test_image(8, 4), "dng", None).is_err());
+}
+
+#[test]
+fn registry_write_to_with_options() {
+    let registry = FormatRegistry::global();
+
+    #[cfg(feature = "png")]
+    {
+        use vfx_io::png::{BitDepth, PngWriterOptions};
+
+        let image = test_image(16, 8);
+        let options = PngWriterOptions {
+            bit_depth: BitDepth::Sixteen,
+            ..Default::default()
+        };
+        let mut out = Vec::new();
+        registry
+            .write_to(&mut out, &image, "png", Some(&options))
+            .expect("write PNG with options");
+
+        let decoded = registry.read_from_memory(&out, None).expect("read PNG");
+        assert_eq!(decoded.format, PixelFormat::U16);
+
+        // Options of another writer are rejected
+        let wrong = 16u32;
+        assert!(registry.write_to_memory(&image, "png", Some(&wrong)).is_err());
+    }
+}
+
+#[test]
+fn registry_read_subimage_from_stream() {
+    use std::io::Cursor;
+
+    let registry = FormatRegistry::global();
+
+    #[cfg(feature = "tiff")]
+    {
+        let pages = [test_image(64, 48), test_image(32, 16)];
+        let bytes = vfx_io::tiff::TiffWriter::new()
+            .write_pages_to_memory(&pages)
+            .expect("write TIFF pages");
+
+        let page = registry
+            .read_subimage_from(&mut Cursor::new(&bytes), None, 1, 0)
+            .expect("read TIFF page 1");
+        assert_eq!((page.width, page.height), (32, 16));
+    }
+
+    #[cfg(feature = "exr")]
+    {
+        let bytes = std::fs::read(fixture_path("test.exr")).expect("read EXR bytes");
+        let expected = vfx_io::exr::read_layer(fixture_path("test.exr"), 0, 0).expect("read layer");
+        let layer = registry
+            .read_subimage_from(&mut Cursor::new(&bytes), None, 0, 0)
+            .expect("read EXR layer 0");
+        assert_eq!((layer.width, layer.height), (expected.width, expected.height));
+        assert_eq!(layer.to_f32(), expected.to_f32());
+        assert!(registry.read_subimage_from_memory(&bytes, None, 99, 0).is_err());
+    }
+
+    #[cfg(feature = "png")]
+    {
+        let bytes = registry
+            .write_to_memory(&test_image(8, 8), "png", None)
+            .expect("write PNG");
+        assert!(registry.read_subimage_from_memory(&bytes, None, 0, 0).is_ok());
+        assert!(registry.read_subimage_from_memory(&bytes, None, 1, 0).is_err());
+    }
+}
+
+#[test]
+fn registry_read_deep_from_stream() {
+    use std::io::Cursor;
+    use vfx_core::TypeDesc;
+    use vfx_io::deepdata::DeepData;
+
+    let registry = FormatRegistry::global();
+
+    #[cfg(feature = "exr")]
+    {
+        let deep = DeepData::new(4, &[TypeDesc::FLOAT], &["Z"]);
+        deep.set_all_samples(&[1, 0, 2, 1]);
+        let temp_path = std::env::temp_dir().join("vfx_io_registry_deep_stream.exr");
+        vfx_io::exr::write_deep(&temp_path, &deep, 2, 2).expect("write deep EXR");
+        let bytes = std::fs::read(&temp_path).expect("read deep EXR bytes");
+        let _ = std::fs::remove_file(&temp_path);
+
+        let loaded = registry
+            .read_deep_from(&mut Cursor::new(bytes), None)
+            .expect("read deep EXR from stream");
+        assert_eq!(loaded.pixels(), 4);
+        assert_eq!(loaded.all_samples(), deep.all_samples());
+    }
+
+    #[cfg(feature = "png")]
+    {
+        let bytes = registry
+            .write_to_memory(&test_image(8, 8), "png", None)
+            .expect("write PNG");
+        assert!(registry.read_deep_from_memory(&bytes, None).is_err());
+    }
+}
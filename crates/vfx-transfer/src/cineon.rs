@@ -0,0 +1,174 @@
+//! Kodak Cineon log transfer function.
+//!
+//! Cineon encodes printing density in 10-bit code values: each code value
+//! is 0.002 density and the negative film gamma is 0.6. Reference black
+//! (code 95) maps to linear 0.0 and reference white (code 685) to 1.0.
+//! Values above reference white keep the film's highlight headroom.
+//!
+//! # Range
+//!
+//! - Encoded: [0, 1] (code value / 1023)
+//! - Linear: Scene-referred, ~[-0.011, 13.5] for code values 0-1023
+//!
+//! # Parameters
+//!
+//! Scanners and film-out vendors sometimes use other reference points.
+//! [`CineonParams`] with [`encode_with`] / [`decode_with`] covers those;
+//! [`encode`] / [`decode`] use the Kodak defaults.
+//!
+//! # Reference
+//!
+//! Kodak Cineon Digital Film System, "Conversion of 10-bit Log Film Data
+//! To 8-bit Linear or Video Data"
+
+/// Cineon conversion parameters, in 10-bit code values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CineonParams {
+    /// Code value of reference white (default 685).
+    pub ref_white: f32,
+    /// Code value of reference black (default 95).
+    pub ref_black: f32,
+    /// Negative film gamma (default 0.6).
+    pub film_gamma: f32,
+    /// Printing density per code value (default 0.002).
+    pub density_per_code: f32,
+}
+
+impl Default for CineonParams {
+    fn default() -> Self {
+        Self {
+            ref_white: 685.0,
+            ref_black: 95.0,
+            film_gamma: 0.6,
+            density_per_code: 0.002,
+        }
+    }
+}
+
+impl CineonParams {
+    /// Code values per decade of linear light.
+    #[inline]
+    fn codes_per_decade(&self) -> f32 {
+        self.film_gamma / self.density_per_code
+    }
+
+    /// Linear offset that maps reference black to 0.
+    #[inline]
+    fn black_offset(&self) -> f32 {
+        10.0_f32.powf((self.ref_black - self.ref_white) / self.codes_per_decade())
+    }
+}
+
+/// Cineon encode with custom parameters: Linear to normalized code value.
+///
+/// Values at or below the log curve's floor encode to 0.
+#[inline]
+pub fn encode_with(linear: f32, params: &CineonParams) -> f32 {
+    let offset = params.black_offset();
+    let v = linear * (1.0 - offset) + offset;
+    if v <= 0.0 {
+        return 0.0;
+    }
+    (params.ref_white + v.log10() * params.codes_per_decade()) / 1023.0
+}
+
+/// Cineon decode with custom parameters: Normalized code value to linear.
+#[inline]
+pub fn decode_with(log: f32, params: &CineonParams) -> f32 {
+    let offset = params.black_offset();
+    let v = 10.0_f32.powf((log * 1023.0 - params.ref_white) / params.codes_per_decade());
+    (v - offset) / (1.0 - offset)
+}
+
+/// Cineon encode: Linear to Cineon log.
+///
+/// # Example
+///
+/// ```rust
+/// use vfx_transfer::cineon::encode;
+///
+/// // Reference white encodes to code value 685
+/// assert!((encode(1.0) * 1023.0 - 685.0).abs() < 0.01);
+/// ```
+#[inline]
+pub fn encode(linear: f32) -> f32 {
+    encode_with(linear, &CineonParams::default())
+}
+
+/// Cineon decode: Cineon log to linear.
+///
+/// # Example
+///
+/// ```rust
+/// use vfx_transfer::cineon::decode;
+///
+/// // Reference black decodes to 0
+/// assert!(decode(95.0 / 1023.0).abs() < 1e-5);
+/// ```
+#[inline]
+pub fn decode(log: f32) -> f32 {
+    decode_with(log, &CineonParams::default())
+}
+
+/// Applies Cineon encoding to RGB.
+#[inline]
+pub fn encode_rgb(rgb: [f32; 3]) -> [f32; 3] {
+    [encode(rgb[0]), encode(rgb[1]), encode(rgb[2])]
+}
+
+/// Applies Cineon decoding to RGB.
+#[inline]
+pub fn decode_rgb(rgb: [f32; 3]) -> [f32; 3] {
+    [decode(rgb[0]), decode(rgb[1]), decode(rgb[2])]
+}
+
+/// Returns the Cineon value for 18% gray.
+#[inline]
+pub fn middle_gray() -> f32 {
+    encode(0.18)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let test_values = [0.0, 0.001, 0.01, 0.18, 0.5, 1.0, 4.0, 10.0];
+        for &l in &test_values {
+            let decoded = decode(encode(l));
+            assert!(
+                (l - decoded).abs() < l * 0.001 + 0.0001,
+                "l={}, decoded={}",
+                l,
+                decoded
+            );
+        }
+    }
+
+    #[test]
+    fn test_reference_points() {
+        assert!((encode(0.0) * 1023.0 - 95.0).abs() < 0.01);
+        assert!((encode(1.0) * 1023.0 - 685.0).abs() < 0.01);
+        // 18% gray sits near code value 468
+        let gray = middle_gray() * 1023.0;
+        assert!((gray - 468.0).abs() < 1.0, "middle gray = {}", gray);
+    }
+
+    #[test]
+    fn test_custom_params() {
+        let params = CineonParams {
+            ref_white: 680.0,
+            ref_black: 100.0,
+            ..Default::default()
+        };
+        assert!((encode_with(1.0, &params) * 1023.0 - 680.0).abs() < 0.01);
+        assert!(decode_with(100.0 / 1023.0, &params).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_below_floor() {
+        assert_eq!(encode(-1.0), 0.0);
+        assert!(decode(0.0) < 0.0);
+    }
+}
@@ -29,6 +29,7 @@
 //! | [`v_log`] | Panasonic cameras | Scene-referred |
 //! | [`red_log`] | RED cameras (REDLogFilm, REDLog3G10) | Scene-referred |
 //! | [`bmd_film`] | Blackmagic cameras (BMDFilm Gen5) | Scene-referred |
+//! | [`cineon`] | Kodak Cineon film scans | Scene-referred |
 //!
 //! # Usage
 //!
@@ -83,6 +84,7 @@ pub mod red_log;
 pub mod bmd_film;
 pub mod d_log;
 pub mod davinci_intermediate;
+pub mod cineon;
 
 // Re-export common functions
 pub use srgb::{eotf as srgb_eotf, oetf as srgb_oetf};
@@ -103,3 +105,4 @@ pub use red_log::{redlogfilm_encode, redlogfilm_decode, log3g10_encode, log3g10_
 pub use bmd_film::{bmd_film_gen5_encode, bmd_film_gen5_decode};
 pub use d_log::{encode as d_log_encode, decode as d_log_decode};
 pub use davinci_intermediate::{encode as davinci_int_encode, decode as davinci_int_decode};
+pub use cineon::{encode as cineon_encode, decode as cineon_decode};
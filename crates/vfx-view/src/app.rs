@@ -122,7 +122,7 @@ impl ViewerApp {
     /// Open file dialog and load selected image.
     fn open_file_dialog(&mut self) {
         if let Some(path) = rfd::FileDialog::new()
-            .add_filter("Images", &["exr", "hdr", "png", "jpg", "jpeg", "tif", "tiff", "dpx"])
+            .add_filter("Images", &["exr", "hdr", "png", "jpg", "jpeg", "tif", "tiff", "dpx", "cin", "bmp", "tga", "pnm", "pbm", "pgm", "ppm", "pam", "pfm", "dng"])
             .add_filter("All files", &["*"])
             .pick_file()
         {